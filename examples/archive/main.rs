@@ -0,0 +1,324 @@
+//! A virtual table which stores files in an SQLite database, similar to the [SQLite
+//! Archive](https://sqlite.org/sqlar.html) format.
+//!
+//! Each archive virtual table stores its files in a shadow table named `<table>_data`. Files
+//! are inserted either by providing the data directly, or by providing the path to a file on
+//! disk in the hidden `path` column, in which case the file is streamed into the database
+//! without loading it into memory. The overloaded function `archive_extract(name, dest_path)`
+//! streams a file back out of the archive.
+//!
+//! ```sql
+//! CREATE VIRTUAL TABLE ar USING archive;
+//! INSERT INTO ar(name, path) VALUES ('hello.txt', '/tmp/hello.txt');
+//! SELECT name, mode, mtime, sz FROM ar;
+//! SELECT archive_extract(name, '/tmp/hello-copy.txt') FROM ar WHERE name = 'hello.txt';
+//! ```
+
+use sqlite3_ext::{function::*, query::*, vtab::*, *};
+use std::{
+    fs::File,
+    io::{self, Read},
+    time::UNIX_EPOCH,
+};
+
+const COLUMN_NAME: usize = 0;
+const COLUMN_MODE: usize = 1;
+const COLUMN_MTIME: usize = 2;
+const COLUMN_DATA: usize = 4;
+const COLUMN_PATH: usize = 5;
+
+fn quote_identifier(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+#[sqlite3_ext_vtab(StandardModule, UpdateVTab, FindFunctionVTab)]
+struct Archive<'vtab> {
+    db: &'vtab Connection,
+    schema: String,
+    shadow: String,
+    functions: VTabFunctionList<'vtab, Self>,
+}
+
+impl<'vtab> Archive<'vtab> {
+    fn connect_create(db: &'vtab VTabConnection, args: &[&str]) -> Result<(String, Self)> {
+        let vtab = Archive {
+            db,
+            schema: args[1].to_owned(),
+            shadow: format!("{}_data", args[2]),
+            functions: VTabFunctionList::default(),
+        };
+        vtab.functions
+            .add_method(2, "archive_extract", None, |vtab, ctx, args| {
+                let name = args[0].get_str()?.to_owned();
+                let dest = args[1].get_str()?.to_owned();
+                ctx.set_result(vtab.extract(&name, &dest)? as i64)
+            });
+        Ok((
+            "CREATE TABLE x ( name, mode, mtime, sz, data, path HIDDEN )".to_owned(),
+            vtab,
+        ))
+    }
+
+    fn qualified_shadow(&self) -> String {
+        format!(
+            "{}.{}",
+            quote_identifier(&self.schema),
+            quote_identifier(&self.shadow)
+        )
+    }
+
+    /// Insert a file from disk, streaming its contents into a zeroblob.
+    fn insert_path(&self, name: Option<&str>, path: &str) -> Result<i64> {
+        let mut file = File::open(path).map_err(|e| Error::Module(format!("{path}: {e}")))?;
+        let meta = file
+            .metadata()
+            .map_err(|e| Error::Module(format!("{path}: {e}")))?;
+        let mode = file_mode(&meta);
+        let mtime = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        let sz = meta.len();
+        let mut stmt = self.db.prepare(&format!(
+            "INSERT INTO {} (name, mode, mtime, sz, data) VALUES (?, ?, ?, ?, ?)",
+            self.qualified_shadow()
+        ))?;
+        stmt.bind_zeroblob(5, sz)?;
+        let rowid = stmt.insert(params![name.unwrap_or(path), mode, mtime, sz as i64])?;
+        let ret = self.stream_in(&mut file, rowid);
+        if ret.is_err() {
+            // Remove the partially-written file before reporting the error.
+            self.delete(rowid)?;
+        }
+        ret.map(|_| rowid)
+    }
+
+    fn stream_in(&self, file: &mut File, rowid: i64) -> Result<()> {
+        let mut blob = self
+            .db
+            .open_blob(&self.schema, &self.shadow, "data", rowid, false)?;
+        let len = blob.len() as u64;
+        let copied =
+            io::copy(&mut file.take(len), &mut blob).map_err(|e| Error::Module(e.to_string()))?;
+        if copied < len {
+            return Err(Error::Module("file was truncated while reading".to_owned()));
+        }
+        Ok(())
+    }
+
+    fn delete(&self, rowid: i64) -> Result<()> {
+        self.db.execute(
+            &format!("DELETE FROM {} WHERE rowid = ?", self.qualified_shadow()),
+            [rowid],
+        )?;
+        Ok(())
+    }
+
+    /// Stream the named file out of the archive. Returns the number of bytes written.
+    fn extract(&self, name: &str, dest: &str) -> Result<usize> {
+        let rowid = self.db.query_row(
+            &format!(
+                "SELECT rowid FROM {} WHERE name = ?",
+                self.qualified_shadow()
+            ),
+            [name],
+            |r| Ok(r[0].get_i64()),
+        )?;
+        let mut blob = self
+            .db
+            .open_blob(&self.schema, &self.shadow, "data", rowid, true)?;
+        let mut file = File::create(dest).map_err(|e| Error::Module(format!("{dest}: {e}")))?;
+        let copied = io::copy(&mut blob, &mut file).map_err(|e| Error::Module(e.to_string()))?;
+        Ok(copied as usize)
+    }
+}
+
+#[cfg(unix)]
+fn file_mode(meta: &std::fs::Metadata) -> i64 {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() as _
+}
+
+#[cfg(not(unix))]
+fn file_mode(meta: &std::fs::Metadata) -> i64 {
+    if meta.permissions().readonly() {
+        0o100444
+    } else {
+        0o100644
+    }
+}
+
+impl<'vtab> VTab<'vtab> for Archive<'vtab> {
+    type Aux = ();
+    type Cursor = ArchiveCursor<'vtab>;
+
+    fn connect(db: &'vtab VTabConnection, _: &Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        Self::connect_create(db, args)
+    }
+
+    fn best_index(&self, index_info: &mut IndexInfo) -> Result<()> {
+        for mut c in index_info.constraints() {
+            if c.usable() && c.column() == COLUMN_NAME as i32 && c.op() == ConstraintOp::Eq {
+                c.set_argv_index(Some(0));
+                c.set_omit(true);
+                index_info.set_index_num(1);
+                index_info.set_estimated_cost(1.0);
+                index_info.set_estimated_rows(1);
+                return Ok(());
+            }
+        }
+        index_info.set_estimated_cost(1000.0);
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        Ok(ArchiveCursor {
+            vtab: self,
+            stmt: None,
+            eof: true,
+        })
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for Archive<'vtab> {
+    const SHADOW_NAMES: &'static [&'static str] = &["data"];
+
+    fn create(db: &'vtab VTabConnection, _: &Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        let ret = Self::connect_create(db, args)?;
+        db.execute(
+            &format!(
+                "CREATE TABLE {} (name TEXT PRIMARY KEY, mode INT, mtime INT, sz INT, data BLOB)",
+                ret.1.qualified_shadow()
+            ),
+            (),
+        )?;
+        Ok(ret)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        let sql = format!("DROP TABLE {}", self.qualified_shadow());
+        match self.db.execute(&sql, ()) {
+            Ok(_) => Ok(()),
+            Err(e) => Err((self, e)),
+        }
+    }
+}
+
+impl<'vtab> UpdateVTab<'vtab> for Archive<'vtab> {
+    fn update(&'vtab self, info: &mut ChangeInfo) -> Result<i64> {
+        match info.change_type() {
+            ChangeType::Delete => {
+                self.delete(info.rowid().get_i64())?;
+                Ok(0)
+            }
+            ChangeType::Insert => {
+                let args = info.args_mut();
+                if !args[0].is_null() {
+                    return Err(Error::Module("cannot specify rowid".to_owned()));
+                }
+                let cols = &mut args[1..];
+                let name = match cols[COLUMN_NAME].is_null() {
+                    true => None,
+                    false => Some(cols[COLUMN_NAME].get_str()?.to_owned()),
+                };
+                if !cols[COLUMN_PATH].is_null() {
+                    let path = cols[COLUMN_PATH].get_str()?.to_owned();
+                    return self.insert_path(name.as_deref(), &path);
+                }
+                let name = name.ok_or_else(|| Error::Module("name is required".to_owned()))?;
+                let mode = match cols[COLUMN_MODE].is_null() {
+                    true => 0o100644,
+                    false => cols[COLUMN_MODE].get_i64(),
+                };
+                let mtime = cols[COLUMN_MTIME].get_i64();
+                let data = cols[COLUMN_DATA].get_blob()?;
+                self.db.insert(
+                    &format!(
+                        "INSERT INTO {} (name, mode, mtime, sz, data) VALUES (?, ?, ?, ?, ?)",
+                        self.qualified_shadow()
+                    ),
+                    params![name, mode, mtime, data.len() as i64, data],
+                )
+            }
+            ChangeType::Update => Err(Error::Module("archive does not support UPDATE".to_owned())),
+        }
+    }
+}
+
+impl<'vtab> FindFunctionVTab<'vtab> for Archive<'vtab> {
+    fn functions(&self) -> &VTabFunctionList<'vtab, Self> {
+        &self.functions
+    }
+}
+
+struct ArchiveCursor<'vtab> {
+    vtab: &'vtab Archive<'vtab>,
+    stmt: Option<Statement>,
+    eof: bool,
+}
+
+impl<'vtab> ArchiveCursor<'vtab> {
+    fn row(&mut self) -> &mut QueryResult {
+        self.stmt
+            .as_mut()
+            .and_then(|s| s.current_result_mut())
+            .expect("cursor is not on a row")
+    }
+}
+
+impl<'vtab> VTabCursor for ArchiveCursor<'vtab> {
+    fn filter(
+        &mut self,
+        index_num: i32,
+        _: Option<&str>,
+        args: &mut [&mut ValueRef],
+    ) -> Result<()> {
+        let mut sql = format!(
+            "SELECT rowid, name, mode, mtime, sz, data FROM {}",
+            self.vtab.qualified_shadow()
+        );
+        let mut stmt = if index_num == 1 {
+            sql.push_str(" WHERE name = ?");
+            let mut stmt = self.vtab.db.prepare(&sql)?;
+            stmt.query([&*args[0]])?;
+            stmt
+        } else {
+            self.vtab.db.prepare(&sql)?
+        };
+        self.eof = stmt.next()?.is_none();
+        self.stmt = Some(stmt);
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.eof = self.stmt.as_mut().unwrap().next()?.is_none();
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        self.eof
+    }
+
+    fn column(&mut self, idx: usize, c: &ColumnContext) -> Result<()> {
+        match idx {
+            COLUMN_PATH => Ok(()),
+            _ => c.set_result(self.row()[idx + 1].as_ref()),
+        }
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(self.row()[0].get_i64())
+    }
+}
+
+#[sqlite3_ext_main]
+fn init(db: &Connection) -> Result<()> {
+    db.create_overloaded_function("archive_extract", &FunctionOptions::default().set_n_args(2))?;
+    db.create_module("archive", Archive::module(), ())?;
+    Ok(())
+}
+
+#[cfg(all(test, feature = "static"))]
+mod test;
@@ -0,0 +1,182 @@
+use super::*;
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs,
+    hash::Hasher,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// Size of the buffer used when generating and checking test files.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+fn setup() -> Result<Database> {
+    let conn = Database::open(":memory:")?;
+    init(&conn)?;
+    conn.execute("CREATE VIRTUAL TABLE ar USING archive", ())?;
+    Ok(conn)
+}
+
+/// A scratch directory which is removed when dropped.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("sqlite3_ext_archive_{}_{name}", std::process::id()));
+        fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+
+    fn path(&self, name: &str) -> String {
+        self.0.join(name).to_str().unwrap().to_owned()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.0).unwrap();
+    }
+}
+
+/// Write a file of pseudo-random data, returning its checksum.
+fn write_file(path: &str, len: usize) -> u64 {
+    let mut file = File::create(path).unwrap();
+    let mut hasher = DefaultHasher::new();
+    let mut state = 0x2545f4914f6cdd1du64 ^ len as u64;
+    let mut buf = Vec::with_capacity(CHUNK_SIZE);
+    let mut remaining = len;
+    while remaining > 0 {
+        buf.clear();
+        while buf.len() < CHUNK_SIZE.min(remaining) {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            buf.push(state as u8);
+        }
+        hasher.write(&buf);
+        file.write_all(&buf).unwrap();
+        remaining -= buf.len();
+    }
+    hasher.finish()
+}
+
+fn checksum(path: impl AsRef<Path>) -> u64 {
+    let mut file = File::open(path).unwrap();
+    let mut hasher = DefaultHasher::new();
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    loop {
+        let len = file.read(&mut buf).unwrap();
+        if len == 0 {
+            return hasher.finish();
+        }
+        hasher.write(&buf[..len]);
+    }
+}
+
+fn count(conn: &Connection) -> Result<i64> {
+    conn.query_row("SELECT COUNT(*) FROM ar", (), |r| Ok(r[0].get_i64()))
+}
+
+#[test]
+fn round_trip() -> Result<()> {
+    let conn = setup()?;
+    let dir = TempDir::new("round_trip");
+    for len in [
+        0,
+        1,
+        CHUNK_SIZE - 1,
+        CHUNK_SIZE + 1,
+        1_000_000,
+        64 * 1024 * 1024,
+    ] {
+        let name = format!("file_{len}");
+        let src = dir.path(&name);
+        let expected = write_file(&src, len);
+        conn.execute(
+            "INSERT INTO ar(name, path) VALUES (?, ?)",
+            params![name.as_str(), src.as_str()],
+        )?;
+        let sz = conn.query_row("SELECT sz FROM ar WHERE name = ?", [name.as_str()], |r| {
+            Ok(r[0].get_i64())
+        })?;
+        assert_eq!(sz, len as i64);
+        let dest = dir.path(&format!("{name}.out"));
+        let written = conn.query_row(
+            "SELECT archive_extract(name, ?) FROM ar WHERE name = ?",
+            params![dest.as_str(), name.as_str()],
+            |r| Ok(r[0].get_i64()),
+        )?;
+        assert_eq!(written, len as i64);
+        assert_eq!(checksum(&dest), expected, "checksum mismatch for {name}");
+    }
+    Ok(())
+}
+
+#[test]
+fn data_and_delete() -> Result<()> {
+    let conn = setup()?;
+    conn.execute(
+        "INSERT INTO ar(name, mode, mtime, data) VALUES ('a.txt', 420, 1000, 'hello')",
+        (),
+    )?;
+    let ret = conn.query_row("SELECT name, mode, mtime, sz, data FROM ar", (), |r| {
+        Ok((
+            r[0].get_str()?.to_owned(),
+            r[1].get_i64(),
+            r[2].get_i64(),
+            r[3].get_i64(),
+            r[4].get_blob()?.to_vec(),
+        ))
+    })?;
+    assert_eq!(ret, ("a.txt".to_owned(), 420, 1000, 5, b"hello".to_vec()));
+    conn.execute("DELETE FROM ar WHERE name = 'a.txt'", ())?;
+    assert_eq!(count(&conn)?, 0);
+    Ok(())
+}
+
+#[test]
+fn rollback() -> Result<()> {
+    let conn = setup()?;
+    let dir = TempDir::new("rollback");
+    let src = dir.path("file");
+    write_file(&src, 1_000_000);
+    conn.execute("BEGIN", ())?;
+    conn.execute(
+        "INSERT INTO ar(name, path) VALUES ('file', ?)",
+        [src.as_str()],
+    )?;
+    assert_eq!(count(&conn)?, 1);
+    conn.execute("ROLLBACK", ())?;
+    assert_eq!(count(&conn)?, 0);
+    Ok(())
+}
+
+#[test]
+fn partial_insert() -> Result<()> {
+    let conn = setup()?;
+    let dir = TempDir::new("partial_insert");
+    // Reading a directory fails after the zeroblob has been inserted.
+    let src = dir.path("subdir");
+    fs::create_dir(&src).unwrap();
+    conn.execute(
+        "INSERT INTO ar(name, path) VALUES ('subdir', ?)",
+        [src.as_str()],
+    )
+    .unwrap_err();
+    assert_eq!(count(&conn)?, 0);
+    Ok(())
+}
+
+#[test]
+fn drop_table() -> Result<()> {
+    let conn = setup()?;
+    conn.execute("DROP TABLE ar", ())?;
+    let ret = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE name = 'ar_data'",
+        (),
+        |r| Ok(r[0].get_i64()),
+    )?;
+    assert_eq!(ret, 0);
+    Ok(())
+}
@@ -0,0 +1,509 @@
+//! Rust implementation of the CSV virtual table distributed with SQLite.
+//!
+//! Usage example:
+//!
+//! ```sql
+//! CREATE VIRTUAL TABLE temp.csv USING csv(filename='thefile.csv', header=YES);
+//! SELECT * FROM csv;
+//! ```
+//!
+//! The arguments are the same as the original: exactly one of `filename=` or `data=`, and
+//! optionally `header=`, `columns=`, and `schema=`. The `testflags=` argument is not
+//! supported. Unlike the original, the file is read once when the table is connected, and
+//! must contain valid UTF-8.
+//!
+//! For more information, consult [the original implementation](https://sqlite.org/src/file/ext/misc/csv.c).
+
+use sqlite3_ext::{vtab::*, *};
+
+#[sqlite3_ext_vtab(StandardModule)]
+struct CsvTable {
+    data: String,
+    /// Offset of the first row of data, after the header.
+    start: usize,
+    columns: usize,
+}
+
+struct CsvCursor<'vtab> {
+    vtab: &'vtab CsvTable,
+    reader: Reader<'vtab>,
+    row: Vec<Option<String>>,
+    /// The rowid of the current row, or -1 at EOF.
+    rowid: i64,
+}
+
+/// Reads fields using the same rules as csv.c.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    /// The character which terminated the last field, or None at EOF.
+    term: Option<u8>,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a str, pos: usize) -> Self {
+        let data = data.as_bytes();
+        // Skip the UTF-8 BOM at the start of the file.
+        let pos = match pos == 0 && data.starts_with(b"\xef\xbb\xbf") {
+            true => 3,
+            false => pos,
+        };
+        Reader {
+            data,
+            pos,
+            term: None,
+        }
+    }
+
+    fn getc(&mut self) -> Option<u8> {
+        let c = self.data.get(self.pos).copied();
+        if c.is_some() {
+            self.pos += 1;
+        }
+        c
+    }
+
+    /// Read a single field, returning None if the reader is at EOF.
+    fn read_field(&mut self) -> Option<String> {
+        let mut field = Vec::new();
+        let mut c = match self.getc() {
+            Some(c) => c,
+            None => {
+                self.term = None;
+                return None;
+            }
+        };
+        if c == b'"' {
+            let (mut pc, mut ppc) = (None, None);
+            loop {
+                let c = self.getc();
+                if c == Some(b'"') && pc == Some(b'"') {
+                    pc = None;
+                    continue;
+                }
+                let closed = match c {
+                    Some(b',') | Some(b'\n') | None => pc == Some(b'"'),
+                    _ => false,
+                } || (c == Some(b'\n') && pc == Some(b'\r') && ppc == Some(b'"'));
+                if closed {
+                    // Remove the closing quote and anything after it.
+                    let end = field.iter().rposition(|&c| c == b'"').unwrap();
+                    field.truncate(end);
+                    self.term = c;
+                    break;
+                }
+                if pc == Some(b'"') && c != Some(b'\r') {
+                    // An unescaped quote ends the field.
+                    self.term = c;
+                    break;
+                }
+                match c {
+                    Some(c) => field.push(c),
+                    None => {
+                        // Unterminated quoted field.
+                        self.term = None;
+                        break;
+                    }
+                }
+                ppc = pc;
+                pc = c;
+            }
+        } else {
+            loop {
+                if c == b',' || c == b'\n' {
+                    break;
+                }
+                field.push(c);
+                match self.getc() {
+                    Some(x) => c = x,
+                    None => {
+                        self.term = None;
+                        return Some(String::from_utf8_lossy(&field).into_owned());
+                    }
+                }
+            }
+            if c == b'\n' && field.last() == Some(&b'\r') {
+                field.pop();
+            }
+            self.term = Some(c);
+        }
+        Some(String::from_utf8_lossy(&field).into_owned())
+    }
+
+    /// Returns true if the last field was terminated by a comma, meaning there are more
+    /// fields in the row.
+    fn more(&self) -> bool {
+        self.term == Some(b',')
+    }
+}
+
+/// If the argument is `key=value`, return the value.
+fn parameter<'a>(key: &str, arg: &'a str) -> Option<&'a str> {
+    let rest = arg.trim_start().strip_prefix(key)?;
+    let rest = rest.trim_start().strip_prefix('=')?;
+    Some(rest.trim_start())
+}
+
+/// Remove the quotes from a string parameter.
+fn dequote(val: &str) -> String {
+    let val = val.trim();
+    for q in ['\'', '"'] {
+        if val.len() >= 2 && val.starts_with(q) && val.ends_with(q) {
+            let doubled: String = [q, q].iter().collect();
+            return val[1..val.len() - 1].replace(&doubled, &q.to_string());
+        }
+    }
+    val.to_owned()
+}
+
+fn boolean(val: &str) -> Option<bool> {
+    match val.trim().to_ascii_lowercase().as_str() {
+        "yes" | "on" | "true" | "1" => Some(true),
+        "no" | "off" | "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parse the header parameter, which may be given without a value to mean YES.
+fn header_parameter(arg: &str) -> Option<bool> {
+    let rest = arg.trim_start().strip_prefix("header")?.trim_start();
+    if rest.is_empty() {
+        return Some(true);
+    }
+    boolean(rest.strip_prefix('=')?)
+}
+
+fn err(msg: String) -> Error {
+    Error::Module(msg)
+}
+
+impl CsvTable {
+    fn connect_create(args: &[&str]) -> Result<(String, Self)> {
+        let mut filename = None;
+        let mut data = None;
+        let mut schema = None;
+        let mut header = None;
+        let mut columns = None;
+        for arg in &args[3..] {
+            let strings = [
+                ("filename", &mut filename),
+                ("data", &mut data),
+                ("schema", &mut schema),
+            ];
+            let mut found = false;
+            for (key, dest) in strings {
+                if let Some(val) = parameter(key, arg) {
+                    if dest.is_some() {
+                        return Err(err(format!("more than one '{key}' parameter")));
+                    }
+                    *dest = Some(dequote(val));
+                    found = true;
+                    break;
+                }
+            }
+            if found {
+                continue;
+            }
+            if let Some(b) = header_parameter(arg) {
+                if header.is_some() {
+                    return Err(err("more than one 'header' parameter".to_owned()));
+                }
+                header = Some(b);
+            } else if let Some(val) = parameter("columns", arg) {
+                if columns.is_some() {
+                    return Err(err("more than one 'columns' parameter".to_owned()));
+                }
+                match val.trim().parse::<usize>() {
+                    Ok(n) if n > 0 => columns = Some(n),
+                    _ => return Err(err("column= value must be positive".to_owned())),
+                }
+            } else {
+                return Err(err(format!("bad parameter: '{arg}'")));
+            }
+        }
+        let data = match (filename, data) {
+            (Some(f), None) => std::fs::read_to_string(&f)
+                .map_err(|_| err(format!("cannot open '{f}' for reading")))?,
+            (None, Some(d)) => d,
+            _ => {
+                return Err(err(
+                    "must specify either filename= or data= but not both".to_owned()
+                ))
+            }
+        };
+        let header = header.unwrap_or(false);
+
+        let mut reader = Reader::new(&data, 0);
+        let mut names = Vec::new();
+        if header || columns.is_none() {
+            loop {
+                names.push(reader.read_field().unwrap_or_default());
+                if !reader.more() {
+                    break;
+                }
+            }
+        }
+        let columns = columns.unwrap_or(names.len());
+        let start = match header {
+            true => reader.pos,
+            false => 0,
+        };
+        let schema = match schema {
+            Some(s) => s,
+            None => {
+                let defs: Vec<_> = (0..columns)
+                    .map(|i| match (header, names.get(i)) {
+                        (true, Some(n)) => format!("\"{}\" TEXT", n.replace('"', "\"\"")),
+                        (true, None) => format!("c{} TEXT", i + 1),
+                        (false, _) => format!("c{i} TEXT"),
+                    })
+                    .collect();
+                format!("CREATE TABLE x({})", defs.join(","))
+            }
+        };
+        Ok((
+            schema,
+            CsvTable {
+                data,
+                start,
+                columns,
+            },
+        ))
+    }
+}
+
+impl<'vtab> VTab<'vtab> for CsvTable {
+    type Aux = ();
+    type Cursor = CsvCursor<'vtab>;
+
+    fn connect(_: &VTabConnection, _: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        Self::connect_create(args)
+    }
+
+    fn best_index(&self, index_info: &mut IndexInfo) -> Result<()> {
+        index_info.set_estimated_cost(1000000f64);
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        Ok(CsvCursor {
+            vtab: self,
+            reader: Reader::new(&self.data, self.start),
+            row: vec![None; self.columns],
+            rowid: 0,
+        })
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for CsvTable {
+    fn create(_: &VTabConnection, _: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        Self::connect_create(args)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+impl VTabCursor for CsvCursor<'_> {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        self.reader = Reader::new(&self.vtab.data, self.vtab.start);
+        self.rowid = 0;
+        self.next()
+    }
+
+    fn next(&mut self) -> Result<()> {
+        let mut i = 0;
+        while let Some(field) = self.reader.read_field() {
+            if i < self.row.len() {
+                self.row[i] = Some(field);
+            }
+            i += 1;
+            if !self.reader.more() {
+                break;
+            }
+        }
+        if i == 0 {
+            self.rowid = -1;
+        } else {
+            self.rowid += 1;
+            for val in self.row.iter_mut().skip(i) {
+                *val = None;
+            }
+        }
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        self.rowid < 0
+    }
+
+    fn column(&mut self, idx: usize, ctx: &ColumnContext) -> Result<()> {
+        ctx.set_result(self.row.get(idx).cloned().flatten())
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(self.rowid)
+    }
+}
+
+#[sqlite3_ext_main]
+fn init(db: &Connection) -> Result<()> {
+    db.create_module("csv", CsvTable::module(), ())?;
+    Ok(())
+}
+
+#[cfg(all(test, feature = "static"))]
+mod test {
+    use super::*;
+
+    fn setup() -> Result<Database> {
+        let conn = Database::open(":memory:")?;
+        init(&conn)?;
+        Ok(conn)
+    }
+
+    /// Run the SQL and return each row with its columns joined by "|", or the error code
+    /// and message.
+    fn run(conn: &Connection, sql: &str) -> std::result::Result<Vec<String>, (i32, String)> {
+        let err = |e: Error| match e {
+            Error::Sqlite(code, msg) => (code & 0xff, msg.unwrap_or_default()),
+            e => panic!("{e:?}"),
+        };
+        let mut stmt = conn.prepare(sql).map_err(err)?;
+        stmt.query(()).map_err(err)?;
+        let mut ret = Vec::new();
+        while let Some(row) = stmt.next().map_err(err)? {
+            let cols: Vec<String> = (0..row.len())
+                .map(|i| match row[i].is_null() {
+                    true => "NULL".to_owned(),
+                    false => row[i].get_str().unwrap().to_owned(),
+                })
+                .collect();
+            ret.push(cols.join("|"));
+        }
+        Ok(ret)
+    }
+
+    /// The fixture used by the conformance tests. It covers quoted fields, escaped quotes,
+    /// embedded newlines, CRLF line endings, and short, long, and blank rows.
+    const FIXTURE: &str = "name,qty,note\r\n\
+                           apple,3,\"red, round\"\r\n\
+                           \"pear\",,\"says \"\"hi\"\"\"\n\
+                           \"multi\nline\",7\n\
+                           \n\
+                           extra,1,2,3\n";
+
+    /// The rows printed by csv.c, or the error code and message it fails with.
+    type Expected = std::result::Result<&'static [&'static str], (i32, &'static str)>;
+
+    /// Each case is the SQL, and the result produced by csv.c in SQLite 3.39. The table
+    /// `fixture` is created from [FIXTURE] with `header=YES`, and `raw` without a header.
+    const CASES: &[(&str, Expected)] = &[
+        (
+            "SELECT rowid, * FROM fixture",
+            Ok(&[
+                "1|apple|3|red, round",
+                "2|pear||says \"hi\"",
+                "3|multi\nline|7|NULL",
+                "4||NULL|NULL",
+                "5|extra|1|2",
+            ]),
+        ),
+        (
+            "SELECT name FROM pragma_table_info('fixture')",
+            Ok(&["name", "qty", "note"]),
+        ),
+        ("SELECT c0, c2 FROM raw WHERE rowid = 1", Ok(&["name|note"])),
+        ("SELECT count(*) FROM raw", Ok(&["6"])),
+        (
+            "SELECT name FROM fixture WHERE qty IS NOT NULL ORDER BY name DESC",
+            Ok(&["pear", "multi\nline", "extra", "apple"]),
+        ),
+        (
+            "SELECT typeof(qty) FROM fixture WHERE rowid = 1",
+            Ok(&["text"]),
+        ),
+        (
+            "SELECT * FROM csv_cols",
+            Ok(&["apple|3", "pear|", "multi\nline|7", "|NULL", "extra|1"]),
+        ),
+        (
+            "INSERT INTO fixture VALUES (1, 2, 3)",
+            Err((ffi::SQLITE_ERROR, "table fixture may not be modified")),
+        ),
+        (
+            "CREATE VIRTUAL TABLE temp.bad USING csv(bogus=1)",
+            Err((ffi::SQLITE_ERROR, "bad parameter: 'bogus=1'")),
+        ),
+        (
+            "CREATE VIRTUAL TABLE temp.bad USING csv(header=YES)",
+            Err((
+                ffi::SQLITE_ERROR,
+                "must specify either filename= or data= but not both",
+            )),
+        ),
+        (
+            "CREATE VIRTUAL TABLE temp.bad USING csv(data='a', data='b')",
+            Err((ffi::SQLITE_ERROR, "more than one 'data' parameter")),
+        ),
+        (
+            "CREATE VIRTUAL TABLE temp.bad USING csv(data='a', columns=0)",
+            Err((ffi::SQLITE_ERROR, "column= value must be positive")),
+        ),
+    ];
+
+    #[test]
+    fn conformance() -> Result<()> {
+        let conn = setup()?;
+        let data = FIXTURE.replace('\'', "''");
+        conn.execute(
+            &format!("CREATE VIRTUAL TABLE temp.fixture USING csv(data='{data}', header=YES)"),
+            (),
+        )?;
+        conn.execute(
+            &format!("CREATE VIRTUAL TABLE temp.raw USING csv(data='{data}', header = no)"),
+            (),
+        )?;
+        conn.execute(
+            &format!(
+                "CREATE VIRTUAL TABLE temp.csv_cols USING csv(data='{data}', header, columns=2)"
+            ),
+            (),
+        )?;
+        for (sql, expected) in CASES {
+            let expected = expected
+                .map(|rows| rows.iter().map(|r| (*r).to_owned()).collect::<Vec<_>>())
+                .map_err(|(code, msg)| (code, msg.to_owned()));
+            assert_eq!(run(&conn, sql), expected, "{sql}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn filename() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("sqlite3_ext_csv_{}.csv", std::process::id()));
+        std::fs::write(&path, "\u{feff}a,b\n1,2\n").unwrap();
+        let conn = setup()?;
+        conn.execute(
+            &format!(
+                "CREATE VIRTUAL TABLE temp.f USING csv(filename=\"{}\", header=YES)",
+                path.display()
+            ),
+            (),
+        )?;
+        assert_eq!(run(&conn, "SELECT a, b FROM f"), Ok(vec!["1|2".to_owned()]));
+        std::fs::remove_file(&path).unwrap();
+        let err = conn
+            .execute(
+                "CREATE VIRTUAL TABLE temp.g USING csv(filename='/nonexistent.csv')",
+                (),
+            )
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::Sqlite(ffi::SQLITE_ERROR, _)),
+            "{err:?}"
+        );
+        Ok(())
+    }
+}
@@ -4,11 +4,18 @@
 
 use sqlite3_ext::{vtab::*, *};
 
-const COLUMN_START: i32 = 1;
-const COLUMN_STOP: i32 = 2;
-const COLUMN_STEP: i32 = 3;
+#[derive(VTabColumns)]
+enum Col {
+    Value,
+    #[column(hidden)]
+    Start,
+    #[column(hidden)]
+    Stop,
+    #[column(hidden)]
+    Step,
+}
 
-#[sqlite3_ext_vtab(EponymousModule)]
+#[sqlite3_ext_vtab(EponymousModule, name = "generate_series")]
 struct GenerateSeries {}
 
 impl VTab<'_> for GenerateSeries {
@@ -18,7 +25,7 @@ impl VTab<'_> for GenerateSeries {
     fn connect(db: &VTabConnection, _aux: &Self::Aux, _args: &[&str]) -> Result<(String, Self)> {
         db.set_risk_level(RiskLevel::Innocuous);
         Ok((
-            "CREATE TABLE x ( value, start HIDDEN, stop HIDDEN, step HIDDEN )".to_owned(),
+            format!("CREATE TABLE x ( {} )", Col::SCHEMA_FRAGMENT),
             GenerateSeries {},
         ))
     }
@@ -46,12 +53,12 @@ impl VTab<'_> for GenerateSeries {
         let mut has_start = false;
         let mut arg_index: [Option<usize>; 3] = [None, None, None];
         for (i, constraint) in index_info.constraints().enumerate() {
-            if constraint.column() < COLUMN_START {
+            if constraint.column() < i32::from(Col::Start) {
                 continue;
             }
-            let bit = (constraint.column() - COLUMN_START) as usize;
+            let bit = (constraint.column() - i32::from(Col::Start)) as usize;
             assert!(bit <= 2);
-            if constraint.column() == COLUMN_START {
+            if constraint.column() == i32::from(Col::Start) {
                 has_start = true;
             }
             if !constraint.usable() {
@@ -88,7 +95,7 @@ impl VTab<'_> for GenerateSeries {
             index_info.set_estimated_cost((2 - ((query_plan & 4) != 0) as isize) as f64);
             index_info.set_estimated_rows(1000);
             if let Some(order) = index_info.order_by().next() {
-                if order.column() == 0 {
+                if order.column() == i32::from(Col::Value) {
                     if order.desc() {
                         query_plan |= 8;
                     } else {
@@ -190,11 +197,11 @@ impl VTabCursor for Cursor {
     }
 
     fn column(&mut self, idx: usize, c: &ColumnContext) -> Result<()> {
-        let ret = match idx as _ {
-            COLUMN_START => self.min_value,
-            COLUMN_STOP => self.max_value,
-            COLUMN_STEP => self.step,
-            _ => self.value,
+        let ret = match Col::from_index(idx) {
+            Some(Col::Start) => self.min_value,
+            Some(Col::Stop) => self.max_value,
+            Some(Col::Step) => self.step,
+            Some(Col::Value) | None => self.value,
         };
         c.set_result(ret)
     }
@@ -206,8 +213,7 @@ impl VTabCursor for Cursor {
 
 #[sqlite3_ext_main]
 fn init(db: &Connection) -> Result<()> {
-    db.create_module("generate_series", GenerateSeries::module(), ())?;
-    Ok(())
+    GenerateSeries::register(db, ())
 }
 
 #[cfg(all(test, feature = "static"))]
@@ -241,6 +247,48 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn progress_handler() -> Result<()> {
+        let conn = setup()?;
+        let calls = std::rc::Rc::new(std::cell::Cell::new(0));
+        let calls_inner = calls.clone();
+        conn.set_progress_handler(100, move || {
+            calls_inner.set(calls_inner.get() + 1);
+            calls_inner.get() >= 5
+        })?;
+        let err = conn
+            .query_row(
+                "SELECT count(*) FROM generate_series(1, 1000000000)",
+                (),
+                |_| Ok(()),
+            )
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::Sqlite(ffi::SQLITE_INTERRUPT, _)),
+            "{err:?}"
+        );
+        assert_eq!(calls.get(), 5);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(modern_sqlite)]
+    fn manifest() -> Result<()> {
+        let json: serde_json::Value = serde_json::from_str(&init.manifest_json()?).unwrap();
+        assert_eq!(json["entry_point"], "sqlite3_generateseries_init");
+        assert_eq!(json["modules"], serde_json::json!(["generate_series"]));
+        assert_eq!(json["functions"], serde_json::json!([]));
+        assert_eq!(json["collations"], serde_json::json!([]));
+
+        let dir = std::env::temp_dir().join(format!("generate_series_{}", std::process::id()));
+        let (header, _) = build_support::write_artifacts_to(&init, "generate_series", &dir)?;
+        let header = std::fs::read_to_string(header).unwrap();
+        assert!(header.contains("#ifndef GENERATE_SERIES_H"));
+        assert!(header.contains("int sqlite3_generateseries_init(sqlite3 *db, char **pzErrMsg, const sqlite3_api_routines *pApi);"));
+        std::fs::remove_dir_all(dir).unwrap();
+        Ok(())
+    }
+
     macro_rules! case {
         ($test_name:ident { sql: $sql:expr, expected: $expected:expr, }) => {
             #[test]
@@ -291,4 +339,150 @@ mod test {
         sql: "SELECT value FROM generate_series(1) LIMIT 5",
         expected: Ok(vec![1, 2, 3, 4, 5]),
     });
+
+    /// Run the SQL and return each row with its columns joined by "|", or the error code
+    /// and message.
+    fn run(conn: &Connection, sql: &str) -> std::result::Result<Vec<String>, (i32, String)> {
+        let err = |e: Error| match e {
+            Error::Sqlite(code, msg) => (code & 0xff, msg.unwrap_or_default()),
+            e => panic!("{e:?}"),
+        };
+        conn.prepare(sql)
+            .map_err(err)?
+            .query(())
+            .map_err(err)?
+            .map(|row| {
+                let cols: Vec<String> = (0..row.len())
+                    .map(|i| row[i].get_str().map(String::from))
+                    .collect::<Result<_>>()?;
+                Ok(cols.join("|"))
+            })
+            .collect()
+            .map_err(err)
+    }
+
+    type Expected = std::result::Result<&'static [&'static str], (i32, &'static str)>;
+
+    /// Each case is the SQL, and the result produced by series.c in SQLite 3.39.
+    const CASES: &[(&str, Expected)] = &[
+        (
+            "SELECT value FROM generate_series(5, 100, 25)",
+            Ok(&["5", "30", "55", "80"]),
+        ),
+        (
+            "SELECT rowid, value FROM generate_series(10, 12)",
+            Ok(&["1|10", "2|11", "3|12"]),
+        ),
+        (
+            "SELECT value, start, stop, step FROM generate_series(1, 10, 4)",
+            Ok(&["1|1|10|4", "5|1|10|4", "9|1|10|4"]),
+        ),
+        (
+            "SELECT value FROM generate_series(5, 10, -2)",
+            Ok(&["9", "7", "5"]),
+        ),
+        (
+            "SELECT value FROM generate_series(5, 10, -2) ORDER BY value",
+            Ok(&["5", "7", "9"]),
+        ),
+        (
+            "SELECT value FROM generate_series(1, 4) ORDER BY value DESC",
+            Ok(&["4", "3", "2", "1"]),
+        ),
+        (
+            "SELECT value FROM generate_series(1, 3, 0)",
+            Ok(&["1", "2", "3"]),
+        ),
+        ("SELECT value FROM generate_series(3, 1)", Ok(&[])),
+        ("SELECT value FROM generate_series(1, NULL)", Ok(&[])),
+        (
+            "SELECT value FROM generate_series(1.9, '3')",
+            Ok(&["1", "2", "3"]),
+        ),
+        (
+            "SELECT value FROM generate_series WHERE start = 2 AND stop = 4",
+            Ok(&["2", "3", "4"]),
+        ),
+        (
+            "SELECT value FROM generate_series(1, 10) WHERE value % 3 = 0",
+            Ok(&["3", "6", "9"]),
+        ),
+        (
+            "SELECT a.value, b.value FROM generate_series(1, 2) a, generate_series(a.value, 2) b",
+            Ok(&["1|1", "1|2", "2|2"]),
+        ),
+        (
+            "SELECT count(*) FROM generate_series(1, 1000)",
+            Ok(&["1000"]),
+        ),
+        (
+            "SELECT value FROM generate_series()",
+            Err((
+                ffi::SQLITE_ERROR,
+                "first argument to \"generate_series()\" missing or unusable",
+            )),
+        ),
+        (
+            "SELECT value FROM generate_series WHERE stop = 3",
+            Err((
+                ffi::SQLITE_ERROR,
+                "first argument to \"generate_series()\" missing or unusable",
+            )),
+        ),
+        (
+            "SELECT value FROM generate_series(1, 2, 3, 4)",
+            Err((
+                ffi::SQLITE_ERROR,
+                "too many arguments on generate_series() - max 3",
+            )),
+        ),
+        (
+            "INSERT INTO generate_series(value) VALUES (1)",
+            Err((
+                ffi::SQLITE_ERROR,
+                "table generate_series may not be modified",
+            )),
+        ),
+    ];
+
+    /// Cases where this implementation intentionally differs from series.c. Each case is
+    /// the SQL, the result from series.c, and the result from this implementation.
+    const DIFFERENCES: &[(&str, Expected, Expected)] = &[(
+        // series.c uses 4294967295 as the default stop value, but this implementation
+        // uses i64::MAX.
+        "SELECT count(*) FROM (SELECT value FROM generate_series(4294967290) LIMIT 10)",
+        Ok(&["6"]),
+        Ok(&["10"]),
+    )];
+
+    fn owned(expected: &Expected) -> std::result::Result<Vec<String>, (i32, String)> {
+        expected
+            .map(|rows| rows.iter().map(|r| (*r).to_owned()).collect())
+            .map_err(|(code, msg)| (code, msg.to_owned()))
+    }
+
+    /// Run the conformance cases against this implementation and, if SQLite was compiled
+    /// with its own generate_series, against that too.
+    #[test]
+    fn conformance() -> Result<()> {
+        let builtin = Database::open(":memory:")?;
+        let builtin = match builtin.prepare("SELECT value FROM generate_series(1, 1)") {
+            Ok(_) => Some(builtin),
+            Err(_) => None,
+        };
+        let conn = setup()?;
+        for (sql, expected) in CASES {
+            assert_eq!(run(&conn, sql), owned(expected), "{sql}");
+            if let Some(builtin) = &builtin {
+                assert_eq!(run(builtin, sql), owned(expected), "builtin: {sql}");
+            }
+        }
+        for (sql, c, rust) in DIFFERENCES {
+            assert_eq!(run(&conn, sql), owned(rust), "{sql}");
+            if let Some(builtin) = &builtin {
+                assert_eq!(run(builtin, sql), owned(c), "builtin: {sql}");
+            }
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,387 @@
+//! Example virtual table exposing a tree stored outside of SQLite, showing why rowids need
+//! to be stable.
+//!
+//! The tree is a map from the name of each node to the name of its parent, shared between the
+//! application and the virtual table. The virtual table enumerates the nodes in order of
+//! their names, so the position of a node in the enumeration changes whenever a node is
+//! added, removed, or renamed.
+//!
+//! SQLite runs an UPDATE or DELETE on a virtual table by first collecting the rowids of the
+//! affected rows, and then applying the change to each rowid in turn. If the rowid of a node
+//! is its position in the enumeration (which is what a table created with the `naive`
+//! argument does), then changing the first node renumbers the others, and the rest of the
+//! statement changes the wrong nodes. Without the `naive` argument, the virtual table uses a
+//! [RowidMap] to give each node a rowid which stays the same for as long as the virtual
+//! table is connected.
+//!
+//! ```sql
+//! CREATE VIRTUAL TABLE tree USING hierarchy;
+//! INSERT INTO tree (name, parent) VALUES ('root', NULL), ('a', 'root'), ('b', 'a');
+//! -- Delete a subtree.
+//! WITH RECURSIVE sub(name) AS (
+//!     SELECT 'a' UNION ALL SELECT tree.name FROM tree JOIN sub ON tree.parent = sub.name
+//! )
+//! DELETE FROM tree WHERE name IN sub;
+//! ```
+
+use sqlite3_ext::{vtab::*, *};
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    ops::Bound::{Excluded, Unbounded},
+    rc::Rc,
+};
+
+/// The external data: the parent of each node, keyed by the name of the node.
+pub type Tree = Rc<RefCell<BTreeMap<String, Option<String>>>>;
+
+#[sqlite3_ext_main]
+fn init(db: &Connection) -> Result<()> {
+    register(db, Tree::default())
+}
+
+/// Register the module using the provided tree.
+pub fn register(db: &Connection, tree: Tree) -> Result<()> {
+    db.create_module("hierarchy", HierarchyVTab::module(), tree)
+}
+
+#[sqlite3_ext_vtab(StandardModule, UpdateVTab)]
+struct HierarchyVTab<'vtab> {
+    tree: &'vtab Tree,
+    /// None if the table was created with the `naive` argument.
+    rowids: Option<RefCell<RowidMap>>,
+}
+
+impl HierarchyVTab<'_> {
+    fn rowid(&self, name: &str) -> Result<i64> {
+        match &self.rowids {
+            Some(rowids) => rowids.borrow_mut().rowid(name),
+            None => Ok(self
+                .tree
+                .borrow()
+                .range::<str, _>((Unbounded, Excluded(name)))
+                .count() as i64
+                + 1),
+        }
+    }
+
+    fn name(&self, rowid: i64) -> Option<String> {
+        match &self.rowids {
+            Some(rowids) => match rowids.borrow().key(rowid) {
+                Some(RowidKey::Text(name)) => Some(name.clone()),
+                _ => None,
+            },
+            None => {
+                let idx = usize::try_from(rowid).ok()?.checked_sub(1)?;
+                self.tree.borrow().keys().nth(idx).cloned()
+            }
+        }
+    }
+
+    fn row(info: &mut ChangeInfo) -> Result<(String, Option<String>)> {
+        let args = info.args_mut();
+        let name = match args[1].is_null() {
+            true => return Err(Error::Module("name cannot be NULL".to_owned())),
+            false => args[1].get_str()?.to_owned(),
+        };
+        let parent = match args[2].is_null() {
+            true => None,
+            false => Some(args[2].get_str()?.to_owned()),
+        };
+        Ok((name, parent))
+    }
+
+    fn insert(&self, info: &mut ChangeInfo) -> Result<i64> {
+        let (name, parent) = Self::row(info)?;
+        if self.tree.borrow().contains_key(&name) {
+            return Err(SQLITE_CONSTRAINT);
+        }
+        let requested = info.args()[0];
+        if !requested.is_null() {
+            match &self.rowids {
+                Some(rowids) => rowids
+                    .borrow_mut()
+                    .insert(name.as_str(), requested.get_i64())?,
+                None => return Err(SQLITE_CONSTRAINT),
+            }
+        }
+        self.tree.borrow_mut().insert(name.clone(), parent);
+        self.rowid(&name)
+    }
+
+    fn update(&self, info: &mut ChangeInfo) -> Result<i64> {
+        let rowid = info.rowid().get_i64();
+        if info.args()[0].get_i64() != rowid {
+            return Err(Error::Module(
+                "cannot change the rowid of a node".to_owned(),
+            ));
+        }
+        let (name, parent) = Self::row(info)?;
+        let old = match self.name(rowid) {
+            Some(x) => x,
+            None => return Ok(rowid),
+        };
+        let mut tree = self.tree.borrow_mut();
+        if name != old {
+            if tree.contains_key(&name) {
+                return Err(SQLITE_CONSTRAINT);
+            }
+            if let Some(rowids) = &self.rowids {
+                rowids.borrow_mut().rekey(rowid, name.as_str())?;
+            }
+            tree.remove(&old);
+        }
+        tree.insert(name, parent);
+        Ok(rowid)
+    }
+
+    fn delete(&self, info: &mut ChangeInfo) -> Result<i64> {
+        let rowid = info.rowid().get_i64();
+        if let Some(name) = self.name(rowid) {
+            self.tree.borrow_mut().remove(&name);
+            if let Some(rowids) = &self.rowids {
+                rowids.borrow_mut().remove_rowid(rowid);
+            }
+        }
+        Ok(rowid)
+    }
+}
+
+impl<'vtab> VTab<'vtab> for HierarchyVTab<'vtab> {
+    type Aux = Tree;
+    type Cursor = HierarchyCursor<'vtab>;
+
+    fn connect(_: &VTabConnection, aux: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        let rowids = match &args[3..] {
+            [] => Some(RefCell::default()),
+            ["naive"] => None,
+            _ => return Err(Error::Module("expected no arguments or naive".to_owned())),
+        };
+        let vtab = HierarchyVTab { tree: aux, rowids };
+        Ok(("CREATE TABLE x (name TEXT, parent TEXT)".to_owned(), vtab))
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        Ok(HierarchyCursor {
+            vtab: self,
+            current: None,
+        })
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for HierarchyVTab<'vtab> {
+    fn create(
+        db: &'vtab VTabConnection,
+        aux: &'vtab Self::Aux,
+        args: &[&str],
+    ) -> Result<(String, Self)> {
+        Self::connect(db, aux, args)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+impl<'vtab> UpdateVTab<'vtab> for HierarchyVTab<'vtab> {
+    fn update(&'vtab self, info: &mut ChangeInfo) -> Result<i64> {
+        match info.change_type() {
+            ChangeType::Insert => self.insert(info),
+            ChangeType::Update => self.update(info),
+            ChangeType::Delete => self.delete(info),
+        }
+    }
+}
+
+struct HierarchyCursor<'vtab> {
+    vtab: &'vtab HierarchyVTab<'vtab>,
+    /// The current node. The cursor finds the next node by name, so it tolerates changes to
+    /// the tree while it is open.
+    current: Option<(String, Option<String>)>,
+}
+
+impl VTabCursor for HierarchyCursor<'_> {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        let tree = self.vtab.tree.borrow();
+        self.current = tree.iter().next().map(|(k, v)| (k.clone(), v.clone()));
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        let tree = self.vtab.tree.borrow();
+        self.current = match &self.current {
+            Some((name, _)) => tree
+                .range::<str, _>((Excluded(name.as_str()), Unbounded))
+                .next()
+                .map(|(k, v)| (k.clone(), v.clone())),
+            None => None,
+        };
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        self.current.is_none()
+    }
+
+    fn column(&mut self, idx: usize, ctx: &ColumnContext) -> Result<()> {
+        let (name, parent) = self.current.as_ref().unwrap();
+        match idx {
+            0 => ctx.set_result(name.clone()),
+            _ => ctx.set_result(parent.clone()),
+        }
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        let (name, _) = self.current.as_ref().unwrap();
+        self.vtab.rowid(name)
+    }
+}
+
+#[cfg(all(test, feature = "static"))]
+mod test {
+    use super::*;
+
+    fn setup(naive: bool, nodes: &[(&str, Option<&str>)]) -> Result<(Database, Tree)> {
+        let tree = Tree::default();
+        tree.borrow_mut().extend(
+            nodes
+                .iter()
+                .map(|(name, parent)| (name.to_string(), parent.map(str::to_owned))),
+        );
+        let db = Database::open(":memory:")?;
+        register(&db, tree.clone())?;
+        let args = if naive { "(naive)" } else { "" };
+        db.execute(&format!("CREATE VIRTUAL TABLE h USING hierarchy{args}"), ())?;
+        Ok((db, tree))
+    }
+
+    fn nodes(tree: &Tree) -> Vec<(String, Option<String>)> {
+        tree.borrow()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+
+    fn node(name: &str, parent: Option<&str>) -> (String, Option<String>) {
+        (name.to_owned(), parent.map(str::to_owned))
+    }
+
+    /// Prefix the name of every child of the root with the name of its parent.
+    const RENAME_CHILDREN: &str = "UPDATE h SET name = p.name || '.' || h.name
+        FROM h AS p WHERE p.name = h.parent AND p.parent IS NULL";
+
+    /// Delete every leaf node.
+    const DELETE_LEAVES: &str = "DELETE FROM h WHERE rowid IN (
+        SELECT c.rowid FROM h AS c LEFT JOIN h AS k ON k.parent = c.name
+        WHERE k.name IS NULL)";
+
+    const TREE: &[(&str, Option<&str>)] = &[
+        ("m", None),
+        ("a", Some("m")),
+        ("b", Some("m")),
+        ("x", Some("a")),
+    ];
+
+    #[test]
+    fn naive_rowids() -> Result<()> {
+        // Renaming "a" to "m.a" moves it after "m", so the rowid SQLite collected for "b"
+        // now refers to "m".
+        let (db, tree) = setup(true, TREE)?;
+        db.execute(RENAME_CHILDREN, ())?;
+        assert_ne!(
+            nodes(&tree),
+            vec![
+                node("m", None),
+                node("m.a", Some("m")),
+                node("m.b", Some("m")),
+                node("x", Some("a")),
+            ]
+        );
+
+        // Deleting "b" renumbers "x", so it is not deleted.
+        let (db, tree) = setup(true, TREE)?;
+        db.execute(DELETE_LEAVES, ())?;
+        assert_ne!(nodes(&tree), vec![node("a", Some("m")), node("m", None)]);
+        Ok(())
+    }
+
+    #[test]
+    fn stable_update() -> Result<()> {
+        let (db, tree) = setup(false, TREE)?;
+        db.execute(RENAME_CHILDREN, ())?;
+        assert_eq!(
+            nodes(&tree),
+            vec![
+                node("m", None),
+                node("m.a", Some("m")),
+                node("m.b", Some("m")),
+                node("x", Some("a")),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn stable_delete() -> Result<()> {
+        let (db, tree) = setup(false, TREE)?;
+        db.execute(DELETE_LEAVES, ())?;
+        assert_eq!(nodes(&tree), vec![node("a", Some("m")), node("m", None)]);
+        Ok(())
+    }
+
+    #[test]
+    fn recursive_delete() -> Result<()> {
+        let (db, tree) = setup(false, TREE)?;
+        let depth: Vec<(String, i64)> = db
+            .prepare(
+                "WITH RECURSIVE sub(name, depth) AS (
+                    SELECT name, 0 FROM h WHERE parent IS NULL
+                    UNION ALL
+                    SELECT h.name, sub.depth + 1 FROM h JOIN sub ON h.parent = sub.name
+                ) SELECT name, depth FROM sub ORDER BY name",
+            )?
+            .query(())?
+            .map(|row| Ok((row[0].get_str()?.to_owned(), row[1].get_i64())))
+            .collect()?;
+        assert_eq!(
+            depth,
+            vec![
+                ("a".to_owned(), 1),
+                ("b".to_owned(), 1),
+                ("m".to_owned(), 0),
+                ("x".to_owned(), 2),
+            ]
+        );
+        db.execute(
+            "WITH RECURSIVE sub(name) AS (
+                SELECT 'a' UNION ALL SELECT h.name FROM h JOIN sub ON h.parent = sub.name
+            ) DELETE FROM h WHERE name IN sub",
+            (),
+        )?;
+        assert_eq!(nodes(&tree), vec![node("b", Some("m")), node("m", None)]);
+        Ok(())
+    }
+
+    #[test]
+    fn external_changes() -> Result<()> {
+        let (db, tree) = setup(false, TREE)?;
+        let rowid = |name: &str| -> Result<i64> {
+            db.query_row("SELECT rowid FROM h WHERE name = ?", [name], |r| {
+                Ok(r[0].get_i64())
+            })
+        };
+        let before = rowid("m")?;
+        tree.borrow_mut().insert("0".to_owned(), None);
+        assert_eq!(rowid("m")?, before);
+        db.execute("INSERT INTO h (rowid, name) VALUES (100, 'z')", ())?;
+        assert_eq!(rowid("z")?, 100);
+        assert!(db
+            .execute("INSERT INTO h (rowid, name) VALUES (100, 'y')", ())
+            .is_err());
+        Ok(())
+    }
+}
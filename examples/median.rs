@@ -0,0 +1,191 @@
+//! An aggregate function which computes the median of its arguments, without holding all of
+//! them in memory.
+use sqlite3_ext::{function::aggregate::SpillingAccumulator, function::*, *};
+
+/// Number of values held in memory before spilling to a temporary table.
+const THRESHOLD: usize = 10_000;
+
+#[sqlite3_ext_fn(n_args=1, risk_level=Innocuous, deterministic)]
+struct Median {
+    values: SpillingAccumulator,
+}
+
+impl FromUserData<usize> for Median {
+    fn from_user_data(threshold: &usize) -> Self {
+        Median {
+            values: SpillingAccumulator::new(*threshold),
+        }
+    }
+}
+
+impl AggregateFunction<usize> for Median {
+    fn step(&mut self, ctx: &Context, args: &mut [&mut ValueRef]) -> Result<()> {
+        match args[0].value_type() {
+            ValueType::Null => Ok(()),
+            ValueType::Integer | ValueType::Float => {
+                self.values.push(ctx.db(), args[0].to_owned()?)
+            }
+            _ => Err(Error::Module(
+                "median() requires numeric arguments".to_owned(),
+            )),
+        }
+    }
+
+    fn value(&self, ctx: &Context) -> Result<()> {
+        let len = self.values.len();
+        if len == 0 {
+            return ctx.set_result(());
+        }
+        let mut iter = self.values.sorted_iter()?.skip((len - 1) / 2);
+        let lower = as_f64(iter.next()?);
+        let upper = match len % 2 {
+            0 => as_f64(iter.next()?),
+            _ => lower,
+        };
+        ctx.set_result((lower + upper) / 2.0)
+    }
+
+    fn inverse(&mut self, _: &Context, _: &mut [&mut ValueRef]) -> Result<()> {
+        Err(Error::Module(
+            "median() does not support window frames which remove rows".to_owned(),
+        ))
+    }
+}
+
+fn as_f64(value: Option<Value>) -> f64 {
+    match value {
+        Some(Value::Integer(x)) => x as f64,
+        Some(Value::Float(x)) => x,
+        _ => unreachable!(),
+    }
+}
+
+fn register(db: &Connection, threshold: usize) -> Result<()> {
+    db.create_aggregate_function::<_, Median>("median", &MEDIAN_OPTS, threshold)
+}
+
+#[sqlite3_ext_main]
+fn init(db: &Connection) -> Result<()> {
+    register(db, THRESHOLD)
+}
+
+#[cfg(all(test, feature = "static"))]
+mod test {
+    use super::*;
+
+    fn setup(threshold: usize, values: &[i64]) -> Result<Database> {
+        let conn = Database::open(":memory:")?;
+        register(&conn, threshold)?;
+        conn.execute("CREATE TABLE tbl (x)", ())?;
+        let mut stmt = conn.prepare("INSERT INTO tbl VALUES (?)")?;
+        for x in values {
+            stmt.execute([*x])?;
+        }
+        Ok(conn)
+    }
+
+    fn median(conn: &Connection) -> Result<Option<f64>> {
+        conn.query_row("SELECT median(x) FROM tbl", (), |r| {
+            Ok(match r[0].value_type() {
+                ValueType::Null => None,
+                _ => Some(r[0].get_f64()),
+            })
+        })
+    }
+
+    /// Return the number of rows in the spill table, or None if nothing ever spilled.
+    fn spilled_rows(conn: &Connection) -> Result<Option<i64>> {
+        let sql = "SELECT COUNT(*) FROM sqlite_temp_master WHERE name = 'sqlite3_ext_spill'";
+        match conn.query_row(sql, (), |r| Ok(r[0].get_i64()))? {
+            0 => Ok(None),
+            _ => conn.query_row("SELECT COUNT(*) FROM temp.sqlite3_ext_spill", (), |r| {
+                Ok(Some(r[0].get_i64()))
+            }),
+        }
+    }
+
+    #[test]
+    fn straddle_threshold() -> Result<()> {
+        const THRESHOLD: usize = 8;
+        for len in [0, 1, 2, 7, 8, 9, 10, 16, 17, 100] {
+            // Insert the values out of order.
+            let values: Vec<i64> = (0..len).map(|i| (i * 37) % len).collect();
+            let conn = setup(THRESHOLD, &values)?;
+            let expected = match len {
+                0 => None,
+                _ => Some((len - 1) as f64 / 2.0),
+            };
+            assert_eq!(median(&conn)?, expected, "len {len}");
+            let spilled = (len as usize > THRESHOLD).then_some(0);
+            assert_eq!(spilled_rows(&conn)?, spilled, "len {len}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn mixed() -> Result<()> {
+        let conn = setup(2, &[])?;
+        conn.execute(
+            "INSERT INTO tbl VALUES (NULL), (3.5), (1), (NULL), (-2), (10), (2.5)",
+            (),
+        )?;
+        assert_eq!(median(&conn)?, Some(2.5));
+        conn.execute("INSERT INTO tbl VALUES ('x')", ())?;
+        let err = median(&conn).unwrap_err();
+        assert!(
+            matches!(err, Error::Sqlite(_, Some(ref m)) if m.contains("numeric")),
+            "{err:?}"
+        );
+        assert_eq!(spilled_rows(&conn)?, Some(0));
+        Ok(())
+    }
+
+    #[test]
+    fn empty() -> Result<()> {
+        // The aggregate is finalized without ever being stepped.
+        let conn = setup(0, &[1, 2, 3])?;
+        let (ret,): (Option<f64>,) =
+            conn.query_row_as("SELECT median(x) FROM tbl WHERE x > 10", ())?;
+        assert_eq!(ret, None);
+        let (ret,): (f64,) = conn.query_row_as("SELECT median(x) FROM tbl WHERE x > 1", ())?;
+        assert_eq!(ret, 2.5);
+        Ok(())
+    }
+
+    #[test]
+    fn grouped() -> Result<()> {
+        let values: Vec<i64> = (0..50).collect();
+        let conn = setup(4, &values)?;
+        let results: Vec<(i64, f64)> = conn
+            .prepare("SELECT x % 3, median(x) FROM tbl GROUP BY 1 ORDER BY 1")?
+            .query_as(())?
+            .collect()?;
+        assert_eq!(results, vec![(0, 24.0), (1, 25.0), (2, 24.5)]);
+        assert_eq!(spilled_rows(&conn)?, Some(0));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(modern_sqlite)]
+    fn window() -> Result<()> {
+        let conn = setup(2, &[5, 1, 4, 2, 3])?;
+        // A frame which only grows works.
+        let results: Vec<(f64,)> = conn
+            .prepare("SELECT median(x) OVER (ORDER BY x) FROM tbl")?
+            .query_as(())?
+            .collect()?;
+        assert_eq!(results, vec![(1.0,), (1.5,), (2.0,), (2.5,), (3.0,)]);
+        // A sliding frame needs to remove rows, which is an error.
+        let err = conn
+            .prepare("SELECT median(x) OVER (ORDER BY x ROWS 1 PRECEDING) FROM tbl")?
+            .query_as::<(f64,), _>(())?
+            .collect::<Vec<_>>()
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::Sqlite(_, Some(ref m)) if m.contains("window")),
+            "{err:?}"
+        );
+        assert_eq!(spilled_rows(&conn)?, Some(0));
+        Ok(())
+    }
+}
@@ -7,13 +7,13 @@
 use sqlite3_ext::{vtab::*, *};
 use std::rc::Rc;
 
-const COLUMN_POINTER: i32 = 1;
-
 /// Passed-in arrays must be of this type.
 type ArrayPointer = Rc<[Value]>;
 
 #[sqlite3_ext_vtab(EponymousModule)]
-struct Rarray {}
+struct Rarray {
+    schema: SchemaBuilder,
+}
 
 impl VTab<'_> for Rarray {
     type Aux = ();
@@ -21,13 +21,14 @@ impl VTab<'_> for Rarray {
 
     fn connect(db: &VTabConnection, _aux: &Self::Aux, _args: &[&str]) -> Result<(String, Self)> {
         db.set_risk_level(RiskLevel::Innocuous);
-        Ok((
-            "CREATE TABLE x ( value, pointer HIDDEN )".to_owned(),
-            Rarray {},
-        ))
+        let schema = SchemaBuilder::new()
+            .column("value", "")
+            .hidden_column("pointer", "");
+        Ok((schema.build()?, Rarray { schema }))
     }
 
     fn best_index(&self, index_info: &mut IndexInfo) -> Result<()> {
+        let pointer = self.schema.column_index("pointer");
         let mut has_ptr = false;
         for mut constraint in index_info.constraints() {
             if !constraint.usable() {
@@ -36,7 +37,7 @@ impl VTab<'_> for Rarray {
             if constraint.op() != ConstraintOp::Eq {
                 continue;
             }
-            if constraint.column() == COLUMN_POINTER {
+            if Some(constraint.column()) == pointer {
                 has_ptr = true;
                 constraint.set_argv_index(Some(0));
                 constraint.set_omit(true);
@@ -84,9 +85,9 @@ impl VTabCursor for Cursor {
     }
 
     fn column(&mut self, idx: usize, c: &ColumnContext) -> Result<()> {
-        match idx as _ {
-            COLUMN_POINTER => Ok(()),
-            _ => c.set_result(self.array.as_ref().map(|a| a[self.rowid as usize].clone())),
+        match idx {
+            0 => c.set_result(self.array.as_ref().map(|a| a[self.rowid as usize].clone())),
+            _ => Ok(()),
         }
     }
 
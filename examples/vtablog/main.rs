@@ -1,8 +1,11 @@
 //! Rust implementation of the vtablog virtual table.
 //!
 //! For more information, consult [the original implementation](https://sqlite.org/src/file/ext/misc/vtablog.c).
+//!
+//! Unlike the original, the `schema=` argument is optional. Without it, the table has the
+//! columns a, b, and c.
 
-use sqlite3_ext::{vtab::*, *};
+use sqlite3_ext::{function::FunctionOptions, vtab::*, *};
 use std::{
     cell::{Cell, RefCell},
     fmt::Arguments,
@@ -54,7 +57,7 @@ mod parsing {
 
 struct DB<O: Write> {
     out: Rc<RefCell<O>>,
-    n_inst: RefCell<usize>,
+    n_inst: AuxCell<usize>,
 }
 
 #[sqlite3_ext_vtab(StandardModule, UpdateVTab, TransactionVTab, RenameVTab)]
@@ -87,11 +90,10 @@ impl<O: Write> VTabLog<O> {
     }
 
     fn connect_create(aux: &Rc<DB<O>>, args: &[&str], method: &str) -> Result<(String, Self)> {
-        let id = {
-            let mut n_inst = aux.n_inst.borrow_mut();
+        let id = aux.n_inst.with(|n_inst| {
             *n_inst += 100;
             *n_inst
-        };
+        });
 
         let mut num_rows = 0;
         let mut schema = None;
@@ -107,7 +109,14 @@ impl<O: Write> VTabLog<O> {
             }
         }
 
-        let schema = schema.ok_or_else(|| Error::Module("schema not provided".to_owned()))?;
+        let schema = match schema {
+            Some(s) => s,
+            None => SchemaBuilder::new()
+                .column("a", "")
+                .column("b", "")
+                .column("c", "")
+                .build()?,
+        };
         let vtab = VTabLog {
             db: aux.clone(),
             id,
@@ -211,8 +220,12 @@ impl<'vtab, O: Write + 'static> TransactionVTab<'vtab> for VTabLog<O> {
 }
 
 impl<'vtab, O: Write + 'static> RenameVTab<'vtab> for VTabLog<O> {
-    fn rename(&self, name: &str) -> Result<()> {
-        writeln!(self, "rename(tab={}, name={name:?})", self.id)?;
+    fn rename(&self, schema: &str, from: &str, to: &str) -> Result<()> {
+        writeln!(
+            self,
+            "rename(tab={}, schema={schema:?}, from={from:?}, to={to:?})",
+            self.id
+        )?;
         Ok(())
     }
 }
@@ -274,7 +287,7 @@ impl<'vtab, O: Write> VTabCursor for VTabLogCursor<'vtab, O> {
         ) {
             ret = Err(e.into())
         }
-        context.set_result(ret)
+        context.set_result(ret?)
     }
 
     fn rowid(&mut self) -> Result<i64> {
@@ -360,15 +373,27 @@ impl<O: Write> Drop for VTabLogTransaction<'_, O> {
     }
 }
 
+// Each connection logs into its own buffer, which is read and cleared by
+// `SELECT vtablog_output()`. Set the VTABLOG_STDERR environment variable to log to stderr
+// instead, like the original implementation.
 #[sqlite3_ext_main]
-fn init_stderr(db: &Connection) -> Result<()> {
-    init(db, Rc::new(RefCell::new(stderr())))
+fn init_main(db: &Connection) -> Result<()> {
+    if std::env::var_os("VTABLOG_STDERR").is_some() {
+        return init(db, Rc::new(RefCell::new(stderr())));
+    }
+    let out = Rc::new(RefCell::new(vec![]));
+    init(db, out.clone())?;
+    db.create_scalar_function(
+        "vtablog_output",
+        &FunctionOptions::default().set_n_args(0),
+        move |ctx, _| ctx.set_result(String::from_utf8_lossy(&out.take()).into_owned()),
+    )
 }
 
 fn init<O: Write + 'static>(db: &Connection, out: Rc<RefCell<O>>) -> Result<()> {
     let aux = Rc::new(DB {
         out,
-        n_inst: RefCell::new(0),
+        n_inst: AuxCell::new(0),
     });
     db.create_module("vtablog", VTabLog::module(), aux)?;
     Ok(())
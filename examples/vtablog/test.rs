@@ -239,7 +239,7 @@ fn rename() -> Result<()> {
         sync(tab=100, transaction=101)
         commit(tab=100, transaction=101)
         drop_transaction(tab=100, transaction=101)
-        rename(tab=100, name="newname")
+        rename(tab=100, schema="temp", from="log", to="newname")
         disconnect(tab=100)
         drop(tab=100)
         connect(tab=200, args=["vtablog", "temp", "newname", "schema='CREATE TABLE x(a,b,c)'", "rows=3"])
@@ -273,3 +273,92 @@ fn shadow_name() -> Result<()> {
     assert_eq!(out, expected);
     Ok(())
 }
+
+#[test]
+#[cfg(modern_sqlite)]
+fn savepoint() -> Result<()> {
+    let (conn, out) = setup()?;
+    let mut outer = conn.savepoint("outer")?;
+    outer.execute("INSERT INTO log VALUES (1, 2, 3)", ())?;
+    {
+        let mut inner = outer.savepoint("inner")?;
+        inner.execute("INSERT INTO log VALUES (4, 5, 6)", ())?;
+        {
+            let mut innermost = inner.savepoint("innermost")?;
+            innermost.execute("INSERT INTO log VALUES (7, 8, 9)", ())?;
+            innermost.rollback_to()?;
+            innermost.release()?;
+        }
+        inner.rollback_to()?;
+        inner.release()?;
+    }
+    outer.release()?;
+    drop(conn);
+    let out = from_utf8(&out.borrow()).unwrap().to_owned();
+    // The outermost savepoint starts the transaction, so it is not reported to the virtual
+    // table.
+    let expected = indoc! {r#"
+        create(tab=100, args=["vtablog", "temp", "log", "schema='CREATE TABLE x(a,b,c)'", "rows=3"])
+        begin(tab=100, transaction=101)
+        sync(tab=100, transaction=101)
+        commit(tab=100, transaction=101)
+        drop_transaction(tab=100, transaction=101)
+        begin(tab=100, transaction=102)
+        update(tab=100, args=ChangeInfo { change_type: Insert, rowid: Null, args: [Null, Integer(1), Integer(2), Integer(3)], conflict_mode: Abort })
+        savepoint(tab=100, transaction=102, n=0)
+        update(tab=100, args=ChangeInfo { change_type: Insert, rowid: Null, args: [Null, Integer(4), Integer(5), Integer(6)], conflict_mode: Abort })
+        savepoint(tab=100, transaction=102, n=1)
+        update(tab=100, args=ChangeInfo { change_type: Insert, rowid: Null, args: [Null, Integer(7), Integer(8), Integer(9)], conflict_mode: Abort })
+        rollback_to(tab=100, transaction=102, n=1)
+        release(tab=100, transaction=102, n=1)
+        rollback_to(tab=100, transaction=102, n=0)
+        release(tab=100, transaction=102, n=0)
+        sync(tab=100, transaction=102)
+        commit(tab=100, transaction=102)
+        drop_transaction(tab=100, transaction=102)
+        disconnect(tab=100)
+        drop(tab=100)
+    "#};
+    assert_eq!(out, expected);
+    Ok(())
+}
+
+#[test]
+fn default_schema() -> Result<()> {
+    let conn = Database::open(":memory:")?;
+    init(&conn, Rc::new(RefCell::new(vec![])))?;
+    conn.execute("CREATE VIRTUAL TABLE temp.log USING vtablog(rows=1)", ())?;
+    let ret: (String, String, String) = conn.query_row_as("SELECT a, b, c FROM log", ())?;
+    assert_eq!(ret, ("a0".to_owned(), "b0".to_owned(), "c0".to_owned()));
+    Ok(())
+}
+
+#[test]
+fn output_per_connection() -> Result<()> {
+    let a = Database::open(":memory:")?;
+    let b = Database::open(":memory:")?;
+    init_main(&a)?;
+    init_main(&b)?;
+    a.execute(
+        "CREATE VIRTUAL TABLE temp.log USING vtablog(schema='CREATE TABLE x(a,b,c)', rows=3)",
+        (),
+    )?;
+    let output = |db: &Connection| {
+        db.query_row("SELECT vtablog_output()", (), |r| {
+            Ok(r[0].get_str()?.to_owned())
+        })
+    };
+    assert_eq!(
+        output(&a)?,
+        indoc! {r#"
+            create(tab=100, args=["vtablog", "temp", "log", "schema='CREATE TABLE x(a,b,c)'", "rows=3"])
+            begin(tab=100, transaction=101)
+            sync(tab=100, transaction=101)
+            commit(tab=100, transaction=101)
+            drop_transaction(tab=100, transaction=101)
+        "#}
+    );
+    assert_eq!(output(&a)?, "");
+    assert_eq!(output(&b)?, "");
+    Ok(())
+}
@@ -0,0 +1,141 @@
+//! Harnesses shared by the fuzz targets and by the fuzz smoke test in the main crate. Each
+//! harness accepts arbitrary input and panics only if it finds a bug.
+use sqlite3_ext::{function::*, vtab::*, *};
+
+/// A virtual table which exposes the arguments it was created with as rows.
+#[sqlite3_ext_vtab(StandardModule)]
+struct ArgsVTab {
+    args: Vec<String>,
+}
+
+struct ArgsCursor<'vtab> {
+    vtab: &'vtab ArgsVTab,
+    idx: usize,
+}
+
+impl ArgsVTab {
+    fn new(args: &[&str]) -> Result<(String, Self)> {
+        let args = args.iter().skip(3).map(|s| (*s).to_owned()).collect();
+        Ok(("CREATE TABLE x (arg)".to_owned(), ArgsVTab { args }))
+    }
+}
+
+impl<'vtab> VTab<'vtab> for ArgsVTab {
+    type Aux = ();
+    type Cursor = ArgsCursor<'vtab>;
+
+    fn connect(_: &VTabConnection, _: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        Self::new(args)
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        Ok(ArgsCursor { vtab: self, idx: 0 })
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for ArgsVTab {
+    fn create(_: &VTabConnection, _: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        Self::new(args)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+impl VTabCursor for ArgsCursor<'_> {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        self.idx = 0;
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.idx += 1;
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        self.idx >= self.vtab.args.len()
+    }
+
+    fn column(&mut self, _: usize, ctx: &ColumnContext) -> Result<()> {
+        ctx.set_result(self.vtab.args[self.idx].clone())
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(self.idx as _)
+    }
+}
+
+/// Create a virtual table using the input as its argument list, and verify that every
+/// argument the module received is a part of the input.
+pub fn vtab_args(input: &str) {
+    let db = Database::open(":memory:").unwrap();
+    db.create_module("args", ArgsVTab::module(), ()).unwrap();
+    let sql = format!("CREATE VIRTUAL TABLE tbl USING args({input})");
+    if db.execute(&sql, ()).is_err() {
+        return;
+    }
+    // The input may have created other tables, so the table might not be ours.
+    let args: Vec<String> =
+        match db.query_row("SELECT group_concat(arg, x'00') FROM tbl", (), |r| {
+            Ok(r[0].get_str()?.to_owned())
+        }) {
+            Ok(x) => x.split('\0').map(|s| s.to_owned()).collect(),
+            Err(_) => return,
+        };
+    for arg in args {
+        assert!(input.contains(&arg), "argument {arg:?} not in {input:?}");
+    }
+}
+
+/// Pass the input through scalar functions as both a BLOB and a TEXT value, and verify that
+/// the result is unchanged. The TEXT value may not be valid UTF-8.
+pub fn value_roundtrip(data: &[u8]) {
+    let db = Database::open(":memory:").unwrap();
+    let opts = FunctionOptions::default().set_n_args(1);
+    db.create_scalar_function("echo_blob", &opts, |ctx, args| {
+        ctx.set_result(Blob::from(args[0].get_blob()?))
+    })
+    .unwrap();
+    db.create_scalar_function("echo_text", &opts, |ctx, args| {
+        ctx.set_result(args[0].get_str()?.to_owned())
+    })
+    .unwrap();
+
+    let blob: Vec<u8> = db
+        .query_row("SELECT echo_blob(?)", [data], |r| {
+            Ok(r[0].get_blob()?.to_vec())
+        })
+        .unwrap();
+    assert_eq!(blob, data);
+
+    let text = db.query_row("SELECT echo_text(CAST(? AS TEXT))", [data], |r| {
+        Ok(r[0].get_blob()?.to_vec())
+    });
+    match (std::str::from_utf8(data), text) {
+        (Ok(_), Ok(text)) => assert_eq!(text, data),
+        (Err(_), Err(_)) => (),
+        (expected, actual) => panic!("expected {expected:?}, got {actual:?}"),
+    }
+}
+
+/// Prepare every statement in the input, verifying that each remainder is a strictly
+/// shorter suffix of the previous one.
+pub fn prepare_first(sql: &str) {
+    let db = Database::open(":memory:").unwrap();
+    let mut rest = sql;
+    while !rest.is_empty() {
+        let (_, next) = match db.prepare_first(rest) {
+            Ok(x) => x,
+            Err(_) => return,
+        };
+        assert!(next.len() < rest.len(), "no progress at {rest:?}");
+        assert!(rest.ends_with(next));
+        rest = next;
+    }
+}
@@ -0,0 +1,36 @@
+use super::kw;
+use syn::{
+    parse::{Parse, ParseStream},
+    *,
+};
+
+pub enum ColumnAttr {
+    Name(LitStr),
+    Decltype(LitStr),
+    Collate(LitStr),
+    Hidden,
+}
+
+impl Parse for ColumnAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(kw::name) {
+            input.parse::<kw::name>()?;
+            input.parse::<Token![=]>()?;
+            Ok(ColumnAttr::Name(input.parse()?))
+        } else if lookahead.peek(kw::decltype) {
+            input.parse::<kw::decltype>()?;
+            input.parse::<Token![=]>()?;
+            Ok(ColumnAttr::Decltype(input.parse()?))
+        } else if lookahead.peek(kw::collate) {
+            input.parse::<kw::collate>()?;
+            input.parse::<Token![=]>()?;
+            Ok(ColumnAttr::Collate(input.parse()?))
+        } else if lookahead.peek(kw::hidden) {
+            input.parse::<kw::hidden>()?;
+            Ok(ColumnAttr::Hidden)
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
@@ -6,13 +6,19 @@ use syn::{
 
 pub enum ExtAttr {
     Export(ExtAttrExport),
-    Persistent(kw::persistent),
+    Persistent(ExtAttrPersistent),
+    UnloadSafe,
 }
 
 pub struct ExtAttrExport {
     pub value: Ident,
 }
 
+pub struct ExtAttrPersistent {
+    pub tok: kw::persistent,
+    pub required: bool,
+}
+
 impl Parse for ExtAttr {
     fn parse(input: ParseStream) -> Result<Self> {
         let lookahead = input.lookahead1();
@@ -20,6 +26,10 @@ impl Parse for ExtAttr {
             input.parse().map(ExtAttr::Export)
         } else if lookahead.peek(kw::persistent) {
             input.parse().map(ExtAttr::Persistent)
+        } else if lookahead.peek(kw::unload_safe) {
+            input
+                .parse::<kw::unload_safe>()
+                .map(|_| ExtAttr::UnloadSafe)
         } else {
             Err(lookahead.error())
         }
@@ -35,3 +45,18 @@ impl Parse for ExtAttrExport {
         })
     }
 }
+
+impl Parse for ExtAttrPersistent {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let tok = input.parse::<kw::persistent>()?;
+        let required = if input.peek(token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            content.parse::<kw::required>()?;
+            true
+        } else {
+            false
+        };
+        Ok(ExtAttrPersistent { tok, required })
+    }
+}
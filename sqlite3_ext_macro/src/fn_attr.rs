@@ -8,6 +8,8 @@ pub enum FnAttr {
     NumArgs(LitInt),
     RiskLevel(FnAttrRiskLevel),
     Deterministic,
+    Export(kw::export, LitStr),
+    UserData(kw::user_data, Box<Expr>),
 }
 
 impl Parse for FnAttr {
@@ -24,6 +26,14 @@ impl Parse for FnAttr {
         } else if lookahead.peek(kw::deterministic) {
             input.parse::<kw::deterministic>()?;
             Ok(FnAttr::Deterministic)
+        } else if lookahead.peek(kw::export) {
+            let tok = input.parse::<kw::export>()?;
+            input.parse::<Token![=]>()?;
+            Ok(FnAttr::Export(tok, input.parse()?))
+        } else if lookahead.peek(kw::user_data) {
+            let tok = input.parse::<kw::user_data>()?;
+            input.parse::<Token![=]>()?;
+            Ok(FnAttr::UserData(tok, input.parse()?))
         } else {
             Err(lookahead.error())
         }
@@ -1,14 +1,16 @@
+use column_attr::*;
 use convert_case::{Case, Casing};
 use ext_attr::*;
 use fn_attr::*;
 use proc_macro::TokenStream;
 use proc_macro2::Span;
-use quote::{format_ident, quote, ToTokens};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
 use regex::Regex;
 use std::mem::replace;
 use syn::{punctuated::Punctuated, *};
 use vtab_attr::*;
 
+mod column_attr;
 mod ext_attr;
 mod fn_attr;
 mod vtab_attr;
@@ -18,16 +20,27 @@ mod kw {
     syn::custom_keyword!(EponymousModule);
     syn::custom_keyword!(EponymousOnlyModule);
     syn::custom_keyword!(FindFunctionVTab);
+    syn::custom_keyword!(HasWorkers);
     syn::custom_keyword!(Innocuous);
+    syn::custom_keyword!(IntegrityVTab);
+    syn::custom_keyword!(ReadTransactionVTab);
     syn::custom_keyword!(RenameVTab);
     syn::custom_keyword!(StandardModule);
     syn::custom_keyword!(TransactionVTab);
     syn::custom_keyword!(UpdateVTab);
+    syn::custom_keyword!(WithoutRowId);
+    syn::custom_keyword!(collate);
+    syn::custom_keyword!(decltype);
     syn::custom_keyword!(deterministic);
     syn::custom_keyword!(export);
+    syn::custom_keyword!(hidden);
     syn::custom_keyword!(n_args);
+    syn::custom_keyword!(name);
     syn::custom_keyword!(persistent);
+    syn::custom_keyword!(required);
     syn::custom_keyword!(risk_level);
+    syn::custom_keyword!(unload_safe);
+    syn::custom_keyword!(user_data);
 }
 
 /// Declare the primary extension entry point for the crate.
@@ -76,6 +89,18 @@ pub fn sqlite3_ext_main(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// documentation](https://www.sqlite.org/loadext.html#persistent_loadable_extensions) for more
 /// information.
 ///
+/// If the unload_safe keyword is included, registering functions or virtual tables whose
+/// state needs to be dropped fails while the extension is being loaded non-persistently,
+/// because SQLite may unload the library before that state is dropped. See
+/// `sqlite3_ext::Extension` for details.
+///
+/// Persistent loading requires SQLite 3.14.0. On earlier versions, an extension declared
+/// with `persistent` is loaded normally instead, and may be unloaded when the connection
+/// closes. An extension which cannot work correctly unless it stays loaded should be
+/// declared with `persistent(required)`, which makes the entry point fail on earlier
+/// versions without calling the init function. Either way, the init function can use
+/// `sqlite3_ext::Extension::is_persistent_load` to check how it is being loaded.
+///
 /// # Example
 ///
 /// Specifying a nonstandard entry point name:
@@ -96,6 +121,19 @@ pub fn sqlite3_ext_main(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// SELECT load_extension('path/to/extension', 'nonstandard_entry_point');
 /// ```
 ///
+/// Requiring persistent loading:
+///
+/// ```no_run
+/// # use sqlite3_ext_macro::*;
+/// use sqlite3_ext::*;
+///
+/// #[sqlite3_ext_init(export = sqlite3_myextension_init, persistent(required))]
+/// fn init(db: &Connection) -> Result<()> {
+///     assert!(Extension::is_persistent_load());
+///     Ok(())
+/// }
+/// ```
+///
 /// # Implementation
 ///
 /// This macro renames the original Rust function and instead creates an
@@ -107,11 +145,12 @@ pub fn sqlite3_ext_init(attr: TokenStream, item: TokenStream) -> TokenStream {
     let directives =
         parse_macro_input!(attr with Punctuated::<ExtAttr, Token![,]>::parse_terminated);
     let mut export: Option<Ident> = None;
-    let mut persistent: Option<kw::persistent> = None;
+    let mut persistent: Option<ExtAttrPersistent> = None;
+    let mut unload_safe = false;
     for d in directives {
         match d {
             ExtAttr::Export(ExtAttrExport { value }) => {
-                if let Some(_) = export {
+                if export.is_some() {
                     return Error::new(value.span(), "export specified multiple times")
                         .into_compile_error()
                         .into();
@@ -119,30 +158,47 @@ pub fn sqlite3_ext_init(attr: TokenStream, item: TokenStream) -> TokenStream {
                     export = Some(value)
                 }
             }
-            ExtAttr::Persistent(tok) => {
-                persistent = Some(tok);
+            ExtAttr::Persistent(p) => {
+                persistent = Some(p);
+            }
+            ExtAttr::UnloadSafe => {
+                unload_safe = true;
             }
         }
     }
     let mut item = parse_macro_input!(item as ItemFn);
     let extension_vis = replace(&mut item.vis, Visibility::Inherited);
     let name = item.sig.ident.clone();
-    let load_result = match persistent {
-        None => quote!(::sqlite3_ext::ffi::SQLITE_OK),
-        Some(tok) => {
-            if let Some(_) = export {
+    let (load_result, load_persistent, load_check) = match &persistent {
+        None => (quote!(::sqlite3_ext::ffi::SQLITE_OK), quote!(false), None),
+        Some(p) => {
+            if export.is_some() {
                 // Persistent loadable extensions were added in SQLite 3.14.0. If
                 // we were to return SQLITE_OK_LOAD_PERSISTENT, then the load
-                // would fail. We want the load to complete: any API which
-                // requires persistent extensions would return an error, but
-                // ignored errors imply that the persistent loading requirement
-                // is optional.
-                quote!(::sqlite3_ext::sqlite3_match_version!(
-                    3_014_000 => ::sqlite3_ext::ffi::SQLITE_OK_LOAD_PERMANENTLY,
-                    _ => ::sqlite3_ext::ffi::SQLITE_OK,
-                ))
+                // would fail. Unless persistence is required, we want the load to
+                // complete: any API which requires persistent extensions would
+                // return an error, but ignored errors imply that the persistent
+                // loading requirement is optional.
+                let load_check = p.required.then(|| {
+                    quote! {
+                        if let Err(e) = ::sqlite3_ext::Extension::require_persistent_load() {
+                            return ::sqlite3_ext::ffi::handle_error(e, err_msg);
+                        }
+                    }
+                });
+                (
+                    quote!(::sqlite3_ext::sqlite3_match_version!(
+                        3_014_000 => ::sqlite3_ext::ffi::SQLITE_OK_LOAD_PERMANENTLY,
+                        _ => ::sqlite3_ext::ffi::SQLITE_OK,
+                    )),
+                    quote!(::sqlite3_ext::sqlite3_match_version!(
+                        3_014_000 => true,
+                        _ => false,
+                    )),
+                    load_check,
+                )
             } else {
-                return Error::new(tok.span, "unexported extension cannot be persistent")
+                return Error::new(p.tok.span, "unexported extension cannot be persistent")
                     .into_compile_error()
                     .into();
             }
@@ -150,6 +206,11 @@ pub fn sqlite3_ext_init(attr: TokenStream, item: TokenStream) -> TokenStream {
     };
 
     let c_export = export.as_ref().map(|_| quote!(#[no_mangle] pub));
+    let with_export = export.as_ref().map(|x| {
+        let x = x.to_string();
+        let persistent = persistent.is_some();
+        quote!(.with_export(#x, #persistent))
+    });
     let c_name = match export {
         None => format_ident!("{}_entry", item.sig.ident),
         Some(x) => x,
@@ -167,7 +228,9 @@ pub fn sqlite3_ext_init(attr: TokenStream, item: TokenStream) -> TokenStream {
                 if let Err(e) = ::sqlite3_ext::ffi::init_api_routines(api) {
                     return ::sqlite3_ext::ffi::handle_error(e, err_msg);
                 }
-                match #name(::sqlite3_ext::Connection::from_ptr(db)) {
+                #load_check
+                let conn = ::sqlite3_ext::Connection::from_ptr(db);
+                match ::sqlite3_ext::Extension::enter_init(#load_persistent, #unload_safe, || #name(conn)) {
                     Ok(_) => #load_result,
                     Err(e) => ::sqlite3_ext::ffi::handle_error(e, err_msg),
                 }
@@ -175,7 +238,7 @@ pub fn sqlite3_ext_init(attr: TokenStream, item: TokenStream) -> TokenStream {
 
             #item
 
-            ::sqlite3_ext::Extension::new(#c_name, #name)
+            ::sqlite3_ext::Extension::new(#c_name, #name)#with_export
         };
     };
     TokenStream::from(expanded)
@@ -188,10 +251,20 @@ pub fn sqlite3_ext_init(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// of StandardModule, EponymousModule, EponymousOnlyModule. The subsequent parameters refer to
 /// traits in sqlite3_ext::vtab, and describe the functionality which the virtual table
 /// supports. See the corresponding structs and traits in sqlite3_ext::vtab for more details.
+/// The parameter WithoutRowId is the exception, which declares that the virtual table is
+/// WITHOUT ROWID (see `Module::with_without_rowid`).
 ///
 /// The resulting struct will have an associated method `module` which returns the concrete
 /// type of module specified in the first parameter, or a Result containing it.
 ///
+/// The parameter `name = "..."` sets the name of the module. The struct then also has an
+/// associated constant `MODULE_NAME` containing the name, and an associated function
+/// `register(db, aux)` which registers the module with `Connection::create_module` using that
+/// name.
+///
+/// If the struct does not implement one of the traits listed in the parameters, the compiler
+/// error points to that trait in the attribute.
+///
 /// # Examples
 ///
 /// Declare a table-valued function:
@@ -244,6 +317,22 @@ pub fn sqlite3_ext_init(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///     Ok(())
 /// }
 /// ```
+///
+/// Declare a table with a module name, and register it using that name:
+///
+/// ```no_run
+/// # use sqlite3_ext_macro::*;
+/// use sqlite3_ext::*;
+///
+/// #[sqlite3_ext_vtab(StandardModule, UpdateVTab, name = "my_table")]
+/// struct MyTable {}
+/// # sqlite3_ext_doctest_impl!(MyTable);
+///
+/// #[sqlite3_ext_main]
+/// fn init(db: &Connection) -> Result<()> {
+///     MyTable::register(db, ())
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn sqlite3_ext_vtab(attr: TokenStream, item: TokenStream) -> TokenStream {
     let attr = match parse::<VTabAttr>(attr) {
@@ -312,26 +401,62 @@ pub fn sqlite3_ext_vtab(attr: TokenStream, item: TokenStream) -> TokenStream {
         quote!(: #lifetime_bounds)
     };
     let base = match attr.base {
-        VTabBase::Standard(_) => quote!(::sqlite3_ext::vtab::StandardModule),
-        VTabBase::Eponymous(_) => quote!(::sqlite3_ext::vtab::EponymousModule),
-        VTabBase::EponymousOnly(_) => quote!(::sqlite3_ext::vtab::EponymousOnlyModule),
+        VTabBase::Standard => quote!(::sqlite3_ext::vtab::StandardModule),
+        VTabBase::Eponymous => quote!(::sqlite3_ext::vtab::EponymousModule),
+        VTabBase::EponymousOnly => quote!(::sqlite3_ext::vtab::EponymousOnlyModule),
     };
     let mut expr = quote!(#base::<Self>::new());
-    let ret = if let VTabBase::EponymousOnly(_) = attr.base {
-        expr.extend(quote!(?));
+    // EponymousOnlyModule::new is fallible, so the builder methods are applied to the
+    // unwrapped module, which is then wrapped in Ok again.
+    let fallible = matches!(attr.base, VTabBase::EponymousOnly);
+    let wrap_result = fallible && !attr.additional.is_empty();
+    let ret = if fallible {
+        if wrap_result {
+            expr.extend(quote!(?));
+        }
         quote!(::sqlite3_ext::Result<#base<#lifetime, Self>>)
     } else {
         quote!(#base<#lifetime, Self>)
     };
-    for t in attr.additional {
-        match t {
-            VTabTrait::UpdateVTab(_) => expr.extend(quote!(.with_update())),
-            VTabTrait::TransactionVTab(_) => expr.extend(quote!(.with_transactions())),
-            VTabTrait::FindFunctionVTab(_) => expr.extend(quote!(.with_find_function())),
-            VTabTrait::RenameVTab(_) => expr.extend(quote!(.with_rename())),
-        }
+    for t in &attr.additional {
+        // Use the span of the trait in the attribute, so that a missing trait
+        // implementation is reported there.
+        let method = match t {
+            VTabTrait::UpdateVTab(_) => "with_update",
+            VTabTrait::TransactionVTab(_) => "with_transactions",
+            VTabTrait::ReadTransactionVTab(_) => "with_read_transactions",
+            VTabTrait::FindFunctionVTab(_) => "with_find_function",
+            VTabTrait::RenameVTab(_) => "with_rename",
+            VTabTrait::IntegrityVTab(_) => "with_integrity",
+            VTabTrait::HasWorkers(_) => "with_workers",
+            VTabTrait::WithoutRowId(_) => "with_without_rowid",
+        };
+        let method = Ident::new(method, t.span());
+        expr.extend(quote_spanned!(t.span() => .#method()));
     }
-    if let VTabBase::EponymousOnly(_) = attr.base {
+    let register = attr.name.map(|name| {
+        let module = match attr.base {
+            VTabBase::EponymousOnly => quote!(Self::module()?),
+            _ => quote!(Self::module()),
+        };
+        quote! {
+            /// The name used by [register](Self::register).
+            pub const MODULE_NAME: &'static str = #name;
+
+            /// Register this virtual table with the connection, using
+            /// [MODULE_NAME](Self::MODULE_NAME) as the name of the module.
+            pub fn register<#lifetime #lifetime_bounds>(
+                db: &#lifetime ::sqlite3_ext::Connection,
+                aux: <Self as ::sqlite3_ext::vtab::VTab<#lifetime>>::Aux,
+            ) -> ::sqlite3_ext::Result<()>
+            where
+                Self: ::sqlite3_ext::vtab::VTab<#lifetime> + #lifetime,
+            {
+                db.create_module(Self::MODULE_NAME, #module, aux)
+            }
+        }
+    });
+    if wrap_result {
         expr = quote!(Ok(#expr));
     };
     let expanded = quote! {
@@ -345,11 +470,132 @@ pub fn sqlite3_ext_vtab(attr: TokenStream, item: TokenStream) -> TokenStream {
                 use ::sqlite3_ext::vtab::*;
                 #expr
             }
+
+            #register
         }
     };
     TokenStream::from(expanded)
 }
 
+/// Implement `sqlite3_ext::vtab::VTabColumns` for a fieldless enum.
+///
+/// Each variant declares one column, in order. Variants can be annotated with
+/// `#[column(...)]` to set the name, type, collating sequence, or HIDDEN flag of the column.
+/// See `sqlite3_ext::vtab::VTabColumns` for details.
+///
+/// # Example
+///
+/// ```no_run
+/// use sqlite3_ext::{vtab::*, *};
+///
+/// #[derive(VTabColumns)]
+/// enum Col {
+///     Id,
+///     #[column(decltype = "TEXT", collate = "NOCASE")]
+///     Name,
+///     #[column(hidden)]
+///     Payload,
+/// }
+///
+/// fn column_is_name(index_info: &IndexInfo) -> bool {
+///     index_info
+///         .constraints()
+///         .any(|c| c.column() == i32::from(Col::Name))
+/// }
+/// ```
+#[proc_macro_derive(VTabColumns, attributes(column))]
+pub fn derive_vtab_columns(item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as DeriveInput);
+    match vtab_columns(&item) {
+        Ok(x) => TokenStream::from(x),
+        Err(e) => TokenStream::from(e.into_compile_error()),
+    }
+}
+
+fn vtab_columns(item: &DeriveInput) -> Result<proc_macro2::TokenStream> {
+    let data = match &item.data {
+        Data::Enum(x) => x,
+        _ => return Err(Error::new_spanned(item, "only applies to enums")),
+    };
+    if data.variants.is_empty() {
+        return Err(Error::new_spanned(item, "at least one column is required"));
+    }
+    let mut fragment = vec![];
+    let mut from_index = vec![];
+    let mut builder = quote!(::sqlite3_ext::vtab::SchemaBuilder::new());
+    for (i, v) in data.variants.iter().enumerate() {
+        if !matches!(v.fields, Fields::Unit) {
+            return Err(Error::new_spanned(v, "columns cannot have fields"));
+        }
+        if let Some((_, d)) = &v.discriminant {
+            return Err(Error::new_spanned(d, "columns cannot have discriminants"));
+        }
+        let quote_ident = |x: &str| format!("\"{}\"", x.replace('"', "\"\""));
+        let mut name = v.ident.to_string().to_case(Case::Snake);
+        let mut ty = String::new();
+        let mut collate = String::new();
+        let mut hidden = false;
+        for attr in v.attrs.iter().filter(|a| a.path.is_ident("column")) {
+            let directives =
+                attr.parse_args_with(Punctuated::<ColumnAttr, Token![,]>::parse_terminated)?;
+            for d in directives {
+                match d {
+                    ColumnAttr::Name(x) => name = x.value(),
+                    ColumnAttr::Decltype(x) => ty = x.value(),
+                    ColumnAttr::Collate(x) => {
+                        collate = format!("COLLATE {}", quote_ident(&x.value()))
+                    }
+                    ColumnAttr::Hidden => hidden = true,
+                }
+            }
+        }
+        let join = |parts: &[&str]| {
+            let parts: Vec<_> = parts.iter().filter(|p| !p.is_empty()).copied().collect();
+            parts.join(" ")
+        };
+        let decltype = join(&[&ty, &collate]);
+        // HIDDEN must be part of the type, so it goes before the COLLATE constraint.
+        let quoted = quote_ident(&name);
+        let hidden_kw = if hidden { "HIDDEN" } else { "" };
+        fragment.push(join(&[&quoted, &ty, hidden_kw, &collate]));
+        let ident = &v.ident;
+        from_index.push(quote!(#i => Some(Self::#ident)));
+        builder.extend(match hidden {
+            true => quote!(.hidden_column(#name, #decltype)),
+            false => quote!(.column(#name, #decltype)),
+        });
+    }
+    let ident = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+    let count = data.variants.len();
+    let fragment = fragment.join(", ");
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics ::sqlite3_ext::vtab::VTabColumns for #ident #ty_generics #where_clause {
+            const COUNT: usize = #count;
+            const SCHEMA_FRAGMENT: &'static str = #fragment;
+
+            fn from_index(idx: usize) -> Option<Self> {
+                match idx {
+                    #(#from_index,)*
+                    _ => None,
+                }
+            }
+
+            fn schema_builder() -> ::sqlite3_ext::vtab::SchemaBuilder {
+                #builder
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics From<#ident #ty_generics> for i32 #where_clause {
+            fn from(col: #ident #ty_generics) -> i32 {
+                col as i32
+            }
+        }
+    })
+}
+
 /// Create a FunctionOptions for an application-defined function.
 ///
 /// This macro declares a FunctionOptions constant with the provided values. The constant will
@@ -364,6 +610,14 @@ pub fn sqlite3_ext_vtab(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// - `n_args=N` corresponds to set_n_args.
 /// - `risk_level=X` corresponds to set_risk_level.
 /// - `deterministic` corresponds to set_desterministic with true.
+/// - `export="name"` additionally generates a function which registers the function with a
+///   connection under the given SQL name. The generated function is named after the
+///   function, so the examples above would create `register_count_horses`. A fn is
+///   registered with create_scalar_function and a struct is registered with
+///   create_aggregate_function.
+/// - `user_data=EXPR` provides the user data for an aggregate function registered with
+///   `export`. The expression is evaluated each time the function is registered. If it is
+///   omitted, the user data is `()`.
 ///
 /// # Example
 ///
@@ -379,6 +633,36 @@ pub fn sqlite3_ext_vtab(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///     db.create_scalar_function("random_number", &RANDOM_NUMBER_OPTS, random_number)
 /// }
 /// ```
+///
+/// Generating the registration function:
+///
+/// ```no_run
+/// use sqlite3_ext::{function::*, *};
+///
+/// #[sqlite3_ext_fn(n_args=1, export="total_len", user_data=0usize)]
+/// #[derive(Default)]
+/// pub struct TotalLen(usize);
+///
+/// impl AggregateFunction<usize> for TotalLen {
+///     fn step(&mut self, _: &Context, args: &mut [&mut ValueRef]) -> Result<()> {
+///         self.0 += args[0].get_str()?.len();
+///         Ok(())
+///     }
+///
+///     fn value(&self, ctx: &Context) -> Result<()> {
+///         ctx.set_result(self.0 as i64)
+///     }
+///
+///     fn inverse(&mut self, _: &Context, args: &mut [&mut ValueRef]) -> Result<()> {
+///         self.0 -= args[0].get_str()?.len();
+///         Ok(())
+///     }
+/// }
+///
+/// pub fn init(db: &Connection) -> Result<()> {
+///     register_total_len(db)
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn sqlite3_ext_fn(attr: TokenStream, item: TokenStream) -> TokenStream {
     let directives =
@@ -401,6 +685,8 @@ pub fn sqlite3_ext_fn(attr: TokenStream, item: TokenStream) -> TokenStream {
         #[automatically_derived]
         #vis const #opts_name: ::sqlite3_ext::function::FunctionOptions = ::sqlite3_ext::function::FunctionOptions::default()
     };
+    let mut export: Option<(kw::export, LitStr)> = None;
+    let mut user_data: Option<(kw::user_data, Box<Expr>)> = None;
     for d in directives {
         match d {
             FnAttr::NumArgs(x) => opts.extend(quote!(.set_n_args(#x))),
@@ -411,10 +697,65 @@ pub fn sqlite3_ext_fn(attr: TokenStream, item: TokenStream) -> TokenStream {
                 opts.extend(quote!(.set_risk_level(::sqlite3_ext::RiskLevel::DirectOnly)))
             }
             FnAttr::Deterministic => opts.extend(quote!(.set_deterministic(true))),
+            FnAttr::Export(tok, name) => {
+                if export.is_some() {
+                    return TokenStream::from(
+                        Error::new(tok.span, "duplicate export").into_compile_error(),
+                    );
+                }
+                export = Some((tok, name));
+            }
+            FnAttr::UserData(tok, expr) => {
+                if user_data.is_some() {
+                    return TokenStream::from(
+                        Error::new(tok.span, "duplicate user_data").into_compile_error(),
+                    );
+                }
+                user_data = Some((tok, expr));
+            }
         }
     }
+    let register = match (&export, &user_data, &item) {
+        (None, None, _) => quote!(),
+        (None, Some((tok, _)), _) => {
+            return TokenStream::from(
+                Error::new(tok.span, "user_data requires export").into_compile_error(),
+            )
+        }
+        (Some(_), Some((tok, _)), Item::Fn(_)) => {
+            return TokenStream::from(
+                Error::new(tok.span, "user_data only applies to aggregate functions")
+                    .into_compile_error(),
+            )
+        }
+        (Some((_, name)), None, Item::Fn(_)) => {
+            let register_name =
+                format_ident!("register_{}", ident.to_string().to_case(Case::Snake));
+            quote! {
+                #[automatically_derived]
+                #vis fn #register_name(db: &::sqlite3_ext::Connection) -> ::sqlite3_ext::Result<()> {
+                    db.create_scalar_function(#name, &#opts_name, #ident)
+                }
+            }
+        }
+        (Some((_, name)), user_data, _) => {
+            let register_name =
+                format_ident!("register_{}", ident.to_string().to_case(Case::Snake));
+            let user_data = match user_data {
+                Some((_, expr)) => quote!(#expr),
+                None => quote!(()),
+            };
+            quote! {
+                #[automatically_derived]
+                #vis fn #register_name(db: &::sqlite3_ext::Connection) -> ::sqlite3_ext::Result<()> {
+                    db.create_aggregate_function::<_, #ident>(#name, &#opts_name, #user_data)
+                }
+            }
+        }
+    };
     let expanded = quote! {
         #opts;
+        #register
         #item
     };
     TokenStream::from(expanded)
@@ -1,4 +1,5 @@
 use super::kw;
+use proc_macro2::Span;
 use syn::{
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
@@ -7,31 +8,81 @@ use syn::{
 
 pub struct VTabAttr {
     pub base: VTabBase,
-    pub additional: Punctuated<VTabTrait, Token![,]>,
+    pub additional: Vec<VTabTrait>,
+    pub name: Option<LitStr>,
 }
 
 pub enum VTabBase {
-    Standard(kw::StandardModule),
-    Eponymous(kw::EponymousModule),
-    EponymousOnly(kw::EponymousOnlyModule),
+    Standard,
+    Eponymous,
+    EponymousOnly,
+}
+
+enum VTabOption {
+    Trait(VTabTrait),
+    Name(LitStr),
 }
 
 pub enum VTabTrait {
     UpdateVTab(kw::UpdateVTab),
     TransactionVTab(kw::TransactionVTab),
+    ReadTransactionVTab(kw::ReadTransactionVTab),
     FindFunctionVTab(kw::FindFunctionVTab),
     RenameVTab(kw::RenameVTab),
+    IntegrityVTab(kw::IntegrityVTab),
+    HasWorkers(kw::HasWorkers),
+    WithoutRowId(kw::WithoutRowId),
 }
 
 impl Parse for VTabAttr {
     fn parse(input: ParseStream) -> Result<Self> {
         let base = input.parse()?;
-        let additional = if let Ok(_) = input.parse::<Token![,]>() {
-            Punctuated::parse_terminated(input)?
+        let options = if input.parse::<Token![,]>().is_ok() {
+            Punctuated::<VTabOption, Token![,]>::parse_terminated(input)?
         } else {
             Punctuated::new()
         };
-        Ok(VTabAttr { base, additional })
+        let mut additional: Vec<VTabTrait> = vec![];
+        let mut name = None;
+        for o in options {
+            match o {
+                VTabOption::Trait(t) => {
+                    if additional
+                        .iter()
+                        .any(|x| std::mem::discriminant(x) == std::mem::discriminant(&t))
+                    {
+                        return Err(Error::new(t.span(), "duplicate trait"));
+                    }
+                    additional.push(t);
+                }
+                VTabOption::Name(x) => {
+                    if name.is_some() {
+                        return Err(Error::new_spanned(x, "duplicate name"));
+                    }
+                    if x.value().is_empty() {
+                        return Err(Error::new_spanned(x, "name cannot be empty"));
+                    }
+                    name = Some(x);
+                }
+            }
+        }
+        Ok(VTabAttr {
+            base,
+            additional,
+            name,
+        })
+    }
+}
+
+impl Parse for VTabOption {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(kw::name) {
+            input.parse::<kw::name>()?;
+            input.parse::<Token![=]>()?;
+            input.parse().map(VTabOption::Name)
+        } else {
+            input.parse().map(VTabOption::Trait)
+        }
     }
 }
 
@@ -39,11 +90,17 @@ impl Parse for VTabBase {
     fn parse(input: ParseStream) -> Result<Self> {
         let lookahead = input.lookahead1();
         if lookahead.peek(kw::StandardModule) {
-            input.parse().map(VTabBase::Standard)
+            input
+                .parse::<kw::StandardModule>()
+                .map(|_| VTabBase::Standard)
         } else if lookahead.peek(kw::EponymousModule) {
-            input.parse().map(VTabBase::Eponymous)
+            input
+                .parse::<kw::EponymousModule>()
+                .map(|_| VTabBase::Eponymous)
         } else if lookahead.peek(kw::EponymousOnlyModule) {
-            input.parse().map(VTabBase::EponymousOnly)
+            input
+                .parse::<kw::EponymousOnlyModule>()
+                .map(|_| VTabBase::EponymousOnly)
         } else {
             Err(lookahead.error())
         }
@@ -57,12 +114,35 @@ impl Parse for VTabTrait {
             input.parse().map(VTabTrait::UpdateVTab)
         } else if lookahead.peek(kw::TransactionVTab) {
             input.parse().map(VTabTrait::TransactionVTab)
+        } else if lookahead.peek(kw::ReadTransactionVTab) {
+            input.parse().map(VTabTrait::ReadTransactionVTab)
         } else if lookahead.peek(kw::FindFunctionVTab) {
             input.parse().map(VTabTrait::FindFunctionVTab)
         } else if lookahead.peek(kw::RenameVTab) {
             input.parse().map(VTabTrait::RenameVTab)
+        } else if lookahead.peek(kw::IntegrityVTab) {
+            input.parse().map(VTabTrait::IntegrityVTab)
+        } else if lookahead.peek(kw::HasWorkers) {
+            input.parse().map(VTabTrait::HasWorkers)
+        } else if lookahead.peek(kw::WithoutRowId) {
+            input.parse().map(VTabTrait::WithoutRowId)
         } else {
             Err(lookahead.error())
         }
     }
 }
+
+impl VTabTrait {
+    pub fn span(&self) -> Span {
+        match self {
+            VTabTrait::UpdateVTab(x) => x.span,
+            VTabTrait::TransactionVTab(x) => x.span,
+            VTabTrait::ReadTransactionVTab(x) => x.span,
+            VTabTrait::FindFunctionVTab(x) => x.span,
+            VTabTrait::RenameVTab(x) => x.span,
+            VTabTrait::IntegrityVTab(x) => x.span,
+            VTabTrait::HasWorkers(x) => x.span,
+            VTabTrait::WithoutRowId(x) => x.span,
+        }
+    }
+}
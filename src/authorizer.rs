@@ -0,0 +1,366 @@
+use crate::{extension::check_transient_drop, ffi, types::*, Connection};
+use std::{
+    ffi::{c_void, CStr},
+    os::raw::{c_char, c_int},
+    panic::{catch_unwind, AssertUnwindSafe},
+    ptr::null_mut,
+};
+
+/// Key of the connection slot which owns the authorizer closure.
+const AUTHORIZER_SLOT: &str = "authorizer";
+
+/// An action which a statement being prepared will perform, as passed to the callback set
+/// with [Connection::set_authorizer].
+///
+/// The common action codes are decoded into their own variants. See [the SQLite
+/// documentation](https://www.sqlite.org/c3ref/c_alter_table.html) for the meaning of the
+/// arguments of each code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AuthAction {
+    /// Read a column of a table. If the callback returns [AuthResult::Ignore], the column
+    /// is read as NULL.
+    Read { table: String, column: String },
+    /// Insert rows into a table.
+    Insert { table: String },
+    /// Update a column of a table. If the callback returns [AuthResult::Ignore], the column
+    /// is not changed.
+    Update { table: String, column: String },
+    /// Delete rows from a table. If the callback returns [AuthResult::Ignore] for a DELETE
+    /// without a WHERE clause, the truncate optimization is disabled.
+    Delete { table: String },
+    /// Run a SELECT statement.
+    Select,
+    /// Call an SQL function.
+    Function { name: String },
+    /// Run a PRAGMA statement, with an optional argument.
+    Pragma { name: String, value: Option<String> },
+    /// Begin, commit, or roll back a transaction. The operation is "BEGIN", "COMMIT", or
+    /// "ROLLBACK".
+    Transaction { operation: String },
+    /// Create, release, or roll back to a savepoint. The operation is "BEGIN", "RELEASE",
+    /// or "ROLLBACK".
+    Savepoint { operation: String, name: String },
+    /// Attach a database file.
+    Attach { filename: String },
+    /// Detach a database.
+    Detach { database: String },
+    /// Create a table, which may be a temporary table.
+    CreateTable { table: String, temp: bool },
+    /// Drop a table, which may be a temporary table.
+    DropTable { table: String, temp: bool },
+    /// Create an index on a table, either of which may be temporary.
+    CreateIndex {
+        index: String,
+        table: String,
+        temp: bool,
+    },
+    /// Drop an index on a table, either of which may be temporary.
+    DropIndex {
+        index: String,
+        table: String,
+        temp: bool,
+    },
+    /// Create a view, which may be a temporary view.
+    CreateView { view: String, temp: bool },
+    /// Drop a view, which may be a temporary view.
+    DropView { view: String, temp: bool },
+    /// Create a trigger on a table, which may be a temporary trigger.
+    CreateTrigger {
+        trigger: String,
+        table: String,
+        temp: bool,
+    },
+    /// Drop a trigger on a table, which may be a temporary trigger.
+    DropTrigger {
+        trigger: String,
+        table: String,
+        temp: bool,
+    },
+    /// Alter a table in the given database.
+    AlterTable { database: String, table: String },
+    /// Create a virtual table using the given module.
+    CreateVTable { table: String, module: String },
+    /// Drop a virtual table using the given module.
+    DropVTable { table: String, module: String },
+    /// Any other action, with the action code and its two arguments.
+    Other(i32, Option<String>, Option<String>),
+}
+
+impl AuthAction {
+    fn from_sqlite(code: c_int, arg1: Option<String>, arg2: Option<String>) -> Self {
+        let (a, b) = (arg1.clone().unwrap_or_default(), arg2.clone());
+        let b_str = || b.clone().unwrap_or_default();
+        match code {
+            ffi::SQLITE_READ => Self::Read {
+                table: a,
+                column: b_str(),
+            },
+            ffi::SQLITE_INSERT => Self::Insert { table: a },
+            ffi::SQLITE_UPDATE => Self::Update {
+                table: a,
+                column: b_str(),
+            },
+            ffi::SQLITE_DELETE => Self::Delete { table: a },
+            ffi::SQLITE_SELECT => Self::Select,
+            ffi::SQLITE_FUNCTION => Self::Function { name: b_str() },
+            ffi::SQLITE_PRAGMA => Self::Pragma { name: a, value: b },
+            ffi::SQLITE_TRANSACTION => Self::Transaction { operation: a },
+            ffi::SQLITE_SAVEPOINT => Self::Savepoint {
+                operation: a,
+                name: b_str(),
+            },
+            ffi::SQLITE_ATTACH => Self::Attach { filename: a },
+            ffi::SQLITE_DETACH => Self::Detach { database: a },
+            ffi::SQLITE_CREATE_TABLE | ffi::SQLITE_CREATE_TEMP_TABLE => Self::CreateTable {
+                table: a,
+                temp: code == ffi::SQLITE_CREATE_TEMP_TABLE,
+            },
+            ffi::SQLITE_DROP_TABLE | ffi::SQLITE_DROP_TEMP_TABLE => Self::DropTable {
+                table: a,
+                temp: code == ffi::SQLITE_DROP_TEMP_TABLE,
+            },
+            ffi::SQLITE_CREATE_INDEX | ffi::SQLITE_CREATE_TEMP_INDEX => Self::CreateIndex {
+                index: a,
+                table: b_str(),
+                temp: code == ffi::SQLITE_CREATE_TEMP_INDEX,
+            },
+            ffi::SQLITE_DROP_INDEX | ffi::SQLITE_DROP_TEMP_INDEX => Self::DropIndex {
+                index: a,
+                table: b_str(),
+                temp: code == ffi::SQLITE_DROP_TEMP_INDEX,
+            },
+            ffi::SQLITE_CREATE_VIEW | ffi::SQLITE_CREATE_TEMP_VIEW => Self::CreateView {
+                view: a,
+                temp: code == ffi::SQLITE_CREATE_TEMP_VIEW,
+            },
+            ffi::SQLITE_DROP_VIEW | ffi::SQLITE_DROP_TEMP_VIEW => Self::DropView {
+                view: a,
+                temp: code == ffi::SQLITE_DROP_TEMP_VIEW,
+            },
+            ffi::SQLITE_CREATE_TRIGGER | ffi::SQLITE_CREATE_TEMP_TRIGGER => Self::CreateTrigger {
+                trigger: a,
+                table: b_str(),
+                temp: code == ffi::SQLITE_CREATE_TEMP_TRIGGER,
+            },
+            ffi::SQLITE_DROP_TRIGGER | ffi::SQLITE_DROP_TEMP_TRIGGER => Self::DropTrigger {
+                trigger: a,
+                table: b_str(),
+                temp: code == ffi::SQLITE_DROP_TEMP_TRIGGER,
+            },
+            ffi::SQLITE_ALTER_TABLE => Self::AlterTable {
+                database: a,
+                table: b_str(),
+            },
+            ffi::SQLITE_CREATE_VTABLE => Self::CreateVTable {
+                table: a,
+                module: b_str(),
+            },
+            ffi::SQLITE_DROP_VTABLE => Self::DropVTable {
+                table: a,
+                module: b_str(),
+            },
+            _ => Self::Other(code, arg1, arg2),
+        }
+    }
+}
+
+/// The decision made by the callback set with [Connection::set_authorizer].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthResult {
+    /// Allow the action.
+    Allow,
+    /// Reject the statement. Preparing it fails with
+    /// [SQLITE_AUTH](ffi::SQLITE_AUTH).
+    Deny,
+    /// Disallow the specific action, but allow the statement to be prepared. The effect
+    /// depends on the action; see [AuthAction]. For most actions, this is the same as
+    /// [Deny](Self::Deny).
+    Ignore,
+}
+
+impl Connection {
+    /// Set a callback which is invoked while statements are prepared, for each action the
+    /// statement will perform. This can be used to restrict what untrusted SQL can do.
+    ///
+    /// The callback is only consulted when a statement is prepared, so statements which
+    /// were prepared before it was set are not affected. If the callback panics, the
+    /// action is denied.
+    ///
+    /// A connection has a single authorizer, so this method replaces any previous
+    /// authorizer, which is dropped. The authorizer is dropped when the connection is
+    /// closed.
+    ///
+    /// The callback must not modify the database connection.
+    ///
+    /// If the callback needs to be dropped and this is called by a non-persistent
+    /// `unload_safe` extension, this function fails. See
+    /// [Extension](crate::Extension#non-persistent-extensions) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::*;
+    ///
+    /// fn forbid_attach(db: &Connection) -> Result<()> {
+    ///     db.set_authorizer(|action| match action {
+    ///         AuthAction::Attach { .. } => AuthResult::Deny,
+    ///         _ => AuthResult::Allow,
+    ///     })
+    /// }
+    /// ```
+    pub fn set_authorizer<F: FnMut(AuthAction) -> AuthResult + 'static>(
+        &self,
+        func: F,
+    ) -> Result<()> {
+        check_transient_drop::<F>("authorizer")?;
+        let _guard = self.lock();
+        let func = Box::into_raw(Box::new(func));
+        unsafe {
+            let rc = ffi::sqlite3_set_authorizer(
+                self.as_mut_ptr(),
+                Some(call_authorizer::<F>),
+                func as _,
+            );
+            if let Err(e) = Error::from_sqlite(rc) {
+                drop(Box::from_raw(func));
+                return Err(e);
+            }
+            let ret = self.set_slot(AUTHORIZER_SLOT, Some(Box::from_raw(func)));
+            if ret.is_err() {
+                ffi::sqlite3_set_authorizer(self.as_mut_ptr(), None, null_mut());
+            }
+            ret
+        }
+    }
+
+    /// Remove the authorizer set with [set_authorizer](Self::set_authorizer), dropping it.
+    pub fn clear_authorizer(&self) -> Result<()> {
+        let _guard = self.lock();
+        unsafe { ffi::sqlite3_set_authorizer(self.as_mut_ptr(), None, null_mut()) };
+        self.set_slot::<()>(AUTHORIZER_SLOT, None)
+    }
+}
+
+unsafe extern "C" fn call_authorizer<F: FnMut(AuthAction) -> AuthResult>(
+    data: *mut c_void,
+    code: c_int,
+    arg1: *const c_char,
+    arg2: *const c_char,
+    _db_name: *const c_char,
+    _trigger: *const c_char,
+) -> c_int {
+    let func = &mut *(data as *mut F);
+    let string = |s: *const c_char| match s.is_null() {
+        true => None,
+        false => Some(CStr::from_ptr(s).to_string_lossy().into_owned()),
+    };
+    let action = AuthAction::from_sqlite(code, string(arg1), string(arg2));
+    match catch_unwind(AssertUnwindSafe(|| func(action))) {
+        Ok(AuthResult::Allow) => ffi::SQLITE_OK,
+        Ok(AuthResult::Ignore) => ffi::SQLITE_IGNORE,
+        Ok(AuthResult::Deny) | Err(_) => ffi::SQLITE_DENY,
+    }
+}
+
+#[cfg(all(test, feature = "static"))]
+mod test {
+    use super::*;
+    use crate::test_helpers::prelude::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[test]
+    fn deny_column() -> Result<()> {
+        let h = TestHelpers::new();
+        h.db.execute("CREATE TABLE users(name, password)", ())?;
+        h.db.execute("INSERT INTO users VALUES ('alice', 'secret')", ())?;
+        h.db.set_authorizer(|action| match action {
+            AuthAction::Read { table, column } if table == "users" && column == "password" => {
+                AuthResult::Deny
+            }
+            _ => AuthResult::Allow,
+        })?;
+        let name = h.db.query_row("SELECT name FROM users", (), |r| {
+            Ok(r[0].get_str()?.to_owned())
+        })?;
+        assert_eq!(name, "alice");
+        let err = h.db.prepare("SELECT password FROM users").unwrap_err();
+        assert_eq!(err, Error::Sqlite(ffi::SQLITE_AUTH, None));
+        assert_eq!(err.to_string(), "access to users.password is prohibited");
+
+        h.db.clear_authorizer()?;
+        h.db.prepare("SELECT password FROM users")?;
+        Ok(())
+    }
+
+    #[test]
+    fn ignore_and_actions() -> Result<()> {
+        let h = TestHelpers::new();
+        h.db.execute("CREATE TABLE t(a, b)", ())?;
+        h.db.execute("INSERT INTO t VALUES (1, 2)", ())?;
+        let log = Rc::new(RefCell::new(vec![]));
+        let l = log.clone();
+        h.db.set_authorizer(move |action| {
+            let ret = match &action {
+                AuthAction::Read { column, .. } if column == "b" => AuthResult::Ignore,
+                _ => AuthResult::Allow,
+            };
+            l.borrow_mut().push(action);
+            ret
+        })?;
+        let row = h.db.query_row("SELECT a, b, abs(a) FROM t", (), |r| {
+            Ok((r[0].to_owned()?, r[1].to_owned()?))
+        })?;
+        assert_eq!(row, (Value::Integer(1), Value::Null));
+        assert_eq!(
+            *log.borrow(),
+            vec![
+                AuthAction::Select,
+                AuthAction::Read {
+                    table: "t".to_owned(),
+                    column: "a".to_owned()
+                },
+                AuthAction::Read {
+                    table: "t".to_owned(),
+                    column: "b".to_owned()
+                },
+                AuthAction::Function {
+                    name: "abs".to_owned()
+                },
+                AuthAction::Read {
+                    table: "t".to_owned(),
+                    column: "a".to_owned()
+                },
+            ]
+        );
+        log.borrow_mut().clear();
+        h.db.execute("PRAGMA user_version = 3", ())?;
+        assert_eq!(
+            log.borrow()[0],
+            AuthAction::Pragma {
+                name: "user_version".to_owned(),
+                value: Some("3".to_owned())
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn panic_denies() -> Result<()> {
+        let h = TestHelpers::new();
+        let first = Rc::new(());
+        let f = first.clone();
+        h.db.set_authorizer(move |_| {
+            let _ = &f;
+            panic!("authorizer failed")
+        })?;
+        let err = h.db.prepare("SELECT 1").unwrap_err();
+        assert_eq!(err, Error::Sqlite(ffi::SQLITE_AUTH, None));
+
+        // Replacing the authorizer drops the previous one.
+        h.db.set_authorizer(|_| AuthResult::Allow)?;
+        assert_eq!(Rc::strong_count(&first), 1);
+        h.db.prepare("SELECT 1")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,478 @@
+use crate::{
+    connection::quote_identifier, ffi, sqlite3_require_version, types::*, Connection,
+    TransactionType,
+};
+use std::{
+    ffi::CString,
+    io,
+    os::raw::c_int,
+    ptr::{null_mut, NonNull},
+};
+
+/// A handle for incremental I/O on a single BLOB value in the database.
+///
+/// This allows reading and writing portions of a large BLOB without loading the entire value
+/// into memory. The size of the BLOB cannot be changed through this handle; to create a BLOB
+/// which will be written incrementally, insert a `zeroblob(N)` first. This type is distinct
+/// from [Blob](crate::Blob), which is an owned BLOB value.
+///
+/// The handle also implements [Read](std::io::Read), [Write](std::io::Write), and
+/// [Seek](std::io::Seek), using an internal cursor which starts at the beginning of the
+/// BLOB. Errors from SQLite are wrapped in [std::io::Error] and can be recovered with
+/// [std::io::Error::get_ref].
+///
+/// If the row containing the BLOB is modified or deleted by any means other than this
+/// handle, the handle expires, and further operations fail with
+/// [SQLITE_ABORT](ffi::SQLITE_ABORT). An expired handle cannot be reused, so a new one must
+/// be opened with [Connection::open_blob].
+///
+/// # Examples
+///
+/// ```no_run
+/// use sqlite3_ext::*;
+/// use std::io::Read;
+///
+/// fn read_payload(db: &Connection, rowid: i64) -> Result<Vec<u8>> {
+///     let mut blob = db.open_blob("main", "payloads", "data", rowid, true)?;
+///     let mut ret = Vec::with_capacity(blob.len());
+///     blob.read_to_end(&mut ret).map_err(|e| Error::Module(e.to_string()))?;
+///     Ok(ret)
+/// }
+/// ```
+pub struct BlobIo<'db> {
+    db: &'db Connection,
+    blob: NonNull<ffi::sqlite3_blob>,
+    pos: usize,
+}
+
+impl Connection {
+    /// Open a handle for incremental I/O on the BLOB stored in the given database, table,
+    /// column, and rowid. If readonly is false, the handle can also be used to write to the
+    /// BLOB.
+    ///
+    /// Fails with [SQLITE_ERROR](ffi::SQLITE_ERROR) if the value is not a BLOB or TEXT, or
+    /// if the row does not exist.
+    pub fn open_blob(
+        &self,
+        db: &str,
+        table: &str,
+        column: &str,
+        rowid: i64,
+        readonly: bool,
+    ) -> Result<BlobIo<'_>> {
+        let db_name = CString::new(db)?;
+        let table = CString::new(table)?;
+        let column = CString::new(column)?;
+        let guard = self.lock();
+        let mut blob: *mut ffi::sqlite3_blob = null_mut();
+        unsafe {
+            let rc = ffi::sqlite3_blob_open(
+                self.as_mut_ptr(),
+                db_name.as_ptr(),
+                table.as_ptr(),
+                column.as_ptr(),
+                rowid,
+                !readonly as c_int,
+                &mut blob,
+            );
+            Error::from_sqlite_desc(rc, guard)?;
+        }
+        Ok(BlobIo {
+            db: self,
+            blob: NonNull::new(blob).unwrap(),
+            pos: 0,
+        })
+    }
+
+    /// Insert a new row into the given table of the main database, with the given column set
+    /// to a BLOB of len bytes read from reader, and return the rowid of the new row. Other
+    /// columns receive their default values.
+    ///
+    /// The BLOB is never held in memory: a zeroblob is inserted using
+    /// [Statement::bind_zeroblob](crate::query::Statement::bind_zeroblob), and then filled
+    /// from the reader using [open_blob](Self::open_blob). This happens inside of
+    /// [with_transaction](Self::with_transaction), so if the reader fails, or ends before
+    /// producing len bytes, the row is not inserted and an error is returned. Bytes after
+    /// the first len are not read. Like with_transaction, this fails while a statement which
+    /// writes to the database is running.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::*;
+    /// use std::fs::File;
+    ///
+    /// fn store_file(db: &Connection, file: File) -> Result<i64> {
+    ///     let len = file.metadata().map_err(|e| Error::Module(e.to_string()))?.len();
+    ///     db.insert_blob_streaming("payloads", "data", file, len)
+    /// }
+    /// ```
+    pub fn insert_blob_streaming(
+        &self,
+        table: &str,
+        column: &str,
+        mut reader: impl io::Read,
+        len: u64,
+    ) -> Result<i64> {
+        const CHUNK: usize = 64 * 1024;
+        let sql = format!(
+            "INSERT INTO main.{} ({}) VALUES (?)",
+            quote_identifier(table),
+            quote_identifier(column)
+        );
+        self.with_transaction(TransactionType::Deferred, |db| {
+            let mut stmt = db.prepare(&sql)?;
+            stmt.bind_zeroblob(1, len)?;
+            let rowid = stmt.insert(())?;
+            if len == 0 {
+                return Ok(rowid);
+            }
+            let mut blob = db.open_blob("main", table, column, rowid, false)?;
+            let mut buf = vec![0; usize::try_from(len).map_or(CHUNK, |len| len.min(CHUNK))];
+            let mut offset = 0;
+            while offset < blob.len() {
+                let want = buf.len().min(blob.len() - offset);
+                let read = match reader.read(&mut buf[..want]) {
+                    Ok(0) => {
+                        return Err(Error::Module(format!(
+                            "reader ended after {offset} of {len} bytes"
+                        )))
+                    }
+                    Ok(read) => read,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(Error::Module(e.to_string())),
+                };
+                blob.write_at(&buf[..read], offset)?;
+                offset += read;
+            }
+            Ok(rowid)
+        })
+    }
+}
+
+impl BlobIo<'_> {
+    /// Returns the size of the BLOB in bytes.
+    pub fn len(&self) -> usize {
+        unsafe { ffi::sqlite3_blob_bytes(self.blob.as_ptr()) as _ }
+    }
+
+    /// Returns true if the BLOB is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Fill buf with the bytes starting at offset. Fails with
+    /// [SQLITE_ERROR](ffi::SQLITE_ERROR) if the range extends past the end of the BLOB.
+    pub fn read_at(&self, buf: &mut [u8], offset: usize) -> Result<()> {
+        let (len, offset) = self.range(buf.len(), offset)?;
+        let guard = self.db.lock();
+        let rc = unsafe {
+            ffi::sqlite3_blob_read(self.blob.as_ptr(), buf.as_mut_ptr() as _, len, offset)
+        };
+        Error::from_sqlite_desc(rc, guard)
+    }
+
+    /// Write buf to the BLOB starting at offset. Fails with
+    /// [SQLITE_ERROR](ffi::SQLITE_ERROR) if the range extends past the end of the BLOB, or
+    /// [SQLITE_READONLY](ffi::SQLITE_READONLY) if the handle was opened as readonly.
+    pub fn write_at(&mut self, buf: &[u8], offset: usize) -> Result<()> {
+        let (len, offset) = self.range(buf.len(), offset)?;
+        let guard = self.db.lock();
+        let rc =
+            unsafe { ffi::sqlite3_blob_write(self.blob.as_ptr(), buf.as_ptr() as _, len, offset) };
+        Error::from_sqlite_desc(rc, guard)
+    }
+
+    /// Move the handle to the same column of a different row of the same table, and reset
+    /// the cursor to the beginning of the BLOB. This is faster than opening a new handle.
+    ///
+    /// Requires SQLite 3.7.4.
+    pub fn reopen(&mut self, rowid: i64) -> Result<()> {
+        let _ = rowid;
+        sqlite3_require_version!(3_007_004, {
+            let guard = self.db.lock();
+            let rc = unsafe { ffi::sqlite3_blob_reopen(self.blob.as_ptr(), rowid) };
+            Error::from_sqlite_desc(rc, guard)?;
+            self.pos = 0;
+            Ok(())
+        })
+    }
+
+    fn range(&self, len: usize, offset: usize) -> Result<(c_int, c_int)> {
+        match (c_int::try_from(len), c_int::try_from(offset)) {
+            (Ok(len), Ok(offset)) => Ok((len, offset)),
+            _ => Err(SQLITE_RANGE),
+        }
+    }
+}
+
+impl io::Read for BlobIo<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = buf.len().min(self.len().saturating_sub(self.pos));
+        if n == 0 {
+            return Ok(0);
+        }
+        self.read_at(&mut buf[..n], self.pos).map_err(into_io)?;
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+impl io::Write for BlobIo<'_> {
+    /// Writes as much of buf as fits before the end of the BLOB. Returns Ok(0) once the
+    /// end is reached, because a BLOB cannot be resized through this handle.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = buf.len().min(self.len().saturating_sub(self.pos));
+        if n == 0 {
+            return Ok(0);
+        }
+        self.write_at(&buf[..n], self.pos).map_err(into_io)?;
+        self.pos += n;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl io::Seek for BlobIo<'_> {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        let (base, offset) = match pos {
+            io::SeekFrom::Start(x) => (0, x as i64),
+            io::SeekFrom::End(x) => (self.len() as i64, x),
+            io::SeekFrom::Current(x) => (self.pos as i64, x),
+        };
+        match base.checked_add(offset) {
+            Some(x) if x >= 0 => {
+                self.pos = x as _;
+                Ok(x as _)
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+impl Drop for BlobIo<'_> {
+    fn drop(&mut self) {
+        unsafe { ffi::sqlite3_blob_close(self.blob.as_ptr()) };
+    }
+}
+
+impl std::fmt::Debug for BlobIo<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BlobIo")
+            .field("len", &self.len())
+            .field("pos", &self.pos)
+            .finish()
+    }
+}
+
+fn into_io(err: Error) -> io::Error {
+    let kind = match err {
+        Error::Sqlite(ffi::SQLITE_READONLY, _) => io::ErrorKind::PermissionDenied,
+        _ => io::ErrorKind::Other,
+    };
+    io::Error::new(kind, err)
+}
+
+#[cfg(all(test, feature = "static"))]
+mod test {
+    use crate::test_helpers::prelude::*;
+    use std::io::{Read, Seek, SeekFrom, Write};
+
+    const LEN: usize = 4 * 1024 * 1024;
+    const CHUNK: usize = 64 * 1024 + 7;
+
+    fn setup() -> Result<TestHelpers> {
+        let h = TestHelpers::new();
+        h.db.execute("CREATE TABLE tbl (data)", ())?;
+        h.db.execute(
+            "INSERT INTO tbl (rowid, data) VALUES (1, zeroblob(?))",
+            [LEN as i64],
+        )?;
+        Ok(h)
+    }
+
+    fn pattern(i: usize) -> u8 {
+        (i % 251) as u8
+    }
+
+    #[test]
+    fn chunked_io() -> Result<()> {
+        let h = setup()?;
+        let mut blob = h.db.open_blob("main", "tbl", "data", 1, false)?;
+        assert_eq!(blob.len(), LEN);
+        let data: Vec<u8> = (0..LEN).map(pattern).collect();
+        for chunk in data.chunks(CHUNK) {
+            blob.write_all(chunk).unwrap();
+        }
+        assert_eq!(blob.write(&[0]).unwrap(), 0);
+        drop(blob);
+
+        let blob = h.db.open_blob("main", "tbl", "data", 1, true)?;
+        let mut buf = vec![0; CHUNK];
+        blob.read_at(&mut buf, LEN - CHUNK)?;
+        assert_eq!(buf, &data[LEN - CHUNK..]);
+        let mut blob = blob;
+        blob.seek(SeekFrom::Start(0)).unwrap();
+        let mut ret = Vec::new();
+        loop {
+            let n = blob.read(&mut buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            ret.extend_from_slice(&buf[..n]);
+        }
+        assert!(ret == data, "data read does not match data written");
+        assert_eq!(blob.seek(SeekFrom::End(-1)).unwrap(), LEN as u64 - 1);
+        assert!(blob.seek(SeekFrom::Current(-(LEN as i64))).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn errors() -> Result<()> {
+        let h = setup()?;
+        h.db.execute("INSERT INTO tbl (rowid, data) VALUES (2, 5)", ())?;
+        match h.db.open_blob("main", "tbl", "data", 2, true) {
+            Err(Error::Sqlite(ffi::SQLITE_ERROR, Some(_))) => (),
+            x => panic!("expected SQLITE_ERROR, got {x:?}"),
+        }
+
+        let mut blob = h.db.open_blob("main", "tbl", "data", 1, true)?;
+        assert!(matches!(
+            blob.write_at(&[1], 0),
+            Err(Error::Sqlite(ffi::SQLITE_READONLY, _))
+        ));
+        let err = blob.write(&[1]).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+        assert!(matches!(
+            err.get_ref().unwrap().downcast_ref::<Error>(),
+            Some(Error::Sqlite(ffi::SQLITE_READONLY, _))
+        ));
+        assert!(blob.read_at(&mut [0; 2], LEN - 1).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(modern_sqlite)]
+    fn reopen() -> Result<()> {
+        let h = setup()?;
+        h.db.execute("INSERT INTO tbl (rowid, data) VALUES (3, zeroblob(10))", ())?;
+        let mut blob = h.db.open_blob("main", "tbl", "data", 1, true)?;
+        blob.reopen(3)?;
+        assert_eq!(blob.len(), 10);
+        h.db.execute("UPDATE tbl SET data = zeroblob(20) WHERE rowid = 3", ())?;
+        match blob.read_at(&mut [0; 1], 0) {
+            Err(Error::Sqlite(ffi::SQLITE_ABORT, _)) => (),
+            x => panic!("expected SQLITE_ABORT, got {x:?}"),
+        }
+        assert!(matches!(
+            blob.reopen(1),
+            Err(Error::Sqlite(ffi::SQLITE_ABORT, _))
+        ));
+        let blob = h.db.open_blob("main", "tbl", "data", 3, true)?;
+        assert_eq!(blob.len(), 20);
+        Ok(())
+    }
+
+    /// Produces pattern bytes in reads of varying sizes, and fails or ends early if asked.
+    struct PatternReader {
+        pos: usize,
+        len: usize,
+        fail: bool,
+    }
+
+    impl Read for PatternReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.pos == self.len && self.fail {
+                return Err(std::io::Error::other("disk on fire"));
+            }
+            let n = buf
+                .len()
+                .min(self.len - self.pos)
+                .min(1000 + self.pos % 5000);
+            for (i, b) in buf[..n].iter_mut().enumerate() {
+                *b = pattern(self.pos + i);
+            }
+            self.pos += n;
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn insert_streaming() -> Result<()> {
+        const LEN: usize = 10 * 1024 * 1024;
+        let h = TestHelpers::new();
+        h.db.execute("CREATE TABLE tbl (name DEFAULT 'x', data)", ())?;
+        let reader = PatternReader {
+            pos: 0,
+            len: LEN + 100,
+            fail: false,
+        };
+        let rowid =
+            h.db.insert_blob_streaming("tbl", "data", reader, LEN as u64)?;
+        let blob = h.db.open_blob("main", "tbl", "data", rowid, true)?;
+        assert_eq!(blob.len(), LEN);
+        let mut buf = vec![0; CHUNK];
+        for offset in (0..LEN).step_by(CHUNK) {
+            let buf = &mut buf[..CHUNK.min(LEN - offset)];
+            blob.read_at(buf, offset)?;
+            assert!(
+                buf.iter()
+                    .enumerate()
+                    .all(|(i, b)| *b == pattern(offset + i)),
+                "data read does not match data written at {offset}"
+            );
+        }
+        drop(blob);
+
+        let rowid =
+            h.db.insert_blob_streaming("tbl", "data", std::io::empty(), 0)?;
+        let (len, ty, name): (i64, String, String) = h.db.query_row_as(
+            "SELECT length(data), typeof(data), name FROM tbl WHERE rowid = ?",
+            [rowid],
+        )?;
+        assert_eq!((len, ty.as_str(), name.as_str()), (0, "blob", "x"));
+        Ok(())
+    }
+
+    #[test]
+    fn insert_streaming_errors() -> Result<()> {
+        let h = TestHelpers::new();
+        h.db.execute("CREATE TABLE tbl (data)", ())?;
+        let short = PatternReader {
+            pos: 0,
+            len: 100,
+            fail: false,
+        };
+        match h.db.insert_blob_streaming("tbl", "data", short, 1000) {
+            Err(Error::Module(msg)) => assert_eq!(msg, "reader ended after 100 of 1000 bytes"),
+            x => panic!("expected Error::Module, got {x:?}"),
+        }
+        let failing = PatternReader {
+            pos: 0,
+            len: 100,
+            fail: true,
+        };
+        match h.db.insert_blob_streaming("tbl", "data", failing, 1000) {
+            Err(Error::Module(msg)) => assert_eq!(msg, "disk on fire"),
+            x => panic!("expected Error::Module, got {x:?}"),
+        }
+        let (count,): (i64,) = h.db.query_row_as("SELECT COUNT(*) FROM tbl", ())?;
+        assert_eq!(count, 0);
+        assert_ne!(unsafe { ffi::sqlite3_get_autocommit(h.db.as_mut_ptr()) }, 0);
+
+        let mut stmt = h.db.prepare("SELECT length(?)")?;
+        assert_eq!(
+            stmt.bind_zeroblob(1, u64::MAX),
+            Err(Error::Sqlite(ffi::SQLITE_TOOBIG, None))
+        );
+        stmt.bind_zeroblob(1, 10)?;
+        assert_eq!(stmt.query_row((), |r| Ok(r[0].get_i64()))?, 10);
+        Ok(())
+    }
+}
@@ -0,0 +1,265 @@
+//! Generate packaging metadata for an extension.
+//!
+//! Distributors of a compiled extension often want a C header declaring its entry point and a
+//! machine-readable description of the SQL objects it provides. This module produces both by
+//! running the extension's init function against a throwaway in-memory database and
+//! introspecting the result with `PRAGMA function_list`, `PRAGMA module_list`, and `PRAGMA
+//! collation_list`. Because the init function is actually executed, any side effects it has
+//! (such as [registering an automatic extension](crate::Extension::register_auto)) also occur.
+//!
+//! The easiest way to use this module is to call [write_artifacts] from a test:
+//!
+//! ```no_run
+//! use sqlite3_ext::*;
+//!
+//! #[sqlite3_ext_main]
+//! fn init(db: &Connection) -> Result<()> {
+//!     Ok(())
+//! }
+//!
+//! #[test]
+//! fn artifacts() -> Result<()> {
+//!     build_support::write_artifacts(&init, "my_extension")?;
+//!     Ok(())
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! Running `SQLITE3_EXT_MANIFEST_DIR=target/dist cargo test` then writes
+//! `target/dist/my_extension.h` and `target/dist/my_extension.manifest.json`. When the
+//! environment variable is not set, nothing is written.
+//!
+//! This module requires the `static` feature and SQLite 3.30.0.
+#![cfg(feature = "static")]
+#![cfg_attr(docsrs, doc(cfg(feature = "static")))]
+
+#[cfg_attr(not(modern_sqlite), allow(unused_imports))]
+use super::{
+    connection::SLOT_PREFIX, ffi, sqlite3_require_version, types::*, Connection, Database,
+    Extension, FallibleIterator,
+};
+use std::{
+    collections::BTreeSet,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The environment variable which controls where [write_artifacts] writes its output.
+pub const MANIFEST_DIR_VAR: &str = "SQLITE3_EXT_MANIFEST_DIR";
+
+/// A description of the SQL objects provided by an extension. See [Extension::manifest].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    /// The name of the exported C entry point, if the extension has one.
+    pub entry_point: Option<String>,
+    /// Whether the extension is loaded persistently.
+    pub persistent: bool,
+    /// The application-defined functions registered by the extension, sorted by name.
+    pub functions: Vec<ManifestFunction>,
+    /// The names of the virtual table modules registered by the extension, sorted.
+    pub modules: Vec<String>,
+    /// The names of the collating sequences registered by the extension, sorted.
+    pub collations: Vec<String>,
+}
+
+/// A function described by a [Manifest].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ManifestFunction {
+    pub name: String,
+    /// One of "scalar", "aggregate", or "window".
+    pub kind: String,
+    /// The number of arguments, or -1 for a function which accepts any number.
+    pub n_args: i32,
+    pub deterministic: bool,
+    pub innocuous: bool,
+    pub direct_only: bool,
+}
+
+impl Manifest {
+    pub(crate) fn collect(ext: &Extension) -> Result<Manifest> {
+        let _ = ext;
+        sqlite3_require_version!(3_030_000, {
+            let baseline = Database::open(":memory:")?;
+            let db = Database::open(":memory:")?;
+            (**ext)(&db)?;
+
+            const FUNCTIONS: &str =
+                "SELECT name, type, narg, flags FROM pragma_function_list WHERE builtin = 0";
+            // Extensions compiled into SQLite also show up as non-builtin functions, so
+            // everything is compared against a fresh connection. Pragma table-valued
+            // functions are registered as modules the first time they are used, so both
+            // connections need to run the same queries before their module lists can be
+            // compared.
+            let functions = |db: &Connection| -> Result<Vec<(String, String, i32, i32)>> {
+                db.prepare(FUNCTIONS)?.query_as(())?.collect()
+            };
+            let baseline_functions: BTreeSet<_> = functions(&baseline)?.into_iter().collect();
+            let mut functions: Vec<ManifestFunction> = functions(&db)?
+                .into_iter()
+                .filter(|x| !baseline_functions.contains(x))
+                .map(|(name, kind, n_args, flags)| ManifestFunction {
+                    name,
+                    kind: match kind.as_str() {
+                        "a" => "aggregate",
+                        "w" => "window",
+                        _ => "scalar",
+                    }
+                    .to_owned(),
+                    n_args,
+                    deterministic: flags & ffi::SQLITE_DETERMINISTIC != 0,
+                    innocuous: flags & ffi::SQLITE_INNOCUOUS != 0,
+                    direct_only: flags & ffi::SQLITE_DIRECTONLY != 0,
+                })
+                .collect();
+            functions.sort();
+            functions.dedup();
+            Ok(Manifest {
+                entry_point: ext.entry_point().map(String::from),
+                persistent: ext.is_persistent(),
+                functions,
+                modules: added_names(&baseline, &db, "SELECT name FROM pragma_module_list")?,
+                collations: added_names(
+                    &baseline,
+                    &db,
+                    &format!(
+                        "SELECT name FROM pragma_collation_list WHERE substr(name, 1, {}) <> '{SLOT_PREFIX}'",
+                        SLOT_PREFIX.len()
+                    ),
+                )?,
+            })
+        })
+    }
+
+    /// Serialize the manifest as JSON.
+    pub fn to_json(&self) -> String {
+        let mut ret = String::new();
+        ret.push_str("{\n");
+        match &self.entry_point {
+            Some(x) => writeln!(ret, "  \"entry_point\": {},", json_str(x)),
+            None => writeln!(ret, "  \"entry_point\": null,"),
+        }
+        .unwrap();
+        writeln!(ret, "  \"persistent\": {},", self.persistent).unwrap();
+        ret.push_str("  \"functions\": [");
+        for (i, f) in self.functions.iter().enumerate() {
+            ret.push_str(if i == 0 { "\n" } else { ",\n" });
+            write!(
+                ret,
+                "    {{ \"name\": {}, \"kind\": {}, \"n_args\": {}, \"deterministic\": {}, \"innocuous\": {}, \"direct_only\": {} }}",
+                json_str(&f.name),
+                json_str(&f.kind),
+                f.n_args,
+                f.deterministic,
+                f.innocuous,
+                f.direct_only
+            )
+            .unwrap();
+        }
+        ret.push_str(if self.functions.is_empty() {
+            "],\n"
+        } else {
+            "\n  ],\n"
+        });
+        writeln!(ret, "  \"modules\": {},", json_list(&self.modules)).unwrap();
+        writeln!(ret, "  \"collations\": {}", json_list(&self.collations)).unwrap();
+        ret.push_str("}\n");
+        ret
+    }
+
+    /// Generate a C header which declares the entry point of the extension. The name is
+    /// used for the include guard.
+    ///
+    /// Fails if the extension does not have an exported entry point.
+    pub fn to_c_header(&self, name: &str) -> Result<String> {
+        let entry = self.entry_point.as_ref().ok_or_else(|| {
+            Error::Module("extension does not have an exported entry point".to_owned())
+        })?;
+        let guard: String = name
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' => c.to_ascii_uppercase(),
+                _ => '_',
+            })
+            .collect();
+        Ok(format!(
+            "/* Generated by sqlite3_ext. Do not edit. */\n\
+             #ifndef {guard}_H\n\
+             #define {guard}_H\n\
+             \n\
+             #include <sqlite3.h>\n\
+             \n\
+             #ifdef __cplusplus\n\
+             extern \"C\" {{\n\
+             #endif\n\
+             \n\
+             int {entry}(sqlite3 *db, char **pzErrMsg, const sqlite3_api_routines *pApi);\n\
+             \n\
+             #ifdef __cplusplus\n\
+             }}\n\
+             #endif\n\
+             \n\
+             #endif /* {guard}_H */\n"
+        ))
+    }
+}
+
+/// Write `<name>.h` and `<name>.manifest.json` for the extension into the directory named by
+/// the [MANIFEST_DIR_VAR] environment variable. If the variable is not set, nothing is
+/// written and this function returns None; otherwise it returns the paths of the header and
+/// manifest.
+pub fn write_artifacts(ext: &Extension, name: &str) -> Result<Option<(PathBuf, PathBuf)>> {
+    match std::env::var_os(MANIFEST_DIR_VAR) {
+        Some(dir) => write_artifacts_to(ext, name, Path::new(&dir)).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Write `<name>.h` and `<name>.manifest.json` for the extension into the given directory,
+/// creating it if necessary. Returns the paths of the header and manifest.
+pub fn write_artifacts_to(ext: &Extension, name: &str, dir: &Path) -> Result<(PathBuf, PathBuf)> {
+    let manifest = ext.manifest()?;
+    let header_path = dir.join(format!("{name}.h"));
+    let json_path = dir.join(format!("{name}.manifest.json"));
+    let write = |path: &PathBuf, contents: String| {
+        fs::create_dir_all(dir)
+            .and_then(|_| fs::write(path, contents))
+            .map_err(|e| Error::Module(format!("{}: {e}", path.display())))
+    };
+    write(&header_path, manifest.to_c_header(name)?)?;
+    write(&json_path, manifest.to_json())?;
+    Ok((header_path, json_path))
+}
+
+#[cfg_attr(not(modern_sqlite), allow(dead_code))]
+fn added_names(baseline: &Connection, db: &Connection, sql: &str) -> Result<Vec<String>> {
+    let names = |db: &Connection| -> Result<BTreeSet<String>> {
+        let rows: Vec<(String,)> = db.prepare(sql)?.query_as(())?.collect()?;
+        Ok(rows.into_iter().map(|(x,)| x).collect())
+    };
+    let baseline = names(baseline)?;
+    Ok(names(db)?.difference(&baseline).cloned().collect())
+}
+
+fn json_str(val: &str) -> String {
+    let mut ret = String::with_capacity(val.len() + 2);
+    ret.push('"');
+    for c in val.chars() {
+        match c {
+            '"' => ret.push_str("\\\""),
+            '\\' => ret.push_str("\\\\"),
+            '\n' => ret.push_str("\\n"),
+            '\r' => ret.push_str("\\r"),
+            '\t' => ret.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(ret, "\\u{:04x}", c as u32).unwrap(),
+            c => ret.push(c),
+        }
+    }
+    ret.push('"');
+    ret
+}
+
+fn json_list(vals: &[String]) -> String {
+    let items: Vec<String> = vals.iter().map(|x| json_str(x)).collect();
+    format!("[{}]", items.join(", "))
+}
@@ -0,0 +1,265 @@
+use super::{ffi, Connection, SQLITE_VERSION};
+use std::{ffi::CString, fmt, sync::OnceLock};
+
+/// How the JSON functions are provided by SQLite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum JsonSupport {
+    /// The JSON functions are not available.
+    Unavailable,
+    /// The JSON functions were compiled in using the JSON1 extension, which was required
+    /// before SQLite 3.38.0.
+    Extension,
+    /// The JSON functions are built in to SQLite, which is the default since SQLite 3.38.0.
+    Builtin,
+}
+
+/// The threading mode that SQLite was compiled with. See [sqlite3_threadsafe].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreadsafeMode {
+    /// SQLite was compiled without mutexes, and must not be used from more than one thread
+    /// at a time.
+    SingleThread,
+    /// Mutexes are enabled, and each connection is protected by its own mutex.
+    Serialized,
+    /// Mutexes are enabled, but a connection must not be used from more than one thread at
+    /// a time.
+    MultiThread,
+}
+
+/// A summary of the features supported by the running version of SQLite. See
+/// [capabilities].
+///
+/// Some features depend on the options SQLite was compiled with. These are detected using
+/// [sqlite3_compileoption_used], so features which require an `SQLITE_ENABLE_*` option
+/// are reported as unavailable if SQLite was compiled with
+/// `SQLITE_OMIT_COMPILEOPTION_DIAGS`.
+///
+/// These flags describe SQLite itself. Functions in this crate which use a feature still
+/// check for it, using [sqlite3_match_version](crate::sqlite3_match_version) or
+/// [sqlite3_require_version](crate::sqlite3_require_version), because the feature may also
+/// need to have been available when this crate was compiled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// The numeric version of SQLite. See [SqliteVersion::as_i32](crate::SqliteVersion::as_i32).
+    pub version: i32,
+    /// Aggregate functions can be used as window functions (SQLite 3.25.0).
+    pub window_functions: bool,
+    /// Virtual tables can process all values of an IN constraint at once (SQLite 3.38.0).
+    /// See [IndexInfoConstraint::set_value_list_wanted](crate::vtab::IndexInfoConstraint::set_value_list_wanted).
+    pub vtab_in_values: bool,
+    /// INSERT supports the ON CONFLICT clause (SQLite 3.24.0).
+    pub upsert: bool,
+    /// INSERT, UPDATE, and DELETE support the RETURNING clause (SQLite 3.35.0).
+    pub returning_clause: bool,
+    /// How the JSON functions are provided.
+    pub json: JsonSupport,
+    /// The FTS5 extension was compiled in.
+    pub fts5: bool,
+    /// Tables can be declared STRICT (SQLite 3.37.0).
+    pub strict_tables: bool,
+    /// The preupdate hook was compiled in (SQLite 3.13.0).
+    pub preupdate_hook: bool,
+    /// The serialize and deserialize interfaces were compiled in (SQLite 3.23.0).
+    pub serialize: bool,
+    /// Pointers can be passed through SQL values (SQLite 3.20.0). See
+    /// [PassedRef](crate::PassedRef).
+    pub value_pointer: bool,
+    /// The threading mode that SQLite was compiled with.
+    pub threadsafe: ThreadsafeMode,
+}
+
+static CAPABILITIES: OnceLock<Capabilities> = OnceLock::new();
+
+/// Returns the features supported by the running version of SQLite.
+///
+/// The capabilities are probed the first time this function is called, and the same value
+/// is returned for the rest of the process. Extensions can use it to decide once, during
+/// initialization, which optional features to register.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sqlite3_ext::*;
+///
+/// fn init(db: &Connection) -> Result<()> {
+///     if capabilities().window_functions {
+///         // Register window functions
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn capabilities() -> &'static Capabilities {
+    CAPABILITIES.get_or_init(Capabilities::probe)
+}
+
+/// Returns true if SQLite was compiled with the given option. The `SQLITE_` prefix is
+/// optional. For example, `sqlite3_compileoption_used("ENABLE_FTS5")`.
+pub fn sqlite3_compileoption_used(name: &str) -> bool {
+    match CString::new(name) {
+        Ok(name) => unsafe { ffi::sqlite3_compileoption_used(name.as_ptr()) != 0 },
+        Err(_) => false,
+    }
+}
+
+/// Returns the threading mode that SQLite was compiled with. The mode of an individual
+/// connection can be changed using [OpenFlags](crate::OpenFlags), unless SQLite was compiled
+/// in [SingleThread](ThreadsafeMode::SingleThread) mode.
+pub fn sqlite3_threadsafe() -> ThreadsafeMode {
+    match unsafe { ffi::sqlite3_threadsafe() } {
+        0 => ThreadsafeMode::SingleThread,
+        2 => ThreadsafeMode::MultiThread,
+        _ => ThreadsafeMode::Serialized,
+    }
+}
+
+impl Capabilities {
+    fn probe() -> Self {
+        let version = SQLITE_VERSION.as_i32();
+        let json = if version >= 3_038_000 && !sqlite3_compileoption_used("OMIT_JSON") {
+            JsonSupport::Builtin
+        } else if sqlite3_compileoption_used("ENABLE_JSON1") {
+            JsonSupport::Extension
+        } else {
+            JsonSupport::Unavailable
+        };
+        Capabilities {
+            version,
+            window_functions: version >= 3_025_000
+                && !sqlite3_compileoption_used("OMIT_WINDOWFUNC"),
+            vtab_in_values: version >= 3_038_000,
+            upsert: version >= 3_024_000,
+            returning_clause: version >= 3_035_000,
+            json,
+            fts5: sqlite3_compileoption_used("ENABLE_FTS5"),
+            strict_tables: version >= 3_037_000,
+            preupdate_hook: version >= 3_013_000
+                && sqlite3_compileoption_used("ENABLE_PREUPDATE_HOOK"),
+            serialize: (version >= 3_036_000 && !sqlite3_compileoption_used("OMIT_DESERIALIZE"))
+                || (version >= 3_023_000 && sqlite3_compileoption_used("ENABLE_DESERIALIZE")),
+            value_pointer: version >= 3_020_000,
+            threadsafe: sqlite3_threadsafe(),
+        }
+    }
+}
+
+/// Lists the version of SQLite followed by the supported features, for example `SQLite
+/// 3.38.5: window_functions upsert json(builtin)`.
+impl fmt::Display for Capabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let v = self.version;
+        write!(
+            f,
+            "SQLite {}.{}.{}:",
+            v / 1_000_000,
+            (v / 1000) % 1000,
+            v % 1000
+        )?;
+        let flags = [
+            ("window_functions", self.window_functions),
+            ("vtab_in_values", self.vtab_in_values),
+            ("upsert", self.upsert),
+            ("returning_clause", self.returning_clause),
+            ("json(extension)", self.json == JsonSupport::Extension),
+            ("json(builtin)", self.json == JsonSupport::Builtin),
+            ("fts5", self.fts5),
+            ("strict_tables", self.strict_tables),
+            ("preupdate_hook", self.preupdate_hook),
+            ("serialize", self.serialize),
+            ("value_pointer", self.value_pointer),
+        ];
+        for (name, _) in flags.iter().filter(|(_, enabled)| *enabled) {
+            write!(f, " {name}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Connection {
+    /// Returns the features supported by the running version of SQLite. This is the same as
+    /// [capabilities].
+    pub fn capabilities(&self) -> &'static Capabilities {
+        capabilities()
+    }
+}
+
+#[cfg(all(test, feature = "static"))]
+mod test {
+    use super::*;
+    use crate::{test_helpers::prelude::*, Result};
+
+    #[test]
+    fn probe() -> Result<()> {
+        let h = TestHelpers::new();
+        let caps = h.db.capabilities();
+        assert!(std::ptr::eq(caps, capabilities()));
+        assert_eq!(caps.version, SQLITE_VERSION.as_i32());
+        assert_eq!(caps.upsert, caps.version >= 3_024_000);
+        assert_eq!(caps.returning_clause, caps.version >= 3_035_000);
+        assert_eq!(caps.strict_tables, caps.version >= 3_037_000);
+        assert_eq!(caps.vtab_in_values, caps.version >= 3_038_000);
+        assert_eq!(caps.fts5, sqlite3_compileoption_used("SQLITE_ENABLE_FTS5"));
+        assert_eq!(
+            caps.preupdate_hook,
+            sqlite3_compileoption_used("ENABLE_PREUPDATE_HOOK")
+        );
+        assert!(!sqlite3_compileoption_used("NOT_A_REAL_OPTION"));
+        assert!(!sqlite3_compileoption_used("A\0B"));
+
+        // The flags agree with what SQLite actually accepts.
+        let works = |sql: &str| h.db.prepare(sql).is_ok();
+        h.db.execute("CREATE TABLE t (a INTEGER PRIMARY KEY, b)", ())?;
+        assert_eq!(
+            works("INSERT INTO t VALUES (1, 1) ON CONFLICT (a) DO NOTHING"),
+            caps.upsert
+        );
+        assert_eq!(
+            works("DELETE FROM t WHERE a = 2 RETURNING a"),
+            caps.returning_clause
+        );
+        assert_eq!(
+            works("CREATE TABLE s (a INTEGER) STRICT"),
+            caps.strict_tables
+        );
+        assert_eq!(
+            works("SELECT json_array(1)"),
+            caps.json != JsonSupport::Unavailable
+        );
+        assert_eq!(works("SELECT sum(1) OVER ()"), caps.window_functions);
+
+        let display = caps.to_string();
+        assert!(display.starts_with(&format!("SQLite {}:", SQLITE_VERSION.as_str())));
+        assert_eq!(display.contains(" upsert"), caps.upsert);
+        Ok(())
+    }
+
+    #[test]
+    fn match_version() {
+        let caps = capabilities();
+        // The match macro only takes a branch if the feature is also available at runtime.
+        // In builds without modern_sqlite it always takes the fallback.
+        let check = |matched: bool, flag: bool| {
+            assert!(!matched || flag);
+            #[cfg(modern_sqlite)]
+            assert_eq!(matched, flag);
+        };
+        check(
+            sqlite3_match_version! { 3_020_000 => true, _ => false },
+            caps.value_pointer,
+        );
+        check(
+            sqlite3_match_version! { 3_025_000 => true, _ => false },
+            caps.version >= 3_025_000,
+        );
+        check(
+            sqlite3_match_version! { 3_038_000 => true, _ => false },
+            caps.vtab_in_values,
+        );
+        assert_eq!(caps.threadsafe, sqlite3_threadsafe());
+        assert_eq!(
+            caps.threadsafe == ThreadsafeMode::SingleThread,
+            sqlite3_compileoption_used("THREADSAFE=0")
+        );
+    }
+}
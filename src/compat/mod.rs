@@ -0,0 +1,5 @@
+//! Compatibility layers which ease migrating code written for other SQLite libraries.
+#![cfg(feature = "rusqlite_compat")]
+#![cfg_attr(docsrs, doc(cfg(feature = "rusqlite_compat")))]
+
+pub mod rusqlite_functions;
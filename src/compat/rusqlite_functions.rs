@@ -0,0 +1,700 @@
+//! An implementation of the scalar function API of
+//! [rusqlite](https://docs.rs/rusqlite/0.28/rusqlite/functions/index.html), for porting
+//! existing functions.
+//!
+//! Functions written against `rusqlite::functions::Context` can usually be moved to this
+//! crate by replacing the imports of `rusqlite::functions::*`, `rusqlite::types::*`,
+//! `rusqlite::Error` and `rusqlite::Result` with `sqlite3_ext::compat::rusqlite_functions::*`,
+//! and registering the function with [register_rusqlite_style_fn] instead of
+//! `rusqlite::Connection::create_scalar_function`. This module does not depend on rusqlite,
+//! so it can be used in loadable extensions.
+//!
+//! ```no_run
+//! use sqlite3_ext::{compat::rusqlite_functions::*, Connection};
+//!
+//! fn half(ctx: &Context<'_>) -> Result<f64> {
+//!     let value = ctx.get::<f64>(0)?;
+//!     Ok(value / 2f64)
+//! }
+//!
+//! fn init(db: &Connection) -> sqlite3_ext::Result<()> {
+//!     register_rusqlite_style_fn(db, "half", 1, FunctionFlags::default(), half)
+//! }
+//! ```
+//!
+//! # Differences from rusqlite
+//!
+//! This module is intended for migration, and new functions should use
+//! [Connection::create_scalar_function] directly. The following parts of rusqlite are
+//! different or missing:
+//!
+//! - Only scalar functions are supported. Aggregate and window functions should be ported
+//!   to [AggregateFunction](crate::function::AggregateFunction).
+//! - `Context::get_connection` is not available. Use [Context::db] to access the database
+//!   through this crate's [Connection].
+//! - [Error] only has the variants which can result from calling a function. Errors from
+//!   this crate are wrapped in [Error::SqliteFailure], which holds a [crate::Error] rather
+//!   than an error code and message. Every error is reported to SQLite using its message,
+//!   except [Error::SqliteFailure], which is reported as the wrapped error.
+//! - [FromSql] and [ToSql] are only implemented for the primitive types, strings, blobs,
+//!   [Value], and [Option]. The implementations for types from other crates, like chrono
+//!   or serde_json, are not available. [ToSqlOutput] cannot hold zeroblobs or arrays.
+//! - [Context::get_aux] returns `Ok(None)` if the stored data has a different type, instead
+//!   of failing.
+//! - Panics in the function are not caught.
+//! - The function may be `FnMut`, like in rusqlite, but it does not need to be `Send`. A
+//!   function which recursively invokes itself through SQL fails with an error.
+//! - [FunctionFlags::SQLITE_SUBTYPE] is ignored. [FunctionFlags::SQLITE_DIRECTONLY] and
+//!   [FunctionFlags::SQLITE_INNOCUOUS] are applied using
+//!   [FunctionOptions::set_risk_level].
+use crate::{
+    function::{Context as FunctionContext, FunctionOptions, TextEncoding},
+    Blob, Connection, FromValue, RiskLevel, ValueType,
+};
+use bitflags::bitflags;
+use std::{cell::RefCell, fmt, str, sync::Arc};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// A result whose error type defaults to [Error], like `rusqlite::Result`.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The result of converting a value using [FromSql].
+pub type FromSqlResult<T> = Result<T, FromSqlError>;
+
+bitflags! {
+    /// Flags describing a function, like `rusqlite::functions::FunctionFlags`.
+    pub struct FunctionFlags: i32 {
+        /// Specifies UTF-8 as the text encoding this SQL function prefers for its
+        /// parameters.
+        const SQLITE_UTF8 = 0x0000_0001;
+        /// Specifies UTF-16 using little-endian byte order as the text encoding this SQL
+        /// function prefers for its parameters.
+        const SQLITE_UTF16LE = 0x0000_0002;
+        /// Specifies UTF-16 using big-endian byte order as the text encoding this SQL
+        /// function prefers for its parameters.
+        const SQLITE_UTF16BE = 0x0000_0003;
+        /// Specifies UTF-16 using native byte order as the text encoding this SQL function
+        /// prefers for its parameters.
+        const SQLITE_UTF16 = 0x0000_0004;
+        /// Means that the function always gives the same output when the input parameters
+        /// are the same.
+        const SQLITE_DETERMINISTIC = 0x0000_0800;
+        /// Means that the function may only be invoked from top-level SQL.
+        const SQLITE_DIRECTONLY = 0x0008_0000;
+        /// Indicates to SQLite that a function may call `sqlite3_value_subtype()` to inspect
+        /// the sub-types of its arguments. Ignored by this crate.
+        const SQLITE_SUBTYPE = 0x0010_0000;
+        /// Means that the function is unlikely to cause problems even if misused.
+        const SQLITE_INNOCUOUS = 0x0020_0000;
+    }
+}
+
+/// The default flags are [FunctionFlags::SQLITE_UTF8] and
+/// [FunctionFlags::SQLITE_DETERMINISTIC], like in rusqlite.
+impl Default for FunctionFlags {
+    fn default() -> FunctionFlags {
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC
+    }
+}
+
+impl FunctionFlags {
+    fn to_options(self, n_args: i32) -> FunctionOptions {
+        let encoding = match self.bits() & 0x7 {
+            0x2 => TextEncoding::Utf16le,
+            0x3 => TextEncoding::Utf16be,
+            0x4 => TextEncoding::Utf16,
+            _ => TextEncoding::Utf8,
+        };
+        let mut opts = FunctionOptions::default()
+            .set_n_args(n_args)
+            .set_text_encoding(encoding)
+            .set_deterministic(self.contains(FunctionFlags::SQLITE_DETERMINISTIC));
+        if self.contains(FunctionFlags::SQLITE_DIRECTONLY) {
+            opts = opts.set_risk_level(RiskLevel::DirectOnly);
+        } else if self.contains(FunctionFlags::SQLITE_INNOCUOUS) {
+            opts = opts.set_risk_level(RiskLevel::Innocuous);
+        }
+        opts
+    }
+}
+
+/// The fundamental datatypes of SQLite, like `rusqlite::types::Type`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Type {
+    Null,
+    Integer,
+    Real,
+    Text,
+    Blob,
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Null => f.pad("Null"),
+            Type::Integer => f.pad("Integer"),
+            Type::Real => f.pad("Real"),
+            Type::Text => f.pad("Text"),
+            Type::Blob => f.pad("Blob"),
+        }
+    }
+}
+
+/// A borrowed SQLite value, like `rusqlite::types::ValueRef`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ValueRef<'a> {
+    Null,
+    Integer(i64),
+    Real(f64),
+    /// The bytes of a TEXT value, which are not guaranteed to be valid UTF-8.
+    Text(&'a [u8]),
+    Blob(&'a [u8]),
+}
+
+impl<'a> ValueRef<'a> {
+    fn from_value(value: &'a crate::ValueRef) -> Self {
+        match value.value_type() {
+            ValueType::Integer => ValueRef::Integer(value.get_i64()),
+            ValueType::Float => ValueRef::Real(value.get_f64()),
+            ValueType::Text => ValueRef::Text(unsafe { value.get_blob_unchecked() }),
+            ValueType::Blob => ValueRef::Blob(unsafe { value.get_blob_unchecked() }),
+            ValueType::Null => ValueRef::Null,
+        }
+    }
+
+    /// Returns the SQLite type of this value.
+    pub fn data_type(&self) -> Type {
+        match self {
+            ValueRef::Null => Type::Null,
+            ValueRef::Integer(_) => Type::Integer,
+            ValueRef::Real(_) => Type::Real,
+            ValueRef::Text(_) => Type::Text,
+            ValueRef::Blob(_) => Type::Blob,
+        }
+    }
+
+    /// If this is an INTEGER, returns it. Otherwise, fails with [FromSqlError::InvalidType].
+    pub fn as_i64(&self) -> FromSqlResult<i64> {
+        match self {
+            ValueRef::Integer(i) => Ok(*i),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+
+    /// If this is a REAL, returns it. Otherwise, fails with [FromSqlError::InvalidType].
+    pub fn as_f64(&self) -> FromSqlResult<f64> {
+        match self {
+            ValueRef::Real(f) => Ok(*f),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+
+    /// If this is TEXT, returns it. Otherwise, fails with [FromSqlError::InvalidType]. Fails
+    /// with [FromSqlError::Other] if the text is not valid UTF-8.
+    pub fn as_str(&self) -> FromSqlResult<&'a str> {
+        match self {
+            ValueRef::Text(t) => str::from_utf8(t).map_err(|e| FromSqlError::Other(Box::new(e))),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+
+    /// If this is a BLOB, returns it. Otherwise, fails with [FromSqlError::InvalidType].
+    pub fn as_blob(&self) -> FromSqlResult<&'a [u8]> {
+        match self {
+            ValueRef::Blob(b) => Ok(b),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+/// An owned SQLite value, like `rusqlite::types::Value`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl Value {
+    /// Returns the SQLite type of this value.
+    pub fn data_type(&self) -> Type {
+        match self {
+            Value::Null => Type::Null,
+            Value::Integer(_) => Type::Integer,
+            Value::Real(_) => Type::Real,
+            Value::Text(_) => Type::Text,
+            Value::Blob(_) => Type::Blob,
+        }
+    }
+}
+
+impl From<Value> for crate::Value {
+    fn from(val: Value) -> crate::Value {
+        match val {
+            Value::Null => crate::Value::Null,
+            Value::Integer(i) => crate::Value::Integer(i),
+            Value::Real(f) => crate::Value::Float(f),
+            Value::Text(t) => crate::Value::Text(t),
+            Value::Blob(b) => crate::Value::Blob(Blob::from(b.as_slice())),
+        }
+    }
+}
+
+/// The error produced by [FromSql], like `rusqlite::types::FromSqlError`.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum FromSqlError {
+    /// The value has a type which cannot be converted.
+    InvalidType,
+    /// The value is an integer which is out of range for the requested type.
+    OutOfRange(i64),
+    /// Any other error.
+    Other(BoxError),
+}
+
+impl fmt::Display for FromSqlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FromSqlError::InvalidType => write!(f, "Invalid type"),
+            FromSqlError::OutOfRange(i) => write!(f, "Value {i} out of range"),
+            FromSqlError::Other(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for FromSqlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FromSqlError::Other(e) => Some(&**e),
+            _ => None,
+        }
+    }
+}
+
+/// The error type for functions, like `rusqlite::Error`. See the
+/// [module documentation](self#differences-from-rusqlite) for how it differs.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// An error from this crate.
+    SqliteFailure(crate::Error),
+    /// A value could not be converted to the requested type. Holds the index of the
+    /// argument, the type of the value, and the underlying error.
+    FromSqlConversionFailure(usize, Type, BoxError),
+    /// An integer argument was out of range for the requested type. Holds the index of the
+    /// argument and the value.
+    IntegralValueOutOfRange(usize, i64),
+    /// A string could not be converted to UTF-8.
+    Utf8Error(str::Utf8Error),
+    /// A value could not be converted to an SQLite value.
+    ToSqlConversionFailure(BoxError),
+    /// An argument had a type which cannot be converted to the requested type. Holds the
+    /// index of the argument and the type of the value.
+    InvalidFunctionParameterType(usize, Type),
+    /// An error returned by the function.
+    UserFunctionError(BoxError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::SqliteFailure(e) => e.fmt(f),
+            Error::FromSqlConversionFailure(i, t, e) => {
+                write!(f, "Conversion error from type {t} at index: {i}, {e}")
+            }
+            Error::IntegralValueOutOfRange(i, val) => {
+                write!(f, "Integer {val} is out of range at index {i}")
+            }
+            Error::Utf8Error(e) => e.fmt(f),
+            Error::ToSqlConversionFailure(e) => e.fmt(f),
+            Error::InvalidFunctionParameterType(i, t) => {
+                write!(f, "Invalid function parameter type {t} at index {i}")
+            }
+            Error::UserFunctionError(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::SqliteFailure(e) => Some(e),
+            Error::Utf8Error(e) => Some(e),
+            Error::FromSqlConversionFailure(_, _, e)
+            | Error::ToSqlConversionFailure(e)
+            | Error::UserFunctionError(e) => Some(&**e),
+            Error::IntegralValueOutOfRange(..) | Error::InvalidFunctionParameterType(..) => None,
+        }
+    }
+}
+
+impl From<crate::Error> for Error {
+    fn from(e: crate::Error) -> Self {
+        Error::SqliteFailure(e)
+    }
+}
+
+impl From<str::Utf8Error> for Error {
+    fn from(e: str::Utf8Error) -> Self {
+        Error::Utf8Error(e)
+    }
+}
+
+/// [Error::SqliteFailure] is unwrapped. Other errors become [crate::Error::Module] with the
+/// error's message.
+impl From<Error> for crate::Error {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::SqliteFailure(e) => e,
+            e => crate::Error::Module(e.to_string()),
+        }
+    }
+}
+
+/// A type which can be converted from an SQLite value, like `rusqlite::types::FromSql`.
+pub trait FromSql: Sized {
+    /// Convert the value.
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self>;
+}
+
+macro_rules! from_sql_integral {
+    ($($ty:ty),*) => {
+        $(
+        impl FromSql for $ty {
+            fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+                let i = value.as_i64()?;
+                <$ty>::try_from(i).map_err(|_| FromSqlError::OutOfRange(i))
+            }
+        }
+        )*
+    };
+}
+
+from_sql_integral!(i8, i16, i32, isize, u8, u16, u32, u64, usize);
+
+impl FromSql for i64 {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value.as_i64()
+    }
+}
+
+impl FromSql for f64 {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Integer(i) => Ok(i as f64),
+            ValueRef::Real(f) => Ok(f),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+impl FromSql for f32 {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        f64::column_result(value).map(|f| f as f32)
+    }
+}
+
+impl FromSql for bool {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value.as_i64().map(|i| i != 0)
+    }
+}
+
+impl FromSql for String {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value.as_str().map(ToOwned::to_owned)
+    }
+}
+
+impl FromSql for Vec<u8> {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value.as_blob().map(ToOwned::to_owned)
+    }
+}
+
+/// NULL is converted to None.
+impl<T: FromSql> FromSql for Option<T> {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Null => Ok(None),
+            _ => T::column_result(value).map(Some),
+        }
+    }
+}
+
+impl FromSql for Value {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        Ok(match value {
+            ValueRef::Null => Value::Null,
+            ValueRef::Integer(i) => Value::Integer(i),
+            ValueRef::Real(f) => Value::Real(f),
+            ValueRef::Text(_) => Value::Text(value.as_str()?.to_owned()),
+            ValueRef::Blob(b) => Value::Blob(b.to_vec()),
+        })
+    }
+}
+
+/// The SQL NULL value, like `rusqlite::types::Null`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Null;
+
+/// A value which a function can return, like `rusqlite::types::ToSqlOutput`.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum ToSqlOutput<'a> {
+    /// A borrowed value.
+    Borrowed(ValueRef<'a>),
+    /// An owned value.
+    Owned(Value),
+}
+
+impl ToSqlOutput<'_> {
+    fn into_value(self) -> Result<crate::Value> {
+        Ok(match self {
+            ToSqlOutput::Borrowed(ValueRef::Null) => crate::Value::Null,
+            ToSqlOutput::Borrowed(ValueRef::Integer(i)) => crate::Value::Integer(i),
+            ToSqlOutput::Borrowed(ValueRef::Real(f)) => crate::Value::Float(f),
+            ToSqlOutput::Borrowed(ValueRef::Text(t)) => {
+                crate::Value::Text(str::from_utf8(t)?.to_owned())
+            }
+            ToSqlOutput::Borrowed(ValueRef::Blob(b)) => crate::Value::Blob(Blob::from(b)),
+            ToSqlOutput::Owned(v) => v.into(),
+        })
+    }
+}
+
+/// A type which can be returned from a function, like `rusqlite::ToSql`.
+pub trait ToSql {
+    /// Convert the value.
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>>;
+}
+
+macro_rules! to_sql_integral {
+    ($($ty:ty),*) => {
+        $(
+        impl ToSql for $ty {
+            fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+                Ok(ToSqlOutput::Owned(Value::Integer(i64::from(*self))))
+            }
+        }
+        )*
+    };
+}
+
+to_sql_integral!(i8, i16, i32, i64, u8, u16, u32, bool);
+
+macro_rules! to_sql_try_integral {
+    ($($ty:ty),*) => {
+        $(
+        /// Fails with [Error::ToSqlConversionFailure] if the value does not fit in an i64.
+        impl ToSql for $ty {
+            fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+                match i64::try_from(*self) {
+                    Ok(i) => Ok(ToSqlOutput::Owned(Value::Integer(i))),
+                    Err(e) => Err(Error::ToSqlConversionFailure(e.into())),
+                }
+            }
+        }
+        )*
+    };
+}
+
+to_sql_try_integral!(isize, u64, usize);
+
+impl ToSql for f32 {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::Owned(Value::Real(f64::from(*self))))
+    }
+}
+
+impl ToSql for f64 {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::Owned(Value::Real(*self)))
+    }
+}
+
+impl ToSql for str {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::Borrowed(ValueRef::Text(self.as_bytes())))
+    }
+}
+
+impl ToSql for String {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        self.as_str().to_sql()
+    }
+}
+
+impl ToSql for [u8] {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::Borrowed(ValueRef::Blob(self)))
+    }
+}
+
+impl ToSql for Vec<u8> {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        self.as_slice().to_sql()
+    }
+}
+
+impl ToSql for Null {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::Borrowed(ValueRef::Null))
+    }
+}
+
+impl ToSql for Value {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::Borrowed(match self {
+            Value::Null => ValueRef::Null,
+            Value::Integer(i) => ValueRef::Integer(*i),
+            Value::Real(f) => ValueRef::Real(*f),
+            Value::Text(t) => ValueRef::Text(t.as_bytes()),
+            Value::Blob(b) => ValueRef::Blob(b),
+        }))
+    }
+}
+
+impl ToSql for ToSqlOutput<'_> {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        Ok(self.clone())
+    }
+}
+
+/// None is converted to NULL.
+impl<T: ToSql> ToSql for Option<T> {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        match self {
+            Some(x) => x.to_sql(),
+            None => Null.to_sql(),
+        }
+    }
+}
+
+impl<T: ToSql + ?Sized> ToSql for &T {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        (**self).to_sql()
+    }
+}
+
+impl<T: ToSql + ?Sized> ToSql for Box<T> {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        (**self).to_sql()
+    }
+}
+
+/// The arguments of a function invocation, like `rusqlite::functions::Context`.
+pub struct Context<'a> {
+    ctx: &'a FunctionContext,
+    args: &'a [&'a mut crate::ValueRef],
+}
+
+impl Context<'_> {
+    /// Returns the number of arguments to the function.
+    pub fn len(&self) -> usize {
+        self.args.len()
+    }
+
+    /// Returns true when there are no arguments to the function.
+    pub fn is_empty(&self) -> bool {
+        self.args.is_empty()
+    }
+
+    /// Returns the `idx`th argument as a `T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is not less than [len](Context::len).
+    pub fn get<T: FromSql>(&self, idx: usize) -> Result<T> {
+        let value = self.get_raw(idx);
+        T::column_result(value).map_err(|err| match err {
+            FromSqlError::InvalidType => {
+                Error::InvalidFunctionParameterType(idx, value.data_type())
+            }
+            FromSqlError::OutOfRange(i) => Error::IntegralValueOutOfRange(idx, i),
+            FromSqlError::Other(err) => {
+                Error::FromSqlConversionFailure(idx, value.data_type(), err)
+            }
+        })
+    }
+
+    /// Returns the `idx`th argument as a [ValueRef].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is not less than [len](Context::len).
+    pub fn get_raw(&self, idx: usize) -> ValueRef<'_> {
+        ValueRef::from_value(self.args[idx])
+    }
+
+    /// Returns the auxiliary data associated with the given argument, creating it with the
+    /// given function if there is none. The function receives the value of the argument. An
+    /// error returned by the function is wrapped in [Error::UserFunctionError].
+    pub fn get_or_create_aux<T, E, F>(&self, arg: i32, func: F) -> Result<Arc<T>>
+    where
+        T: Send + Sync + 'static,
+        E: Into<BoxError>,
+        F: FnOnce(ValueRef<'_>) -> Result<T, E>,
+    {
+        if let Some(v) = self.get_aux(arg)? {
+            Ok(v)
+        } else {
+            let vr = self.get_raw(arg as usize);
+            self.set_aux(
+                arg,
+                func(vr).map_err(|e| Error::UserFunctionError(e.into()))?,
+            )
+        }
+    }
+
+    /// Sets the auxiliary data associated with the given argument. See
+    /// [Context::set_aux_data](crate::function::Context::set_aux_data).
+    pub fn set_aux<T: Send + Sync + 'static>(&self, arg: i32, value: T) -> Result<Arc<T>> {
+        let value = Arc::new(value);
+        self.ctx.set_aux_data(arg as usize, value.clone());
+        Ok(value)
+    }
+
+    /// Returns the auxiliary data associated with the given argument, if it was set with
+    /// the same type.
+    pub fn get_aux<T: Send + Sync + 'static>(&self, arg: i32) -> Result<Option<Arc<T>>> {
+        Ok(self.ctx.aux_data::<Arc<T>>(arg as usize).cloned())
+    }
+
+    /// Returns the database the function is being invoked on. This replaces rusqlite's
+    /// `Context::get_connection`.
+    pub fn db(&self) -> &Connection {
+        self.ctx.db()
+    }
+}
+
+/// Register a scalar function written for rusqlite. This is the equivalent of
+/// `rusqlite::Connection::create_scalar_function`, and accepts the same arguments. See the
+/// [module documentation](self) for details.
+///
+/// The function is registered using [Connection::create_scalar_function], so the same
+/// restrictions apply to closures registered by non-persistent extensions.
+pub fn register_rusqlite_style_fn<F, T>(
+    db: &Connection,
+    name: &str,
+    n_args: i32,
+    flags: FunctionFlags,
+    x_func: F,
+) -> crate::Result<()>
+where
+    F: FnMut(&Context<'_>) -> Result<T> + 'static,
+    T: ToSql,
+{
+    let x_func = RefCell::new(x_func);
+    db.create_scalar_function(name, &flags.to_options(n_args), move |ctx, args| {
+        let mut x_func = x_func.try_borrow_mut().map_err(|_| {
+            crate::Error::Module("function cannot be invoked recursively".to_owned())
+        })?;
+        let ret = x_func(&Context { ctx, args })?;
+        let val = ret.to_sql()?.into_value()?;
+        ctx.set_result(val)
+    })
+}
@@ -0,0 +1,133 @@
+//! Configure the SQLite library before it is initialized.
+//!
+//! This module is a safe subset of `sqlite3_config`, intended for programs which statically
+//! link SQLite and control the entire process, such as test harnesses for extensions. The
+//! configuration is global to the process and must be applied before SQLite is initialized,
+//! which happens automatically when the first database connection is opened.
+//!
+//! Loadable extensions must never use this module: by the time an extension is loaded, the
+//! host application has already initialized SQLite. For this reason, the module is only
+//! available with the `static` feature.
+#![cfg(feature = "static")]
+#![cfg_attr(docsrs, doc(cfg(feature = "static")))]
+
+use super::{ffi, sqlite3_require_version, types::*};
+use std::os::raw::c_int;
+
+/// The threading mode used by SQLite. See
+/// [the SQLite documentation](https://www.sqlite.org/threadsafe.html) for details.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum ThreadingMode {
+    /// All mutexes are disabled. SQLite may not be used from more than one thread at a
+    /// time.
+    SingleThread,
+    /// A single database connection may not be used by more than one thread at a time.
+    MultiThread,
+    /// SQLite can be used by multiple threads with no restriction.
+    Serialized,
+}
+
+/// Builder for the global SQLite configuration.
+///
+/// Options which are not set are left at the defaults chosen when SQLite was compiled.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sqlite3_ext::{config::*, *};
+///
+/// fn main() -> Result<()> {
+///     ConfigBuilder::new()
+///         .memstatus(true)
+///         .lookaside(0, 0)
+///         .apply()?;
+///     let db = Database::open(":memory:")?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct ConfigBuilder {
+    memstatus: Option<bool>,
+    lookaside: Option<(usize, usize)>,
+    threading: Option<ThreadingMode>,
+    uri: Option<bool>,
+}
+
+impl ConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable or disable the collection of memory allocation statistics.
+    pub fn memstatus(mut self, val: bool) -> Self {
+        self.memstatus = Some(val);
+        self
+    }
+
+    /// Set the default size of the lookaside memory allocator for new database
+    /// connections. Each connection will have `slots` slots of `slot_size` bytes. Setting
+    /// either value to 0 disables lookaside.
+    pub fn lookaside(mut self, slot_size: usize, slots: usize) -> Self {
+        self.lookaside = Some((slot_size, slots));
+        self
+    }
+
+    /// Set the threading mode. SQLite must have been compiled with thread safety enabled
+    /// to use a mode other than [ThreadingMode::SingleThread].
+    pub fn threading(mut self, mode: ThreadingMode) -> Self {
+        self.threading = Some(mode);
+        self
+    }
+
+    /// Enable or disable the interpretation of filenames as URIs.
+    ///
+    /// Requires SQLite 3.7.7.
+    pub fn uri(mut self, val: bool) -> Self {
+        self.uri = Some(val);
+        self
+    }
+
+    /// Apply the configuration.
+    ///
+    /// This method fails with [SQLITE_MISUSE] if SQLite has already been initialized.
+    /// If a later option fails to apply, options before it remain in effect.
+    pub fn apply(&self) -> Result<()> {
+        if let Some(mode) = self.threading {
+            let op = match mode {
+                ThreadingMode::SingleThread => ffi::SQLITE_CONFIG_SINGLETHREAD,
+                ThreadingMode::MultiThread => ffi::SQLITE_CONFIG_MULTITHREAD,
+                ThreadingMode::Serialized => ffi::SQLITE_CONFIG_SERIALIZED,
+            };
+            check(unsafe { ffi::sqlite3_config(op) })?;
+        }
+        if let Some(val) = self.memstatus {
+            check(unsafe { ffi::sqlite3_config(ffi::SQLITE_CONFIG_MEMSTATUS, val as c_int) })?;
+        }
+        if let Some((slot_size, slots)) = self.lookaside {
+            check(unsafe {
+                ffi::sqlite3_config(
+                    ffi::SQLITE_CONFIG_LOOKASIDE,
+                    slot_size as c_int,
+                    slots as c_int,
+                )
+            })?;
+        }
+        if let Some(val) = self.uri {
+            let _ = val;
+            sqlite3_require_version!(3_007_007, {
+                check(unsafe { ffi::sqlite3_config(ffi::SQLITE_CONFIG_URI, val as c_int) })
+            })?;
+        }
+        Ok(())
+    }
+}
+
+fn check(rc: c_int) -> Result<()> {
+    match rc {
+        ffi::SQLITE_MISUSE => Err(Error::Sqlite(
+            rc,
+            Some("SQLite must be configured before it is initialized".to_owned()),
+        )),
+        _ => Error::from_sqlite(rc),
+    }
+}
@@ -1,19 +1,78 @@
 #[cfg(modern_sqlite)]
 use crate::mutex::SQLiteMutexGuard;
-use crate::{ffi, sqlite3_match_version, sqlite3_require_version, types::*};
+use crate::{
+    extension::{boxed_destructor, check_transient_drop},
+    ffi,
+    iterator::*,
+    sqlite3_match_version, sqlite3_require_version,
+    types::*,
+};
 use bitflags::bitflags;
 #[cfg(modern_sqlite)]
-use std::ptr::{null, NonNull};
+use std::ptr::NonNull;
 use std::{
-    ffi::{CStr, CString},
+    ffi::{c_void, CStr, CString},
     mem::MaybeUninit,
     ops::{Deref, DerefMut},
-    os::raw::c_int,
-    path::Path,
-    ptr::null_mut,
+    os::raw::{c_char, c_int},
+    path::{Path, PathBuf},
+    ptr::{null, null_mut},
     thread::panicking,
+    time::Duration,
 };
 
+/// Key of the connection slot which owns the busy handler closure.
+const BUSY_HANDLER_SLOT: &str = "busy_handler";
+
+/// Key of the connection slot which owns the progress handler closure.
+const PROGRESS_HANDLER_SLOT: &str = "progress_handler";
+
+/// Prefix of the names under which [Connection::set_slot] stores data. On SQLite versions
+/// before 3.44.0 these are the names of collations.
+pub(crate) const SLOT_PREFIX: &str = "sqlite3_ext:";
+
+/// Fail if the collation name is reserved for slots. Collation names are not case sensitive,
+/// and replacing or removing one of these collations would free data which SQLite still uses.
+pub(crate) fn check_collation_name(name: &str) -> Result<()> {
+    match name.get(..SLOT_PREFIX.len()) {
+        Some(prefix) if prefix.eq_ignore_ascii_case(SLOT_PREFIX) => Err(Error::Sqlite(
+            ffi::SQLITE_MISUSE,
+            Some(format!(
+                "collation names starting with {SLOT_PREFIX} are reserved"
+            )),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Comparison function of the collations which hold slots. It is never meant to be used.
+unsafe extern "C" fn compare_slot(
+    _: *mut c_void,
+    _: c_int,
+    _: *const c_void,
+    _: c_int,
+    _: *const c_void,
+) -> c_int {
+    0
+}
+
+/// Returns the first keyword of the SQL statement, skipping whitespace and comments.
+fn leading_keyword(mut sql: &str) -> &str {
+    loop {
+        sql = sql.trim_start();
+        if let Some(rest) = sql.strip_prefix("--") {
+            sql = rest.find('\n').map_or("", |i| &rest[i..]);
+        } else if let Some(rest) = sql.strip_prefix("/*") {
+            sql = rest.find("*/").map_or("", |i| &rest[i + 2..]);
+        } else {
+            let end = sql
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(sql.len());
+            return &sql[..end];
+        }
+    }
+}
+
 bitflags! {
     /// These are the flags that can be passed to [Database::open_with_flags] and variants.
     #[repr(transparent)]
@@ -61,6 +120,10 @@ bitflags! {
         const EXRESCODE = ffi::SQLITE_OPEN_EXRESCODE;
         /// The database filename is not allowed to be a symbolic link.
         const NOFOLLOW = ffi::SQLITE_OPEN_NOFOLLOW;
+        /// The filename is interpreted as a URI, such as
+        /// `file:data.db?mode=ro&cache=shared`, even if URI filenames are disabled
+        /// globally.
+        const URI = ffi::SQLITE_OPEN_URI;
 
         /// This is the set of flags used when calling open methods that do not accept
         /// flags.
@@ -68,7 +131,116 @@ bitflags! {
     }
 }
 
+/// Counters which can be retrieved with [Connection::status]. See
+/// [the SQLite documentation](https://www.sqlite.org/c3ref/c_dbstatus_options.html) for
+/// details of each counter.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[repr(i32)]
+pub enum DbStatusOp {
+    /// The number of lookaside memory slots currently checked out.
+    LookasideUsed = ffi::SQLITE_DBSTATUS_LOOKASIDE_USED,
+    /// The approximate number of bytes of heap memory used by all pager caches.
+    CacheUsed = ffi::SQLITE_DBSTATUS_CACHE_USED,
+    /// The approximate number of bytes of heap memory used to store the schema.
+    SchemaUsed = ffi::SQLITE_DBSTATUS_SCHEMA_USED,
+    /// The approximate number of bytes of heap and lookaside memory used by all prepared
+    /// statements.
+    StmtUsed = ffi::SQLITE_DBSTATUS_STMT_USED,
+    /// The number of allocations satisfied using lookaside memory. Only the highwater value
+    /// is meaningful.
+    LookasideHit = ffi::SQLITE_DBSTATUS_LOOKASIDE_HIT,
+    /// The number of allocations which could not use lookaside memory because they were
+    /// too large. Only the highwater value is meaningful.
+    LookasideMissSize = ffi::SQLITE_DBSTATUS_LOOKASIDE_MISS_SIZE,
+    /// The number of allocations which could not use lookaside memory because all slots
+    /// were in use. Only the highwater value is meaningful.
+    LookasideMissFull = ffi::SQLITE_DBSTATUS_LOOKASIDE_MISS_FULL,
+    /// The number of pager cache hits. The highwater value is always 0.
+    CacheHit = ffi::SQLITE_DBSTATUS_CACHE_HIT,
+    /// The number of pager cache misses. The highwater value is always 0.
+    CacheMiss = ffi::SQLITE_DBSTATUS_CACHE_MISS,
+    /// The number of dirty cache pages written to disk. The highwater value is always 0.
+    CacheWrite = ffi::SQLITE_DBSTATUS_CACHE_WRITE,
+    /// 1 if all foreign key constraints have been resolved, 0 otherwise. The highwater
+    /// value is always 0.
+    DeferredFks = ffi::SQLITE_DBSTATUS_DEFERRED_FKS,
+    /// Like [CacheUsed](Self::CacheUsed), but memory shared with other connections is
+    /// divided evenly between them.
+    CacheUsedShared = ffi::SQLITE_DBSTATUS_CACHE_USED_SHARED,
+    /// The number of dirty cache pages written to disk in the middle of a transaction
+    /// because the cache was full. The highwater value is always 0.
+    CacheSpill = ffi::SQLITE_DBSTATUS_CACHE_SPILL,
+}
+
+/// Run-time limits which can be retrieved and changed with [Connection::limit] and
+/// [Connection::set_limit]. See
+/// [the SQLite documentation](https://www.sqlite.org/c3ref/c_limit_attached.html) for
+/// details of each limit.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[repr(i32)]
+pub enum Limit {
+    /// The maximum size of any string or BLOB or table row, in bytes.
+    Length = ffi::SQLITE_LIMIT_LENGTH,
+    /// The maximum length of an SQL statement, in bytes.
+    SqlLength = ffi::SQLITE_LIMIT_SQL_LENGTH,
+    /// The maximum number of columns in a table definition, in the result set of a SELECT,
+    /// or in an index, ORDER BY, or GROUP BY clause.
+    Column = ffi::SQLITE_LIMIT_COLUMN,
+    /// The maximum depth of the parse tree on any expression.
+    ExprDepth = ffi::SQLITE_LIMIT_EXPR_DEPTH,
+    /// The maximum number of terms in a compound SELECT statement.
+    CompoundSelect = ffi::SQLITE_LIMIT_COMPOUND_SELECT,
+    /// The maximum number of instructions in a virtual machine program used to implement
+    /// an SQL statement.
+    VdbeOp = ffi::SQLITE_LIMIT_VDBE_OP,
+    /// The maximum number of arguments on a function.
+    FunctionArg = ffi::SQLITE_LIMIT_FUNCTION_ARG,
+    /// The maximum number of attached databases.
+    Attached = ffi::SQLITE_LIMIT_ATTACHED,
+    /// The maximum length of the pattern argument to the LIKE or GLOB operators.
+    LikePatternLength = ffi::SQLITE_LIMIT_LIKE_PATTERN_LENGTH,
+    /// The maximum index number of any parameter in an SQL statement.
+    VariableNumber = ffi::SQLITE_LIMIT_VARIABLE_NUMBER,
+    /// The maximum depth of recursion for triggers.
+    TriggerDepth = ffi::SQLITE_LIMIT_TRIGGER_DEPTH,
+    /// The maximum number of auxiliary worker threads that a single prepared statement may
+    /// start.
+    WorkerThreads = ffi::SQLITE_LIMIT_WORKER_THREADS,
+}
+
+/// An opaque identifier for a database connection.
+///
+/// Two identifiers are equal exactly when they refer to the same connection. An identifier is
+/// only unique for the lifetime of the connection it was taken from; after the connection is
+/// closed, a new connection may receive the same identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConnectionId(usize);
+
+impl ConnectionId {
+    pub(crate) fn from_ptr(db: *mut ffi::sqlite3) -> Self {
+        ConnectionId(db as usize)
+    }
+}
+
+/// Information about a column of a table, returned by [Connection::column_metadata].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnMetadata {
+    /// The declared type of the column, or None if the column was declared without a type.
+    pub declared_type: Option<String>,
+    /// The name of the default collating sequence of the column.
+    pub collation: Option<String>,
+    /// True if the column has a NOT NULL constraint.
+    pub not_null: bool,
+    /// True if the column is part of the PRIMARY KEY.
+    pub primary_key: bool,
+    /// True if the column is an AUTOINCREMENT primary key.
+    pub autoincrement: bool,
+}
+
 /// Represents a borrowed connection to an SQLite database.
+///
+/// Connections compare equal when they refer to the same underlying SQLite handle. See
+/// [Connection::id].
 #[repr(transparent)]
 pub struct Connection {
     db: ffi::sqlite3,
@@ -93,6 +265,12 @@ impl Connection {
         &self.db as *const _ as _
     }
 
+    /// Return the identifier for this connection. Extensions which maintain state for each
+    /// connection can use this as the key.
+    pub fn id(&self) -> ConnectionId {
+        ConnectionId::from_ptr(unsafe { self.as_mut_ptr() })
+    }
+
     /// Load the extension at the given path, optionally providing a specific entry point.
     ///
     /// # Safety
@@ -184,6 +362,686 @@ impl Connection {
             }
         }
     }
+
+    /// Returns the number of prepared statements on this connection which have been stepped
+    /// but have not run to completion or been reset. Intended for diagnosing problems with
+    /// statements which are left running, or which run SQL while another statement is
+    /// running (see [Context::db_execute_guarded](crate::function::Context::db_execute_guarded)).
+    ///
+    /// Requires SQLite 3.7.16. On earlier versions of SQLite, this method always returns 0.
+    pub fn busy_statements(&self) -> usize {
+        self.busy_statement_info().len()
+    }
+
+    /// Returns true if an ALTER TABLE statement is running on this connection, which means
+    /// that SQLite may be reparsing the schema.
+    ///
+    /// SQLite does not say why it connects a virtual table or invokes a function, so this
+    /// is a best-effort heuristic based on the text of the running statements. It is
+    /// intended for [VTab::connect](crate::vtab::VTab::connect) (through
+    /// [VTabConnection](crate::vtab::VTabConnection)) and for functions (through
+    /// [Context::db](crate::function::Context::db)), which can use it to avoid failing an
+    /// ALTER TABLE because of a resource that the statement never uses. Note that a
+    /// virtual table connected during the ALTER TABLE remains connected afterwards; see
+    /// [LazyConnect](crate::vtab::LazyConnect) for a way to defer acquiring resources
+    /// which does not have this problem.
+    ///
+    /// Requires SQLite 3.7.16. On earlier versions of SQLite, this method always returns
+    /// false.
+    pub fn is_schema_parse(&self) -> bool {
+        self.busy_statement_info()
+            .iter()
+            .any(|(sql, _)| leading_keyword(sql).eq_ignore_ascii_case("alter"))
+    }
+
+    /// Returns the SQL text of every busy statement, and whether the statement is
+    /// read-only, starting with the most recently prepared one.
+    pub(crate) fn busy_statement_info(&self) -> Vec<(String, bool)> {
+        sqlite3_match_version! {
+            3_007_016 => {
+                let _guard = self.lock();
+                let mut ret = vec![];
+                unsafe {
+                    let mut stmt = ffi::sqlite3_next_stmt(self.as_mut_ptr(), null_mut());
+                    while !stmt.is_null() {
+                        if ffi::sqlite3_stmt_busy(stmt) != 0 {
+                            // Statements prepared with the legacy interface have no SQL.
+                            let sql = ffi::sqlite3_sql(stmt);
+                            let sql = if sql.is_null() {
+                                String::new()
+                            } else {
+                                CStr::from_ptr(sql).to_string_lossy().into_owned()
+                            };
+                            ret.push((sql, ffi::sqlite3_stmt_readonly(stmt) != 0));
+                        }
+                        stmt = ffi::sqlite3_next_stmt(self.as_mut_ptr(), stmt);
+                    }
+                }
+                ret
+            }
+            _ => vec![],
+        }
+    }
+
+    /// Returns the size of a database page in bytes for the given schema (e.g. "main",
+    /// "temp", or the name of an attached database).
+    ///
+    /// Fails with [SQLITE_ERROR](ffi::SQLITE_ERROR) if the schema does not exist.
+    pub fn page_size(&self, schema: &str) -> Result<u64> {
+        self.pragma_u64(schema, "page_size", None)
+    }
+
+    /// Returns the total number of pages in the given schema.
+    pub fn page_count(&self, schema: &str) -> Result<u64> {
+        self.pragma_u64(schema, "page_count", None)
+    }
+
+    /// Returns the number of unused pages in the given schema.
+    pub fn freelist_count(&self, schema: &str) -> Result<u64> {
+        self.pragma_u64(schema, "freelist_count", None)
+    }
+
+    /// Returns the size of the given schema in bytes, computed as
+    /// [page_size](Self::page_size) times [page_count](Self::page_count).
+    pub fn database_size_bytes(&self, schema: &str) -> Result<u64> {
+        let page_size = self.page_size(schema)?;
+        let page_count = self.page_count(schema)?;
+        Ok(page_size * page_count)
+    }
+
+    /// Query or set the maximum number of pages in the given schema. Once the limit is
+    /// reached, operations which would grow the database fail with
+    /// [SQLITE_FULL](ffi::SQLITE_FULL). Returns the limit in effect after the call. SQLite
+    /// will not lower the limit below the current [page_count](Self::page_count).
+    ///
+    /// The limit belongs to the connection and is not stored in the database file, so it can
+    /// be set on a read-only database.
+    pub fn max_page_count(&self, schema: &str, new: Option<u64>) -> Result<u64> {
+        self.pragma_u64(schema, "max_page_count", new)
+    }
+
+    /// Returns the schema name of the database with the given index on this connection.
+    /// Index 0 is always "main" and index 1 is always "temp", followed by any attached
+    /// databases. Returns None if the index is out of range.
+    ///
+    /// Requires SQLite 3.39.0, and is not available when statically linking. See
+    /// [databases](Self::databases) for a method which works with all versions.
+    pub fn db_name(&self, index: usize) -> Result<Option<String>> {
+        let _ = index;
+        #[cfg(not(feature = "static"))]
+        {
+            sqlite3_require_version!(3_039_000, {
+                let index = match c_int::try_from(index) {
+                    Ok(index) => index,
+                    Err(_) => return Ok(None),
+                };
+                let _guard = self.lock();
+                unsafe {
+                    let name = ffi::sqlite3_db_name(self.as_mut_ptr(), index);
+                    if name.is_null() {
+                        Ok(None)
+                    } else {
+                        Ok(Some(CStr::from_ptr(name).to_str()?.to_owned()))
+                    }
+                }
+            })
+        }
+        #[cfg(feature = "static")]
+        Err(Error::VersionNotSatisfied(3_039_000))
+    }
+
+    /// Returns the filename of the given schema, or None if it is a temporary or in-memory
+    /// database. Fails with [SQLITE_ERROR](ffi::SQLITE_ERROR) if the schema does not
+    /// exist.
+    ///
+    /// Requires SQLite 3.7.10.
+    pub fn db_filename(&self, schema: &str) -> Result<Option<PathBuf>> {
+        let _ = schema;
+        sqlite3_require_version!(3_007_010, {
+            let schema = CString::new(schema)?;
+            let _guard = self.lock();
+            unsafe {
+                let filename = ffi::sqlite3_db_filename(self.as_mut_ptr(), schema.as_ptr());
+                if filename.is_null() {
+                    Err(no_such_database(&schema))
+                } else {
+                    let filename = CStr::from_ptr(filename).to_str()?;
+                    Ok((!filename.is_empty()).then(|| PathBuf::from(filename)))
+                }
+            }
+        })
+    }
+
+    /// Returns true if the given schema is read-only. Fails with
+    /// [SQLITE_ERROR](ffi::SQLITE_ERROR) if the schema does not exist.
+    ///
+    /// Requires SQLite 3.7.11.
+    pub fn db_readonly(&self, schema: &str) -> Result<bool> {
+        let _ = schema;
+        sqlite3_require_version!(3_007_011, {
+            let schema = CString::new(schema)?;
+            match unsafe { ffi::sqlite3_db_readonly(self.as_mut_ptr(), schema.as_ptr()) } {
+                -1 => Err(no_such_database(&schema)),
+                x => Ok(x != 0),
+            }
+        })
+    }
+
+    /// Returns the schema name and filename of every database on this connection: "main",
+    /// "temp", and any attached databases, in that order. The filename is None for
+    /// temporary and in-memory databases.
+    ///
+    /// This method uses [db_name](Self::db_name) when it is available, and PRAGMA
+    /// database_list otherwise.
+    pub fn databases(&self) -> Result<Vec<(String, Option<PathBuf>)>> {
+        match self.db_name(0) {
+            Ok(_) => (0..)
+                .map_while(|i| self.db_name(i).transpose())
+                .map(|name| {
+                    let name = name?;
+                    let filename = self.db_filename(&name)?;
+                    Ok((name, filename))
+                })
+                .collect(),
+            Err(Error::VersionNotSatisfied(_)) => {
+                let mut stmt = self.prepare("PRAGMA database_list")?;
+                let mut ret: Vec<(String, Option<PathBuf>)> = stmt
+                    .query_as(())?
+                    .map(|(_, name, file): (i64, String, String)| {
+                        Ok((name, (!file.is_empty()).then(|| PathBuf::from(file))))
+                    })
+                    .collect()?;
+                // The pragma omits the temp database until it has been used.
+                if !ret.iter().any(|(name, _)| name == "temp") {
+                    ret.insert(1, ("temp".to_owned(), None));
+                }
+                Ok(ret)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns information about a column of a table. The schema may be None to search
+    /// all attached databases, in the same order that SQLite resolves unqualified table
+    /// names. If the column is None, this method only checks that the table exists, and
+    /// returns the default ColumnMetadata. Fails with [Error::NoSuchTable] if the table does
+    /// not exist, and with [Error::NoSuchColumn] if the table exists but the column does
+    /// not.
+    ///
+    /// The rowid of an ordinary table may be named even if it is not declared; it is
+    /// reported as an INTEGER primary key.
+    ///
+    /// This method requires SQLite to be compiled with SQLITE_ENABLE_COLUMN_METADATA.
+    pub fn column_metadata(
+        &self,
+        schema: Option<&str>,
+        table: &str,
+        column: Option<&str>,
+    ) -> Result<ColumnMetadata> {
+        let schema = schema.map(CString::new).transpose()?;
+        let table = CString::new(table)?;
+        let column = column.map(CString::new).transpose()?;
+        let guard = self.lock();
+        let (mut declared_type, mut collation) = (null(), null());
+        let (mut not_null, mut primary_key, mut autoincrement) = (0, 0, 0);
+        let rc = unsafe {
+            ffi::sqlite3_table_column_metadata(
+                self.as_mut_ptr(),
+                schema.as_ref().map_or(null(), |s| s.as_ptr()),
+                table.as_ptr(),
+                column.as_ref().map_or(null(), |s| s.as_ptr()),
+                &mut declared_type,
+                &mut collation,
+                &mut not_null,
+                &mut primary_key,
+                &mut autoincrement,
+            )
+        };
+        match (rc, &column) {
+            (ffi::SQLITE_OK, Some(_)) => (),
+            (ffi::SQLITE_OK, None) => return Ok(ColumnMetadata::default()),
+            // SQLite reports a missing table and a missing column the same way, so check
+            // whether the table exists to tell them apart.
+            (ffi::SQLITE_ERROR, Some(column)) => {
+                let table_rc = unsafe {
+                    ffi::sqlite3_table_column_metadata(
+                        self.as_mut_ptr(),
+                        schema.as_ref().map_or(null(), |s| s.as_ptr()),
+                        table.as_ptr(),
+                        null(),
+                        null_mut(),
+                        null_mut(),
+                        null_mut(),
+                        null_mut(),
+                        null_mut(),
+                    )
+                };
+                let table = table.to_string_lossy().into_owned();
+                return Err(match table_rc {
+                    ffi::SQLITE_OK => Error::NoSuchColumn {
+                        table,
+                        column: column.to_string_lossy().into_owned(),
+                    },
+                    _ => Error::NoSuchTable { table },
+                });
+            }
+            (ffi::SQLITE_ERROR, None) => {
+                return Err(Error::NoSuchTable {
+                    table: table.to_string_lossy().into_owned(),
+                })
+            }
+            _ => return Error::from_sqlite_desc(rc, guard).map(|_| unreachable!()),
+        }
+        // The strings belong to the schema, so they must be copied before the lock is
+        // released.
+        let copy = |s: *const c_char| -> Result<Option<String>> {
+            match s.is_null() {
+                true => Ok(None),
+                false => Ok(Some(unsafe { CStr::from_ptr(s) }.to_str()?.to_owned())),
+            }
+        };
+        Ok(ColumnMetadata {
+            declared_type: copy(declared_type)?,
+            collation: copy(collation)?,
+            not_null: not_null != 0,
+            primary_key: primary_key != 0,
+            autoincrement: autoincrement != 0,
+        })
+    }
+
+    /// Returns the current value of a run-time limit for this connection. Limits which are
+    /// not supported by the version of SQLite in use return -1.
+    pub fn limit(&self, limit: Limit) -> i32 {
+        unsafe { ffi::sqlite3_limit(self.as_mut_ptr(), limit as _, -1) }
+    }
+
+    /// Change a run-time limit for this connection, returning the previous value. Limits
+    /// cannot be raised above the compile-time maximum; larger values are silently
+    /// truncated. A negative value leaves the limit unchanged.
+    pub fn set_limit(&self, limit: Limit, value: i32) -> i32 {
+        unsafe { ffi::sqlite3_limit(self.as_mut_ptr(), limit as _, value) }
+    }
+
+    /// Retrieve the current and highwater values of a counter for this connection. If reset
+    /// is true, the highwater value (or, for counters which have no highwater, the current
+    /// value) is reset after it is retrieved.
+    ///
+    /// Counters which are not supported by the version of SQLite in use fail with
+    /// [SQLITE_ERROR](ffi::SQLITE_ERROR).
+    pub fn status(&self, op: DbStatusOp, reset: bool) -> Result<(i64, i64)> {
+        let (mut cur, mut hiwtr): (c_int, c_int) = (0, 0);
+        let rc = unsafe {
+            ffi::sqlite3_db_status(self.as_mut_ptr(), op as _, &mut cur, &mut hiwtr, reset as _)
+        };
+        Error::from_sqlite(rc)?;
+        Ok((cur as _, hiwtr as _))
+    }
+
+    /// Returns the number of rows modified by the most recently completed INSERT, UPDATE,
+    /// or DELETE statement on this connection.
+    ///
+    /// Before SQLite 3.37.0, the counter is 32 bits wide. On those versions, a counter which
+    /// has overflowed is reported as [i32::MAX].
+    pub fn changes(&self) -> i64 {
+        unsafe {
+            sqlite3_match_version! {
+                3_037_000 => ffi::sqlite3_changes64(self.as_mut_ptr()),
+                _ => saturate(ffi::sqlite3_changes(self.as_mut_ptr())),
+            }
+        }
+    }
+
+    /// Returns the number of rows modified by all INSERT, UPDATE, and DELETE statements
+    /// since the connection was opened.
+    ///
+    /// Before SQLite 3.37.0, the counter is 32 bits wide. On those versions, a counter which
+    /// has overflowed is reported as [i32::MAX].
+    pub fn total_changes(&self) -> i64 {
+        unsafe {
+            sqlite3_match_version! {
+                3_037_000 => ffi::sqlite3_total_changes64(self.as_mut_ptr()),
+                _ => saturate(ffi::sqlite3_total_changes(self.as_mut_ptr())),
+            }
+        }
+    }
+
+    /// Returns the rowid of the most recent successful INSERT into a rowid table or virtual
+    /// table on this connection, or 0 if there has been none.
+    pub fn last_insert_rowid(&self) -> i64 {
+        unsafe { ffi::sqlite3_last_insert_rowid(self.as_mut_ptr()) }
+    }
+
+    /// Set the value returned by [last_insert_rowid](Self::last_insert_rowid).
+    ///
+    /// Requires SQLite 3.18.0.
+    pub fn set_last_insert_rowid(&self, rowid: i64) -> Result<()> {
+        let _ = rowid;
+        sqlite3_require_version!(3_018_000, {
+            unsafe { ffi::sqlite3_set_last_insert_rowid(self.as_mut_ptr(), rowid) };
+            Ok(())
+        })
+    }
+
+    /// Run a function which modifies the database, and return the function's result along
+    /// with the number of rows it modified.
+    pub fn count_changes<R>(&self, f: impl FnOnce() -> Result<R>) -> Result<(R, i64)> {
+        let before = self.total_changes();
+        let ret = f()?;
+        Ok((ret, self.total_changes().saturating_sub(before)))
+    }
+
+    /// Run a function which modifies the database, then restore the value of
+    /// [last_insert_rowid](Self::last_insert_rowid) to what it was before the function
+    /// was called. The value is restored even if the function fails.
+    ///
+    /// This is intended for virtual tables which write to shadow tables from within
+    /// [UpdateVTab](crate::vtab::UpdateVTab) methods, so that the application sees the
+    /// rowid of the row it inserted into the virtual table instead of the shadow table.
+    /// SQLite provides no way to restore [total_changes](Self::total_changes), which will
+    /// include the rows modified by the function. When this is a problem, perform the
+    /// writes on a separate connection created with [open_sibling](Self::open_sibling).
+    ///
+    /// Requires SQLite 3.18.0. On earlier versions, the function is run but the rowid is not
+    /// restored.
+    pub fn preserve_changes<R>(&self, f: impl FnOnce() -> Result<R>) -> Result<R> {
+        let rowid = self.last_insert_rowid();
+        let ret = f();
+        let _ = rowid;
+        sqlite3_match_version! {
+            3_018_000 => self.set_last_insert_rowid(rowid)?,
+            _ => (),
+        }
+        ret
+    }
+
+    /// Open a new connection to the main database of this connection. The new connection
+    /// is read-only if this connection's main database is read-only. Statements run on the
+    /// new connection do not affect [changes](Self::changes),
+    /// [total_changes](Self::total_changes), or
+    /// [last_insert_rowid](Self::last_insert_rowid) of this connection.
+    ///
+    /// The new connection is subject to the usual locking rules, so it cannot write to the
+    /// database while this connection has a write transaction open. In particular, it
+    /// cannot be used to write from within a virtual table's update method.
+    ///
+    /// Fails with [SQLITE_MISUSE](ffi::SQLITE_MISUSE) if the main database is an in-memory
+    /// or temporary database, since these cannot be shared.
+    ///
+    /// Requires SQLite 3.7.11.
+    pub fn open_sibling(&self) -> Result<Database> {
+        sqlite3_require_version!(3_007_011, {
+            let main = CString::new("main").unwrap();
+            let (filename, readonly) = unsafe {
+                let filename = ffi::sqlite3_db_filename(self.as_mut_ptr(), main.as_ptr());
+                let readonly = ffi::sqlite3_db_readonly(self.as_mut_ptr(), main.as_ptr());
+                if filename.is_null() || *filename == 0 {
+                    return Err(Error::Sqlite(
+                        ffi::SQLITE_MISUSE,
+                        Some("cannot open a sibling of an in-memory database".to_owned()),
+                    ));
+                }
+                (CStr::from_ptr(filename), readonly == 1)
+            };
+            let flags = match readonly {
+                true => OpenFlags::READONLY,
+                false => OpenFlags::READWRITE,
+            };
+            Database::_open(filename, flags)
+        })
+    }
+
+    /// Run a pragma which returns a single integer, optionally setting it first.
+    fn pragma_u64(&self, schema: &str, pragma: &str, value: Option<u64>) -> Result<u64> {
+        let sql = match value {
+            Some(v) => format!("PRAGMA {}.{pragma} = {v}", quote_identifier(schema)),
+            None => format!("PRAGMA {}.{pragma}", quote_identifier(schema)),
+        };
+        let (ret,): (i64,) = self.query_row_as(&sql, ())?;
+        u64::try_from(ret).map_err(|_| {
+            Error::Sqlite(
+                ffi::SQLITE_MISMATCH,
+                Some(format!("PRAGMA {pragma} returned {ret}")),
+            )
+        })
+    }
+
+    /// Set a busy timeout for the connection. When a table is locked, SQLite will sleep and
+    /// retry until at least the given amount of time has elapsed, after which the operation
+    /// fails with [SQLITE_BUSY](ffi::SQLITE_BUSY). A zero timeout disables the busy timeout.
+    ///
+    /// A connection has a single busy handler, so this method replaces any handler set with
+    /// [set_busy_handler](Self::set_busy_handler).
+    pub fn set_busy_timeout(&self, timeout: Duration) -> Result<()> {
+        let ms = timeout.as_millis().min(c_int::MAX as _) as c_int;
+        let guard = self.lock();
+        unsafe {
+            Error::from_sqlite_desc(ffi::sqlite3_busy_timeout(self.as_mut_ptr(), ms), guard)?;
+        }
+        self.set_slot::<()>(BUSY_HANDLER_SLOT, None)
+    }
+
+    /// Set a callback which is invoked when a table is locked. The callback receives the
+    /// number of times it has previously been invoked for the same locking event. If it
+    /// returns true, SQLite will retry the operation; otherwise the operation fails with
+    /// [SQLITE_BUSY](ffi::SQLITE_BUSY). Passing None removes the busy handler.
+    ///
+    /// A connection has a single busy handler, so this method replaces any previous handler
+    /// or busy timeout. The previous handler is dropped. The handler is dropped when the
+    /// connection is closed.
+    ///
+    /// If the handler needs to be dropped and this is called by a non-persistent
+    /// `unload_safe` extension, this function fails. See
+    /// [Extension](crate::Extension#non-persistent-extensions) for details.
+    pub fn set_busy_handler<F: FnMut(i32) -> bool + 'static>(&self, func: Option<F>) -> Result<()> {
+        let guard = self.lock();
+        match func {
+            None => {
+                unsafe { ffi::sqlite3_busy_handler(self.as_mut_ptr(), None, null_mut()) };
+                self.set_slot::<F>(BUSY_HANDLER_SLOT, None)
+            }
+            Some(func) => {
+                check_transient_drop::<F>("busy handler")?;
+                let func = Box::into_raw(Box::new(func));
+                unsafe {
+                    let rc = ffi::sqlite3_busy_handler(
+                        self.as_mut_ptr(),
+                        Some(call_busy_handler::<F>),
+                        func as _,
+                    );
+                    if rc != ffi::SQLITE_OK {
+                        drop(Box::from_raw(func));
+                        return Error::from_sqlite_desc(rc, guard);
+                    }
+                    let ret = self.set_slot(BUSY_HANDLER_SLOT, Some(Box::from_raw(func)));
+                    if ret.is_err() {
+                        ffi::sqlite3_busy_handler(self.as_mut_ptr(), None, null_mut());
+                    }
+                    ret
+                }
+            }
+        }
+    }
+
+    /// Interrupt any pending database operation on this connection. The operation fails
+    /// with [SQLITE_INTERRUPT](ffi::SQLITE_INTERRUPT) at its earliest opportunity. If no
+    /// operation is running, this method has no effect on operations started later.
+    pub fn interrupt(&self) {
+        unsafe { ffi::sqlite3_interrupt(self.as_mut_ptr()) }
+    }
+
+    /// Returns true if an interrupt is in effect for this connection, either because
+    /// [interrupt](Self::interrupt) was called or because a progress handler aborted the
+    /// current operation.
+    ///
+    /// Requires SQLite 3.41.0. On earlier versions of SQLite, and when statically linking
+    /// (the bindings used for static linking do not include `sqlite3_is_interrupted`), this
+    /// method always returns false.
+    pub fn is_interrupted(&self) -> bool {
+        #[cfg(not(feature = "static"))]
+        {
+            sqlite3_match_version! {
+                3_041_000 => unsafe { ffi::sqlite3_is_interrupted(self.as_mut_ptr()) != 0 },
+                _ => false,
+            }
+        }
+        #[cfg(feature = "static")]
+        false
+    }
+
+    /// Set a callback which is invoked periodically during long-running operations, about
+    /// once every `n_ops` virtual machine instructions. If the callback returns true, the
+    /// current operation is aborted and fails with
+    /// [SQLITE_INTERRUPT](ffi::SQLITE_INTERRUPT).
+    ///
+    /// A connection has a single progress handler, so this method replaces any previous
+    /// handler, which is dropped. The handler is dropped when the connection is closed.
+    ///
+    /// If the handler needs to be dropped and this is called by a non-persistent
+    /// `unload_safe` extension, this function fails. See
+    /// [Extension](crate::Extension#non-persistent-extensions) for details.
+    pub fn set_progress_handler<F: FnMut() -> bool + 'static>(
+        &self,
+        n_ops: i32,
+        func: F,
+    ) -> Result<()> {
+        check_transient_drop::<F>("progress handler")?;
+        let _guard = self.lock();
+        let func = Box::into_raw(Box::new(func));
+        unsafe {
+            ffi::sqlite3_progress_handler(
+                self.as_mut_ptr(),
+                n_ops,
+                Some(call_progress_handler::<F>),
+                func as _,
+            );
+            let ret = self.set_slot(PROGRESS_HANDLER_SLOT, Some(Box::from_raw(func)));
+            if ret.is_err() {
+                ffi::sqlite3_progress_handler(self.as_mut_ptr(), 0, None, null_mut());
+            }
+            ret
+        }
+    }
+
+    /// Remove the progress handler set with [set_progress_handler](Self::set_progress_handler),
+    /// dropping it.
+    pub fn clear_progress_handler(&self) -> Result<()> {
+        let _guard = self.lock();
+        unsafe { ffi::sqlite3_progress_handler(self.as_mut_ptr(), 0, None, null_mut()) };
+        self.set_slot::<()>(PROGRESS_HANDLER_SLOT, None)
+    }
+
+    /// Attach data to the connection. The data is dropped when the connection is closed or
+    /// when the same slot is assigned again. If this fails, the data has already been dropped.
+    ///
+    /// On SQLite 3.44.0 and later, the data is stored with `sqlite3_set_clientdata`. Earlier
+    /// versions cannot attach data to a connection directly, so the data is held by a
+    /// collation named after the slot, which SQLite destroys when it is replaced or when the
+    /// connection is closed. SQLite refuses to replace a collation while any statements are
+    /// running, so on these versions reassigning a slot fails with
+    /// [SQLITE_BUSY](ffi::SQLITE_BUSY) at such a time.
+    ///
+    /// While a non-persistent `unload_safe` extension is being loaded, the data is leaked
+    /// instead of being dropped with the connection.
+    pub(crate) fn set_slot<T>(&self, name: &'static str, data: Option<Box<T>>) -> Result<()> {
+        let name = CString::new(format!("{SLOT_PREFIX}{name}")).unwrap();
+        let data = data.map_or(null_mut(), Box::into_raw);
+        let destroy = boxed_destructor::<T>();
+        let guard = self.lock();
+        unsafe {
+            if let Some(rc) = self.set_clientdata(&name, data as _, destroy) {
+                // SQLite calls the destructor, if there is one, when this fails.
+                if rc != ffi::SQLITE_OK && destroy.is_none() && !data.is_null() {
+                    drop(Box::from_raw(data));
+                }
+                return Error::from_sqlite(rc);
+            }
+            // Registering a collation without a comparison function removes it.
+            let compare = match data.is_null() {
+                true => None,
+                false => Some(compare_slot as _),
+            };
+            let rc = ffi::sqlite3_create_collation_v2(
+                self.as_mut_ptr(),
+                name.as_ptr() as _,
+                ffi::SQLITE_UTF8,
+                data as _,
+                compare,
+                destroy.filter(|_| !data.is_null()),
+            );
+            if rc != ffi::SQLITE_OK && !data.is_null() {
+                // The xDestroy callback is not called if the
+                // sqlite3_create_collation_v2() function fails.
+                drop(Box::from_raw(data));
+            }
+            Error::from_sqlite_desc(rc, guard)
+        }
+    }
+
+    /// Store the data with `sqlite3_set_clientdata`, returning None if the running version
+    /// of SQLite does not support it.
+    #[cfg(not(feature = "static"))]
+    unsafe fn set_clientdata(
+        &self,
+        name: &CStr,
+        data: *mut c_void,
+        destroy: Option<unsafe extern "C" fn(*mut c_void)>,
+    ) -> Option<c_int> {
+        let _ = (name, data, destroy);
+        sqlite3_match_version! {
+            3_044_000 => Some(ffi::sqlite3_set_clientdata(
+                self.as_mut_ptr(),
+                name.as_ptr(),
+                data,
+                destroy,
+            )),
+            _ => None,
+        }
+    }
+
+    /// The bindings used for static linking do not include `sqlite3_set_clientdata`.
+    #[cfg(feature = "static")]
+    unsafe fn set_clientdata(
+        &self,
+        _name: &CStr,
+        _data: *mut c_void,
+        _destroy: Option<unsafe extern "C" fn(*mut c_void)>,
+    ) -> Option<c_int> {
+        None
+    }
+}
+
+/// Convert a 32-bit change counter, which may have overflowed, to an i64.
+fn saturate(val: c_int) -> i64 {
+    match val {
+        x if x < 0 => i32::MAX as _,
+        x => x as _,
+    }
+}
+
+/// Quote an identifier (such as a schema or table name) for use in SQL text.
+pub(crate) fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+#[cfg(modern_sqlite)]
+fn no_such_database(schema: &CStr) -> Error {
+    Error::Sqlite(
+        ffi::SQLITE_ERROR,
+        Some(format!("no such database: {}", schema.to_string_lossy())),
+    )
+}
+
+unsafe extern "C" fn call_busy_handler<F: FnMut(i32) -> bool>(
+    data: *mut c_void,
+    count: c_int,
+) -> c_int {
+    let func = &mut *(data as *mut F);
+    func(count) as _
+}
+
+unsafe extern "C" fn call_progress_handler<F: FnMut() -> bool>(data: *mut c_void) -> c_int {
+    let func = &mut *(data as *mut F);
+    func() as _
 }
 
 impl std::fmt::Debug for Connection {
@@ -192,6 +1050,14 @@ impl std::fmt::Debug for Connection {
     }
 }
 
+impl PartialEq for Connection {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Eq for Connection {}
+
 #[cfg(unix)]
 fn path_to_cstring(path: &Path) -> CString {
     use std::os::unix::ffi::OsStrExt;
@@ -218,6 +1084,13 @@ impl Database {
     }
 
     fn _open(filename: &CStr, flags: OpenFlags) -> Result<Database> {
+        #[cfg(feature = "send")]
+        if unsafe { ffi::sqlite3_threadsafe() } == 0 {
+            return Err(Error::Sqlite(
+                ffi::SQLITE_MISUSE,
+                Some("the send feature requires SQLite compiled with SQLITE_THREADSAFE".to_owned()),
+            ));
+        }
         let mut db = MaybeUninit::uninit();
         let rc = Error::from_sqlite(unsafe {
             ffi::sqlite3_open_v2(
@@ -228,9 +1101,10 @@ impl Database {
             )
         });
         match rc {
-            Ok(()) => Ok(Database {
-                db: unsafe { *db.as_ptr() },
-            }),
+            Ok(()) => {
+                let db = unsafe { *db.as_ptr() };
+                Ok(Database { db })
+            }
             Err(e) => {
                 if !db.as_ptr().is_null() {
                     // Panic if we can't close the database we failed to open
@@ -256,6 +1130,45 @@ impl Database {
         self.db = null_mut();
         Ok(())
     }
+
+    /// Run the closure with this database. This is a synchronous entry point intended to be
+    /// the body of a blocking task, such as one started with tokio's `spawn_blocking`, when
+    /// used with [SendDatabase].
+    pub fn call<F, R>(&mut self, f: F) -> Result<R>
+    where
+        F: FnOnce(&mut Database) -> Result<R>,
+    {
+        f(self)
+    }
+
+    /// Convert this database into a [SendDatabase], which can be moved to another thread.
+    ///
+    /// Requires the `send` feature. Fails with SQLITE_MISUSE if the connection was not opened
+    /// in serialized mode, which is the default unless SQLite was configured otherwise or the
+    /// connection was opened with [OpenFlags::UNSAFE_NOMUTEX]. Serialized mode is required because
+    /// a [Statement](crate::query::Statement) prepared from this database does not borrow it,
+    /// and so may still be used from the original thread. On failure, the Database is returned
+    /// along with the error.
+    ///
+    /// # Safety
+    ///
+    /// Everything registered with the connection, before or after this call, must be safe to
+    /// use from another thread. This includes the closures passed to functions like
+    /// [Connection::create_scalar_function] and [Connection::set_progress_handler], the
+    /// virtual table implementations and their Aux data, and values stored in slots. None of
+    /// these are required to be `Send` by the type system.
+    #[cfg(feature = "send")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "send")))]
+    pub unsafe fn into_send(self) -> std::result::Result<SendDatabase, (Error, Database)> {
+        if ffi::sqlite3_db_mutex(self.db).is_null() {
+            let err = Error::Sqlite(
+                ffi::SQLITE_MISUSE,
+                Some("the connection is not in serialized mode".to_owned()),
+            );
+            return Err((err, self));
+        }
+        Ok(SendDatabase { db: self })
+    }
 }
 
 impl std::fmt::Debug for Database {
@@ -290,6 +1203,58 @@ impl DerefMut for Database {
     }
 }
 
+/// A [Database] which can be moved to another thread.
+///
+/// Create one with [Database::into_send]. This type dereferences to the underlying
+/// [Database], so [Database::call] is available to run a closure with it.
+///
+/// ```no_run
+/// use sqlite3_ext::*;
+///
+/// fn count_rows(mut db: SendDatabase) -> Result<i64> {
+///     std::thread::spawn(move || {
+///         db.call(|db| db.query_row("SELECT COUNT(*) FROM tbl", (), |r| Ok(r[0].get_i64())))
+///     })
+///     .join()
+///     .unwrap()
+/// }
+/// ```
+#[cfg(feature = "send")]
+#[cfg_attr(docsrs, doc(cfg(feature = "send")))]
+#[derive(Debug)]
+pub struct SendDatabase {
+    db: Database,
+}
+
+// SAFETY: the connection is in serialized mode, and the caller of Database::into_send
+// promised that everything registered with it is safe to use from another thread.
+#[cfg(feature = "send")]
+unsafe impl Send for SendDatabase {}
+
+#[cfg(feature = "send")]
+impl SendDatabase {
+    /// Return the underlying [Database].
+    pub fn into_inner(self) -> Database {
+        self.db
+    }
+}
+
+#[cfg(feature = "send")]
+impl Deref for SendDatabase {
+    type Target = Database;
+
+    fn deref(&self) -> &Database {
+        &self.db
+    }
+}
+
+#[cfg(feature = "send")]
+impl DerefMut for SendDatabase {
+    fn deref_mut(&mut self) -> &mut Database {
+        &mut self.db
+    }
+}
+
 #[cfg(modern_sqlite)]
 struct LoadExtensionGuard<'a> {
     db: &'a SQLiteMutexGuard<'a, Connection>,
@@ -343,3 +1308,529 @@ impl Drop for LoadExtensionGuard<'_> {
         }
     }
 }
+
+#[cfg(all(test, feature = "static"))]
+mod test {
+    use crate::test_helpers::prelude::*;
+    use std::{cell::Cell, rc::Rc, time::Duration};
+
+    fn open_pair(name: &str) -> Result<(Database, Database, std::path::PathBuf)> {
+        let path =
+            std::env::temp_dir().join(format!("sqlite3_ext_{}_{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let a = Database::open(&path)?;
+        let b = Database::open(&path)?;
+        a.execute("CREATE TABLE tbl(col)", ())?;
+        a.execute("BEGIN EXCLUSIVE", ())?;
+        Ok((a, b, path))
+    }
+
+    #[test]
+    fn busy_handler() -> Result<()> {
+        let (a, b, path) = open_pair("busy_handler")?;
+        let calls = Rc::new(Cell::new(0));
+        let calls_inner = calls.clone();
+        b.set_busy_handler(Some(move |count| {
+            assert_eq!(count, calls_inner.get());
+            calls_inner.set(count + 1);
+            count < 2
+        }))?;
+        let err = b
+            .query_row("SELECT COUNT(*) FROM tbl", (), |_| Ok(()))
+            .unwrap_err();
+        assert!(matches!(err, Error::Sqlite(ffi::SQLITE_BUSY, _)), "{err:?}");
+        assert_eq!(calls.get(), 3);
+
+        // Replacing or removing the handler drops the previous closure.
+        b.set_busy_handler(Some(|_| false))?;
+        assert_eq!(Rc::strong_count(&calls), 1);
+        let calls_inner = calls.clone();
+        b.set_busy_handler(Some(move |_| {
+            calls_inner.set(calls_inner.get() + 1);
+            false
+        }))?;
+        assert_eq!(Rc::strong_count(&calls), 2);
+        b.set_busy_handler(None::<fn(i32) -> bool>)?;
+        assert_eq!(Rc::strong_count(&calls), 1);
+
+        a.execute("COMMIT", ())?;
+        b.query_row("SELECT COUNT(*) FROM tbl", (), |_| Ok(()))?;
+        drop((a, b));
+        std::fs::remove_file(path).unwrap();
+        Ok(())
+    }
+
+    #[test]
+    fn busy_timeout() -> Result<()> {
+        let (a, b, path) = open_pair("busy_timeout")?;
+        let dropped = Rc::new(());
+        let inner = dropped.clone();
+        b.set_busy_handler(Some(move |_| {
+            let _ = &inner;
+            false
+        }))?;
+        b.set_busy_timeout(Duration::from_millis(50))?;
+        assert_eq!(Rc::strong_count(&dropped), 1);
+        let start = std::time::Instant::now();
+        let err = b
+            .query_row("SELECT COUNT(*) FROM tbl", (), |_| Ok(()))
+            .unwrap_err();
+        assert!(matches!(err, Error::Sqlite(ffi::SQLITE_BUSY, _)), "{err:?}");
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        drop((a, b));
+        std::fs::remove_file(path).unwrap();
+        Ok(())
+    }
+
+    #[test]
+    fn busy_handler_dropped_on_close() -> Result<()> {
+        let db = Database::open(":memory:")?;
+        let data = Rc::new(());
+        let inner = data.clone();
+        db.set_busy_handler(Some(move |_| {
+            let _ = &inner;
+            false
+        }))?;
+        assert_eq!(Rc::strong_count(&data), 2);
+        drop(db);
+        assert_eq!(Rc::strong_count(&data), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn handler_dropped_on_foreign_close() -> Result<()> {
+        let mut raw = std::ptr::null_mut();
+        let rc = unsafe { ffi::sqlite3_open(c":memory:".as_ptr(), &mut raw) };
+        Error::from_sqlite(rc)?;
+        let data = Rc::new(());
+        let inner = data.clone();
+        let db = unsafe { Connection::from_ptr(raw) };
+        db.set_progress_handler(1000, move || {
+            let _ = &inner;
+            false
+        })?;
+        assert_eq!(Rc::strong_count(&data), 2);
+        Error::from_sqlite(unsafe { ffi::sqlite3_close(raw) })?;
+        assert_eq!(Rc::strong_count(&data), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn progress_handler() -> Result<()> {
+        let h = TestHelpers::new();
+        let calls = Rc::new(Cell::new(0));
+        let calls_inner = calls.clone();
+        h.db.set_progress_handler(1, move || {
+            calls_inner.set(calls_inner.get() + 1);
+            false
+        })?;
+        h.db.query_row("SELECT 1", (), |_| Ok(()))?;
+        assert!(calls.get() > 0);
+
+        // Replacing or clearing the handler drops the previous closure.
+        h.db.set_progress_handler(1, || true)?;
+        assert_eq!(Rc::strong_count(&calls), 1);
+        let err = h.db.query_row("SELECT 1", (), |_| Ok(())).unwrap_err();
+        assert!(
+            matches!(err, Error::Sqlite(ffi::SQLITE_INTERRUPT, _)),
+            "{err:?}"
+        );
+        let calls_inner = calls.clone();
+        h.db.set_progress_handler(1, move || {
+            let _ = &calls_inner;
+            false
+        })?;
+        assert_eq!(Rc::strong_count(&calls), 2);
+        h.db.clear_progress_handler()?;
+        assert_eq!(Rc::strong_count(&calls), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn interrupt() -> Result<()> {
+        let h = TestHelpers::new();
+        h.db.create_scalar_function("interrupt", &FunctionOptions::default(), |ctx, _| {
+            ctx.db().interrupt();
+            ctx.set_result(())
+        })?;
+        assert!(!h.db.is_interrupted());
+        let err =
+            h.db.query_row(
+                "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c) \
+                 SELECT count(interrupt()) FROM c",
+                (),
+                |_| Ok(()),
+            )
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::Sqlite(ffi::SQLITE_INTERRUPT, _)),
+            "{err:?}"
+        );
+        // The interrupt is cleared once no statements are running.
+        assert!(!h.db.is_interrupted());
+        h.db.query_row("SELECT 1", (), |_| Ok(()))?;
+        Ok(())
+    }
+
+    #[test]
+    fn leading_keyword() {
+        use super::leading_keyword;
+        assert_eq!(leading_keyword("ALTER TABLE t RENAME TO u"), "ALTER");
+        assert_eq!(leading_keyword("  -- note\n\t/* x */ alter table"), "alter");
+        assert_eq!(leading_keyword("/* unterminated"), "");
+        assert_eq!(leading_keyword("-- only a comment"), "");
+        assert_eq!(leading_keyword("SELECT 1"), "SELECT");
+    }
+
+    #[test]
+    fn page_stats() -> Result<()> {
+        let h = TestHelpers::new();
+        h.db.execute("ATTACH ':memory:' AS \"odd\"\"name\"", ())?;
+        for (schema, create) in [
+            ("main", "CREATE TABLE main.tbl (x)"),
+            ("temp", "CREATE TEMP TABLE tbl (x)"),
+            ("odd\"name", "CREATE TABLE \"odd\"\"name\".tbl (x)"),
+        ] {
+            let before = h.db.page_count(schema)?;
+            h.db.execute(create, ())?;
+            let page_size = h.db.page_size(schema)?;
+            let page_count = h.db.page_count(schema)?;
+            assert!(page_size >= 512, "{schema}: page size {page_size}");
+            assert!(page_count > before, "{schema}: {page_count} <= {before}");
+            assert_eq!(h.db.freelist_count(schema)?, 0);
+            assert_eq!(h.db.database_size_bytes(schema)?, page_size * page_count);
+        }
+        match h.db.page_count("nosuch") {
+            Err(Error::Sqlite(ffi::SQLITE_ERROR, Some(msg))) => {
+                assert!(msg.contains("nosuch"), "{msg}")
+            }
+            x => panic!("expected an error, got {x:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn status() -> Result<()> {
+        let h = TestHelpers::new();
+        let (before, _) = h.db.status(DbStatusOp::CacheUsed, false)?;
+        h.db.execute("CREATE TABLE tbl (x)", ())?;
+        let mut stmt = h.db.prepare("INSERT INTO tbl VALUES (randomblob(1000))")?;
+        for _ in 0..100 {
+            stmt.execute(())?;
+        }
+        let (after, _) = h.db.status(DbStatusOp::CacheUsed, false)?;
+        assert!(after > before, "{after} <= {before}");
+        let (stmt_used, _) = h.db.status(DbStatusOp::StmtUsed, false)?;
+        assert!(stmt_used > 0);
+
+        h.db.query_row("SELECT COUNT(*) FROM tbl", (), |_| Ok(()))?;
+        let (hits, _) = h.db.status(DbStatusOp::CacheHit, true)?;
+        assert!(hits > 0);
+        assert_eq!(h.db.status(DbStatusOp::CacheHit, false)?, (0, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn max_page_count() -> Result<()> {
+        let path = std::env::temp_dir().join(format!(
+            "sqlite3_ext_max_page_count_{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let db = Database::open(&path)?;
+        db.execute("CREATE TABLE tbl (x)", ())?;
+        let limit = db.page_count("main")? + 1;
+        assert_eq!(db.max_page_count("main", Some(limit))?, limit);
+        assert_eq!(db.max_page_count("main", None)?, limit);
+        let err = db
+            .execute("INSERT INTO tbl VALUES (zeroblob(1000000))", ())
+            .unwrap_err();
+        assert!(matches!(err, Error::Sqlite(ffi::SQLITE_FULL, _)), "{err:?}");
+        drop(db);
+
+        // The limit is not stored in the file, so a read-only connection can set it.
+        let db = Database::open_with_flags(&path, OpenFlags::READONLY)?;
+        let pages = db.page_count("main")?;
+        assert_eq!(db.max_page_count("main", Some(pages + 10))?, pages + 10);
+        assert!(db.max_page_count("nosuch", Some(10)).is_err());
+        drop(db);
+        std::fs::remove_file(&path).unwrap();
+        Ok(())
+    }
+
+    #[test]
+    fn counters() -> Result<()> {
+        let h = TestHelpers::new();
+        h.db.execute("CREATE TABLE tbl (x)", ())?;
+        h.db.execute("INSERT INTO tbl VALUES (1), (2), (3)", ())?;
+        assert_eq!(h.db.changes(), 3);
+        assert_eq!(h.db.last_insert_rowid(), 3);
+        let ((), n) = h.db.count_changes(|| {
+            h.db.execute("UPDATE tbl SET x = x + 1 WHERE x > 1", ())?;
+            h.db.execute("DELETE FROM tbl WHERE x = 1", ())?;
+            Ok(())
+        })?;
+        assert_eq!(n, 3);
+        assert_eq!(h.db.changes(), 1);
+        assert_eq!(h.db.total_changes(), 6);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(modern_sqlite)]
+    fn preserve_changes() -> Result<()> {
+        let h = TestHelpers::new();
+        h.db.execute("CREATE TABLE tbl (x)", ())?;
+        h.db.execute("INSERT INTO tbl VALUES (1)", ())?;
+        let ret = h.db.preserve_changes(|| {
+            h.db.execute("INSERT INTO tbl VALUES (2), (3)", ())?;
+            assert_eq!(h.db.last_insert_rowid(), 3);
+            Err::<(), _>(SQLITE_MISUSE)
+        });
+        assert!(ret.is_err());
+        assert_eq!(h.db.last_insert_rowid(), 1);
+        h.db.set_last_insert_rowid(10)?;
+        assert_eq!(h.db.last_insert_rowid(), 10);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(modern_sqlite)]
+    fn open_sibling() -> Result<()> {
+        let path = std::env::temp_dir().join(format!(
+            "sqlite3_ext_open_sibling_{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let db = Database::open(&path)?;
+        db.execute("CREATE TABLE tbl (x)", ())?;
+        db.execute("INSERT INTO tbl VALUES (1)", ())?;
+        let total = db.total_changes();
+        let sibling = db.open_sibling()?;
+        sibling.execute("INSERT INTO tbl VALUES (2), (3)", ())?;
+        assert_eq!(sibling.changes(), 2);
+        assert_eq!(db.changes(), 1);
+        assert_eq!(db.total_changes(), total);
+        assert_eq!(db.last_insert_rowid(), 1);
+        let (count,): (i64,) = db.query_row_as("SELECT COUNT(*) FROM tbl", ())?;
+        assert_eq!(count, 3);
+        drop((db, sibling));
+
+        let db = Database::open_with_flags(&path, OpenFlags::READONLY)?;
+        let sibling = db.open_sibling()?;
+        let err = sibling.execute("DELETE FROM tbl", ()).unwrap_err();
+        assert!(
+            matches!(err, Error::Sqlite(ffi::SQLITE_READONLY, _)),
+            "{err:?}"
+        );
+        drop((db, sibling));
+        std::fs::remove_file(&path).unwrap();
+
+        let err = Database::open(":memory:")?.open_sibling().unwrap_err();
+        assert!(
+            matches!(err, Error::Sqlite(ffi::SQLITE_MISUSE, _)),
+            "{err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(modern_sqlite)]
+    fn databases() -> Result<()> {
+        let file = TempFile::new("databases");
+        Database::open(&file.0)?.execute("CREATE TABLE tbl (x)", ())?;
+        let path = file.0.canonicalize().unwrap();
+
+        let h = TestHelpers::new();
+        h.db.execute("ATTACH ? AS other", [path.to_str().unwrap()])?;
+        h.db.execute("ATTACH ':memory:' AS mem", ())?;
+        let expected = vec![
+            ("main".to_owned(), None),
+            ("temp".to_owned(), None),
+            ("other".to_owned(), Some(path.clone())),
+            ("mem".to_owned(), None),
+        ];
+        assert_eq!(h.db.databases()?, expected);
+        match h.db.db_name(2) {
+            Ok(name) => {
+                assert_eq!(name.as_deref(), Some("other"));
+                assert_eq!(h.db.db_name(4)?, None);
+            }
+            Err(e) => assert_eq!(e, Error::VersionNotSatisfied(3_039_000)),
+        }
+        assert_eq!(h.db.db_filename("other")?, Some(path.clone()));
+        assert_eq!(h.db.db_filename("main")?, None);
+        assert_eq!(
+            h.db.db_filename("nosuch").unwrap_err().to_string(),
+            "no such database: nosuch"
+        );
+        assert!(!h.db.db_readonly("other")?);
+        assert!(h.db.db_readonly("nosuch").is_err());
+
+        // The temp database is listed the same way once it has been used.
+        h.db.execute("CREATE TEMP TABLE t (x)", ())?;
+        assert_eq!(h.db.databases()?, expected);
+
+        let db = Database::open_with_flags(&path, OpenFlags::READONLY)?;
+        db.execute("ATTACH ':memory:' AS mem", ())?;
+        assert!(db.db_readonly("main")?);
+        assert_eq!(db.databases()?[0], ("main".to_owned(), Some(path.clone())));
+        Ok(())
+    }
+
+    #[test]
+    fn column_metadata() -> Result<()> {
+        let h = TestHelpers::new();
+        h.db.execute(
+            "CREATE TABLE docs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                title TEXT NOT NULL COLLATE NOCASE,
+                body,
+                UNIQUE (title, body)
+            )",
+            (),
+        )?;
+        h.db.execute(
+            "CREATE TABLE tags (doc INTEGER, tag VARCHAR(10), PRIMARY KEY (doc, tag))",
+            (),
+        )?;
+        let meta = h.db.column_metadata(None, "docs", Some("id"))?;
+        assert_eq!(
+            meta,
+            ColumnMetadata {
+                declared_type: Some("INTEGER".to_owned()),
+                collation: Some("BINARY".to_owned()),
+                not_null: false,
+                primary_key: true,
+                autoincrement: true,
+            }
+        );
+        let meta = h.db.column_metadata(Some("main"), "DOCS", Some("Title"))?;
+        assert_eq!(meta.declared_type.as_deref(), Some("TEXT"));
+        assert_eq!(meta.collation.as_deref(), Some("NOCASE"));
+        assert!(meta.not_null && !meta.primary_key && !meta.autoincrement);
+        let meta = h.db.column_metadata(None, "docs", Some("body"))?;
+        assert_eq!(meta.declared_type, None);
+        assert!(!meta.not_null && !meta.primary_key);
+        let meta = h.db.column_metadata(None, "tags", Some("tag"))?;
+        assert_eq!(meta.declared_type.as_deref(), Some("VARCHAR(10)"));
+        assert!(meta.primary_key && !meta.autoincrement);
+        let meta = h.db.column_metadata(None, "tags", Some("rowid"))?;
+        assert_eq!(meta.declared_type.as_deref(), Some("INTEGER"));
+        assert!(meta.primary_key);
+
+        assert_eq!(
+            h.db.column_metadata(None, "docs", None)?,
+            ColumnMetadata::default()
+        );
+        let err = h.db.column_metadata(None, "nosuch", None).unwrap_err();
+        assert_eq!(
+            err,
+            Error::NoSuchTable {
+                table: "nosuch".to_owned()
+            }
+        );
+        assert_eq!(err.to_string(), "no such table: nosuch");
+        let err =
+            h.db.column_metadata(None, "nosuch", Some("id"))
+                .unwrap_err();
+        assert_eq!(
+            err,
+            Error::NoSuchTable {
+                table: "nosuch".to_owned()
+            }
+        );
+        let err =
+            h.db.column_metadata(None, "docs", Some("nosuch"))
+                .unwrap_err();
+        assert_eq!(
+            err,
+            Error::NoSuchColumn {
+                table: "docs".to_owned(),
+                column: "nosuch".to_owned()
+            }
+        );
+        assert_eq!(err.to_string(), "no such column: docs.nosuch");
+        let err =
+            h.db.column_metadata(Some("temp"), "docs", Some("id"))
+                .unwrap_err();
+        assert!(matches!(err, Error::NoSuchTable { .. }), "{err:?}");
+        Ok(())
+    }
+
+    fn open_exrescode(extended: bool) -> Result<Database> {
+        let flags = match extended {
+            true => OpenFlags::DEFAULT | OpenFlags::EXRESCODE,
+            false => OpenFlags::DEFAULT,
+        };
+        let db = Database::open_with_flags(":memory:", flags)?;
+        db.execute("CREATE TABLE t (x UNIQUE NOT NULL)", ())?;
+        db.execute("INSERT INTO t VALUES (1)", ())?;
+        db.create_scalar_function("fail", &FunctionOptions::default(), |_, args| {
+            Err(Error::Sqlite(
+                args[0].get_i64() as _,
+                Some(args[1].get_str()?.to_owned()),
+            ))
+        })?;
+        Ok(db)
+    }
+
+    /// Returns the error and the primary and extended codes reported by the C API.
+    fn codes(db: &Database, sql: &str) -> (Error, i32, i32) {
+        let err = db.execute(sql, ()).unwrap_err();
+        let (primary, extended) = unsafe {
+            (
+                ffi::sqlite3_errcode(db.as_mut_ptr()),
+                ffi::sqlite3_extended_errcode(db.as_mut_ptr()),
+            )
+        };
+        (err, primary, extended)
+    }
+
+    #[test]
+    fn extended_result_codes() -> Result<()> {
+        let unique = "INSERT INTO t VALUES (1)";
+        let not_null = "INSERT INTO t VALUES (NULL)";
+        let func = format!("SELECT fail({}, 'custom')", ffi::SQLITE_IOERR_READ);
+
+        let db = open_exrescode(true)?;
+        let (err, primary, extended) = codes(&db, unique);
+        assert_eq!(err, Error::Sqlite(ffi::SQLITE_CONSTRAINT_UNIQUE, None));
+        assert_eq!(err.to_string(), "UNIQUE constraint failed: t.x");
+        assert_eq!(
+            (primary, extended),
+            (ffi::SQLITE_CONSTRAINT_UNIQUE, ffi::SQLITE_CONSTRAINT_UNIQUE)
+        );
+        let (err, ..) = codes(&db, not_null);
+        assert_eq!(err, Error::Sqlite(ffi::SQLITE_CONSTRAINT_NOTNULL, None));
+        // The code of a function error is kept along with its message.
+        let (err, _, extended) = codes(&db, &func);
+        assert_eq!(err, Error::Sqlite(ffi::SQLITE_IOERR_READ, None));
+        assert_eq!(err.to_string(), "custom");
+        assert_eq!(extended, ffi::SQLITE_IOERR_READ);
+
+        let db = open_exrescode(false)?;
+        let (err, primary, extended) = codes(&db, unique);
+        assert_eq!(err, Error::Sqlite(ffi::SQLITE_CONSTRAINT, None));
+        assert_eq!(err.to_string(), "UNIQUE constraint failed: t.x");
+        assert_eq!(
+            (primary, extended),
+            (ffi::SQLITE_CONSTRAINT, ffi::SQLITE_CONSTRAINT_UNIQUE)
+        );
+        let (err, _, extended) = codes(&db, &func);
+        assert_eq!(err, Error::Sqlite(ffi::SQLITE_IOERR, None));
+        assert_eq!(err.to_string(), "custom");
+        assert_eq!(extended, ffi::SQLITE_IOERR_READ);
+
+        // Errors from opening the database are returned directly.
+        let dir = std::env::temp_dir();
+        for flags in [
+            OpenFlags::DEFAULT,
+            OpenFlags::DEFAULT | OpenFlags::EXRESCODE,
+        ] {
+            let err = Database::open_with_flags(&dir, flags).unwrap_err();
+            assert_eq!(err, Error::Sqlite(ffi::SQLITE_CANTOPEN, None));
+        }
+        Ok(())
+    }
+}
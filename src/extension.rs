@@ -1,7 +1,8 @@
 use super::*;
 use std::{
+    cell::Cell,
     ffi::c_void,
-    mem::transmute,
+    mem::{needs_drop, transmute},
     ops::Deref,
     os::raw::{c_char, c_int},
 };
@@ -12,6 +13,34 @@ type CEntry = unsafe extern "C" fn(
     api: *mut ffi::sqlite3_api_routines,
 ) -> c_int;
 
+/// How the init function currently running on this thread was invoked.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LoadMode {
+    /// Not from an entry point generated by [sqlite3_ext_init], for example by calling the
+    /// Rust function directly.
+    Direct,
+    /// From an entry point which does not load the extension persistently.
+    Transient,
+    /// From an entry point declared with `unload_safe` which does not load the extension
+    /// persistently.
+    UnloadSafe,
+    /// From an entry point which loads the extension persistently.
+    Persistent,
+}
+
+thread_local! {
+    static LOAD_MODE: Cell<LoadMode> = const { Cell::new(LoadMode::Direct) };
+}
+
+/// Restores the previous [LoadMode] when the init function returns or panics.
+struct RestoreLoadMode(LoadMode);
+
+impl Drop for RestoreLoadMode {
+    fn drop(&mut self) {
+        LOAD_MODE.with(|t| t.set(self.0));
+    }
+}
+
 /// Represents an SQLite-compatible extension entry point.
 ///
 /// Because the original Rust function is the [Deref] target for Extension, it can be called
@@ -39,10 +68,41 @@ type CEntry = unsafe extern "C" fn(
 /// }
 /// # fn main() {}
 /// ```
+///
+/// # Non-persistent extensions
+///
+/// When an extension which is not [persistent](sqlite3_ext_init) is loaded with
+/// `load_extension`, SQLite may unload the shared library once the connection closes. Any
+/// destructor registered with SQLite lives in that library, so state which needs to be dropped
+/// (for example a closure which captures an [Rc](std::rc::Rc), or an Aux type with a [Drop]
+/// implementation) may be dropped after the code which drops it has been unmapped.
+///
+/// Extensions which may be loaded this way can be declared with `unload_safe`. While the entry
+/// point of such an extension is running, registering a function, collation, or module whose
+/// state needs to be dropped fails with [Error::Module]. State with no drop glue (plain `fn`
+/// items, closures which capture nothing, `()`) is always accepted, and is leaked rather than
+/// freed, so that SQLite never calls back into the library to destroy it. To register droppable
+/// state anyway, either make the extension persistent or use a leaky registration method,
+/// such as [create_scalar_function_leaky](Connection::create_scalar_function_leaky) or
+/// [create_module_leaky](Connection::create_module_leaky), which never drop the registered
+/// state. The check does not apply when the Rust init function is called directly, or when the
+/// entry point is invoked as a persistent extension.
+///
+/// ```no_run
+/// use sqlite3_ext::*;
+///
+/// #[sqlite3_ext_main(unload_safe)]
+/// fn init(db: &Connection) -> Result<()> {
+///     Ok(())
+/// }
+/// # fn main() {}
+/// ```
 #[repr(C)]
 pub struct Extension {
     c_entry: unsafe extern "C" fn(),
     init: fn(&Connection) -> Result<()>,
+    entry_point: Option<&'static str>,
+    persistent: bool,
 }
 
 impl Extension {
@@ -54,15 +114,99 @@ impl Extension {
             Extension {
                 c_entry: transmute(c_entry as *mut c_void),
                 init,
+                entry_point: None,
+                persistent: false,
             }
         }
     }
 
+    /// Record the exported name of the entry point and whether it loads the extension
+    /// persistently. This is called by [sqlite3_ext_init] for exported entry points.
+    #[doc(hidden)]
+    pub const fn with_export(mut self, entry_point: &'static str, persistent: bool) -> Self {
+        self.entry_point = Some(entry_point);
+        self.persistent = persistent;
+        self
+    }
+
+    /// Returns the exported name of the C entry point, if the extension has one.
+    pub fn entry_point(&self) -> Option<&'static str> {
+        self.entry_point
+    }
+
+    /// Returns true if the entry point loads the extension persistently.
+    pub fn is_persistent(&self) -> bool {
+        self.persistent
+    }
+
+    /// Describe the SQL objects provided by this extension. See
+    /// [build_support](crate::build_support) for details.
+    ///
+    /// Requires SQLite 3.30.0.
+    #[cfg(feature = "static")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "static")))]
+    pub fn manifest(&self) -> Result<build_support::Manifest> {
+        build_support::Manifest::collect(self)
+    }
+
+    /// Describe the SQL objects provided by this extension as JSON. See
+    /// [build_support](crate::build_support) for details.
+    ///
+    /// Requires SQLite 3.30.0.
+    #[cfg(feature = "static")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "static")))]
+    pub fn manifest_json(&self) -> Result<String> {
+        Ok(self.manifest()?.to_json())
+    }
+
+    /// Run the init function of an extension. This is called by the entry point generated by
+    /// [sqlite3_ext_init], and records whether the extension is being loaded persistently.
+    #[doc(hidden)]
+    pub fn enter_init(
+        persistent: bool,
+        unload_safe: bool,
+        f: impl FnOnce() -> Result<()>,
+    ) -> Result<()> {
+        let mode = match (persistent, unload_safe) {
+            (true, _) => LoadMode::Persistent,
+            (false, false) => LoadMode::Transient,
+            (false, true) => LoadMode::UnloadSafe,
+        };
+        let _restore = RestoreLoadMode(LOAD_MODE.with(|t| t.replace(mode)));
+        f()
+    }
+
+    /// Fail unless the running version of SQLite supports persistent extensions. This is
+    /// called by the entry points of extensions declared with `persistent(required)`, before
+    /// the init function runs.
+    #[doc(hidden)]
+    pub fn require_persistent_load() -> Result<()> {
+        sqlite3_match_version! {
+            3_014_000 => Ok(()),
+            _ => Err(Error::Module(
+                "extension must be loaded persistently, which requires SQLite 3.14.0 or above"
+                    .to_owned(),
+            )),
+        }
+    }
+
+    /// Returns true if the init function currently running was invoked by SQLite from an
+    /// entry point which loads the extension persistently.
+    ///
+    /// An extension declared as `persistent` is loaded persistently only on SQLite 3.14.0
+    /// and above, so init code which depends on staying loaded, for example by registering
+    /// automatic extensions or starting background threads, can use this method to adapt
+    /// on older versions. This method returns false when the Rust init function is called
+    /// directly. See [sqlite3_ext_init] for details.
+    pub fn is_persistent_load() -> bool {
+        LOAD_MODE.with(|t| t.get()) == LoadMode::Persistent
+    }
+
     /// Register this extension as an automatic extension.
     ///
     /// The provided method will be invoked on all database connections opened in the
-    /// future. For more information, consult the SQLite documentation for
-    /// `sqlite3_auto_extension`.
+    /// future. Registering an extension which is already registered has no effect. For more
+    /// information, consult the SQLite documentation for `sqlite3_auto_extension`.
     ///
     /// Requires SQLite 3.8.7.
     pub fn register_auto(&'static self) -> Result<()> {
@@ -76,17 +220,18 @@ impl Extension {
     /// For more information, consult the SQLite documentation for
     /// `sqlite3_reset_auto_extension`.
     ///
-    /// Requires SQLite 3.8.7. On earlier verions this method is a no-op.
+    /// Requires SQLite 3.8.7. On earlier verions this method is a no-op. This is the same as
+    /// [reset_auto_extensions].
     pub fn reset_auto() {
-        sqlite3_match_version! {
-            3_008_007 => unsafe { ffi::sqlite3_reset_auto_extension() },
-            _ => (),
-        }
+        reset_auto_extensions()
     }
 
-    /// Remove a previously-registered automatic extension.
+    /// Remove a previously-registered automatic extension. Connections which have already
+    /// been opened are not affected.
     ///
-    /// For more information, consult the SQLite documentation for
+    /// Returns true if the extension was registered. Because registering an extension more
+    /// than once has no effect, a single call removes the extension regardless of how many
+    /// times it was registered. For more information, consult the SQLite documentation for
     /// `sqlite3_cancel_auto_extension`.
     ///
     /// Requires SQLite 3.8.7.
@@ -104,3 +249,40 @@ impl Deref for Extension {
         &self.init
     }
 }
+
+/// Remove all automatic extensions registered with [Extension::register_auto], including
+/// those registered by other libraries in the process.
+///
+/// For more information, consult the SQLite documentation for
+/// `sqlite3_reset_auto_extension`.
+///
+/// Requires SQLite 3.8.7. On earlier verions this function is a no-op.
+pub fn reset_auto_extensions() {
+    sqlite3_match_version! {
+        3_008_007 => unsafe { ffi::sqlite3_reset_auto_extension() },
+        _ => (),
+    }
+}
+
+/// Fail if a non-persistent extension declared with `unload_safe` is currently being loaded
+/// and T has drop glue. See [Extension] for details.
+pub(crate) fn check_transient_drop<T>(what: &str) -> Result<()> {
+    if needs_drop::<T>() && LOAD_MODE.with(|t| t.get()) == LoadMode::UnloadSafe {
+        Err(Error::Module(format!(
+            "{what} has state which must be dropped and cannot be registered by a non-persistent extension"
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the destructor to register with SQLite for a `Box<T>`. While a non-persistent
+/// `unload_safe` extension is being loaded this is None, since the destructor lives in the
+/// library, which may be unmapped by the time SQLite calls it. [check_transient_drop] ensures
+/// that only values without drop glue are leaked this way.
+pub(crate) fn boxed_destructor<T>() -> Option<unsafe extern "C" fn(*mut c_void)> {
+    match LOAD_MODE.with(|t| t.get()) {
+        LoadMode::UnloadSafe => None,
+        _ => Some(ffi::drop_boxed::<T>),
+    }
+}
@@ -0,0 +1,1199 @@
+//! The SQLite API which is available to loadable extensions.
+//!
+//! Every function is listed exactly once, in the order of the fields of
+//! `sqlite3_api_routines`, grouped by the version of SQLite which added it to the extension
+//! API. Functions older than the minimum supported version of SQLite are listed as 3.6.8. The
+//! list generates:
+//!
+//! - the `sqlite3_api_routines` struct, which is the layout SQLite uses to pass the API to a
+//!   dynamically loaded extension;
+//! - a wrapper for each function, which calls through `sqlite3_api_routines` when
+//!   dynamically linking, and calls the extern function directly when statically linking;
+//! - [API_VERSIONS] and [available], which describe the version of SQLite that each function
+//!   requires.
+//!
+//! Variadic functions cannot be wrapped, so their wrappers return the function pointer
+//! instead. Adding a new function only requires adding it to the end of the list, under the
+//! version which introduced it.
+use super::{is_version, sqlite3types::*};
+use std::os::raw::*;
+
+/// Generate the wrapper for a single function, when statically linking.
+#[cfg(feature = "static")]
+macro_rules! api_function {
+    ([$(#[$attr:meta])*] $field:ident $name:ident ($($arg:ident : $ty:ty),* $(,)?) ($($ret:ty)?)) => {
+        $(#[$attr])*
+        #[allow(clippy::too_many_arguments, clippy::missing_safety_doc)]
+        pub unsafe fn $name($($arg: $ty),*) $(-> $ret)? {
+            extern "C" {
+                fn $name($($arg: $ty),*) $(-> $ret)?;
+            }
+            $name($($arg),*)
+        }
+    };
+    ([$(#[$attr:meta])*] $field:ident $name:ident ($($arg:ident : $ty:ty,)* ...) ($($ret:ty)?)) => {
+        $(#[$attr])*
+        #[allow(clippy::too_many_arguments, clippy::missing_safety_doc)]
+        pub unsafe fn $name() -> unsafe extern "C" fn($($arg: $ty,)* ...) $(-> $ret)? {
+            extern "C" {
+                fn $name($($arg: $ty,)* ...) $(-> $ret)?;
+            }
+            $name
+        }
+    };
+}
+
+/// Generate the wrapper for a single function, when dynamically linking.
+#[cfg(not(feature = "static"))]
+macro_rules! api_function {
+    ([$(#[$attr:meta])*] $field:ident $name:ident ($($arg:ident : $ty:ty),* $(,)?) ($($ret:ty)?)) => {
+        $(#[$attr])*
+        #[allow(clippy::too_many_arguments, clippy::missing_safety_doc)]
+        pub unsafe fn $name($($arg: $ty),*) $(-> $ret)? {
+            api_function!(@get $field $name)($($arg),*)
+        }
+    };
+    ([$(#[$attr:meta])*] $field:ident $name:ident ($($arg:ident : $ty:ty,)* ...) ($($ret:ty)?)) => {
+        $(#[$attr])*
+        #[allow(clippy::too_many_arguments, clippy::missing_safety_doc)]
+        pub unsafe fn $name() -> unsafe extern "C" fn($($arg: $ty,)* ...) $(-> $ret)? {
+            api_function!(@get $field $name)
+        }
+    };
+    (@get $field:ident $name:ident) => {{
+        debug_assert!(!API.is_null(), "SQLite API not initialized");
+        (*API)
+            .$field
+            .expect(concat!(stringify!($name), " is not provided by this SQLite"))
+    }};
+}
+
+/// Returns true if the field is set in the API routines provided by SQLite.
+#[cfg(feature = "static")]
+macro_rules! api_present {
+    ($field:ident) => {
+        true
+    };
+}
+
+#[cfg(not(feature = "static"))]
+macro_rules! api_present {
+    ($field:ident) => {
+        unsafe { !API.is_null() && (*API).$field.is_some() }
+    };
+}
+
+/// Generate the API from the list of functions. Attributes on a version group or a function
+/// are applied to the generated wrappers, so `cfg` can be used to omit functions which the
+/// linked SQLite may not provide. The `sqlite3_api_routines` struct always has every field.
+macro_rules! sqlite3_api {
+    // Flatten each version group into its functions, each of which carries the attributes of
+    // its group.
+    (@flatten [$($done:tt)*]) => {
+        sqlite3_api!(@emit $($done)*);
+    };
+    (@flatten [$($done:tt)*] $(#[$group:meta])* $version:literal { $($body:tt)* } $($rest:tt)*) => {
+        sqlite3_api!(@group [$($done)*] [$(#[$group])*] $version { $($body)* } $($rest)*);
+    };
+    (@group [$($done:tt)*] $group:tt $version:literal {
+        $(
+            $(#[$attr:meta])*
+            $field:ident => fn $name:ident ($($args:tt)*) $(-> $ret:ty)?;
+        )*
+    } $($rest:tt)*) => {
+        sqlite3_api!(@flatten [
+            $($done)*
+            $($group [$(#[$attr])*] $version $field $name ($($args)*) ($($ret)?))*
+        ] $($rest)*);
+    };
+
+    (@emit $(
+        [$($group:tt)*] [$($attr:tt)*] $version:literal $field:ident $name:ident ($($args:tt)*) ($($ret:ty)?)
+    )*) => {
+        /// The table of SQLite functions which is passed to a loadable extension.
+        #[repr(C)]
+        #[derive(Debug, Copy, Clone)]
+        pub struct sqlite3_api_routines {
+            $(pub $field: Option<unsafe extern "C" fn($($args)*) $(-> $ret)?>,)*
+        }
+
+        $(api_function!([$($group)* $($attr)*] $field $name ($($args)*) ($($ret)?));)*
+
+        /// The version of SQLite which added each function to the extension API.
+        pub const API_VERSIONS: &[(&str, c_int)] = &[$((stringify!($name), $version),)*];
+
+        /// Returns true if the named SQLite function can be called.
+        ///
+        /// A function can be called if it was compiled into this crate and the running
+        /// version of SQLite provides it.
+        ///
+        /// ```no_run
+        /// use sqlite3_ext::ffi;
+        ///
+        /// assert!(ffi::available("sqlite3_libversion"));
+        /// assert!(!ffi::available("sqlite3_not_a_function"));
+        /// ```
+        pub fn available(name: &str) -> bool {
+            match name {
+                $(
+                    $($group)* $($attr)*
+                    stringify!($name) => is_version($version) && api_present!($field),
+                )*
+                _ => false,
+            }
+        }
+    };
+
+    ($($tail:tt)*) => {
+        sqlite3_api!(@flatten [] $($tail)*);
+    };
+}
+
+#[cfg(feature = "static")]
+extern crate libsqlite3_sys;
+
+#[cfg(feature = "static")]
+pub unsafe fn init_api_routines(api: *mut sqlite3_api_routines) -> crate::types::Result<()> {
+    // This method is called when this statically linked extension is loaded on a database
+    // connection. However, it's possible that the extension is being loaded dynamically, in
+    // which case there may be multiple versions of SQLite. We can check this by verifying
+    // that the dynamically-provided API routines are at the same address as our
+    // statically-known ones. Note that if api is a null pointer, it means we are statically
+    // linked into an application with SQLITE_OMIT_LOAD_EXTENSION (so no worries).
+    let ours: unsafe extern "C" fn() -> c_int = libsqlite3_sys::sqlite3_libversion_number;
+    if !api.is_null() && (*api).libversion_number.map(|f| f as usize) != Some(ours as usize) {
+        return Err(crate::types::Error::Module(
+            "this extension is statically linked to SQLite and cannot be used as a loadable extension".to_owned(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "static"))]
+static mut API: *mut sqlite3_api_routines = std::ptr::null_mut();
+
+#[cfg(not(feature = "static"))]
+pub unsafe fn init_api_routines(api: *mut sqlite3_api_routines) -> crate::types::Result<()> {
+    API = api;
+    Ok(())
+}
+
+sqlite3_api! {
+    3_006_008 {
+        aggregate_context => fn sqlite3_aggregate_context(
+            arg1: *mut sqlite3_context,
+            nBytes: c_int,
+        ) -> *mut c_void;
+        aggregate_count => fn sqlite3_aggregate_count(arg1: *mut sqlite3_context) -> c_int;
+        bind_blob => fn sqlite3_bind_blob(
+            arg1: *mut sqlite3_stmt,
+            arg2: c_int,
+            arg3: *const c_void,
+            n: c_int,
+            arg4: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+        ) -> c_int;
+        bind_double => fn sqlite3_bind_double(
+            arg1: *mut sqlite3_stmt,
+            arg2: c_int,
+            arg3: f64,
+        ) -> c_int;
+        bind_int => fn sqlite3_bind_int(arg1: *mut sqlite3_stmt, arg2: c_int, arg3: c_int)
+            -> c_int;
+        bind_int64 => fn sqlite3_bind_int64(
+            arg1: *mut sqlite3_stmt,
+            arg2: c_int,
+            arg3: sqlite_int64,
+        ) -> c_int;
+        bind_null => fn sqlite3_bind_null(arg1: *mut sqlite3_stmt, arg2: c_int) -> c_int;
+        bind_parameter_count => fn sqlite3_bind_parameter_count(arg1: *mut sqlite3_stmt) -> c_int;
+        bind_parameter_index => fn sqlite3_bind_parameter_index(
+            arg1: *mut sqlite3_stmt,
+            zName: *const c_char,
+        ) -> c_int;
+        bind_parameter_name => fn sqlite3_bind_parameter_name(
+            arg1: *mut sqlite3_stmt,
+            arg2: c_int,
+        ) -> *const c_char;
+        bind_text => fn sqlite3_bind_text(
+            arg1: *mut sqlite3_stmt,
+            arg2: c_int,
+            arg3: *const c_char,
+            n: c_int,
+            arg4: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+        ) -> c_int;
+        bind_text16 => fn sqlite3_bind_text16(
+            arg1: *mut sqlite3_stmt,
+            arg2: c_int,
+            arg3: *const c_void,
+            arg4: c_int,
+            arg5: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+        ) -> c_int;
+        bind_value => fn sqlite3_bind_value(
+            arg1: *mut sqlite3_stmt,
+            arg2: c_int,
+            arg3: *const sqlite3_value,
+        ) -> c_int;
+        busy_handler => fn sqlite3_busy_handler(
+            arg1: *mut sqlite3,
+            arg2: Option<unsafe extern "C" fn(arg1: *mut c_void, arg2: c_int) -> c_int>,
+            arg3: *mut c_void,
+        ) -> c_int;
+        busy_timeout => fn sqlite3_busy_timeout(arg1: *mut sqlite3, ms: c_int) -> c_int;
+        changes => fn sqlite3_changes(arg1: *mut sqlite3) -> c_int;
+        close => fn sqlite3_close(arg1: *mut sqlite3) -> c_int;
+        collation_needed => fn sqlite3_collation_needed(
+            arg1: *mut sqlite3,
+            arg2: *mut c_void,
+            arg3: Option<
+                unsafe extern "C" fn(
+                    arg1: *mut c_void,
+                    arg2: *mut sqlite3,
+                    eTextRep: c_int,
+                    arg3: *const c_char,
+                ),
+            >,
+        ) -> c_int;
+        collation_needed16 => fn sqlite3_collation_needed16(
+            arg1: *mut sqlite3,
+            arg2: *mut c_void,
+            arg3: Option<
+                unsafe extern "C" fn(
+                    arg1: *mut c_void,
+                    arg2: *mut sqlite3,
+                    eTextRep: c_int,
+                    arg3: *const c_void,
+                ),
+            >,
+        ) -> c_int;
+        column_blob => fn sqlite3_column_blob(arg1: *mut sqlite3_stmt, iCol: c_int) -> *const c_void;
+        column_bytes => fn sqlite3_column_bytes(arg1: *mut sqlite3_stmt, iCol: c_int) -> c_int;
+        column_bytes16 => fn sqlite3_column_bytes16(arg1: *mut sqlite3_stmt, iCol: c_int) -> c_int;
+        column_count => fn sqlite3_column_count(pStmt: *mut sqlite3_stmt) -> c_int;
+        column_database_name => fn sqlite3_column_database_name(
+            arg1: *mut sqlite3_stmt,
+            arg2: c_int,
+        ) -> *const c_char;
+        column_database_name16 => fn sqlite3_column_database_name16(
+            arg1: *mut sqlite3_stmt,
+            arg2: c_int,
+        ) -> *const c_void;
+        column_decltype => fn sqlite3_column_decltype(arg1: *mut sqlite3_stmt, i: c_int) -> *const c_char;
+        column_decltype16 => fn sqlite3_column_decltype16(
+            arg1: *mut sqlite3_stmt,
+            arg2: c_int,
+        ) -> *const c_void;
+        column_double => fn sqlite3_column_double(arg1: *mut sqlite3_stmt, iCol: c_int) -> f64;
+        column_int => fn sqlite3_column_int(arg1: *mut sqlite3_stmt, iCol: c_int) -> c_int;
+        column_int64 => fn sqlite3_column_int64(arg1: *mut sqlite3_stmt, iCol: c_int) -> sqlite_int64;
+        column_name => fn sqlite3_column_name(arg1: *mut sqlite3_stmt, arg2: c_int) -> *const c_char;
+        column_name16 => fn sqlite3_column_name16(arg1: *mut sqlite3_stmt, arg2: c_int)
+            -> *const c_void;
+        column_origin_name => fn sqlite3_column_origin_name(
+            arg1: *mut sqlite3_stmt,
+            arg2: c_int,
+        ) -> *const c_char;
+        column_origin_name16 => fn sqlite3_column_origin_name16(
+            arg1: *mut sqlite3_stmt,
+            arg2: c_int,
+        ) -> *const c_void;
+        column_table_name => fn sqlite3_column_table_name(
+            arg1: *mut sqlite3_stmt,
+            arg2: c_int,
+        ) -> *const c_char;
+        column_table_name16 => fn sqlite3_column_table_name16(
+            arg1: *mut sqlite3_stmt,
+            arg2: c_int,
+        ) -> *const c_void;
+        column_text => fn sqlite3_column_text(arg1: *mut sqlite3_stmt, iCol: c_int) -> *const c_uchar;
+        column_text16 => fn sqlite3_column_text16(arg1: *mut sqlite3_stmt, iCol: c_int)
+            -> *const c_void;
+        column_type => fn sqlite3_column_type(arg1: *mut sqlite3_stmt, iCol: c_int) -> c_int;
+        column_value => fn sqlite3_column_value(
+            arg1: *mut sqlite3_stmt,
+            iCol: c_int,
+        ) -> *mut sqlite3_value;
+        commit_hook => fn sqlite3_commit_hook(
+            arg1: *mut sqlite3,
+            arg2: Option<unsafe extern "C" fn(arg1: *mut c_void) -> c_int>,
+            arg3: *mut c_void,
+        ) -> *mut c_void;
+        complete => fn sqlite3_complete(sql: *const c_char) -> c_int;
+        complete16 => fn sqlite3_complete16(sql: *const c_void) -> c_int;
+        create_collation => fn sqlite3_create_collation(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: c_int,
+            arg4: *mut c_void,
+            arg5: Option<
+                unsafe extern "C" fn(
+                    arg1: *mut c_void,
+                    arg2: c_int,
+                    arg3: *const c_void,
+                    arg4: c_int,
+                    arg5: *const c_void,
+                ) -> c_int,
+            >,
+        ) -> c_int;
+        create_collation16 => fn sqlite3_create_collation16(
+            arg1: *mut sqlite3,
+            arg2: *const c_void,
+            arg3: c_int,
+            arg4: *mut c_void,
+            arg5: Option<
+                unsafe extern "C" fn(
+                    arg1: *mut c_void,
+                    arg2: c_int,
+                    arg3: *const c_void,
+                    arg4: c_int,
+                    arg5: *const c_void,
+                ) -> c_int,
+            >,
+        ) -> c_int;
+        create_function => fn sqlite3_create_function(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: c_int,
+            arg4: c_int,
+            arg5: *mut c_void,
+            xFunc: Option<
+                unsafe extern "C" fn(
+                    arg1: *mut sqlite3_context,
+                    arg2: c_int,
+                    arg3: *mut *mut sqlite3_value,
+                ),
+            >,
+            xStep: Option<
+                unsafe extern "C" fn(
+                    arg1: *mut sqlite3_context,
+                    arg2: c_int,
+                    arg3: *mut *mut sqlite3_value,
+                ),
+            >,
+            xFinal: Option<unsafe extern "C" fn(arg1: *mut sqlite3_context)>,
+        ) -> c_int;
+        create_function16 => fn sqlite3_create_function16(
+            arg1: *mut sqlite3,
+            arg2: *const c_void,
+            arg3: c_int,
+            arg4: c_int,
+            arg5: *mut c_void,
+            xFunc: Option<
+                unsafe extern "C" fn(
+                    arg1: *mut sqlite3_context,
+                    arg2: c_int,
+                    arg3: *mut *mut sqlite3_value,
+                ),
+            >,
+            xStep: Option<
+                unsafe extern "C" fn(
+                    arg1: *mut sqlite3_context,
+                    arg2: c_int,
+                    arg3: *mut *mut sqlite3_value,
+                ),
+            >,
+            xFinal: Option<unsafe extern "C" fn(arg1: *mut sqlite3_context)>,
+        ) -> c_int;
+        create_module => fn sqlite3_create_module(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: *const sqlite3_module,
+            arg4: *mut c_void,
+        ) -> c_int;
+        data_count => fn sqlite3_data_count(pStmt: *mut sqlite3_stmt) -> c_int;
+        db_handle => fn sqlite3_db_handle(arg1: *mut sqlite3_stmt) -> *mut sqlite3;
+        declare_vtab => fn sqlite3_declare_vtab(arg1: *mut sqlite3, arg2: *const c_char) -> c_int;
+        enable_shared_cache => fn sqlite3_enable_shared_cache(arg1: c_int) -> c_int;
+        errcode => fn sqlite3_errcode(db: *mut sqlite3) -> c_int;
+        errmsg => fn sqlite3_errmsg(arg1: *mut sqlite3) -> *const c_char;
+        errmsg16 => fn sqlite3_errmsg16(arg1: *mut sqlite3) -> *const c_void;
+        exec => fn sqlite3_exec(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: sqlite3_callback,
+            arg4: *mut c_void,
+            arg5: *mut *mut c_char,
+        ) -> c_int;
+        expired => fn sqlite3_expired(arg1: *mut sqlite3_stmt) -> c_int;
+        finalize => fn sqlite3_finalize(pStmt: *mut sqlite3_stmt) -> c_int;
+        free => fn sqlite3_free(arg1: *mut c_void);
+        free_table => fn sqlite3_free_table(result: *mut *mut c_char);
+        get_autocommit => fn sqlite3_get_autocommit(arg1: *mut sqlite3) -> c_int;
+        get_auxdata => fn sqlite3_get_auxdata(arg1: *mut sqlite3_context, arg2: c_int) -> *mut c_void;
+        get_table => fn sqlite3_get_table(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: *mut *mut *mut c_char,
+            arg4: *mut c_int,
+            arg5: *mut c_int,
+            arg6: *mut *mut c_char,
+        ) -> c_int;
+        global_recover => fn sqlite3_global_recover() -> c_int;
+        interruptx => fn sqlite3_interrupt(arg1: *mut sqlite3);
+        last_insert_rowid => fn sqlite3_last_insert_rowid(arg1: *mut sqlite3) -> sqlite_int64;
+        libversion => fn sqlite3_libversion() -> *const c_char;
+        libversion_number => fn sqlite3_libversion_number() -> c_int;
+        malloc => fn sqlite3_malloc(arg1: c_int) -> *mut c_void;
+        mprintf => fn sqlite3_mprintf(arg1: *const c_char, ...) -> *mut c_char;
+        open => fn sqlite3_open(arg1: *const c_char, arg2: *mut *mut sqlite3) -> c_int;
+        open16 => fn sqlite3_open16(arg1: *const c_void, arg2: *mut *mut sqlite3) -> c_int;
+        prepare => fn sqlite3_prepare(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: c_int,
+            arg4: *mut *mut sqlite3_stmt,
+            arg5: *mut *const c_char,
+        ) -> c_int;
+        prepare16 => fn sqlite3_prepare16(
+            arg1: *mut sqlite3,
+            arg2: *const c_void,
+            arg3: c_int,
+            arg4: *mut *mut sqlite3_stmt,
+            arg5: *mut *const c_void,
+        ) -> c_int;
+        profile => fn sqlite3_profile(
+            arg1: *mut sqlite3,
+            arg2: Option<
+                unsafe extern "C" fn(
+                    arg1: *mut c_void,
+                    arg2: *const c_char,
+                    arg3: sqlite_uint64,
+                ),
+            >,
+            arg3: *mut c_void,
+        ) -> *mut c_void;
+        progress_handler => fn sqlite3_progress_handler(
+            arg1: *mut sqlite3,
+            arg2: c_int,
+            arg3: Option<unsafe extern "C" fn(arg1: *mut c_void) -> c_int>,
+            arg4: *mut c_void,
+        );
+        realloc => fn sqlite3_realloc(arg1: *mut c_void, arg2: c_int) -> *mut c_void;
+        reset => fn sqlite3_reset(pStmt: *mut sqlite3_stmt) -> c_int;
+        result_blob => fn sqlite3_result_blob(
+            arg1: *mut sqlite3_context,
+            arg2: *const c_void,
+            arg3: c_int,
+            arg4: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+        );
+        result_double => fn sqlite3_result_double(arg1: *mut sqlite3_context, arg2: f64);
+        result_error => fn sqlite3_result_error(
+            arg1: *mut sqlite3_context,
+            arg2: *const c_char,
+            arg3: c_int,
+        );
+        result_error16 => fn sqlite3_result_error16(
+            arg1: *mut sqlite3_context,
+            arg2: *const c_void,
+            arg3: c_int,
+        );
+        result_int => fn sqlite3_result_int(arg1: *mut sqlite3_context, arg2: c_int);
+        result_int64 => fn sqlite3_result_int64(arg1: *mut sqlite3_context, arg2: sqlite_int64);
+        result_null => fn sqlite3_result_null(arg1: *mut sqlite3_context);
+        result_text => fn sqlite3_result_text(
+            arg1: *mut sqlite3_context,
+            arg2: *const c_char,
+            arg3: c_int,
+            arg4: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+        );
+        result_text16 => fn sqlite3_result_text16(
+            arg1: *mut sqlite3_context,
+            arg2: *const c_void,
+            arg3: c_int,
+            arg4: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+        );
+        result_text16be => fn sqlite3_result_text16be(
+            arg1: *mut sqlite3_context,
+            arg2: *const c_void,
+            arg3: c_int,
+            arg4: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+        );
+        result_text16le => fn sqlite3_result_text16le(
+            arg1: *mut sqlite3_context,
+            arg2: *const c_void,
+            arg3: c_int,
+            arg4: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+        );
+        result_value => fn sqlite3_result_value(arg1: *mut sqlite3_context, arg2: *mut sqlite3_value);
+        rollback_hook => fn sqlite3_rollback_hook(
+            arg1: *mut sqlite3,
+            arg2: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+            arg3: *mut c_void,
+        ) -> *mut c_void;
+        set_authorizer => fn sqlite3_set_authorizer(
+            arg1: *mut sqlite3,
+            arg2: Option<
+                unsafe extern "C" fn(
+                    arg1: *mut c_void,
+                    arg2: c_int,
+                    arg3: *const c_char,
+                    arg4: *const c_char,
+                    arg5: *const c_char,
+                    arg6: *const c_char,
+                ) -> c_int,
+            >,
+            arg3: *mut c_void,
+        ) -> c_int;
+        set_auxdata => fn sqlite3_set_auxdata(
+            arg1: *mut sqlite3_context,
+            arg2: c_int,
+            arg3: *mut c_void,
+            arg4: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+        );
+        xsnprintf => fn sqlite3_snprintf(
+            arg1: c_int,
+            arg2: *mut c_char,
+            arg3: *const c_char,
+            ...
+        ) -> *mut c_char;
+        step => fn sqlite3_step(arg1: *mut sqlite3_stmt) -> c_int;
+        table_column_metadata => fn sqlite3_table_column_metadata(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: *const c_char,
+            arg4: *const c_char,
+            arg5: *mut *const c_char,
+            arg6: *mut *const c_char,
+            arg7: *mut c_int,
+            arg8: *mut c_int,
+            arg9: *mut c_int,
+        ) -> c_int;
+        thread_cleanup => fn sqlite3_thread_cleanup();
+        total_changes => fn sqlite3_total_changes(arg1: *mut sqlite3) -> c_int;
+        trace => fn sqlite3_trace(
+            arg1: *mut sqlite3,
+            xTrace: Option<unsafe extern "C" fn(arg1: *mut c_void, arg2: *const c_char)>,
+            arg2: *mut c_void,
+        ) -> *mut c_void;
+        transfer_bindings => fn sqlite3_transfer_bindings(
+            arg1: *mut sqlite3_stmt,
+            arg2: *mut sqlite3_stmt,
+        ) -> c_int;
+        update_hook => fn sqlite3_update_hook(
+            arg1: *mut sqlite3,
+            arg2: Option<
+                unsafe extern "C" fn(
+                    arg1: *mut c_void,
+                    arg2: c_int,
+                    arg3: *const c_char,
+                    arg4: *const c_char,
+                    arg5: sqlite_int64,
+                ),
+            >,
+            arg3: *mut c_void,
+        ) -> *mut c_void;
+        user_data => fn sqlite3_user_data(arg1: *mut sqlite3_context) -> *mut c_void;
+        value_blob => fn sqlite3_value_blob(arg1: *mut sqlite3_value) -> *const c_void;
+        value_bytes => fn sqlite3_value_bytes(arg1: *mut sqlite3_value) -> c_int;
+        value_bytes16 => fn sqlite3_value_bytes16(arg1: *mut sqlite3_value) -> c_int;
+        value_double => fn sqlite3_value_double(arg1: *mut sqlite3_value) -> f64;
+        value_int => fn sqlite3_value_int(arg1: *mut sqlite3_value) -> c_int;
+        value_int64 => fn sqlite3_value_int64(arg1: *mut sqlite3_value) -> sqlite_int64;
+        value_numeric_type => fn sqlite3_value_numeric_type(arg1: *mut sqlite3_value) -> c_int;
+        value_text => fn sqlite3_value_text(arg1: *mut sqlite3_value) -> *const c_uchar;
+        value_text16 => fn sqlite3_value_text16(arg1: *mut sqlite3_value) -> *const c_void;
+        value_text16be => fn sqlite3_value_text16be(arg1: *mut sqlite3_value) -> *const c_void;
+        value_text16le => fn sqlite3_value_text16le(arg1: *mut sqlite3_value) -> *const c_void;
+        value_type => fn sqlite3_value_type(arg1: *mut sqlite3_value) -> c_int;
+        vmprintf => fn sqlite3_vmprintf(arg1: *const c_char, arg2: va_list) -> *mut c_char;
+        overload_function => fn sqlite3_overload_function(
+            arg1: *mut sqlite3,
+            zFuncName: *const c_char,
+            nArg: c_int,
+        ) -> c_int;
+        prepare_v2 => fn sqlite3_prepare_v2(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: c_int,
+            arg4: *mut *mut sqlite3_stmt,
+            arg5: *mut *const c_char,
+        ) -> c_int;
+        prepare16_v2 => fn sqlite3_prepare16_v2(
+            arg1: *mut sqlite3,
+            arg2: *const c_void,
+            arg3: c_int,
+            arg4: *mut *mut sqlite3_stmt,
+            arg5: *mut *const c_void,
+        ) -> c_int;
+        clear_bindings => fn sqlite3_clear_bindings(arg1: *mut sqlite3_stmt) -> c_int;
+        create_module_v2 => fn sqlite3_create_module_v2(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: *const sqlite3_module,
+            arg4: *mut c_void,
+            xDestroy: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+        ) -> c_int;
+        bind_zeroblob => fn sqlite3_bind_zeroblob(
+            arg1: *mut sqlite3_stmt,
+            arg2: c_int,
+            arg3: c_int,
+        ) -> c_int;
+        blob_bytes => fn sqlite3_blob_bytes(arg1: *mut sqlite3_blob) -> c_int;
+        blob_close => fn sqlite3_blob_close(arg1: *mut sqlite3_blob) -> c_int;
+        blob_open => fn sqlite3_blob_open(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: *const c_char,
+            arg4: *const c_char,
+            arg5: sqlite3_int64,
+            arg6: c_int,
+            arg7: *mut *mut sqlite3_blob,
+        ) -> c_int;
+        blob_read => fn sqlite3_blob_read(
+            arg1: *mut sqlite3_blob,
+            arg2: *mut c_void,
+            arg3: c_int,
+            arg4: c_int,
+        ) -> c_int;
+        blob_write => fn sqlite3_blob_write(
+            arg1: *mut sqlite3_blob,
+            arg2: *const c_void,
+            arg3: c_int,
+            arg4: c_int,
+        ) -> c_int;
+        create_collation_v2 => fn sqlite3_create_collation_v2(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: c_int,
+            arg4: *mut c_void,
+            arg5: Option<
+                unsafe extern "C" fn(
+                    arg1: *mut c_void,
+                    arg2: c_int,
+                    arg3: *const c_void,
+                    arg4: c_int,
+                    arg5: *const c_void,
+                ) -> c_int,
+            >,
+            arg6: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+        ) -> c_int;
+        file_control => fn sqlite3_file_control(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: c_int,
+            arg4: *mut c_void,
+        ) -> c_int;
+        memory_highwater => fn sqlite3_memory_highwater(arg1: c_int) -> sqlite3_int64;
+        memory_used => fn sqlite3_memory_used() -> sqlite3_int64;
+        mutex_alloc => fn sqlite3_mutex_alloc(arg1: c_int) -> *mut sqlite3_mutex;
+        mutex_enter => fn sqlite3_mutex_enter(arg1: *mut sqlite3_mutex);
+        mutex_free => fn sqlite3_mutex_free(arg1: *mut sqlite3_mutex);
+        mutex_leave => fn sqlite3_mutex_leave(arg1: *mut sqlite3_mutex);
+        mutex_try => fn sqlite3_mutex_try(arg1: *mut sqlite3_mutex) -> c_int;
+        open_v2 => fn sqlite3_open_v2(
+            arg1: *const c_char,
+            arg2: *mut *mut sqlite3,
+            arg3: c_int,
+            arg4: *const c_char,
+        ) -> c_int;
+        release_memory => fn sqlite3_release_memory(arg1: c_int) -> c_int;
+        result_error_nomem => fn sqlite3_result_error_nomem(arg1: *mut sqlite3_context);
+        result_error_toobig => fn sqlite3_result_error_toobig(arg1: *mut sqlite3_context);
+        sleep => fn sqlite3_sleep(arg1: c_int) -> c_int;
+        soft_heap_limit => fn sqlite3_soft_heap_limit(arg1: c_int);
+        vfs_find => fn sqlite3_vfs_find(arg1: *const c_char) -> *mut sqlite3_vfs;
+        vfs_register => fn sqlite3_vfs_register(arg1: *mut sqlite3_vfs, arg2: c_int) -> c_int;
+        vfs_unregister => fn sqlite3_vfs_unregister(arg1: *mut sqlite3_vfs) -> c_int;
+        xthreadsafe => fn sqlite3_threadsafe() -> c_int;
+        result_zeroblob => fn sqlite3_result_zeroblob(arg1: *mut sqlite3_context, arg2: c_int);
+        result_error_code => fn sqlite3_result_error_code(arg1: *mut sqlite3_context, arg2: c_int);
+        test_control => fn sqlite3_test_control(arg1: c_int, ...) -> c_int;
+        randomness => fn sqlite3_randomness(arg1: c_int, arg2: *mut c_void);
+        context_db_handle => fn sqlite3_context_db_handle(arg1: *mut sqlite3_context) -> *mut sqlite3;
+        extended_result_codes => fn sqlite3_extended_result_codes(arg1: *mut sqlite3, arg2: c_int) -> c_int;
+        limit => fn sqlite3_limit(arg1: *mut sqlite3, arg2: c_int, arg3: c_int) -> c_int;
+        next_stmt => fn sqlite3_next_stmt(
+            arg1: *mut sqlite3,
+            arg2: *mut sqlite3_stmt,
+        ) -> *mut sqlite3_stmt;
+        sql => fn sqlite3_sql(arg1: *mut sqlite3_stmt) -> *const c_char;
+        status => fn sqlite3_status(
+            arg1: c_int,
+            arg2: *mut c_int,
+            arg3: *mut c_int,
+            arg4: c_int,
+        ) -> c_int;
+        backup_finish => fn sqlite3_backup_finish(arg1: *mut sqlite3_backup) -> c_int;
+        backup_init => fn sqlite3_backup_init(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: *mut sqlite3,
+            arg4: *const c_char,
+        ) -> *mut sqlite3_backup;
+        backup_pagecount => fn sqlite3_backup_pagecount(arg1: *mut sqlite3_backup) -> c_int;
+        backup_remaining => fn sqlite3_backup_remaining(arg1: *mut sqlite3_backup) -> c_int;
+        backup_step => fn sqlite3_backup_step(arg1: *mut sqlite3_backup, arg2: c_int) -> c_int;
+        compileoption_get => fn sqlite3_compileoption_get(arg1: c_int) -> *const c_char;
+        compileoption_used => fn sqlite3_compileoption_used(arg1: *const c_char) -> c_int;
+        create_function_v2 => fn sqlite3_create_function_v2(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: c_int,
+            arg4: c_int,
+            arg5: *mut c_void,
+            xFunc: Option<
+                unsafe extern "C" fn(
+                    arg1: *mut sqlite3_context,
+                    arg2: c_int,
+                    arg3: *mut *mut sqlite3_value,
+                ),
+            >,
+            xStep: Option<
+                unsafe extern "C" fn(
+                    arg1: *mut sqlite3_context,
+                    arg2: c_int,
+                    arg3: *mut *mut sqlite3_value,
+                ),
+            >,
+            xFinal: Option<unsafe extern "C" fn(arg1: *mut sqlite3_context)>,
+            xDestroy: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+        ) -> c_int;
+        db_config => fn sqlite3_db_config(arg1: *mut sqlite3, arg2: c_int, ...) -> c_int;
+        db_mutex => fn sqlite3_db_mutex(arg1: *mut sqlite3) -> *mut sqlite3_mutex;
+        db_status => fn sqlite3_db_status(
+            arg1: *mut sqlite3,
+            arg2: c_int,
+            arg3: *mut c_int,
+            arg4: *mut c_int,
+            arg5: c_int,
+        ) -> c_int;
+        extended_errcode => fn sqlite3_extended_errcode(arg1: *mut sqlite3) -> c_int;
+        log => fn sqlite3_log(arg1: c_int, arg2: *const c_char, ...);
+        soft_heap_limit64 => fn sqlite3_soft_heap_limit64(arg1: sqlite3_int64) -> sqlite3_int64;
+        sourceid => fn sqlite3_sourceid() -> *const c_char;
+        stmt_status => fn sqlite3_stmt_status(
+            arg1: *mut sqlite3_stmt,
+            arg2: c_int,
+            arg3: c_int,
+        ) -> c_int;
+        strnicmp => fn sqlite3_strnicmp(
+            arg1: *const c_char,
+            arg2: *const c_char,
+            arg3: c_int,
+        ) -> c_int;
+        // Conditionally compiled by SQLite, and not enabled by libsqlite3-sys.
+        #[cfg(not(feature = "static"))]
+        unlock_notify => fn sqlite3_unlock_notify(
+            arg1: *mut sqlite3,
+            arg2: Option<unsafe extern "C" fn(arg1: *mut *mut c_void, arg2: c_int)>,
+            arg3: *mut c_void,
+        ) -> c_int;
+        wal_autocheckpoint => fn sqlite3_wal_autocheckpoint(arg1: *mut sqlite3, arg2: c_int) -> c_int;
+        wal_checkpoint => fn sqlite3_wal_checkpoint(arg1: *mut sqlite3, arg2: *const c_char) -> c_int;
+        wal_hook => fn sqlite3_wal_hook(
+            arg1: *mut sqlite3,
+            arg2: Option<
+                unsafe extern "C" fn(
+                    arg1: *mut c_void,
+                    arg2: *mut sqlite3,
+                    arg3: *const c_char,
+                    arg4: c_int,
+                ) -> c_int,
+            >,
+            arg3: *mut c_void,
+        ) -> *mut c_void;
+        blob_reopen => fn sqlite3_blob_reopen(arg1: *mut sqlite3_blob, arg2: sqlite3_int64) -> c_int;
+        vtab_config => fn sqlite3_vtab_config(arg1: *mut sqlite3, op: c_int, ...) -> c_int;
+        vtab_on_conflict => fn sqlite3_vtab_on_conflict(arg1: *mut sqlite3) -> c_int;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_007_016 {
+        close_v2 => fn sqlite3_close_v2(arg1: *mut sqlite3) -> c_int;
+        db_filename => fn sqlite3_db_filename(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+        ) -> *const c_char;
+        db_readonly => fn sqlite3_db_readonly(arg1: *mut sqlite3, arg2: *const c_char) -> c_int;
+        db_release_memory => fn sqlite3_db_release_memory(arg1: *mut sqlite3) -> c_int;
+        errstr => fn sqlite3_errstr(arg1: c_int) -> *const c_char;
+        stmt_busy => fn sqlite3_stmt_busy(arg1: *mut sqlite3_stmt) -> c_int;
+        stmt_readonly => fn sqlite3_stmt_readonly(arg1: *mut sqlite3_stmt) -> c_int;
+        stricmp => fn sqlite3_stricmp(arg1: *const c_char, arg2: *const c_char) -> c_int;
+        uri_boolean => fn sqlite3_uri_boolean(
+            arg1: *const c_char,
+            arg2: *const c_char,
+            arg3: c_int,
+        ) -> c_int;
+        uri_int64 => fn sqlite3_uri_int64(
+            arg1: *const c_char,
+            arg2: *const c_char,
+            arg3: sqlite3_int64,
+        ) -> sqlite3_int64;
+        uri_parameter => fn sqlite3_uri_parameter(
+            arg1: *const c_char,
+            arg2: *const c_char,
+        ) -> *const c_char;
+        xvsnprintf => fn sqlite3_vsnprintf(
+            arg1: c_int,
+            arg2: *mut c_char,
+            arg3: *const c_char,
+            arg4: va_list,
+        ) -> *mut c_char;
+        wal_checkpoint_v2 => fn sqlite3_wal_checkpoint_v2(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: c_int,
+            arg4: *mut c_int,
+            arg5: *mut c_int,
+        ) -> c_int;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_008_007 {
+        auto_extension => fn sqlite3_auto_extension(arg1: Option<unsafe extern "C" fn()>) -> c_int;
+        bind_blob64 => fn sqlite3_bind_blob64(
+            arg1: *mut sqlite3_stmt,
+            arg2: c_int,
+            arg3: *const c_void,
+            arg4: sqlite3_uint64,
+            arg5: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+        ) -> c_int;
+        bind_text64 => fn sqlite3_bind_text64(
+            arg1: *mut sqlite3_stmt,
+            arg2: c_int,
+            arg3: *const c_char,
+            arg4: sqlite3_uint64,
+            arg5: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+            arg6: c_uchar,
+        ) -> c_int;
+        cancel_auto_extension => fn sqlite3_cancel_auto_extension(arg1: Option<unsafe extern "C" fn()>)
+            -> c_int;
+        load_extension => fn sqlite3_load_extension(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: *const c_char,
+            arg4: *mut *mut c_char,
+        ) -> c_int;
+        malloc64 => fn sqlite3_malloc64(arg1: sqlite3_uint64) -> *mut c_void;
+        msize => fn sqlite3_msize(arg1: *mut c_void) -> sqlite3_uint64;
+        realloc64 => fn sqlite3_realloc64(arg1: *mut c_void, arg2: sqlite3_uint64) -> *mut c_void;
+        reset_auto_extension => fn sqlite3_reset_auto_extension();
+        result_blob64 => fn sqlite3_result_blob64(
+            arg1: *mut sqlite3_context,
+            arg2: *const c_void,
+            arg3: sqlite3_uint64,
+            arg4: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+        );
+        result_text64 => fn sqlite3_result_text64(
+            arg1: *mut sqlite3_context,
+            arg2: *const c_char,
+            arg3: sqlite3_uint64,
+            arg4: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+            arg5: c_uchar,
+        );
+        strglob => fn sqlite3_strglob(arg1: *const c_char, arg2: *const c_char) -> c_int;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_008_011 {
+        value_dup => fn sqlite3_value_dup(arg1: *const sqlite3_value) -> *mut sqlite3_value;
+        value_free => fn sqlite3_value_free(arg1: *mut sqlite3_value);
+        result_zeroblob64 => fn sqlite3_result_zeroblob64(
+            arg1: *mut sqlite3_context,
+            arg2: sqlite3_uint64,
+        ) -> c_int;
+        bind_zeroblob64 => fn sqlite3_bind_zeroblob64(
+            arg1: *mut sqlite3_stmt,
+            arg2: c_int,
+            arg3: sqlite3_uint64,
+        ) -> c_int;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_009_000 {
+        value_subtype => fn sqlite3_value_subtype(arg1: *mut sqlite3_value) -> c_uint;
+        result_subtype => fn sqlite3_result_subtype(arg1: *mut sqlite3_context, arg2: c_uint);
+    }
+
+    #[cfg(modern_sqlite)]
+    3_010_000 {
+        status64 => fn sqlite3_status64(
+            arg1: c_int,
+            arg2: *mut sqlite3_int64,
+            arg3: *mut sqlite3_int64,
+            arg4: c_int,
+        ) -> c_int;
+        strlike => fn sqlite3_strlike(
+            arg1: *const c_char,
+            arg2: *const c_char,
+            arg3: c_uint,
+        ) -> c_int;
+        db_cacheflush => fn sqlite3_db_cacheflush(arg1: *mut sqlite3) -> c_int;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_012_000 {
+        system_errno => fn sqlite3_system_errno(arg1: *mut sqlite3) -> c_int;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_014_000 {
+        trace_v2 => fn sqlite3_trace_v2(
+            arg1: *mut sqlite3,
+            arg2: c_uint,
+            arg3: Option<
+                unsafe extern "C" fn(
+                    arg1: c_uint,
+                    arg2: *mut c_void,
+                    arg3: *mut c_void,
+                    arg4: *mut c_void,
+                ) -> c_int,
+            >,
+            arg4: *mut c_void,
+        ) -> c_int;
+        expanded_sql => fn sqlite3_expanded_sql(arg1: *mut sqlite3_stmt) -> *mut c_char;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_018_000 {
+        set_last_insert_rowid => fn sqlite3_set_last_insert_rowid(arg1: *mut sqlite3, arg2: sqlite3_int64);
+    }
+
+    #[cfg(modern_sqlite)]
+    3_020_000 {
+        prepare_v3 => fn sqlite3_prepare_v3(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: c_int,
+            arg4: c_uint,
+            arg5: *mut *mut sqlite3_stmt,
+            arg6: *mut *const c_char,
+        ) -> c_int;
+        prepare16_v3 => fn sqlite3_prepare16_v3(
+            arg1: *mut sqlite3,
+            arg2: *const c_void,
+            arg3: c_int,
+            arg4: c_uint,
+            arg5: *mut *mut sqlite3_stmt,
+            arg6: *mut *const c_void,
+        ) -> c_int;
+        bind_pointer => fn sqlite3_bind_pointer(
+            arg1: *mut sqlite3_stmt,
+            arg2: c_int,
+            arg3: *mut c_void,
+            arg4: *const c_char,
+            arg5: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+        ) -> c_int;
+        result_pointer => fn sqlite3_result_pointer(
+            arg1: *mut sqlite3_context,
+            arg2: *mut c_void,
+            arg3: *const c_char,
+            arg4: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+        );
+        value_pointer => fn sqlite3_value_pointer(
+            arg1: *mut sqlite3_value,
+            arg2: *const c_char,
+        ) -> *mut c_void;
+        vtab_nochange => fn sqlite3_vtab_nochange(arg1: *mut sqlite3_context) -> c_int;
+        value_nochange => fn sqlite3_value_nochange(arg1: *mut sqlite3_value) -> c_int;
+        vtab_collation => fn sqlite3_vtab_collation(
+            arg1: *mut sqlite3_index_info,
+            arg2: c_int,
+        ) -> *const c_char;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_024_000 {
+        keyword_count => fn sqlite3_keyword_count() -> c_int;
+        keyword_name => fn sqlite3_keyword_name(
+            arg1: c_int,
+            arg2: *mut *const c_char,
+            arg3: *mut c_int,
+        ) -> c_int;
+        keyword_check => fn sqlite3_keyword_check(arg1: *const c_char, arg2: c_int) -> c_int;
+        str_new => fn sqlite3_str_new(arg1: *mut sqlite3) -> *mut sqlite3_str;
+        str_finish => fn sqlite3_str_finish(arg1: *mut sqlite3_str) -> *mut c_char;
+        str_appendf => fn sqlite3_str_appendf(arg1: *mut sqlite3_str, zFormat: *const c_char, ...);
+        str_vappendf => fn sqlite3_str_vappendf(
+            arg1: *mut sqlite3_str,
+            zFormat: *const c_char,
+            arg2: va_list,
+        );
+        str_append => fn sqlite3_str_append(arg1: *mut sqlite3_str, zIn: *const c_char, N: c_int);
+        str_appendall => fn sqlite3_str_appendall(arg1: *mut sqlite3_str, zIn: *const c_char);
+        str_appendchar => fn sqlite3_str_appendchar(arg1: *mut sqlite3_str, N: c_int, C: c_char);
+        str_reset => fn sqlite3_str_reset(arg1: *mut sqlite3_str);
+        str_errcode => fn sqlite3_str_errcode(arg1: *mut sqlite3_str) -> c_int;
+        str_length => fn sqlite3_str_length(arg1: *mut sqlite3_str) -> c_int;
+        str_value => fn sqlite3_str_value(arg1: *mut sqlite3_str) -> *mut c_char;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_025_000 {
+        create_window_function => fn sqlite3_create_window_function(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: c_int,
+            arg4: c_int,
+            arg5: *mut c_void,
+            xStep: Option<
+                unsafe extern "C" fn(
+                    arg1: *mut sqlite3_context,
+                    arg2: c_int,
+                    arg3: *mut *mut sqlite3_value,
+                ),
+            >,
+            xFinal: Option<unsafe extern "C" fn(arg1: *mut sqlite3_context)>,
+            xValue: Option<unsafe extern "C" fn(arg1: *mut sqlite3_context)>,
+            xInv: Option<
+                unsafe extern "C" fn(
+                    arg1: *mut sqlite3_context,
+                    arg2: c_int,
+                    arg3: *mut *mut sqlite3_value,
+                ),
+            >,
+            xDestroy: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+        ) -> c_int;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_026_000 {
+        // Conditionally compiled by SQLite, and not enabled by libsqlite3-sys.
+        #[cfg(not(feature = "static"))]
+        normalized_sql => fn sqlite3_normalized_sql(arg1: *mut sqlite3_stmt) -> *const c_char;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_028_000 {
+        stmt_isexplain => fn sqlite3_stmt_isexplain(arg1: *mut sqlite3_stmt) -> c_int;
+        value_frombind => fn sqlite3_value_frombind(arg1: *mut sqlite3_value) -> c_int;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_030_000 {
+        drop_modules => fn sqlite3_drop_modules(arg1: *mut sqlite3, arg2: *mut *const c_char) -> c_int;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_031_000 {
+        hard_heap_limit64 => fn sqlite3_hard_heap_limit64(arg1: sqlite3_int64) -> sqlite3_int64;
+        uri_key => fn sqlite3_uri_key(arg1: *const c_char, arg2: c_int) -> *const c_char;
+        filename_database => fn sqlite3_filename_database(arg1: *const c_char) -> *const c_char;
+        filename_journal => fn sqlite3_filename_journal(arg1: *const c_char) -> *const c_char;
+        filename_wal => fn sqlite3_filename_wal(arg1: *const c_char) -> *const c_char;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_032_000 {
+        create_filename => fn sqlite3_create_filename(
+            arg1: *const c_char,
+            arg2: *const c_char,
+            arg3: *const c_char,
+            arg4: c_int,
+            arg5: *mut *const c_char,
+        ) -> *mut c_char;
+        free_filename => fn sqlite3_free_filename(arg1: *mut c_char);
+        database_file_object => fn sqlite3_database_file_object(arg1: *const c_char) -> *mut sqlite3_file;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_034_000 {
+        txn_state => fn sqlite3_txn_state(arg1: *mut sqlite3, arg2: *const c_char) -> c_int;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_036_001 {
+        changes64 => fn sqlite3_changes64(arg1: *mut sqlite3) -> sqlite3_int64;
+        total_changes64 => fn sqlite3_total_changes64(arg1: *mut sqlite3) -> sqlite3_int64;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_037_000 {
+        autovacuum_pages => fn sqlite3_autovacuum_pages(
+            arg1: *mut sqlite3,
+            arg2: Option<
+                unsafe extern "C" fn(
+                    arg1: *mut c_void,
+                    arg2: *const c_char,
+                    arg3: c_uint,
+                    arg4: c_uint,
+                    arg5: c_uint,
+                ) -> c_uint,
+            >,
+            arg3: *mut c_void,
+            arg4: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+        ) -> c_int;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_038_000 {
+        error_offset => fn sqlite3_error_offset(arg1: *mut sqlite3) -> c_int;
+        vtab_rhs_value => fn sqlite3_vtab_rhs_value(
+            arg1: *mut sqlite3_index_info,
+            arg2: c_int,
+            arg3: *mut *mut sqlite3_value,
+        ) -> c_int;
+        vtab_distinct => fn sqlite3_vtab_distinct(arg1: *mut sqlite3_index_info) -> c_int;
+        vtab_in => fn sqlite3_vtab_in(
+            arg1: *mut sqlite3_index_info,
+            arg2: c_int,
+            arg3: c_int,
+        ) -> c_int;
+        vtab_in_first => fn sqlite3_vtab_in_first(
+            arg1: *mut sqlite3_value,
+            arg2: *mut *mut sqlite3_value,
+        ) -> c_int;
+        vtab_in_next => fn sqlite3_vtab_in_next(
+            arg1: *mut sqlite3_value,
+            arg2: *mut *mut sqlite3_value,
+        ) -> c_int;
+    }
+
+    // The following functions may be newer than the SQLite that libsqlite3-sys links, so
+    // they are only available when dynamically linking.
+    #[cfg(modern_sqlite)]
+    3_039_000 {
+        #[cfg(not(feature = "static"))]
+        deserialize => fn sqlite3_deserialize(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: *mut c_uchar,
+            arg4: sqlite3_int64,
+            arg5: sqlite3_int64,
+            arg6: c_uint,
+        ) -> c_int;
+        #[cfg(not(feature = "static"))]
+        serialize => fn sqlite3_serialize(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: *mut sqlite3_int64,
+            arg4: c_uint,
+        ) -> *mut c_uchar;
+        #[cfg(not(feature = "static"))]
+        db_name => fn sqlite3_db_name(arg1: *mut sqlite3, arg2: c_int) -> *const c_char;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_040_000 {
+        #[cfg(not(feature = "static"))]
+        value_encoding => fn sqlite3_value_encoding(arg1: *mut sqlite3_value) -> c_int;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_041_000 {
+        #[cfg(not(feature = "static"))]
+        is_interrupted => fn sqlite3_is_interrupted(arg1: *mut sqlite3) -> c_int;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_043_000 {
+        #[cfg(not(feature = "static"))]
+        stmt_explain => fn sqlite3_stmt_explain(arg1: *mut sqlite3_stmt, arg2: c_int) -> c_int;
+    }
+
+    #[cfg(modern_sqlite)]
+    3_044_000 {
+        #[cfg(not(feature = "static"))]
+        get_clientdata => fn sqlite3_get_clientdata(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+        ) -> *mut c_void;
+        #[cfg(not(feature = "static"))]
+        set_clientdata => fn sqlite3_set_clientdata(
+            arg1: *mut sqlite3,
+            arg2: *const c_char,
+            arg3: *mut c_void,
+            arg4: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+        ) -> c_int;
+    }
+}
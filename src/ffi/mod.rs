@@ -2,20 +2,56 @@
 #![allow(non_snake_case)]
 #![allow(dead_code)]
 
-use crate::{value::Blob, Error};
-pub use linking::*;
+use crate::{value::Blob, Error, SqliteBuffer};
+pub use api::*;
 pub use sqlite3types::*;
 use std::{
     ffi::{c_void, CString},
     os::raw::{c_char, c_int},
-    ptr,
+    str::FromStr,
 };
 
-mod sqlite3funcs;
+mod api;
 mod sqlite3types;
 
-mod linking {
-    include!(concat!(env!("OUT_DIR"), "/linking.rs"));
+// sqlite3_config is not part of the extension API, so it is only available when statically
+// linking.
+#[cfg(feature = "static")]
+extern "C" {
+    pub(crate) fn sqlite3_config(op: c_int, ...) -> c_int;
+}
+
+// The preupdate hook is not part of the extension API, and is only present when SQLite is
+// compiled with SQLITE_ENABLE_PREUPDATE_HOOK.
+#[cfg(feature = "preupdate_hook")]
+extern "C" {
+    pub(crate) fn sqlite3_preupdate_hook(
+        db: *mut sqlite3,
+        xPreUpdate: Option<
+            unsafe extern "C" fn(
+                pCtx: *mut c_void,
+                db: *mut sqlite3,
+                op: c_int,
+                zDb: *const c_char,
+                zName: *const c_char,
+                iKey1: sqlite3_int64,
+                iKey2: sqlite3_int64,
+            ),
+        >,
+        arg: *mut c_void,
+    ) -> *mut c_void;
+    pub(crate) fn sqlite3_preupdate_old(
+        db: *mut sqlite3,
+        i: c_int,
+        ppValue: *mut *mut sqlite3_value,
+    ) -> c_int;
+    pub(crate) fn sqlite3_preupdate_new(
+        db: *mut sqlite3,
+        i: c_int,
+        ppValue: *mut *mut sqlite3_value,
+    ) -> c_int;
+    pub(crate) fn sqlite3_preupdate_count(db: *mut sqlite3) -> c_int;
+    pub(crate) fn sqlite3_preupdate_depth(db: *mut sqlite3) -> c_int;
 }
 
 /// We have to do this trampoline construct because the cfg attributes are evaluated in the
@@ -191,29 +227,16 @@ pub const unsafe fn sqlite_transient() -> Option<unsafe extern "C" fn(arg1: *mut
     std::mem::transmute(-1 as isize as usize)
 }
 
-/// Clone the provided string into a nul-terminated string created by sqlite3_malloc. This
-/// function returns a NULL pointer if the input string is empty. SQLite interfaces generally
-/// understand this to mean "no string", but other consumers may expect a 0-length string.
+/// Clone the provided string into a nul-terminated string created by sqlite3_malloc. An empty
+/// string results in a pointer to a single nul byte, never a NULL pointer. See
+/// [SqliteBuffer](crate::SqliteBuffer).
 pub fn str_to_sqlite3(val: &str) -> Result<*mut c_char, Error> {
-    if val.is_empty() {
-        return Ok(ptr::null_mut());
-    }
-    let len: usize = val.len().checked_add(1).ok_or(crate::types::SQLITE_NOMEM)?;
-    unsafe {
-        let ptr: *mut c_char = sqlite3_match_version! {
-            3_008_007 => sqlite3_malloc64(len as _) as _,
-            _ => sqlite3_malloc(len as _) as _,
-        };
-        if !ptr.is_null() {
-            ptr::copy_nonoverlapping(val.as_ptr(), ptr as _, len as _);
-            *ptr.add(len - 1) = 0;
-            Ok(ptr)
-        } else {
-            Err(crate::types::SQLITE_NOMEM)
-        }
-    }
+    Ok(SqliteBuffer::from_str(val)?.into_raw() as _)
 }
 
+/// Convert the error into an SQLite result code, and store its message in `msg`, which may
+/// be null. Any message already stored in `msg` must have been allocated by SQLite, and is
+/// freed.
 pub unsafe fn handle_error(err: impl Into<Error>, msg: *mut *mut c_char) -> c_int {
     err.into().into_sqlite(msg)
 }
@@ -225,6 +248,14 @@ pub unsafe fn handle_result(result: Result<(), Error>, msg: *mut *mut c_char) ->
     }
 }
 
+/// Write a warning to the SQLite error log (see SQLITE_CONFIG_LOG). Messages containing a
+/// nul byte are not logged.
+pub(crate) fn log_warning(msg: &str) {
+    if let Ok(msg) = CString::new(msg) {
+        unsafe { sqlite3_log()(SQLITE_WARNING, c"%s".as_ptr(), msg.as_ptr()) };
+    }
+}
+
 pub fn is_version(min: c_int) -> bool {
     let found = unsafe { sqlite3_libversion_number() };
     found >= min
@@ -244,7 +275,7 @@ pub unsafe extern "C" fn drop_blob(data: *mut c_void) {
 
 #[cfg(test)]
 mod test {
-    use crate::sqlite3_match_version;
+    use super::*;
 
     fn test_patterns() {
         let s = sqlite3_match_version! {
@@ -260,4 +291,24 @@ mod test {
         };
         assert_eq!(s, "expr,");
     }
+
+    #[test]
+    fn api_routines_layout() {
+        assert_eq!(
+            std::mem::size_of::<sqlite3_api_routines>(),
+            API_VERSIONS.len() * std::mem::size_of::<usize>()
+        );
+        assert!(API_VERSIONS.contains(&("sqlite3_error_offset", 3_038_000)));
+    }
+
+    #[test]
+    #[cfg(feature = "static")]
+    fn available_functions() {
+        assert!(available("sqlite3_libversion"));
+        assert!(!available("sqlite3_not_a_function"));
+        assert_eq!(
+            available("sqlite3_error_offset"),
+            cfg!(modern_sqlite) && is_version(3_038_000)
+        );
+    }
 }
@@ -1,1702 +0,0 @@
-/* automatically generated by rust-bindgen 0.64.0 */
-
-#![allow(non_snake_case)]
-#![allow(dead_code)]
-#![allow(non_camel_case_types)]
-use super::sqlite3types::*;
-
-extern "C" {
-    pub fn sqlite3_libversion() -> *const ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_sourceid() -> *const ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_libversion_number() -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_compileoption_used(
-        zOptName: *const ::std::os::raw::c_char,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_compileoption_get(N: ::std::os::raw::c_int) -> *const ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_threadsafe() -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_close(arg1: *mut sqlite3) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_close_v2(arg1: *mut sqlite3) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_exec(
-        arg1: *mut sqlite3,
-        sql: *const ::std::os::raw::c_char,
-        callback: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut ::std::os::raw::c_void,
-                arg2: ::std::os::raw::c_int,
-                arg3: *mut *mut ::std::os::raw::c_char,
-                arg4: *mut *mut ::std::os::raw::c_char,
-            ) -> ::std::os::raw::c_int,
-        >,
-        arg2: *mut ::std::os::raw::c_void,
-        errmsg: *mut *mut ::std::os::raw::c_char,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_initialize() -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_shutdown() -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_os_init() -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_os_end() -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_config(arg1: ::std::os::raw::c_int, ...) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_db_config(
-        arg1: *mut sqlite3,
-        op: ::std::os::raw::c_int,
-        ...
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_extended_result_codes(
-        arg1: *mut sqlite3,
-        onoff: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_last_insert_rowid(arg1: *mut sqlite3) -> sqlite3_int64;
-}
-extern "C" {
-    pub fn sqlite3_set_last_insert_rowid(arg1: *mut sqlite3, arg2: sqlite3_int64);
-}
-extern "C" {
-    pub fn sqlite3_changes(arg1: *mut sqlite3) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_changes64(arg1: *mut sqlite3) -> sqlite3_int64;
-}
-extern "C" {
-    pub fn sqlite3_total_changes(arg1: *mut sqlite3) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_total_changes64(arg1: *mut sqlite3) -> sqlite3_int64;
-}
-extern "C" {
-    pub fn sqlite3_interrupt(arg1: *mut sqlite3);
-}
-extern "C" {
-    pub fn sqlite3_complete(sql: *const ::std::os::raw::c_char) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_complete16(sql: *const ::std::os::raw::c_void) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_busy_handler(
-        arg1: *mut sqlite3,
-        arg2: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut ::std::os::raw::c_void,
-                arg2: ::std::os::raw::c_int,
-            ) -> ::std::os::raw::c_int,
-        >,
-        arg3: *mut ::std::os::raw::c_void,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_busy_timeout(
-        arg1: *mut sqlite3,
-        ms: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_get_table(
-        db: *mut sqlite3,
-        zSql: *const ::std::os::raw::c_char,
-        pazResult: *mut *mut *mut ::std::os::raw::c_char,
-        pnRow: *mut ::std::os::raw::c_int,
-        pnColumn: *mut ::std::os::raw::c_int,
-        pzErrmsg: *mut *mut ::std::os::raw::c_char,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_free_table(result: *mut *mut ::std::os::raw::c_char);
-}
-extern "C" {
-    pub fn sqlite3_mprintf(arg1: *const ::std::os::raw::c_char, ...)
-        -> *mut ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_vmprintf(
-        arg1: *const ::std::os::raw::c_char,
-        arg2: va_list,
-    ) -> *mut ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_snprintf(
-        arg1: ::std::os::raw::c_int,
-        arg2: *mut ::std::os::raw::c_char,
-        arg3: *const ::std::os::raw::c_char,
-        ...
-    ) -> *mut ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_vsnprintf(
-        arg1: ::std::os::raw::c_int,
-        arg2: *mut ::std::os::raw::c_char,
-        arg3: *const ::std::os::raw::c_char,
-        arg4: va_list,
-    ) -> *mut ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_malloc(arg1: ::std::os::raw::c_int) -> *mut ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_malloc64(arg1: sqlite3_uint64) -> *mut ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_realloc(
-        arg1: *mut ::std::os::raw::c_void,
-        arg2: ::std::os::raw::c_int,
-    ) -> *mut ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_realloc64(
-        arg1: *mut ::std::os::raw::c_void,
-        arg2: sqlite3_uint64,
-    ) -> *mut ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_free(arg1: *mut ::std::os::raw::c_void);
-}
-extern "C" {
-    pub fn sqlite3_msize(arg1: *mut ::std::os::raw::c_void) -> sqlite3_uint64;
-}
-extern "C" {
-    pub fn sqlite3_memory_used() -> sqlite3_int64;
-}
-extern "C" {
-    pub fn sqlite3_memory_highwater(resetFlag: ::std::os::raw::c_int) -> sqlite3_int64;
-}
-extern "C" {
-    pub fn sqlite3_randomness(N: ::std::os::raw::c_int, P: *mut ::std::os::raw::c_void);
-}
-extern "C" {
-    pub fn sqlite3_set_authorizer(
-        arg1: *mut sqlite3,
-        xAuth: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut ::std::os::raw::c_void,
-                arg2: ::std::os::raw::c_int,
-                arg3: *const ::std::os::raw::c_char,
-                arg4: *const ::std::os::raw::c_char,
-                arg5: *const ::std::os::raw::c_char,
-                arg6: *const ::std::os::raw::c_char,
-            ) -> ::std::os::raw::c_int,
-        >,
-        pUserData: *mut ::std::os::raw::c_void,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_trace(
-        arg1: *mut sqlite3,
-        xTrace: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut ::std::os::raw::c_void,
-                arg2: *const ::std::os::raw::c_char,
-            ),
-        >,
-        arg2: *mut ::std::os::raw::c_void,
-    ) -> *mut ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_profile(
-        arg1: *mut sqlite3,
-        xProfile: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut ::std::os::raw::c_void,
-                arg2: *const ::std::os::raw::c_char,
-                arg3: sqlite3_uint64,
-            ),
-        >,
-        arg2: *mut ::std::os::raw::c_void,
-    ) -> *mut ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_trace_v2(
-        arg1: *mut sqlite3,
-        uMask: ::std::os::raw::c_uint,
-        xCallback: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: ::std::os::raw::c_uint,
-                arg2: *mut ::std::os::raw::c_void,
-                arg3: *mut ::std::os::raw::c_void,
-                arg4: *mut ::std::os::raw::c_void,
-            ) -> ::std::os::raw::c_int,
-        >,
-        pCtx: *mut ::std::os::raw::c_void,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_progress_handler(
-        arg1: *mut sqlite3,
-        arg2: ::std::os::raw::c_int,
-        arg3: ::std::option::Option<
-            unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void) -> ::std::os::raw::c_int,
-        >,
-        arg4: *mut ::std::os::raw::c_void,
-    );
-}
-extern "C" {
-    pub fn sqlite3_open(
-        filename: *const ::std::os::raw::c_char,
-        ppDb: *mut *mut sqlite3,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_open16(
-        filename: *const ::std::os::raw::c_void,
-        ppDb: *mut *mut sqlite3,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_open_v2(
-        filename: *const ::std::os::raw::c_char,
-        ppDb: *mut *mut sqlite3,
-        flags: ::std::os::raw::c_int,
-        zVfs: *const ::std::os::raw::c_char,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_uri_parameter(
-        zFilename: *const ::std::os::raw::c_char,
-        zParam: *const ::std::os::raw::c_char,
-    ) -> *const ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_uri_boolean(
-        zFile: *const ::std::os::raw::c_char,
-        zParam: *const ::std::os::raw::c_char,
-        bDefault: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_uri_int64(
-        arg1: *const ::std::os::raw::c_char,
-        arg2: *const ::std::os::raw::c_char,
-        arg3: sqlite3_int64,
-    ) -> sqlite3_int64;
-}
-extern "C" {
-    pub fn sqlite3_uri_key(
-        zFilename: *const ::std::os::raw::c_char,
-        N: ::std::os::raw::c_int,
-    ) -> *const ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_filename_database(
-        arg1: *const ::std::os::raw::c_char,
-    ) -> *const ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_filename_journal(
-        arg1: *const ::std::os::raw::c_char,
-    ) -> *const ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_filename_wal(
-        arg1: *const ::std::os::raw::c_char,
-    ) -> *const ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_database_file_object(arg1: *const ::std::os::raw::c_char) -> *mut sqlite3_file;
-}
-extern "C" {
-    pub fn sqlite3_create_filename(
-        zDatabase: *const ::std::os::raw::c_char,
-        zJournal: *const ::std::os::raw::c_char,
-        zWal: *const ::std::os::raw::c_char,
-        nParam: ::std::os::raw::c_int,
-        azParam: *mut *const ::std::os::raw::c_char,
-    ) -> *mut ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_free_filename(arg1: *mut ::std::os::raw::c_char);
-}
-extern "C" {
-    pub fn sqlite3_errcode(db: *mut sqlite3) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_extended_errcode(db: *mut sqlite3) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_errmsg(arg1: *mut sqlite3) -> *const ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_errmsg16(arg1: *mut sqlite3) -> *const ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_errstr(arg1: ::std::os::raw::c_int) -> *const ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_error_offset(db: *mut sqlite3) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_limit(
-        arg1: *mut sqlite3,
-        id: ::std::os::raw::c_int,
-        newVal: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_prepare(
-        db: *mut sqlite3,
-        zSql: *const ::std::os::raw::c_char,
-        nByte: ::std::os::raw::c_int,
-        ppStmt: *mut *mut sqlite3_stmt,
-        pzTail: *mut *const ::std::os::raw::c_char,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_prepare_v2(
-        db: *mut sqlite3,
-        zSql: *const ::std::os::raw::c_char,
-        nByte: ::std::os::raw::c_int,
-        ppStmt: *mut *mut sqlite3_stmt,
-        pzTail: *mut *const ::std::os::raw::c_char,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_prepare_v3(
-        db: *mut sqlite3,
-        zSql: *const ::std::os::raw::c_char,
-        nByte: ::std::os::raw::c_int,
-        prepFlags: ::std::os::raw::c_uint,
-        ppStmt: *mut *mut sqlite3_stmt,
-        pzTail: *mut *const ::std::os::raw::c_char,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_prepare16(
-        db: *mut sqlite3,
-        zSql: *const ::std::os::raw::c_void,
-        nByte: ::std::os::raw::c_int,
-        ppStmt: *mut *mut sqlite3_stmt,
-        pzTail: *mut *const ::std::os::raw::c_void,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_prepare16_v2(
-        db: *mut sqlite3,
-        zSql: *const ::std::os::raw::c_void,
-        nByte: ::std::os::raw::c_int,
-        ppStmt: *mut *mut sqlite3_stmt,
-        pzTail: *mut *const ::std::os::raw::c_void,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_prepare16_v3(
-        db: *mut sqlite3,
-        zSql: *const ::std::os::raw::c_void,
-        nByte: ::std::os::raw::c_int,
-        prepFlags: ::std::os::raw::c_uint,
-        ppStmt: *mut *mut sqlite3_stmt,
-        pzTail: *mut *const ::std::os::raw::c_void,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_sql(pStmt: *mut sqlite3_stmt) -> *const ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_expanded_sql(pStmt: *mut sqlite3_stmt) -> *mut ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_stmt_readonly(pStmt: *mut sqlite3_stmt) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_stmt_isexplain(pStmt: *mut sqlite3_stmt) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_stmt_busy(arg1: *mut sqlite3_stmt) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_bind_blob(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-        arg3: *const ::std::os::raw::c_void,
-        n: ::std::os::raw::c_int,
-        arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_bind_blob64(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-        arg3: *const ::std::os::raw::c_void,
-        arg4: sqlite3_uint64,
-        arg5: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_bind_double(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-        arg3: f64,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_bind_int(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-        arg3: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_bind_int64(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-        arg3: sqlite3_int64,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_bind_null(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_bind_text(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-        arg3: *const ::std::os::raw::c_char,
-        arg4: ::std::os::raw::c_int,
-        arg5: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_bind_text16(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-        arg3: *const ::std::os::raw::c_void,
-        arg4: ::std::os::raw::c_int,
-        arg5: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_bind_text64(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-        arg3: *const ::std::os::raw::c_char,
-        arg4: sqlite3_uint64,
-        arg5: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        encoding: ::std::os::raw::c_uchar,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_bind_value(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-        arg3: *const sqlite3_value,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_bind_pointer(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-        arg3: *mut ::std::os::raw::c_void,
-        arg4: *const ::std::os::raw::c_char,
-        arg5: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_bind_zeroblob(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-        n: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_bind_zeroblob64(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-        arg3: sqlite3_uint64,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_bind_parameter_count(arg1: *mut sqlite3_stmt) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_bind_parameter_name(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-    ) -> *const ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_bind_parameter_index(
-        arg1: *mut sqlite3_stmt,
-        zName: *const ::std::os::raw::c_char,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_clear_bindings(arg1: *mut sqlite3_stmt) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_column_count(pStmt: *mut sqlite3_stmt) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_column_name(
-        arg1: *mut sqlite3_stmt,
-        N: ::std::os::raw::c_int,
-    ) -> *const ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_column_name16(
-        arg1: *mut sqlite3_stmt,
-        N: ::std::os::raw::c_int,
-    ) -> *const ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_column_database_name(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-    ) -> *const ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_column_database_name16(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-    ) -> *const ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_column_table_name(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-    ) -> *const ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_column_table_name16(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-    ) -> *const ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_column_origin_name(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-    ) -> *const ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_column_origin_name16(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-    ) -> *const ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_column_decltype(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-    ) -> *const ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_column_decltype16(
-        arg1: *mut sqlite3_stmt,
-        arg2: ::std::os::raw::c_int,
-    ) -> *const ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_step(arg1: *mut sqlite3_stmt) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_data_count(pStmt: *mut sqlite3_stmt) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_column_blob(
-        arg1: *mut sqlite3_stmt,
-        iCol: ::std::os::raw::c_int,
-    ) -> *const ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_column_double(arg1: *mut sqlite3_stmt, iCol: ::std::os::raw::c_int) -> f64;
-}
-extern "C" {
-    pub fn sqlite3_column_int(
-        arg1: *mut sqlite3_stmt,
-        iCol: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_column_int64(
-        arg1: *mut sqlite3_stmt,
-        iCol: ::std::os::raw::c_int,
-    ) -> sqlite3_int64;
-}
-extern "C" {
-    pub fn sqlite3_column_text(
-        arg1: *mut sqlite3_stmt,
-        iCol: ::std::os::raw::c_int,
-    ) -> *const ::std::os::raw::c_uchar;
-}
-extern "C" {
-    pub fn sqlite3_column_text16(
-        arg1: *mut sqlite3_stmt,
-        iCol: ::std::os::raw::c_int,
-    ) -> *const ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_column_value(
-        arg1: *mut sqlite3_stmt,
-        iCol: ::std::os::raw::c_int,
-    ) -> *mut sqlite3_value;
-}
-extern "C" {
-    pub fn sqlite3_column_bytes(
-        arg1: *mut sqlite3_stmt,
-        iCol: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_column_bytes16(
-        arg1: *mut sqlite3_stmt,
-        iCol: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_column_type(
-        arg1: *mut sqlite3_stmt,
-        iCol: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_finalize(pStmt: *mut sqlite3_stmt) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_reset(pStmt: *mut sqlite3_stmt) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_create_function(
-        db: *mut sqlite3,
-        zFunctionName: *const ::std::os::raw::c_char,
-        nArg: ::std::os::raw::c_int,
-        eTextRep: ::std::os::raw::c_int,
-        pApp: *mut ::std::os::raw::c_void,
-        xFunc: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut sqlite3_context,
-                arg2: ::std::os::raw::c_int,
-                arg3: *mut *mut sqlite3_value,
-            ),
-        >,
-        xStep: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut sqlite3_context,
-                arg2: ::std::os::raw::c_int,
-                arg3: *mut *mut sqlite3_value,
-            ),
-        >,
-        xFinal: ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_context)>,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_create_function16(
-        db: *mut sqlite3,
-        zFunctionName: *const ::std::os::raw::c_void,
-        nArg: ::std::os::raw::c_int,
-        eTextRep: ::std::os::raw::c_int,
-        pApp: *mut ::std::os::raw::c_void,
-        xFunc: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut sqlite3_context,
-                arg2: ::std::os::raw::c_int,
-                arg3: *mut *mut sqlite3_value,
-            ),
-        >,
-        xStep: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut sqlite3_context,
-                arg2: ::std::os::raw::c_int,
-                arg3: *mut *mut sqlite3_value,
-            ),
-        >,
-        xFinal: ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_context)>,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_create_function_v2(
-        db: *mut sqlite3,
-        zFunctionName: *const ::std::os::raw::c_char,
-        nArg: ::std::os::raw::c_int,
-        eTextRep: ::std::os::raw::c_int,
-        pApp: *mut ::std::os::raw::c_void,
-        xFunc: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut sqlite3_context,
-                arg2: ::std::os::raw::c_int,
-                arg3: *mut *mut sqlite3_value,
-            ),
-        >,
-        xStep: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut sqlite3_context,
-                arg2: ::std::os::raw::c_int,
-                arg3: *mut *mut sqlite3_value,
-            ),
-        >,
-        xFinal: ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_context)>,
-        xDestroy: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_create_window_function(
-        db: *mut sqlite3,
-        zFunctionName: *const ::std::os::raw::c_char,
-        nArg: ::std::os::raw::c_int,
-        eTextRep: ::std::os::raw::c_int,
-        pApp: *mut ::std::os::raw::c_void,
-        xStep: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut sqlite3_context,
-                arg2: ::std::os::raw::c_int,
-                arg3: *mut *mut sqlite3_value,
-            ),
-        >,
-        xFinal: ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_context)>,
-        xValue: ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_context)>,
-        xInverse: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut sqlite3_context,
-                arg2: ::std::os::raw::c_int,
-                arg3: *mut *mut sqlite3_value,
-            ),
-        >,
-        xDestroy: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_aggregate_count(arg1: *mut sqlite3_context) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_expired(arg1: *mut sqlite3_stmt) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_transfer_bindings(
-        arg1: *mut sqlite3_stmt,
-        arg2: *mut sqlite3_stmt,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_global_recover() -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_thread_cleanup();
-}
-extern "C" {
-    pub fn sqlite3_memory_alarm(
-        arg1: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut ::std::os::raw::c_void,
-                arg2: sqlite3_int64,
-                arg3: ::std::os::raw::c_int,
-            ),
-        >,
-        arg2: *mut ::std::os::raw::c_void,
-        arg3: sqlite3_int64,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_value_blob(arg1: *mut sqlite3_value) -> *const ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_value_double(arg1: *mut sqlite3_value) -> f64;
-}
-extern "C" {
-    pub fn sqlite3_value_int(arg1: *mut sqlite3_value) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_value_int64(arg1: *mut sqlite3_value) -> sqlite3_int64;
-}
-extern "C" {
-    pub fn sqlite3_value_pointer(
-        arg1: *mut sqlite3_value,
-        arg2: *const ::std::os::raw::c_char,
-    ) -> *mut ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_value_text(arg1: *mut sqlite3_value) -> *const ::std::os::raw::c_uchar;
-}
-extern "C" {
-    pub fn sqlite3_value_text16(arg1: *mut sqlite3_value) -> *const ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_value_text16le(arg1: *mut sqlite3_value) -> *const ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_value_text16be(arg1: *mut sqlite3_value) -> *const ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_value_bytes(arg1: *mut sqlite3_value) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_value_bytes16(arg1: *mut sqlite3_value) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_value_type(arg1: *mut sqlite3_value) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_value_numeric_type(arg1: *mut sqlite3_value) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_value_nochange(arg1: *mut sqlite3_value) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_value_frombind(arg1: *mut sqlite3_value) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_value_subtype(arg1: *mut sqlite3_value) -> ::std::os::raw::c_uint;
-}
-extern "C" {
-    pub fn sqlite3_value_dup(arg1: *const sqlite3_value) -> *mut sqlite3_value;
-}
-extern "C" {
-    pub fn sqlite3_value_free(arg1: *mut sqlite3_value);
-}
-extern "C" {
-    pub fn sqlite3_aggregate_context(
-        arg1: *mut sqlite3_context,
-        nBytes: ::std::os::raw::c_int,
-    ) -> *mut ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_user_data(arg1: *mut sqlite3_context) -> *mut ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_context_db_handle(arg1: *mut sqlite3_context) -> *mut sqlite3;
-}
-extern "C" {
-    pub fn sqlite3_get_auxdata(
-        arg1: *mut sqlite3_context,
-        N: ::std::os::raw::c_int,
-    ) -> *mut ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_set_auxdata(
-        arg1: *mut sqlite3_context,
-        N: ::std::os::raw::c_int,
-        arg2: *mut ::std::os::raw::c_void,
-        arg3: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-    );
-}
-extern "C" {
-    pub fn sqlite3_result_blob(
-        arg1: *mut sqlite3_context,
-        arg2: *const ::std::os::raw::c_void,
-        arg3: ::std::os::raw::c_int,
-        arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-    );
-}
-extern "C" {
-    pub fn sqlite3_result_blob64(
-        arg1: *mut sqlite3_context,
-        arg2: *const ::std::os::raw::c_void,
-        arg3: sqlite3_uint64,
-        arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-    );
-}
-extern "C" {
-    pub fn sqlite3_result_double(arg1: *mut sqlite3_context, arg2: f64);
-}
-extern "C" {
-    pub fn sqlite3_result_error(
-        arg1: *mut sqlite3_context,
-        arg2: *const ::std::os::raw::c_char,
-        arg3: ::std::os::raw::c_int,
-    );
-}
-extern "C" {
-    pub fn sqlite3_result_error16(
-        arg1: *mut sqlite3_context,
-        arg2: *const ::std::os::raw::c_void,
-        arg3: ::std::os::raw::c_int,
-    );
-}
-extern "C" {
-    pub fn sqlite3_result_error_toobig(arg1: *mut sqlite3_context);
-}
-extern "C" {
-    pub fn sqlite3_result_error_nomem(arg1: *mut sqlite3_context);
-}
-extern "C" {
-    pub fn sqlite3_result_error_code(arg1: *mut sqlite3_context, arg2: ::std::os::raw::c_int);
-}
-extern "C" {
-    pub fn sqlite3_result_int(arg1: *mut sqlite3_context, arg2: ::std::os::raw::c_int);
-}
-extern "C" {
-    pub fn sqlite3_result_int64(arg1: *mut sqlite3_context, arg2: sqlite3_int64);
-}
-extern "C" {
-    pub fn sqlite3_result_null(arg1: *mut sqlite3_context);
-}
-extern "C" {
-    pub fn sqlite3_result_text(
-        arg1: *mut sqlite3_context,
-        arg2: *const ::std::os::raw::c_char,
-        arg3: ::std::os::raw::c_int,
-        arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-    );
-}
-extern "C" {
-    pub fn sqlite3_result_text64(
-        arg1: *mut sqlite3_context,
-        arg2: *const ::std::os::raw::c_char,
-        arg3: sqlite3_uint64,
-        arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        encoding: ::std::os::raw::c_uchar,
-    );
-}
-extern "C" {
-    pub fn sqlite3_result_text16(
-        arg1: *mut sqlite3_context,
-        arg2: *const ::std::os::raw::c_void,
-        arg3: ::std::os::raw::c_int,
-        arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-    );
-}
-extern "C" {
-    pub fn sqlite3_result_text16le(
-        arg1: *mut sqlite3_context,
-        arg2: *const ::std::os::raw::c_void,
-        arg3: ::std::os::raw::c_int,
-        arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-    );
-}
-extern "C" {
-    pub fn sqlite3_result_text16be(
-        arg1: *mut sqlite3_context,
-        arg2: *const ::std::os::raw::c_void,
-        arg3: ::std::os::raw::c_int,
-        arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-    );
-}
-extern "C" {
-    pub fn sqlite3_result_value(arg1: *mut sqlite3_context, arg2: *mut sqlite3_value);
-}
-extern "C" {
-    pub fn sqlite3_result_pointer(
-        arg1: *mut sqlite3_context,
-        arg2: *mut ::std::os::raw::c_void,
-        arg3: *const ::std::os::raw::c_char,
-        arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-    );
-}
-extern "C" {
-    pub fn sqlite3_result_zeroblob(arg1: *mut sqlite3_context, n: ::std::os::raw::c_int);
-}
-extern "C" {
-    pub fn sqlite3_result_zeroblob64(
-        arg1: *mut sqlite3_context,
-        n: sqlite3_uint64,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_result_subtype(arg1: *mut sqlite3_context, arg2: ::std::os::raw::c_uint);
-}
-extern "C" {
-    pub fn sqlite3_create_collation(
-        arg1: *mut sqlite3,
-        zName: *const ::std::os::raw::c_char,
-        eTextRep: ::std::os::raw::c_int,
-        pArg: *mut ::std::os::raw::c_void,
-        xCompare: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut ::std::os::raw::c_void,
-                arg2: ::std::os::raw::c_int,
-                arg3: *const ::std::os::raw::c_void,
-                arg4: ::std::os::raw::c_int,
-                arg5: *const ::std::os::raw::c_void,
-            ) -> ::std::os::raw::c_int,
-        >,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_create_collation_v2(
-        arg1: *mut sqlite3,
-        zName: *const ::std::os::raw::c_char,
-        eTextRep: ::std::os::raw::c_int,
-        pArg: *mut ::std::os::raw::c_void,
-        xCompare: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut ::std::os::raw::c_void,
-                arg2: ::std::os::raw::c_int,
-                arg3: *const ::std::os::raw::c_void,
-                arg4: ::std::os::raw::c_int,
-                arg5: *const ::std::os::raw::c_void,
-            ) -> ::std::os::raw::c_int,
-        >,
-        xDestroy: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_create_collation16(
-        arg1: *mut sqlite3,
-        zName: *const ::std::os::raw::c_void,
-        eTextRep: ::std::os::raw::c_int,
-        pArg: *mut ::std::os::raw::c_void,
-        xCompare: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut ::std::os::raw::c_void,
-                arg2: ::std::os::raw::c_int,
-                arg3: *const ::std::os::raw::c_void,
-                arg4: ::std::os::raw::c_int,
-                arg5: *const ::std::os::raw::c_void,
-            ) -> ::std::os::raw::c_int,
-        >,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_collation_needed(
-        arg1: *mut sqlite3,
-        arg2: *mut ::std::os::raw::c_void,
-        arg3: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut ::std::os::raw::c_void,
-                arg2: *mut sqlite3,
-                eTextRep: ::std::os::raw::c_int,
-                arg3: *const ::std::os::raw::c_char,
-            ),
-        >,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_collation_needed16(
-        arg1: *mut sqlite3,
-        arg2: *mut ::std::os::raw::c_void,
-        arg3: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut ::std::os::raw::c_void,
-                arg2: *mut sqlite3,
-                eTextRep: ::std::os::raw::c_int,
-                arg3: *const ::std::os::raw::c_void,
-            ),
-        >,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_sleep(arg1: ::std::os::raw::c_int) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_win32_set_directory(
-        type_: ::std::os::raw::c_ulong,
-        zValue: *mut ::std::os::raw::c_void,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_win32_set_directory8(
-        type_: ::std::os::raw::c_ulong,
-        zValue: *const ::std::os::raw::c_char,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_win32_set_directory16(
-        type_: ::std::os::raw::c_ulong,
-        zValue: *const ::std::os::raw::c_void,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_get_autocommit(arg1: *mut sqlite3) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_db_handle(arg1: *mut sqlite3_stmt) -> *mut sqlite3;
-}
-extern "C" {
-    pub fn sqlite3_db_filename(
-        db: *mut sqlite3,
-        zDbName: *const ::std::os::raw::c_char,
-    ) -> *const ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_db_readonly(
-        db: *mut sqlite3,
-        zDbName: *const ::std::os::raw::c_char,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_txn_state(
-        arg1: *mut sqlite3,
-        zSchema: *const ::std::os::raw::c_char,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_next_stmt(pDb: *mut sqlite3, pStmt: *mut sqlite3_stmt) -> *mut sqlite3_stmt;
-}
-extern "C" {
-    pub fn sqlite3_commit_hook(
-        arg1: *mut sqlite3,
-        arg2: ::std::option::Option<
-            unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void) -> ::std::os::raw::c_int,
-        >,
-        arg3: *mut ::std::os::raw::c_void,
-    ) -> *mut ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_rollback_hook(
-        arg1: *mut sqlite3,
-        arg2: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        arg3: *mut ::std::os::raw::c_void,
-    ) -> *mut ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_autovacuum_pages(
-        db: *mut sqlite3,
-        arg1: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut ::std::os::raw::c_void,
-                arg2: *const ::std::os::raw::c_char,
-                arg3: ::std::os::raw::c_uint,
-                arg4: ::std::os::raw::c_uint,
-                arg5: ::std::os::raw::c_uint,
-            ) -> ::std::os::raw::c_uint,
-        >,
-        arg2: *mut ::std::os::raw::c_void,
-        arg3: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_update_hook(
-        arg1: *mut sqlite3,
-        arg2: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut ::std::os::raw::c_void,
-                arg2: ::std::os::raw::c_int,
-                arg3: *const ::std::os::raw::c_char,
-                arg4: *const ::std::os::raw::c_char,
-                arg5: sqlite3_int64,
-            ),
-        >,
-        arg3: *mut ::std::os::raw::c_void,
-    ) -> *mut ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_enable_shared_cache(arg1: ::std::os::raw::c_int) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_release_memory(arg1: ::std::os::raw::c_int) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_db_release_memory(arg1: *mut sqlite3) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_soft_heap_limit64(N: sqlite3_int64) -> sqlite3_int64;
-}
-extern "C" {
-    pub fn sqlite3_hard_heap_limit64(N: sqlite3_int64) -> sqlite3_int64;
-}
-extern "C" {
-    pub fn sqlite3_soft_heap_limit(N: ::std::os::raw::c_int);
-}
-extern "C" {
-    pub fn sqlite3_table_column_metadata(
-        db: *mut sqlite3,
-        zDbName: *const ::std::os::raw::c_char,
-        zTableName: *const ::std::os::raw::c_char,
-        zColumnName: *const ::std::os::raw::c_char,
-        pzDataType: *mut *const ::std::os::raw::c_char,
-        pzCollSeq: *mut *const ::std::os::raw::c_char,
-        pNotNull: *mut ::std::os::raw::c_int,
-        pPrimaryKey: *mut ::std::os::raw::c_int,
-        pAutoinc: *mut ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_load_extension(
-        db: *mut sqlite3,
-        zFile: *const ::std::os::raw::c_char,
-        zProc: *const ::std::os::raw::c_char,
-        pzErrMsg: *mut *mut ::std::os::raw::c_char,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_enable_load_extension(
-        db: *mut sqlite3,
-        onoff: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_auto_extension(
-        xEntryPoint: ::std::option::Option<unsafe extern "C" fn()>,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_cancel_auto_extension(
-        xEntryPoint: ::std::option::Option<unsafe extern "C" fn()>,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_reset_auto_extension();
-}
-extern "C" {
-    pub fn sqlite3_create_module(
-        db: *mut sqlite3,
-        zName: *const ::std::os::raw::c_char,
-        p: *const sqlite3_module,
-        pClientData: *mut ::std::os::raw::c_void,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_create_module_v2(
-        db: *mut sqlite3,
-        zName: *const ::std::os::raw::c_char,
-        p: *const sqlite3_module,
-        pClientData: *mut ::std::os::raw::c_void,
-        xDestroy: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_drop_modules(
-        db: *mut sqlite3,
-        azKeep: *mut *const ::std::os::raw::c_char,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_declare_vtab(
-        arg1: *mut sqlite3,
-        zSQL: *const ::std::os::raw::c_char,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_overload_function(
-        arg1: *mut sqlite3,
-        zFuncName: *const ::std::os::raw::c_char,
-        nArg: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_blob_open(
-        arg1: *mut sqlite3,
-        zDb: *const ::std::os::raw::c_char,
-        zTable: *const ::std::os::raw::c_char,
-        zColumn: *const ::std::os::raw::c_char,
-        iRow: sqlite3_int64,
-        flags: ::std::os::raw::c_int,
-        ppBlob: *mut *mut sqlite3_blob,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_blob_reopen(
-        arg1: *mut sqlite3_blob,
-        arg2: sqlite3_int64,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_blob_close(arg1: *mut sqlite3_blob) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_blob_bytes(arg1: *mut sqlite3_blob) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_blob_read(
-        arg1: *mut sqlite3_blob,
-        Z: *mut ::std::os::raw::c_void,
-        N: ::std::os::raw::c_int,
-        iOffset: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_blob_write(
-        arg1: *mut sqlite3_blob,
-        z: *const ::std::os::raw::c_void,
-        n: ::std::os::raw::c_int,
-        iOffset: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_vfs_find(zVfsName: *const ::std::os::raw::c_char) -> *mut sqlite3_vfs;
-}
-extern "C" {
-    pub fn sqlite3_vfs_register(
-        arg1: *mut sqlite3_vfs,
-        makeDflt: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_vfs_unregister(arg1: *mut sqlite3_vfs) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_mutex_alloc(arg1: ::std::os::raw::c_int) -> *mut sqlite3_mutex;
-}
-extern "C" {
-    pub fn sqlite3_mutex_free(arg1: *mut sqlite3_mutex);
-}
-extern "C" {
-    pub fn sqlite3_mutex_enter(arg1: *mut sqlite3_mutex);
-}
-extern "C" {
-    pub fn sqlite3_mutex_try(arg1: *mut sqlite3_mutex) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_mutex_leave(arg1: *mut sqlite3_mutex);
-}
-extern "C" {
-    pub fn sqlite3_mutex_held(arg1: *mut sqlite3_mutex) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_mutex_notheld(arg1: *mut sqlite3_mutex) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_db_mutex(arg1: *mut sqlite3) -> *mut sqlite3_mutex;
-}
-extern "C" {
-    pub fn sqlite3_file_control(
-        arg1: *mut sqlite3,
-        zDbName: *const ::std::os::raw::c_char,
-        op: ::std::os::raw::c_int,
-        arg2: *mut ::std::os::raw::c_void,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_test_control(op: ::std::os::raw::c_int, ...) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_keyword_count() -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_keyword_name(
-        arg1: ::std::os::raw::c_int,
-        arg2: *mut *const ::std::os::raw::c_char,
-        arg3: *mut ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_keyword_check(
-        arg1: *const ::std::os::raw::c_char,
-        arg2: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_str_new(arg1: *mut sqlite3) -> *mut sqlite3_str;
-}
-extern "C" {
-    pub fn sqlite3_str_finish(arg1: *mut sqlite3_str) -> *mut ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_str_appendf(arg1: *mut sqlite3_str, zFormat: *const ::std::os::raw::c_char, ...);
-}
-extern "C" {
-    pub fn sqlite3_str_vappendf(
-        arg1: *mut sqlite3_str,
-        zFormat: *const ::std::os::raw::c_char,
-        arg2: va_list,
-    );
-}
-extern "C" {
-    pub fn sqlite3_str_append(
-        arg1: *mut sqlite3_str,
-        zIn: *const ::std::os::raw::c_char,
-        N: ::std::os::raw::c_int,
-    );
-}
-extern "C" {
-    pub fn sqlite3_str_appendall(arg1: *mut sqlite3_str, zIn: *const ::std::os::raw::c_char);
-}
-extern "C" {
-    pub fn sqlite3_str_appendchar(
-        arg1: *mut sqlite3_str,
-        N: ::std::os::raw::c_int,
-        C: ::std::os::raw::c_char,
-    );
-}
-extern "C" {
-    pub fn sqlite3_str_reset(arg1: *mut sqlite3_str);
-}
-extern "C" {
-    pub fn sqlite3_str_errcode(arg1: *mut sqlite3_str) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_str_length(arg1: *mut sqlite3_str) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_str_value(arg1: *mut sqlite3_str) -> *mut ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_status(
-        op: ::std::os::raw::c_int,
-        pCurrent: *mut ::std::os::raw::c_int,
-        pHighwater: *mut ::std::os::raw::c_int,
-        resetFlag: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_status64(
-        op: ::std::os::raw::c_int,
-        pCurrent: *mut sqlite3_int64,
-        pHighwater: *mut sqlite3_int64,
-        resetFlag: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_db_status(
-        arg1: *mut sqlite3,
-        op: ::std::os::raw::c_int,
-        pCur: *mut ::std::os::raw::c_int,
-        pHiwtr: *mut ::std::os::raw::c_int,
-        resetFlg: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_stmt_status(
-        arg1: *mut sqlite3_stmt,
-        op: ::std::os::raw::c_int,
-        resetFlg: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_backup_init(
-        pDest: *mut sqlite3,
-        zDestName: *const ::std::os::raw::c_char,
-        pSource: *mut sqlite3,
-        zSourceName: *const ::std::os::raw::c_char,
-    ) -> *mut sqlite3_backup;
-}
-extern "C" {
-    pub fn sqlite3_backup_step(
-        p: *mut sqlite3_backup,
-        nPage: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_backup_finish(p: *mut sqlite3_backup) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_backup_remaining(p: *mut sqlite3_backup) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_backup_pagecount(p: *mut sqlite3_backup) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_unlock_notify(
-        pBlocked: *mut sqlite3,
-        xNotify: ::std::option::Option<
-            unsafe extern "C" fn(
-                apArg: *mut *mut ::std::os::raw::c_void,
-                nArg: ::std::os::raw::c_int,
-            ),
-        >,
-        pNotifyArg: *mut ::std::os::raw::c_void,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_stricmp(
-        arg1: *const ::std::os::raw::c_char,
-        arg2: *const ::std::os::raw::c_char,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_strnicmp(
-        arg1: *const ::std::os::raw::c_char,
-        arg2: *const ::std::os::raw::c_char,
-        arg3: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_strglob(
-        zGlob: *const ::std::os::raw::c_char,
-        zStr: *const ::std::os::raw::c_char,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_strlike(
-        zGlob: *const ::std::os::raw::c_char,
-        zStr: *const ::std::os::raw::c_char,
-        cEsc: ::std::os::raw::c_uint,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_log(
-        iErrCode: ::std::os::raw::c_int,
-        zFormat: *const ::std::os::raw::c_char,
-        ...
-    );
-}
-extern "C" {
-    pub fn sqlite3_wal_hook(
-        arg1: *mut sqlite3,
-        arg2: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut ::std::os::raw::c_void,
-                arg2: *mut sqlite3,
-                arg3: *const ::std::os::raw::c_char,
-                arg4: ::std::os::raw::c_int,
-            ) -> ::std::os::raw::c_int,
-        >,
-        arg3: *mut ::std::os::raw::c_void,
-    ) -> *mut ::std::os::raw::c_void;
-}
-extern "C" {
-    pub fn sqlite3_wal_autocheckpoint(
-        db: *mut sqlite3,
-        N: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_wal_checkpoint(
-        db: *mut sqlite3,
-        zDb: *const ::std::os::raw::c_char,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_wal_checkpoint_v2(
-        db: *mut sqlite3,
-        zDb: *const ::std::os::raw::c_char,
-        eMode: ::std::os::raw::c_int,
-        pnLog: *mut ::std::os::raw::c_int,
-        pnCkpt: *mut ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_vtab_config(
-        arg1: *mut sqlite3,
-        op: ::std::os::raw::c_int,
-        ...
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_vtab_on_conflict(arg1: *mut sqlite3) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_vtab_nochange(arg1: *mut sqlite3_context) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_vtab_collation(
-        arg1: *mut sqlite3_index_info,
-        arg2: ::std::os::raw::c_int,
-    ) -> *const ::std::os::raw::c_char;
-}
-extern "C" {
-    pub fn sqlite3_vtab_distinct(arg1: *mut sqlite3_index_info) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_vtab_in(
-        arg1: *mut sqlite3_index_info,
-        iCons: ::std::os::raw::c_int,
-        bHandle: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_vtab_in_first(
-        pVal: *mut sqlite3_value,
-        ppOut: *mut *mut sqlite3_value,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_vtab_in_next(
-        pVal: *mut sqlite3_value,
-        ppOut: *mut *mut sqlite3_value,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_vtab_rhs_value(
-        arg1: *mut sqlite3_index_info,
-        arg2: ::std::os::raw::c_int,
-        ppVal: *mut *mut sqlite3_value,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_stmt_scanstatus(
-        pStmt: *mut sqlite3_stmt,
-        idx: ::std::os::raw::c_int,
-        iScanStatusOp: ::std::os::raw::c_int,
-        pOut: *mut ::std::os::raw::c_void,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_stmt_scanstatus_reset(arg1: *mut sqlite3_stmt);
-}
-extern "C" {
-    pub fn sqlite3_db_cacheflush(arg1: *mut sqlite3) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_system_errno(arg1: *mut sqlite3) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_snapshot_get(
-        db: *mut sqlite3,
-        zSchema: *const ::std::os::raw::c_char,
-        ppSnapshot: *mut *mut sqlite3_snapshot,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_snapshot_open(
-        db: *mut sqlite3,
-        zSchema: *const ::std::os::raw::c_char,
-        pSnapshot: *mut sqlite3_snapshot,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_snapshot_free(arg1: *mut sqlite3_snapshot);
-}
-extern "C" {
-    pub fn sqlite3_snapshot_cmp(
-        p1: *mut sqlite3_snapshot,
-        p2: *mut sqlite3_snapshot,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_snapshot_recover(
-        db: *mut sqlite3,
-        zDb: *const ::std::os::raw::c_char,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_serialize(
-        db: *mut sqlite3,
-        zSchema: *const ::std::os::raw::c_char,
-        piSize: *mut sqlite3_int64,
-        mFlags: ::std::os::raw::c_uint,
-    ) -> *mut ::std::os::raw::c_uchar;
-}
-extern "C" {
-    pub fn sqlite3_deserialize(
-        db: *mut sqlite3,
-        zSchema: *const ::std::os::raw::c_char,
-        pData: *mut ::std::os::raw::c_uchar,
-        szDb: sqlite3_int64,
-        szBuf: sqlite3_int64,
-        mFlags: ::std::os::raw::c_uint,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_rtree_geometry_callback(
-        db: *mut sqlite3,
-        zGeom: *const ::std::os::raw::c_char,
-        xGeom: ::std::option::Option<
-            unsafe extern "C" fn(
-                arg1: *mut sqlite3_rtree_geometry,
-                arg2: ::std::os::raw::c_int,
-                arg3: *mut sqlite3_rtree_dbl,
-                arg4: *mut ::std::os::raw::c_int,
-            ) -> ::std::os::raw::c_int,
-        >,
-        pContext: *mut ::std::os::raw::c_void,
-    ) -> ::std::os::raw::c_int;
-}
-extern "C" {
-    pub fn sqlite3_rtree_query_callback(
-        db: *mut sqlite3,
-        zQueryFunc: *const ::std::os::raw::c_char,
-        xQueryFunc: ::std::option::Option<
-            unsafe extern "C" fn(arg1: *mut sqlite3_rtree_query_info) -> ::std::os::raw::c_int,
-        >,
-        pContext: *mut ::std::os::raw::c_void,
-        xDestructor: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-    ) -> ::std::os::raw::c_int;
-}
@@ -4,6 +4,9 @@
 #![allow(dead_code)]
 #![allow(non_camel_case_types)]
 
+// sqlite3_api_routines is generated from the list of functions in api.rs.
+use super::sqlite3_api_routines;
+
 pub const SQLITE_VERSION: &[u8; 7usize] = b"3.38.5\0";
 pub const SQLITE_VERSION_NUMBER: i32 = 3038005;
 pub const SQLITE_SOURCE_ID: &[u8; 85usize] =
@@ -1466,6 +1469,15 @@ pub struct sqlite3_module {
     pub xShadowName: ::std::option::Option<
         unsafe extern "C" fn(arg1: *const ::std::os::raw::c_char) -> ::std::os::raw::c_int,
     >,
+    pub xIntegrity: ::std::option::Option<
+        unsafe extern "C" fn(
+            pVTab: *mut sqlite3_vtab,
+            zSchema: *const ::std::os::raw::c_char,
+            zTabName: *const ::std::os::raw::c_char,
+            mFlags: ::std::os::raw::c_int,
+            pzErr: *mut *mut ::std::os::raw::c_char,
+        ) -> ::std::os::raw::c_int,
+    >,
 }
 #[test]
 fn bindgen_test_layout_sqlite3_module() {
@@ -1473,7 +1485,7 @@ fn bindgen_test_layout_sqlite3_module() {
     let ptr = UNINIT.as_ptr();
     assert_eq!(
         ::std::mem::size_of::<sqlite3_module>(),
-        192usize,
+        200usize,
         concat!("Size of: ", stringify!(sqlite3_module))
     );
     assert_eq!(
@@ -1721,6 +1733,16 @@ fn bindgen_test_layout_sqlite3_module() {
             stringify!(xShadowName)
         )
     );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).xIntegrity) as usize - ptr as usize },
+        192usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sqlite3_module),
+            "::",
+            stringify!(xIntegrity)
+        )
+    );
 }
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
@@ -3571,4108 +3593,6 @@ fn bindgen_test_layout_fts5_api() {
         )
     );
 }
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-pub struct sqlite3_api_routines {
-    pub aggregate_context: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_context,
-            nBytes: ::std::os::raw::c_int,
-        ) -> *mut ::std::os::raw::c_void,
-    >,
-    pub aggregate_count: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_context) -> ::std::os::raw::c_int,
-    >,
-    pub bind_blob: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-            arg3: *const ::std::os::raw::c_void,
-            n: ::std::os::raw::c_int,
-            arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub bind_double: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-            arg3: f64,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub bind_int: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-            arg3: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub bind_int64: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-            arg3: sqlite_int64,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub bind_null: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub bind_parameter_count: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_stmt) -> ::std::os::raw::c_int,
-    >,
-    pub bind_parameter_index: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            zName: *const ::std::os::raw::c_char,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub bind_parameter_name: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-        ) -> *const ::std::os::raw::c_char,
-    >,
-    pub bind_text: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-            arg3: *const ::std::os::raw::c_char,
-            n: ::std::os::raw::c_int,
-            arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub bind_text16: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-            arg3: *const ::std::os::raw::c_void,
-            arg4: ::std::os::raw::c_int,
-            arg5: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub bind_value: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-            arg3: *const sqlite3_value,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub busy_handler: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut ::std::os::raw::c_void,
-                    arg2: ::std::os::raw::c_int,
-                ) -> ::std::os::raw::c_int,
-            >,
-            arg3: *mut ::std::os::raw::c_void,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub busy_timeout: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            ms: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub changes:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3) -> ::std::os::raw::c_int>,
-    pub close:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3) -> ::std::os::raw::c_int>,
-    pub collation_needed: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *mut ::std::os::raw::c_void,
-            arg3: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut ::std::os::raw::c_void,
-                    arg2: *mut sqlite3,
-                    eTextRep: ::std::os::raw::c_int,
-                    arg3: *const ::std::os::raw::c_char,
-                ),
-            >,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub collation_needed16: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *mut ::std::os::raw::c_void,
-            arg3: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut ::std::os::raw::c_void,
-                    arg2: *mut sqlite3,
-                    eTextRep: ::std::os::raw::c_int,
-                    arg3: *const ::std::os::raw::c_void,
-                ),
-            >,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub column_blob: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            iCol: ::std::os::raw::c_int,
-        ) -> *const ::std::os::raw::c_void,
-    >,
-    pub column_bytes: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            iCol: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub column_bytes16: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            iCol: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub column_count: ::std::option::Option<
-        unsafe extern "C" fn(pStmt: *mut sqlite3_stmt) -> ::std::os::raw::c_int,
-    >,
-    pub column_database_name: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-        ) -> *const ::std::os::raw::c_char,
-    >,
-    pub column_database_name16: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-        ) -> *const ::std::os::raw::c_void,
-    >,
-    pub column_decltype: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            i: ::std::os::raw::c_int,
-        ) -> *const ::std::os::raw::c_char,
-    >,
-    pub column_decltype16: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-        ) -> *const ::std::os::raw::c_void,
-    >,
-    pub column_double: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_stmt, iCol: ::std::os::raw::c_int) -> f64,
-    >,
-    pub column_int: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            iCol: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub column_int64: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_stmt, iCol: ::std::os::raw::c_int) -> sqlite_int64,
-    >,
-    pub column_name: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-        ) -> *const ::std::os::raw::c_char,
-    >,
-    pub column_name16: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-        ) -> *const ::std::os::raw::c_void,
-    >,
-    pub column_origin_name: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-        ) -> *const ::std::os::raw::c_char,
-    >,
-    pub column_origin_name16: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-        ) -> *const ::std::os::raw::c_void,
-    >,
-    pub column_table_name: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-        ) -> *const ::std::os::raw::c_char,
-    >,
-    pub column_table_name16: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-        ) -> *const ::std::os::raw::c_void,
-    >,
-    pub column_text: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            iCol: ::std::os::raw::c_int,
-        ) -> *const ::std::os::raw::c_uchar,
-    >,
-    pub column_text16: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            iCol: ::std::os::raw::c_int,
-        ) -> *const ::std::os::raw::c_void,
-    >,
-    pub column_type: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            iCol: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub column_value: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            iCol: ::std::os::raw::c_int,
-        ) -> *mut sqlite3_value,
-    >,
-    pub commit_hook: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: ::std::option::Option<
-                unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void) -> ::std::os::raw::c_int,
-            >,
-            arg3: *mut ::std::os::raw::c_void,
-        ) -> *mut ::std::os::raw::c_void,
-    >,
-    pub complete: ::std::option::Option<
-        unsafe extern "C" fn(sql: *const ::std::os::raw::c_char) -> ::std::os::raw::c_int,
-    >,
-    pub complete16: ::std::option::Option<
-        unsafe extern "C" fn(sql: *const ::std::os::raw::c_void) -> ::std::os::raw::c_int,
-    >,
-    pub create_collation: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: ::std::os::raw::c_int,
-            arg4: *mut ::std::os::raw::c_void,
-            arg5: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut ::std::os::raw::c_void,
-                    arg2: ::std::os::raw::c_int,
-                    arg3: *const ::std::os::raw::c_void,
-                    arg4: ::std::os::raw::c_int,
-                    arg5: *const ::std::os::raw::c_void,
-                ) -> ::std::os::raw::c_int,
-            >,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub create_collation16: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_void,
-            arg3: ::std::os::raw::c_int,
-            arg4: *mut ::std::os::raw::c_void,
-            arg5: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut ::std::os::raw::c_void,
-                    arg2: ::std::os::raw::c_int,
-                    arg3: *const ::std::os::raw::c_void,
-                    arg4: ::std::os::raw::c_int,
-                    arg5: *const ::std::os::raw::c_void,
-                ) -> ::std::os::raw::c_int,
-            >,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub create_function: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: ::std::os::raw::c_int,
-            arg4: ::std::os::raw::c_int,
-            arg5: *mut ::std::os::raw::c_void,
-            xFunc: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut sqlite3_context,
-                    arg2: ::std::os::raw::c_int,
-                    arg3: *mut *mut sqlite3_value,
-                ),
-            >,
-            xStep: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut sqlite3_context,
-                    arg2: ::std::os::raw::c_int,
-                    arg3: *mut *mut sqlite3_value,
-                ),
-            >,
-            xFinal: ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_context)>,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub create_function16: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_void,
-            arg3: ::std::os::raw::c_int,
-            arg4: ::std::os::raw::c_int,
-            arg5: *mut ::std::os::raw::c_void,
-            xFunc: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut sqlite3_context,
-                    arg2: ::std::os::raw::c_int,
-                    arg3: *mut *mut sqlite3_value,
-                ),
-            >,
-            xStep: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut sqlite3_context,
-                    arg2: ::std::os::raw::c_int,
-                    arg3: *mut *mut sqlite3_value,
-                ),
-            >,
-            xFinal: ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_context)>,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub create_module: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: *const sqlite3_module,
-            arg4: *mut ::std::os::raw::c_void,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub data_count: ::std::option::Option<
-        unsafe extern "C" fn(pStmt: *mut sqlite3_stmt) -> ::std::os::raw::c_int,
-    >,
-    pub db_handle:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_stmt) -> *mut sqlite3>,
-    pub declare_vtab: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub enable_shared_cache: ::std::option::Option<
-        unsafe extern "C" fn(arg1: ::std::os::raw::c_int) -> ::std::os::raw::c_int,
-    >,
-    pub errcode:
-        ::std::option::Option<unsafe extern "C" fn(db: *mut sqlite3) -> ::std::os::raw::c_int>,
-    pub errmsg: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3) -> *const ::std::os::raw::c_char,
-    >,
-    pub errmsg16: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3) -> *const ::std::os::raw::c_void,
-    >,
-    pub exec: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: sqlite3_callback,
-            arg4: *mut ::std::os::raw::c_void,
-            arg5: *mut *mut ::std::os::raw::c_char,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub expired: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_stmt) -> ::std::os::raw::c_int,
-    >,
-    pub finalize: ::std::option::Option<
-        unsafe extern "C" fn(pStmt: *mut sqlite3_stmt) -> ::std::os::raw::c_int,
-    >,
-    pub free: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-    pub free_table:
-        ::std::option::Option<unsafe extern "C" fn(result: *mut *mut ::std::os::raw::c_char)>,
-    pub get_autocommit:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3) -> ::std::os::raw::c_int>,
-    pub get_auxdata: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_context,
-            arg2: ::std::os::raw::c_int,
-        ) -> *mut ::std::os::raw::c_void,
-    >,
-    pub get_table: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: *mut *mut *mut ::std::os::raw::c_char,
-            arg4: *mut ::std::os::raw::c_int,
-            arg5: *mut ::std::os::raw::c_int,
-            arg6: *mut *mut ::std::os::raw::c_char,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub global_recover: ::std::option::Option<unsafe extern "C" fn() -> ::std::os::raw::c_int>,
-    pub interruptx: ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3)>,
-    pub last_insert_rowid:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3) -> sqlite_int64>,
-    pub libversion: ::std::option::Option<unsafe extern "C" fn() -> *const ::std::os::raw::c_char>,
-    pub libversion_number: ::std::option::Option<unsafe extern "C" fn() -> ::std::os::raw::c_int>,
-    pub malloc: ::std::option::Option<
-        unsafe extern "C" fn(arg1: ::std::os::raw::c_int) -> *mut ::std::os::raw::c_void,
-    >,
-    pub mprintf: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *const ::std::os::raw::c_char,
-            ...
-        ) -> *mut ::std::os::raw::c_char,
-    >,
-    pub open: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *const ::std::os::raw::c_char,
-            arg2: *mut *mut sqlite3,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub open16: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *const ::std::os::raw::c_void,
-            arg2: *mut *mut sqlite3,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub prepare: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: ::std::os::raw::c_int,
-            arg4: *mut *mut sqlite3_stmt,
-            arg5: *mut *const ::std::os::raw::c_char,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub prepare16: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_void,
-            arg3: ::std::os::raw::c_int,
-            arg4: *mut *mut sqlite3_stmt,
-            arg5: *mut *const ::std::os::raw::c_void,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub profile: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut ::std::os::raw::c_void,
-                    arg2: *const ::std::os::raw::c_char,
-                    arg3: sqlite_uint64,
-                ),
-            >,
-            arg3: *mut ::std::os::raw::c_void,
-        ) -> *mut ::std::os::raw::c_void,
-    >,
-    pub progress_handler: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: ::std::os::raw::c_int,
-            arg3: ::std::option::Option<
-                unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void) -> ::std::os::raw::c_int,
-            >,
-            arg4: *mut ::std::os::raw::c_void,
-        ),
-    >,
-    pub realloc: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut ::std::os::raw::c_void,
-            arg2: ::std::os::raw::c_int,
-        ) -> *mut ::std::os::raw::c_void,
-    >,
-    pub reset: ::std::option::Option<
-        unsafe extern "C" fn(pStmt: *mut sqlite3_stmt) -> ::std::os::raw::c_int,
-    >,
-    pub result_blob: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_context,
-            arg2: *const ::std::os::raw::c_void,
-            arg3: ::std::os::raw::c_int,
-            arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        ),
-    >,
-    pub result_double:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_context, arg2: f64)>,
-    pub result_error: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_context,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: ::std::os::raw::c_int,
-        ),
-    >,
-    pub result_error16: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_context,
-            arg2: *const ::std::os::raw::c_void,
-            arg3: ::std::os::raw::c_int,
-        ),
-    >,
-    pub result_int: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_context, arg2: ::std::os::raw::c_int),
-    >,
-    pub result_int64:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_context, arg2: sqlite_int64)>,
-    pub result_null: ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_context)>,
-    pub result_text: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_context,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: ::std::os::raw::c_int,
-            arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        ),
-    >,
-    pub result_text16: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_context,
-            arg2: *const ::std::os::raw::c_void,
-            arg3: ::std::os::raw::c_int,
-            arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        ),
-    >,
-    pub result_text16be: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_context,
-            arg2: *const ::std::os::raw::c_void,
-            arg3: ::std::os::raw::c_int,
-            arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        ),
-    >,
-    pub result_text16le: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_context,
-            arg2: *const ::std::os::raw::c_void,
-            arg3: ::std::os::raw::c_int,
-            arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        ),
-    >,
-    pub result_value: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_context, arg2: *mut sqlite3_value),
-    >,
-    pub rollback_hook: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-            arg3: *mut ::std::os::raw::c_void,
-        ) -> *mut ::std::os::raw::c_void,
-    >,
-    pub set_authorizer: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut ::std::os::raw::c_void,
-                    arg2: ::std::os::raw::c_int,
-                    arg3: *const ::std::os::raw::c_char,
-                    arg4: *const ::std::os::raw::c_char,
-                    arg5: *const ::std::os::raw::c_char,
-                    arg6: *const ::std::os::raw::c_char,
-                ) -> ::std::os::raw::c_int,
-            >,
-            arg3: *mut ::std::os::raw::c_void,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub set_auxdata: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_context,
-            arg2: ::std::os::raw::c_int,
-            arg3: *mut ::std::os::raw::c_void,
-            arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        ),
-    >,
-    pub xsnprintf: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: ::std::os::raw::c_int,
-            arg2: *mut ::std::os::raw::c_char,
-            arg3: *const ::std::os::raw::c_char,
-            ...
-        ) -> *mut ::std::os::raw::c_char,
-    >,
-    pub step: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_stmt) -> ::std::os::raw::c_int,
-    >,
-    pub table_column_metadata: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: *const ::std::os::raw::c_char,
-            arg4: *const ::std::os::raw::c_char,
-            arg5: *mut *const ::std::os::raw::c_char,
-            arg6: *mut *const ::std::os::raw::c_char,
-            arg7: *mut ::std::os::raw::c_int,
-            arg8: *mut ::std::os::raw::c_int,
-            arg9: *mut ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub thread_cleanup: ::std::option::Option<unsafe extern "C" fn()>,
-    pub total_changes:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3) -> ::std::os::raw::c_int>,
-    pub trace: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            xTrace: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut ::std::os::raw::c_void,
-                    arg2: *const ::std::os::raw::c_char,
-                ),
-            >,
-            arg2: *mut ::std::os::raw::c_void,
-        ) -> *mut ::std::os::raw::c_void,
-    >,
-    pub transfer_bindings: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: *mut sqlite3_stmt,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub update_hook: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut ::std::os::raw::c_void,
-                    arg2: ::std::os::raw::c_int,
-                    arg3: *const ::std::os::raw::c_char,
-                    arg4: *const ::std::os::raw::c_char,
-                    arg5: sqlite_int64,
-                ),
-            >,
-            arg3: *mut ::std::os::raw::c_void,
-        ) -> *mut ::std::os::raw::c_void,
-    >,
-    pub user_data: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_context) -> *mut ::std::os::raw::c_void,
-    >,
-    pub value_blob: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_value) -> *const ::std::os::raw::c_void,
-    >,
-    pub value_bytes: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_value) -> ::std::os::raw::c_int,
-    >,
-    pub value_bytes16: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_value) -> ::std::os::raw::c_int,
-    >,
-    pub value_double: ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_value) -> f64>,
-    pub value_int: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_value) -> ::std::os::raw::c_int,
-    >,
-    pub value_int64:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_value) -> sqlite_int64>,
-    pub value_numeric_type: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_value) -> ::std::os::raw::c_int,
-    >,
-    pub value_text: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_value) -> *const ::std::os::raw::c_uchar,
-    >,
-    pub value_text16: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_value) -> *const ::std::os::raw::c_void,
-    >,
-    pub value_text16be: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_value) -> *const ::std::os::raw::c_void,
-    >,
-    pub value_text16le: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_value) -> *const ::std::os::raw::c_void,
-    >,
-    pub value_type: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_value) -> ::std::os::raw::c_int,
-    >,
-    pub vmprintf: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *const ::std::os::raw::c_char,
-            arg2: va_list,
-        ) -> *mut ::std::os::raw::c_char,
-    >,
-    pub overload_function: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            zFuncName: *const ::std::os::raw::c_char,
-            nArg: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub prepare_v2: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: ::std::os::raw::c_int,
-            arg4: *mut *mut sqlite3_stmt,
-            arg5: *mut *const ::std::os::raw::c_char,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub prepare16_v2: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_void,
-            arg3: ::std::os::raw::c_int,
-            arg4: *mut *mut sqlite3_stmt,
-            arg5: *mut *const ::std::os::raw::c_void,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub clear_bindings: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_stmt) -> ::std::os::raw::c_int,
-    >,
-    pub create_module_v2: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: *const sqlite3_module,
-            arg4: *mut ::std::os::raw::c_void,
-            xDestroy: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub bind_zeroblob: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-            arg3: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub blob_bytes: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_blob) -> ::std::os::raw::c_int,
-    >,
-    pub blob_close: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_blob) -> ::std::os::raw::c_int,
-    >,
-    pub blob_open: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: *const ::std::os::raw::c_char,
-            arg4: *const ::std::os::raw::c_char,
-            arg5: sqlite3_int64,
-            arg6: ::std::os::raw::c_int,
-            arg7: *mut *mut sqlite3_blob,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub blob_read: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_blob,
-            arg2: *mut ::std::os::raw::c_void,
-            arg3: ::std::os::raw::c_int,
-            arg4: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub blob_write: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_blob,
-            arg2: *const ::std::os::raw::c_void,
-            arg3: ::std::os::raw::c_int,
-            arg4: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub create_collation_v2: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: ::std::os::raw::c_int,
-            arg4: *mut ::std::os::raw::c_void,
-            arg5: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut ::std::os::raw::c_void,
-                    arg2: ::std::os::raw::c_int,
-                    arg3: *const ::std::os::raw::c_void,
-                    arg4: ::std::os::raw::c_int,
-                    arg5: *const ::std::os::raw::c_void,
-                ) -> ::std::os::raw::c_int,
-            >,
-            arg6: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub file_control: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: ::std::os::raw::c_int,
-            arg4: *mut ::std::os::raw::c_void,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub memory_highwater:
-        ::std::option::Option<unsafe extern "C" fn(arg1: ::std::os::raw::c_int) -> sqlite3_int64>,
-    pub memory_used: ::std::option::Option<unsafe extern "C" fn() -> sqlite3_int64>,
-    pub mutex_alloc: ::std::option::Option<
-        unsafe extern "C" fn(arg1: ::std::os::raw::c_int) -> *mut sqlite3_mutex,
-    >,
-    pub mutex_enter: ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_mutex)>,
-    pub mutex_free: ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_mutex)>,
-    pub mutex_leave: ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_mutex)>,
-    pub mutex_try: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_mutex) -> ::std::os::raw::c_int,
-    >,
-    pub open_v2: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *const ::std::os::raw::c_char,
-            arg2: *mut *mut sqlite3,
-            arg3: ::std::os::raw::c_int,
-            arg4: *const ::std::os::raw::c_char,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub release_memory: ::std::option::Option<
-        unsafe extern "C" fn(arg1: ::std::os::raw::c_int) -> ::std::os::raw::c_int,
-    >,
-    pub result_error_nomem: ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_context)>,
-    pub result_error_toobig:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_context)>,
-    pub sleep: ::std::option::Option<
-        unsafe extern "C" fn(arg1: ::std::os::raw::c_int) -> ::std::os::raw::c_int,
-    >,
-    pub soft_heap_limit: ::std::option::Option<unsafe extern "C" fn(arg1: ::std::os::raw::c_int)>,
-    pub vfs_find: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *const ::std::os::raw::c_char) -> *mut sqlite3_vfs,
-    >,
-    pub vfs_register: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_vfs,
-            arg2: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub vfs_unregister: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_vfs) -> ::std::os::raw::c_int,
-    >,
-    pub xthreadsafe: ::std::option::Option<unsafe extern "C" fn() -> ::std::os::raw::c_int>,
-    pub result_zeroblob: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_context, arg2: ::std::os::raw::c_int),
-    >,
-    pub result_error_code: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_context, arg2: ::std::os::raw::c_int),
-    >,
-    pub test_control: ::std::option::Option<
-        unsafe extern "C" fn(arg1: ::std::os::raw::c_int, ...) -> ::std::os::raw::c_int,
-    >,
-    pub randomness: ::std::option::Option<
-        unsafe extern "C" fn(arg1: ::std::os::raw::c_int, arg2: *mut ::std::os::raw::c_void),
-    >,
-    pub context_db_handle:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_context) -> *mut sqlite3>,
-    pub extended_result_codes: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub limit: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: ::std::os::raw::c_int,
-            arg3: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub next_stmt: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3, arg2: *mut sqlite3_stmt) -> *mut sqlite3_stmt,
-    >,
-    pub sql: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_stmt) -> *const ::std::os::raw::c_char,
-    >,
-    pub status: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: ::std::os::raw::c_int,
-            arg2: *mut ::std::os::raw::c_int,
-            arg3: *mut ::std::os::raw::c_int,
-            arg4: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub backup_finish: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_backup) -> ::std::os::raw::c_int,
-    >,
-    pub backup_init: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: *mut sqlite3,
-            arg4: *const ::std::os::raw::c_char,
-        ) -> *mut sqlite3_backup,
-    >,
-    pub backup_pagecount: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_backup) -> ::std::os::raw::c_int,
-    >,
-    pub backup_remaining: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_backup) -> ::std::os::raw::c_int,
-    >,
-    pub backup_step: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_backup,
-            arg2: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub compileoption_get: ::std::option::Option<
-        unsafe extern "C" fn(arg1: ::std::os::raw::c_int) -> *const ::std::os::raw::c_char,
-    >,
-    pub compileoption_used: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *const ::std::os::raw::c_char) -> ::std::os::raw::c_int,
-    >,
-    pub create_function_v2: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: ::std::os::raw::c_int,
-            arg4: ::std::os::raw::c_int,
-            arg5: *mut ::std::os::raw::c_void,
-            xFunc: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut sqlite3_context,
-                    arg2: ::std::os::raw::c_int,
-                    arg3: *mut *mut sqlite3_value,
-                ),
-            >,
-            xStep: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut sqlite3_context,
-                    arg2: ::std::os::raw::c_int,
-                    arg3: *mut *mut sqlite3_value,
-                ),
-            >,
-            xFinal: ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_context)>,
-            xDestroy: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub db_config: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: ::std::os::raw::c_int,
-            ...
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub db_mutex:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3) -> *mut sqlite3_mutex>,
-    pub db_status: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: ::std::os::raw::c_int,
-            arg3: *mut ::std::os::raw::c_int,
-            arg4: *mut ::std::os::raw::c_int,
-            arg5: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub extended_errcode:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3) -> ::std::os::raw::c_int>,
-    pub log: ::std::option::Option<
-        unsafe extern "C" fn(arg1: ::std::os::raw::c_int, arg2: *const ::std::os::raw::c_char, ...),
-    >,
-    pub soft_heap_limit64:
-        ::std::option::Option<unsafe extern "C" fn(arg1: sqlite3_int64) -> sqlite3_int64>,
-    pub sourceid: ::std::option::Option<unsafe extern "C" fn() -> *const ::std::os::raw::c_char>,
-    pub stmt_status: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-            arg3: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub strnicmp: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *const ::std::os::raw::c_char,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub unlock_notify: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut *mut ::std::os::raw::c_void,
-                    arg2: ::std::os::raw::c_int,
-                ),
-            >,
-            arg3: *mut ::std::os::raw::c_void,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub wal_autocheckpoint: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub wal_checkpoint: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub wal_hook: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut ::std::os::raw::c_void,
-                    arg2: *mut sqlite3,
-                    arg3: *const ::std::os::raw::c_char,
-                    arg4: ::std::os::raw::c_int,
-                ) -> ::std::os::raw::c_int,
-            >,
-            arg3: *mut ::std::os::raw::c_void,
-        ) -> *mut ::std::os::raw::c_void,
-    >,
-    pub blob_reopen: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_blob, arg2: sqlite3_int64) -> ::std::os::raw::c_int,
-    >,
-    pub vtab_config: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            op: ::std::os::raw::c_int,
-            ...
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub vtab_on_conflict:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3) -> ::std::os::raw::c_int>,
-    pub close_v2:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3) -> ::std::os::raw::c_int>,
-    pub db_filename: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-        ) -> *const ::std::os::raw::c_char,
-    >,
-    pub db_readonly: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub db_release_memory:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3) -> ::std::os::raw::c_int>,
-    pub errstr: ::std::option::Option<
-        unsafe extern "C" fn(arg1: ::std::os::raw::c_int) -> *const ::std::os::raw::c_char,
-    >,
-    pub stmt_busy: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_stmt) -> ::std::os::raw::c_int,
-    >,
-    pub stmt_readonly: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_stmt) -> ::std::os::raw::c_int,
-    >,
-    pub stricmp: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *const ::std::os::raw::c_char,
-            arg2: *const ::std::os::raw::c_char,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub uri_boolean: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *const ::std::os::raw::c_char,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub uri_int64: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *const ::std::os::raw::c_char,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: sqlite3_int64,
-        ) -> sqlite3_int64,
-    >,
-    pub uri_parameter: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *const ::std::os::raw::c_char,
-            arg2: *const ::std::os::raw::c_char,
-        ) -> *const ::std::os::raw::c_char,
-    >,
-    pub xvsnprintf: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: ::std::os::raw::c_int,
-            arg2: *mut ::std::os::raw::c_char,
-            arg3: *const ::std::os::raw::c_char,
-            arg4: va_list,
-        ) -> *mut ::std::os::raw::c_char,
-    >,
-    pub wal_checkpoint_v2: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: ::std::os::raw::c_int,
-            arg4: *mut ::std::os::raw::c_int,
-            arg5: *mut ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub auto_extension: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: ::std::option::Option<unsafe extern "C" fn()>,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub bind_blob64: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-            arg3: *const ::std::os::raw::c_void,
-            arg4: sqlite3_uint64,
-            arg5: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub bind_text64: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-            arg3: *const ::std::os::raw::c_char,
-            arg4: sqlite3_uint64,
-            arg5: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-            arg6: ::std::os::raw::c_uchar,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub cancel_auto_extension: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: ::std::option::Option<unsafe extern "C" fn()>,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub load_extension: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: *const ::std::os::raw::c_char,
-            arg4: *mut *mut ::std::os::raw::c_char,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub malloc64: ::std::option::Option<
-        unsafe extern "C" fn(arg1: sqlite3_uint64) -> *mut ::std::os::raw::c_void,
-    >,
-    pub msize: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void) -> sqlite3_uint64,
-    >,
-    pub realloc64: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut ::std::os::raw::c_void,
-            arg2: sqlite3_uint64,
-        ) -> *mut ::std::os::raw::c_void,
-    >,
-    pub reset_auto_extension: ::std::option::Option<unsafe extern "C" fn()>,
-    pub result_blob64: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_context,
-            arg2: *const ::std::os::raw::c_void,
-            arg3: sqlite3_uint64,
-            arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        ),
-    >,
-    pub result_text64: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_context,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: sqlite3_uint64,
-            arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-            arg5: ::std::os::raw::c_uchar,
-        ),
-    >,
-    pub strglob: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *const ::std::os::raw::c_char,
-            arg2: *const ::std::os::raw::c_char,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub value_dup: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *const sqlite3_value) -> *mut sqlite3_value,
-    >,
-    pub value_free: ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_value)>,
-    pub result_zeroblob64: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_context,
-            arg2: sqlite3_uint64,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub bind_zeroblob64: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-            arg3: sqlite3_uint64,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub value_subtype: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_value) -> ::std::os::raw::c_uint,
-    >,
-    pub result_subtype: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_context, arg2: ::std::os::raw::c_uint),
-    >,
-    pub status64: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: ::std::os::raw::c_int,
-            arg2: *mut sqlite3_int64,
-            arg3: *mut sqlite3_int64,
-            arg4: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub strlike: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *const ::std::os::raw::c_char,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: ::std::os::raw::c_uint,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub db_cacheflush:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3) -> ::std::os::raw::c_int>,
-    pub system_errno:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3) -> ::std::os::raw::c_int>,
-    pub trace_v2: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: ::std::os::raw::c_uint,
-            arg3: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: ::std::os::raw::c_uint,
-                    arg2: *mut ::std::os::raw::c_void,
-                    arg3: *mut ::std::os::raw::c_void,
-                    arg4: *mut ::std::os::raw::c_void,
-                ) -> ::std::os::raw::c_int,
-            >,
-            arg4: *mut ::std::os::raw::c_void,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub expanded_sql: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_stmt) -> *mut ::std::os::raw::c_char,
-    >,
-    pub set_last_insert_rowid:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3, arg2: sqlite3_int64)>,
-    pub prepare_v3: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: ::std::os::raw::c_int,
-            arg4: ::std::os::raw::c_uint,
-            arg5: *mut *mut sqlite3_stmt,
-            arg6: *mut *const ::std::os::raw::c_char,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub prepare16_v3: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_void,
-            arg3: ::std::os::raw::c_int,
-            arg4: ::std::os::raw::c_uint,
-            arg5: *mut *mut sqlite3_stmt,
-            arg6: *mut *const ::std::os::raw::c_void,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub bind_pointer: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_stmt,
-            arg2: ::std::os::raw::c_int,
-            arg3: *mut ::std::os::raw::c_void,
-            arg4: *const ::std::os::raw::c_char,
-            arg5: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub result_pointer: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_context,
-            arg2: *mut ::std::os::raw::c_void,
-            arg3: *const ::std::os::raw::c_char,
-            arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        ),
-    >,
-    pub value_pointer: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_value,
-            arg2: *const ::std::os::raw::c_char,
-        ) -> *mut ::std::os::raw::c_void,
-    >,
-    pub vtab_nochange: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_context) -> ::std::os::raw::c_int,
-    >,
-    pub value_nochange: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_value) -> ::std::os::raw::c_int,
-    >,
-    pub vtab_collation: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_index_info,
-            arg2: ::std::os::raw::c_int,
-        ) -> *const ::std::os::raw::c_char,
-    >,
-    pub keyword_count: ::std::option::Option<unsafe extern "C" fn() -> ::std::os::raw::c_int>,
-    pub keyword_name: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: ::std::os::raw::c_int,
-            arg2: *mut *const ::std::os::raw::c_char,
-            arg3: *mut ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub keyword_check: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *const ::std::os::raw::c_char,
-            arg2: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub str_new:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3) -> *mut sqlite3_str>,
-    pub str_finish: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_str) -> *mut ::std::os::raw::c_char,
-    >,
-    pub str_appendf: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_str, zFormat: *const ::std::os::raw::c_char, ...),
-    >,
-    pub str_vappendf: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_str,
-            zFormat: *const ::std::os::raw::c_char,
-            arg2: va_list,
-        ),
-    >,
-    pub str_append: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_str,
-            zIn: *const ::std::os::raw::c_char,
-            N: ::std::os::raw::c_int,
-        ),
-    >,
-    pub str_appendall: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_str, zIn: *const ::std::os::raw::c_char),
-    >,
-    pub str_appendchar: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_str,
-            N: ::std::os::raw::c_int,
-            C: ::std::os::raw::c_char,
-        ),
-    >,
-    pub str_reset: ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_str)>,
-    pub str_errcode: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_str) -> ::std::os::raw::c_int,
-    >,
-    pub str_length: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_str) -> ::std::os::raw::c_int,
-    >,
-    pub str_value: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_str) -> *mut ::std::os::raw::c_char,
-    >,
-    pub create_window_function: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: ::std::os::raw::c_int,
-            arg4: ::std::os::raw::c_int,
-            arg5: *mut ::std::os::raw::c_void,
-            xStep: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut sqlite3_context,
-                    arg2: ::std::os::raw::c_int,
-                    arg3: *mut *mut sqlite3_value,
-                ),
-            >,
-            xFinal: ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_context)>,
-            xValue: ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3_context)>,
-            xInv: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut sqlite3_context,
-                    arg2: ::std::os::raw::c_int,
-                    arg3: *mut *mut sqlite3_value,
-                ),
-            >,
-            xDestroy: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub normalized_sql: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_stmt) -> *const ::std::os::raw::c_char,
-    >,
-    pub stmt_isexplain: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_stmt) -> ::std::os::raw::c_int,
-    >,
-    pub value_frombind: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_value) -> ::std::os::raw::c_int,
-    >,
-    pub drop_modules: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *mut *const ::std::os::raw::c_char,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub hard_heap_limit64:
-        ::std::option::Option<unsafe extern "C" fn(arg1: sqlite3_int64) -> sqlite3_int64>,
-    pub uri_key: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *const ::std::os::raw::c_char,
-            arg2: ::std::os::raw::c_int,
-        ) -> *const ::std::os::raw::c_char,
-    >,
-    pub filename_database: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *const ::std::os::raw::c_char) -> *const ::std::os::raw::c_char,
-    >,
-    pub filename_journal: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *const ::std::os::raw::c_char) -> *const ::std::os::raw::c_char,
-    >,
-    pub filename_wal: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *const ::std::os::raw::c_char) -> *const ::std::os::raw::c_char,
-    >,
-    pub create_filename: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *const ::std::os::raw::c_char,
-            arg2: *const ::std::os::raw::c_char,
-            arg3: *const ::std::os::raw::c_char,
-            arg4: ::std::os::raw::c_int,
-            arg5: *mut *const ::std::os::raw::c_char,
-        ) -> *mut ::std::os::raw::c_char,
-    >,
-    pub free_filename:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_char)>,
-    pub database_file_object: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *const ::std::os::raw::c_char) -> *mut sqlite3_file,
-    >,
-    pub txn_state: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: *const ::std::os::raw::c_char,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub changes64: ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3) -> sqlite3_int64>,
-    pub total_changes64:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3) -> sqlite3_int64>,
-    pub autovacuum_pages: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3,
-            arg2: ::std::option::Option<
-                unsafe extern "C" fn(
-                    arg1: *mut ::std::os::raw::c_void,
-                    arg2: *const ::std::os::raw::c_char,
-                    arg3: ::std::os::raw::c_uint,
-                    arg4: ::std::os::raw::c_uint,
-                    arg5: ::std::os::raw::c_uint,
-                ) -> ::std::os::raw::c_uint,
-            >,
-            arg3: *mut ::std::os::raw::c_void,
-            arg4: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub error_offset:
-        ::std::option::Option<unsafe extern "C" fn(arg1: *mut sqlite3) -> ::std::os::raw::c_int>,
-    pub vtab_rhs_value: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_index_info,
-            arg2: ::std::os::raw::c_int,
-            arg3: *mut *mut sqlite3_value,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub vtab_distinct: ::std::option::Option<
-        unsafe extern "C" fn(arg1: *mut sqlite3_index_info) -> ::std::os::raw::c_int,
-    >,
-    pub vtab_in: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_index_info,
-            arg2: ::std::os::raw::c_int,
-            arg3: ::std::os::raw::c_int,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub vtab_in_first: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_value,
-            arg2: *mut *mut sqlite3_value,
-        ) -> ::std::os::raw::c_int,
-    >,
-    pub vtab_in_next: ::std::option::Option<
-        unsafe extern "C" fn(
-            arg1: *mut sqlite3_value,
-            arg2: *mut *mut sqlite3_value,
-        ) -> ::std::os::raw::c_int,
-    >,
-}
-#[test]
-fn bindgen_test_layout_sqlite3_api_routines() {
-    const UNINIT: ::std::mem::MaybeUninit<sqlite3_api_routines> = ::std::mem::MaybeUninit::uninit();
-    let ptr = UNINIT.as_ptr();
-    assert_eq!(
-        ::std::mem::size_of::<sqlite3_api_routines>(),
-        2096usize,
-        concat!("Size of: ", stringify!(sqlite3_api_routines))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<sqlite3_api_routines>(),
-        8usize,
-        concat!("Alignment of ", stringify!(sqlite3_api_routines))
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).aggregate_context) as usize - ptr as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(aggregate_context)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).aggregate_count) as usize - ptr as usize },
-        8usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(aggregate_count)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).bind_blob) as usize - ptr as usize },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(bind_blob)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).bind_double) as usize - ptr as usize },
-        24usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(bind_double)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).bind_int) as usize - ptr as usize },
-        32usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(bind_int)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).bind_int64) as usize - ptr as usize },
-        40usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(bind_int64)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).bind_null) as usize - ptr as usize },
-        48usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(bind_null)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).bind_parameter_count) as usize - ptr as usize },
-        56usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(bind_parameter_count)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).bind_parameter_index) as usize - ptr as usize },
-        64usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(bind_parameter_index)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).bind_parameter_name) as usize - ptr as usize },
-        72usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(bind_parameter_name)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).bind_text) as usize - ptr as usize },
-        80usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(bind_text)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).bind_text16) as usize - ptr as usize },
-        88usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(bind_text16)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).bind_value) as usize - ptr as usize },
-        96usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(bind_value)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).busy_handler) as usize - ptr as usize },
-        104usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(busy_handler)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).busy_timeout) as usize - ptr as usize },
-        112usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(busy_timeout)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).changes) as usize - ptr as usize },
-        120usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(changes)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).close) as usize - ptr as usize },
-        128usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(close)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).collation_needed) as usize - ptr as usize },
-        136usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(collation_needed)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).collation_needed16) as usize - ptr as usize },
-        144usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(collation_needed16)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_blob) as usize - ptr as usize },
-        152usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_blob)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_bytes) as usize - ptr as usize },
-        160usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_bytes)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_bytes16) as usize - ptr as usize },
-        168usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_bytes16)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_count) as usize - ptr as usize },
-        176usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_count)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_database_name) as usize - ptr as usize },
-        184usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_database_name)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_database_name16) as usize - ptr as usize },
-        192usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_database_name16)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_decltype) as usize - ptr as usize },
-        200usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_decltype)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_decltype16) as usize - ptr as usize },
-        208usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_decltype16)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_double) as usize - ptr as usize },
-        216usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_double)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_int) as usize - ptr as usize },
-        224usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_int)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_int64) as usize - ptr as usize },
-        232usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_int64)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_name) as usize - ptr as usize },
-        240usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_name)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_name16) as usize - ptr as usize },
-        248usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_name16)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_origin_name) as usize - ptr as usize },
-        256usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_origin_name)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_origin_name16) as usize - ptr as usize },
-        264usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_origin_name16)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_table_name) as usize - ptr as usize },
-        272usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_table_name)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_table_name16) as usize - ptr as usize },
-        280usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_table_name16)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_text) as usize - ptr as usize },
-        288usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_text)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_text16) as usize - ptr as usize },
-        296usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_text16)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_type) as usize - ptr as usize },
-        304usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_type)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).column_value) as usize - ptr as usize },
-        312usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(column_value)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).commit_hook) as usize - ptr as usize },
-        320usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(commit_hook)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).complete) as usize - ptr as usize },
-        328usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(complete)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).complete16) as usize - ptr as usize },
-        336usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(complete16)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).create_collation) as usize - ptr as usize },
-        344usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(create_collation)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).create_collation16) as usize - ptr as usize },
-        352usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(create_collation16)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).create_function) as usize - ptr as usize },
-        360usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(create_function)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).create_function16) as usize - ptr as usize },
-        368usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(create_function16)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).create_module) as usize - ptr as usize },
-        376usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(create_module)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).data_count) as usize - ptr as usize },
-        384usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(data_count)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).db_handle) as usize - ptr as usize },
-        392usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(db_handle)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).declare_vtab) as usize - ptr as usize },
-        400usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(declare_vtab)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).enable_shared_cache) as usize - ptr as usize },
-        408usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(enable_shared_cache)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).errcode) as usize - ptr as usize },
-        416usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(errcode)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).errmsg) as usize - ptr as usize },
-        424usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(errmsg)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).errmsg16) as usize - ptr as usize },
-        432usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(errmsg16)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).exec) as usize - ptr as usize },
-        440usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(exec)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).expired) as usize - ptr as usize },
-        448usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(expired)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).finalize) as usize - ptr as usize },
-        456usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(finalize)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).free) as usize - ptr as usize },
-        464usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(free)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).free_table) as usize - ptr as usize },
-        472usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(free_table)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).get_autocommit) as usize - ptr as usize },
-        480usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(get_autocommit)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).get_auxdata) as usize - ptr as usize },
-        488usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(get_auxdata)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).get_table) as usize - ptr as usize },
-        496usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(get_table)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).global_recover) as usize - ptr as usize },
-        504usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(global_recover)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).interruptx) as usize - ptr as usize },
-        512usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(interruptx)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).last_insert_rowid) as usize - ptr as usize },
-        520usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(last_insert_rowid)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).libversion) as usize - ptr as usize },
-        528usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(libversion)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).libversion_number) as usize - ptr as usize },
-        536usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(libversion_number)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).malloc) as usize - ptr as usize },
-        544usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(malloc)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).mprintf) as usize - ptr as usize },
-        552usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(mprintf)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).open) as usize - ptr as usize },
-        560usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(open)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).open16) as usize - ptr as usize },
-        568usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(open16)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).prepare) as usize - ptr as usize },
-        576usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(prepare)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).prepare16) as usize - ptr as usize },
-        584usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(prepare16)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).profile) as usize - ptr as usize },
-        592usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(profile)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).progress_handler) as usize - ptr as usize },
-        600usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(progress_handler)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).realloc) as usize - ptr as usize },
-        608usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(realloc)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).reset) as usize - ptr as usize },
-        616usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(reset)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_blob) as usize - ptr as usize },
-        624usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_blob)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_double) as usize - ptr as usize },
-        632usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_double)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_error) as usize - ptr as usize },
-        640usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_error)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_error16) as usize - ptr as usize },
-        648usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_error16)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_int) as usize - ptr as usize },
-        656usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_int)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_int64) as usize - ptr as usize },
-        664usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_int64)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_null) as usize - ptr as usize },
-        672usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_null)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_text) as usize - ptr as usize },
-        680usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_text)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_text16) as usize - ptr as usize },
-        688usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_text16)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_text16be) as usize - ptr as usize },
-        696usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_text16be)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_text16le) as usize - ptr as usize },
-        704usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_text16le)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_value) as usize - ptr as usize },
-        712usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_value)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).rollback_hook) as usize - ptr as usize },
-        720usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(rollback_hook)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).set_authorizer) as usize - ptr as usize },
-        728usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(set_authorizer)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).set_auxdata) as usize - ptr as usize },
-        736usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(set_auxdata)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).xsnprintf) as usize - ptr as usize },
-        744usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(xsnprintf)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).step) as usize - ptr as usize },
-        752usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(step)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).table_column_metadata) as usize - ptr as usize },
-        760usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(table_column_metadata)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).thread_cleanup) as usize - ptr as usize },
-        768usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(thread_cleanup)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).total_changes) as usize - ptr as usize },
-        776usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(total_changes)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).trace) as usize - ptr as usize },
-        784usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(trace)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).transfer_bindings) as usize - ptr as usize },
-        792usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(transfer_bindings)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).update_hook) as usize - ptr as usize },
-        800usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(update_hook)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).user_data) as usize - ptr as usize },
-        808usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(user_data)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).value_blob) as usize - ptr as usize },
-        816usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(value_blob)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).value_bytes) as usize - ptr as usize },
-        824usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(value_bytes)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).value_bytes16) as usize - ptr as usize },
-        832usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(value_bytes16)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).value_double) as usize - ptr as usize },
-        840usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(value_double)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).value_int) as usize - ptr as usize },
-        848usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(value_int)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).value_int64) as usize - ptr as usize },
-        856usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(value_int64)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).value_numeric_type) as usize - ptr as usize },
-        864usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(value_numeric_type)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).value_text) as usize - ptr as usize },
-        872usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(value_text)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).value_text16) as usize - ptr as usize },
-        880usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(value_text16)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).value_text16be) as usize - ptr as usize },
-        888usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(value_text16be)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).value_text16le) as usize - ptr as usize },
-        896usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(value_text16le)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).value_type) as usize - ptr as usize },
-        904usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(value_type)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).vmprintf) as usize - ptr as usize },
-        912usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(vmprintf)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).overload_function) as usize - ptr as usize },
-        920usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(overload_function)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).prepare_v2) as usize - ptr as usize },
-        928usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(prepare_v2)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).prepare16_v2) as usize - ptr as usize },
-        936usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(prepare16_v2)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).clear_bindings) as usize - ptr as usize },
-        944usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(clear_bindings)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).create_module_v2) as usize - ptr as usize },
-        952usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(create_module_v2)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).bind_zeroblob) as usize - ptr as usize },
-        960usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(bind_zeroblob)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).blob_bytes) as usize - ptr as usize },
-        968usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(blob_bytes)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).blob_close) as usize - ptr as usize },
-        976usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(blob_close)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).blob_open) as usize - ptr as usize },
-        984usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(blob_open)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).blob_read) as usize - ptr as usize },
-        992usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(blob_read)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).blob_write) as usize - ptr as usize },
-        1000usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(blob_write)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).create_collation_v2) as usize - ptr as usize },
-        1008usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(create_collation_v2)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).file_control) as usize - ptr as usize },
-        1016usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(file_control)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).memory_highwater) as usize - ptr as usize },
-        1024usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(memory_highwater)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).memory_used) as usize - ptr as usize },
-        1032usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(memory_used)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).mutex_alloc) as usize - ptr as usize },
-        1040usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(mutex_alloc)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).mutex_enter) as usize - ptr as usize },
-        1048usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(mutex_enter)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).mutex_free) as usize - ptr as usize },
-        1056usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(mutex_free)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).mutex_leave) as usize - ptr as usize },
-        1064usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(mutex_leave)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).mutex_try) as usize - ptr as usize },
-        1072usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(mutex_try)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).open_v2) as usize - ptr as usize },
-        1080usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(open_v2)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).release_memory) as usize - ptr as usize },
-        1088usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(release_memory)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_error_nomem) as usize - ptr as usize },
-        1096usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_error_nomem)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_error_toobig) as usize - ptr as usize },
-        1104usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_error_toobig)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).sleep) as usize - ptr as usize },
-        1112usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(sleep)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).soft_heap_limit) as usize - ptr as usize },
-        1120usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(soft_heap_limit)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).vfs_find) as usize - ptr as usize },
-        1128usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(vfs_find)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).vfs_register) as usize - ptr as usize },
-        1136usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(vfs_register)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).vfs_unregister) as usize - ptr as usize },
-        1144usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(vfs_unregister)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).xthreadsafe) as usize - ptr as usize },
-        1152usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(xthreadsafe)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_zeroblob) as usize - ptr as usize },
-        1160usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_zeroblob)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_error_code) as usize - ptr as usize },
-        1168usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_error_code)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).test_control) as usize - ptr as usize },
-        1176usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(test_control)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).randomness) as usize - ptr as usize },
-        1184usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(randomness)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).context_db_handle) as usize - ptr as usize },
-        1192usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(context_db_handle)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).extended_result_codes) as usize - ptr as usize },
-        1200usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(extended_result_codes)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).limit) as usize - ptr as usize },
-        1208usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(limit)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).next_stmt) as usize - ptr as usize },
-        1216usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(next_stmt)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).sql) as usize - ptr as usize },
-        1224usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(sql)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).status) as usize - ptr as usize },
-        1232usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(status)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).backup_finish) as usize - ptr as usize },
-        1240usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(backup_finish)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).backup_init) as usize - ptr as usize },
-        1248usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(backup_init)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).backup_pagecount) as usize - ptr as usize },
-        1256usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(backup_pagecount)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).backup_remaining) as usize - ptr as usize },
-        1264usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(backup_remaining)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).backup_step) as usize - ptr as usize },
-        1272usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(backup_step)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).compileoption_get) as usize - ptr as usize },
-        1280usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(compileoption_get)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).compileoption_used) as usize - ptr as usize },
-        1288usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(compileoption_used)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).create_function_v2) as usize - ptr as usize },
-        1296usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(create_function_v2)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).db_config) as usize - ptr as usize },
-        1304usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(db_config)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).db_mutex) as usize - ptr as usize },
-        1312usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(db_mutex)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).db_status) as usize - ptr as usize },
-        1320usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(db_status)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).extended_errcode) as usize - ptr as usize },
-        1328usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(extended_errcode)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).log) as usize - ptr as usize },
-        1336usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(log)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).soft_heap_limit64) as usize - ptr as usize },
-        1344usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(soft_heap_limit64)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).sourceid) as usize - ptr as usize },
-        1352usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(sourceid)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).stmt_status) as usize - ptr as usize },
-        1360usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(stmt_status)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).strnicmp) as usize - ptr as usize },
-        1368usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(strnicmp)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).unlock_notify) as usize - ptr as usize },
-        1376usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(unlock_notify)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).wal_autocheckpoint) as usize - ptr as usize },
-        1384usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(wal_autocheckpoint)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).wal_checkpoint) as usize - ptr as usize },
-        1392usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(wal_checkpoint)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).wal_hook) as usize - ptr as usize },
-        1400usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(wal_hook)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).blob_reopen) as usize - ptr as usize },
-        1408usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(blob_reopen)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).vtab_config) as usize - ptr as usize },
-        1416usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(vtab_config)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).vtab_on_conflict) as usize - ptr as usize },
-        1424usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(vtab_on_conflict)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).close_v2) as usize - ptr as usize },
-        1432usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(close_v2)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).db_filename) as usize - ptr as usize },
-        1440usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(db_filename)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).db_readonly) as usize - ptr as usize },
-        1448usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(db_readonly)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).db_release_memory) as usize - ptr as usize },
-        1456usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(db_release_memory)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).errstr) as usize - ptr as usize },
-        1464usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(errstr)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).stmt_busy) as usize - ptr as usize },
-        1472usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(stmt_busy)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).stmt_readonly) as usize - ptr as usize },
-        1480usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(stmt_readonly)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).stricmp) as usize - ptr as usize },
-        1488usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(stricmp)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).uri_boolean) as usize - ptr as usize },
-        1496usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(uri_boolean)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).uri_int64) as usize - ptr as usize },
-        1504usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(uri_int64)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).uri_parameter) as usize - ptr as usize },
-        1512usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(uri_parameter)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).xvsnprintf) as usize - ptr as usize },
-        1520usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(xvsnprintf)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).wal_checkpoint_v2) as usize - ptr as usize },
-        1528usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(wal_checkpoint_v2)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).auto_extension) as usize - ptr as usize },
-        1536usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(auto_extension)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).bind_blob64) as usize - ptr as usize },
-        1544usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(bind_blob64)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).bind_text64) as usize - ptr as usize },
-        1552usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(bind_text64)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).cancel_auto_extension) as usize - ptr as usize },
-        1560usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(cancel_auto_extension)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).load_extension) as usize - ptr as usize },
-        1568usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(load_extension)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).malloc64) as usize - ptr as usize },
-        1576usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(malloc64)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).msize) as usize - ptr as usize },
-        1584usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(msize)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).realloc64) as usize - ptr as usize },
-        1592usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(realloc64)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).reset_auto_extension) as usize - ptr as usize },
-        1600usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(reset_auto_extension)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_blob64) as usize - ptr as usize },
-        1608usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_blob64)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_text64) as usize - ptr as usize },
-        1616usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_text64)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).strglob) as usize - ptr as usize },
-        1624usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(strglob)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).value_dup) as usize - ptr as usize },
-        1632usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(value_dup)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).value_free) as usize - ptr as usize },
-        1640usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(value_free)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_zeroblob64) as usize - ptr as usize },
-        1648usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_zeroblob64)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).bind_zeroblob64) as usize - ptr as usize },
-        1656usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(bind_zeroblob64)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).value_subtype) as usize - ptr as usize },
-        1664usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(value_subtype)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_subtype) as usize - ptr as usize },
-        1672usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_subtype)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).status64) as usize - ptr as usize },
-        1680usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(status64)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).strlike) as usize - ptr as usize },
-        1688usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(strlike)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).db_cacheflush) as usize - ptr as usize },
-        1696usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(db_cacheflush)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).system_errno) as usize - ptr as usize },
-        1704usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(system_errno)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).trace_v2) as usize - ptr as usize },
-        1712usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(trace_v2)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).expanded_sql) as usize - ptr as usize },
-        1720usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(expanded_sql)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).set_last_insert_rowid) as usize - ptr as usize },
-        1728usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(set_last_insert_rowid)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).prepare_v3) as usize - ptr as usize },
-        1736usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(prepare_v3)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).prepare16_v3) as usize - ptr as usize },
-        1744usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(prepare16_v3)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).bind_pointer) as usize - ptr as usize },
-        1752usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(bind_pointer)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).result_pointer) as usize - ptr as usize },
-        1760usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(result_pointer)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).value_pointer) as usize - ptr as usize },
-        1768usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(value_pointer)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).vtab_nochange) as usize - ptr as usize },
-        1776usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(vtab_nochange)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).value_nochange) as usize - ptr as usize },
-        1784usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(value_nochange)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).vtab_collation) as usize - ptr as usize },
-        1792usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(vtab_collation)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).keyword_count) as usize - ptr as usize },
-        1800usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(keyword_count)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).keyword_name) as usize - ptr as usize },
-        1808usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(keyword_name)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).keyword_check) as usize - ptr as usize },
-        1816usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(keyword_check)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).str_new) as usize - ptr as usize },
-        1824usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(str_new)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).str_finish) as usize - ptr as usize },
-        1832usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(str_finish)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).str_appendf) as usize - ptr as usize },
-        1840usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(str_appendf)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).str_vappendf) as usize - ptr as usize },
-        1848usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(str_vappendf)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).str_append) as usize - ptr as usize },
-        1856usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(str_append)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).str_appendall) as usize - ptr as usize },
-        1864usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(str_appendall)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).str_appendchar) as usize - ptr as usize },
-        1872usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(str_appendchar)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).str_reset) as usize - ptr as usize },
-        1880usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(str_reset)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).str_errcode) as usize - ptr as usize },
-        1888usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(str_errcode)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).str_length) as usize - ptr as usize },
-        1896usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(str_length)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).str_value) as usize - ptr as usize },
-        1904usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(str_value)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).create_window_function) as usize - ptr as usize },
-        1912usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(create_window_function)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).normalized_sql) as usize - ptr as usize },
-        1920usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(normalized_sql)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).stmt_isexplain) as usize - ptr as usize },
-        1928usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(stmt_isexplain)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).value_frombind) as usize - ptr as usize },
-        1936usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(value_frombind)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).drop_modules) as usize - ptr as usize },
-        1944usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(drop_modules)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).hard_heap_limit64) as usize - ptr as usize },
-        1952usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(hard_heap_limit64)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).uri_key) as usize - ptr as usize },
-        1960usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(uri_key)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).filename_database) as usize - ptr as usize },
-        1968usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(filename_database)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).filename_journal) as usize - ptr as usize },
-        1976usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(filename_journal)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).filename_wal) as usize - ptr as usize },
-        1984usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(filename_wal)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).create_filename) as usize - ptr as usize },
-        1992usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(create_filename)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).free_filename) as usize - ptr as usize },
-        2000usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(free_filename)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).database_file_object) as usize - ptr as usize },
-        2008usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(database_file_object)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).txn_state) as usize - ptr as usize },
-        2016usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(txn_state)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).changes64) as usize - ptr as usize },
-        2024usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(changes64)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).total_changes64) as usize - ptr as usize },
-        2032usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(total_changes64)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).autovacuum_pages) as usize - ptr as usize },
-        2040usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(autovacuum_pages)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).error_offset) as usize - ptr as usize },
-        2048usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(error_offset)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).vtab_rhs_value) as usize - ptr as usize },
-        2056usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(vtab_rhs_value)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).vtab_distinct) as usize - ptr as usize },
-        2064usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(vtab_distinct)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).vtab_in) as usize - ptr as usize },
-        2072usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(vtab_in)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).vtab_in_first) as usize - ptr as usize },
-        2080usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(vtab_in_first)
-        )
-    );
-    assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).vtab_in_next) as usize - ptr as usize },
-        2088usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(sqlite3_api_routines),
-            "::",
-            stringify!(vtab_in_next)
-        )
-    );
-}
 pub type sqlite3_loadext_entry = ::std::option::Option<
     unsafe extern "C" fn(
         db: *mut sqlite3,
@@ -0,0 +1,369 @@
+//! Helpers for implementing aggregate functions.
+use crate::{
+    query::Statement, types::*, value::*, Connection, FallibleIterator, FallibleIteratorMut,
+};
+use std::{
+    cell::{Cell, Ref, RefCell},
+    cmp::Ordering,
+    sync::atomic::{AtomicI64, Ordering as AtomicOrdering},
+};
+
+/// The table used to hold spilled values. It is created in the temp schema of the connection
+/// the first time an accumulator spills, and is shared by all accumulators on that connection.
+const SPILL_TABLE: &str = "temp.sqlite3_ext_spill";
+
+/// Identifies the rows belonging to a single accumulator in the spill table.
+static NEXT_RUN: AtomicI64 = AtomicI64::new(1);
+
+/// Collects values in memory up to a threshold, then spills them to a temporary table.
+///
+/// This is useful for aggregate functions like median or percentile, which need to see every
+/// value before producing a result, but which may be run over more rows than can be held in
+/// memory. Up to `threshold` values are held in memory. When that is exceeded, the values are
+/// moved into a table in the connection's temp schema, and memory is used again for the
+/// following values. [sorted_iter](Self::sorted_iter) merges the values in memory with the
+/// spilled ones, so callers never need to know whether a spill happened.
+///
+/// Spilled values are removed from the table by [clear](Self::clear) and when the accumulator
+/// is dropped. When the accumulator is the state of an [AggregateFunction](super::AggregateFunction),
+/// this happens when SQLite finalizes the aggregate, including when the aggregate is
+/// finalized without ever having been stepped. Errors while removing the values when the
+/// accumulator is dropped are ignored; call [clear](Self::clear) to handle them. The spill
+/// table itself is shared by all accumulators on the connection, because a table cannot be
+/// dropped while the statement running the aggregate is active.
+///
+/// # Window functions
+///
+/// Values cannot be removed from the accumulator, so it cannot implement
+/// [AggregateFunction::inverse](super::AggregateFunction::inverse). Aggregates built on it
+/// should return an error from that method, which limits them to window frames that only
+/// grow.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sqlite3_ext::{function::aggregate::SpillingAccumulator, *};
+///
+/// fn max_text(db: &Connection) -> Result<Option<Value>> {
+///     let mut acc = SpillingAccumulator::new(1000);
+///     let mut stmt = db.prepare("SELECT name FROM users")?;
+///     stmt.query(())?;
+///     while let Some(row) = stmt.next()? {
+///         acc.push(db, row[0].to_owned()?)?;
+///     }
+///     let max = acc.sorted_iter()?.last()?;
+///     Ok(max)
+/// }
+/// ```
+pub struct SpillingAccumulator {
+    threshold: usize,
+    memory: RefCell<Vec<Value>>,
+    sorted: Cell<bool>,
+    spill: Option<Spill>,
+    spilled: usize,
+}
+
+struct Spill {
+    // Holding a statement keeps the connection open, so this is also used to reach it.
+    insert: Statement,
+    run: i64,
+}
+
+impl SpillingAccumulator {
+    /// Create an accumulator which holds at most `threshold` values in memory.
+    pub fn new(threshold: usize) -> Self {
+        Self {
+            threshold,
+            memory: RefCell::new(vec![]),
+            sorted: Cell::new(true),
+            spill: None,
+            spilled: 0,
+        }
+    }
+
+    /// Add a value to the accumulator.
+    ///
+    /// If this causes the number of values in memory to exceed the threshold, they are
+    /// written to the spill table using the given connection. Once the accumulator has
+    /// spilled, it always uses the connection that it first spilled to.
+    ///
+    /// Floating point NaN values are stored as NULL, matching the behavior of SQLite.
+    pub fn push(&mut self, db: &Connection, value: Value) -> Result<()> {
+        let value = match value {
+            Value::Float(x) if x.is_nan() => Value::Null,
+            x => x,
+        };
+        let memory = self.memory.get_mut();
+        memory.push(value);
+        let len = memory.len();
+        self.sorted.set(len < 2);
+        if len > self.threshold {
+            self.spill(db)?;
+        }
+        Ok(())
+    }
+
+    /// Return the total number of values in the accumulator.
+    pub fn len(&self) -> usize {
+        self.memory.borrow().len() + self.spilled
+    }
+
+    /// Return true if the accumulator has no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return true if any values have been written to the spill table.
+    pub fn has_spilled(&self) -> bool {
+        self.spilled > 0
+    }
+
+    /// Iterate over all of the values in the accumulator, in the order that SQLite would sort
+    /// them with the BINARY collation.
+    ///
+    /// The accumulator cannot be modified while the iterator exists. Attempting to do so will
+    /// panic.
+    pub fn sorted_iter(&self) -> Result<SortedIter<'_>> {
+        if !self.sorted.get() {
+            self.memory.borrow_mut().sort_by(compare);
+            self.sorted.set(true);
+        }
+        let spilled = match &self.spill {
+            Some(spill) if self.spilled > 0 => {
+                let sql = format!("SELECT value FROM {SPILL_TABLE} WHERE run = ? ORDER BY value");
+                let mut stmt = unsafe { spill.insert.db() }.prepare(&sql)?;
+                stmt.query([spill.run])?;
+                Some(stmt)
+            }
+            _ => None,
+        };
+        Ok(SortedIter {
+            memory: self.memory.borrow(),
+            pos: 0,
+            spilled,
+            pending: None,
+        })
+    }
+
+    /// Remove all values from the accumulator, including any which were spilled.
+    pub fn clear(&mut self) -> Result<()> {
+        self.memory.get_mut().clear();
+        self.sorted.set(true);
+        if let Some(spill) = &self.spill {
+            if self.spilled > 0 {
+                let sql = format!("DELETE FROM {SPILL_TABLE} WHERE run = ?");
+                unsafe { spill.insert.db() }.execute(&sql, [spill.run])?;
+                self.spilled = 0;
+            }
+        }
+        Ok(())
+    }
+
+    fn spill(&mut self, db: &Connection) -> Result<()> {
+        let spill = match &mut self.spill {
+            Some(x) => x,
+            None => {
+                let table = "CREATE TABLE IF NOT EXISTS temp.sqlite3_ext_spill (run INTEGER NOT NULL, value)";
+                let index = "CREATE INDEX IF NOT EXISTS temp.sqlite3_ext_spill_run ON sqlite3_ext_spill (run, value)";
+                db.execute(table, ())?;
+                db.execute(index, ())?;
+                let insert = db.prepare(&format!("INSERT INTO {SPILL_TABLE} VALUES (?, ?)"))?;
+                let run = NEXT_RUN.fetch_add(1, AtomicOrdering::Relaxed);
+                self.spill.insert(Spill { insert, run })
+            }
+        };
+        let memory = self.memory.get_mut();
+        while let Some(value) = memory.last() {
+            spill
+                .insert
+                .execute(crate::params!(spill.run, value.clone()))?;
+            memory.pop();
+            self.spilled += 1;
+        }
+        self.sorted.set(true);
+        Ok(())
+    }
+}
+
+impl Drop for SpillingAccumulator {
+    fn drop(&mut self) {
+        // The values are removed with the rest of the temp schema when the connection is
+        // closed, so there is nothing useful to do with an error here.
+        let _ = self.clear();
+    }
+}
+
+impl std::fmt::Debug for SpillingAccumulator {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("SpillingAccumulator")
+            .field("threshold", &self.threshold)
+            .field("len", &self.len())
+            .field("spilled", &self.spilled)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Iterator over the values of a [SpillingAccumulator], in sorted order.
+pub struct SortedIter<'a> {
+    memory: Ref<'a, Vec<Value>>,
+    pos: usize,
+    spilled: Option<Statement>,
+    pending: Option<Value>,
+}
+
+impl FallibleIterator for SortedIter<'_> {
+    type Item = Value;
+    type Error = Error;
+
+    fn next(&mut self) -> Result<Option<Value>> {
+        if self.pending.is_none() {
+            if let Some(stmt) = &mut self.spilled {
+                match stmt.next()? {
+                    Some(row) => self.pending = Some(row[0].to_owned()?),
+                    None => self.spilled = None,
+                }
+            }
+        }
+        let take_memory = match (self.memory.get(self.pos), &self.pending) {
+            (Some(a), Some(b)) => compare(a, b) != Ordering::Greater,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        match take_memory {
+            true => {
+                self.pos += 1;
+                Ok(Some(self.memory[self.pos - 1].clone()))
+            }
+            false => Ok(self.pending.take()),
+        }
+    }
+}
+
+/// Compare values the way SQLite does with the BINARY collation: NULL first, then numbers,
+/// then text, then blobs.
+fn compare(a: &Value, b: &Value) -> Ordering {
+    fn class(v: &Value) -> u8 {
+        match v {
+            Value::Null => 0,
+            Value::Integer(_) | Value::Float(_) => 1,
+            Value::Text(_) => 2,
+            Value::Blob(_) => 3,
+        }
+    }
+    match (a, b) {
+        (Value::Integer(a), Value::Integer(b)) => a.cmp(b),
+        (Value::Integer(a), Value::Float(b)) => compare_int_float(*a, *b),
+        (Value::Float(a), Value::Integer(b)) => compare_int_float(*b, *a).reverse(),
+        (Value::Float(a), Value::Float(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+        (Value::Text(a), Value::Text(b)) => a.as_bytes().cmp(b.as_bytes()),
+        (Value::Blob(a), Value::Blob(b)) => a.as_slice().cmp(b.as_slice()),
+        _ => class(a).cmp(&class(b)),
+    }
+}
+
+fn compare_int_float(a: i64, b: f64) -> Ordering {
+    // Integers beyond 2^53 cannot be converted to a float exactly, so compare in the integer
+    // domain when the float is in range.
+    if (-9223372036854775808.0..9223372036854775808.0).contains(&b) {
+        match a.cmp(&(b as i64)) {
+            Ordering::Equal => (0.0).partial_cmp(&b.fract()).unwrap_or(Ordering::Equal),
+            x => x,
+        }
+    } else {
+        (a as f64).partial_cmp(&b).unwrap_or(Ordering::Equal)
+    }
+}
+
+#[cfg(all(test, feature = "static"))]
+mod test {
+    use super::*;
+    use crate::test_helpers::prelude::*;
+
+    fn collect(acc: &SpillingAccumulator) -> Result<Vec<Value>> {
+        acc.sorted_iter()?.collect()
+    }
+
+    fn spilled_rows(db: &Connection) -> Result<i64> {
+        let sql = format!("SELECT COUNT(*) FROM {SPILL_TABLE}");
+        db.query_row(&sql, (), |r| Ok(r[0].get_i64()))
+    }
+
+    #[test]
+    fn memory_only() -> Result<()> {
+        let h = TestHelpers::new();
+        let mut acc = SpillingAccumulator::new(4);
+        for x in [3, 1, 2] {
+            acc.push(&h.db, Value::from(x))?;
+        }
+        assert_eq!(acc.len(), 3);
+        assert!(!acc.has_spilled());
+        assert_eq!(collect(&acc)?, vec![1.into(), 2.into(), 3.into()]);
+        // The table is only created when needed.
+        assert!(spilled_rows(&h.db).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn spill() -> Result<()> {
+        let h = TestHelpers::new();
+        let mut acc = SpillingAccumulator::new(3);
+        let values: Vec<i64> = vec![5, 9, 0, 7, 3, 8, 1, 6, 2, 4];
+        for x in values.iter() {
+            acc.push(&h.db, Value::from(*x))?;
+        }
+        assert_eq!(acc.len(), 10);
+        assert!(acc.has_spilled());
+        assert_eq!(spilled_rows(&h.db)?, 8);
+        let expected: Vec<Value> = (0..10).map(Value::from).collect();
+        assert_eq!(collect(&acc)?, expected);
+        // Iterating does not consume the values.
+        assert_eq!(collect(&acc)?, expected);
+        acc.push(&h.db, Value::from(-1))?;
+        assert_eq!(collect(&acc)?[0], Value::from(-1));
+
+        let mut other = SpillingAccumulator::new(0);
+        other.push(&h.db, Value::from(100))?;
+        assert_eq!(collect(&other)?, vec![Value::from(100)]);
+        drop(acc);
+        assert_eq!(spilled_rows(&h.db)?, 1);
+        other.clear()?;
+        assert!(other.is_empty());
+        assert_eq!(spilled_rows(&h.db)?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn ordering() -> Result<()> {
+        let h = TestHelpers::new();
+        let values = vec![
+            Value::from("b".to_owned()),
+            Value::Blob(Blob::from(b"\x00")),
+            Value::from(2.5),
+            Value::from(f64::NAN),
+            Value::from(2),
+            Value::from("a".to_owned()),
+            Value::from(3),
+            Value::Null,
+            Value::from(-0.5),
+        ];
+        let expected = vec![
+            Value::Null,
+            Value::Null,
+            Value::from(-0.5),
+            Value::from(2),
+            Value::from(2.5),
+            Value::from(3),
+            Value::from("a".to_owned()),
+            Value::from("b".to_owned()),
+            Value::Blob(Blob::from(b"\x00")),
+        ];
+        for threshold in [0, 4, 100] {
+            let mut acc = SpillingAccumulator::new(threshold);
+            for v in values.iter() {
+                acc.push(&h.db, v.clone())?;
+            }
+            assert_eq!(collect(&acc)?, expected, "threshold {threshold}");
+        }
+        Ok(())
+    }
+}
@@ -1,10 +1,14 @@
 use super::FromUserData;
-use crate::{ffi, sqlite3_match_version, types::*, value::*, Connection};
+use crate::{
+    ffi, query::Params, sqlite3_match_version, sqlite3_require_version, types::*, value::*,
+    Connection, ConnectionId, TransactionType,
+};
 use sealed::sealed;
 use std::{
     any::TypeId,
-    ffi::CString,
     mem::{size_of, MaybeUninit},
+    ops::Range,
+    slice,
 };
 
 #[repr(transparent)]
@@ -91,6 +95,58 @@ impl Context {
         unsafe { Connection::from_ptr(ffi::sqlite3_context_db_handle(self.as_ptr())) }
     }
 
+    /// Execute an SQL statement on the current database while this function is running, and
+    /// return the number of rows changed, like [Connection::execute].
+    ///
+    /// The statement which invoked the function is still running, which restricts what the
+    /// nested statement can do:
+    ///
+    /// - It may read and write any table, including tables which the invoking statement is
+    ///   reading. The invoking statement may or may not see rows which are changed.
+    /// - It may not drop tables or indexes, because SQLite does not allow this while any
+    ///   statement is running. SQLite fails with [SQLITE_LOCKED](ffi::SQLITE_LOCKED), which
+    ///   this method reports with a message describing the conflict.
+    /// - If the nested statement writes, the connection is not in a transaction, and no
+    ///   running statement writes, the nested statement runs in its own BEGIN IMMEDIATE
+    ///   transaction which is committed before this method returns. Otherwise, it becomes
+    ///   part of the transaction which is already open.
+    ///
+    /// Functions which modify the database should be registered with
+    /// [RiskLevel::DirectOnly](super::RiskLevel::DirectOnly), so that they cannot be
+    /// invoked by triggers, views, or the schema.
+    ///
+    /// Running statements can only be detected on SQLite 3.7.16 and later. On earlier
+    /// versions, this method never starts a transaction.
+    pub fn db_execute_guarded<P: Params>(&self, sql: &str, params: P) -> Result<i64> {
+        let db = self.db();
+        let mut stmt = db.prepare(sql)?;
+        let busy = db.busy_statement_info();
+        let writes = !stmt.is_readonly().unwrap_or(false);
+        let autocommit = unsafe { ffi::sqlite3_get_autocommit(db.as_mut_ptr()) != 0 };
+        let ret = if writes && autocommit && !busy.is_empty() && busy.iter().all(|(_, ro)| *ro) {
+            let txn = db.transaction(TransactionType::Immediate)?;
+            stmt.execute(params)
+                .and_then(|changes| txn.commit().map(|_| changes))
+        } else {
+            stmt.execute(params)
+        };
+        ret.map_err(|e| match e {
+            Error::Sqlite(code, msg) if code & 0xff == ffi::SQLITE_LOCKED => nesting_error(
+                code,
+                sql,
+                msg,
+                busy.first().map(|(outer, _)| outer.as_str()),
+            ),
+            e => e,
+        })
+    }
+
+    /// Return the identifier of the current database. This is equivalent to
+    /// `self.db().id()`.
+    pub fn db_id(&self) -> ConnectionId {
+        ConnectionId::from_ptr(unsafe { ffi::sqlite3_context_db_handle(self.as_ptr()) })
+    }
+
     /// Retrieve data about a function parameter that was previously set with
     /// [set_aux_data](Context::set_aux_data).
     ///
@@ -138,6 +194,122 @@ impl Context {
         unsafe { val.assign_to(self.as_ptr()) };
         Ok(())
     }
+
+    /// Assign a borrowed string to the result of the function. SQLite copies the string
+    /// before this method returns, so the string only needs to live for the duration of the
+    /// call. Unlike passing an owned String to [set_result](Self::set_result), no
+    /// intermediate copy is made in Rust.
+    pub fn set_result_borrowed(&self, val: &str) -> Result<()> {
+        unsafe { result_text_transient(self.as_ptr(), val.as_bytes()) };
+        Ok(())
+    }
+
+    /// Assign a borrowed BLOB to the result of the function. SQLite copies the BLOB before
+    /// this method returns. This is the same as passing the slice to
+    /// [set_result](Self::set_result).
+    pub fn set_result_borrowed_blob(&self, val: &[u8]) -> Result<()> {
+        self.set_result(val)
+    }
+
+    /// Assign a range of the bytes of a TEXT or BLOB argument to the result of the function,
+    /// keeping the type of the argument. This is useful for functions like `substr` which
+    /// return part of an argument.
+    ///
+    /// The bytes are read directly from the argument, without converting or copying it in
+    /// Rust. SQLite copies the selected bytes once, because the memory of an argument is not
+    /// guaranteed to outlive the result. Only the selected bytes of a TEXT argument are
+    /// checked for valid UTF-8.
+    ///
+    /// This method fails with [SQLITE_MISMATCH] if the argument is not TEXT or BLOB, with
+    /// [SQLITE_RANGE] if the range is outside of the argument, and with
+    /// [Error::Utf8Error] if the range of a TEXT argument does not fall on character
+    /// boundaries.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::{function::*, *};
+    ///
+    /// // prefix(X, N) returns the first N bytes of X.
+    /// fn prefix(ctx: &Context, args: &mut [&mut ValueRef]) -> Result<()> {
+    ///     let len = args[0].get_blob()?.len();
+    ///     let n = (args[1].get_i64().max(0) as usize).min(len);
+    ///     ctx.set_result_from_arg_slice(args[0], 0..n)
+    /// }
+    /// ```
+    pub fn set_result_from_arg_slice(&self, arg: &ValueRef, range: Range<usize>) -> Result<()> {
+        let is_text = match arg.value_type() {
+            ValueType::Text => true,
+            ValueType::Blob => false,
+            _ => return Err(SQLITE_MISMATCH),
+        };
+        let bytes = match is_text {
+            // The text may be stored in UTF-16, so request it as UTF-8.
+            true => unsafe {
+                let data = ffi::sqlite3_value_text(arg.as_ptr());
+                let len = ffi::sqlite3_value_bytes(arg.as_ptr());
+                match (data.is_null(), len) {
+                    (_, 0) => &[],
+                    (true, _) => return Err(SQLITE_NOMEM),
+                    (false, len) => slice::from_raw_parts(data, len as _),
+                }
+            },
+            false => unsafe { arg.get_blob_unchecked() },
+        };
+        let slice = match bytes.get(range.clone()) {
+            Some(x) => x,
+            None => {
+                return Err(Error::Sqlite(
+                    ffi::SQLITE_RANGE,
+                    Some(format!(
+                        "range {}..{} is out of bounds for a value of {} bytes",
+                        range.start,
+                        range.end,
+                        bytes.len()
+                    )),
+                ))
+            }
+        };
+        match is_text {
+            true => self.set_result_borrowed(std::str::from_utf8(slice)?),
+            false => self.set_result_borrowed_blob(slice),
+        }
+    }
+
+    /// Assign the given value to the result of the function, and tag it with the given
+    /// subtype. The subtype can be read by functions which receive the result as an
+    /// argument using [ValueRef::subtype]. This is the mechanism SQLite's own JSON functions
+    /// use to recognize values which were produced as JSON.
+    ///
+    /// Beginning with SQLite 3.45.0, the function should also be registered with
+    /// [FunctionOptions::set_returns_subtype](super::FunctionOptions::set_returns_subtype).
+    ///
+    /// Requires SQLite 3.9.0.
+    pub fn set_result_with_subtype(&self, val: impl ToContextResult, subtype: u8) -> Result<()> {
+        let _ = (&val, subtype);
+        sqlite3_require_version!(3_009_000, unsafe {
+            val.assign_to(self.as_ptr());
+            ffi::sqlite3_result_subtype(self.as_ptr(), subtype as _);
+            Ok(())
+        })
+    }
+}
+
+/// Describe an SQLITE_LOCKED error which happened while running a nested statement.
+fn nesting_error(code: i32, sql: &str, msg: Option<String>, outer: Option<&str>) -> Error {
+    let msg = msg.unwrap_or_else(|| "database table is locked".to_owned());
+    let mut desc = match msg.strip_prefix("database table is locked: ") {
+        Some(table) => {
+            format!(
+                "cannot write to table {table} while it is being read by the invoking statement"
+            )
+        }
+        None => format!("cannot execute \"{sql}\" while the invoking statement is running ({msg})"),
+    };
+    if let Some(outer) = outer {
+        desc.push_str(&format!("; invoking statement: {outer}"));
+    }
+    Error::Sqlite(code, Some(desc))
 }
 
 /// A value that can be returned from an SQL function.
@@ -187,14 +359,13 @@ to_context_result! {
             _ => ffi::sqlite3_result_text(ctx, val.as_ptr() as _, len as _, None),
         }
     },
-    /// Assign an owned string to the context result.
+    /// Assign an owned string to the context result. The string may contain nul bytes.
     match String as (ctx, val) => {
-        let val = val.as_bytes();
+        let val = Blob::from(val.as_bytes());
         let len = val.len();
-        let cstring = CString::new(val).unwrap().into_raw();
         sqlite3_match_version! {
-            3_008_007 => ffi::sqlite3_result_text64(ctx, cstring, len as _, Some(ffi::drop_cstring), ffi::SQLITE_UTF8 as _),
-            _ => ffi::sqlite3_result_text(ctx, cstring, len as _, Some(ffi::drop_cstring)),
+            3_008_007 => ffi::sqlite3_result_text64(ctx, val.into_raw() as _, len as _, Some(ffi::drop_blob), ffi::SQLITE_UTF8 as _),
+            _ => ffi::sqlite3_result_text(ctx, val.into_raw() as _, len as _, Some(ffi::drop_blob)),
         }
     },
     match Blob as (ctx, val) => {
@@ -207,9 +378,14 @@ to_context_result! {
     /// Sets the context error to this error.
     match Error as (ctx, err) => {
         match err {
-            Error::Sqlite(_, Some(desc)) => {
+            Error::Sqlite(code, Some(desc)) => {
                 let bytes = desc.as_bytes();
-                ffi::sqlite3_result_error(ctx, bytes.as_ptr() as _, bytes.len() as _)
+                ffi::sqlite3_result_error(ctx, bytes.as_ptr() as _, bytes.len() as _);
+                // sqlite3_result_error always uses SQLITE_ERROR. Setting the code afterwards
+                // keeps the message.
+                if code != ffi::SQLITE_ERROR {
+                    ffi::sqlite3_result_error_code(ctx, code);
+                }
             },
             Error::Sqlite(code, None) => ffi::sqlite3_result_error_code(ctx, code),
             Error::NoChange => (),
@@ -223,6 +399,15 @@ to_context_result! {
     }
 }
 
+/// Set the result to a copy of the text.
+unsafe fn result_text_transient(ctx: *mut ffi::sqlite3_context, val: &[u8]) {
+    let len = val.len();
+    sqlite3_match_version! {
+        3_008_007 => ffi::sqlite3_result_text64(ctx, val.as_ptr() as _, len as _, ffi::sqlite_transient(), ffi::SQLITE_UTF8 as _),
+        _ => ffi::sqlite3_result_text(ctx, val.as_ptr() as _, len as _, ffi::sqlite_transient()),
+    }
+}
+
 /// Sets the context result to the contained value.
 #[sealed]
 impl<'a> ToContextResult for &'a ValueRef {
@@ -320,6 +505,20 @@ impl<T: 'static + ?Sized> ToContextResult for UnsafePtr<T> {
     }
 }
 
+/// Sets the context result to NULL with this pointer and tag. SQLite does not take
+/// ownership of the pointed-to value.
+#[sealed]
+impl<T> ToContextResult for UnsafePassedRef<T> {
+    unsafe fn assign_to(self, context: *mut ffi::sqlite3_context) {
+        let (ptr, tag) = self.as_parts();
+        let _ = (ptr, tag, context);
+        sqlite3_match_version! {
+            3_020_000 => ffi::sqlite3_result_pointer(context, ptr as _, tag.as_ptr(), None),
+            _ => (),
+        }
+    }
+}
+
 /// Sets the context result to NULL with this value as an associated pointer.
 #[sealed]
 impl<T: 'static> ToContextResult for PassedRef<T> {
@@ -0,0 +1,281 @@
+//! Bulk conversion of function arguments.
+//!
+//! See [ExtractArgs::extract].
+use crate::{ffi, types::*, value::*};
+use std::ops::{Deref, DerefMut};
+
+/// A type which can be converted from a single argument using [ExtractArgs::extract].
+///
+/// Unlike the conversion methods of [FromValue], these conversions do not coerce between
+/// storage classes: an argument extracted as `i64` must be an INTEGER, and an argument
+/// extracted as `&str` must be TEXT. The only exception is `f64`, which also accepts
+/// INTEGER. SQL NULL is only accepted by `Option<T>`, [Value], and `&ValueRef`.
+pub trait FromSqlValue<'a>: Sized {
+    /// Convert the value, failing with [SQLITE_MISMATCH] if it has the wrong type.
+    fn from_sql_value(value: &'a mut ValueRef) -> Result<Self>;
+}
+
+fn type_name(ty: ValueType) -> &'static str {
+    match ty {
+        ValueType::Integer => "integer",
+        ValueType::Float => "real",
+        ValueType::Text => "text",
+        ValueType::Blob => "blob",
+        ValueType::Null => "NULL",
+    }
+}
+
+fn mismatch<T>(expected: &str, value: &ValueRef) -> Result<T> {
+    Err(Error::Sqlite(
+        ffi::SQLITE_MISMATCH,
+        Some(format!(
+            "expected {expected}, got {}",
+            type_name(value.value_type())
+        )),
+    ))
+}
+
+impl FromSqlValue<'_> for i64 {
+    fn from_sql_value(value: &mut ValueRef) -> Result<Self> {
+        match value.value_type() {
+            ValueType::Integer => Ok(value.get_i64()),
+            _ => mismatch("integer", value),
+        }
+    }
+}
+
+impl FromSqlValue<'_> for i32 {
+    fn from_sql_value(value: &mut ValueRef) -> Result<Self> {
+        let x = i64::from_sql_value(value)?;
+        i32::try_from(x).map_err(|_| {
+            Error::Sqlite(
+                ffi::SQLITE_MISMATCH,
+                Some(format!("{x} is out of range for i32")),
+            )
+        })
+    }
+}
+
+/// Accepts the integer values 0 and 1.
+impl FromSqlValue<'_> for bool {
+    fn from_sql_value(value: &mut ValueRef) -> Result<Self> {
+        match value.value_type() {
+            ValueType::Integer => value.get_bool(),
+            _ => mismatch("integer", value),
+        }
+    }
+}
+
+/// Accepts INTEGER and REAL values.
+impl FromSqlValue<'_> for f64 {
+    fn from_sql_value(value: &mut ValueRef) -> Result<Self> {
+        match value.value_type() {
+            ValueType::Integer | ValueType::Float => Ok(value.get_f64()),
+            _ => mismatch("real", value),
+        }
+    }
+}
+
+impl<'a> FromSqlValue<'a> for &'a str {
+    fn from_sql_value(value: &'a mut ValueRef) -> Result<Self> {
+        match value.value_type() {
+            ValueType::Text => value.get_str(),
+            _ => mismatch("text", value),
+        }
+    }
+}
+
+impl FromSqlValue<'_> for String {
+    fn from_sql_value(value: &mut ValueRef) -> Result<Self> {
+        <&str>::from_sql_value(value).map(String::from)
+    }
+}
+
+impl<'a> FromSqlValue<'a> for &'a [u8] {
+    fn from_sql_value(value: &'a mut ValueRef) -> Result<Self> {
+        match value.value_type() {
+            ValueType::Blob => value.get_blob(),
+            _ => mismatch("blob", value),
+        }
+    }
+}
+
+impl FromSqlValue<'_> for Vec<u8> {
+    fn from_sql_value(value: &mut ValueRef) -> Result<Self> {
+        <&[u8]>::from_sql_value(value).map(Vec::from)
+    }
+}
+
+/// Accepts any value, including NULL.
+impl FromSqlValue<'_> for Value {
+    fn from_sql_value(value: &mut ValueRef) -> Result<Self> {
+        value.to_owned()
+    }
+}
+
+/// Accepts any value, including NULL.
+impl<'a> FromSqlValue<'a> for &'a mut ValueRef {
+    fn from_sql_value(value: &'a mut ValueRef) -> Result<Self> {
+        Ok(value)
+    }
+}
+
+/// NULL is converted to None.
+impl<'a, T: FromSqlValue<'a>> FromSqlValue<'a> for Option<T> {
+    fn from_sql_value(value: &'a mut ValueRef) -> Result<Self> {
+        if value.is_null() {
+            Ok(None)
+        } else {
+            T::from_sql_value(value).map(Some)
+        }
+    }
+}
+
+/// The remaining arguments of a variadic function, used as the last element of a tuple
+/// passed to [ExtractArgs::extract].
+pub struct Rest<'a> {
+    offset: usize,
+    args: Vec<&'a mut ValueRef>,
+}
+
+impl<'a> Rest<'a> {
+    /// Convert every remaining argument to the same type. Errors name the position of
+    /// the argument in the original argument list.
+    pub fn extract_each<T: FromSqlValue<'a>>(self) -> Result<Vec<T>> {
+        let offset = self.offset;
+        self.args
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| extract_arg(offset + i, v))
+            .collect()
+    }
+}
+
+impl<'a> Deref for Rest<'a> {
+    type Target = [&'a mut ValueRef];
+
+    fn deref(&self) -> &Self::Target {
+        &self.args
+    }
+}
+
+impl DerefMut for Rest<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.args
+    }
+}
+
+impl std::fmt::Debug for Rest<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.args.iter()).finish()
+    }
+}
+
+/// A tuple which can be converted from the arguments of a function using
+/// [ExtractArgs::extract].
+///
+/// This trait is implemented for tuples of up to 8 elements which implement
+/// [FromSqlValue]. The last element may instead be [Rest], in which case the function accepts
+/// any number of additional arguments.
+pub trait FromArgs<'a>: Sized {
+    /// Convert the arguments.
+    fn from_args(args: &'a mut [&mut ValueRef]) -> Result<Self>;
+}
+
+fn extract_arg<'a, T: FromSqlValue<'a>>(idx: usize, value: &'a mut ValueRef) -> Result<T> {
+    T::from_sql_value(value).map_err(|e| {
+        let msg = format!("argument {}: {e}", idx + 1);
+        match e {
+            Error::Sqlite(code, _) => Error::Sqlite(code, Some(msg)),
+            _ => Error::Module(msg),
+        }
+    })
+}
+
+fn check_count(expected: usize, actual: usize, variadic: bool) -> Result<()> {
+    let plural = if expected == 1 { "" } else { "s" };
+    if actual == expected || (variadic && actual > expected) {
+        Ok(())
+    } else if variadic {
+        Err(Error::Module(format!(
+            "expected at least {expected} argument{plural} but {actual} were given"
+        )))
+    } else {
+        Err(Error::Module(format!(
+            "expected {expected} argument{plural} but {actual} were given"
+        )))
+    }
+}
+
+macro_rules! from_args_tuple {
+    ($n:literal; $($ty:ident $idx:literal),*) => {
+        impl<'a, $($ty: FromSqlValue<'a>),*> FromArgs<'a> for ($($ty,)*) {
+            #[allow(unused_variables, unused_mut)]
+            fn from_args(args: &'a mut [&mut ValueRef]) -> Result<Self> {
+                check_count($n, args.len(), false)?;
+                let mut args = args.iter_mut();
+                Ok(($(extract_arg::<$ty>($idx, &mut **args.next().unwrap())?,)*))
+            }
+        }
+
+        impl<'a, $($ty: FromSqlValue<'a>),*> FromArgs<'a> for ($($ty,)* Rest<'a>,) {
+            #[allow(unused_mut)]
+            fn from_args(args: &'a mut [&mut ValueRef]) -> Result<Self> {
+                check_count($n, args.len(), true)?;
+                let mut args = args.iter_mut();
+                Ok((
+                    $(extract_arg::<$ty>($idx, &mut **args.next().unwrap())?,)*
+                    Rest {
+                        offset: $n,
+                        args: args.map(|v| &mut **v).collect(),
+                    },
+                ))
+            }
+        }
+    };
+}
+
+from_args_tuple!(0;);
+from_args_tuple!(1; A 0);
+from_args_tuple!(2; A 0, B 1);
+from_args_tuple!(3; A 0, B 1, C 2);
+from_args_tuple!(4; A 0, B 1, C 2, D 3);
+from_args_tuple!(5; A 0, B 1, C 2, D 3, E 4);
+from_args_tuple!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+from_args_tuple!(7; A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+from_args_tuple!(8; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+/// Extension trait which converts the arguments of a function all at once.
+///
+/// This trait is implemented for the argument slice passed to
+/// [ScalarFunction::call](super::ScalarFunction::call) and to the `step` methods of
+/// aggregate functions.
+pub trait ExtractArgs {
+    /// Convert the arguments to a tuple. The number of arguments must match the number
+    /// of elements in the tuple, unless the last element is [Rest]. On failure, the error
+    /// describes the problem and names the position of the argument, counting from 1.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::{function::*, *};
+    ///
+    /// fn repeat(ctx: &Context, args: &mut [&mut ValueRef]) -> Result<()> {
+    ///     let (text, count, sep) = args.extract::<(&str, i64, Option<&str>)>()?;
+    ///     ctx.set_result(vec![text; count as usize].join(sep.unwrap_or("")))
+    /// }
+    ///
+    /// fn sum_all(ctx: &Context, args: &mut [&mut ValueRef]) -> Result<()> {
+    ///     let (first, rest) = args.extract::<(f64, Rest)>()?;
+    ///     let rest = rest.extract_each::<f64>()?;
+    ///     ctx.set_result(first + rest.iter().sum::<f64>())
+    /// }
+    /// ```
+    fn extract<'a, T: FromArgs<'a>>(&'a mut self) -> Result<T>;
+}
+
+impl ExtractArgs for [&mut ValueRef] {
+    fn extract<'a, T: FromArgs<'a>>(&'a mut self) -> Result<T> {
+        T::from_args(self)
+    }
+}
@@ -2,13 +2,36 @@
 //!
 //! The functionality in this module is primarily exposed through
 //! [Connection::create_scalar_function] and [Connection::create_aggregate_function].
-use super::{ffi, sqlite3_match_version, types::*, value::*, Connection, RiskLevel};
+#[cfg(modern_sqlite)]
+use super::capabilities;
+use super::{
+    connection::{check_collation_name, quote_identifier, SLOT_PREFIX},
+    extension::{boxed_destructor, check_transient_drop},
+    ffi, sqlite3_match_version,
+    types::*,
+    value::*,
+    Connection, Limit, RiskLevel,
+};
 pub use context::*;
-use std::{cmp::Ordering, ffi::CString, ptr::null_mut};
+pub use extract::*;
+use std::{
+    cmp::Ordering,
+    ffi::{c_void, CString},
+    ptr::null_mut,
+};
+pub use typed::*;
+pub use window::*;
 
+pub mod aggregate;
 mod context;
+mod extract;
 mod stubs;
 mod test;
+mod typed;
+mod window;
+
+/// Key of the connection slot which owns the collation needed callback.
+const COLLATION_NEEDED_SLOT: &str = "collation_needed";
 
 /// Constructor for aggregate functions.
 ///
@@ -108,6 +131,43 @@ pub trait AggregateFunction<UserData>: FromUserData<UserData> {
     fn inverse(&mut self, context: &Context, args: &mut [&mut ValueRef]) -> Result<()>;
 }
 
+/// Implement an application-defined aggregate window function which returns its result
+/// directly.
+///
+/// This is an alternative to [AggregateFunction] which does not require calling
+/// [Context::set_result]. There is a blanket implementation of [AggregateFunction] for all
+/// types implementing this trait, so these functions are also registered using
+/// [Connection::create_aggregate_function].
+pub trait TypedAggregateFunction<UserData>: FromUserData<UserData> {
+    /// The type of the result of the aggregate function.
+    type Output: ToContextResult;
+
+    /// Return the default value of the aggregate function.
+    ///
+    /// This method is called when the aggregate function is invoked over an empty set of
+    /// rows. The default implementation is equivalent to
+    /// `Self::from_user_data(user_data).value()`.
+    fn default_value(user_data: &UserData) -> Result<Self::Output>
+    where
+        Self: Sized,
+    {
+        Self::from_user_data(user_data).value()
+    }
+
+    /// Add a new row to the aggregate.
+    fn step(&mut self, context: &Context, args: &mut [&mut ValueRef]) -> Result<()>;
+
+    /// Return the current value of the aggregate function. If the function returns an Err
+    /// value, the SQL statement will fail.
+    fn value(&self) -> Result<Self::Output>;
+
+    /// Remove the oldest presently aggregated row.
+    ///
+    /// The args are the same that were passed to [TypedAggregateFunction::step] when this
+    /// row was added.
+    fn inverse(&mut self, context: &Context, args: &mut [&mut ValueRef]) -> Result<()>;
+}
+
 impl<U, F: Default> FromUserData<U> for F {
     fn from_user_data(_: &U) -> F {
         F::default()
@@ -128,10 +188,65 @@ impl<U, T: AggregateFunction<U>> LegacyAggregateFunction<U> for T {
     }
 }
 
+impl<U, T: TypedAggregateFunction<U>> AggregateFunction<U> for T {
+    fn default_value(user_data: &U, context: &Context) -> Result<()> {
+        context.set_result(<T as TypedAggregateFunction<U>>::default_value(user_data)?)
+    }
+
+    fn step(&mut self, context: &Context, args: &mut [&mut ValueRef]) -> Result<()> {
+        <T as TypedAggregateFunction<U>>::step(self, context, args)
+    }
+
+    fn value(&self, context: &Context) -> Result<()> {
+        context.set_result(<T as TypedAggregateFunction<U>>::value(self)?)
+    }
+
+    fn inverse(&mut self, context: &Context, args: &mut [&mut ValueRef]) -> Result<()> {
+        <T as TypedAggregateFunction<U>>::inverse(self, context, args)
+    }
+}
+
+/// The text encoding preferred by an application-defined function.
+///
+/// SQLite converts text arguments whose encoding differs from the preferred encoding before
+/// invoking the function. Choosing the encoding which the database uses means that text read
+/// from the database is usually passed through unchanged, but values in another encoding, such
+/// as text produced by other functions, are still converted. The function will be invoked
+/// regardless of the encoding of its arguments.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum TextEncoding {
+    /// UTF-8. This is the default.
+    Utf8,
+    /// UTF-16, little endian.
+    Utf16le,
+    /// UTF-16, big endian.
+    Utf16be,
+    /// UTF-16, in the native byte order of the host machine.
+    Utf16,
+}
+
+impl TextEncoding {
+    const fn as_flags(self) -> i32 {
+        match self {
+            TextEncoding::Utf8 => ffi::SQLITE_UTF8,
+            TextEncoding::Utf16le => ffi::SQLITE_UTF16LE,
+            TextEncoding::Utf16be => ffi::SQLITE_UTF16BE,
+            TextEncoding::Utf16 => ffi::SQLITE_UTF16,
+        }
+    }
+}
+
+/// The bits of the function flags which hold the text encoding.
+const ENCODING_MASK: i32 = 0x7;
+
+/// Added in SQLite 3.45.0, so it is not present in the bindings. Earlier versions ignore it.
+const SQLITE_RESULT_SUBTYPE: i32 = 0x0100_0000;
+
 #[derive(Debug, Clone)]
 pub struct FunctionOptions {
     n_args: i32,
     flags: i32,
+    check_shadowing: bool,
 }
 
 impl Default for FunctionOptions {
@@ -145,6 +260,7 @@ impl FunctionOptions {
         FunctionOptions {
             n_args: -1,
             flags: 0,
+            check_shadowing: false,
         }
     }
 
@@ -202,9 +318,116 @@ impl FunctionOptions {
         }
         self
     }
+
+    /// Enable or disable the flag which indicates that the function may assign a subtype to
+    /// its result using [Context::set_result_with_subtype]. Beginning with SQLite 3.45.0,
+    /// subtypes are only preserved through intermediate storage for functions which set
+    /// this flag.
+    ///
+    /// Requires SQLite 3.45.0. On earlier versions of SQLite, this flag is ignored.
+    pub const fn set_returns_subtype(mut self, val: bool) -> Self {
+        if val {
+            self.flags |= SQLITE_RESULT_SUBTYPE;
+        } else {
+            self.flags &= !SQLITE_RESULT_SUBTYPE;
+        }
+        self
+    }
+
+    /// Enable or disable the flag which indicates that the function may read the subtype of
+    /// its arguments using [ValueRef::subtype](crate::ValueRef::subtype). SQLite may
+    /// optimize away the subtypes of the arguments of functions without this flag.
+    ///
+    /// Requires SQLite 3.30.0. On earlier versions of SQLite, this flag is ignored.
+    pub const fn set_reads_subtype(mut self, val: bool) -> Self {
+        if val {
+            self.flags |= ffi::SQLITE_SUBTYPE;
+        } else {
+            self.flags &= !ffi::SQLITE_SUBTYPE;
+        }
+        self
+    }
+
+    /// Set the preferred text encoding for the function's arguments. See [TextEncoding] for
+    /// details.
+    pub const fn set_text_encoding(mut self, encoding: TextEncoding) -> Self {
+        self.flags = (self.flags & !ENCODING_MASK) | encoding.as_flags();
+        self
+    }
+
+    /// Refuse to register the function if it would replace a built-in SQL function with the
+    /// same name and number of arguments, since that changes the meaning of every query which
+    /// uses the built-in function. By default, such functions are registered. See
+    /// [Connection::create_scalar_function] for details.
+    pub const fn set_check_shadowing(mut self, val: bool) -> Self {
+        self.check_shadowing = val;
+        self
+    }
 }
 
+/// The longest function name that SQLite accepts, in bytes.
+const MAX_FUNCTION_NAME_LEN: usize = 255;
+
 impl Connection {
+    /// Check that a function can be registered with the given name and options, so that
+    /// the problem can be described before SQLite rejects it with a generic error, or
+    /// accepts a function which can never be called.
+    fn check_function(&self, name: &str, opts: &FunctionOptions) -> Result<()> {
+        let misuse = |msg: String| Err(Error::Sqlite(ffi::SQLITE_MISUSE, Some(msg)));
+        if name.len() > MAX_FUNCTION_NAME_LEN {
+            return misuse(format!(
+                "function name is longer than {MAX_FUNCTION_NAME_LEN} bytes: {name}"
+            ));
+        }
+        let max_args = self.limit(Limit::FunctionArg);
+        if opts.n_args > max_args {
+            return misuse(format!(
+                "{name}() takes {} arguments, but SQLITE_LIMIT_FUNCTION_ARG is {max_args}",
+                opts.n_args
+            ));
+        }
+        if opts.check_shadowing && self.is_builtin_function(name, opts.n_args)? {
+            return misuse(format!(
+                "{name}() with {} arguments would shadow a built-in function",
+                opts.n_args
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns true if SQLite provides a built-in function with the given name and number
+    /// of arguments. Requires SQLite 3.30.0; on earlier versions, or if SQLite was compiled
+    /// without PRAGMA function_list, this always returns false.
+    fn is_builtin_function(&self, name: &str, n_args: i32) -> Result<bool> {
+        let _ = (name, n_args);
+        sqlite3_match_version! {
+            3_030_000 => {
+                use crate::FallibleIterator;
+                use std::{collections::HashSet, sync::OnceLock};
+                // The built-in functions are the same on every connection, so they are only
+                // listed once.
+                static BUILTINS: OnceLock<HashSet<(String, i32)>> = OnceLock::new();
+                let builtins = match BUILTINS.get() {
+                    Some(x) => x,
+                    None => {
+                        let sql = "SELECT name, narg FROM pragma_function_list WHERE builtin";
+                        let rows: Vec<(String, i32)> = match self.prepare(sql) {
+                            Ok(mut stmt) => stmt.query_as(())?.collect()?,
+                            Err(_) => vec![],
+                        };
+                        let list = rows
+                            .into_iter()
+                            .map(|(name, n_args)| (name.to_ascii_lowercase(), n_args))
+                            .collect();
+                        BUILTINS.get_or_init(|| list)
+                    }
+                };
+                Ok(builtins.contains(&(name.to_ascii_lowercase(), n_args)))
+            }
+            _ => Ok(false),
+        }
+    }
+
     /// Create a stub function that always fails.
     ///
     /// This API makes sure a global version of a function with a particular name and
@@ -236,6 +459,24 @@ impl Connection {
     /// [Self::create_scalar_function_object] function is an alternative that allows using an
     /// alternative lifetime.
     ///
+    /// If a closure which captures variables needing to be dropped is registered by a
+    /// non-persistent extension, this function fails. See
+    /// [Extension](crate::Extension#non-persistent-extensions) for details.
+    ///
+    /// # Validation
+    ///
+    /// This function, and the other functions which register scalar and aggregate
+    /// functions, fail with [SQLITE_MISUSE](ffi::SQLITE_MISUSE) and a message describing
+    /// the problem if:
+    ///
+    /// - the name is longer than 255 bytes;
+    /// - the function takes more arguments than [Limit::FunctionArg](crate::Limit::FunctionArg)
+    ///   allows, so it could never be called; or
+    /// - [FunctionOptions::set_check_shadowing] is set, and SQLite has a built-in function
+    ///   with the same name and number of arguments. Only functions which are built into
+    ///   SQLite are considered, not those registered by extensions such as FTS5. This check
+    ///   requires SQLite 3.30.0.
+    ///
     /// # Compatibility
     ///
     /// On versions of SQLite earlier than 3.7.3, this function will leak the function and
@@ -253,6 +494,23 @@ impl Connection {
         self.create_scalar_function_object(name, &opts, ScalarClosure(func))
     }
 
+    /// Create a new scalar function which is never dropped. This function is identical to
+    /// [Self::create_scalar_function], except that SQLite is not given a destructor for the
+    /// function, so the closure and any captured variables are leaked. This allows
+    /// non-persistent extensions to register closures with captured state. See
+    /// [Extension](crate::Extension#non-persistent-extensions) for details.
+    pub fn create_scalar_function_leaky<F>(
+        &self,
+        name: &str,
+        opts: &FunctionOptions,
+        func: F,
+    ) -> Result<()>
+    where
+        F: Fn(&Context, &mut [&mut ValueRef]) -> Result<()> + 'static,
+    {
+        self.create_scalar_function_internal(name, opts, ScalarClosure(func), true)
+    }
+
     /// Create a new scalar function using a struct. This function is identical to
     /// [Self::create_scalar_function], but uses a trait object instead of a closure. This enables
     /// creating scalar functions that maintain references with a lifetime smaller than `'static`.
@@ -265,6 +523,24 @@ impl Connection {
     where
         F: ScalarFunction<'db>,
     {
+        check_transient_drop::<F>("scalar function")?;
+        self.create_scalar_function_internal(name, opts, func, false)
+    }
+
+    fn create_scalar_function_internal<'db, F>(
+        &'db self,
+        name: &str,
+        opts: &FunctionOptions,
+        func: F,
+        leak: bool,
+    ) -> Result<()>
+    where
+        F: ScalarFunction<'db>,
+    {
+        #[cfg_attr(not(modern_sqlite), allow(unused_variables))]
+        let destroy: Option<unsafe extern "C" fn(*mut c_void)> =
+            if leak { None } else { boxed_destructor::<F>() };
+        self.check_function(name, opts)?;
         let guard = self.lock();
         let name = unsafe { CString::from_vec_unchecked(name.as_bytes().into()) };
         let func = Box::new(func);
@@ -280,7 +556,7 @@ impl Connection {
                         Some(stubs::call_scalar::<F>),
                         None,
                         None,
-                        Some(ffi::drop_boxed::<F>),
+                        destroy,
                     ),
                     _ => ffi::sqlite3_create_function(
                         self.as_mut_ptr(),
@@ -310,12 +586,23 @@ impl Connection {
     /// On versions of SQLite earlier than 3.7.3, this function will leak the user data.
     /// This is because these versions of SQLite did not provide the ability to specify a
     /// destructor function.
-    pub fn create_legacy_aggregate_function<U, F: LegacyAggregateFunction<U>>(
+    pub fn create_legacy_aggregate_function<U: 'static, F: LegacyAggregateFunction<U>>(
         &self,
         name: &str,
         opts: &FunctionOptions,
         user_data: U,
     ) -> Result<()> {
+        self.create_legacy_aggregate_function_internal::<U, F>(name, opts, user_data)
+    }
+
+    fn create_legacy_aggregate_function_internal<'db, U: 'db, F: LegacyAggregateFunction<U>>(
+        &'db self,
+        name: &str,
+        opts: &FunctionOptions,
+        user_data: U,
+    ) -> Result<()> {
+        check_transient_drop::<U>("aggregate function user data")?;
+        self.check_function(name, opts)?;
         let guard = self.lock();
         let name = unsafe { CString::from_vec_unchecked(name.as_bytes().into()) };
         let user_data = Box::new(user_data);
@@ -331,7 +618,7 @@ impl Connection {
                         None,
                         Some(stubs::aggregate_step::<U, F>),
                         Some(stubs::aggregate_final::<U, F>),
-                        Some(ffi::drop_boxed::<U>),
+                        boxed_destructor::<U>(),
                     ),
                     _ => ffi::sqlite3_create_function(
                         self.as_mut_ptr(),
@@ -351,19 +638,48 @@ impl Connection {
 
     /// Create a new aggregate function.
     ///
+    /// The user data is dropped when the function is removed or replaced, or when the
+    /// connection is closed. Its lifetime must be `'static`; the
+    /// [Self::create_aggregate_function_object] function is an alternative that allows using
+    /// an alternative lifetime.
+    ///
+    /// If the user data needs to be dropped and this is called by a non-persistent
+    /// `unload_safe` extension, this function fails. See
+    /// [Extension](crate::Extension#non-persistent-extensions) for details.
+    ///
     /// # Compatibility
     ///
-    /// Window functions require SQLite 3.25.0. On earlier versions of SQLite, this
-    /// function will automatically fall back to
+    /// Window functions require SQLite 3.25.0. On earlier versions of SQLite, or if SQLite
+    /// does not support window functions (see [Capabilities::window_functions](crate::Capabilities::window_functions)),
+    /// this function will automatically fall back to
     /// [create_legacy_aggregate_function](Connection::create_legacy_aggregate_function).
-    pub fn create_aggregate_function<U, F: AggregateFunction<U>>(
+    pub fn create_aggregate_function<U: 'static, F: AggregateFunction<U>>(
         &self,
         name: &str,
         opts: &FunctionOptions,
         user_data: U,
     ) -> Result<()> {
+        self.create_aggregate_function_object::<U, F>(name, opts, user_data)
+    }
+
+    /// Create a new aggregate function whose user data has a lifetime smaller than
+    /// `'static`. This function is identical to [Self::create_aggregate_function], but
+    /// allows the user data to hold references which live as long as the connection, such
+    /// as a cache owned by the same struct as the [Database](crate::Database).
+    pub fn create_aggregate_function_object<'db, U: 'db, F: AggregateFunction<U>>(
+        &'db self,
+        name: &str,
+        opts: &FunctionOptions,
+        user_data: U,
+    ) -> Result<()> {
+        check_transient_drop::<U>("aggregate function user data")?;
         sqlite3_match_version! {
             3_025_000 => {
+                if !capabilities().window_functions {
+                    return self
+                        .create_legacy_aggregate_function_internal::<U, F>(name, opts, user_data);
+                }
+                self.check_function(name, opts)?;
                 let name = unsafe { CString::from_vec_unchecked(name.as_bytes().into()) };
                 let user_data = Box::new(user_data);
                 let guard = self.lock();
@@ -378,11 +694,11 @@ impl Connection {
                         Some(stubs::aggregate_final::<U, F>),
                         Some(stubs::aggregate_value::<U, F>),
                         Some(stubs::aggregate_inverse::<U, F>),
-                        Some(ffi::drop_boxed::<U>),
+                        boxed_destructor::<U>(),
                     ), guard)
                 }
             },
-            _ => self.create_legacy_aggregate_function::<U, F>(name, opts, user_data),
+            _ => self.create_legacy_aggregate_function_internal::<U, F>(name, opts, user_data),
         }
     }
 
@@ -409,11 +725,66 @@ impl Connection {
     }
 
     /// Register a new collating sequence.
+    ///
+    /// Names which start with `sqlite3_ext:` are reserved for this crate, and registering a
+    /// collation with such a name fails with [SQLITE_MISUSE](ffi::SQLITE_MISUSE).
+    ///
+    /// The function is registered for UTF-8 text. The function is never called with text that
+    /// is not valid UTF-8; such text sorts after all valid text, and byte-wise relative to
+    /// other invalid text. Use [create_collation_raw](Connection::create_collation_raw) to
+    /// handle invalid text explicitly.
+    ///
+    /// If the function needs to be dropped and this is called by a non-persistent
+    /// `unload_safe` extension, this function fails. See
+    /// [Extension](crate::Extension#non-persistent-extensions) for details.
     pub fn create_collation<F: Fn(&str, &str) -> Ordering>(
         &self,
         name: &str,
         func: F,
     ) -> Result<()> {
+        self.create_collation_internal(name, ffi::SQLITE_UTF8, func, stubs::compare::<F>)
+    }
+
+    /// Register a new collating sequence which operates on the raw UTF-8 bytes of the text.
+    ///
+    /// This function is identical to [create_collation](Connection::create_collation),
+    /// except that the function receives the bytes without validation, so it is responsible
+    /// for handling text which is not valid UTF-8. The function must still implement a total
+    /// order.
+    pub fn create_collation_raw<F: Fn(&[u8], &[u8]) -> Ordering>(
+        &self,
+        name: &str,
+        func: F,
+    ) -> Result<()> {
+        self.create_collation_internal(name, ffi::SQLITE_UTF8, func, stubs::compare_raw::<F>)
+    }
+
+    /// Register a new collating sequence for UTF-16 text, in the native byte order of the
+    /// host machine.
+    ///
+    /// This function is identical to [create_collation](Connection::create_collation),
+    /// except that the function receives UTF-16 code units instead of UTF-8. SQLite converts
+    /// each compared value to native-order UTF-16 if it is stored in a different encoding, so
+    /// this version only saves conversions when the database itself uses native-order UTF-16.
+    /// A collation may be registered under the same name with both encodings, in which case
+    /// SQLite uses the one which requires the least conversion.
+    pub fn create_collation_utf16<F: Fn(&[u16], &[u16]) -> Ordering>(
+        &self,
+        name: &str,
+        func: F,
+    ) -> Result<()> {
+        self.create_collation_internal(name, ffi::SQLITE_UTF16, func, stubs::compare_utf16::<F>)
+    }
+
+    fn create_collation_internal<F>(
+        &self,
+        name: &str,
+        encoding: i32,
+        func: F,
+        compare: unsafe extern "C" fn(*mut c_void, i32, *const c_void, i32, *const c_void) -> i32,
+    ) -> Result<()> {
+        check_transient_drop::<F>("collation")?;
+        check_collation_name(name)?;
         let name = unsafe { CString::from_vec_unchecked(name.as_bytes().into()) };
         let func = Box::into_raw(Box::new(func));
         let guard = self.lock();
@@ -421,10 +792,10 @@ impl Connection {
             let rc = ffi::sqlite3_create_collation_v2(
                 self.as_mut_ptr(),
                 name.as_ptr() as _,
-                ffi::SQLITE_UTF8,
+                encoding,
                 func as _,
-                Some(stubs::compare::<F>),
-                Some(ffi::drop_boxed::<F>),
+                Some(compare),
+                boxed_destructor::<F>(),
             );
             if rc != ffi::SQLITE_OK {
                 // The xDestroy callback is not called if the
@@ -435,25 +806,98 @@ impl Connection {
         }
     }
 
+    /// Remove a collating sequence which was registered with
+    /// [create_collation](Connection::create_collation) or one of its variants. Both the
+    /// UTF-8 and UTF-16 versions of the collation are removed, and their functions are
+    /// dropped. Statements which use the collation fail to prepare afterwards, unless
+    /// the function registered with
+    /// [set_collation_needed_func](Connection::set_collation_needed_func) provides it again.
+    ///
+    /// SQLite refuses to remove a collation while any statements are running, and this
+    /// method fails with [SQLITE_BUSY](ffi::SQLITE_BUSY) if it is called at such a time. The
+    /// collations which this crate reserves cannot be removed, and this method fails with
+    /// [SQLITE_MISUSE](ffi::SQLITE_MISUSE) for them.
+    pub fn remove_collation(&self, name: &str) -> Result<()> {
+        check_collation_name(name)?;
+        let name = unsafe { CString::from_vec_unchecked(name.as_bytes().into()) };
+        let _guard = self.lock();
+        for encoding in [ffi::SQLITE_UTF8, ffi::SQLITE_UTF16] {
+            let guard = self.lock();
+            unsafe {
+                Error::from_sqlite_desc(
+                    ffi::sqlite3_create_collation_v2(
+                        self.as_mut_ptr(),
+                        name.as_ptr() as _,
+                        encoding,
+                        null_mut(),
+                        None,
+                        None,
+                    ),
+                    guard,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the names of the collating sequences available on this connection, using
+    /// `PRAGMA collation_list`. This includes the built-in collations, such as BINARY and
+    /// NOCASE, but not the collations which this crate uses internally on SQLite versions
+    /// before 3.44.0 to attach callbacks to the connection.
+    ///
+    /// SQLite continues to list collations after they are removed with
+    /// [remove_collation](Connection::remove_collation), so each name is checked by
+    /// preparing a statement which uses it. This may invoke the function registered with
+    /// [set_collation_needed_func](Connection::set_collation_needed_func), and collations
+    /// which it provides are included.
+    pub fn collations(&self) -> Result<Vec<String>> {
+        let mut names = vec![];
+        self.pragma_query("collation_list", |row| {
+            names.push(row[1].get_str()?.to_owned());
+            Ok(())
+        })?;
+        Ok(names
+            .into_iter()
+            .filter(|name| {
+                if name.starts_with(SLOT_PREFIX) {
+                    return false;
+                }
+                let sql = format!("SELECT '' < '' COLLATE {}", quote_identifier(name));
+                self.prepare(&sql).is_ok()
+            })
+            .collect())
+    }
+
     /// Register a callback for when SQLite needs a collation sequence. The function will
     /// be invoked when a collation sequence is needed, and
     /// [create_collation](Connection::create_collation) can be used to provide the needed
     /// sequence.
     ///
-    /// Note: the provided function and any captured variables will be leaked. SQLite does
-    /// not provide any facilities for cleaning up this data.
+    /// A connection has a single callback, so this method replaces any previous callback,
+    /// which is dropped. The callback is dropped when the connection is closed.
+    ///
+    /// If the callback needs to be dropped and this is called by a non-persistent
+    /// `unload_safe` extension, this function fails. See
+    /// [Extension](crate::Extension#non-persistent-extensions) for details.
     pub fn set_collation_needed_func<F: Fn(&str)>(&self, func: F) -> Result<()> {
-        let func = Box::new(func);
+        check_transient_drop::<F>("collation needed callback")?;
+        let func = Box::into_raw(Box::new(func));
         let guard = self.lock();
         unsafe {
-            Error::from_sqlite_desc(
-                ffi::sqlite3_collation_needed(
-                    self.as_mut_ptr(),
-                    Box::into_raw(func) as _,
-                    Some(stubs::collation_needed::<F>),
-                ),
-                guard,
-            )
+            let rc = ffi::sqlite3_collation_needed(
+                self.as_mut_ptr(),
+                func as _,
+                Some(stubs::collation_needed::<F>),
+            );
+            if rc != ffi::SQLITE_OK {
+                drop(Box::from_raw(func));
+                return Error::from_sqlite_desc(rc, guard);
+            }
+            let ret = self.set_slot(COLLATION_NEEDED_SLOT, Some(Box::from_raw(func)));
+            if ret.is_err() {
+                ffi::sqlite3_collation_needed(self.as_mut_ptr(), null_mut(), None);
+            }
+            ret
         }
     }
 }
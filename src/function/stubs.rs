@@ -3,10 +3,11 @@ use super::{
     *,
 };
 use std::{
+    borrow::Cow,
     cmp::Ordering,
     ffi::{c_void, CStr},
+    mem::{align_of, size_of},
     slice,
-    str::from_utf8_unchecked,
 };
 
 pub unsafe extern "C" fn call_scalar<'a, F>(
@@ -80,6 +81,37 @@ pub unsafe extern "C" fn aggregate_inverse<U, F: AggregateFunction<U>>(
     }
 }
 
+fn ordering_to_int(ord: Ordering) -> i32 {
+    match ord {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    }
+}
+
+unsafe fn collation_bytes<'a>(len: i32, bytes: *const c_void) -> &'a [u8] {
+    if len <= 0 || bytes.is_null() {
+        &[]
+    } else {
+        slice::from_raw_parts(bytes as *const u8, len as _)
+    }
+}
+
+fn utf16_units(bytes: &[u8]) -> Cow<'_, [u16]> {
+    let len = bytes.len() / size_of::<u16>();
+    if bytes.as_ptr().align_offset(align_of::<u16>()) == 0 {
+        // Safety: the pointer is aligned and the slice covers len complete code units.
+        Cow::Borrowed(unsafe { slice::from_raw_parts(bytes.as_ptr() as *const u16, len) })
+    } else {
+        Cow::Owned(
+            bytes
+                .chunks_exact(size_of::<u16>())
+                .map(|c| u16::from_ne_bytes([c[0], c[1]]))
+                .collect(),
+        )
+    }
+}
+
 pub unsafe extern "C" fn compare<F: Fn(&str, &str) -> Ordering>(
     func: *mut c_void,
     len_a: i32,
@@ -88,13 +120,44 @@ pub unsafe extern "C" fn compare<F: Fn(&str, &str) -> Ordering>(
     bytes_b: *const c_void,
 ) -> i32 {
     let func = &*(func as *const F);
-    let a = from_utf8_unchecked(slice::from_raw_parts(bytes_a as *const u8, len_a as _));
-    let b = from_utf8_unchecked(slice::from_raw_parts(bytes_b as *const u8, len_b as _));
-    match func(a, b) {
-        Ordering::Less => -1,
-        Ordering::Equal => 0,
-        Ordering::Greater => 1,
-    }
+    let a = collation_bytes(len_a, bytes_a);
+    let b = collation_bytes(len_b, bytes_b);
+    // Invalid UTF-8 sorts after all valid text, and byte-wise among itself. This keeps the
+    // ordering total and consistent without exposing invalid data as &str.
+    let ret = match (std::str::from_utf8(a), std::str::from_utf8(b)) {
+        (Ok(a), Ok(b)) => func(a, b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    };
+    ordering_to_int(ret)
+}
+
+pub unsafe extern "C" fn compare_raw<F: Fn(&[u8], &[u8]) -> Ordering>(
+    func: *mut c_void,
+    len_a: i32,
+    bytes_a: *const c_void,
+    len_b: i32,
+    bytes_b: *const c_void,
+) -> i32 {
+    let func = &*(func as *const F);
+    ordering_to_int(func(
+        collation_bytes(len_a, bytes_a),
+        collation_bytes(len_b, bytes_b),
+    ))
+}
+
+pub unsafe extern "C" fn compare_utf16<F: Fn(&[u16], &[u16]) -> Ordering>(
+    func: *mut c_void,
+    len_a: i32,
+    bytes_a: *const c_void,
+    len_b: i32,
+    bytes_b: *const c_void,
+) -> i32 {
+    let func = &*(func as *const F);
+    let a = utf16_units(collation_bytes(len_a, bytes_a));
+    let b = utf16_units(collation_bytes(len_b, bytes_b));
+    ordering_to_int(func(&a, &b))
 }
 
 pub unsafe extern "C" fn collation_needed<F: Fn(&str)>(
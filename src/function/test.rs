@@ -1,5 +1,8 @@
 #![cfg(all(test, feature = "static"))]
-use crate::test_helpers::prelude::*;
+use crate::{connection::SLOT_PREFIX, test_helpers::prelude::*};
+#[cfg(modern_sqlite)]
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
 
 struct Agg {
     sep: &'static str,
@@ -82,6 +85,124 @@ fn user_data_aggregate() -> Result<()> {
     Ok(())
 }
 
+#[derive(Default)]
+struct TypedSum {
+    count: usize,
+    total: i64,
+}
+
+impl TypedAggregateFunction<()> for TypedSum {
+    type Output = Option<i64>;
+
+    fn step(&mut self, _: &Context, args: &mut [&mut ValueRef]) -> Result<()> {
+        self.count += 1;
+        self.total += args[0].get_i64();
+        Ok(())
+    }
+
+    fn value(&self) -> Result<Self::Output> {
+        Ok((self.count > 0).then_some(self.total))
+    }
+
+    fn inverse(&mut self, _: &Context, args: &mut [&mut ValueRef]) -> Result<()> {
+        self.count -= 1;
+        self.total -= args[0].get_i64();
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct MovingAverage {
+    count: usize,
+    total: f64,
+}
+
+impl TypedAggregateFunction<()> for MovingAverage {
+    type Output = f64;
+
+    fn default_value(_: &()) -> Result<f64> {
+        Err(Error::Module("moving_avg() of no rows".to_owned()))
+    }
+
+    fn step(&mut self, _: &Context, args: &mut [&mut ValueRef]) -> Result<()> {
+        self.count += 1;
+        self.total += args[0].get_f64();
+        Ok(())
+    }
+
+    fn value(&self) -> Result<f64> {
+        Ok(self.total / self.count as f64)
+    }
+
+    fn inverse(&mut self, _: &Context, args: &mut [&mut ValueRef]) -> Result<()> {
+        self.count -= 1;
+        self.total -= args[0].get_f64();
+        Ok(())
+    }
+}
+
+#[test]
+fn typed_aggregate() -> Result<()> {
+    let h = TestHelpers::new();
+    let opts = FunctionOptions::default()
+        .set_deterministic(true)
+        .set_risk_level(RiskLevel::Innocuous)
+        .set_n_args(1);
+    h.db.create_aggregate_function::<_, TypedSum>("typed_sum", &opts, ())?;
+    h.db.create_aggregate_function::<_, MovingAverage>("moving_avg", &opts, ())?;
+
+    let (ret,): (Option<i64>,) = h.db.query_row_as(
+        "SELECT typed_sum(column1) FROM ( VALUES (1), (2), (3) )",
+        (),
+    )?;
+    assert_eq!(ret, Some(6));
+    let (ret,): (Option<i64>,) = h.db.query_row_as(
+        "SELECT typed_sum(column1) FROM ( VALUES (1) ) WHERE column1 > 1",
+        (),
+    )?;
+    assert_eq!(ret, None);
+    let err =
+        h.db.query_row_as::<(f64,), _>(
+            "SELECT moving_avg(column1) FROM ( VALUES (1) ) WHERE column1 > 1",
+            (),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Error::Sqlite(
+            ffi::SQLITE_ERROR,
+            Some("moving_avg() of no rows".to_owned())
+        )
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(modern_sqlite)]
+fn typed_aggregate_window() -> Result<()> {
+    let h = TestHelpers::new();
+    let opts = FunctionOptions::default()
+        .set_deterministic(true)
+        .set_risk_level(RiskLevel::Innocuous)
+        .set_n_args(1);
+    h.db.create_aggregate_function::<_, TypedSum>("typed_sum", &opts, ())?;
+    h.db.create_aggregate_function::<_, MovingAverage>("moving_avg", &opts, ())?;
+
+    let ret: Vec<(i64, f64)> =
+        h.db.prepare(
+            "SELECT typed_sum(column1) OVER w, moving_avg(column1) OVER w \
+             FROM ( VALUES (1), (2), (6), (4), (8) ) \
+             WINDOW w AS (ROWS 2 PRECEDING)",
+        )?
+        .query_as(())?
+        .collect()?;
+    assert_eq!(
+        ret,
+        vec![(1, 1.0), (3, 1.5), (9, 3.0), (12, 4.0), (18, 6.0)]
+    );
+    Ok(())
+}
+
 #[test]
 fn aux_data() -> Result<()> {
     let h = TestHelpers::new();
@@ -147,3 +268,754 @@ fn collation() -> Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn remove_collation() -> Result<()> {
+    let h = TestHelpers::new();
+    h.db.create_collation("rev", |a, b| b.cmp(a))?;
+    h.db.create_collation_utf16("rev", |a, b| b.cmp(a))?;
+    let collations = h.db.collations()?;
+    assert!(collations.contains(&"rev".to_owned()), "{collations:?}");
+    assert!(collations.contains(&"NOCASE".to_owned()), "{collations:?}");
+    let sql = "SELECT column1 FROM ( VALUES ('a'), ('b') ) ORDER BY column1 COLLATE rev";
+    let (first,): (String,) = h.db.query_row_as(sql, ())?;
+    assert_eq!(first, "b");
+
+    h.db.remove_collation("rev")?;
+    assert!(!h.db.collations()?.contains(&"rev".to_owned()));
+    let err = h.db.prepare(sql).map(|_| ()).unwrap_err();
+    assert_eq!(err.to_string(), "no such collation sequence: rev");
+    Ok(())
+}
+
+#[test]
+fn collation_needed_replace() -> Result<()> {
+    let h = TestHelpers::new();
+    let first = Rc::new(());
+    let second = Rc::new(());
+    let captured = first.clone();
+    h.db.set_collation_needed_func(move |_| {
+        let _ = &captured;
+    })?;
+    assert_eq!(Rc::strong_count(&first), 2);
+    let captured = second.clone();
+    h.db.set_collation_needed_func(move |_| {
+        let _ = &captured;
+    })?;
+    // The previous callback was dropped when it was replaced.
+    assert_eq!(Rc::strong_count(&first), 1);
+    assert_eq!(Rc::strong_count(&second), 2);
+    drop(h);
+    assert_eq!(Rc::strong_count(&second), 1);
+    Ok(())
+}
+
+#[test]
+fn callbacks_not_listed() -> Result<()> {
+    let h = TestHelpers::new();
+    h.db.set_collation_needed_func(|_| ())?;
+    h.db.set_progress_handler(1000, || false)?;
+    h.db.create_collation("sqlite3_ext_rev", |a, b| b.cmp(a))?;
+    let mut listed = vec![];
+    h.db.pragma_query("collation_list", |row| {
+        listed.push(row[1].get_str()?.to_owned());
+        Ok(())
+    })?;
+    // Before SQLite 3.44.0, the callbacks are held by collations, which are listed by the
+    // pragma but not by collations().
+    assert_eq!(
+        listed
+            .iter()
+            .filter(|n| n.starts_with("sqlite3_ext") && !n.starts_with(SLOT_PREFIX))
+            .collect::<Vec<_>>(),
+        ["sqlite3_ext_rev"]
+    );
+    let collations = h.db.collations()?;
+    assert!(collations.contains(&"sqlite3_ext_rev".to_owned()));
+    assert!(!collations.iter().any(|n| n.starts_with(SLOT_PREFIX)));
+    Ok(())
+}
+
+#[test]
+fn slot_collations_reserved() -> Result<()> {
+    let h = TestHelpers::new();
+    let state = Rc::new(());
+    let captured = state.clone();
+    h.db.set_busy_handler(Some(move |_| {
+        let _ = &captured;
+        false
+    }))?;
+    for name in ["sqlite3_ext:busy_handler", "SQLITE3_EXT:busy_handler"] {
+        match h.db.remove_collation(name) {
+            Err(Error::Sqlite(ffi::SQLITE_MISUSE, Some(_))) => (),
+            x => panic!("expected SQLITE_MISUSE, got {x:?}"),
+        }
+        match h.db.create_collation(name, |a, b| a.cmp(b)) {
+            Err(Error::Sqlite(ffi::SQLITE_MISUSE, Some(_))) => (),
+            x => panic!("expected SQLITE_MISUSE, got {x:?}"),
+        }
+    }
+    assert_eq!(Rc::strong_count(&state), 2);
+    h.db.set_busy_handler(None::<fn(i32) -> bool>)?;
+    assert_eq!(Rc::strong_count(&state), 1);
+    Ok(())
+}
+
+#[test]
+fn collation_utf16() -> Result<()> {
+    fn fold(c: char) -> char {
+        c.to_ascii_lowercase()
+    }
+    let values = "('b'), ('A'), ('é'), ('a_'), ('C'), ('Ä'), ('😀'), ('\u{fff0}')";
+    let query = |db: &Database, collation: &str| -> Result<Vec<String>> {
+        let sql =
+            format!("SELECT column1 FROM ( VALUES {values} ) ORDER BY column1 COLLATE {collation}");
+        db.prepare(&sql)?
+            .query(())?
+            .map(|row| Ok(row[0].get_str()?.to_owned()))
+            .collect()
+    };
+    let mut results = vec![];
+    for encoding in ["UTF-8", "UTF-16le", "UTF-16be"] {
+        let db = Database::open(":memory:")?;
+        db.execute(&format!("PRAGMA encoding = '{encoding}'"), ())?;
+        db.create_collation("nocase8", |a, b| {
+            a.chars().map(fold).cmp(b.chars().map(fold))
+        })?;
+        db.create_collation_utf16("nocase16", |a, b| {
+            let a = char::decode_utf16(a.iter().copied()).map(|c| fold(c.unwrap_or('\u{fffd}')));
+            let b = char::decode_utf16(b.iter().copied()).map(|c| fold(c.unwrap_or('\u{fffd}')));
+            a.cmp(b)
+        })?;
+        results.push(query(&db, "nocase8")?);
+        results.push(query(&db, "nocase16")?);
+    }
+    assert_eq!(
+        results[0],
+        vec!["A", "a_", "b", "C", "Ä", "é", "\u{fff0}", "😀"]
+    );
+    for r in &results[1..] {
+        assert_eq!(r, &results[0]);
+    }
+    Ok(())
+}
+
+#[test]
+fn text_encoding() -> Result<()> {
+    let h = TestHelpers::new();
+    for (name, encoding) in [
+        ("enc_utf8", TextEncoding::Utf8),
+        ("enc_utf16", TextEncoding::Utf16),
+        ("enc_utf16le", TextEncoding::Utf16le),
+        ("enc_utf16be", TextEncoding::Utf16be),
+    ] {
+        let opts = FunctionOptions::default()
+            .set_n_args(1)
+            .set_text_encoding(TextEncoding::Utf16be)
+            .set_text_encoding(encoding);
+        h.db.create_scalar_function(name, &opts, |c, a| {
+            c.set_result(format!("<{}>", a[0].get_str()?))
+        })?;
+        let ret: String =
+            h.db.query_row(&format!("SELECT {name}('héllo')"), (), |r| {
+                Ok(r[0].get_str()?.to_owned())
+            })?;
+        assert_eq!(ret, "<héllo>");
+    }
+    Ok(())
+}
+
+#[test]
+fn collation_invalid_utf8() -> Result<()> {
+    let h = TestHelpers::new();
+    h.db.create_collation("rev", |a, b| b.cmp(a))?;
+    h.db.create_collation_raw("rev_raw", |a, b| b.cmp(a))?;
+    h.db.execute("CREATE TABLE tbl (val BLOB)", ())?;
+    h.db.execute(
+        "INSERT INTO tbl VALUES ('a'), (CAST(x'ff01' AS TEXT)), ('c'), (CAST(x'c328' AS TEXT)), ('b')",
+        (),
+    )?;
+    let query = |collation: &str| -> Result<Vec<Vec<u8>>> {
+        h.db.prepare(&format!(
+            "SELECT CAST(val AS BLOB) FROM tbl ORDER BY val COLLATE {collation}"
+        ))?
+        .query(())?
+        .map(|row| Ok(row[0].get_blob()?.to_vec()))
+        .collect()
+    };
+    let expected: Vec<Vec<u8>> = vec![
+        b"c".to_vec(),
+        b"b".to_vec(),
+        b"a".to_vec(),
+        vec![0xc3, 0x28],
+        vec![0xff, 0x01],
+    ];
+    assert_eq!(query("rev")?, expected);
+    // The ordering is stable across repeated sorts.
+    assert_eq!(query("rev")?, expected);
+    assert_eq!(
+        query("rev_raw")?,
+        vec![
+            vec![0xff, 0x01],
+            vec![0xc3, 0x28],
+            b"c".to_vec(),
+            b"b".to_vec(),
+            b"a".to_vec(),
+        ]
+    );
+    Ok(())
+}
+
+fn reverse(data: &[u8]) -> Result<Blob> {
+    let mut ret = data.to_vec();
+    ret.reverse();
+    Ok(Blob::from(ret.as_slice()))
+}
+
+fn greet(name: Option<&str>, times: Option<i64>) -> Result<String> {
+    let name = name.unwrap_or("nobody");
+    Ok(vec![name; times.unwrap_or(1) as usize].join(" "))
+}
+
+#[test]
+fn typed_scalar() -> Result<()> {
+    let h = TestHelpers::new();
+    let opts = FunctionOptions::default().set_deterministic(true);
+    h.db.create_scalar_function_typed("reverse", &opts, reverse)?;
+    h.db.create_scalar_function_typed("greet", &opts, greet)?;
+    h.db.create_scalar_function_typed("half", &opts, |x: f64| Ok(x / 2.0))?;
+    h.db.create_scalar_function_typed("type_of", &opts, |v: &ValueRef| {
+        Ok(format!("{:?}", v.value_type()))
+    })?;
+    let ret: (Vec<u8>, String, String, f64, String) = h.db.query_row_as(
+        "SELECT reverse(x'010203'), greet('hi', 2), greet(NULL, NULL), half(3), type_of(NULL)",
+        (),
+    )?;
+    assert_eq!(
+        ret,
+        (
+            vec![3, 2, 1],
+            "hi hi".to_owned(),
+            "nobody".to_owned(),
+            1.5,
+            "Null".to_owned()
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn typed_scalar_null() -> Result<()> {
+    let h = TestHelpers::new();
+    let opts = FunctionOptions::default();
+    h.db.create_scalar_function_typed("plus", &opts, |a: i64, b: i64| Ok(a + b))?;
+    h.db.create_scalar_function_typed("len", &opts, |s: &str| Ok(s.len() as i64))?;
+    let ret: (Option<i64>, Option<i64>, Option<i64>, Option<i64>) = h.db.query_row_as(
+        "SELECT plus(1, 2), plus(1, NULL), len('abc'), len(NULL)",
+        (),
+    )?;
+    assert_eq!(ret, (Some(3), None, Some(3), None));
+    Ok(())
+}
+
+#[test]
+fn typed_scalar_arity() -> Result<()> {
+    let h = TestHelpers::new();
+    let opts = FunctionOptions::default().set_n_args(2);
+    let err =
+        h.db.create_scalar_function_typed("reverse", &opts, reverse)
+            .unwrap_err();
+    assert_eq!(
+        err,
+        Error::Module("reverse() takes 1 arguments but n_args is 2".to_owned())
+    );
+    h.db.create_scalar_function_typed("reverse", &FunctionOptions::default(), reverse)?;
+    let err =
+        h.db.query_row("SELECT reverse(1, 2)", (), |_| Ok(()))
+            .unwrap_err();
+    assert_eq!(
+        err,
+        Error::Sqlite(
+            ffi::SQLITE_ERROR,
+            Some("wrong number of arguments to function reverse()".to_owned())
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn db_id() -> Result<()> {
+    let h = TestHelpers::new();
+    let other = Database::open(":memory:")?;
+    let same = unsafe { Connection::from_ptr(h.db.as_mut_ptr()) };
+    assert_eq!(h.db.id(), same.id());
+    assert_ne!(h.db.id(), other.id());
+    assert!(*h.db == *same);
+    assert!(*h.db != *other);
+    let id = h.db.id();
+    h.db.create_scalar_function("check_id", &FunctionOptions::default(), move |c, _| {
+        assert_eq!(c.db_id(), id);
+        assert_eq!(c.db().id(), id);
+        c.set_result(())
+    })?;
+    h.db.query_row("SELECT check_id()", (), |_| Ok(()))?;
+    Ok(())
+}
+
+#[test]
+#[cfg(modern_sqlite)]
+fn subtype() -> Result<()> {
+    const TAG: u8 = b'J';
+    let h = TestHelpers::new();
+    let opts = FunctionOptions::default().set_returns_subtype(true);
+    h.db.create_scalar_function("tagged", &opts, |c, a| {
+        c.set_result_with_subtype(&*a[0], TAG)
+    })?;
+    let opts = FunctionOptions::default().set_reads_subtype(true);
+    h.db.create_scalar_function("is_tagged", &opts, |c, a| {
+        c.set_result(a[0].subtype() == TAG)
+    })?;
+    let ret: (bool, bool, bool) = h.db.query_row_as(
+        "SELECT is_tagged(tagged('{}')), is_tagged('{}'), is_tagged(tagged(1) + 1)",
+        (),
+    )?;
+    assert_eq!(ret, (true, false, false));
+    Ok(())
+}
+
+fn closure_sum(db: &Connection, name: &str, window: bool) -> Result<()> {
+    let builder = db
+        .create_window_function::<i64>(name, &FunctionOptions::default().set_n_args(1))
+        .step(|acc, args| {
+            *acc += args[0].get_i64();
+            Ok(())
+        })
+        .value(|acc, ctx| ctx.set_result(*acc));
+    match window {
+        true => builder
+            .inverse(|acc, args| {
+                *acc -= args[0].get_i64();
+                Ok(())
+            })
+            .finalize(),
+        false => builder.finalize(),
+    }
+}
+
+#[test]
+fn closure_aggregate() -> Result<()> {
+    let h = TestHelpers::new();
+    closure_sum(&h.db, "legacy_sum", false)?;
+    closure_sum(&h.db, "window_sum", true)?;
+    h.db.execute("CREATE TABLE tbl (grp, val)", ())?;
+    h.db.execute(
+        "INSERT INTO tbl VALUES (1, 1), (2, 10), (1, 2), (3, 100), (2, 20), (1, 3)",
+        (),
+    )?;
+    for func in ["legacy_sum", "window_sum"] {
+        // Each group, and each call within a group, has its own state.
+        let ret: Vec<(i64, i64, i64)> =
+            h.db.prepare(&format!(
+                "SELECT grp, {func}(val), {func}(-val) FROM tbl GROUP BY grp ORDER BY grp"
+            ))?
+            .query_as(())?
+            .collect()?;
+        assert_eq!(
+            ret,
+            vec![(1, 6, -6), (2, 30, -30), (3, 100, -100)],
+            "{func}"
+        );
+        let (empty,): (i64,) =
+            h.db.query_row_as(&format!("SELECT {func}(val) FROM tbl WHERE 0"), ())?;
+        assert_eq!(empty, 0, "{func}");
+    }
+    let err =
+        h.db.create_window_function::<i64>("incomplete", &FunctionOptions::default())
+            .value(|acc, ctx| ctx.set_result(*acc))
+            .finalize()
+            .unwrap_err();
+    assert_eq!(
+        err,
+        Error::Module("incomplete() requires both step and value".to_owned())
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(modern_sqlite)]
+fn closure_aggregate_window() -> Result<()> {
+    let h = TestHelpers::new();
+    closure_sum(&h.db, "legacy_sum", false)?;
+    closure_sum(&h.db, "window_sum", true)?;
+    h.db.execute("CREATE TABLE tbl (val)", ())?;
+    h.db.execute("INSERT INTO tbl VALUES (1), (2), (3), (4), (5)", ())?;
+    let sql = |func: &str| format!("SELECT {func}(val) OVER (ROWS 1 PRECEDING) FROM tbl");
+    let ret: Vec<i64> =
+        h.db.prepare(&sql("window_sum"))?
+            .query(())?
+            .map(|row| Ok(row[0].get_i64()))
+            .collect()?;
+    assert_eq!(ret, vec![1, 3, 5, 7, 9]);
+    let err = h.db.prepare(&sql("legacy_sum")).unwrap_err();
+    assert!(err.to_string().contains("legacy_sum"), "{err}");
+    Ok(())
+}
+
+#[test]
+fn extract_args() -> Result<()> {
+    let h = TestHelpers::new();
+    let opts = FunctionOptions::default();
+    h.db.create_scalar_function("describe", &opts, |ctx, args| {
+        let (a, b, c) = args.extract::<(i64, Option<&str>, f64)>()?;
+        ctx.set_result(format!("{a} {b:?} {c}"))
+    })?;
+    h.db.create_scalar_function("join_all", &opts, |ctx, args| {
+        let (sep, rest) = args.extract::<(&str, Rest)>()?;
+        let sep = sep.to_owned();
+        ctx.set_result(rest.extract_each::<String>()?.join(&sep))
+    })?;
+    let ret: (String, String, String, String) = h.db.query_row_as(
+        "SELECT describe(1, 'a', 2.5), describe(1, NULL, 2), join_all('-', 'a', 'b', 'c'), join_all(',')",
+        (),
+    )?;
+    assert_eq!(
+        ret,
+        (
+            "1 Some(\"a\") 2.5".to_owned(),
+            "1 None 2".to_owned(),
+            "a-b-c".to_owned(),
+            "".to_owned()
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn extract_args_errors() -> Result<()> {
+    let h = TestHelpers::new();
+    let opts = FunctionOptions::default();
+    h.db.create_scalar_function("describe", &opts, |ctx, args| {
+        let (a, b, c) = args.extract::<(i64, Option<&str>, f64)>()?;
+        ctx.set_result(format!("{a} {b:?} {c}"))
+    })?;
+    h.db.create_scalar_function("join_all", &opts, |ctx, args| {
+        let (sep, rest) = args.extract::<(&str, Rest)>()?;
+        let sep = sep.to_owned();
+        ctx.set_result(rest.extract_each::<String>()?.join(&sep))
+    })?;
+    let cases = [
+        (
+            "SELECT describe(1, 'a')",
+            "expected 3 arguments but 2 were given",
+        ),
+        (
+            "SELECT join_all()",
+            "expected at least 1 argument but 0 were given",
+        ),
+        (
+            "SELECT describe(NULL, 'a', 1.0)",
+            "argument 1: expected integer, got NULL",
+        ),
+        (
+            "SELECT describe(1, x'00', 1.0)",
+            "argument 2: expected text, got blob",
+        ),
+        (
+            "SELECT describe(1, 'a', 'b')",
+            "argument 3: expected real, got text",
+        ),
+        (
+            "SELECT join_all('-', 'a', 2)",
+            "argument 3: expected text, got integer",
+        ),
+    ];
+    for (sql, msg) in cases {
+        let err = h.db.query_row(sql, (), |_| Ok(())).unwrap_err();
+        assert_eq!(err.to_string(), msg, "{sql}");
+    }
+    Ok(())
+}
+
+#[test]
+fn extract_args_aggregate() -> Result<()> {
+    let h = TestHelpers::new();
+    let opts = FunctionOptions::default().set_n_args(2);
+    h.db.create_window_function::<f64>("weighted_sum", &opts)
+        .step(|acc, args| {
+            let (value, weight) = args.extract::<(f64, Option<f64>)>()?;
+            *acc += value * weight.unwrap_or(1.0);
+            Ok(())
+        })
+        .value(|acc, ctx| ctx.set_result(*acc))
+        .finalize()?;
+    let (ret,): (f64,) = h.db.query_row_as(
+        "SELECT weighted_sum(column1, column2) FROM ( VALUES (1, 2), (3, NULL), (4, 0.5) )",
+        (),
+    )?;
+    assert_eq!(ret, 7.0);
+    Ok(())
+}
+
+#[test]
+fn registration_validation() -> Result<()> {
+    let h = TestHelpers::new();
+    let noop = |_: &Context, _: &mut [&mut ValueRef]| Ok(());
+    let err =
+        h.db.create_scalar_function(&"f".repeat(256), &FunctionOptions::default(), noop)
+            .unwrap_err();
+    assert_eq!(err, SQLITE_MISUSE);
+    assert!(err
+        .to_string()
+        .starts_with("function name is longer than 255 bytes"));
+    h.db.create_scalar_function(&"f".repeat(255), &FunctionOptions::default(), noop)?;
+
+    h.db.set_limit(Limit::FunctionArg, 2);
+    assert_eq!(h.db.limit(Limit::FunctionArg), 2);
+    let opts = FunctionOptions::default().set_n_args(3);
+    let err =
+        h.db.create_scalar_function("three", &opts, noop)
+            .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "three() takes 3 arguments, but SQLITE_LIMIT_FUNCTION_ARG is 2"
+    );
+    let err =
+        h.db.create_window_function::<i64>("three", &opts)
+            .step(|_, _| Ok(()))
+            .value(|_, _| Ok(()))
+            .finalize()
+            .unwrap_err();
+    assert_eq!(err, SQLITE_MISUSE);
+    Ok(())
+}
+
+#[test]
+#[cfg(modern_sqlite)]
+fn registration_shadow_builtin() -> Result<()> {
+    let h = TestHelpers::new();
+    let opts = FunctionOptions::default()
+        .set_n_args(1)
+        .set_check_shadowing(true);
+    let shout = |ctx: &Context, args: &mut [&mut ValueRef]| {
+        ctx.set_result(format!("{}!", args[0].get_str()?))
+    };
+    let err =
+        h.db.create_scalar_function("UPPER", &opts, shout)
+            .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "UPPER() with 1 arguments would shadow a built-in function"
+    );
+    let err =
+        h.db.create_aggregate_function::<_, TypedSum>("sum", &opts, ())
+            .unwrap_err();
+    assert_eq!(err, SQLITE_MISUSE);
+
+    // A different number of arguments does not shadow the built-in function.
+    h.db.create_scalar_function("upper", &opts.clone().set_n_args(2), shout)?;
+    let (ret,): (String,) = h.db.query_row_as("SELECT upper('a')", ())?;
+    assert_eq!(ret, "A");
+
+    h.db.create_scalar_function("upper", &opts.set_check_shadowing(false), shout)?;
+    let (ret,): (String,) = h.db.query_row_as("SELECT upper('a')", ())?;
+    assert_eq!(ret, "a!");
+    Ok(())
+}
+
+#[cfg(modern_sqlite)]
+struct Shared<'a> {
+    prefixes: &'a RefCell<Vec<String>>,
+    drops: &'a Cell<usize>,
+}
+
+#[cfg(modern_sqlite)]
+impl Drop for Shared<'_> {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+    }
+}
+
+#[cfg(modern_sqlite)]
+struct Prefixed<'a> {
+    prefixes: &'a RefCell<Vec<String>>,
+    acc: Vec<String>,
+}
+
+#[cfg(modern_sqlite)]
+impl<'a> FromUserData<Shared<'a>> for Prefixed<'a> {
+    fn from_user_data(shared: &Shared<'a>) -> Self {
+        Prefixed {
+            prefixes: shared.prefixes,
+            acc: vec![],
+        }
+    }
+}
+
+#[cfg(modern_sqlite)]
+impl<'a> AggregateFunction<Shared<'a>> for Prefixed<'a> {
+    fn step(&mut self, _: &Context, args: &mut [&mut ValueRef]) -> Result<()> {
+        let prefix = self.prefixes.borrow().join("");
+        self.acc.push(format!("{prefix}{}", args[0].get_str()?));
+        Ok(())
+    }
+
+    fn value(&self, c: &Context) -> Result<()> {
+        c.set_result(self.acc.join(","))
+    }
+
+    fn inverse(&mut self, _: &Context, _: &mut [&mut ValueRef]) -> Result<()> {
+        self.acc.remove(0);
+        Ok(())
+    }
+}
+
+#[test]
+#[cfg(modern_sqlite)] // Destructors require SQLite 3.7.3.
+fn aggregate_function_object() -> Result<()> {
+    let prefixes = RefCell::new(vec!["x".to_owned()]);
+    let drops = Cell::new(0);
+    let shared = || Shared {
+        prefixes: &prefixes,
+        drops: &drops,
+    };
+    let opts = FunctionOptions::default().set_n_args(1);
+    {
+        let h = TestHelpers::new();
+        h.db.create_aggregate_function_object::<_, Prefixed>("prefixed", &opts, shared())?;
+        let sql = "SELECT prefixed(column1) FROM ( VALUES ('a'), ('b') )";
+        let (ret,): (String,) = h.db.query_row_as(sql, ())?;
+        assert_eq!(ret, "xa,xb");
+        prefixes.borrow_mut().push("y".to_owned());
+        let (ret,): (String,) = h.db.query_row_as(sql, ())?;
+        assert_eq!(ret, "xya,xyb");
+        assert_eq!(drops.get(), 0);
+
+        h.db.remove_function("prefixed", 1)?;
+        assert_eq!(drops.get(), 1);
+
+        h.db.create_aggregate_function_object::<_, Prefixed>("prefixed", &opts, shared())?;
+        assert_eq!(drops.get(), 1);
+    }
+    assert_eq!(drops.get(), 2);
+    Ok(())
+}
+
+#[test]
+#[cfg(modern_sqlite)]
+fn db_execute_guarded() -> Result<()> {
+    let h = TestHelpers::new();
+    for sql in [
+        "CREATE TABLE t (x)",
+        "INSERT INTO t VALUES (1), (2)",
+        "CREATE TABLE log (x)",
+        "CREATE TABLE other (x)",
+    ] {
+        h.db.execute(sql, ())?;
+    }
+    let busy = Rc::new(Cell::new(0));
+    let opts = FunctionOptions::default()
+        .set_risk_level(RiskLevel::DirectOnly)
+        .set_n_args(-1);
+    h.db.create_scalar_function("run", &opts, {
+        let busy = busy.clone();
+        move |c, args| {
+            busy.set(c.db().busy_statements());
+            let (sql, rest) = args.split_first_mut().unwrap();
+            let sql = sql.get_str()?.to_owned();
+            c.set_result(c.db_execute_guarded(&sql, rest)?)
+        }
+    })?;
+
+    let sql = "SELECT SUM(run('INSERT INTO log VALUES (?)', x)) FROM t";
+    let (changes,): (i64,) = h.db.query_row_as(sql, ())?;
+    assert_eq!(changes, 2);
+    assert_eq!(busy.get(), 1);
+    assert_eq!(h.db.busy_statements(), 0);
+    let (count,): (i64,) = h.db.query_row_as("SELECT COUNT(*) FROM log", ())?;
+    assert_eq!(count, 2);
+    // The nested transaction was committed.
+    assert!(h.db.transaction(TransactionType::Deferred).is_ok());
+
+    let sql = "SELECT run('DROP TABLE other') FROM t";
+    let err = h.db.query_row(sql, (), |_| Ok(())).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "cannot execute \"DROP TABLE other\" while the invoking statement is running \
+         (database table is locked); invoking statement: SELECT run('DROP TABLE other') FROM t"
+    );
+    Ok(())
+}
+
+#[test]
+fn borrowed_results() -> Result<()> {
+    let h = TestHelpers::new();
+    let opts = FunctionOptions::default()
+        .set_deterministic(true)
+        .set_n_args(3);
+    let range = |a: &[&mut ValueRef]| a[1].get_i64() as usize..a[2].get_i64() as usize;
+    // byte_substr(X, START, END) returns bytes START..END of X, in three ways.
+    h.db.create_scalar_function("substr_owned", &opts, move |c, a| {
+        let range = range(a);
+        c.set_result(a[0].get_str()?[range].to_owned())
+    })?;
+    h.db.create_scalar_function("substr_borrowed", &opts, move |c, a| {
+        let range = range(a);
+        c.set_result_borrowed(&a[0].get_str()?[range])
+    })?;
+    h.db.create_scalar_function("substr_slice", &opts, move |c, a| {
+        let range = range(a);
+        c.set_result_from_arg_slice(a[0], range)
+    })?;
+
+    let large = "abcdé".repeat(200_000);
+    let mut stmt = h.db.prepare(
+        "SELECT substr_owned(?1, ?2, ?3), substr_borrowed(?1, ?2, ?3), substr_slice(?1, ?2, ?3)",
+    )?;
+    for (start, end) in [(0, large.len()), (4, 600_000), (12, 12)] {
+        let ret = stmt.query_row(params![large.as_str(), start as i64, end as i64], |r| {
+            Ok([r[0].to_owned()?, r[1].to_owned()?, r[2].to_owned()?])
+        })?;
+        let expected = Value::Text(large[start..end].to_owned());
+        assert_eq!(ret, [expected.clone(), expected.clone(), expected]);
+    }
+
+    // The type of the argument is kept.
+    let ret =
+        h.db.query_row("SELECT substr_slice(x'00010203', 1, 3)", (), |r| {
+            r[0].to_owned()
+        })?;
+    assert_eq!(ret, Value::Blob(Blob::from([1, 2])));
+    let ret =
+        h.db.query_row("SELECT typeof(substr_slice('', 0, 0))", (), |r| {
+            Ok(r[0].get_str()?.to_owned())
+        })?;
+    assert_eq!(ret, "text");
+
+    let err = |sql: &str| h.db.query_row(sql, (), |_| Ok(())).unwrap_err();
+    let e = err("SELECT substr_slice('abc', 2, 4)");
+    assert_eq!(e, Error::Sqlite(ffi::SQLITE_RANGE, None));
+    assert_eq!(
+        e.to_string(),
+        "range 2..4 is out of bounds for a value of 3 bytes"
+    );
+    // 'é' is two bytes, so the range splits it.
+    let e = err("SELECT substr_slice('é', 0, 1)");
+    assert_eq!(e, Error::Sqlite(ffi::SQLITE_ERROR, None));
+    assert!(e.to_string().contains("utf-8"), "{e}");
+    assert_eq!(err("SELECT substr_slice(12, 0, 1)"), SQLITE_MISMATCH);
+
+    // Text stored as UTF-16 is sliced by its UTF-8 bytes.
+    let db = Database::open(":memory:")?;
+    db.execute("PRAGMA encoding = 'UTF-16le'", ())?;
+    db.create_scalar_function("substr_slice", &opts, move |c, a| {
+        let range = range(a);
+        c.set_result_from_arg_slice(a[0], range)
+    })?;
+    db.execute("CREATE TABLE tbl (x)", ())?;
+    db.execute("INSERT INTO tbl VALUES ('abcdé')", ())?;
+    let ret = db.query_row("SELECT substr_slice(x, 2, 6) FROM tbl", (), |r| {
+        Ok(r[0].get_str()?.to_owned())
+    })?;
+    assert_eq!(ret, "cdé");
+    Ok(())
+}
@@ -0,0 +1,242 @@
+//! Scalar functions with typed arguments.
+//!
+//! See [Connection::create_scalar_function_typed].
+use super::{Context, FunctionOptions, ScalarClosure, ToContextResult};
+use crate::{types::*, value::*, Connection};
+use std::marker::PhantomData;
+
+/// A type which can be extracted by value from an argument of a
+/// [typed scalar function](Connection::create_scalar_function_typed).
+pub trait FromFunctionArg: Sized {
+    /// Whether SQL NULL is passed to the function. When this is false, a NULL argument
+    /// causes the function to return NULL without being called.
+    const ACCEPTS_NULL: bool = false;
+
+    /// Extract the argument from the value.
+    fn from_arg(value: &mut ValueRef) -> Result<Self>;
+}
+
+/// A type which can be borrowed from an argument of a
+/// [typed scalar function](Connection::create_scalar_function_typed).
+pub trait FromFunctionArgRef {
+    /// Whether SQL NULL is passed to the function. When this is false, a NULL argument
+    /// causes the function to return NULL without being called.
+    const ACCEPTS_NULL: bool = false;
+
+    /// Borrow the argument from the value.
+    fn from_arg_ref(value: &mut ValueRef) -> Result<&Self>;
+}
+
+impl FromFunctionArg for i64 {
+    fn from_arg(value: &mut ValueRef) -> Result<Self> {
+        Ok(value.get_i64())
+    }
+}
+
+impl FromFunctionArg for f64 {
+    fn from_arg(value: &mut ValueRef) -> Result<Self> {
+        Ok(value.get_f64())
+    }
+}
+
+/// NULL is passed to the function as None.
+impl<T: FromFunctionArg> FromFunctionArg for Option<T> {
+    const ACCEPTS_NULL: bool = true;
+
+    fn from_arg(value: &mut ValueRef) -> Result<Self> {
+        if value.is_null() {
+            Ok(None)
+        } else {
+            T::from_arg(value).map(Some)
+        }
+    }
+}
+
+impl FromFunctionArgRef for str {
+    fn from_arg_ref(value: &mut ValueRef) -> Result<&Self> {
+        value.get_str()
+    }
+}
+
+impl FromFunctionArgRef for [u8] {
+    fn from_arg_ref(value: &mut ValueRef) -> Result<&Self> {
+        value.get_blob()
+    }
+}
+
+/// The value is passed to the function unchanged, including NULL.
+impl FromFunctionArgRef for ValueRef {
+    const ACCEPTS_NULL: bool = true;
+
+    fn from_arg_ref(value: &mut ValueRef) -> Result<&Self> {
+        Ok(value)
+    }
+}
+
+#[doc(hidden)]
+pub struct ByValue<T>(PhantomData<T>);
+
+#[doc(hidden)]
+pub struct ByRef<T: ?Sized>(PhantomData<T>);
+
+#[doc(hidden)]
+pub struct OptRef<T: ?Sized>(PhantomData<T>);
+
+/// A Rust function which can be registered using [Connection::create_scalar_function_typed].
+///
+/// This trait is implemented for functions and closures of up to 4 parameters, where each
+/// parameter is one of:
+///
+/// - `T` where `T` implements [FromFunctionArg], for example `i64`, `f64`, or `Option<i64>`.
+/// - `&T` where `T` implements [FromFunctionArgRef], for example `&str`, `&[u8]`, or
+///   `&ValueRef`.
+/// - `Option<&T>` where `T` implements [FromFunctionArgRef].
+///
+/// The function must return `Result<R>` where `R` implements [ToContextResult].
+///
+/// The `Args` parameter is only used to distinguish between the implementations and should
+/// be left to type inference.
+pub trait TypedScalarFunction<Args>: 'static {
+    #[doc(hidden)]
+    const N_ARGS: usize;
+
+    #[doc(hidden)]
+    fn call_typed(&self, name: &str, context: &Context, args: &mut [&mut ValueRef]) -> Result<()>;
+}
+
+fn check_arity(name: &str, expected: usize, actual: usize) -> Result<()> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(Error::Module(format!(
+            "{name}() takes {expected} arguments but {actual} were given"
+        )))
+    }
+}
+
+macro_rules! typed_extract {
+    (value $ty:ident, $v:expr) => {{
+        let v: &mut ValueRef = $v;
+        if !<$ty as FromFunctionArg>::ACCEPTS_NULL && v.is_null() {
+            return Ok(());
+        }
+        <$ty as FromFunctionArg>::from_arg(v)?
+    }};
+    (ref $ty:ident, $v:expr) => {{
+        let v: &mut ValueRef = $v;
+        if !<$ty as FromFunctionArgRef>::ACCEPTS_NULL && v.is_null() {
+            return Ok(());
+        }
+        <$ty as FromFunctionArgRef>::from_arg_ref(v)?
+    }};
+    (optref $ty:ident, $v:expr) => {{
+        let v: &mut ValueRef = $v;
+        if v.is_null() {
+            None
+        } else {
+            Some(<$ty as FromFunctionArgRef>::from_arg_ref(v)?)
+        }
+    }};
+}
+
+macro_rules! typed_scalar_function {
+    // Every parameter has been assigned a kind, so emit the implementation.
+    (@expand [$(($marker:ty, $param:ty, [$($bound:tt)*], $kind:ident $ty:ident $arg:ident))*] []) => {
+        impl<F, R, $($ty: ?Sized),*> TypedScalarFunction<($($marker,)*)> for F
+        where
+            F: for<'a> Fn($($param),*) -> Result<R> + 'static,
+            R: ToContextResult,
+            $($($bound)*,)*
+        {
+            const N_ARGS: usize = 0 $(+ typed_scalar_function!(@one $ty))*;
+
+            #[allow(unused_variables, unused_mut)]
+            fn call_typed(
+                &self,
+                name: &str,
+                context: &Context,
+                args: &mut [&mut ValueRef],
+            ) -> Result<()> {
+                check_arity(name, Self::N_ARGS, args.len())?;
+                let mut args = args.iter_mut();
+                $(let $arg = typed_extract!($kind $ty, &mut **args.next().unwrap());)*
+                context.set_result(self($($arg),*)?)
+            }
+        }
+    };
+    (@one $ty:ident) => {
+        1
+    };
+    // Assign each possible kind to the next parameter.
+    (@expand [$($done:tt)*] [($ty:ident $arg:ident) $($rest:tt)*]) => {
+        typed_scalar_function!(@expand [$($done)* (ByValue<$ty>, $ty, [$ty: FromFunctionArg], value $ty $arg)] [$($rest)*]);
+        typed_scalar_function!(@expand [$($done)* (ByRef<$ty>, &'a $ty, [$ty: FromFunctionArgRef], ref $ty $arg)] [$($rest)*]);
+        typed_scalar_function!(@expand [$($done)* (OptRef<$ty>, Option<&'a $ty>, [$ty: FromFunctionArgRef], optref $ty $arg)] [$($rest)*]);
+    };
+    ($(($ty:ident $arg:ident))*) => {
+        typed_scalar_function!(@expand [] [$(($ty $arg))*]);
+    };
+}
+
+typed_scalar_function!();
+typed_scalar_function!((A a));
+typed_scalar_function!((A a)(B b));
+typed_scalar_function!((A a)(B b)(C c));
+typed_scalar_function!((A a)(B b)(C c)(D d));
+
+impl Connection {
+    /// Create a new scalar function from a Rust function with typed parameters.
+    ///
+    /// The arguments are extracted according to the parameter types of the function, and
+    /// the returned value is assigned to the result using [ToContextResult]. See
+    /// [TypedScalarFunction] for the supported parameter types. If any parameter does not
+    /// accept NULL and the corresponding argument is NULL, the function is not called and
+    /// SQL NULL is returned, so `fn f(x: i64)` is a strict function and `fn f(x:
+    /// Option<i64>)` is not.
+    ///
+    /// If the n_args of the options is -1, it is set to the number of parameters of the
+    /// function. Otherwise, it must match the number of parameters of the function.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::{function::*, *};
+    ///
+    /// #[sqlite3_ext_fn(n_args=1, deterministic)]
+    /// fn checksum(data: &[u8]) -> Result<i64> {
+    ///     Ok(data.iter().map(|b| *b as i64).sum())
+    /// }
+    ///
+    /// fn init(db: &Connection) -> Result<()> {
+    ///     db.create_scalar_function_typed("checksum", &CHECKSUM_OPTS, checksum)
+    /// }
+    /// ```
+    pub fn create_scalar_function_typed<Args, F>(
+        &self,
+        name: &str,
+        opts: &FunctionOptions,
+        func: F,
+    ) -> Result<()>
+    where
+        F: TypedScalarFunction<Args>,
+    {
+        let n_args = F::N_ARGS as i32;
+        let opts = match opts.n_args {
+            -1 => opts.clone().set_n_args(n_args),
+            x if x == n_args => opts.clone(),
+            x => {
+                return Err(Error::Module(format!(
+                    "{name}() takes {n_args} arguments but n_args is {x}"
+                )))
+            }
+        };
+        let owned_name = name.to_owned();
+        self.create_scalar_function_object(
+            name,
+            &opts,
+            ScalarClosure(move |ctx: &Context, args: &mut [&mut ValueRef]| {
+                func.call_typed(&owned_name, ctx, args)
+            }),
+        )
+    }
+}
@@ -0,0 +1,169 @@
+//! Aggregate functions built from closures.
+//!
+//! See [Connection::create_window_function].
+use super::{AggregateFunction, Context, FromUserData, FunctionOptions};
+use crate::{types::*, value::*, Connection};
+use std::rc::Rc;
+
+type StepFn<S> = dyn Fn(&mut S, &mut [&mut ValueRef]) -> Result<()>;
+type ValueFn<S> = dyn Fn(&S, &Context) -> Result<()>;
+
+/// Builder for an aggregate function made from closures. See
+/// [Connection::create_window_function].
+#[must_use = "the function is only registered when finalize is called"]
+pub struct WindowFunctionBuilder<'db, S> {
+    db: &'db Connection,
+    name: String,
+    opts: FunctionOptions,
+    step: Option<Box<StepFn<S>>>,
+    value: Option<Box<ValueFn<S>>>,
+    inverse: Option<Box<StepFn<S>>>,
+}
+
+impl<S: Default + 'static> WindowFunctionBuilder<'_, S> {
+    /// Set the closure which adds a new row to the aggregate. This is required.
+    pub fn step<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut S, &mut [&mut ValueRef]) -> Result<()> + 'static,
+    {
+        self.step = Some(Box::new(f));
+        self
+    }
+
+    /// Set the closure which assigns the current value of the aggregate to the context using
+    /// [Context::set_result]. This is required.
+    ///
+    /// The closure is also used to produce the final result, and the result for an empty
+    /// set of rows, in which case it receives `S::default()`.
+    pub fn value<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&S, &Context) -> Result<()> + 'static,
+    {
+        self.value = Some(Box::new(f));
+        self
+    }
+
+    /// Set the closure which removes the oldest presently aggregated row. The args are the
+    /// same that were passed to the step closure when this row was added.
+    ///
+    /// If this is not set, the function is registered using
+    /// [Connection::create_legacy_aggregate_function], so it cannot be used as a window
+    /// function.
+    pub fn inverse<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut S, &mut [&mut ValueRef]) -> Result<()> + 'static,
+    {
+        self.inverse = Some(Box::new(f));
+        self
+    }
+
+    /// Register the function with the connection.
+    pub fn finalize(self) -> Result<()> {
+        let (step, value) = match (self.step, self.value) {
+            (Some(step), Some(value)) => (step, value),
+            _ => {
+                return Err(Error::Module(format!(
+                    "{}() requires both step and value",
+                    self.name
+                )))
+            }
+        };
+        let window = self.inverse.is_some();
+        let funcs = Rc::new(ClosureFunctions {
+            step,
+            value,
+            inverse: self.inverse,
+        });
+        if window {
+            self.db
+                .create_aggregate_function::<_, ClosureAggregate<S>>(&self.name, &self.opts, funcs)
+        } else {
+            self.db
+                .create_legacy_aggregate_function::<_, ClosureAggregate<S>>(
+                    &self.name, &self.opts, funcs,
+                )
+        }
+    }
+}
+
+struct ClosureFunctions<S> {
+    step: Box<StepFn<S>>,
+    value: Box<ValueFn<S>>,
+    inverse: Option<Box<StepFn<S>>>,
+}
+
+struct ClosureAggregate<S> {
+    state: S,
+    funcs: Rc<ClosureFunctions<S>>,
+}
+
+impl<S: Default> FromUserData<Rc<ClosureFunctions<S>>> for ClosureAggregate<S> {
+    fn from_user_data(funcs: &Rc<ClosureFunctions<S>>) -> Self {
+        ClosureAggregate {
+            state: S::default(),
+            funcs: funcs.clone(),
+        }
+    }
+}
+
+impl<S: Default> AggregateFunction<Rc<ClosureFunctions<S>>> for ClosureAggregate<S> {
+    fn step(&mut self, _: &Context, args: &mut [&mut ValueRef]) -> Result<()> {
+        (self.funcs.step)(&mut self.state, args)
+    }
+
+    fn value(&self, context: &Context) -> Result<()> {
+        (self.funcs.value)(&self.state, context)
+    }
+
+    fn inverse(&mut self, _: &Context, args: &mut [&mut ValueRef]) -> Result<()> {
+        match &self.funcs.inverse {
+            Some(inverse) => inverse(&mut self.state, args),
+            None => Err(SQLITE_MISUSE),
+        }
+    }
+}
+
+impl Connection {
+    /// Create a new aggregate function from closures, without defining a type implementing
+    /// [AggregateFunction].
+    ///
+    /// The state of each invocation of the aggregate starts out as `S::default()`, and is
+    /// updated by the closures passed to the returned builder. The function is registered
+    /// when [finalize](WindowFunctionBuilder::finalize) is called. If no inverse closure is
+    /// provided, the function is registered as a legacy aggregate function, which cannot be
+    /// used as a window function.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::{function::*, *};
+    ///
+    /// fn init(db: &Connection) -> Result<()> {
+    ///     db.create_window_function::<i64>("my_sum", &FunctionOptions::default())
+    ///         .step(|acc, args| {
+    ///             *acc += args[0].get_i64();
+    ///             Ok(())
+    ///         })
+    ///         .inverse(|acc, args| {
+    ///             *acc -= args[0].get_i64();
+    ///             Ok(())
+    ///         })
+    ///         .value(|acc, ctx| ctx.set_result(*acc))
+    ///         .finalize()
+    /// }
+    /// ```
+    pub fn create_window_function<S: Default + 'static>(
+        &self,
+        name: &str,
+        opts: &FunctionOptions,
+    ) -> WindowFunctionBuilder<'_, S> {
+        WindowFunctionBuilder {
+            db: self,
+            name: name.to_owned(),
+            opts: opts.clone(),
+            step: None,
+            value: None,
+            inverse: None,
+        }
+    }
+}
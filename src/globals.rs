@@ -1,4 +1,6 @@
 use super::{ffi, sqlite3_match_version, sqlite3_require_version, types::*};
+#[cfg(any(test, feature = "testing"))]
+use std::cell::Cell;
 use std::{cmp::Ordering, ffi::CStr, str};
 
 /// The version of SQLite.
@@ -7,6 +9,22 @@ pub struct SqliteVersion;
 /// The version of SQLite. See [SqliteVersion] for details.
 pub static SQLITE_VERSION: SqliteVersion = SqliteVersion;
 
+#[cfg(any(test, feature = "testing"))]
+thread_local! {
+    static VERSION_OVERRIDE: Cell<Option<i32>> = const { Cell::new(None) };
+}
+
+/// Restores the previous version override when dropped, even if the function panicked.
+#[cfg(any(test, feature = "testing"))]
+struct RestoreOverride(Option<i32>);
+
+#[cfg(any(test, feature = "testing"))]
+impl Drop for RestoreOverride {
+    fn drop(&mut self) {
+        VERSION_OVERRIDE.with(|v| v.set(self.0));
+    }
+}
+
 impl SqliteVersion {
     /// Returns the numeric version of SQLite.
     ///
@@ -14,9 +32,34 @@ impl SqliteVersion {
     /// 1000000 + minor * 1000 + patch`. For example, SQLite version 3.8.2 is encoded as
     /// `3_008_002`.
     pub fn as_i32(&self) -> i32 {
+        #[cfg(any(test, feature = "testing"))]
+        if let Some(version) = VERSION_OVERRIDE.with(|v| v.get()) {
+            return version;
+        }
         unsafe { ffi::sqlite3_libversion_number() }
     }
 
+    /// Run the function while [as_i32](Self::as_i32) reports an older version of SQLite,
+    /// so that the runtime checks of [sqlite3_match_version] take their fallback paths.
+    /// This only affects the current thread, and is intended for testing the fallbacks.
+    ///
+    /// Requires the `testing` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the version is newer than the running version of SQLite, since pretending
+    /// that a missing function is available would be unsound.
+    #[doc(hidden)]
+    #[cfg(any(test, feature = "testing"))]
+    pub fn with_override<R>(&self, version: i32, f: impl FnOnce() -> R) -> R {
+        assert!(
+            version <= self.as_i32(),
+            "cannot override the SQLite version with a newer version"
+        );
+        let _restore = RestoreOverride(VERSION_OVERRIDE.with(|v| v.replace(Some(version))));
+        f()
+    }
+
     /// Returns the human-readable version of SQLite. Example: `"3.8.2"`.
     pub fn as_str(&self) -> &'static str {
         let ret = unsafe { CStr::from_ptr(ffi::sqlite3_libversion()) };
@@ -56,7 +99,7 @@ pub fn sqlite3_stricmp(a: &str, b: &str) -> Ordering {
             } else if rc > 0 {
                 Ordering::Greater
             } else {
-                Ordering::Equal
+                a.len().cmp(&b.len())
             }
         }
         _ => a
@@ -102,6 +145,57 @@ pub fn sqlite3_strlike(
     })
 }
 
+/// Process-wide counters which can be retrieved with [sqlite3_status]. See
+/// [the SQLite documentation](https://www.sqlite.org/c3ref/c_status_malloc_count.html) for
+/// details of each counter.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[repr(i32)]
+pub enum StatusOp {
+    /// The number of bytes of memory currently checked out by SQLite's allocator.
+    MemoryUsed = ffi::SQLITE_STATUS_MEMORY_USED,
+    /// The number of pages used out of the page cache memory pool.
+    PagecacheUsed = ffi::SQLITE_STATUS_PAGECACHE_USED,
+    /// The number of bytes of page cache allocations which could not be satisfied from the
+    /// page cache memory pool.
+    PagecacheOverflow = ffi::SQLITE_STATUS_PAGECACHE_OVERFLOW,
+    /// The size in bytes of the largest allocation requested. Only the highwater value is
+    /// meaningful.
+    MallocSize = ffi::SQLITE_STATUS_MALLOC_SIZE,
+    /// The deepest parser stack. Only meaningful if SQLite was compiled with
+    /// YYTRACKMAXSTACKDEPTH.
+    ParserStack = ffi::SQLITE_STATUS_PARSER_STACK,
+    /// The size in bytes of the largest page cache allocation requested. Only the highwater
+    /// value is meaningful.
+    PagecacheSize = ffi::SQLITE_STATUS_PAGECACHE_SIZE,
+    /// The number of separate memory allocations currently checked out.
+    MallocCount = ffi::SQLITE_STATUS_MALLOC_COUNT,
+}
+
+/// Retrieve the current and highwater values of a process-wide counter. If reset is true, the
+/// highwater value is reset to the current value after it is retrieved.
+///
+/// Counters which are not supported by the version of SQLite in use fail with
+/// [SQLITE_MISUSE](ffi::SQLITE_MISUSE). Memory counters are only maintained if memory
+/// statistics are enabled, which is the default.
+///
+/// On versions of SQLite before 3.10.0, values which do not fit in 32 bits are truncated.
+pub fn sqlite3_status(op: StatusOp, reset: bool) -> Result<(i64, i64)> {
+    sqlite3_match_version! {
+        3_010_000 => {
+            let (mut cur, mut hiwtr) = (0, 0);
+            let rc = unsafe { ffi::sqlite3_status64(op as _, &mut cur, &mut hiwtr, reset as _) };
+            Error::from_sqlite(rc)?;
+            Ok((cur, hiwtr))
+        }
+        _ => {
+            let (mut cur, mut hiwtr) = (0, 0);
+            let rc = unsafe { ffi::sqlite3_status(op as _, &mut cur, &mut hiwtr, reset as _) };
+            Error::from_sqlite(rc)?;
+            Ok((cur as _, hiwtr as _))
+        }
+    }
+}
+
 pub fn sqlite3_randomness(n: usize) -> Vec<u8> {
     let mut ret = vec![0; n];
     unsafe { ffi::sqlite3_randomness(n as _, ret.as_mut_ptr() as _) };
@@ -124,11 +218,23 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn override_restored_after_panic() {
+        let real = SqliteVersion.as_i32();
+        let ret = std::panic::catch_unwind(|| {
+            SqliteVersion.with_override(3_006_008, || panic!("failed while overridden"))
+        });
+        assert!(ret.is_err());
+        assert_eq!(SqliteVersion.as_i32(), real);
+    }
+
     #[test]
     fn strings() -> Result<()> {
         assert_eq!(sqlite3_stricmp("FOO", "bar"), Ordering::Greater);
         assert_eq!(sqlite3_stricmp("bar", "FOO"), Ordering::Less);
         assert_eq!(sqlite3_stricmp("bar", "BAR"), Ordering::Equal);
+        assert_eq!(sqlite3_stricmp("bar", "BARS"), Ordering::Less);
+        assert_eq!(sqlite3_stricmp("bars", "BAR"), Ordering::Greater);
         sqlite3_match_version! {
             3_007_017 => assert_eq!(sqlite3_strglob("a/**/b", "a/c/d/e/f/b"), Ok(true)),
             _ => (),
@@ -143,6 +249,15 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn status() -> Result<()> {
+        let (cur, hiwtr) = sqlite3_status(StatusOp::MemoryUsed, false)?;
+        assert!(hiwtr >= cur);
+        let (_, largest) = sqlite3_status(StatusOp::MallocSize, false)?;
+        assert!(largest >= 0);
+        Ok(())
+    }
+
     #[test]
     fn randomness() {
         let ret = sqlite3_randomness(32);
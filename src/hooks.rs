@@ -0,0 +1,292 @@
+//! Callbacks for observing changes to the database.
+//!
+//! The preupdate hook is invoked before each change to a rowid table, and provides access to
+//! the values of the row before and after the change. It requires the `preupdate_hook`
+//! feature, SQLite 3.13.0, and an SQLite library compiled with
+//! [SQLITE_ENABLE_PREUPDATE_HOOK](https://www.sqlite.org/compile.html#enable_preupdate_hook).
+//! Because it is not part of the extension API, it is only available when statically
+//! linking.
+#![cfg(feature = "preupdate_hook")]
+#![cfg_attr(docsrs, doc(cfg(feature = "preupdate_hook")))]
+
+#[cfg_attr(not(modern_sqlite), allow(unused_imports))]
+use crate::{
+    extension::check_transient_drop, ffi, sqlite3_require_version, types::*, value::*, Connection,
+};
+use std::{
+    ffi::{c_void, CStr},
+    os::raw::{c_char, c_int},
+    ptr::null_mut,
+};
+
+/// Key of the connection slot which owns the preupdate hook closure.
+#[cfg_attr(not(modern_sqlite), allow(dead_code))]
+const PREUPDATE_HOOK_SLOT: &str = "preupdate_hook";
+
+/// The kind of change being made to a row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreUpdateOp {
+    Insert,
+    Delete,
+    Update,
+}
+
+/// Describes a change which is about to be made to a row. See
+/// [Connection::set_preupdate_hook].
+pub struct PreUpdateCase<'a> {
+    db: *mut ffi::sqlite3,
+    op: PreUpdateOp,
+    db_name: &'a str,
+    table: &'a str,
+    old_rowid: i64,
+    new_rowid: i64,
+}
+
+impl PreUpdateCase<'_> {
+    /// The kind of change being made.
+    pub fn op(&self) -> PreUpdateOp {
+        self.op
+    }
+
+    /// The name of the database being changed, e.g. "main" or "temp".
+    pub fn db_name(&self) -> &str {
+        self.db_name
+    }
+
+    /// The name of the table being changed.
+    pub fn table(&self) -> &str {
+        self.table
+    }
+
+    /// The rowid of the row before the change. For an INSERT, this is meaningless.
+    pub fn old_rowid(&self) -> i64 {
+        self.old_rowid
+    }
+
+    /// The rowid of the row after the change. For a DELETE, this is meaningless.
+    pub fn new_rowid(&self) -> i64 {
+        self.new_rowid
+    }
+
+    /// The number of columns in the row being changed.
+    pub fn count(&self) -> usize {
+        unsafe { ffi::sqlite3_preupdate_count(self.db) as _ }
+    }
+
+    /// The depth of the trigger which caused the change. This is 0 for direct changes, 1
+    /// for changes made by a trigger fired by a direct change, and so on.
+    pub fn depth(&self) -> i32 {
+        unsafe { ffi::sqlite3_preupdate_depth(self.db) }
+    }
+
+    /// Retrieve the value of a column before the change. Fails with
+    /// [SQLITE_MISUSE](ffi::SQLITE_MISUSE) for an INSERT.
+    pub fn old(&mut self, idx: usize) -> Result<&mut ValueRef> {
+        self.value(idx, ffi::sqlite3_preupdate_old)
+    }
+
+    /// Retrieve the value of a column after the change. Fails with
+    /// [SQLITE_MISUSE](ffi::SQLITE_MISUSE) for a DELETE.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(&mut self, idx: usize) -> Result<&mut ValueRef> {
+        self.value(idx, ffi::sqlite3_preupdate_new)
+    }
+
+    fn value(
+        &mut self,
+        idx: usize,
+        func: unsafe extern "C" fn(*mut ffi::sqlite3, c_int, *mut *mut ffi::sqlite3_value) -> c_int,
+    ) -> Result<&mut ValueRef> {
+        if idx >= self.count() {
+            return Err(Error::Sqlite(ffi::SQLITE_RANGE, None));
+        }
+        let mut ret: *mut ffi::sqlite3_value = null_mut();
+        unsafe {
+            Error::from_sqlite(func(self.db, idx as _, &mut ret))?;
+            if ret.is_null() {
+                return Err(Error::Sqlite(ffi::SQLITE_MISUSE, None));
+            }
+            Ok(ValueRef::from_ptr(ret))
+        }
+    }
+}
+
+impl Connection {
+    /// Set a callback which is invoked before each INSERT, UPDATE, or DELETE on a rowid
+    /// table. Passing None removes the hook.
+    ///
+    /// A connection has a single preupdate hook, so this method replaces any previous hook.
+    /// The previous hook is dropped. The hook is dropped when the connection is closed.
+    ///
+    /// The callback must not modify the database connection.
+    ///
+    /// If the hook needs to be dropped and this is called by a non-persistent `unload_safe`
+    /// extension, this function fails. See
+    /// [Extension](crate::Extension#non-persistent-extensions) for details.
+    ///
+    /// Requires SQLite 3.13.0.
+    pub fn set_preupdate_hook<F: FnMut(&mut PreUpdateCase) + 'static>(
+        &self,
+        func: Option<F>,
+    ) -> Result<()> {
+        let _ = func;
+        sqlite3_require_version!(3_013_000, {
+            let _guard = self.lock();
+            match func {
+                None => {
+                    unsafe { ffi::sqlite3_preupdate_hook(self.as_mut_ptr(), None, null_mut()) };
+                    self.set_slot::<F>(PREUPDATE_HOOK_SLOT, None)
+                }
+                Some(func) => {
+                    check_transient_drop::<F>("preupdate hook")?;
+                    let func = Box::into_raw(Box::new(func));
+                    unsafe {
+                        ffi::sqlite3_preupdate_hook(
+                            self.as_mut_ptr(),
+                            Some(call_preupdate_hook::<F>),
+                            func as _,
+                        );
+                        let ret = self.set_slot(PREUPDATE_HOOK_SLOT, Some(Box::from_raw(func)));
+                        if ret.is_err() {
+                            ffi::sqlite3_preupdate_hook(self.as_mut_ptr(), None, null_mut());
+                        }
+                        ret
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg_attr(not(modern_sqlite), allow(dead_code))]
+unsafe extern "C" fn call_preupdate_hook<F: FnMut(&mut PreUpdateCase)>(
+    data: *mut c_void,
+    db: *mut ffi::sqlite3,
+    op: c_int,
+    db_name: *const c_char,
+    table: *const c_char,
+    old_rowid: i64,
+    new_rowid: i64,
+) {
+    let func = &mut *(data as *mut F);
+    let op = match op {
+        ffi::SQLITE_INSERT => PreUpdateOp::Insert,
+        ffi::SQLITE_DELETE => PreUpdateOp::Delete,
+        _ => PreUpdateOp::Update,
+    };
+    // Database and table names are always valid UTF-8, since they come from SQL text.
+    let (db_name, table) = match (
+        CStr::from_ptr(db_name).to_str(),
+        CStr::from_ptr(table).to_str(),
+    ) {
+        (Ok(d), Ok(t)) => (d, t),
+        _ => return,
+    };
+    func(&mut PreUpdateCase {
+        db,
+        op,
+        db_name,
+        table,
+        old_rowid,
+        new_rowid,
+    });
+}
+
+#[cfg(all(test, modern_sqlite))]
+mod test {
+    use super::*;
+    use crate::test_helpers::prelude::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    type Log = Rc<
+        RefCell<
+            Vec<(
+                PreUpdateOp,
+                i64,
+                i64,
+                Vec<Option<Value>>,
+                Vec<Option<Value>>,
+            )>,
+        >,
+    >;
+
+    fn setup() -> Result<(TestHelpers, Log)> {
+        let h = TestHelpers::new();
+        h.db.execute("CREATE TABLE tbl (a, b)", ())?;
+        let log: Log = Default::default();
+        let inner = log.clone();
+        h.db.set_preupdate_hook(Some(move |case: &mut PreUpdateCase| {
+            assert_eq!(case.db_name(), "main");
+            assert_eq!(case.table(), "tbl");
+            assert_eq!(case.depth(), 0);
+            let values = |get: &mut dyn FnMut(usize) -> Result<Value>, count| {
+                (0..count).map(|i| get(i).ok()).collect::<Vec<_>>()
+            };
+            let count = case.count();
+            let old = values(&mut |i| case.old(i)?.to_owned(), count);
+            let new = values(&mut |i| case.new(i)?.to_owned(), count);
+            inner
+                .borrow_mut()
+                .push((case.op(), case.old_rowid(), case.new_rowid(), old, new));
+        }))?;
+        Ok((h, log))
+    }
+
+    #[test]
+    fn insert() -> Result<()> {
+        let (h, log) = setup()?;
+        h.db.execute("INSERT INTO tbl VALUES (1, 'x')", ())?;
+        assert_eq!(
+            *log.borrow(),
+            vec![(
+                PreUpdateOp::Insert,
+                1,
+                1,
+                vec![None, None],
+                vec![Some(Value::Integer(1)), Some(Value::Text("x".to_owned()))]
+            )]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn delete() -> Result<()> {
+        let (h, log) = setup()?;
+        h.db.execute("INSERT INTO tbl VALUES (1, 'x')", ())?;
+        log.borrow_mut().clear();
+        h.db.execute("DELETE FROM tbl", ())?;
+        assert_eq!(
+            *log.borrow(),
+            vec![(
+                PreUpdateOp::Delete,
+                1,
+                1,
+                vec![Some(Value::Integer(1)), Some(Value::Text("x".to_owned()))],
+                vec![None, None]
+            )]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn update() -> Result<()> {
+        let (h, log) = setup()?;
+        h.db.execute("INSERT INTO tbl VALUES (1, 'x')", ())?;
+        log.borrow_mut().clear();
+        h.db.execute("UPDATE tbl SET b = 'y', rowid = 5", ())?;
+        assert_eq!(
+            *log.borrow(),
+            vec![(
+                PreUpdateOp::Update,
+                1,
+                5,
+                vec![Some(Value::Integer(1)), Some(Value::Text("x".to_owned()))],
+                vec![Some(Value::Integer(1)), Some(Value::Text("y".to_owned()))]
+            )]
+        );
+        h.db.set_preupdate_hook(None::<fn(&mut PreUpdateCase)>)?;
+        h.db.execute("DELETE FROM tbl", ())?;
+        assert_eq!(log.borrow().len(), 1);
+        Ok(())
+    }
+}
@@ -48,6 +48,110 @@ pub trait FallibleIteratorMut {
     {
         Map { it: self, f }
     }
+
+    /// Stop iterating before the iterator is exhausted, releasing any resources it holds.
+    /// Afterwards, [next](Self::next) returns `None`.
+    ///
+    /// The adapters in this trait call this method when they stop early. The default
+    /// implementation does nothing. A [Statement](crate::query::Statement) resets itself, so
+    /// that it no longer holds a read transaction open.
+    #[inline]
+    fn stop(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Create an iterator which yields at most `n` elements. The underlying iterator is
+    /// [stopped](Self::stop) when the limit is reached.
+    #[inline]
+    fn take(&mut self, n: usize) -> Take<'_, Self>
+    where
+        Self: Sized,
+    {
+        Take { it: self, n }
+    }
+
+    /// Convert this iterator into a [FallibleIterator] which applies a function to each
+    /// element and yields the values which are not `None`.
+    #[inline]
+    fn filter_map<F, B>(&mut self, f: F) -> FilterMap<'_, Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&mut Self::Item) -> Result<Option<B>, Self::Error>,
+    {
+        FilterMap { it: self, f }
+    }
+
+    /// Apply a function to each element, accumulating a single value. If the function or
+    /// the iterator fails, the iterator is [stopped](Self::stop) and the error is returned
+    /// immediately.
+    fn try_fold<B, F>(&mut self, init: B, mut f: F) -> Result<B, Self::Error>
+    where
+        Self: Sized,
+        F: FnMut(B, &mut Self::Item) -> Result<B, Self::Error>,
+    {
+        let mut acc = init;
+        loop {
+            let res = match self.next() {
+                Ok(Some(x)) => f(acc, x),
+                Ok(None) => return Ok(acc),
+                Err(e) => Err(e),
+            };
+            match res {
+                Ok(x) => acc = x,
+                Err(e) => {
+                    // Prioritize the original error over a failure to stop.
+                    let _ = self.stop();
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    /// Call a function on each element. See [try_fold](Self::try_fold) for error handling.
+    #[inline]
+    fn for_each<F>(&mut self, mut f: F) -> Result<(), Self::Error>
+    where
+        Self: Sized,
+        F: FnMut(&mut Self::Item) -> Result<(), Self::Error>,
+    {
+        self.try_fold((), |(), x| f(x))
+    }
+
+    /// Consume the iterator, returning the number of elements.
+    #[inline]
+    fn count(&mut self) -> Result<usize, Self::Error>
+    where
+        Self: Sized,
+    {
+        self.try_fold(0, |n, _| Ok(n + 1))
+    }
+
+    /// Apply a function to each element and collect the results. Any collection which
+    /// implements [FromIterator] can be used, including a `HashMap` or `BTreeMap` when the
+    /// function returns key-value pairs. See [try_fold](Self::try_fold) for error handling.
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::*;
+    /// use std::collections::HashMap;
+    ///
+    /// fn load_settings(db: &Connection) -> Result<HashMap<String, i64>> {
+    ///     db.prepare("SELECT key, value FROM settings")?
+    ///         .collect_with(|row| Ok((row[0].get_str()?.to_owned(), row[1].get_i64())))
+    /// }
+    /// ```
+    fn collect_with<C, B, F>(&mut self, mut f: F) -> Result<C, Self::Error>
+    where
+        Self: Sized,
+        C: FromIterator<B>,
+        F: FnMut(&mut Self::Item) -> Result<B, Self::Error>,
+    {
+        let mut items = vec![];
+        self.try_fold((), |(), x| {
+            items.push(f(x)?);
+            Ok(())
+        })?;
+        Ok(items.into_iter().collect())
+    }
 }
 
 pub struct Map<'a, I, F> {
@@ -77,3 +181,69 @@ where
         self.it.size_hint()
     }
 }
+
+/// An iterator which yields at most a fixed number of elements. See
+/// [FallibleIteratorMut::take].
+pub struct Take<'a, I> {
+    it: &'a mut I,
+    n: usize,
+}
+
+impl<I: FallibleIteratorMut> FallibleIteratorMut for Take<'_, I> {
+    type Item = I::Item;
+    type Error = I::Error;
+
+    fn next(&mut self) -> Result<Option<&mut I::Item>, I::Error> {
+        if self.n == 0 {
+            self.it.stop()?;
+            return Ok(None);
+        }
+        self.n -= 1;
+        self.it.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.it.size_hint();
+        (
+            lower.min(self.n),
+            Some(upper.map_or(self.n, |x| x.min(self.n))),
+        )
+    }
+
+    #[inline]
+    fn stop(&mut self) -> Result<(), I::Error> {
+        self.n = 0;
+        self.it.stop()
+    }
+}
+
+/// An iterator which maps elements to optional values and yields the values which are
+/// present. See [FallibleIteratorMut::filter_map].
+pub struct FilterMap<'a, I, F> {
+    it: &'a mut I,
+    f: F,
+}
+
+impl<'a, I, F, B> FallibleIterator for FilterMap<'a, I, F>
+where
+    I: FallibleIteratorMut,
+    F: FnMut(&mut I::Item) -> Result<Option<B>, I::Error>,
+{
+    type Item = B;
+    type Error = I::Error;
+
+    #[inline]
+    fn next(&mut self) -> Result<Option<B>, I::Error> {
+        while let Some(v) = self.it.next()? {
+            if let Some(x) = (self.f)(v)? {
+                return Ok(Some(x));
+            }
+        }
+        Ok(None)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.it.size_hint().1)
+    }
+}
@@ -1,22 +1,35 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
+pub use authorizer::*;
+pub use blob_io::*;
+pub use capabilities::*;
 pub use connection::*;
-pub use extension::Extension;
+pub use extension::{reset_auto_extensions, Extension};
 pub use globals::*;
 pub use iterator::*;
 pub use sqlite3_ext_macro::*;
+pub use trace::*;
 pub use transaction::*;
 pub use types::*;
 pub use value::*;
 
+mod authorizer;
+mod blob_io;
+pub mod build_support;
+mod capabilities;
+pub mod compat;
+pub mod config;
 mod connection;
 mod extension;
 pub mod ffi;
 pub mod function;
 mod globals;
+pub mod hooks;
 mod iterator;
 mod mutex;
 pub mod query;
+pub mod sql;
 mod test_helpers;
+mod trace;
 mod transaction;
 mod types;
 mod value;
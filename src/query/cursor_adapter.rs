@@ -0,0 +1,142 @@
+use super::{Params, Statement};
+use crate::{iterator::*, types::*, value::*, vtab::ColumnContext};
+
+/// Adapts a [Statement] into the shape of a [VTabCursor](crate::vtab::VTabCursor).
+///
+/// A common virtual table design stores its data in ordinary "shadow" tables, so that each
+/// cursor is simply a query against those tables. Holding a Statement and its current row
+/// across the separate cursor methods is awkward, so this struct owns the Statement and
+/// provides methods which map directly onto the cursor methods. Values are forwarded using
+/// sqlite3_result_value, so they are never copied into Rust.
+///
+/// The columns of the query must be in the same order as the columns of the virtual table.
+/// The query may return additional columns after these, for example to provide the rowid.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sqlite3_ext::{query::CursorAdapter, vtab::*, *};
+///
+/// struct MyCursor(CursorAdapter);
+///
+/// impl VTabCursor for MyCursor {
+///     fn filter(&mut self, _: i32, _: Option<&str>, args: &mut [&mut ValueRef]) -> Result<()> {
+///         self.0.filter(args)
+///     }
+///
+///     fn next(&mut self) -> Result<()> {
+///         self.0.next()
+///     }
+///
+///     fn eof(&mut self) -> bool {
+///         self.0.eof()
+///     }
+///
+///     fn column(&mut self, idx: usize, ctx: &ColumnContext) -> Result<()> {
+///         self.0.column(idx, ctx)
+///     }
+///
+///     fn rowid(&mut self) -> Result<i64> {
+///         self.0.rowid()
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct CursorAdapter {
+    stmt: Statement,
+    rowid_column: Option<usize>,
+    row: Option<i64>,
+}
+
+impl CursorAdapter {
+    /// Create an adapter for the given statement. If rowid_column is provided, that column
+    /// of the query is used as the rowid. Otherwise the rowid is the 0-based index of the
+    /// current row.
+    pub fn new(stmt: Statement, rowid_column: Option<usize>) -> Self {
+        Self {
+            stmt,
+            rowid_column,
+            row: None,
+        }
+    }
+
+    /// Returns the underlying statement.
+    pub fn statement(&self) -> &Statement {
+        &self.stmt
+    }
+
+    /// Replace the underlying statement, for example when [VTabCursor::filter] selects a
+    /// different query plan. The cursor is positioned at EOF until the next call to
+    /// [filter](Self::filter).
+    ///
+    /// [VTabCursor::filter]: crate::vtab::VTabCursor::filter
+    pub fn set_statement(&mut self, stmt: Statement) {
+        self.stmt = stmt;
+        self.row = None;
+    }
+
+    /// Bind the parameters, restart the query, and advance to the first row. Corresponds to
+    /// [VTabCursor::filter](crate::vtab::VTabCursor::filter).
+    pub fn filter<P: Params>(&mut self, params: P) -> Result<()> {
+        self.row = None;
+        self.stmt.query(params)?;
+        self.step(0)
+    }
+
+    /// Advance to the next row. Corresponds to
+    /// [VTabCursor::next](crate::vtab::VTabCursor::next).
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Result<()> {
+        match self.row {
+            Some(row) => self.step(row + 1),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns true if there is no current row. Corresponds to
+    /// [VTabCursor::eof](crate::vtab::VTabCursor::eof).
+    ///
+    /// If stepping the query fails, the cursor is positioned at EOF, so that no stale row
+    /// is ever returned.
+    pub fn eof(&self) -> bool {
+        self.row.is_none()
+    }
+
+    /// Set the result of ctx to the given column of the current row. Corresponds to
+    /// [VTabCursor::column](crate::vtab::VTabCursor::column).
+    pub fn column(&self, idx: usize, ctx: &ColumnContext) -> Result<()> {
+        if idx >= self.stmt.column_count() {
+            return Err(SQLITE_RANGE);
+        }
+        match (self.row, self.stmt.current_result()) {
+            (Some(_), Some(row)) => ctx.set_result(AsRef::<ValueRef>::as_ref(&row[idx])),
+            _ => Err(SQLITE_MISUSE),
+        }
+    }
+
+    /// Returns the rowid of the current row. Corresponds to
+    /// [VTabCursor::rowid](crate::vtab::VTabCursor::rowid).
+    pub fn rowid(&self) -> Result<i64> {
+        match (self.row, self.stmt.current_result(), self.rowid_column) {
+            (Some(_), Some(row), Some(col)) if col < row.len() => Ok(row[col].get_i64()),
+            (Some(_), Some(_), Some(_)) => Err(SQLITE_RANGE),
+            (Some(row), _, None) => Ok(row),
+            _ => Err(SQLITE_MISUSE),
+        }
+    }
+
+    fn step(&mut self, row: i64) -> Result<()> {
+        // Clear the row first so that an error leaves the cursor at EOF.
+        self.row = None;
+        if self.stmt.next()?.is_some() {
+            self.row = Some(row);
+        }
+        Ok(())
+    }
+}
+
+impl From<Statement> for CursorAdapter {
+    fn from(stmt: Statement) -> Self {
+        Self::new(stmt, None)
+    }
+}
@@ -0,0 +1,152 @@
+use super::{Column, Params, QueryResult, Statement};
+use crate::{ffi, iterator::*, types::*, value::*, Connection};
+
+/// Trait for types which can be extracted from a single [Column].
+///
+/// NULL values can only be converted into `Option` types, and will otherwise fail with
+/// [SQLITE_MISMATCH]. Numeric conversions use the checked accessors on [FromValue], so a
+/// value of the wrong type, or an out-of-range integer, will fail instead of being converted
+/// with loss.
+pub trait FromColumn: Sized {
+    /// Convert the column into this type.
+    fn from_column(column: &mut Column) -> Result<Self>;
+}
+
+fn not_null(column: &Column) -> Result<()> {
+    if column.is_null() {
+        Err(Error::Sqlite(
+            ffi::SQLITE_MISMATCH,
+            Some(format!(
+                "column {} is NULL but a non-NULL value was expected",
+                column.position
+            )),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+macro_rules! from_column {
+    ($ty:ty as ($col:ident) => $impl:expr) => {
+        impl FromColumn for $ty {
+            fn from_column($col: &mut Column) -> Result<Self> {
+                not_null($col)?;
+                $impl
+            }
+        }
+    };
+}
+
+from_column!(bool as (col) => col.get_bool());
+from_column!(i16 as (col) => col.get_i16());
+from_column!(i32 as (col) => col.try_get_i32());
+from_column!(i64 as (col) => col.try_get_i64());
+from_column!(u32 as (col) => col.get_u32());
+from_column!(u64 as (col) => col.get_u64());
+from_column!(f32 as (col) => col.get_f32());
+from_column!(f64 as (col) => col.try_get_f64());
+from_column!(String as (col) => Ok(col.get_str()?.to_owned()));
+from_column!(Vec<u8> as (col) => Ok(col.get_blob()?.to_vec()));
+from_column!(Blob as (col) => Ok(Blob::from(col.get_blob()?)));
+
+/// Returns the column as a dynamically typed [Value]. NULL is allowed.
+impl FromColumn for Value {
+    fn from_column(column: &mut Column) -> Result<Self> {
+        column.to_owned()
+    }
+}
+
+/// Returns None if the column is NULL, otherwise converts the value.
+impl<T: FromColumn> FromColumn for Option<T> {
+    fn from_column(column: &mut Column) -> Result<Self> {
+        if column.is_null() {
+            Ok(None)
+        } else {
+            T::from_column(column).map(Some)
+        }
+    }
+}
+
+/// Trait for types which can be constructed from an entire row of a query.
+///
+/// This trait is implemented for tuples of up to 12 [FromColumn] types. The number of columns
+/// in the row must exactly match the size of the tuple.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sqlite3_ext::*;
+///
+/// fn pages(conn: &Connection, user_id: i64) -> Result<Vec<(i64, String)>> {
+///     conn.prepare("SELECT id, name FROM pages WHERE owner_id = ?")?
+///         .query_as([user_id])?
+///         .collect()
+/// }
+/// ```
+pub trait FromRow: Sized {
+    /// Construct an instance from the given row.
+    fn from_row(row: &mut QueryResult) -> Result<Self>;
+}
+
+fn check_len(row: &QueryResult, expected: usize) -> Result<()> {
+    if row.len() != expected {
+        Err(Error::Sqlite(
+            ffi::SQLITE_MISMATCH,
+            Some(format!(
+                "query returned {} columns, but {expected} were expected",
+                row.len()
+            )),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+macro_rules! from_row_tuple {
+    ($len:literal => $($name:ident $idx:tt),+) => {
+        impl<$($name: FromColumn),+> FromRow for ($($name,)+) {
+            fn from_row(row: &mut QueryResult) -> Result<Self> {
+                check_len(row, $len)?;
+                Ok(($($name::from_column(&mut row[$idx])?,)+))
+            }
+        }
+    };
+}
+
+from_row_tuple!(1 => A 0);
+from_row_tuple!(2 => A 0, B 1);
+from_row_tuple!(3 => A 0, B 1, C 2);
+from_row_tuple!(4 => A 0, B 1, C 2, D 3);
+from_row_tuple!(5 => A 0, B 1, C 2, D 3, E 4);
+from_row_tuple!(6 => A 0, B 1, C 2, D 3, E 4, F 5);
+from_row_tuple!(7 => A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+from_row_tuple!(8 => A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+from_row_tuple!(9 => A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+from_row_tuple!(10 => A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+from_row_tuple!(11 => A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+from_row_tuple!(12 => A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+
+/// The iterator returned by [Statement::query_as].
+pub type QueryAs<'a, T> = Map<'a, Statement, fn(&mut QueryResult) -> Result<T>>;
+
+impl Statement {
+    /// Bind the provided parameters to the query, and return an iterator which converts each
+    /// row using [FromRow].
+    pub fn query_as<T: FromRow, P: Params>(&mut self, params: P) -> Result<QueryAs<'_, T>> {
+        Ok(self.query(params)?.map(T::from_row as _))
+    }
+
+    /// Execute a query which is expected to return only a single row, and convert it using
+    /// [FromRow]. See [query_row](Self::query_row).
+    pub fn query_row_as<T: FromRow, P: Params>(&mut self, params: P) -> Result<T> {
+        self.query_row(params, T::from_row)
+    }
+}
+
+impl Connection {
+    /// Convenience method for `self.prepare(sql)?.query_row_as(params)`. See
+    /// [Statement::query_row_as].
+    pub fn query_row_as<T: FromRow, P: Params>(&self, sql: &str, params: P) -> Result<T> {
+        self.prepare(sql)?.query_row_as(params)
+    }
+}
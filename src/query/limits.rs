@@ -0,0 +1,164 @@
+use super::{QueryResult, Statement, ToParam};
+use crate::{
+    ffi,
+    iterator::*,
+    sql::{count_compound_terms, estimate_expr_depth},
+    types::*,
+    Connection, Limit,
+};
+
+/// Messages which SQLite uses when a statement exceeds a run-time limit.
+const LIMIT_MESSAGES: &[(&str, Limit)] = &[
+    ("Expression tree is too large", Limit::ExprDepth),
+    ("too many terms in compound SELECT", Limit::CompoundSelect),
+    ("statement too long", Limit::SqlLength),
+    ("too many columns", Limit::Column),
+    ("too many SQL variables", Limit::VariableNumber),
+    ("too many arguments on function", Limit::FunctionArg),
+];
+
+impl Connection {
+    /// Check whether some SQL is likely to exceed the run-time limits of this connection,
+    /// without preparing it. This examines the length of the SQL, the depth of its
+    /// expressions as estimated by [estimate_expr_depth], and the number of terms in a
+    /// compound SELECT, and fails with [Error::LimitExceeded] for the first limit which
+    /// would be exceeded.
+    ///
+    /// The checks are estimates, so passing them does not guarantee that the statement
+    /// can be prepared. Use [try_prepare_checked](Self::try_prepare_checked) to learn
+    /// which limit caused a statement to fail.
+    pub fn statement_complexity_guard(&self, sql: &str) -> Result<()> {
+        let checks = [
+            (Limit::SqlLength, sql.len()),
+            (Limit::ExprDepth, estimate_expr_depth(sql)),
+            (Limit::CompoundSelect, count_compound_terms(sql)),
+        ];
+        for (limit, value) in checks {
+            let max = self.limit(limit);
+            // A limit of 0 disables the check for some limits, and -1 is unsupported.
+            if max > 0 && value > max as usize {
+                return Err(Error::LimitExceeded { limit, max });
+            }
+        }
+        Ok(())
+    }
+
+    /// Prepare some SQL for execution, reporting statements which exceed a run-time limit
+    /// of the connection as [Error::LimitExceeded]. Otherwise, this method is the same as
+    /// [prepare](Self::prepare).
+    ///
+    /// This allows code which generates SQL, such as a lookup with many `OR` terms, to
+    /// recover by splitting the statement into smaller pieces, which
+    /// [query_chunked](Self::query_chunked) does automatically. The limits recognized are
+    /// [Limit::SqlLength], [Limit::Column], [Limit::ExprDepth], [Limit::CompoundSelect],
+    /// [Limit::FunctionArg], and [Limit::VariableNumber]. A statement whose program exceeds
+    /// [Limit::VdbeOp] fails with [SQLITE_NOMEM], which cannot be distinguished from other
+    /// allocation failures, so it is returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::*;
+    ///
+    /// fn count_matching(db: &Connection, ids: &[i64]) -> Result<i64> {
+    ///     let terms = vec!["id = ?"; ids.len()].join(" OR ");
+    ///     let sql = format!("SELECT COUNT(*) FROM items WHERE {terms}");
+    ///     match db.try_prepare_checked(&sql) {
+    ///         Err(Error::LimitExceeded { .. }) if ids.len() > 1 => {
+    ///             let (a, b) = ids.split_at(ids.len() / 2);
+    ///             Ok(count_matching(db, a)? + count_matching(db, b)?)
+    ///         }
+    ///         stmt => {
+    ///             let mut stmt = stmt?;
+    ///             stmt.query(ids)?;
+    ///             Ok(stmt.next()?.unwrap()[0].get_i64())
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn try_prepare_checked(&self, sql: &str) -> Result<Statement> {
+        let err = match self.prepare(sql) {
+            Ok(stmt) => return Ok(stmt),
+            Err(e) => e,
+        };
+        let limit = match &err {
+            Error::Sqlite(ffi::SQLITE_ERROR | ffi::SQLITE_TOOBIG, Some(msg)) => LIMIT_MESSAGES
+                .iter()
+                .find(|(prefix, _)| msg.starts_with(prefix))
+                .map(|&(_, limit)| limit),
+            _ => None,
+        };
+        match limit {
+            Some(limit) => Err(Error::LimitExceeded {
+                limit,
+                max: self.limit(limit),
+            }),
+            None => Err(err),
+        }
+    }
+
+    /// Run a query over a list of values, such as a lookup with a large `IN` list,
+    /// splitting the list into smaller pieces whenever the statement would exceed a
+    /// run-time limit of the connection.
+    ///
+    /// `build` is called with a number of values, and returns SQL with that many
+    /// parameters, which are bound to clones of the values. The statement is prepared with
+    /// [try_prepare_checked](Self::try_prepare_checked), and if it fails with
+    /// [Error::LimitExceeded], the values are split in half and each half is queried
+    /// separately. `f` is called for every row of every piece, in the order of the values.
+    /// If the statement for a single value still exceeds a limit, that error is returned.
+    ///
+    /// Each piece is a separate statement, so the result is only the same as that of a
+    /// single query when every row is produced by one of the values, as with
+    /// `WHERE x IN (...)`. Aggregates, `ORDER BY`, `LIMIT`, and `DISTINCT` apply to each
+    /// piece separately.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::*;
+    ///
+    /// fn names(db: &Connection, ids: &[i64]) -> Result<Vec<String>> {
+    ///     db.query_chunked(
+    ///         ids,
+    ///         |n| format!("SELECT name FROM items WHERE id IN ({})", vec!["?"; n].join(", ")),
+    ///         |row| Ok(row[0].get_str()?.to_owned()),
+    ///     )
+    /// }
+    /// ```
+    pub fn query_chunked<T, R, B, F>(&self, values: &[T], mut build: B, mut f: F) -> Result<Vec<R>>
+    where
+        T: ToParam + Clone,
+        B: FnMut(usize) -> String,
+        F: FnMut(&mut QueryResult) -> Result<R>,
+    {
+        let mut ret = vec![];
+        self.query_chunked_into(values, &mut build, &mut f, &mut ret)?;
+        Ok(ret)
+    }
+
+    fn query_chunked_into<T, R>(
+        &self,
+        values: &[T],
+        build: &mut dyn FnMut(usize) -> String,
+        f: &mut dyn FnMut(&mut QueryResult) -> Result<R>,
+        ret: &mut Vec<R>,
+    ) -> Result<()>
+    where
+        T: ToParam + Clone,
+    {
+        let mut stmt = match self.try_prepare_checked(&build(values.len())) {
+            Err(Error::LimitExceeded { .. }) if values.len() > 1 => {
+                let (a, b) = values.split_at(values.len() / 2);
+                self.query_chunked_into(a, build, f, ret)?;
+                return self.query_chunked_into(b, build, f, ret);
+            }
+            stmt => stmt?,
+        };
+        stmt.query(values.to_vec())?;
+        while let Some(row) = stmt.next()? {
+            ret.push(f(row)?);
+        }
+        Ok(())
+    }
+}
@@ -2,20 +2,99 @@
 //!
 //! The main entry points into this module are [Connection::prepare], [Connection::execute],
 //! and [Connection::query_row].
-use super::{ffi, iterator::*, sqlite3_match_version, types::*, value::*, Connection};
+use super::{
+    ffi, iterator::*, sqlite3_match_version, sqlite3_require_version, types::*, value::*,
+    Connection,
+};
+use bitflags::bitflags;
+pub use cursor_adapter::*;
+pub use from_row::*;
 pub use params::*;
+pub use row::*;
+pub use script::*;
 use std::{
+    cell::OnceCell,
+    collections::HashMap,
     convert::{AsMut, AsRef},
     ffi::{CStr, CString},
     mem::MaybeUninit,
     num::NonZeroI32,
     ops::{Index, IndexMut},
+    os::raw::c_int,
     slice, str,
+    thread::sleep,
+    time::Duration,
 };
+pub use table::*;
 
+mod cursor_adapter;
+mod from_row;
+mod limits;
 mod params;
+mod pragma;
+mod row;
+mod script;
+mod table;
 mod test;
 
+bitflags! {
+    /// Flags which can be passed to [Connection::prepare_with] and
+    /// [Connection::prepare_first_with].
+    #[repr(transparent)]
+    pub struct PrepareFlags: u32 {
+        /// The statement is likely to be retained for a long time and reused many times,
+        /// such as a statement cached by a virtual table. SQLite avoids using lookaside
+        /// memory for such statements.
+        const PERSISTENT = ffi::SQLITE_PREPARE_PERSISTENT as _;
+        /// No longer used by SQLite. Provided for compatibility.
+        const NORMALIZE = ffi::SQLITE_PREPARE_NORMALIZE as _;
+        /// Preparing the statement fails if it uses any virtual tables.
+        const NO_VTAB = ffi::SQLITE_PREPARE_NO_VTAB as _;
+    }
+}
+
+/// The kind of EXPLAIN statement a [Statement] is. See [Statement::explain_mode].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExplainMode {
+    /// The statement is not an EXPLAIN statement.
+    None,
+    /// The statement is an EXPLAIN statement.
+    Explain,
+    /// The statement is an EXPLAIN QUERY PLAN statement.
+    QueryPlan,
+}
+
+/// Performance counters which can be retrieved with [Statement::status]. See
+/// [the SQLite documentation](https://www.sqlite.org/c3ref/c_stmtstatus_counter.html) for
+/// details of each counter.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[repr(i32)]
+pub enum StatementStatus {
+    /// The number of times SQLite has stepped forward in a table as part of a full table
+    /// scan.
+    FullscanStep = ffi::SQLITE_STMTSTATUS_FULLSCAN_STEP,
+    /// The number of sort operations that have occurred.
+    Sort = ffi::SQLITE_STMTSTATUS_SORT,
+    /// The number of rows inserted into transient indices that were created automatically.
+    /// Requires SQLite 3.7.0.
+    Autoindex = ffi::SQLITE_STMTSTATUS_AUTOINDEX,
+    /// The number of virtual machine operations executed. Requires SQLite 3.10.0.
+    VmStep = ffi::SQLITE_STMTSTATUS_VM_STEP,
+    /// The number of times the statement has been automatically regenerated due to schema
+    /// changes. Requires SQLite 3.20.0.
+    Reprepare = ffi::SQLITE_STMTSTATUS_REPREPARE,
+    /// The number of times the statement has been run to completion or reset. Requires
+    /// SQLite 3.20.0.
+    Run = ffi::SQLITE_STMTSTATUS_RUN,
+    /// The approximate number of bytes of heap memory used by the statement. Requires SQLite
+    /// 3.20.0.
+    MemUsed = ffi::SQLITE_STMTSTATUS_MEMUSED,
+}
+
+/// The number of times [Statement::next] will re-prepare a statement which fails with
+/// SQLITE_SCHEMA before returning the error.
+const SCHEMA_RETRIES: usize = 3;
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 enum QueryState {
     Ready,
@@ -81,14 +160,51 @@ pub struct Statement {
     // implementation. It's possible to skip this if we add a lifetime parameter to Column to
     // prevent pointer aliasing, but then we can't use Index and IndexMut.
     columns: Box<[Column]>,
+    // Map from the ASCII-lowercased name of each column to its position, built on first use.
+    names: OnceCell<HashMap<String, usize>>,
+    // Parameters bound to the statement, in the order they were bound. None if the rebind
+    // cache is disabled. A parameter which could not be recorded is stored as None.
+    rebind: Option<Vec<(i32, Option<Value>)>>,
+    // The flags the statement was prepared with, used when re-preparing it.
+    flags: PrepareFlags,
+}
+
+/// The result of [Statement::run].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunSummary {
+    /// The number of rows returned by the statement, which were discarded.
+    pub rows_discarded: u64,
+    /// The number of rows modified by the statement, as reported by [Connection::changes].
+    /// This is 0 for statements which are not INSERT, UPDATE, or DELETE.
+    pub changes: i64,
+    /// The value of [Connection::last_insert_rowid] after the statement completed.
+    pub last_insert_rowid: i64,
 }
 
 impl Connection {
     /// Prepare some SQL for execution. This method will return the prepared statement and
     /// a slice containing the portion of the original input which was after the first SQL
     /// statement.
+    ///
+    /// SQLite stops parsing at a nul byte, so this method fails with
+    /// [SQLITE_MISUSE](ffi::SQLITE_MISUSE) if a nul byte is reached before any statement.
+    /// Input longer than [c_int::MAX] bytes fails with [SQLITE_TOOBIG](ffi::SQLITE_TOOBIG).
     pub fn prepare_first<'a>(&self, sql: &'a str) -> Result<(Option<Statement>, &'a str)> {
-        const FLAGS: u32 = 0;
+        self.prepare_first_with(sql, PrepareFlags::empty())
+    }
+
+    /// Prepare some SQL for execution using the provided flags. Otherwise, this method is
+    /// the same as [prepare_first](Self::prepare_first).
+    ///
+    /// The flags require SQLite 3.20.0. On earlier versions of SQLite, they are ignored.
+    pub fn prepare_first_with<'a>(
+        &self,
+        sql: &'a str,
+        flags: PrepareFlags,
+    ) -> Result<(Option<Statement>, &'a str)> {
+        if c_int::try_from(sql.len()).is_err() {
+            return Err(Error::Sqlite(ffi::SQLITE_TOOBIG, None));
+        }
         let guard = self.lock();
         let mut ret = MaybeUninit::uninit();
         let mut rest = MaybeUninit::uninit();
@@ -99,7 +215,7 @@ impl Connection {
                         self.as_mut_ptr(),
                         sql.as_ptr() as _,
                         sql.len() as _,
-                        FLAGS,
+                        flags.bits() as _,
                         ret.as_mut_ptr(),
                         rest.as_mut_ptr(),
                     ),
@@ -125,12 +241,21 @@ impl Connection {
                 base: stmt,
                 state: QueryState::Ready,
                 columns,
+                names: OnceCell::new(),
+                rebind: None,
+                flags,
             })
         };
 
         let rest = unsafe { rest.assume_init() };
         let offset = rest as usize - sql.as_ptr() as usize;
         let rest = unsafe { sql.get_unchecked(offset..) };
+        if stmt.is_none() && rest.starts_with('\0') {
+            return Err(Error::Sqlite(
+                ffi::SQLITE_MISUSE,
+                Some("SQL contains a nul byte".to_owned()),
+            ));
+        }
         Ok((stmt, rest))
     }
 
@@ -140,6 +265,14 @@ impl Connection {
         self.prepare_first(sql)?.0.ok_or(SQLITE_MISUSE)
     }
 
+    /// Prepare some SQL for execution using the provided flags. Otherwise, this method is
+    /// the same as [prepare](Self::prepare).
+    ///
+    /// The flags require SQLite 3.20.0. On earlier versions of SQLite, they are ignored.
+    pub fn prepare_with(&self, sql: &str, flags: PrepareFlags) -> Result<Statement> {
+        self.prepare_first_with(sql, flags)?.0.ok_or(SQLITE_MISUSE)
+    }
+
     /// Convenience method to prepare a query and bind it with values. See
     /// [Statement::query].
     pub fn query<P>(&self, sql: &str, params: P) -> Result<Statement>
@@ -240,18 +373,55 @@ impl Statement {
         match res {
             Ok(false) => {
                 reset_res?;
-                Ok(unsafe {
-                    sqlite3_match_version! {
-                        3_037_000 => ffi::sqlite3_changes64(db.as_mut_ptr()),
-                        _ => ffi::sqlite3_changes(db.as_mut_ptr()) as _,
-                    }
-                })
+                Ok(db.changes())
             }
             Ok(true) => Err(SQLITE_MISUSE), // Query returned rows!
             Err(e) => Err(e),
         }
     }
 
+    /// Execute a query to completion, discarding any rows that it returns.
+    ///
+    /// Unlike [execute](Self::execute), this method accepts statements which return rows,
+    /// which makes it suitable for running SQL whose kind is not known in advance. The
+    /// returned [RunSummary] counts the rows which were discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::*;
+    ///
+    /// fn run_user_sql(db: &Connection, sql: &str) -> Result<i64> {
+    ///     let summary = db.prepare(sql)?.run(())?;
+    ///     Ok(summary.changes)
+    /// }
+    /// ```
+    pub fn run<P: Params>(&mut self, params: P) -> Result<RunSummary> {
+        let db = unsafe { self.db() }.lock();
+        let before = db.total_changes();
+        let res = self.query(params).and_then(|stmt| {
+            let mut rows = 0;
+            while stmt.next()?.is_some() {
+                rows += 1;
+            }
+            Ok(rows)
+        });
+        // Always reset the query after using, although we prioritize a query failure
+        // in the return value.
+        let reset_res = self.reset();
+        let rows_discarded = res?;
+        reset_res?;
+        Ok(RunSummary {
+            rows_discarded,
+            // The changes counter is only updated by INSERT, UPDATE, and DELETE.
+            changes: match db.total_changes() == before {
+                true => 0,
+                false => db.changes(),
+            },
+            last_insert_rowid: db.last_insert_rowid(),
+        })
+    }
+
     /// Execute a query that is expected to be an INSERT, then return the inserted rowid.
     ///
     /// This method will fail with [SQLITE_MISUSE] if this method returns rows, but there are no
@@ -266,13 +436,86 @@ impl Statement {
         match res {
             Ok(false) => {
                 reset_res?;
-                Ok(unsafe { ffi::sqlite3_last_insert_rowid(db.as_mut_ptr()) })
+                Ok(db.last_insert_rowid())
             }
             Ok(true) => Err(SQLITE_MISUSE), // Query returned rows!
             Err(e) => Err(e),
         }
     }
 
+    /// Run a function against this statement, retrying it while it fails with
+    /// [SQLITE_BUSY](ffi::SQLITE_BUSY) or [SQLITE_LOCKED].
+    ///
+    /// The function receives the statement and a copy of the parameters, which are usually a
+    /// reference so that they can be bound repeatedly without cloning them (see
+    /// [Params](Params#binding-by-reference)). The function
+    /// is invoked at most `attempts` times, sleeping for `backoff` between attempts. Any
+    /// other error, or the busy error from the final attempt, is returned immediately.
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::{Connection, Result, Value};
+    /// use std::time::Duration;
+    ///
+    /// fn insert(conn: &Connection, params: &[Value]) -> Result<i64> {
+    ///     conn.prepare("INSERT INTO tbl VALUES (?, ?)")?.retry(
+    ///         params,
+    ///         5,
+    ///         Duration::from_millis(10),
+    ///         |stmt, params| stmt.execute(params),
+    ///     )
+    /// }
+    /// ```
+    pub fn retry<P, R, F>(
+        &mut self,
+        params: P,
+        attempts: usize,
+        backoff: Duration,
+        mut f: F,
+    ) -> Result<R>
+    where
+        P: Params + Copy,
+        F: FnMut(&mut Self, P) -> Result<R>,
+    {
+        let mut attempt = 1;
+        loop {
+            match f(self, params) {
+                Err(Error::Sqlite(code, _))
+                    if attempt < attempts
+                        && matches!(code & 0xff, ffi::SQLITE_BUSY | ffi::SQLITE_LOCKED) =>
+                {
+                    self.reset()?;
+                    attempt += 1;
+                    sleep(backoff);
+                }
+                ret => return ret,
+            }
+        }
+    }
+
+    /// Record the parameters bound to this statement, so that they can be bound again if the
+    /// statement needs to be re-prepared.
+    ///
+    /// SQLite automatically re-prepares statements when the database schema changes, but
+    /// gives up and returns [SQLITE_SCHEMA](ffi::SQLITE_SCHEMA) if the schema keeps changing.
+    /// When this happens, [next](FallibleIteratorMut::next) re-prepares the statement
+    /// itself, up to a few times, before returning the error. A statement with parameters can
+    /// only be re-prepared this way if the rebind cache is enabled and every parameter could
+    /// be recorded. [PassedRef] parameters cannot be recorded.
+    ///
+    /// The cache holds a copy of every parameter, so it is disabled by default.
+    pub fn enable_rebind_cache(&mut self) -> &mut Self {
+        if self.rebind.is_none() {
+            self.rebind = Some(Vec::new());
+        }
+        self
+    }
+
+    pub(crate) fn record_param(&mut self, position: i32, f: impl FnOnce() -> Option<Value>) {
+        if let Some(rebind) = &mut self.rebind {
+            rebind.push((position, f()));
+        }
+    }
+
     /// Returns the original text of the prepared statement.
     pub fn sql(&self) -> Result<&str> {
         unsafe {
@@ -281,6 +524,59 @@ impl Statement {
         }
     }
 
+    /// Bind a value to the parameter at the given position as a pointer with the given tag.
+    /// The parameter appears to be NULL to SQL, but the pointer can be retrieved by
+    /// application-defined functions and virtual tables using
+    /// [ValueRef::get_ref_tagged] with the same tag. SQLite takes ownership of the value
+    /// and drops it when the parameter is cleared or the statement is finalized.
+    ///
+    /// [Self::query] clears all parameters, so this method should be called after it, before
+    /// the statement is stepped. To pass a borrowed value, bind an [UnsafePassedRef] using
+    /// the normal parameter interface instead.
+    ///
+    /// Requires SQLite 3.20.0.
+    pub fn bind_pointer<T: 'static>(
+        &mut self,
+        position: i32,
+        value: T,
+        tag: &'static CStr,
+    ) -> Result<()> {
+        // Pointers cannot be recorded, so the statement cannot be rebound.
+        self.record_param(position, || None);
+        let _ = (&value, tag);
+        sqlite3_require_version!(3_020_000, unsafe {
+            Error::from_sqlite(ffi::sqlite3_bind_pointer(
+                self.base,
+                position,
+                Box::into_raw(Box::new(value)) as _,
+                tag.as_ptr(),
+                Some(ffi::drop_boxed::<T>),
+            ))
+        })
+    }
+
+    /// Bind a BLOB of len bytes, all of them zero, to the parameter at the given position.
+    /// The BLOB is not allocated in memory, so this can be used to reserve space for a
+    /// large BLOB whose content is then written incrementally with [Connection::open_blob].
+    /// [Connection::insert_blob_streaming] does this for a new row.
+    ///
+    /// [Self::query] clears all parameters, so this method should be called after it, before
+    /// the statement is stepped. Fails with [SQLITE_TOOBIG](ffi::SQLITE_TOOBIG) if len is
+    /// larger than the maximum size of a BLOB.
+    pub fn bind_zeroblob(&mut self, position: i32, len: u64) -> Result<()> {
+        // The zeroblob is not recorded, to avoid allocating it.
+        self.record_param(position, || None);
+        unsafe {
+            Error::from_sqlite(sqlite3_match_version! {
+                3_008_011 => ffi::sqlite3_bind_zeroblob64(self.base, position, len),
+                _ => match c_int::try_from(len) {
+                    Ok(len) => ffi::sqlite3_bind_zeroblob(self.base, position, len),
+                    Err(_) => ffi::SQLITE_TOOBIG,
+                },
+            })
+        }
+    }
+
     /// Returns the number of parameters which should be bound to the query. Valid
     /// parameter positions are `1..=self.parameter_count()`.
     pub fn parameter_count(&self) -> i32 {
@@ -313,6 +609,70 @@ impl Statement {
         unsafe { ffi::sqlite3_column_count(self.base) as _ }
     }
 
+    /// Returns true if the statement makes no direct changes to the database file. See
+    /// `sqlite3_stmt_readonly` for details; in particular, statements such as BEGIN and
+    /// COMMIT are read-only because they do not change the file themselves.
+    ///
+    /// Requires SQLite 3.7.16.
+    pub fn is_readonly(&self) -> Result<bool> {
+        sqlite3_require_version!(3_007_016, unsafe {
+            Ok(ffi::sqlite3_stmt_readonly(self.base) != 0)
+        })
+    }
+
+    /// Returns true if the statement is an EXPLAIN or EXPLAIN QUERY PLAN statement. See
+    /// [explain_mode](Self::explain_mode).
+    ///
+    /// Requires SQLite 3.28.0.
+    pub fn is_explain(&self) -> Result<bool> {
+        Ok(self.explain_mode()? != ExplainMode::None)
+    }
+
+    /// Returns whether the statement is an EXPLAIN or EXPLAIN QUERY PLAN statement.
+    ///
+    /// Requires SQLite 3.28.0.
+    pub fn explain_mode(&self) -> Result<ExplainMode> {
+        sqlite3_require_version!(3_028_000, unsafe {
+            Ok(match ffi::sqlite3_stmt_isexplain(self.base) {
+                1 => ExplainMode::Explain,
+                2 => ExplainMode::QueryPlan,
+                _ => ExplainMode::None,
+            })
+        })
+    }
+
+    /// Returns true if the statement has been stepped at least once but has not run to
+    /// completion or been reset.
+    ///
+    /// On versions of SQLite before 3.7.16, this is determined from the state of the
+    /// Statement instead of asking SQLite.
+    pub fn is_busy(&self) -> bool {
+        sqlite3_match_version! {
+            3_007_016 => unsafe { ffi::sqlite3_stmt_busy(self.base) != 0 },
+            _ => self.state == QueryState::Active,
+        }
+    }
+
+    /// Retrieve the value of a performance counter of the statement. If reset is true, the
+    /// counter is reset to zero after it is retrieved.
+    ///
+    /// Counters which are not supported by the version of SQLite in use fail with
+    /// [Error::VersionNotSatisfied]; see [StatementStatus] for the required versions.
+    pub fn status(&self, op: StatementStatus, reset: bool) -> Result<i32> {
+        let min_version = match op {
+            StatementStatus::FullscanStep | StatementStatus::Sort => 3_006_008,
+            StatementStatus::Autoindex => 3_007_000,
+            StatementStatus::VmStep => 3_010_000,
+            StatementStatus::Reprepare | StatementStatus::Run | StatementStatus::MemUsed => {
+                3_020_000
+            }
+        };
+        if !ffi::is_version(min_version) {
+            return Err(Error::VersionNotSatisfied(min_version));
+        }
+        Ok(unsafe { ffi::sqlite3_stmt_status(self.base, op as _, reset as _) })
+    }
+
     /// Returns the current result, without advancing the cursor. This method returns `None` if the
     /// query has already run to completion, or if the query has not been started using
     /// [query](Self::query).
@@ -347,36 +707,115 @@ impl Statement {
             ffi::sqlite3_reset(self.base);
             Error::from_sqlite(ffi::sqlite3_clear_bindings(self.base))?;
         }
+        if let Some(rebind) = &mut self.rebind {
+            rebind.clear();
+        }
         self.state = QueryState::Ready;
         Ok(())
     }
+
+    /// Returns true if the statement can be re-prepared with the same parameters.
+    fn can_reprepare(&self) -> bool {
+        match &self.rebind {
+            Some(rebind) => rebind.iter().all(|(_, val)| val.is_some()),
+            None => self.parameter_count() == 0,
+        }
+    }
+
+    /// Prepare the SQL of this statement again, and bind the recorded parameters to it.
+    /// Fails with [Error::SchemaChanged] if the new statement has a different number of
+    /// columns, in which case the new statement is ready to be stepped.
+    fn reprepare(&mut self) -> Result<()> {
+        if !self.can_reprepare() {
+            return Err(SQLITE_MISUSE);
+        }
+        let sql = self.sql()?.to_owned();
+        let mut stmt = unsafe { self.db() }.prepare_with(&sql, self.flags)?;
+        if let Some(rebind) = self.rebind.take() {
+            stmt.enable_rebind_cache();
+            for (position, val) in rebind {
+                val.unwrap().bind_param(&mut stmt, position)?;
+            }
+        }
+        let changed = stmt.column_count() != self.columns.len();
+        *self = stmt;
+        match changed {
+            true => Err(Error::SchemaChanged),
+            false => Ok(()),
+        }
+    }
+
+    /// Called when SQLite re-prepared the statement automatically and the number of columns
+    /// changed. The statement is reset, keeping its bindings, so that it can be stepped again.
+    fn columns_changed(&mut self) -> Error {
+        unsafe { ffi::sqlite3_reset(self.base) };
+        let len = self.column_count();
+        self.columns = (0..len).map(|i| Column::new(self.base, i)).collect();
+        self.names = OnceCell::new();
+        self.state = QueryState::Ready;
+        Error::SchemaChanged
+    }
 }
 
 impl FallibleIteratorMut for Statement {
     type Item = QueryResult;
     type Error = Error;
 
+    /// Advance the statement to the next row.
+    ///
+    /// If the statement fails with [SQLITE_SCHEMA](ffi::SQLITE_SCHEMA) before returning any
+    /// rows, it is re-prepared and retried (see
+    /// [enable_rebind_cache](Statement::enable_rebind_cache)). If the database schema changes
+    /// in a way that changes the number of result columns, this method fails with
+    /// [Error::SchemaChanged] and the statement is reset; calling this method again
+    /// restarts the query using the new columns.
     fn next(&mut self) -> Result<Option<&mut Self::Item>> {
-        match self.state {
-            QueryState::Ready | QueryState::Active => unsafe {
-                let guard = self.db().lock();
-                let rc = ffi::sqlite3_step(self.base);
-                Error::from_sqlite_desc(rc, guard)?;
-                match rc {
-                    ffi::SQLITE_DONE => {
-                        self.state = QueryState::Finished;
-                        Ok(None)
-                    }
-                    ffi::SQLITE_ROW => {
-                        self.state = QueryState::Active;
-                        Ok(Some(QueryResult::from_statement_mut(self)))
+        let mut attempt = 0;
+        loop {
+            match self.state {
+                QueryState::Ready | QueryState::Active => unsafe {
+                    let guard = self.db().lock();
+                    let rc = ffi::sqlite3_step(self.base);
+                    match Error::from_sqlite_desc(rc, guard) {
+                        Err(Error::Sqlite(code, _))
+                            if code & 0xff == ffi::SQLITE_SCHEMA
+                                && self.state == QueryState::Ready
+                                && attempt < SCHEMA_RETRIES
+                                && self.can_reprepare() =>
+                        {
+                            attempt += 1;
+                            self.reprepare()?;
+                            continue;
+                        }
+                        e => e?,
                     }
-                    _ => unreachable!(),
-                }
-            },
-            QueryState::Finished => Ok(None),
+                    return match rc {
+                        ffi::SQLITE_DONE => {
+                            self.state = QueryState::Finished;
+                            Ok(None)
+                        }
+                        ffi::SQLITE_ROW => {
+                            if self.column_count() != self.columns.len() {
+                                return Err(self.columns_changed());
+                            }
+                            self.state = QueryState::Active;
+                            Ok(Some(QueryResult::from_statement_mut(self)))
+                        }
+                        _ => unreachable!(),
+                    };
+                },
+                QueryState::Finished => return Ok(None),
+            }
         }
     }
+
+    /// Reset the statement, keeping its parameters. It no longer holds a read transaction
+    /// open, and returns no further rows until [query](Statement::query) is called again.
+    fn stop(&mut self) -> Result<()> {
+        unsafe { ffi::sqlite3_reset(self.base) };
+        self.state = QueryState::Finished;
+        Ok(())
+    }
 }
 
 impl std::fmt::Debug for Statement {
@@ -412,6 +851,62 @@ impl QueryResult {
     pub fn len(&self) -> usize {
         self.stmt.column_count()
     }
+
+    /// Returns the position of the column with the given name, if there is one. Names are
+    /// compared case-insensitively, like SQLite does. If several columns have the same
+    /// name, for example in a join, the first one is returned.
+    ///
+    /// The name of a column is only reliable if it was given an AS clause. See
+    /// [Column::name].
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        let names = self.stmt.names.get_or_init(|| {
+            let mut ret = HashMap::new();
+            for (i, c) in self.stmt.columns.iter().enumerate() {
+                if let Ok(name) = c.name() {
+                    ret.entry(name.to_ascii_lowercase()).or_insert(i);
+                }
+            }
+            ret
+        });
+        names.get(&name.to_ascii_lowercase()).copied()
+    }
+
+    /// Returns the column with the given name. See [column_index](Self::column_index) for
+    /// how the name is matched.
+    ///
+    /// Fails with [SQLITE_RANGE] if there is no such column. The error message lists the
+    /// names of the available columns.
+    pub fn col_by_name(&self, name: &str) -> Result<&Column> {
+        match self.column_index(name) {
+            Some(i) => Ok(&self.stmt.columns[i]),
+            None => Err(self.no_such_column(name)),
+        }
+    }
+
+    /// Returns the column with the given name, for use with methods which require `&mut`.
+    /// See [col_by_name](Self::col_by_name).
+    pub fn col_by_name_mut(&mut self, name: &str) -> Result<&mut Column> {
+        match self.column_index(name) {
+            Some(i) => Ok(&mut self.stmt.columns[i]),
+            None => Err(self.no_such_column(name)),
+        }
+    }
+
+    fn no_such_column(&self, name: &str) -> Error {
+        let available: Vec<_> = self
+            .stmt
+            .columns
+            .iter()
+            .map(|c| c.name().unwrap_or("?"))
+            .collect();
+        Error::Sqlite(
+            ffi::SQLITE_RANGE,
+            Some(format!(
+                "no such column: {name} (available columns: {})",
+                available.join(", ")
+            )),
+        )
+    }
 }
 
 impl Index<usize> for QueryResult {
@@ -428,6 +923,26 @@ impl IndexMut<usize> for QueryResult {
     }
 }
 
+/// Look up a column by name. See [QueryResult::column_index] for how the name is matched.
+///
+/// # Panics
+///
+/// Panics if there is no column with the given name. Use [QueryResult::col_by_name] to
+/// handle this case.
+impl Index<&str> for QueryResult {
+    type Output = Column;
+
+    fn index(&self, name: &str) -> &Self::Output {
+        self.col_by_name(name).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+impl IndexMut<&str> for QueryResult {
+    fn index_mut(&mut self, name: &str) -> &mut Self::Output {
+        self.col_by_name_mut(name).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
 impl std::fmt::Debug for QueryResult {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut dt = f.debug_tuple("QueryResult");
@@ -1,6 +1,7 @@
 use super::Statement;
 use crate::{ffi, sqlite3_match_version, sqlite3_require_version, types::*, value::*};
 use sealed::sealed;
+use std::borrow::Cow;
 
 /// Create a [Params] with values of mixed types.
 ///
@@ -52,6 +53,35 @@ macro_rules! params {
     }
 }
 
+/// Create a [Params] which binds values to named parameters.
+///
+/// This is a shorthand for passing `("name", value)` tuples to [params!]. Each name must
+/// include the prefix used in the SQL (`:`, `@`, or `$`). Binding a name which does not appear
+/// in the statement is an error. Parameters which are not mentioned are left as NULL.
+///
+/// ```no_run
+/// use sqlite3_ext::{Connection, Result, named_params};
+///
+/// fn do_thing(conn: &Connection) -> Result<i64> {
+///     conn.execute(
+///         "INSERT INTO tbl VALUES (:number, :name)",
+///         named_params! { ":name": "one thousand twenty four", ":number": 1024 },
+///     )
+/// }
+/// ```
+#[macro_export]
+macro_rules! named_params {
+    ($($name:literal : $val:expr),* $(,)?) => {
+        |stmt: &mut $crate::query::Statement| {{
+            use $crate::query::ToParam;
+            $(
+            ($name, $val).bind_param(stmt, 0)?;
+            )*
+            Ok(())
+        }}
+    }
+}
+
 /// Trait for collections of parameters to a query.
 ///
 /// This is a private trait with no public API. There are existing implementations which should
@@ -60,11 +90,31 @@ macro_rules! params {
 /// - An empty tuple (`()`) binds no parameters to the query.
 /// - An array binds parameters that are all the same type.
 /// - The [params!] macro binds parameters of arbitrary types.
+/// - The [named_params!] macro binds parameters by name.
 /// - A closure can arbitrarily bind parameters.
 ///
 /// Named parameters are implemented by using a tuple of `("name", value)`, and can be in any
 /// order. See [params!] for an example.
 ///
+/// # Binding by reference
+///
+/// A reference to a slice, array, or Vec of parameters is also a Params, as long as a reference
+/// to the element type is a [ToParam]. This allows the same parameters to be bound multiple
+/// times without cloning them, for example when retrying a query which failed with
+/// [SQLITE_BUSY](ffi::SQLITE_BUSY) (see [Statement::retry]).
+///
+/// ```no_run
+/// use sqlite3_ext::{Connection, Result, Value};
+///
+/// fn insert_twice(conn: &Connection, data: Vec<u8>) -> Result<()> {
+///     let params = vec![Value::Blob(data.as_slice().into())];
+///     let mut stmt = conn.prepare("INSERT INTO tbl VALUES (?)")?;
+///     stmt.execute(&params)?;
+///     stmt.execute(&params)?;
+///     Ok(())
+/// }
+/// ```
+///
 /// # Using a closure
 ///
 /// If you are dynamically creating SQL queries and need to dynamically bind parameters to
@@ -119,6 +169,36 @@ impl<T: ToParam, const N: usize> Params for [T; N] {
     }
 }
 
+impl<'a, T> Params for &'a [T]
+where
+    &'a T: ToParam,
+{
+    fn bind_params(self, stmt: &mut Statement) -> Result<()> {
+        for (pos, val) in self.iter().enumerate() {
+            val.bind_param(stmt, pos as i32 + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T, const N: usize> Params for &'a [T; N]
+where
+    &'a T: ToParam,
+{
+    fn bind_params(self, stmt: &mut Statement) -> Result<()> {
+        self.as_slice().bind_params(stmt)
+    }
+}
+
+impl<'a, T> Params for &'a Vec<T>
+where
+    &'a T: ToParam,
+{
+    fn bind_params(self, stmt: &mut Statement) -> Result<()> {
+        self.as_slice().bind_params(stmt)
+    }
+}
+
 impl Params for &mut [&mut ValueRef] {
     fn bind_params(self, stmt: &mut Statement) -> Result<()> {
         for (pos, val) in self.into_iter().enumerate() {
@@ -138,13 +218,16 @@ pub trait ToParam {
     fn bind_param(self, stmt: &mut Statement, position: i32) -> Result<()>;
 }
 
+// The record expression produces the Value which is saved when the statement has a rebind
+// cache. See Statement::enable_rebind_cache.
 macro_rules! to_param {
-    ($(#[$attr:meta])* $ty:ty as ($stmt:ident, $pos:ident, $val:ident) => $impl:expr) => {
+    ($(#[$attr:meta])* $ty:ty as ($stmt:ident, $pos:ident, $val:ident) => $impl:expr, record $rec:expr) => {
         $(#[$attr])*
         #[sealed]
         impl ToParam for $ty {
             fn bind_param(self, stmt: &mut Statement, $pos: i32) -> Result<()> {
                 let $val = self;
+                stmt.record_param($pos, || $rec);
                 let $stmt = stmt.base;
                 Error::from_sqlite(unsafe { $impl })
             }
@@ -152,10 +235,16 @@ macro_rules! to_param {
     };
 }
 
-to_param!(() as (stmt, pos, _val) => ffi::sqlite3_bind_null(stmt, pos));
-to_param!(bool as (stmt, pos, val) => ffi::sqlite3_bind_int(stmt, pos, val as i32));
-to_param!(i64 as (stmt, pos, val) => ffi::sqlite3_bind_int64(stmt, pos, val));
-to_param!(f64 as (stmt, pos, val) => ffi::sqlite3_bind_double(stmt, pos, val));
+to_param!(() as (stmt, pos, _val) => ffi::sqlite3_bind_null(stmt, pos), record Some(Value::Null));
+to_param!(bool as (stmt, pos, val) => ffi::sqlite3_bind_int(stmt, pos, val as i32), record Some(Value::Integer(val as _)));
+to_param!(i64 as (stmt, pos, val) => ffi::sqlite3_bind_int64(stmt, pos, val), record Some(Value::Integer(val)));
+to_param!(f64 as (stmt, pos, val) => ffi::sqlite3_bind_double(stmt, pos, val), record Some(Value::Float(val)));
+to_param!(i32 as (stmt, pos, val) => ffi::sqlite3_bind_int(stmt, pos, val), record Some(Value::Integer(val as _)));
+to_param!(i16 as (stmt, pos, val) => ffi::sqlite3_bind_int(stmt, pos, val as _), record Some(Value::Integer(val as _)));
+to_param!(i8 as (stmt, pos, val) => ffi::sqlite3_bind_int(stmt, pos, val as _), record Some(Value::Integer(val as _)));
+to_param!(u32 as (stmt, pos, val) => ffi::sqlite3_bind_int64(stmt, pos, val as _), record Some(Value::Integer(val as _)));
+to_param!(u16 as (stmt, pos, val) => ffi::sqlite3_bind_int(stmt, pos, val as _), record Some(Value::Integer(val as _)));
+to_param!(u8 as (stmt, pos, val) => ffi::sqlite3_bind_int(stmt, pos, val as _), record Some(Value::Integer(val as _)));
 to_param!(Blob as (stmt, pos, val) => {
     let len = val.len();
     let rc = sqlite3_match_version! {
@@ -163,12 +252,13 @@ to_param!(Blob as (stmt, pos, val) => {
         _ => ffi::sqlite3_bind_blob(stmt, pos, val.into_raw(), len as _, Some(ffi::drop_blob)),
     };
     rc
-});
-to_param!(&mut ValueRef as (stmt, pos, val) => ffi::sqlite3_bind_value(stmt, pos, val.as_ptr()));
+}, record Some(Value::Blob(val.clone())));
+to_param!(&mut ValueRef as (stmt, pos, val) => ffi::sqlite3_bind_value(stmt, pos, val.as_ptr()), record val.to_owned().ok());
 
 #[sealed]
-impl<'a> ToParam for &'a str {
+impl ToParam for &str {
     fn bind_param(self, stmt: &mut Statement, pos: i32) -> Result<()> {
+        stmt.record_param(pos, || Some(Value::Text(self.to_owned())));
         let val = self.as_bytes();
         let len = val.len();
         Error::from_sqlite(unsafe {
@@ -181,15 +271,17 @@ impl<'a> ToParam for &'a str {
 }
 
 #[sealed]
-impl<'a> ToParam for &'a ValueRef {
+impl ToParam for &ValueRef {
     fn bind_param(self, stmt: &mut Statement, pos: i32) -> Result<()> {
+        stmt.record_param(pos, || self.to_owned().ok());
         unsafe { Error::from_sqlite(ffi::sqlite3_bind_value(stmt.base, pos, self.as_ptr())) }
     }
 }
 
 #[sealed]
-impl<'a> ToParam for &'a [u8] {
+impl ToParam for &[u8] {
     fn bind_param(self, stmt: &mut Statement, pos: i32) -> Result<()> {
+        stmt.record_param(pos, || Some(Value::Blob(self.into())));
         let len = self.len();
         unsafe {
             Error::from_sqlite(sqlite3_match_version! {
@@ -213,12 +305,103 @@ impl<'a> ToParam for &'a [u8] {
 }
 
 #[sealed]
-impl<'a, const N: usize> ToParam for &'a [u8; N] {
+impl<const N: usize> ToParam for &[u8; N] {
+    fn bind_param(self, stmt: &mut Statement, pos: i32) -> Result<()> {
+        self.as_slice().bind_param(stmt, pos)
+    }
+}
+
+to_param!(&bool as (stmt, pos, val) => ffi::sqlite3_bind_int(stmt, pos, *val as i32), record Some(Value::Integer(*val as _)));
+to_param!(&i64 as (stmt, pos, val) => ffi::sqlite3_bind_int64(stmt, pos, *val), record Some(Value::Integer(*val)));
+to_param!(&i32 as (stmt, pos, val) => ffi::sqlite3_bind_int(stmt, pos, *val), record Some(Value::Integer(*val as _)));
+to_param!(&u32 as (stmt, pos, val) => ffi::sqlite3_bind_int64(stmt, pos, *val as _), record Some(Value::Integer(*val as _)));
+to_param!(&f64 as (stmt, pos, val) => ffi::sqlite3_bind_double(stmt, pos, *val), record Some(Value::Float(*val)));
+
+#[sealed]
+impl ToParam for String {
+    fn bind_param(self, stmt: &mut Statement, pos: i32) -> Result<()> {
+        self.as_str().bind_param(stmt, pos)
+    }
+}
+
+#[sealed]
+impl<'a> ToParam for Cow<'a, str> {
+    fn bind_param(self, stmt: &mut Statement, pos: i32) -> Result<()> {
+        self.as_ref().bind_param(stmt, pos)
+    }
+}
+
+#[sealed]
+impl<'a, 'b> ToParam for &'a Cow<'b, str> {
+    fn bind_param(self, stmt: &mut Statement, pos: i32) -> Result<()> {
+        self.as_ref().bind_param(stmt, pos)
+    }
+}
+
+#[sealed]
+impl ToParam for &&str {
+    fn bind_param(self, stmt: &mut Statement, pos: i32) -> Result<()> {
+        (*self).bind_param(stmt, pos)
+    }
+}
+
+#[sealed]
+impl ToParam for &String {
+    fn bind_param(self, stmt: &mut Statement, pos: i32) -> Result<()> {
+        self.as_str().bind_param(stmt, pos)
+    }
+}
+
+#[sealed]
+impl ToParam for &&[u8] {
+    fn bind_param(self, stmt: &mut Statement, pos: i32) -> Result<()> {
+        (*self).bind_param(stmt, pos)
+    }
+}
+
+#[sealed]
+impl ToParam for &Vec<u8> {
+    fn bind_param(self, stmt: &mut Statement, pos: i32) -> Result<()> {
+        self.as_slice().bind_param(stmt, pos)
+    }
+}
+
+/// Copies the BLOB into SQLite, leaving the original in place.
+#[sealed]
+impl ToParam for &Blob {
     fn bind_param(self, stmt: &mut Statement, pos: i32) -> Result<()> {
         self.as_slice().bind_param(stmt, pos)
     }
 }
 
+/// Sets the parameter to a dynamically typed [Value] without consuming it.
+#[sealed]
+impl ToParam for &Value {
+    fn bind_param(self, stmt: &mut Statement, pos: i32) -> Result<()> {
+        match self {
+            Value::Integer(x) => x.bind_param(stmt, pos),
+            Value::Float(x) => x.bind_param(stmt, pos),
+            Value::Text(x) => x.bind_param(stmt, pos),
+            Value::Blob(x) => x.bind_param(stmt, pos),
+            Value::Null => ().bind_param(stmt, pos),
+        }
+    }
+}
+
+/// Sets the parameter to the contained value or NULL, without consuming it.
+#[sealed]
+impl<'a, T> ToParam for &'a Option<T>
+where
+    &'a T: ToParam,
+{
+    fn bind_param(self, stmt: &mut Statement, pos: i32) -> Result<()> {
+        match self {
+            Some(x) => x.bind_param(stmt, pos),
+            None => ().bind_param(stmt, pos),
+        }
+    }
+}
+
 /// Sets the parameter to a dynamically typed [Value].
 #[sealed]
 impl ToParam for Value {
@@ -251,6 +434,8 @@ where
 #[sealed]
 impl<T: 'static> ToParam for PassedRef<T> {
     fn bind_param(self, stmt: &mut Statement, pos: i32) -> Result<()> {
+        // Pointers cannot be recorded, so the statement cannot be rebound.
+        stmt.record_param(pos, || None);
         let _ = (POINTER_TAG, &stmt, pos);
         sqlite3_require_version!(3_020_000, unsafe {
             Error::from_sqlite(ffi::sqlite3_bind_pointer(
@@ -264,8 +449,62 @@ impl<T: 'static> ToParam for PassedRef<T> {
     }
 }
 
+/// Sets the parameter to NULL with this pointer and tag. SQLite does not take ownership of
+/// the pointed-to value.
+#[sealed]
+impl<T> ToParam for UnsafePassedRef<T> {
+    fn bind_param(self, stmt: &mut Statement, pos: i32) -> Result<()> {
+        stmt.record_param(pos, || None);
+        let (ptr, tag) = self.as_parts();
+        let _ = (ptr, tag, &stmt, pos);
+        sqlite3_require_version!(3_020_000, unsafe {
+            Error::from_sqlite(ffi::sqlite3_bind_pointer(
+                stmt.base,
+                pos,
+                ptr as _,
+                tag.as_ptr(),
+                None,
+            ))
+        })
+    }
+}
+
+/// Binds a value using a custom conversion.
+///
+/// The conversion is applied when the parameter is bound, and must produce a value which is
+/// a [ToParam]. This allows types which are not supported directly to be used as parameters,
+/// for example by storing a struct as JSON text.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sqlite3_ext::{query::As, *};
+///
+/// struct Point {
+///     x: i64,
+///     y: i64,
+/// }
+///
+/// fn insert(conn: &Connection, point: &Point) -> Result<i64> {
+///     let json = As(point, |p: &Point| Ok(format!("{{\"x\":{},\"y\":{}}}", p.x, p.y)));
+///     conn.execute("INSERT INTO points VALUES (?)", [json])
+/// }
+/// ```
+pub struct As<T, F>(pub T, pub F);
+
+#[sealed]
+impl<T, F, P> ToParam for As<T, F>
+where
+    F: FnOnce(T) -> Result<P>,
+    P: ToParam,
+{
+    fn bind_param(self, stmt: &mut Statement, pos: i32) -> Result<()> {
+        (self.1)(self.0)?.bind_param(stmt, pos)
+    }
+}
+
 /// Used to bind named parameters. Sets the parameter with the name at `self.0` to the value at
-/// `self.1`.
+/// `self.1`. Returns an [SQLITE_RANGE](ffi::SQLITE_RANGE) error if the statement has no parameter with that name.
 #[sealed]
 impl<K, V> ToParam for (K, V)
 where
@@ -273,10 +512,16 @@ where
     V: ToParam,
 {
     fn bind_param(self, stmt: &mut Statement, _: i32) -> Result<()> {
-        let pos = stmt.parameter_position(self.0);
-        match pos {
+        let name: Vec<u8> = self.0.into();
+        match stmt.parameter_position(name.clone()) {
             Some(pos) => self.1.bind_param(stmt, pos.get()),
-            None => Err(SQLITE_RANGE),
+            None => Err(Error::Sqlite(
+                ffi::SQLITE_RANGE,
+                Some(format!(
+                    "no such parameter: {}",
+                    String::from_utf8_lossy(&name)
+                )),
+            )),
         }
     }
 }
@@ -0,0 +1,121 @@
+use super::{QueryResult, ToParam};
+use crate::{connection::quote_identifier, ffi, iterator::*, types::*, value::*, Connection};
+
+impl Connection {
+    /// Returns the value of a pragma, such as `user_version`. Pragmas which return no rows
+    /// produce [Value::Null], and pragmas which return several rows produce the first
+    /// column of the first row. Use [pragma_query](Self::pragma_query) to read all of the
+    /// rows.
+    ///
+    /// The name is quoted, so it cannot be used to inject other SQL.
+    pub fn pragma(&self, name: &str) -> Result<Value> {
+        self.pragma_value(&format!("PRAGMA {}", quote_identifier(name)))
+    }
+
+    /// Returns the value of a pragma for the given schema, such as "main" or the name of an
+    /// attached database. Otherwise, this method is the same as [pragma](Self::pragma).
+    pub fn pragma_on(&self, schema: &str, name: &str) -> Result<Value> {
+        self.pragma_value(&format!(
+            "PRAGMA {}.{}",
+            quote_identifier(schema),
+            quote_identifier(name)
+        ))
+    }
+
+    /// Set the value of a pragma, returning the first column of the first row that the
+    /// pragma produces, or [Value::Null] if it produces no rows. For example, setting
+    /// `journal_mode` returns the new journal mode.
+    ///
+    /// SQLite does not allow parameters in PRAGMA statements, so the value is rendered as an
+    /// SQL literal. Only integers, floats, and text are accepted; other values fail with
+    /// [SQLITE_MISUSE].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::*;
+    ///
+    /// fn bump_version(db: &Connection) -> Result<()> {
+    ///     let version = match db.pragma("user_version")? {
+    ///         Value::Integer(x) => x,
+    ///         _ => 0,
+    ///     };
+    ///     db.set_pragma("user_version", version + 1)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_pragma<P: ToParam>(&self, name: &str, value: P) -> Result<Value> {
+        let literal = self.pragma_literal(value)?;
+        self.pragma_value(&format!("PRAGMA {} = {literal}", quote_identifier(name)))
+    }
+
+    /// Run a pragma which returns rows, such as `database_list`, calling the provided
+    /// function for each row.
+    pub fn pragma_query<F>(&self, name: &str, f: F) -> Result<()>
+    where
+        F: FnMut(&mut QueryResult) -> Result<()>,
+    {
+        self.pragma_rows(&format!("PRAGMA {}", quote_identifier(name)), f)
+    }
+
+    /// Run a pragma which takes an argument and returns rows, such as `table_info`,
+    /// calling the provided function for each row. The argument is rendered the same way
+    /// as in [set_pragma](Self::set_pragma).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::*;
+    ///
+    /// fn column_names(db: &Connection, table: &str) -> Result<Vec<String>> {
+    ///     let mut ret = vec![];
+    ///     db.pragma_query_with("table_info", table, |row| {
+    ///         ret.push(row[1].get_str()?.to_owned());
+    ///         Ok(())
+    ///     })?;
+    ///     Ok(ret)
+    /// }
+    /// ```
+    pub fn pragma_query_with<P: ToParam, F>(&self, name: &str, arg: P, f: F) -> Result<()>
+    where
+        F: FnMut(&mut QueryResult) -> Result<()>,
+    {
+        let literal = self.pragma_literal(arg)?;
+        self.pragma_rows(&format!("PRAGMA {}({literal})", quote_identifier(name)), f)
+    }
+
+    fn pragma_value(&self, sql: &str) -> Result<Value> {
+        let mut stmt = self.prepare(sql)?;
+        stmt.query(())?;
+        match stmt.next()? {
+            Some(row) if row.len() > 0 => row[0].to_owned(),
+            _ => Ok(Value::Null),
+        }
+    }
+
+    fn pragma_rows<F>(&self, sql: &str, mut f: F) -> Result<()>
+    where
+        F: FnMut(&mut QueryResult) -> Result<()>,
+    {
+        let mut stmt = self.prepare(sql)?;
+        stmt.query(())?;
+        while let Some(row) = stmt.next()? {
+            f(row)?;
+        }
+        Ok(())
+    }
+
+    /// Render a value as an SQL literal using the built-in quote function.
+    fn pragma_literal<P: ToParam>(&self, value: P) -> Result<String> {
+        let mut stmt = self.prepare("SELECT quote(?), typeof(?1)")?;
+        stmt.query([value])?;
+        let row = stmt.next()?.ok_or(SQLITE_MISUSE)?;
+        match row[1].get_str()? {
+            "integer" | "real" | "text" => Ok(row[0].get_str()?.to_owned()),
+            ty => Err(Error::Sqlite(
+                ffi::SQLITE_MISUSE,
+                Some(format!("{ty} cannot be used as a pragma value")),
+            )),
+        }
+    }
+}
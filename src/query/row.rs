@@ -0,0 +1,124 @@
+use super::{FromRow, QueryResult, Statement};
+use crate::{iterator::*, sql::ident_eq, types::*, value::*};
+use std::ops::Index;
+
+/// An owned copy of a row returned from a query.
+///
+/// A [QueryResult] borrows the [Statement] which produced it, so it cannot be held while the
+/// statement advances to the next row. A Row copies the values and column names out of the
+/// statement, so it can be kept for as long as necessary. Use
+/// [QueryResult::to_owned_row] to copy a single row, or [Statement::collect_rows] to copy
+/// all of the remaining rows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    values: Vec<Value>,
+    names: Vec<String>,
+}
+
+impl Row {
+    /// Returns the number of columns in the row.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true if the row has no columns.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the value of the column at the given position, or None if the position is
+    /// out of range.
+    pub fn get(&self, idx: usize) -> Option<&Value> {
+        self.values.get(idx)
+    }
+
+    /// Returns the value of the first column with the given name, or None if there is no
+    /// such column. Names are compared using [ident_eq]. See
+    /// [Column::name](super::Column::name) for how columns are named.
+    pub fn get_by_name(&self, name: &str) -> Option<&Value> {
+        let idx = self.names.iter().position(|n| ident_eq(n, name))?;
+        self.values.get(idx)
+    }
+
+    /// Returns the names of the columns.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Returns the values of the columns.
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+
+    /// Consumes the row, returning the values of the columns.
+    pub fn into_values(self) -> Vec<Value> {
+        self.values
+    }
+}
+
+impl Index<usize> for Row {
+    type Output = Value;
+
+    fn index(&self, index: usize) -> &Value {
+        &self.values[index]
+    }
+}
+
+/// Copies the row using [QueryResult::to_owned_row].
+impl FromRow for Row {
+    fn from_row(row: &mut QueryResult) -> Result<Self> {
+        row.to_owned_row()
+    }
+}
+
+impl QueryResult {
+    /// Copy the values and column names of this row into a [Row].
+    pub fn to_owned_row(&self) -> Result<Row> {
+        let names = self.column_names()?;
+        self.to_owned_row_with_names(names)
+    }
+
+    fn column_names(&self) -> Result<Vec<String>> {
+        (0..self.len())
+            .map(|i| Ok(self[i].name()?.to_owned()))
+            .collect()
+    }
+
+    fn to_owned_row_with_names(&self, names: Vec<String>) -> Result<Row> {
+        let values = (0..self.len())
+            .map(|i| self[i].to_owned())
+            .collect::<Result<_>>()?;
+        Ok(Row { values, names })
+    }
+}
+
+impl Statement {
+    /// Copy all of the remaining rows of the query into a Vec. The column names are only
+    /// read from SQLite once, for the first row.
+    ///
+    /// This method does not bind any parameters or reset the statement. Use
+    /// [query](Statement::query) first to start a new query.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::*;
+    ///
+    /// fn values(conn: &Connection) -> Result<Vec<Value>> {
+    ///     let rows = conn.prepare("SELECT x FROM tbl")?.query(())?.collect_rows()?;
+    ///     Ok(rows.into_iter().flat_map(|r| r.into_values()).collect())
+    /// }
+    /// ```
+    pub fn collect_rows(&mut self) -> Result<Vec<Row>> {
+        let mut names = None;
+        let mut ret = vec![];
+        while let Some(row) = self.next()? {
+            let names = match &names {
+                Some(names) => names,
+                None => names.insert(row.column_names()?),
+            };
+            ret.push(row.to_owned_row_with_names(names.clone())?);
+        }
+        Ok(ret)
+    }
+}
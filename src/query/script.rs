@@ -0,0 +1,206 @@
+use super::RunSummary;
+use crate::{ffi, iterator::*, sql::split_statements, types::*, Connection};
+use std::{
+    ffi::CString,
+    ops::ControlFlow,
+    time::{Duration, Instant},
+};
+
+type ProgressFn<'a> = dyn FnMut(usize, usize, &str) -> ControlFlow<()> + 'a;
+
+/// Options for [Connection::execute_script].
+#[derive(Default)]
+pub struct ScriptOptions<'a> {
+    progress: Option<Box<ProgressFn<'a>>>,
+    transaction: bool,
+}
+
+impl<'a> ScriptOptions<'a> {
+    /// Set a callback which is invoked before each statement is executed. The callback
+    /// receives the index of the statement, the estimated number of statements in the
+    /// script, and the SQL of the statement. The estimate counts statements which consist
+    /// only of comments, but those are skipped without invoking the callback. Returning
+    /// [ControlFlow::Break] aborts the script with [SQLITE_ABORT] before the statement is
+    /// executed.
+    pub fn set_progress<F>(mut self, progress: F) -> Self
+    where
+        F: FnMut(usize, usize, &str) -> ControlFlow<()> + 'a,
+    {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Enable or disable running the script inside of a savepoint. If enabled, all of the
+    /// changes made by the script are rolled back if any statement fails or the script is
+    /// aborted. Such a script cannot contain BEGIN, COMMIT, or ROLLBACK statements.
+    pub fn set_transaction(mut self, transaction: bool) -> Self {
+        self.transaction = transaction;
+        self
+    }
+}
+
+impl std::fmt::Debug for ScriptOptions<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScriptOptions")
+            .field("progress", &self.progress.is_some())
+            .field("transaction", &self.transaction)
+            .finish()
+    }
+}
+
+/// The result of a single statement executed by [Connection::execute_script].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatementReport {
+    /// The SQL of the statement.
+    pub sql: String,
+    /// The number of rows modified by the statement, as reported by
+    /// [Connection::changes]. This is 0 for statements which are not INSERT, UPDATE, or
+    /// DELETE.
+    pub changes: i64,
+    /// The time taken to prepare and execute the statement.
+    pub elapsed: Duration,
+}
+
+/// The result of [Connection::execute_script].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptReport {
+    /// The statements which were executed, in order.
+    pub statements: Vec<StatementReport>,
+}
+
+impl ScriptReport {
+    /// Returns the total number of rows modified by the script.
+    pub fn changes(&self) -> i64 {
+        self.statements.iter().map(|s| s.changes).sum()
+    }
+
+    /// Returns the total time taken to execute the script.
+    pub fn elapsed(&self) -> Duration {
+        self.statements.iter().map(|s| s.elapsed).sum()
+    }
+}
+
+impl Connection {
+    /// Execute every statement in a script, such as one which creates the schema of an
+    /// extension.
+    ///
+    /// The script is split into statements using [split_statements], so semicolons inside
+    /// of triggers and string literals are handled correctly. Statements which return rows
+    /// are run to completion and the rows are discarded. Execution stops at the first
+    /// statement which fails, and the error is returned. Unless
+    /// [ScriptOptions::set_transaction] was used, the statements which executed before the
+    /// failure remain in effect.
+    ///
+    /// Fails with [Error::NulError] without executing anything if the script contains a nul
+    /// byte.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::{query::ScriptOptions, *};
+    /// use std::ops::ControlFlow;
+    ///
+    /// const SCHEMA: &str = "
+    ///     CREATE TABLE docs(id INTEGER PRIMARY KEY, body TEXT);
+    ///     CREATE TABLE log(id INTEGER);
+    ///     CREATE TRIGGER docs_log AFTER INSERT ON docs BEGIN
+    ///         INSERT INTO log VALUES (new.id);
+    ///     END;
+    /// ";
+    ///
+    /// fn init_schema(db: &Connection) -> Result<()> {
+    ///     let opts = ScriptOptions::default()
+    ///         .set_transaction(true)
+    ///         .set_progress(|i, total, _| {
+    ///             eprintln!("statement {} of {}", i + 1, total);
+    ///             ControlFlow::Continue(())
+    ///         });
+    ///     db.execute_script(SCHEMA, opts)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn execute_script(&self, script: &str, mut opts: ScriptOptions) -> Result<ScriptReport> {
+        // SQLite stops reading at a nul, so the statements after one would be skipped.
+        CString::new(script)?;
+        let _guard = self.lock();
+        let savepoint = match opts.transaction {
+            true => Some(self.savepoint("execute_script")?),
+            false => None,
+        };
+        let stmts = split_statements(script);
+        let mut report = ScriptReport::default();
+        for sql in stmts.iter().copied() {
+            let start = Instant::now();
+            let mut stmt = match self.prepare_first(sql)?.0 {
+                Some(stmt) => stmt,
+                None => continue,
+            };
+            let index = report.statements.len();
+            if let Some(progress) = opts.progress.as_mut() {
+                if progress(index, stmts.len(), sql).is_break() {
+                    return Err(Error::Sqlite(
+                        ffi::SQLITE_ABORT,
+                        Some(format!("script aborted before statement {}", index + 1)),
+                    ));
+                }
+            }
+            let before = self.total_changes();
+            stmt.query(())?;
+            while stmt.next()?.is_some() {}
+            drop(stmt);
+            let changes = match self.total_changes() == before {
+                true => 0,
+                false => self.changes(),
+            };
+            report.statements.push(StatementReport {
+                sql: sql.to_owned(),
+                changes,
+                elapsed: start.elapsed(),
+            });
+        }
+        if let Some(savepoint) = savepoint {
+            savepoint.release()?;
+        }
+        Ok(report)
+    }
+
+    /// Run every statement in some SQL using [Statement::run], discarding any rows that
+    /// they return, and return a [RunSummary] for each statement.
+    ///
+    /// Execution stops at the first statement which fails. SQLite errors are returned with
+    /// the text of the failed statement appended to the message, and the statements which
+    /// executed before the failure remain in effect. Use [execute_script](Self::execute_script)
+    /// for more control over how the statements are run.
+    pub fn execute_batch(&self, sql: &str) -> Result<Vec<RunSummary>> {
+        let _guard = self.lock();
+        let mut ret = vec![];
+        let mut rest = sql;
+        while !rest.trim().is_empty() {
+            let (stmt, tail) = self.prepare_first(rest).map_err(|e| {
+                let failed = split_statements(rest).first().copied().unwrap_or(rest);
+                in_statement(e, failed)
+            })?;
+            if let Some(mut stmt) = stmt {
+                match stmt.run(()) {
+                    Ok(summary) => ret.push(summary),
+                    Err(e) => return Err(in_statement(e, stmt.sql().unwrap_or(""))),
+                }
+            }
+            if tail.len() == rest.len() {
+                break;
+            }
+            rest = tail;
+        }
+        Ok(ret)
+    }
+}
+
+/// Append the text of a statement to the message of an SQLite error.
+fn in_statement(e: Error, sql: &str) -> Error {
+    match e {
+        Error::Sqlite(code, _) => {
+            Error::Sqlite(code, Some(format!("{e} in statement: {}", sql.trim())))
+        }
+        e => e,
+    }
+}
@@ -0,0 +1,152 @@
+use super::Params;
+use crate::{iterator::*, types::*, value::*, Connection};
+use std::fmt;
+
+/// The complete results of a query, as returned by [Connection::query_table].
+///
+/// The Display implementation renders the table as aligned text, which is useful for
+/// diagnostics and error messages. NULL is rendered as `NULL`, blobs are rendered as
+/// hexadecimal literals, and control characters in text are escaped so that each row is
+/// rendered on a single line. For example:
+///
+/// ```text
+/// id | name  | score
+/// ---+-------+--------
+/// 1  | alice | 2.5
+/// 2  | NULL  | X'00ff'
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    columns: Vec<String>,
+    rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    /// Returns the names of the columns.
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Returns the rows of the table.
+    pub fn rows(&self) -> &[Vec<Value>] {
+        &self.rows
+    }
+
+    /// Returns the number of rows in the table.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns true if the table has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Consumes the table, returning the rows.
+    pub fn into_rows(self) -> Vec<Vec<Value>> {
+        self.rows
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| row.iter().map(render_value).collect())
+            .collect();
+        let header: Vec<String> = self.columns.iter().map(|c| escape(c)).collect();
+        let widths: Vec<usize> = (0..header.len())
+            .map(|i| {
+                std::iter::once(&header)
+                    .chain(cells.iter())
+                    .map(|row| row[i].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let write_row = |f: &mut fmt::Formatter<'_>, row: &[String]| {
+            for (i, cell) in row.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(" | ")?;
+                }
+                match i + 1 == row.len() {
+                    // The last column is not padded, to avoid trailing whitespace.
+                    true => f.write_str(cell)?,
+                    false => write!(f, "{cell:width$}", width = widths[i])?,
+                }
+            }
+            writeln!(f)
+        };
+        write_row(f, &header)?;
+        let rule: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+        writeln!(f, "{}", rule.join("-+-"))?;
+        for row in &cells {
+            write_row(f, row)?;
+        }
+        Ok(())
+    }
+}
+
+fn render_value(val: &Value) -> String {
+    match val {
+        Value::Integer(x) => x.to_string(),
+        Value::Float(x) => format!("{x:?}"),
+        Value::Text(x) => escape(x),
+        Value::Blob(x) => {
+            let hex: String = x.as_slice().iter().map(|b| format!("{b:02x}")).collect();
+            format!("X'{hex}'")
+        }
+        Value::Null => "NULL".to_owned(),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c.is_control() {
+            true => c.escape_default().to_string(),
+            false => c.to_string(),
+        })
+        .collect()
+}
+
+impl Connection {
+    /// Run a query and copy all of its results into a [Table], in the style of
+    /// `sqlite3_get_table`. This is intended for diagnostics, such as printing the contents
+    /// of a table in a test or an error message.
+    ///
+    /// Since the entire result is held in memory, the query fails with
+    /// [Error::TooManyRows] if it returns more than `max_rows` rows.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::*;
+    ///
+    /// fn dump(db: &Connection) -> Result<()> {
+    ///     println!("{}", db.query_table("SELECT * FROM tbl", (), 100)?);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn query_table<P: Params>(&self, sql: &str, params: P, max_rows: usize) -> Result<Table> {
+        let mut stmt = self.prepare(sql)?;
+        let columns = stmt
+            .columns
+            .iter()
+            .map(|c| Ok(c.name()?.to_owned()))
+            .collect::<Result<_>>()?;
+        let mut rows = vec![];
+        stmt.query(params)?;
+        while let Some(row) = stmt.next()? {
+            if rows.len() == max_rows {
+                return Err(Error::TooManyRows { max: max_rows });
+            }
+            rows.push(
+                (0..row.len())
+                    .map(|i| row[i].to_owned())
+                    .collect::<Result<_>>()?,
+            );
+        }
+        Ok(Table { columns, rows })
+    }
+}
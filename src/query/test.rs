@@ -1,7 +1,8 @@
 #![cfg(all(test, feature = "static"))]
 
-use crate::query::{Statement, ToParam};
+use crate::query::{RunSummary, ScriptOptions, Statement, ToParam};
 use crate::test_helpers::prelude::*;
+use std::ops::ControlFlow;
 
 #[test]
 fn basic() -> Result<()> {
@@ -45,6 +46,48 @@ fn basic() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn column_by_name() -> Result<()> {
+    let h = TestHelpers::new();
+    h.db.execute_batch(
+        "CREATE TABLE a(id, name); CREATE TABLE b(id, a_id, name);
+         INSERT INTO a VALUES (1, 'alice'); INSERT INTO b VALUES (10, 1, 'bob');",
+    )?;
+    let ret =
+        h.db.query_row("SELECT name AS Label, id + 1 AS next_id FROM a", (), |r| {
+            assert_eq!(r.column_index("label"), Some(0));
+            assert_eq!(r.column_index("NEXT_ID"), Some(1));
+            assert_eq!(r.column_index("name"), None);
+            Ok((r["LABEL"].get_str()?.to_owned(), r["next_id"].get_i64()))
+        })?;
+    assert_eq!(ret, ("alice".to_owned(), 2));
+
+    // Duplicate names in a join resolve to the first occurrence.
+    let ret =
+        h.db.query_row("SELECT * FROM a JOIN b ON b.a_id = a.id", (), |r| {
+            assert_eq!(r.column_index("id"), Some(0));
+            assert_eq!(r.column_index("name"), Some(1));
+            assert_eq!(r.column_index("a_id"), Some(3));
+            Ok((
+                r["id"].get_i64(),
+                r.col_by_name_mut("name")?.get_str()?.to_owned(),
+            ))
+        })?;
+    assert_eq!(ret, (1, "alice".to_owned()));
+
+    let err =
+        h.db.query_row("SELECT 1 AS x, 2 AS y", (), |r| {
+            r.col_by_name("z").map(|_| ())
+        })
+        .unwrap_err();
+    assert_eq!(err, SQLITE_RANGE);
+    assert_eq!(
+        err.to_string(),
+        "no such column: z (available columns: x, y)"
+    );
+    Ok(())
+}
+
 #[test]
 fn empty_statement() {
     let h = TestHelpers::new();
@@ -52,6 +95,19 @@ fn empty_statement() {
     assert_eq!(err, SQLITE_MISUSE);
 }
 
+#[test]
+fn nul_in_sql() -> Result<()> {
+    let h = TestHelpers::new();
+    let (stmt, rest) = h.db.prepare_first("SELECT 1;\0SELECT 2")?;
+    assert!(stmt.is_some());
+    assert_eq!(rest, "\0SELECT 2");
+    match h.db.prepare_first(rest) {
+        Err(Error::Sqlite(ffi::SQLITE_MISUSE, Some(_))) => (),
+        x => panic!("expected SQLITE_MISUSE, got {x:?}"),
+    }
+    Ok(())
+}
+
 #[test]
 fn invalid_execute() {
     let h = TestHelpers::new();
@@ -93,6 +149,85 @@ fn params() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn more_params() -> Result<()> {
+    use crate::query::As;
+    use std::borrow::Cow;
+
+    struct Point {
+        x: i64,
+        y: i64,
+    }
+
+    let h = TestHelpers::new();
+    let mut stmt = h.db.prepare("SELECT ?, typeof(?)")?;
+    let mut round_trip = |param: &dyn Fn(&mut Statement) -> Result<()>| {
+        stmt.query(|stmt: &mut Statement| param(stmt))?;
+        let ret = stmt.next()?.expect("no row");
+        Ok::<_, Error>((ret[0].to_owned()?, ret[1].get_str()?.to_owned()))
+    };
+    macro_rules! check {
+        ($val:expr, $expected:expr, $ty:literal) => {
+            assert_eq!(
+                round_trip(&|stmt| {
+                    $val.bind_param(stmt, 1)?;
+                    $val.bind_param(stmt, 2)
+                })?,
+                ($expected, $ty.to_owned())
+            );
+        };
+    }
+    check!(200u8, Value::Integer(200), "integer");
+    check!(65535u16, Value::Integer(65535), "integer");
+    check!(u32::MAX, Value::Integer(u32::MAX as i64), "integer");
+    check!(-128i8, Value::Integer(-128), "integer");
+    check!(-32768i16, Value::Integer(-32768), "integer");
+    check!(i32::MIN, Value::Integer(i32::MIN as i64), "integer");
+    check!(true, Value::Integer(1), "integer");
+    check!(&[1u8, 2, 3], Value::Blob(Blob::from([1, 2, 3])), "blob");
+    check!(Cow::Borrowed("cow"), Value::Text("cow".to_owned()), "text");
+    check!(
+        Cow::<str>::Owned("owned".to_owned()),
+        Value::Text("owned".to_owned()),
+        "text"
+    );
+    check!(Some(5u8), Value::Integer(5), "integer");
+    check!(None::<u32>, Value::Null, "null");
+    check!(Value::Float(0.5), Value::Float(0.5), "real");
+
+    // Empty strings and blobs are not NULL.
+    check!("", Value::Text(String::new()), "text");
+    check!(String::new(), Value::Text(String::new()), "text");
+    check!(&[0u8; 0], Value::Blob(Blob::from([])), "blob");
+    check!(
+        Vec::<u8>::new().as_slice(),
+        Value::Blob(Blob::from([])),
+        "blob"
+    );
+    check!(Blob::from([]), Value::Blob(Blob::from([])), "blob");
+
+    // A ValueRef from another query is bound directly.
+    let mut source = h.db.prepare("SELECT x'00ff'")?;
+    let row = source.next()?.expect("no row");
+    check!(row[0].as_ref(), Value::Blob(Blob::from([0, 255])), "blob");
+
+    let point = Point { x: 1, y: -2 };
+    let as_json = |p: &Point| Ok(format!("{{\"x\":{},\"y\":{}}}", p.x, p.y));
+    check!(
+        As(&point, as_json),
+        Value::Text(r#"{"x":1,"y":-2}"#.to_owned()),
+        "text"
+    );
+    let err =
+        h.db.execute(
+            "SELECT ?",
+            [As(&point, |_: &Point| Err::<(), _>(SQLITE_MISMATCH))],
+        )
+        .unwrap_err();
+    assert_eq!(err, SQLITE_MISMATCH);
+    Ok(())
+}
+
 #[test]
 fn value_params() -> Result<()> {
     let h = TestHelpers::new();
@@ -160,6 +295,36 @@ fn named_params() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn named_params_macro() -> Result<()> {
+    let h = TestHelpers::new();
+    let mut stmt = h.db.prepare("SELECT :a, :b")?;
+
+    let ret = stmt.query_row(named_params! { ":a": 1, ":b": "x" }, |r| {
+        Ok((r[0].get_i64(), r[1].get_str()?.to_owned()))
+    })?;
+    assert_eq!(ret, (1, "x".to_owned()));
+    let ret = stmt.query_row(named_params! { ":b": "y", ":a": 2 }, |r| {
+        Ok((r[0].get_i64(), r[1].get_str()?.to_owned()))
+    })?;
+    assert_eq!(ret, (2, "y".to_owned()));
+    // Unbound parameters are NULL
+    let ret = stmt.query_row(named_params! { ":b": "z" }, |r| {
+        Ok((r[0].to_owned()?, r[1].to_owned()?))
+    })?;
+    assert_eq!(ret, (Value::Null, Value::Text("z".to_owned())));
+
+    let err = stmt
+        .query_row(named_params! { ":c": 3 }, |_| Ok(()))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Error::Sqlite(ffi::SQLITE_RANGE, Some("no such parameter: :c".to_owned()))
+    );
+    assert_eq!(err.to_string(), "no such parameter: :c");
+    Ok(())
+}
+
 #[test]
 #[cfg(modern_sqlite)]
 fn passed_ref() -> Result<()> {
@@ -211,3 +376,765 @@ fn reuse_statement() -> Result<()> {
     assert_eq!(ret, Value::Null);
     Ok(())
 }
+
+#[test]
+fn query_as() -> Result<()> {
+    let h = TestHelpers::new();
+    let mut stmt = h.db.prepare("VALUES (1, 'a', NULL), (2, 'b', 2.5)")?;
+    let ret: Vec<(i64, String, Option<f64>)> = stmt.query_as(())?.collect()?;
+    assert_eq!(
+        ret,
+        vec![(1, "a".to_owned(), None), (2, "b".to_owned(), Some(2.5))]
+    );
+
+    let ret: (u32, bool) = h.db.query_row_as("SELECT 4, 1", ())?;
+    assert_eq!(ret, (4, true));
+
+    let err =
+        h.db.query_row_as::<(i64,), _>("SELECT 1, 2", ())
+            .unwrap_err();
+    assert_eq!(
+        err,
+        Error::Sqlite(
+            ffi::SQLITE_MISMATCH,
+            Some("query returned 2 columns, but 1 were expected".to_owned())
+        )
+    );
+    let err =
+        h.db.query_row_as::<(i64,), _>("SELECT NULL", ())
+            .unwrap_err();
+    assert_eq!(
+        err,
+        Error::Sqlite(
+            ffi::SQLITE_MISMATCH,
+            Some("column 0 is NULL but a non-NULL value was expected".to_owned())
+        )
+    );
+    let err = h.db.query_row_as::<(u64,), _>("SELECT -1", ()).unwrap_err();
+    assert_eq!(
+        err,
+        Error::Sqlite(
+            ffi::SQLITE_MISMATCH,
+            Some("-1 is out of range for u64".to_owned())
+        )
+    );
+    let err =
+        h.db.query_row_as::<(i32,), _>("SELECT 1 << 32", ())
+            .unwrap_err();
+    assert_eq!(
+        err,
+        Error::Sqlite(
+            ffi::SQLITE_MISMATCH,
+            Some("4294967296 is out of range for i32".to_owned())
+        )
+    );
+    let err =
+        h.db.query_row_as::<(i32,), _>("SELECT 2.5", ())
+            .unwrap_err();
+    assert_eq!(
+        err,
+        Error::Sqlite(
+            ffi::SQLITE_MISMATCH,
+            Some("2.5 is not an integer".to_owned())
+        )
+    );
+    assert!(h.db.query_row_as::<(i32,), _>("SELECT 'abc'", ()).is_err());
+    let err =
+        h.db.query_row_as::<(i64,), _>("SELECT 2.5", ())
+            .unwrap_err();
+    assert_eq!(
+        err,
+        Error::Sqlite(
+            ffi::SQLITE_MISMATCH,
+            Some("2.5 is not an integer".to_owned())
+        )
+    );
+    assert_eq!(h.db.query_row_as::<(i64,), _>("SELECT 2.0", ())?, (2,));
+    assert!(h.db.query_row_as::<(i64,), _>("SELECT 'abc'", ()).is_err());
+    assert_eq!(h.db.query_row_as::<(f64,), _>("SELECT 2", ())?, (2.0,));
+    let err =
+        h.db.query_row_as::<(f64,), _>("SELECT 'abc'", ())
+            .unwrap_err();
+    assert_eq!(
+        err,
+        Error::Sqlite(
+            ffi::SQLITE_MISMATCH,
+            Some("Text is not a number".to_owned())
+        )
+    );
+    Ok(())
+}
+
+fn open_schema_pair(name: &str) -> Result<(Database, Database, std::path::PathBuf)> {
+    let path = std::env::temp_dir().join(format!("sqlite3_ext_{}_{}.db", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let a = Database::open(&path)?;
+    let b = Database::open(&path)?;
+    a.execute("CREATE TABLE tbl(a)", ())?;
+    a.execute("INSERT INTO tbl VALUES (1)", ())?;
+    Ok((a, b, path))
+}
+
+#[test]
+fn schema_changed() -> Result<()> {
+    let (a, b, path) = open_schema_pair("schema_changed")?;
+    let mut stmt = a.prepare("SELECT * FROM tbl")?;
+    let mut same = a.prepare("SELECT a FROM tbl")?;
+    b.execute("ALTER TABLE tbl ADD COLUMN b DEFAULT 2", ())?;
+
+    // The number of columns is unchanged, so the retry is transparent.
+    assert_eq!(same.query_row((), |r| Ok(r[0].get_i64()))?, 1);
+
+    assert!(matches!(stmt.next(), Err(Error::SchemaChanged)));
+    let row = stmt.next()?.expect("no row");
+    assert_eq!(row.len(), 2);
+    assert_eq!((row[0].get_i64(), row[1].get_i64()), (1, 2));
+    assert!(stmt.next()?.is_none());
+    drop((stmt, same, a, b));
+    std::fs::remove_file(&path).unwrap();
+    Ok(())
+}
+
+#[test]
+fn schema_retry() -> Result<()> {
+    let (a, b, path) = open_schema_pair("schema_retry")?;
+    let mut stmt = a.prepare("SELECT a FROM tbl")?;
+    // Change the schema every time SQLite re-prepares the statement, after the schema has
+    // been loaded, until SQLite gives up and returns SQLITE_SCHEMA. The next attempt, made
+    // by Statement::next, succeeds.
+    let changes = std::rc::Rc::new(std::cell::Cell::new(0));
+    let counter = changes.clone();
+    let bump = move |n: i32| b.execute(&format!("CREATE TABLE t{n}(x)"), ()).map(|_| ());
+    bump(0)?;
+    a.set_authorizer(move |action| {
+        if let AuthAction::Read { .. } = action {
+            let n = counter.get();
+            if n < 50 {
+                counter.set(n + 1);
+                bump(n + 1).unwrap();
+            }
+        }
+        AuthResult::Allow
+    })?;
+    assert_eq!(stmt.query_row((), |r| Ok(r[0].get_i64()))?, 1);
+    assert_eq!(changes.get(), 50);
+    drop(stmt);
+    a.clear_authorizer()?;
+    drop(a);
+    std::fs::remove_file(&path).unwrap();
+    Ok(())
+}
+
+#[test]
+fn reprepare() -> Result<()> {
+    let (a, b, path) = open_schema_pair("reprepare")?;
+    let mut stmt = a.prepare("SELECT ?, ? FROM tbl")?;
+    stmt.query(params!["x", 5])?;
+    assert!(!stmt.can_reprepare());
+    stmt.enable_rebind_cache().query(params!["x", 5])?;
+    b.execute("ALTER TABLE tbl ADD COLUMN b", ())?;
+    stmt.reprepare()?;
+    let row = stmt.next()?.expect("no row");
+    assert_eq!(row[0].get_str()?, "x");
+    assert_eq!(row[1].get_i64(), 5);
+    drop(stmt);
+
+    let mut stmt = a.prepare("SELECT * FROM tbl")?;
+    b.execute("ALTER TABLE tbl ADD COLUMN c", ())?;
+    // Make the connection notice the new schema, as it would have before SQLITE_SCHEMA.
+    a.execute("SELECT 1 FROM tbl", ()).ok();
+    assert!(matches!(stmt.reprepare(), Err(Error::SchemaChanged)));
+    assert_eq!(stmt.next()?.expect("no row").len(), 3);
+    drop((stmt, a, b));
+    std::fs::remove_file(&path).unwrap();
+    Ok(())
+}
+
+#[test]
+fn metadata() -> Result<()> {
+    use crate::query::StatementStatus;
+
+    let h = TestHelpers::new();
+    h.db.execute("CREATE TABLE tbl(a)", ())?;
+    h.db.execute("INSERT INTO tbl VALUES (1), (2), (3)", ())?;
+
+    let mut stmt = h.db.prepare("SELECT a FROM tbl")?;
+    assert!(!stmt.is_busy());
+
+    assert_eq!(stmt.status(StatementStatus::VmStep, false)?, 0);
+    stmt.query(())?;
+    assert!(stmt.next()?.is_some());
+    assert!(stmt.is_busy());
+    let partial = stmt.status(StatementStatus::VmStep, false)?;
+    assert!(partial > 0);
+    while stmt.next()?.is_some() {}
+    assert!(!stmt.is_busy());
+    let total = stmt.status(StatementStatus::VmStep, true)?;
+    assert!(total > partial, "{total} > {partial}");
+    assert_eq!(stmt.status(StatementStatus::VmStep, false)?, 0);
+    assert_eq!(stmt.status(StatementStatus::FullscanStep, false)?, 2);
+    assert_eq!(stmt.status(StatementStatus::Sort, false)?, 0);
+    Ok(())
+}
+
+#[test]
+#[cfg(modern_sqlite)]
+fn metadata_modern() -> Result<()> {
+    use crate::query::{ExplainMode, StatementStatus};
+
+    let h = TestHelpers::new();
+    h.db.execute("CREATE TABLE tbl(a)", ())?;
+    assert!(h.db.prepare("SELECT a FROM tbl")?.is_readonly()?);
+    assert!(!h.db.prepare("INSERT INTO tbl VALUES (4)")?.is_readonly()?);
+
+    let mut stmt = h.db.prepare("SELECT 1")?;
+    assert!(!stmt.is_explain()?);
+    assert_eq!(
+        h.db.prepare("EXPLAIN SELECT 1")?.explain_mode()?,
+        ExplainMode::Explain
+    );
+    let explain = h.db.prepare("EXPLAIN QUERY PLAN SELECT 1")?;
+    assert!(explain.is_explain()?);
+    assert_eq!(explain.explain_mode()?, ExplainMode::QueryPlan);
+
+    assert_eq!(stmt.status(StatementStatus::Run, false)?, 0);
+    stmt.execute(()).unwrap_err();
+    stmt.query_row((), |_| Ok(()))?;
+    assert_eq!(stmt.status(StatementStatus::Run, false)?, 2);
+    assert!(stmt.status(StatementStatus::MemUsed, false)? > 0);
+    Ok(())
+}
+
+#[test]
+#[cfg(modern_sqlite)]
+fn prepare_flags() -> Result<()> {
+    use crate::query::PrepareFlags;
+
+    let h = TestHelpers::new();
+    h.db.execute("CREATE TABLE tbl(a)", ())?;
+    let sql = "SELECT name FROM pragma_table_info('tbl')";
+    let name =
+        h.db.prepare_with(sql, PrepareFlags::PERSISTENT)?
+            .query_row((), |r| Ok(r[0].get_str()?.to_owned()))?;
+    assert_eq!(name, "a");
+    let err = h.db.prepare_with(sql, PrepareFlags::NO_VTAB).unwrap_err();
+    assert_eq!(err, Error::Sqlite(ffi::SQLITE_ERROR, None));
+    h.db.prepare_with("SELECT a FROM tbl", PrepareFlags::NO_VTAB)?;
+
+    let (stmt, rest) = h.db.prepare_first_with(
+        "SELECT a FROM tbl; SELECT 2",
+        PrepareFlags::PERSISTENT | PrepareFlags::NO_VTAB,
+    )?;
+    assert!(stmt.is_some());
+    assert_eq!(rest, " SELECT 2");
+    Ok(())
+}
+
+#[test]
+fn owned_rows() -> Result<()> {
+    use crate::query::Row;
+    let h = TestHelpers::new();
+    h.db.execute("CREATE TABLE tbl(a, b)", ())?;
+    h.db.execute(
+        "INSERT INTO tbl VALUES ('text', x'00ff'), (2, NULL), (3.5, 'last')",
+        (),
+    )?;
+    let mut stmt = h.db.prepare("SELECT a AS first, b AS second FROM tbl")?;
+    let row = stmt.query(())?.next()?.unwrap().to_owned_row()?;
+    // The row is still usable after the statement advances.
+    let second = stmt.next()?.unwrap().to_owned_row()?;
+    assert_eq!(row.len(), 2);
+    assert_eq!(row.names(), ["first", "second"]);
+    assert_eq!(row[0], Value::Text("text".to_owned()));
+    assert_eq!(row.get(1), Some(&Value::Blob(Blob::from([0x00, 0xff]))));
+    assert_eq!(row.get(2), None);
+    assert_eq!(second.get_by_name("first"), Some(&Value::Integer(2)));
+    assert_eq!(second.get_by_name("second"), Some(&Value::Null));
+    assert_eq!(second.get_by_name("SECOND"), Some(&Value::Null));
+    assert_eq!(second.get_by_name("third"), None);
+
+    // The remaining rows, including the names captured before the statement finished.
+    let rest = stmt.collect_rows()?;
+    assert_eq!(rest.len(), 1);
+    assert_eq!(rest[0].names(), ["first", "second"]);
+    assert_eq!(
+        rest[0].clone().into_values(),
+        vec![Value::Float(3.5), Value::Text("last".to_owned())]
+    );
+    assert_eq!(stmt.collect_rows()?, vec![]);
+
+    let all = stmt.query(())?.collect_rows()?;
+    assert_eq!(all.len(), 3);
+    assert_eq!(all[0], row);
+    let as_rows: Vec<Row> = stmt.query_as(())?.collect()?;
+    assert_eq!(as_rows, all);
+    Ok(())
+}
+
+const SCRIPT: &str = "
+    CREATE TABLE docs(id INTEGER PRIMARY KEY, body TEXT);
+    CREATE TABLE log(msg TEXT);
+    -- Semicolons inside of the trigger body must not split the statement.
+    CREATE TRIGGER docs_log AFTER INSERT ON docs BEGIN
+        INSERT INTO log VALUES ('inserted; ' || new.id);
+        INSERT INTO log VALUES ('body: ' || new.body);
+    END;
+    INSERT INTO docs(body) VALUES ('a;b'), ('c');
+    SELECT * FROM docs;
+    UPDATE docs SET body = upper(body)";
+
+#[test]
+fn execute_script() -> Result<()> {
+    let h = TestHelpers::new();
+    let mut seen = vec![];
+    let opts = ScriptOptions::default().set_progress(|i, total, sql| {
+        seen.push((i, total, sql.split_whitespace().next().unwrap().to_owned()));
+        ControlFlow::Continue(())
+    });
+    let report = h.db.execute_script(SCRIPT, opts)?;
+    let changes: Vec<_> = report.statements.iter().map(|s| s.changes).collect();
+    assert_eq!(changes, vec![0, 0, 0, 2, 0, 2]);
+    assert_eq!(report.changes(), 4);
+    assert!(report.statements[2].sql.ends_with("END;"));
+    let verbs: Vec<_> = seen.iter().map(|(_, _, v)| v.as_str()).collect();
+    assert_eq!(
+        verbs,
+        vec!["CREATE", "CREATE", "--", "INSERT", "SELECT", "UPDATE"]
+    );
+    assert!(seen.iter().enumerate().all(|(i, s)| s.0 == i && s.1 == 6));
+    let (count,): (i64,) = h.db.query_row_as("SELECT COUNT(*) FROM log", ())?;
+    assert_eq!(count, 4);
+    let (body,): (String,) =
+        h.db.query_row_as("SELECT body FROM docs WHERE id = 1", ())?;
+    assert_eq!(body, "A;B");
+    Ok(())
+}
+
+#[test]
+fn execute_script_abort() -> Result<()> {
+    let h = TestHelpers::new();
+    let opts = ScriptOptions::default()
+        .set_transaction(true)
+        .set_progress(|i, _, _| match i {
+            4 => ControlFlow::Break(()),
+            _ => ControlFlow::Continue(()),
+        });
+    let err = h.db.execute_script(SCRIPT, opts).unwrap_err();
+    assert_eq!(err, Error::Sqlite(ffi::SQLITE_ABORT, None));
+    assert_eq!(err.to_string(), "script aborted before statement 5");
+    // The savepoint rolled back the schema changes.
+    let (count,): (i64,) =
+        h.db.query_row_as("SELECT COUNT(*) FROM sqlite_master", ())?;
+    assert_eq!(count, 0);
+
+    // Without a transaction, the statements before the abort remain in effect.
+    let opts = ScriptOptions::default().set_progress(|i, _, _| match i {
+        4 => ControlFlow::Break(()),
+        _ => ControlFlow::Continue(()),
+    });
+    h.db.execute_script(SCRIPT, opts).unwrap_err();
+    let (count,): (i64,) = h.db.query_row_as("SELECT COUNT(*) FROM log", ())?;
+    assert_eq!(count, 4);
+    Ok(())
+}
+
+#[test]
+fn execute_script_error() -> Result<()> {
+    let h = TestHelpers::new();
+    let opts = ScriptOptions::default().set_transaction(true);
+    let script = "CREATE TABLE t(x); INSERT INTO t VALUES (1); INSERT INTO missing VALUES (1);";
+    let err = h.db.execute_script(script, opts).unwrap_err();
+    assert_eq!(err.to_string(), "no such table: missing");
+    let (count,): (i64,) =
+        h.db.query_row_as("SELECT COUNT(*) FROM sqlite_master", ())?;
+    assert_eq!(count, 0);
+    let report = h.db.execute_script(&script[..44], Default::default())?;
+    assert_eq!(report.statements.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn execute_script_nul() -> Result<()> {
+    let h = TestHelpers::new();
+    let err =
+        h.db.execute_script("CREATE TABLE t(x);\0CREATE TABLE u(x);", Default::default())
+            .unwrap_err();
+    assert!(matches!(err, Error::NulError(e) if e.nul_position() == 18));
+    let (count,): (i64,) =
+        h.db.query_row_as("SELECT COUNT(*) FROM sqlite_master", ())?;
+    assert_eq!(count, 0);
+    Ok(())
+}
+
+#[test]
+fn pragma() -> Result<()> {
+    let h = TestHelpers::new();
+    assert_eq!(h.db.pragma("journal_mode")?, Value::Text("memory".into()));
+    assert_eq!(
+        h.db.set_pragma("journal_mode", "off")?,
+        Value::Text("off".into())
+    );
+    assert_eq!(
+        h.db.pragma_on("main", "journal_mode")?,
+        Value::Text("off".into())
+    );
+
+    assert_eq!(h.db.pragma("user_version")?, Value::Integer(0));
+    // Setting user_version returns no rows.
+    assert_eq!(h.db.set_pragma("user_version", 42)?, Value::Null);
+    assert_eq!(h.db.pragma("user_version")?, Value::Integer(42));
+    assert_eq!(h.db.set_pragma("user_version", -7)?, Value::Null);
+    assert_eq!(h.db.pragma_on("main", "user_version")?, Value::Integer(-7));
+
+    // Values are rendered as literals, so they cannot inject SQL.
+    h.db.execute("CREATE TABLE t (x)", ())?;
+    h.db.set_pragma("user_version", "1; DROP TABLE t")?;
+    h.db.execute("SELECT * FROM t", ())?;
+    let err = h.db.set_pragma("user_version", ()).unwrap_err();
+    assert_eq!(err.to_string(), "null cannot be used as a pragma value");
+    let err = h.db.set_pragma("user_version", &b"\x01"[..]).unwrap_err();
+    assert_eq!(err.to_string(), "blob cannot be used as a pragma value");
+
+    // Names are quoted.
+    let err = h.db.pragma_on("no\"such", "user_version").unwrap_err();
+    assert_eq!(err.to_string(), "unknown database \"no\"\"such\"");
+    assert_eq!(h.db.pragma("no_such_pragma")?, Value::Null);
+    Ok(())
+}
+
+#[test]
+fn pragma_query() -> Result<()> {
+    let h = TestHelpers::new();
+    h.db.execute(
+        "CREATE TABLE \"odd'name\" (id INTEGER PRIMARY KEY, label TEXT NOT NULL)",
+        (),
+    )?;
+    let mut columns = vec![];
+    h.db.pragma_query_with("table_info", "odd'name", |row| {
+        columns.push((
+            row[1].get_str()?.to_owned(),
+            row[2].get_str()?.to_owned(),
+            row[3].get_i64(),
+            row[5].get_i64(),
+        ));
+        Ok(())
+    })?;
+    assert_eq!(
+        columns,
+        vec![
+            ("id".to_owned(), "INTEGER".to_owned(), 0, 1),
+            ("label".to_owned(), "TEXT".to_owned(), 1, 0),
+        ]
+    );
+
+    let mut names = vec![];
+    h.db.pragma_query("database_list", |row| {
+        names.push(row[1].get_str()?.to_owned());
+        Ok(())
+    })?;
+    assert_eq!(names, vec!["main"]);
+    Ok(())
+}
+
+#[test]
+fn limit_exceeded() -> Result<()> {
+    let h = TestHelpers::new();
+    h.db.set_limit(Limit::ExprDepth, 10);
+    h.db.set_limit(Limit::CompoundSelect, 3);
+    let or_chain = |n: usize| {
+        let terms = vec!["x = ?"; n].join(" OR ");
+        format!("SELECT x FROM (SELECT 1 AS x) WHERE {terms}")
+    };
+
+    // The estimate agrees with SQLite at the boundary.
+    assert_eq!(crate::sql::estimate_expr_depth(&or_chain(9)), 10);
+    h.db.statement_complexity_guard(&or_chain(9))?;
+    h.db.try_prepare_checked(&or_chain(9))?;
+    let expected = Error::LimitExceeded {
+        limit: Limit::ExprDepth,
+        max: 10,
+    };
+    assert_eq!(
+        h.db.statement_complexity_guard(&or_chain(10)),
+        Err(expected.clone())
+    );
+    assert_eq!(
+        h.db.try_prepare_checked(&or_chain(10)).err(),
+        Some(expected)
+    );
+
+    let compound = "SELECT 1 UNION ALL SELECT 2 UNION ALL SELECT 3 UNION ALL SELECT 4";
+    let expected = Error::LimitExceeded {
+        limit: Limit::CompoundSelect,
+        max: 3,
+    };
+    assert_eq!(
+        h.db.statement_complexity_guard(compound),
+        Err(expected.clone())
+    );
+    assert_eq!(h.db.try_prepare_checked(compound).err(), Some(expected));
+
+    // Other errors are returned unchanged.
+    assert_eq!(
+        h.db.try_prepare_checked("SELECT * FROM missing")
+            .err()
+            .map(|e| e.to_string()),
+        Some("no such table: missing".to_owned())
+    );
+    Ok(())
+}
+
+#[test]
+fn limit_exceeded_chunking() -> Result<()> {
+    fn lookup(db: &Connection, ids: &[i64]) -> Result<Vec<i64>> {
+        let terms = vec!["value = ?"; ids.len()].join(" OR ");
+        let sql = format!("SELECT value FROM generate WHERE {terms} ORDER BY value");
+        match db.try_prepare_checked(&sql) {
+            Err(Error::LimitExceeded {
+                limit: Limit::ExprDepth,
+                ..
+            }) => {
+                let (a, b) = ids.split_at(ids.len() / 2);
+                let mut ret = lookup(db, a)?;
+                ret.extend(lookup(db, b)?);
+                Ok(ret)
+            }
+            stmt => stmt?.query(ids)?.map(|r| Ok(r[0].get_i64())).collect(),
+        }
+    }
+
+    let h = TestHelpers::new();
+    h.db.execute(
+        "CREATE TABLE generate AS WITH RECURSIVE c(value) AS (SELECT 0 UNION ALL SELECT value + 1 FROM c WHERE value < 99) SELECT value FROM c",
+        (),
+    )?;
+    h.db.set_limit(Limit::ExprDepth, 8);
+    let ids: Vec<i64> = (0..50).map(|x| x * 2).collect();
+    assert_eq!(lookup(&h.db, &ids)?, ids);
+    Ok(())
+}
+
+#[test]
+fn query_chunked() -> Result<()> {
+    let h = TestHelpers::new();
+    h.db.execute(
+        "CREATE TABLE generate AS WITH RECURSIVE c(value) AS (SELECT 0 UNION ALL SELECT value + 1 FROM c WHERE value < 99) SELECT value FROM c",
+        (),
+    )?;
+    h.db.set_limit(Limit::VariableNumber, 8);
+    let build = |n: usize| {
+        format!(
+            "SELECT value FROM generate WHERE value IN ({}) ORDER BY value",
+            vec!["?"; n].join(", ")
+        )
+    };
+    let ids: Vec<i64> = (0..20).map(|x| x * 3).collect();
+    let mut sizes = vec![];
+    let ret = h.db.query_chunked(
+        &ids,
+        |n| {
+            sizes.push(n);
+            build(n)
+        },
+        |r| Ok(r[0].get_i64()),
+    )?;
+    assert_eq!(ret, ids);
+    assert_eq!(sizes, vec![20, 10, 5, 5, 10, 5, 5]);
+
+    // A single value which is still too complex is an error.
+    h.db.set_limit(Limit::VariableNumber, 0);
+    let err =
+        h.db.query_chunked(&ids, build, |r| Ok(r[0].get_i64()))
+            .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            Error::LimitExceeded {
+                limit: Limit::VariableNumber,
+                ..
+            }
+        ),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn run() -> Result<()> {
+    let h = TestHelpers::new();
+    h.db.execute("CREATE TABLE t(x)", ())?;
+    let summary =
+        h.db.prepare("INSERT INTO t VALUES (1), (2), (3)")?
+            .run(())?;
+    assert_eq!(
+        summary,
+        RunSummary {
+            rows_discarded: 0,
+            changes: 3,
+            last_insert_rowid: 3
+        }
+    );
+    let mut stmt = h.db.prepare("SELECT x FROM t WHERE x >= ?")?;
+    assert_eq!(stmt.run([2])?.rows_discarded, 2);
+    assert_eq!(stmt.run([1])?.rows_discarded, 3);
+    assert_eq!(stmt.run([1])?.changes, 0);
+    Ok(())
+}
+
+#[test]
+fn execute_batch() -> Result<()> {
+    let h = TestHelpers::new();
+    let ret = h.db.execute_batch(SCRIPT)?;
+    let summary: Vec<_> = ret.iter().map(|s| (s.rows_discarded, s.changes)).collect();
+    assert_eq!(
+        summary,
+        vec![(0, 0), (0, 0), (0, 0), (0, 2), (2, 0), (0, 2)]
+    );
+    assert_eq!(ret[3].last_insert_rowid, 2);
+
+    let err =
+        h.db.execute_batch(
+            "INSERT INTO log VALUES ('x'); SELECT 1;\n INSERT INTO missing VALUES (1); SELECT 2",
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "no such table: missing in statement: INSERT INTO missing VALUES (1);"
+    );
+    let err =
+        h.db.execute_batch("SELECT 1; SELECT abs(-9223372036854775808)")
+            .unwrap_err();
+    assert_eq!(err, Error::Sqlite(ffi::SQLITE_ERROR, None));
+    assert_eq!(
+        err.to_string(),
+        "integer overflow in statement: SELECT abs(-9223372036854775808)"
+    );
+    let (count,): (i64,) = h.db.query_row_as("SELECT COUNT(*) FROM log", ())?;
+    assert_eq!(count, 5);
+    Ok(())
+}
+
+#[test]
+fn query_table() -> Result<()> {
+    let h = TestHelpers::new();
+    h.db.execute_batch(
+        "CREATE TABLE tbl(id, name, score);
+         INSERT INTO tbl VALUES (1, 'alice', 2.5), (2, NULL, x'00ff'), (10, 'a\nb', 3.0);",
+    )?;
+    let table =
+        h.db.query_table("SELECT * FROM tbl WHERE id < ?", [5], 10)?;
+    assert_eq!(table.columns(), ["id", "name", "score"]);
+    assert_eq!(table.len(), 2);
+    assert_eq!(table.rows()[1][1], Value::Null);
+    assert_eq!(
+        table.to_string(),
+        "id | name  | score\n\
+         ---+-------+--------\n\
+         1  | alice | 2.5\n\
+         2  | NULL  | X'00ff'\n"
+    );
+
+    let table =
+        h.db.query_table("SELECT id AS n, name FROM tbl WHERE id = 10", (), 10)?;
+    assert_eq!(
+        table.to_string(),
+        "n  | name\n\
+         ---+-----\n\
+         10 | a\\nb\n"
+    );
+
+    let table = h.db.query_table("SELECT 1 AS x WHERE 0", (), 0)?;
+    assert!(table.is_empty());
+    assert_eq!(table.to_string(), "x\n-\n");
+
+    let err = h.db.query_table("SELECT * FROM tbl", (), 2).unwrap_err();
+    assert_eq!(err, Error::TooManyRows { max: 2 });
+    assert_eq!(err.to_string(), "query returned more than 2 rows");
+    Ok(())
+}
+
+#[test]
+fn iterator_adapters() -> Result<()> {
+    use std::collections::{BTreeMap, HashMap};
+
+    let h = TestHelpers::new();
+    h.db.execute_batch("CREATE TABLE t(x); INSERT INTO t VALUES (1), (2), (3), (4), (5);")?;
+    let mut stmt = h.db.prepare("SELECT x FROM t ORDER BY x")?;
+
+    // Stopping early resets the statement.
+    let first: Vec<i64> = stmt.take(2).collect_with(|r| Ok(r[0].get_i64()))?;
+    assert_eq!(first, vec![1, 2]);
+    assert!(!stmt.is_busy());
+    assert!(stmt.next()?.is_none());
+    assert_eq!(stmt.query(())?.count()?, 5);
+    assert!(!stmt.is_busy());
+
+    let even: Vec<i64> = stmt
+        .query(())?
+        .filter_map(|r| Ok(Some(r[0].get_i64()).filter(|x| x % 2 == 0)))
+        .collect()?;
+    assert_eq!(even, vec![2, 4]);
+    let sum = stmt
+        .query(())?
+        .try_fold(0, |acc, r| Ok(acc + r[0].get_i64()))?;
+    assert_eq!(sum, 15);
+    let mut seen = vec![];
+    stmt.query(())?.take(3).for_each(|r| {
+        seen.push(r[0].get_i64());
+        Ok(())
+    })?;
+    assert_eq!(seen, vec![1, 2, 3]);
+
+    let squares: HashMap<i64, i64> = stmt.query(())?.collect_with(|r| {
+        let x = r[0].get_i64();
+        Ok((x, x * x))
+    })?;
+    assert_eq!(squares.len(), 5);
+    assert_eq!(squares[&4], 16);
+    let squares: BTreeMap<i64, i64> = stmt
+        .query(())?
+        .collect_with(|r| Ok((r[0].get_i64(), r[0].get_i64() * r[0].get_i64())))?;
+    assert_eq!(squares.into_iter().next_back(), Some((5, 25)));
+    Ok(())
+}
+
+#[test]
+fn iterator_adapter_errors() -> Result<()> {
+    let h = TestHelpers::new();
+    h.db.execute_batch("CREATE TABLE t(x); INSERT INTO t VALUES (1), (2), (3), (4), (5);")?;
+    let mut stmt = h.db.prepare("SELECT x FROM t ORDER BY x")?;
+
+    // An error from the closure stops the iteration.
+    let mut calls = 0;
+    let err = stmt
+        .collect_with::<Vec<i64>, _, _>(|r| {
+            calls += 1;
+            match r[0].get_i64() {
+                3 => Err(Error::Module("found 3".to_owned())),
+                x => Ok(x),
+            }
+        })
+        .unwrap_err();
+    assert_eq!(err, Error::Module("found 3".to_owned()));
+    assert_eq!(calls, 3);
+    assert!(!stmt.is_busy());
+    assert!(stmt.next()?.is_none());
+
+    // So does an error from SQLite.
+    let mut stmt = h
+        .db
+        .prepare("SELECT CASE x WHEN 3 THEN abs(x - 9223372036854775807 - 4) ELSE x END FROM t")?;
+    let mut seen = vec![];
+    let err = stmt
+        .for_each(|r| {
+            seen.push(r[0].get_i64());
+            Ok(())
+        })
+        .unwrap_err();
+    assert_eq!(err, Error::Sqlite(ffi::SQLITE_ERROR, None));
+    assert_eq!(err.to_string(), "integer overflow");
+    assert_eq!(seen, vec![1, 2]);
+    assert!(!stmt.is_busy());
+    assert_eq!(stmt.take(1).count()?, 0);
+    Ok(())
+}
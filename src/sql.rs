@@ -0,0 +1,419 @@
+//! Helpers for working with SQL text.
+use crate::{ffi, sqlite3_stricmp};
+use std::{cmp::Ordering, ffi::CString};
+
+/// Returns true if the two identifiers refer to the same object.
+///
+/// SQLite compares the names of tables, columns, functions, and pragmas without regard to
+/// the case of ASCII letters, but other characters must match exactly. This function uses
+/// the same rules, via [sqlite3_stricmp]. In particular, it does not perform Unicode case
+/// folding, so `"É"` and `"é"` are different identifiers.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sqlite3_ext::sql::ident_eq;
+///
+/// assert!(ident_eq("tbl_data", "TBL_Data"));
+/// assert!(!ident_eq("É", "é"));
+/// ```
+pub fn ident_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && sqlite3_stricmp(a, b) == Ordering::Equal
+}
+
+/// Split a script containing several SQL statements into the individual statements.
+///
+/// The script is only split at semicolons which end a complete statement, as determined by
+/// sqlite3_complete. This uses the SQLite tokenizer, so semicolons inside of string
+/// literals, quoted identifiers, comments, and the body of a CREATE TRIGGER statement do
+/// not end the statement. The returned statements include their terminating semicolons,
+/// with surrounding whitespace removed. Empty statements are omitted, except that a
+/// semicolon which follows only comments is returned along with them. Any text after the
+/// last complete statement is returned as a final statement, even though it is incomplete.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sqlite3_ext::sql::split_statements;
+///
+/// let script = "CREATE TABLE t(x); CREATE TRIGGER r AFTER INSERT ON t BEGIN SELECT 1; END;";
+/// assert_eq!(
+///     split_statements(script),
+///     vec![
+///         "CREATE TABLE t(x);",
+///         "CREATE TRIGGER r AFTER INSERT ON t BEGIN SELECT 1; END;"
+///     ]
+/// );
+/// ```
+pub fn split_statements<'a>(script: &'a str) -> Vec<&'a str> {
+    let mut ret = vec![];
+    let mut push = |stmt: &'a str| {
+        let stmt = stmt.trim();
+        if !stmt.is_empty() && stmt != ";" {
+            ret.push(stmt);
+        }
+    };
+    let mut start = 0;
+    for (pos, _) in script.match_indices(';') {
+        let candidate = &script[start..=pos];
+        let complete = match CString::new(candidate) {
+            Ok(c) => unsafe { ffi::sqlite3_complete(c.as_ptr()) != 0 },
+            // The tokenizer cannot see past a nul, so leave the rest in one piece.
+            Err(_) => break,
+        };
+        if complete {
+            push(candidate);
+            start = pos + 1;
+        }
+    }
+    push(&script[start..]);
+    ret
+}
+
+/// Estimate the depth of the deepest expression in some SQL, as measured by
+/// [Limit::ExprDepth](crate::Limit::ExprDepth).
+///
+/// SQLite only reports that an expression is too deep after it has parsed it, so this
+/// function allows code which generates SQL to check whether the result is likely to fit
+/// before running it. The estimate follows the precedence of the binary operators, so
+/// that a chain of `x = ? OR x = ? OR ...` is as deep as it has terms, and counts
+/// function calls and CASE expressions as one level. It does not account for everything
+/// that SQLite does, such as views and triggers, so statements near the limit should still
+/// be prepared with [Connection::try_prepare_checked](crate::Connection::try_prepare_checked).
+///
+/// # Examples
+///
+/// ```
+/// use sqlite3_ext::sql::estimate_expr_depth;
+///
+/// assert_eq!(estimate_expr_depth("SELECT 1"), 1);
+/// assert_eq!(estimate_expr_depth("SELECT a = 1 OR a = 2 OR a = 3"), 4);
+/// ```
+pub fn estimate_expr_depth(sql: &str) -> usize {
+    let mut parser = DepthParser {
+        toks: tokenize(sql),
+        pos: 0,
+    };
+    let mut max = 1;
+    while parser.pos < parser.toks.len() {
+        max = max.max(parser.sequence());
+        // Skip the unbalanced ) or END which stopped the sequence.
+        parser.pos += 1;
+    }
+    max
+}
+
+/// Binary operators, from lowest to highest precedence.
+const PRECEDENCE: [&[&str]; 10] = [
+    &["OR"],
+    &["AND"],
+    &[
+        "=", "==", "!=", "<>", "IS", "IN", "LIKE", "GLOB", "MATCH", "REGEXP", "BETWEEN",
+    ],
+    &["<", "<=", ">", ">="],
+    &["ESCAPE"],
+    &["&", "|", "<<", ">>"],
+    &["+", "-"],
+    &["*", "/", "%"],
+    &["||", "->", "->>"],
+    &["COLLATE"],
+];
+
+/// Keywords which separate expressions rather than appearing in them.
+const CLAUSES: &[&str] = &[
+    ",",
+    ";",
+    "SELECT",
+    "DISTINCT",
+    "ALL",
+    "FROM",
+    "WHERE",
+    "GROUP",
+    "ORDER",
+    "BY",
+    "HAVING",
+    "LIMIT",
+    "OFFSET",
+    "AS",
+    "ON",
+    "USING",
+    "JOIN",
+    "UNION",
+    "INTERSECT",
+    "EXCEPT",
+    "VALUES",
+    "SET",
+    "INSERT",
+    "INTO",
+    "UPDATE",
+    "DELETE",
+    "RETURNING",
+    "WHEN",
+    "THEN",
+    "ELSE",
+    "ASC",
+    "DESC",
+];
+
+/// A precedence-climbing parser which computes the height of expression trees without
+/// building them.
+struct DepthParser<'a> {
+    toks: Vec<&'a str>,
+    pos: usize,
+}
+
+impl DepthParser<'_> {
+    fn peek(&self) -> Option<&str> {
+        self.toks.get(self.pos).copied()
+    }
+
+    fn peek_is(&self, kw: &str) -> bool {
+        self.peek().is_some_and(|t| t.eq_ignore_ascii_case(kw))
+    }
+
+    fn precedence(tok: &str) -> Option<usize> {
+        PRECEDENCE
+            .iter()
+            .position(|ops| ops.iter().any(|op| op.eq_ignore_ascii_case(tok)))
+    }
+
+    /// Parse expressions up to an unbalanced ) or END, returning the height of the tallest.
+    fn sequence(&mut self) -> usize {
+        let mut max = 0;
+        while !(self.peek().is_none() || self.peek_is(")") || self.peek_is("END")) {
+            let start = self.pos;
+            max = max.max(self.expr(0));
+            if self.pos == start {
+                self.pos += 1;
+            }
+        }
+        max
+    }
+
+    fn expr(&mut self, min_prec: usize) -> usize {
+        let mut height = self.primary();
+        loop {
+            // NOT IN, NOT LIKE, and so on are a single node.
+            let negated = self.peek_is("NOT")
+                && self
+                    .toks
+                    .get(self.pos + 1)
+                    .and_then(|t| Self::precedence(t))
+                    .is_some();
+            let op_pos = self.pos + negated as usize;
+            let prec = match self.toks.get(op_pos).and_then(|t| Self::precedence(t)) {
+                Some(p) if p >= min_prec && height > 0 => p,
+                _ => return height,
+            };
+            self.pos = op_pos + 1;
+            if self.toks[op_pos].eq_ignore_ascii_case("IS") && self.peek_is("NOT") {
+                self.pos += 1;
+            }
+            let mut rhs = self.expr(prec + 1);
+            if self.toks[op_pos].eq_ignore_ascii_case("BETWEEN") && self.peek_is("AND") {
+                self.pos += 1;
+                rhs = rhs.max(self.expr(prec + 1));
+            }
+            height = height.max(rhs) + 1;
+        }
+    }
+
+    fn primary(&mut self) -> usize {
+        let tok = match self.peek() {
+            Some(t) => t,
+            None => return 0,
+        };
+        let upper = tok.to_ascii_uppercase();
+        match upper.as_str() {
+            ")" | "END" => 0,
+            t if CLAUSES.contains(&t) => 0,
+            "(" => {
+                self.pos += 1;
+                let height = self.sequence();
+                self.close(")");
+                height
+            }
+            "CASE" => {
+                self.pos += 1;
+                let height = self.sequence() + 1;
+                self.close("END");
+                height
+            }
+            // SQLite folds the sign into numeric literals.
+            "-" | "+" => {
+                self.pos += 1;
+                self.primary()
+            }
+            "NOT" | "~" => {
+                self.pos += 1;
+                self.primary() + 1
+            }
+            _ => {
+                self.pos += 1;
+                if self.peek_is("(") {
+                    // A function call is a node with the arguments as children.
+                    self.pos += 1;
+                    let height = self.sequence() + 1;
+                    self.close(")");
+                    height
+                } else {
+                    1
+                }
+            }
+        }
+    }
+
+    fn close(&mut self, kw: &str) {
+        if self.peek_is(kw) {
+            self.pos += 1;
+        }
+    }
+}
+
+/// Count the terms of the outermost compound SELECT in a statement, as measured by
+/// [Limit::CompoundSelect](crate::Limit::CompoundSelect).
+pub(crate) fn count_compound_terms(sql: &str) -> usize {
+    let mut depth = 0;
+    let mut terms = 1;
+    for tok in tokenize(sql) {
+        match tok {
+            "(" => depth += 1,
+            ")" => depth -= 1,
+            t if depth == 0
+                && ["UNION", "INTERSECT", "EXCEPT"]
+                    .iter()
+                    .any(|k| k.eq_ignore_ascii_case(t)) =>
+            {
+                terms += 1
+            }
+            _ => (),
+        }
+    }
+    terms
+}
+
+/// Split SQL into tokens, skipping whitespace and comments. Literals and quoted identifiers
+/// are single tokens. This only needs to be accurate enough for the estimates above.
+fn tokenize(sql: &str) -> Vec<&str> {
+    const SYMBOLS: [&str; 12] = [
+        "->>", "==", "!=", "<>", "<=", ">=", "<<", ">>", "||", "->", "-", "<",
+    ];
+    let b = sql.as_bytes();
+    let mut ret = vec![];
+    let mut i = 0;
+    while i < b.len() {
+        let start = i;
+        let end = match b[i] {
+            c if c.is_ascii_whitespace() => {
+                i += 1;
+                continue;
+            }
+            b'-' if b.get(i + 1) == Some(&b'-') => {
+                i = sql[i..].find('\n').map_or(b.len(), |n| i + n);
+                continue;
+            }
+            b'/' if b.get(i + 1) == Some(&b'*') => {
+                i = sql[i + 2..].find("*/").map_or(b.len(), |n| i + n + 4);
+                continue;
+            }
+            q @ (b'\'' | b'"' | b'`') => {
+                let mut j = i + 1;
+                while j < b.len() {
+                    if b[j] == q {
+                        if b.get(j + 1) == Some(&q) {
+                            j += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    j += 1;
+                }
+                (j + 1).min(b.len())
+            }
+            b'[' => sql[i..].find(']').map_or(b.len(), |n| i + n + 1),
+            c if c.is_ascii_alphanumeric() || c == b'_' || c == b'$' || c >= 0x80 => {
+                let mut j = i + 1;
+                while j < b.len()
+                    && (b[j].is_ascii_alphanumeric()
+                        || b[j] == b'_'
+                        || b[j] == b'$'
+                        || b[j] == b'.'
+                        || b[j] >= 0x80)
+                {
+                    j += 1;
+                }
+                j
+            }
+            _ => match SYMBOLS.iter().find(|s| sql[i..].starts_with(*s)) {
+                Some(s) => i + s.len(),
+                None => i + sql[i..].chars().next().map_or(1, |c| c.len_utf8()),
+            },
+        };
+        ret.push(&sql[start..end]);
+        i = end;
+    }
+    ret
+}
+
+#[cfg(all(test, feature = "static"))]
+mod test {
+    #[test]
+    fn ident_eq() {
+        assert!(super::ident_eq("tbl", "TBL"));
+        assert!(super::ident_eq("a_b_data", "A_B_Data"));
+        assert!(!super::ident_eq("tbl", "tbl_data"));
+        assert!(!super::ident_eq("tbl_data", "tbl"));
+        assert!(!super::ident_eq("É", "é"));
+        assert!(super::ident_eq("É", "É"));
+    }
+
+    #[test]
+    fn split_statements() {
+        let script = "
+            CREATE TABLE t(x, y);
+            INSERT INTO t VALUES ('a;b', \"c;d\"); -- comment; with semicolon
+            /* block; comment */ ;;
+            CREATE TRIGGER r AFTER INSERT ON t BEGIN
+                INSERT INTO log VALUES (new.x);
+                UPDATE t SET y = 'END;' WHERE x = new.x;
+            END;
+            SELECT 1";
+        assert_eq!(
+            super::split_statements(script),
+            vec![
+                "CREATE TABLE t(x, y);",
+                "INSERT INTO t VALUES ('a;b', \"c;d\");",
+                "-- comment; with semicolon\n            /* block; comment */ ;",
+                "CREATE TRIGGER r AFTER INSERT ON t BEGIN\n                INSERT INTO log VALUES (new.x);\n                UPDATE t SET y = 'END;' WHERE x = new.x;\n            END;",
+                "SELECT 1",
+            ]
+        );
+        assert_eq!(super::split_statements("  ;\n "), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn estimate_expr_depth() {
+        use super::estimate_expr_depth as depth;
+        assert_eq!(depth("SELECT 1"), 1);
+        assert_eq!(depth("SELECT -1, 'a OR b' -- OR c"), 1);
+        assert_eq!(depth("SELECT a = 1 OR a = 2 OR a = 3"), 4);
+        assert_eq!(depth("SELECT a + b * c"), 3);
+        assert_eq!(depth("SELECT f(a + 1, b)"), 3);
+        assert_eq!(depth("SELECT (a OR b) AND c"), 3);
+        assert_eq!(
+            depth("SELECT CASE WHEN a = 1 THEN 'x' WHEN a = 2 THEN 'y' END"),
+            3
+        );
+        assert_eq!(depth("SELECT x FROM t WHERE x IN (1, 2, 3, 4, 5)"), 2);
+        assert_eq!(depth("SELECT x NOT BETWEEN 1 AND 2 AND y IS NOT NULL"), 3);
+        assert_eq!(depth("SELECT (((((1)))))"), 1);
+    }
+
+    #[test]
+    fn count_compound_terms() {
+        use super::count_compound_terms as count;
+        assert_eq!(count("SELECT 1"), 1);
+        assert_eq!(count("SELECT 1 UNION ALL SELECT 2 EXCEPT SELECT 3"), 3);
+        assert_eq!(count("SELECT * FROM (SELECT 1 UNION SELECT 2)"), 1);
+    }
+}
@@ -63,6 +63,150 @@ impl TestHelpers {
     }
 }
 
+/// A database file in the temporary directory which is removed when dropped, even if the
+/// test fails.
+#[cfg(modern_sqlite)]
+pub struct TempFile(pub std::path::PathBuf);
+
+#[cfg(modern_sqlite)]
+impl TempFile {
+    pub fn new(name: &str) -> TempFile {
+        let path =
+            std::env::temp_dir().join(format!("sqlite3_ext_{name}_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        TempFile(path)
+    }
+}
+
+#[cfg(modern_sqlite)]
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// A value which exercises an edge case of the value layer. See [value_matrix].
+#[derive(Debug)]
+pub struct ValueCase {
+    pub description: &'static str,
+    /// The value which SQLite holds, as Rust code reads it back.
+    pub value: Value,
+    /// An SQL expression which produces the value.
+    pub sql: &'static str,
+}
+
+/// Every text encoding which a database can use.
+pub const ENCODINGS: [TextEncoding; 3] = [
+    TextEncoding::Utf8,
+    TextEncoding::Utf16le,
+    TextEncoding::Utf16be,
+];
+
+/// Open an in-memory database using the given text encoding, and return it along with a
+/// list of values covering the edge cases of every storage class. The database provides
+/// the functions `matrix_subtype(x)`, which returns its argument with a subtype, and
+/// `matrix_pointer()`, which returns a pointer value.
+pub fn value_matrix(encoding: TextEncoding) -> (Database, Vec<ValueCase>) {
+    let db = TestHelpers::new().db;
+    let pragma = match encoding {
+        TextEncoding::Utf8 => "UTF-8",
+        TextEncoding::Utf16le => "UTF-16le",
+        TextEncoding::Utf16be => "UTF-16be",
+        TextEncoding::Utf16 => "UTF-16",
+    };
+    db.execute(&format!("PRAGMA encoding = '{pragma}'"), ())
+        .unwrap();
+    let opts = FunctionOptions::default().set_n_args(1);
+    db.create_scalar_function("matrix_subtype", &opts, |c, args| {
+        // Subtypes require SQLite 3.9.0; the value is the same without one.
+        c.set_result_with_subtype(&*args[0], b'J')
+            .or_else(|_| c.set_result(&*args[0]))
+    })
+    .unwrap();
+    let opts = FunctionOptions::default().set_n_args(0);
+    db.create_scalar_function("matrix_pointer", &opts, |c, _| {
+        c.set_result(PassedRef::new(0i64))
+    })
+    .unwrap();
+
+    let case = |description, value, sql| ValueCase {
+        description,
+        value,
+        sql,
+    };
+    let cases = vec![
+        case("NULL", Value::Null, "NULL"),
+        case("zero", Value::Integer(0), "0"),
+        case("i64::MAX", Value::Integer(i64::MAX), "9223372036854775807"),
+        case("i64::MIN", Value::Integer(i64::MIN), "-9223372036854775808"),
+        case(
+            "integer literal too large for i64",
+            Value::Float(9223372036854775808.0),
+            "9223372036854775808",
+        ),
+        case("negative zero", Value::Float(-0.0), "-0.0"),
+        case("fraction", Value::Float(0.1), "0.1"),
+        case("f64::MAX", Value::Float(f64::MAX), "1.7976931348623157e308"),
+        case("infinity", Value::Float(f64::INFINITY), "9e999"),
+        case(
+            "negative infinity",
+            Value::Float(f64::NEG_INFINITY),
+            "-9e999",
+        ),
+        case(
+            "NaN, which SQLite stores as NULL",
+            Value::from(f64::NAN),
+            "NULL",
+        ),
+        case("empty TEXT", Value::Text(String::new()), "''"),
+        case("numeric TEXT", Value::Text("1".to_owned()), "'1'"),
+        case(
+            "non-ASCII TEXT",
+            Value::Text("h\u{e9}llo \u{2603} \u{1d11e}".to_owned()),
+            "'h\u{e9}llo \u{2603} \u{1d11e}'",
+        ),
+        case(
+            "TEXT with an embedded NUL",
+            Value::Text("a\0b".to_owned()),
+            "'a' || char(0) || 'b'",
+        ),
+        case("empty BLOB", Value::Blob(Blob::from(&[] as &[u8])), "x''"),
+        case(
+            "BLOB with an embedded NUL",
+            Value::Blob(Blob::from(&[0u8, 1, 0xff] as &[u8])),
+            "x'0001ff'",
+        ),
+        case(
+            "1 MiB BLOB",
+            Value::Blob(Blob::from(vec![0u8; 1 << 20].as_slice())),
+            "zeroblob(1048576)",
+        ),
+        case(
+            "TEXT with a subtype",
+            Value::Text("{}".to_owned()),
+            "matrix_subtype('{}')",
+        ),
+        case("pointer", Value::Null, "matrix_pointer()"),
+    ];
+    (db, cases)
+}
+
+/// Assert that a value read back from SQLite is identical to the expected one. Unlike
+/// [Value]'s PartialEq, REAL values are compared bit for bit, so that negative zero is
+/// distinguished from zero.
+#[track_caller]
+pub fn assert_same_value(case: &ValueCase, path: &str, actual: &Value) {
+    let same = match (actual, &case.value) {
+        (Value::Float(a), Value::Float(b)) => a.to_bits() == b.to_bits(),
+        (a, b) => a == b,
+    };
+    assert!(
+        same,
+        "{} via {path}: expected {:?}, got {:?}",
+        case.description, case.value, actual
+    );
+}
+
 #[test]
 fn with_value() {
     let h = TestHelpers::new();
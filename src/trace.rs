@@ -0,0 +1,253 @@
+#[cfg_attr(not(modern_sqlite), allow(unused_imports))]
+use crate::{
+    extension::check_transient_drop, ffi, sqlite3_match_version, sqlite3_require_version, types::*,
+    Connection,
+};
+use bitflags::bitflags;
+#[cfg(modern_sqlite)]
+use std::ptr::null_mut;
+use std::{
+    ffi::{c_void, CStr},
+    os::raw::{c_char, c_int, c_uint},
+};
+
+/// Key of the connection slot which owns the trace callback.
+#[cfg_attr(not(modern_sqlite), allow(dead_code))]
+const TRACE_SLOT: &str = "trace";
+
+bitflags! {
+    /// The events which are passed to the callback set with [Connection::set_trace].
+    #[repr(transparent)]
+    pub struct TraceMask: c_uint {
+        /// A prepared statement starts running. See [TraceEvent::Stmt].
+        const STMT = ffi::SQLITE_TRACE_STMT as _;
+        /// A prepared statement finishes running. See [TraceEvent::Profile].
+        const PROFILE = ffi::SQLITE_TRACE_PROFILE as _;
+        /// A prepared statement produces a row of results. See [TraceEvent::Row].
+        const ROW = ffi::SQLITE_TRACE_ROW as _;
+        /// The database connection closes. See [TraceEvent::Close].
+        const CLOSE = ffi::SQLITE_TRACE_CLOSE as _;
+    }
+}
+
+/// An event passed to the callback set with [Connection::set_trace].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceEvent {
+    /// A prepared statement starts running. This is also reported when each trigger
+    /// subprogram starts, in which case `unexpanded` is an SQL comment naming the trigger.
+    Stmt {
+        /// The text of the statement, with parameters replaced by their bound values.
+        sql: String,
+        /// The text of the statement as it was prepared.
+        unexpanded: String,
+    },
+    /// A prepared statement finishes running.
+    Profile {
+        /// The text of the statement, with parameters replaced by their bound values.
+        sql: String,
+        /// The approximate wall-clock time that the statement took to run, in nanoseconds.
+        nanos: u64,
+    },
+    /// A prepared statement produces a row of results.
+    Row,
+    /// The database connection closes.
+    Close,
+}
+
+impl Connection {
+    /// Set a callback which is invoked for the events in the mask, for example to log each
+    /// statement which runs and how long it took. The SQL in the events has parameters
+    /// replaced by their bound values where possible, so it may contain sensitive data.
+    ///
+    /// A connection has a single trace callback, so this method replaces any previous
+    /// callback, which is dropped. The callback is dropped when the connection is closed.
+    ///
+    /// The callback must not modify the database connection.
+    ///
+    /// If the callback needs to be dropped and this is called by a non-persistent
+    /// `unload_safe` extension, this function fails. See
+    /// [Extension](crate::Extension#non-persistent-extensions) for details.
+    ///
+    /// Requires SQLite 3.14.0.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::*;
+    ///
+    /// fn log_slow_statements(db: &Connection) -> Result<()> {
+    ///     db.set_trace(TraceMask::PROFILE, |event| {
+    ///         if let TraceEvent::Profile { sql, nanos } = event {
+    ///             if nanos > 100_000_000 {
+    ///                 eprintln!("slow statement ({} ms): {sql}", nanos / 1_000_000);
+    ///             }
+    ///         }
+    ///     })
+    /// }
+    /// ```
+    pub fn set_trace<F: FnMut(TraceEvent) + 'static>(
+        &self,
+        mask: TraceMask,
+        func: F,
+    ) -> Result<()> {
+        let _ = (mask, &func);
+        sqlite3_require_version!(3_014_000, {
+            check_transient_drop::<F>("trace callback")?;
+            let _guard = self.lock();
+            let func = Box::into_raw(Box::new(func));
+            unsafe {
+                let rc = ffi::sqlite3_trace_v2(
+                    self.as_mut_ptr(),
+                    mask.bits(),
+                    Some(call_trace::<F>),
+                    func as _,
+                );
+                if let Err(e) = Error::from_sqlite(rc) {
+                    drop(Box::from_raw(func));
+                    return Err(e);
+                }
+                let ret = self.set_slot(TRACE_SLOT, Some(Box::from_raw(func)));
+                if ret.is_err() {
+                    ffi::sqlite3_trace_v2(self.as_mut_ptr(), 0, None, null_mut());
+                }
+                ret
+            }
+        })
+    }
+
+    /// Remove the trace callback set with [set_trace](Self::set_trace), dropping it.
+    ///
+    /// Requires SQLite 3.14.0.
+    pub fn clear_trace(&self) -> Result<()> {
+        sqlite3_require_version!(3_014_000, {
+            let _guard = self.lock();
+            unsafe { ffi::sqlite3_trace_v2(self.as_mut_ptr(), 0, None, null_mut()) };
+            self.set_slot::<()>(TRACE_SLOT, None)
+        })
+    }
+}
+
+#[cfg_attr(not(modern_sqlite), allow(dead_code))]
+unsafe extern "C" fn call_trace<F: FnMut(TraceEvent)>(
+    event: c_uint,
+    data: *mut c_void,
+    p: *mut c_void,
+    x: *mut c_void,
+) -> c_int {
+    let func = &mut *(data as *mut F);
+    let event = match event as c_int {
+        ffi::SQLITE_TRACE_STMT => TraceEvent::Stmt {
+            sql: statement_sql(p as _),
+            unexpanded: lossy(x as _),
+        },
+        ffi::SQLITE_TRACE_PROFILE => TraceEvent::Profile {
+            sql: statement_sql(p as _),
+            nanos: *(x as *const i64) as _,
+        },
+        ffi::SQLITE_TRACE_ROW => TraceEvent::Row,
+        ffi::SQLITE_TRACE_CLOSE => TraceEvent::Close,
+        _ => return 0,
+    };
+    func(event);
+    0
+}
+
+/// Returns the expanded SQL of the statement, or the original SQL if it cannot be
+/// expanded.
+#[cfg_attr(not(modern_sqlite), allow(dead_code))]
+unsafe fn statement_sql(stmt: *mut ffi::sqlite3_stmt) -> String {
+    sqlite3_match_version! {
+        3_014_000 => {
+            let expanded = ffi::sqlite3_expanded_sql(stmt);
+            if expanded.is_null() {
+                return lossy(ffi::sqlite3_sql(stmt));
+            }
+            let ret = lossy(expanded);
+            ffi::sqlite3_free(expanded as _);
+            ret
+        }
+        _ => lossy(ffi::sqlite3_sql(stmt)),
+    }
+}
+
+#[cfg_attr(not(modern_sqlite), allow(dead_code))]
+unsafe fn lossy(text: *const c_char) -> String {
+    match text.is_null() {
+        true => String::new(),
+        false => CStr::from_ptr(text).to_string_lossy().into_owned(),
+    }
+}
+
+#[cfg(all(test, feature = "static", modern_sqlite))]
+mod test {
+    use super::*;
+    use crate::test_helpers::prelude::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[test]
+    fn expanded_sql() -> Result<()> {
+        let h = TestHelpers::new();
+        let events = Rc::new(RefCell::new(vec![]));
+        let log = events.clone();
+        h.db.set_trace(TraceMask::STMT | TraceMask::ROW, move |e| {
+            log.borrow_mut().push(e)
+        })?;
+        h.db.query_row("SELECT ?, 1", ["needle"], |_| Ok(()))?;
+        assert_eq!(
+            *events.borrow(),
+            vec![
+                TraceEvent::Stmt {
+                    sql: "SELECT 'needle', 1".to_owned(),
+                    unexpanded: "SELECT ?, 1".to_owned(),
+                },
+                TraceEvent::Row,
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn replace_and_clear() -> Result<()> {
+        let h = TestHelpers::new();
+        let first = Rc::new(());
+        let count = Rc::new(RefCell::new(0));
+        let (f, c) = (first.clone(), count.clone());
+        h.db.set_trace(TraceMask::PROFILE, move |e| {
+            let _ = &f;
+            if let TraceEvent::Profile { sql, .. } = e {
+                assert_eq!(sql, "SELECT 1");
+                *c.borrow_mut() += 1;
+            }
+        })?;
+        h.db.query_row("SELECT 1", (), |_| Ok(()))?;
+        assert_eq!(*count.borrow(), 1);
+        assert_eq!(Rc::strong_count(&first), 2);
+
+        let second = Rc::new(());
+        let s = second.clone();
+        h.db.set_trace(TraceMask::CLOSE, move |_| {
+            let _ = &s;
+        })?;
+        assert_eq!(Rc::strong_count(&first), 1);
+        h.db.clear_trace()?;
+        assert_eq!(Rc::strong_count(&second), 1);
+        h.db.query_row("SELECT 1", (), |_| Ok(()))?;
+        assert_eq!(*count.borrow(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn version_fallback() {
+        let h = TestHelpers::new();
+        crate::SQLITE_VERSION.with_override(3_013_000, || {
+            assert_eq!(
+                h.db.set_trace(TraceMask::all(), |_| ()),
+                Err(Error::VersionNotSatisfied(3_014_000))
+            );
+            assert_eq!(
+                h.db.clear_trace(),
+                Err(Error::VersionNotSatisfied(3_014_000))
+            );
+        });
+    }
+}
@@ -1,4 +1,5 @@
-use super::{types::*, Connection};
+use super::{connection::quote_identifier, ffi, types::*, Connection};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// The type of transaction to create.
 pub enum TransactionType {
@@ -54,8 +55,85 @@ impl Connection {
         txn.start(tt)?;
         Ok(txn)
     }
+
+    /// Run the closure inside of a transaction with the specified behavior. The transaction
+    /// is committed if the closure returns Ok, and rolled back if it returns Err or panics.
+    ///
+    /// If a transaction is already active, the closure instead runs inside of a savepoint
+    /// with a generated name, and the behavior is ignored. This allows code which needs to
+    /// make several changes atomically to work whether or not its caller has started a
+    /// transaction.
+    ///
+    /// SQLite does not allow a transaction or savepoint to be started while a statement
+    /// which writes to the database is running. This is the case in
+    /// [CreateVTab::create](crate::vtab::CreateVTab::create) and
+    /// [CreateVTab::destroy](crate::vtab::CreateVTab::destroy), which run during CREATE
+    /// VIRTUAL TABLE and DROP TABLE, and in application-defined functions called by an
+    /// INSERT, UPDATE, or DELETE. This method then fails with
+    /// [SQLITE_MISUSE](ffi::SQLITE_MISUSE) without running the closure. Running statements
+    /// can only be detected on SQLite 3.7.16 and later; on earlier versions, SQLite's own
+    /// error from starting the transaction is returned instead.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::*;
+    ///
+    /// fn transfer(db: &Connection, from: i64, to: i64, amount: i64) -> Result<()> {
+    ///     db.with_transaction(TransactionType::Immediate, |db| {
+    ///         db.execute(
+    ///             "UPDATE accounts SET balance = balance - ? WHERE id = ?",
+    ///             params![amount, from],
+    ///         )?;
+    ///         db.execute(
+    ///             "UPDATE accounts SET balance = balance + ? WHERE id = ?",
+    ///             params![amount, to],
+    ///         )?;
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    pub fn with_transaction<R, F>(&self, tt: TransactionType, f: F) -> Result<R>
+    where
+        F: FnOnce(&Connection) -> Result<R>,
+    {
+        if self.busy_statement_info().iter().any(|(_, ro)| !ro) {
+            return Err(Error::Sqlite(
+                ffi::SQLITE_MISUSE,
+                Some("cannot start a transaction while a write statement is running".to_owned()),
+            ));
+        }
+        let autocommit = unsafe { ffi::sqlite3_get_autocommit(self.as_mut_ptr()) != 0 };
+        if !autocommit {
+            let name = format!(
+                "sqlite3_ext_txn_{}",
+                NEXT_SAVEPOINT.fetch_add(1, Ordering::Relaxed)
+            );
+            let sp = self.savepoint(&name)?;
+            return match f(&sp) {
+                Ok(ret) => sp.release().map(|_| ret),
+                // Prioritize the original error over a failure to roll back.
+                Err(e) => {
+                    let _ = sp.rollback();
+                    Err(e)
+                }
+            };
+        }
+        let txn = self.transaction(tt)?;
+        match f(&txn) {
+            Ok(ret) => txn.commit().map(|_| ret),
+            Err(e) => {
+                let _ = txn.rollback();
+                Err(e)
+            }
+        }
+    }
 }
 
+/// Used to generate unique names for the savepoints created by
+/// [Connection::with_transaction].
+static NEXT_SAVEPOINT: AtomicUsize = AtomicUsize::new(0);
+
 impl<'db> Transaction<'db> {
     fn start(&mut self, tt: TransactionType) -> Result<()> {
         let sql = match tt {
@@ -133,6 +211,95 @@ impl Drop for Transaction<'_> {
     }
 }
 
+/// A RAII wrapper around a named savepoint.
+///
+/// The savepoint can be created using [Connection::savepoint], and nested savepoints can be
+/// created from it using [Savepoint::savepoint]. The savepoint is finalized using
+/// [release](Savepoint::release) or [rollback](Savepoint::rollback). If no finalization method
+/// is used, the savepoint will automatically be rolled back and released when it is dropped.
+/// For convenience, Savepoint derefs to Connection.
+///
+/// If no transaction is active when the savepoint is created, SQLite starts one, and
+/// releasing the savepoint commits it.
+#[derive(Debug)]
+pub struct Savepoint<'db> {
+    db: &'db Connection,
+    name: String,
+    active: bool,
+}
+
+impl Connection {
+    /// Creates a savepoint with the given name. The name is quoted, so any string may be
+    /// used.
+    pub fn savepoint(&self, name: &str) -> Result<Savepoint<'_>> {
+        let name = quote_identifier(name);
+        self.execute(&format!("SAVEPOINT {name}"), ())?;
+        Ok(Savepoint {
+            db: self,
+            name,
+            active: true,
+        })
+    }
+}
+
+impl<'db> Savepoint<'db> {
+    /// Creates a savepoint nested inside of this one. The outer savepoint cannot be used
+    /// until the nested one is finalized.
+    pub fn savepoint(&mut self, name: &str) -> Result<Savepoint<'_>> {
+        self.db.savepoint(name)
+    }
+
+    /// Consumes the Savepoint, releasing it. The changes made since the savepoint was
+    /// created become part of the enclosing savepoint or transaction.
+    pub fn release(mut self) -> Result<&'db Connection> {
+        self.active = false;
+        self.execute(&format!("RELEASE SAVEPOINT {}", self.name), ())?;
+        Ok(self.db)
+    }
+
+    /// Undoes all changes made since the savepoint was created. The savepoint remains
+    /// active, and must still be finalized.
+    pub fn rollback_to(&mut self) -> Result<()> {
+        self.execute(&format!("ROLLBACK TO SAVEPOINT {}", self.name), ())?;
+        Ok(())
+    }
+
+    /// Consumes the Savepoint, undoing all changes made since it was created and then
+    /// releasing it.
+    pub fn rollback(mut self) -> Result<&'db Connection> {
+        self.rollback_mut().map(|_| self.db)
+    }
+
+    fn rollback_mut(&mut self) -> Result<()> {
+        self.active = false;
+        self.rollback_to()?;
+        self.execute(&format!("RELEASE SAVEPOINT {}", self.name), ())?;
+        Ok(())
+    }
+}
+
+impl std::ops::Deref for Savepoint<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.db
+    }
+}
+
+impl Drop for Savepoint<'_> {
+    fn drop(&mut self) {
+        if self.active {
+            if let Err(e) = self.rollback_mut() {
+                if std::thread::panicking() {
+                    eprintln!("Error while closing SQLite savepoint: {e:?}");
+                } else {
+                    panic!("Error while closing SQLite savepoint: {e:?}");
+                }
+            }
+        }
+    }
+}
+
 #[cfg(all(test, feature = "static"))]
 mod test {
     use crate::test_helpers::prelude::*;
@@ -238,4 +405,145 @@ mod test {
         }
         Ok(())
     }
+
+    #[test]
+    fn named_savepoints() -> Result<()> {
+        let h = TestHelpers::new();
+        h.db.execute("CREATE TABLE tbl(col)", ())?;
+        let count = || {
+            h.db.query_row("SELECT COUNT(*) FROM tbl", (), |r| Ok(r[0].get_i64()))
+        };
+        let mut outer = h.db.savepoint("outer \"sp\"")?;
+        outer.execute("INSERT INTO tbl VALUES (1)", ())?;
+        {
+            let mut inner = outer.savepoint("inner")?;
+            inner.execute("INSERT INTO tbl VALUES (2)", ())?;
+            inner.rollback_to()?;
+            assert_eq!(count()?, 1);
+            inner.execute("INSERT INTO tbl VALUES (3)", ())?;
+            inner.release()?;
+        }
+        {
+            let inner = outer.savepoint("inner")?;
+            inner.execute("INSERT INTO tbl VALUES (4)", ())?;
+        }
+        assert_eq!(count()?, 2);
+        outer.release()?;
+        // Releasing the outermost savepoint commits the transaction.
+        assert!(h.db.execute("COMMIT", ()).is_err());
+        assert_eq!(count()?, 2);
+
+        let sp = h.db.savepoint("x")?;
+        sp.execute("INSERT INTO tbl VALUES (5)", ())?;
+        sp.rollback()?;
+        assert!(h.db.execute("COMMIT", ()).is_err());
+        assert_eq!(count()?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn with_transaction() -> Result<()> {
+        let h = TestHelpers::new();
+        h.db.execute("CREATE TABLE tbl(col)", ())?;
+        let count = || {
+            h.db.query_row("SELECT COUNT(*) FROM tbl", (), |r| Ok(r[0].get_i64()))
+        };
+        let ret = h.db.with_transaction(TransactionType::Immediate, |db| {
+            db.execute("INSERT INTO tbl VALUES (1)", ())?;
+            assert!(db.execute("BEGIN", ()).is_err());
+            Ok("done")
+        })?;
+        assert_eq!(ret, "done");
+        assert_eq!(count()?, 1);
+
+        let err =
+            h.db.with_transaction(TransactionType::Deferred, |db| {
+                db.execute("INSERT INTO tbl VALUES (2)", ())?;
+                Err::<(), _>(Error::Module("failed".to_owned()))
+            })
+            .unwrap_err();
+        assert_eq!(err, Error::Module("failed".to_owned()));
+        assert_eq!(count()?, 1);
+        // No transaction was left open.
+        assert!(h.db.execute("COMMIT", ()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn with_transaction_panic() -> Result<()> {
+        let h = TestHelpers::new();
+        h.db.execute("CREATE TABLE tbl(col)", ())?;
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            h.db.with_transaction(TransactionType::Deferred, |db| -> Result<()> {
+                db.execute("INSERT INTO tbl VALUES (1)", ())?;
+                panic!("closure failed");
+            })
+        }));
+        assert!(res.is_err());
+        let count =
+            h.db.query_row("SELECT COUNT(*) FROM tbl", (), |r| Ok(r[0].get_i64()))?;
+        assert_eq!(count, 0);
+        assert!(h.db.execute("COMMIT", ()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn with_transaction_nested() -> Result<()> {
+        let h = TestHelpers::new();
+        h.db.execute("CREATE TABLE tbl(col)", ())?;
+        let values = || -> Result<Vec<i64>> {
+            h.db.prepare("SELECT col FROM tbl ORDER BY col")?
+                .query(())?
+                .map(|r| Ok(r[0].get_i64()))
+                .collect()
+        };
+        let txn = h.db.transaction(TransactionType::Deferred)?;
+        txn.execute("INSERT INTO tbl VALUES (1)", ())?;
+        txn.with_transaction(TransactionType::Exclusive, |db| {
+            db.execute("INSERT INTO tbl VALUES (2)", ())?;
+            let err = db.with_transaction(TransactionType::Exclusive, |db| {
+                db.execute("INSERT INTO tbl VALUES (3)", ())?;
+                Err::<(), _>(SQLITE_CONSTRAINT)
+            });
+            assert_eq!(err, Err(SQLITE_CONSTRAINT));
+            Ok(())
+        })?;
+        assert_eq!(values()?, vec![1, 2]);
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            txn.with_transaction(TransactionType::Deferred, |db| -> Result<()> {
+                db.execute("INSERT INTO tbl VALUES (4)", ())?;
+                panic!("closure failed");
+            })
+        }));
+        assert!(res.is_err());
+        // Only the savepoints were finalized, so the outer transaction is still active.
+        assert_eq!(values()?, vec![1, 2]);
+        txn.rollback()?;
+        assert_eq!(values()?, Vec::<i64>::new());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(modern_sqlite)]
+    fn with_transaction_busy_write() -> Result<()> {
+        let h = TestHelpers::new();
+        h.db.execute("CREATE TABLE tbl(col)", ())?;
+        h.db.create_scalar_function("nested", &FunctionOptions::default(), |ctx, _| {
+            let ret = ctx
+                .db()
+                .with_transaction(TransactionType::Deferred, |_| -> Result<()> {
+                    unreachable!("the closure must not run")
+                });
+            ctx.set_result(ret.unwrap_err().to_string())
+        })?;
+        h.db.execute("INSERT INTO tbl VALUES (nested())", ())?;
+        let msg: String = h.db.query_row("SELECT col FROM tbl", (), |r| {
+            Ok(r[0].get_str()?.to_owned())
+        })?;
+        assert_eq!(
+            msg,
+            "cannot start a transaction while a write statement is running"
+        );
+        Ok(())
+    }
 }
@@ -1,7 +1,10 @@
-use super::{ffi, mutex::SQLiteMutexGuard, sqlite3_require_version, Connection};
+use super::{
+    ffi, mutex::SQLiteMutexGuard, sqlite3_require_version, Connection, Limit, SqliteBuffer,
+};
 use std::{
     ffi::CStr,
     os::raw::{c_char, c_int},
+    str::FromStr,
 };
 
 /// Alias for [Error::Sqlite]\([ffi::SQLITE_LOCKED]\).
@@ -23,7 +26,15 @@ pub const SQLITE_MISUSE: Error = Error::Sqlite(ffi::SQLITE_MISUSE, None);
 /// Alias for [Error::Sqlite]\([ffi::SQLITE_RANGE]\).
 pub const SQLITE_RANGE: Error = Error::Sqlite(ffi::SQLITE_RANGE, None);
 
-#[derive(Clone, Eq, PartialEq)]
+/// The error type used throughout this crate.
+///
+/// Errors compare equal when they are the same kind of error with the same code. In
+/// particular, the description of an [Error::Sqlite] is ignored, so an error returned by
+/// SQLite can be compared against one of the constants like [SQLITE_CONSTRAINT]. Extended
+/// error codes are compared exactly, so `SQLITE_CONSTRAINT_UNIQUE` does not equal
+/// [SQLITE_CONSTRAINT].
+#[derive(Clone)]
+#[non_exhaustive]
 pub enum Error {
     /// An error returned by SQLite.
     Sqlite(i32, Option<String>),
@@ -42,6 +53,36 @@ pub enum Error {
     /// The result was not necessary to produce because it is an unchanged column in an
     /// UPDATE operation. See [ValueRef::nochange](crate::ValueRef::nochange) for details.
     NoChange,
+    /// The schema of the database changed while a [Statement](crate::query::Statement) was
+    /// running, and the statement was re-prepared with a different number of result
+    /// columns. The statement has been reset and can be stepped again to read the rows
+    /// using the new columns.
+    SchemaChanged,
+    /// A statement could not be prepared because it exceeds one of the run-time limits of
+    /// the connection. See [Connection::try_prepare_checked].
+    LimitExceeded {
+        /// The limit which was exceeded.
+        limit: Limit,
+        /// The value of the limit when the statement was prepared.
+        max: i32,
+    },
+    /// A query returned more rows than the caller allowed. See [Connection::query_table].
+    TooManyRows {
+        /// The maximum number of rows which was allowed.
+        max: usize,
+    },
+    /// A table does not exist. See [Connection::column_metadata].
+    NoSuchTable {
+        /// The name of the table.
+        table: String,
+    },
+    /// A table exists, but does not have a column. See [Connection::column_metadata].
+    NoSuchColumn {
+        /// The name of the table.
+        table: String,
+        /// The name of the column.
+        column: String,
+    },
 }
 
 impl Error {
@@ -79,32 +120,131 @@ impl Error {
         }
     }
 
+    /// Add context to the error, such as the name of the table or cursor which produced it.
+    /// The message of the returned error is `"{context}: {message}"`.
+    ///
+    /// An [Error::Sqlite] keeps its error code, and an [Error::Module] remains a module
+    /// error. [Error::NoChange] and [Error::SchemaChanged] are signals rather than
+    /// failures, so they are returned unchanged. Any other error is converted to an
+    /// [Error::Module] containing its message.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sqlite3_ext::*;
+    ///
+    /// let err = Error::Module("row not found".to_owned()).with_context("table \"logs\"");
+    /// assert_eq!(err.to_string(), "table \"logs\": row not found");
+    /// ```
+    pub fn with_context(self, context: impl std::fmt::Display) -> Self {
+        match self {
+            Error::Sqlite(code, _) => Error::Sqlite(code, Some(format!("{context}: {self}"))),
+            e @ Error::NoChange | e @ Error::SchemaChanged => e,
+            e => Error::Module(format!("{context}: {e}")),
+        }
+    }
+
+    /// Convert the error into an SQLite result code, storing the message in `msg`. Any
+    /// message already stored in `msg` is freed, even if the error has no message.
     pub(crate) fn into_sqlite(self, msg: *mut *mut c_char) -> c_int {
         match self {
             Error::Sqlite(code, s) => {
-                if let Some(s) = s {
-                    if let Ok(s) = ffi::str_to_sqlite3(&s) {
-                        unsafe { *msg = s };
-                    }
-                }
+                // An empty message is treated as no message, so that SQLite uses the default
+                // message for the error code.
+                unsafe { set_message(msg, s.as_deref().unwrap_or("")) };
                 code
             }
             e @ Error::Utf8Error(_)
             | e @ Error::NulError(_)
             | e @ Error::VersionNotSatisfied(_)
             | e @ Error::Module(_)
-            | e @ Error::NoChange => {
-                if !msg.is_null() {
-                    if let Ok(s) = ffi::str_to_sqlite3(&format!("{e}")) {
-                        unsafe { *msg = s };
-                    }
-                }
+            | e @ Error::NoChange
+            | e @ Error::SchemaChanged
+            | e @ Error::LimitExceeded { .. }
+            | e @ Error::TooManyRows { .. }
+            | e @ Error::NoSuchTable { .. }
+            | e @ Error::NoSuchColumn { .. } => {
+                unsafe { set_message(msg, &format!("{e}")) };
                 ffi::SQLITE_ERROR
             }
         }
     }
 }
 
+/// Error messages passed to SQLite are truncated to this many bytes.
+const MAX_MESSAGE_LEN: usize = 1024;
+
+/// Replace the SQLite-allocated message in `msg`, freeing the previous one. An empty text
+/// leaves `msg` null.
+unsafe fn set_message(msg: *mut *mut c_char, text: &str) {
+    if msg.is_null() {
+        return;
+    }
+    let new = match text.is_empty() {
+        true => std::ptr::null_mut(),
+        false => match SqliteBuffer::from_str(&truncate_message(text)) {
+            Ok(s) => s.into_raw() as _,
+            // Keep the previous message rather than losing both.
+            Err(_) => return,
+        },
+    };
+    ffi::sqlite3_free(*msg as _);
+    *msg = new;
+}
+
+fn truncate_message(text: &str) -> std::borrow::Cow<'_, str> {
+    const ELLIPSIS: &str = "...";
+    if text.len() <= MAX_MESSAGE_LEN {
+        return text.into();
+    }
+    let mut end = MAX_MESSAGE_LEN - ELLIPSIS.len();
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{ELLIPSIS}", &text[..end]).into()
+}
+
+impl Error {
+    /// Returns true if the errors are the same kind of error with the same code. This is the
+    /// same as `==`, and ignores the description of [Error::Sqlite].
+    pub fn matches(&self, other: &Error) -> bool {
+        self == other
+    }
+}
+
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Error::Sqlite(a, _), Error::Sqlite(b, _)) => a == b,
+            (Error::Utf8Error(a), Error::Utf8Error(b)) => a == b,
+            (Error::NulError(a), Error::NulError(b)) => a == b,
+            (Error::VersionNotSatisfied(a), Error::VersionNotSatisfied(b)) => a == b,
+            (Error::Module(a), Error::Module(b)) => a == b,
+            (Error::NoChange, Error::NoChange) => true,
+            (Error::SchemaChanged, Error::SchemaChanged) => true,
+            (
+                Error::LimitExceeded { limit: a, max: x },
+                Error::LimitExceeded { limit: b, max: y },
+            ) => a == b && x == y,
+            (Error::TooManyRows { max: a }, Error::TooManyRows { max: b }) => a == b,
+            (Error::NoSuchTable { table: a }, Error::NoSuchTable { table: b }) => a == b,
+            (
+                Error::NoSuchColumn {
+                    table: a,
+                    column: x,
+                },
+                Error::NoSuchColumn {
+                    table: b,
+                    column: y,
+                },
+            ) => a == b && x == y,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Error {}
+
 impl From<String> for Error {
     fn from(msg: String) -> Self {
         Self::Module(msg)
@@ -143,6 +283,15 @@ impl std::fmt::Display for Error {
                 v % 1000
             ),
             Error::NoChange => write!(f, "invalid Error::NoChange"),
+            Error::SchemaChanged => write!(f, "database schema has changed"),
+            Error::LimitExceeded { limit, max } => {
+                write!(f, "statement exceeds the {limit:?} limit of {max}")
+            }
+            Error::TooManyRows { max } => write!(f, "query returned more than {max} rows"),
+            Error::NoSuchTable { table } => write!(f, "no such table: {table}"),
+            Error::NoSuchColumn { table, column } => {
+                write!(f, "no such column: {table}.{column}")
+            }
         }
     }
 }
@@ -169,6 +318,22 @@ impl std::fmt::Debug for Error {
                 f.debug_tuple("VersionNotSatisfied").field(&v).finish()
             }
             Error::NoChange => f.debug_tuple("NoChange").finish(),
+            Error::SchemaChanged => f.debug_tuple("SchemaChanged").finish(),
+            Error::LimitExceeded { limit, max } => f
+                .debug_struct("LimitExceeded")
+                .field("limit", &limit)
+                .field("max", &max)
+                .finish(),
+            Error::TooManyRows { max } => f.debug_struct("TooManyRows").field("max", &max).finish(),
+            Error::NoSuchTable { table } => f
+                .debug_struct("NoSuchTable")
+                .field("table", &table)
+                .finish(),
+            Error::NoSuchColumn { table, column } => f
+                .debug_struct("NoSuchColumn")
+                .field("table", &table)
+                .field("column", &column)
+                .finish(),
         }
     }
 }
@@ -188,3 +353,71 @@ impl From<std::ffi::NulError> for Error {
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::ffi::CString;
+
+    #[test]
+    fn equality() {
+        let desc = Error::Sqlite(ffi::SQLITE_CONSTRAINT, Some("UNIQUE failed".to_owned()));
+        assert_eq!(desc, SQLITE_CONSTRAINT);
+        assert!(desc.matches(&SQLITE_CONSTRAINT));
+        assert_ne!(desc, SQLITE_MISUSE);
+        assert_ne!(
+            Error::Sqlite(ffi::SQLITE_CONSTRAINT_UNIQUE, None),
+            SQLITE_CONSTRAINT
+        );
+        assert_eq!(Error::Module("a".to_owned()), Error::Module("a".to_owned()));
+        assert_ne!(Error::Module("a".to_owned()), Error::Module("b".to_owned()));
+        assert_ne!(Error::Module("a".to_owned()), SQLITE_CONSTRAINT);
+        assert_eq!(
+            Error::VersionNotSatisfied(3_008_007),
+            Error::VersionNotSatisfied(3_008_007)
+        );
+        assert_ne!(Error::NoChange, Error::SchemaChanged);
+    }
+
+    #[test]
+    fn conversions() {
+        fn utf8() -> Result<()> {
+            let bytes = vec![0x61, 0xff];
+            std::str::from_utf8(&bytes)?;
+            Ok(())
+        }
+        fn nul() -> Result<()> {
+            CString::new("a\0b")?;
+            Ok(())
+        }
+        let err = utf8().unwrap_err();
+        assert!(matches!(err, Error::Utf8Error(e) if e.valid_up_to() == 1));
+        assert_eq!(utf8().unwrap_err(), utf8().unwrap_err());
+        let err = nul().unwrap_err();
+        assert_eq!(err.clone(), err);
+        assert!(matches!(err, Error::NulError(e) if e.nul_position() == 1));
+    }
+
+    #[test]
+    fn with_context() {
+        let err = Error::Sqlite(ffi::SQLITE_CONSTRAINT, Some("UNIQUE failed".to_owned()));
+        let err = err.with_context("cursor 3");
+        assert_eq!(err, SQLITE_CONSTRAINT);
+        assert_eq!(err.to_string(), "cursor 3: UNIQUE failed");
+        assert_eq!(Error::NoChange.with_context("x"), Error::NoChange);
+        let err = Error::TooManyRows { max: 2 }.with_context("tbl");
+        assert_eq!(
+            err,
+            Error::Module("tbl: query returned more than 2 rows".to_owned())
+        );
+    }
+
+    #[test]
+    fn truncate_message() {
+        assert_eq!(super::truncate_message("short"), "short");
+        let long = "\u{e9}".repeat(MAX_MESSAGE_LEN);
+        let truncated = super::truncate_message(&long);
+        assert!(truncated.len() <= MAX_MESSAGE_LEN);
+        assert!(truncated.ends_with("\u{e9}..."));
+    }
+}
@@ -26,6 +26,9 @@ pub struct Blob {
     data: NonNull<u8>,
 }
 
+// SAFETY: a Blob exclusively owns its allocation, like a Box<[u8]>.
+unsafe impl Send for Blob {}
+
 impl Blob {
     fn alloc(len: usize) -> Blob {
         let data = unsafe { NonNull::new_unchecked(alloc(blob_layout(len))) };
@@ -1,12 +1,14 @@
 use super::{ffi, sqlite3_match_version, types::*};
 pub use blob::*;
 pub use passed_ref::*;
-use std::{marker::PhantomData, ptr, slice, str};
+pub use sqlite_buffer::*;
+use std::{borrow::Cow, ffi::CStr, marker::PhantomData, ptr, slice, str};
 pub use unsafe_ptr::*;
 pub use value_list::*;
 
 mod blob;
 mod passed_ref;
+mod sqlite_buffer;
 mod test;
 mod unsafe_ptr;
 mod value_list;
@@ -58,6 +60,78 @@ pub trait FromValue {
     /// Interpret this value as f64.
     fn get_f64(&self) -> f64;
 
+    /// Attempt to interpret this value as i32 without loss. Unlike [get_i32](Self::get_i32),
+    /// this method fails with [SQLITE_MISMATCH] if the value is not an INTEGER, or a REAL
+    /// which holds an integer, or if the integer does not fit.
+    fn try_get_i32(&self) -> Result<i32> {
+        checked_int(self)
+    }
+
+    /// Attempt to interpret this value as i64 without loss. Unlike [get_i64](Self::get_i64),
+    /// this method fails with [SQLITE_MISMATCH] if the value is not an INTEGER, or a REAL
+    /// which holds an integer.
+    fn try_get_i64(&self) -> Result<i64> {
+        checked_int(self)
+    }
+
+    /// Attempt to interpret this value as f64. Unlike [get_f64](Self::get_f64), this method
+    /// fails with [SQLITE_MISMATCH] if the value is not an INTEGER or REAL.
+    fn try_get_f64(&self) -> Result<f64> {
+        match self.value_type() {
+            ValueType::Integer | ValueType::Float => Ok(self.get_f64()),
+            ty => Err(Error::Sqlite(
+                ffi::SQLITE_MISMATCH,
+                Some(format!("{ty:?} is not a number")),
+            )),
+        }
+    }
+
+    /// Interpret this value as i16. This method fails with [SQLITE_MISMATCH] if the value
+    /// is not an INTEGER, or a REAL which holds an integer, or if the integer does not fit.
+    fn get_i16(&self) -> Result<i16> {
+        checked_int(self)
+    }
+
+    /// Interpret this value as u32. This method fails with [SQLITE_MISMATCH] if the value
+    /// is not an INTEGER, or a REAL which holds an integer, or if the integer does not fit.
+    fn get_u32(&self) -> Result<u32> {
+        checked_int(self)
+    }
+
+    /// Interpret this value as u64. This method fails with [SQLITE_MISMATCH] if the value
+    /// is not an INTEGER, or a REAL which holds an integer, or if the integer is negative.
+    fn get_u64(&self) -> Result<u64> {
+        checked_int(self)
+    }
+
+    /// Interpret this value as bool. The integer values 0 and 1 are accepted; any other
+    /// value, including NULL, fails with [SQLITE_MISMATCH].
+    fn get_bool(&self) -> Result<bool> {
+        match checked_int::<i64, _>(self)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            x => Err(Error::Sqlite(
+                ffi::SQLITE_MISMATCH,
+                Some(format!("{x} is not a valid bool")),
+            )),
+        }
+    }
+
+    /// Interpret this value as f32. This method fails with [SQLITE_MISMATCH] if the value is
+    /// not an INTEGER or REAL, or if it is finite but out of the range of f32.
+    fn get_f32(&self) -> Result<f32> {
+        let x = self.try_get_f64()?;
+        let ret = x as f32;
+        if x.is_finite() && !ret.is_finite() {
+            Err(Error::Sqlite(
+                ffi::SQLITE_MISMATCH,
+                Some(format!("{x} is out of range for f32")),
+            ))
+        } else {
+            Ok(ret)
+        }
+    }
+
     /// Get the bytes of this BLOB value.
     ///
     /// # Safety
@@ -96,6 +170,30 @@ pub trait FromValue {
         Ok(str::from_utf8(self.get_blob()?)?)
     }
 
+    /// Interpret the value as TEXT, replacing invalid UTF-8 with U+FFFD REPLACEMENT
+    /// CHARACTER, as [String::from_utf8_lossy] does. Returns None if the value is NULL.
+    ///
+    /// This method will fail if SQLite runs out of memory while converting the value.
+    fn get_str_lossy(&mut self) -> Result<Option<Cow<'_, str>>> {
+        if self.is_null() {
+            return Ok(None);
+        }
+        Ok(Some(String::from_utf8_lossy(self.get_blob()?)))
+    }
+
+    /// Interpret the value as TEXT and copy it into a String. Returns None if the value is
+    /// NULL.
+    ///
+    /// Unlike [get_str](Self::get_str), the result does not borrow this value, so it can be
+    /// held while other values in the same slice are read or modified. This method will
+    /// fail in the same cases as [get_str](Self::get_str).
+    fn get_text_owned(&mut self) -> Result<Option<String>> {
+        if self.is_null() {
+            return Ok(None);
+        }
+        self.get_str().map(|s| Some(s.to_owned()))
+    }
+
     /// Attempt to interpret this value as TEXT, without converting. If the underlying data
     /// type is not TEXT, this function will fail with Err([SQLITE_MISMATCH]). This
     /// function can also fail if the string has invalid UTF-8.
@@ -118,6 +216,39 @@ pub trait FromValue {
     }
 }
 
+/// Convert the value to an integer type without loss. INTEGER values are accepted, as are
+/// REAL values which hold an integer; everything else fails with [SQLITE_MISMATCH].
+fn checked_int<T: TryFrom<i64>, V: FromValue + ?Sized>(val: &V) -> Result<T> {
+    let x = match val.value_type() {
+        ValueType::Integer => val.get_i64(),
+        ValueType::Float => {
+            let f = val.get_f64();
+            if f.fract() != 0.0 || !(i64::MIN as f64..i64::MAX as f64).contains(&f) {
+                return Err(Error::Sqlite(
+                    ffi::SQLITE_MISMATCH,
+                    Some(format!("{f} is not an integer")),
+                ));
+            }
+            f as i64
+        }
+        ty => {
+            return Err(Error::Sqlite(
+                ffi::SQLITE_MISMATCH,
+                Some(format!("{ty:?} is not an integer")),
+            ))
+        }
+    };
+    T::try_from(x).map_err(|_| {
+        Error::Sqlite(
+            ffi::SQLITE_MISMATCH,
+            Some(format!(
+                "{x} is out of range for {}",
+                std::any::type_name::<T>()
+            )),
+        )
+    })
+}
+
 /// A protected SQL value.
 ///
 /// SQLite always owns all value objects. Consequently, this struct is never owned by Rust
@@ -190,6 +321,25 @@ impl ValueRef {
         }
     }
 
+    /// Returns the subtype of this value, which was set by the function which produced it
+    /// using [Context::set_result_with_subtype](crate::function::Context::set_result_with_subtype).
+    /// A value without a subtype has subtype 0. A function which reads the subtypes of its
+    /// arguments should be registered with
+    /// [FunctionOptions::set_reads_subtype](crate::function::FunctionOptions::set_reads_subtype).
+    ///
+    /// Beginning with SQLite 3.45.0, a subtype does not survive being stored in an
+    /// intermediate table unless the function which produced it was registered with
+    /// [FunctionOptions::set_returns_subtype](crate::function::FunctionOptions::set_returns_subtype).
+    ///
+    /// Requires SQLite 3.9.0. On earlier versions of SQLite, this function will always
+    /// return 0.
+    pub fn subtype(&self) -> u8 {
+        sqlite3_match_version! {
+            3_009_000 => unsafe { ffi::sqlite3_value_subtype(self.as_ptr()) as u8 },
+            _ => 0,
+        }
+    }
+
     // Caller is responsible for enforcing Rust pointer aliasing rules.
     unsafe fn get_ref_internal<T: 'static>(&self) -> Option<&mut PassedRef<T>> {
         sqlite3_match_version! {
@@ -211,6 +361,27 @@ impl ValueRef {
             .map(|x| PassedRef::get(x))
             .unwrap_or(None)
     }
+
+    /// Get a pointer which was passed with the given tag, using
+    /// [Statement::bind_pointer](crate::query::Statement::bind_pointer) or
+    /// [UnsafePassedRef]. This method returns None unless the value holds a pointer whose
+    /// tag is exactly equal to the given tag.
+    ///
+    /// Requires SQLite 3.20.0. On earlier versions of SQLite, this function will always
+    /// return None.
+    ///
+    /// # Safety
+    ///
+    /// Nothing verifies the type of the pointer, so the caller must ensure that every
+    /// pointer passed with this tag points to a valid `T`. Pointers may also come from other
+    /// extensions, so tags should be specific enough not to collide with them.
+    pub unsafe fn get_ref_tagged<T>(&self, tag: &CStr) -> Option<&T> {
+        let _ = tag;
+        sqlite3_match_version! {
+            3_020_000 => (ffi::sqlite3_value_pointer(self.as_ptr(), tag.as_ptr()) as *const T).as_ref(),
+            _ => None,
+        }
+    }
 }
 
 impl FromValue for ValueRef {
@@ -298,6 +469,10 @@ impl std::fmt::Debug for ValueRef {
 }
 
 /// Stores an SQLite-compatible value owned by Rust code.
+///
+/// SQLite cannot store NaN: binding or returning a NaN REAL produces NULL instead. For this
+/// reason, converting NaN to a Value using [From] produces [Value::Null], so that the Value
+/// matches what SQLite would hold.
 #[derive(Debug, PartialEq, Clone)]
 pub enum Value {
     Integer(i64),
@@ -319,7 +494,7 @@ macro_rules! value_from {
 
 value_from!(i32 as (x) => Value::Integer(x as _));
 value_from!(i64 as (x) => Value::Integer(x));
-value_from!(f64 as (x) => Value::Float(x));
+value_from!(f64 as (x) => if x.is_nan() { Value::Null } else { Value::Float(x) });
 value_from!(String as (x) => Value::Text(x));
 value_from!(Blob as (x) => Value::Blob(x));
 value_from!(() as (_x) => Value::Null);
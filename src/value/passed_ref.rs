@@ -1,4 +1,7 @@
-use std::any::{Any, TypeId};
+use std::{
+    any::{Any, TypeId},
+    ffi::CStr,
+};
 
 pub(crate) const POINTER_TAG: *const i8 = b"sqlite3_ext:PassedRef\0".as_ptr() as _;
 
@@ -69,9 +72,55 @@ impl<T: 'static> std::fmt::Debug for PassedRef<T> {
     }
 }
 
+/// Pass a borrowed pointer through SQLite, identified by a tag string.
+///
+/// This is the unchecked counterpart of [PassedRef]. It corresponds directly to the [pointer
+/// passing interface](https://www.sqlite.org/bindptr.html) of SQLite: the pointer is passed
+/// with a tag string, and can only be retrieved using
+/// [ValueRef::get_ref_tagged](super::ValueRef::get_ref_tagged) with a tag that is exactly
+/// equal. Unlike PassedRef, the value does not need to be `'static` and SQLite does not
+/// take ownership of it, so this can be used to pass borrowed data from a producer function
+/// to a consumer function or virtual table within the same statement. Because the tag is a
+/// plain string, pointers can also be exchanged with extensions written in other
+/// languages, such as the carray extension.
+///
+/// This type can be used as a query parameter, and as the result of an application-defined
+/// function.
+///
+/// Requires SQLite 3.20.0. On earlier versions of SQLite, binding an UnsafePassedRef fails
+/// and returning one from an application-defined function has no effect.
+#[derive(Debug)]
+pub struct UnsafePassedRef<T> {
+    ptr: *const T,
+    tag: &'static CStr,
+}
+
+impl<T> UnsafePassedRef<T> {
+    /// Create a new UnsafePassedRef for the pointer and tag.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that:
+    ///
+    /// - The pointer remains valid for as long as SQLite may hand it out. For a query
+    ///   parameter, this is until the statement is reset, rebound, or finalized. For the
+    ///   result of an application-defined function, this is until the statement which called
+    ///   the function finishes.
+    /// - Every reader of the tag interprets the pointer as a `T`. Tags should be unique to
+    ///   the type, for example by including the crate and type names.
+    pub unsafe fn new(ptr: *const T, tag: &'static CStr) -> Self {
+        Self { ptr, tag }
+    }
+
+    pub(crate) fn as_parts(&self) -> (*const T, &'static CStr) {
+        (self.ptr, self.tag)
+    }
+}
+
 #[cfg(all(modern_sqlite, test, feature = "static"))]
 mod test {
     use crate::test_helpers::prelude::*;
+    use std::ffi::CStr;
 
     #[test]
     fn get_ref() {
@@ -122,4 +171,58 @@ mod test {
         });
         assert_eq!(r.get(), 2);
     }
+
+    const TAG: &CStr = c"sqlite3_ext:test";
+    const OTHER_TAG: &CStr = c"sqlite3_ext:other";
+
+    #[test]
+    fn tagged_ref() {
+        let h = TestHelpers::new();
+        let s = "borrowed string".to_owned();
+        let ptr = unsafe { UnsafePassedRef::new(&s as *const String, TAG) };
+        h.with_value(ptr, |val| {
+            assert_eq!(val.value_type(), ValueType::Null);
+            assert_eq!(unsafe { val.get_ref_tagged::<String>(TAG) }, Some(&s));
+            assert_eq!(unsafe { val.get_ref_tagged::<String>(OTHER_TAG) }, None);
+            assert_eq!(
+                unsafe { val.get_ref_tagged::<String>(c"sqlite3_ext:tes") },
+                None
+            );
+            assert_eq!(val.get_ref::<String>(), None);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn tagged_passed_ref() {
+        let h = TestHelpers::new();
+        h.with_value(PassedRef::new(0i32), |val| {
+            assert_eq!(unsafe { val.get_ref_tagged::<i32>(TAG) }, None);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn bind_pointer() -> Result<()> {
+        let h = TestHelpers::new();
+        h.db.create_scalar_function("read", &FunctionOptions::default(), |c, args| {
+            let tagged = unsafe { args[0].get_ref_tagged::<Vec<i64>>(TAG) };
+            let other = unsafe { args[0].get_ref_tagged::<Vec<i64>>(OTHER_TAG) };
+            assert_eq!(other, None);
+            c.set_result(tagged.map(|v| v.iter().sum::<i64>()))
+        })?;
+        let mut stmt = h.db.prepare("SELECT read(?), read(?)")?;
+        stmt.query(())?;
+        stmt.bind_pointer(1, vec![1i64, 2, 3], TAG)?;
+        let row = stmt.next()?.unwrap();
+        assert_eq!(row[0].to_owned()?, Value::Integer(6));
+        assert_eq!(row[1].to_owned()?, Value::Null);
+
+        // Borrowed data can be passed as a parameter.
+        let data = vec![4i64, 5];
+        let ptr = unsafe { UnsafePassedRef::new(&data as *const Vec<i64>, TAG) };
+        let (ret,): (i64,) = h.db.query_row_as("SELECT read(?)", [ptr])?;
+        assert_eq!(ret, 9);
+        Ok(())
+    }
 }
@@ -0,0 +1,151 @@
+use crate::{ffi, sqlite3_match_version, types::*};
+use std::{
+    ffi::c_void,
+    mem::forget,
+    ops::Deref,
+    ptr::{copy_nonoverlapping, NonNull},
+    slice,
+    str::FromStr,
+};
+
+/// Represents memory allocated by `sqlite3_malloc`.
+///
+/// Several SQLite interfaces, such as
+/// [IndexInfo::set_index_str](crate::vtab::IndexInfo::set_index_str) and error messages
+/// returned from virtual tables, take ownership of memory which SQLite later releases with
+/// `sqlite3_free`. This type owns such memory until it is handed to SQLite with
+/// [into_raw](Self::into_raw).
+///
+/// A buffer created from a string is always nul-terminated, and the terminator is not
+/// included in [len](Self::len). In particular, an empty string produces a pointer to a
+/// single nul byte, never a NULL pointer.
+pub struct SqliteBuffer {
+    data: NonNull<u8>,
+    len: usize,
+}
+
+impl SqliteBuffer {
+    fn alloc(len: usize) -> Result<SqliteBuffer> {
+        // sqlite3_malloc returns NULL when asked for 0 bytes.
+        let size = len.max(1);
+        let ptr: *mut u8 = unsafe {
+            sqlite3_match_version! {
+                3_008_007 => ffi::sqlite3_malloc64(size as _) as _,
+                _ => {
+                    if size > i32::MAX as usize {
+                        return Err(SQLITE_NOMEM);
+                    }
+                    ffi::sqlite3_malloc(size as _) as _
+                }
+            }
+        };
+        match NonNull::new(ptr) {
+            Some(data) => Ok(SqliteBuffer { data, len }),
+            None => Err(SQLITE_NOMEM),
+        }
+    }
+
+    /// Copy the provided bytes into a new buffer.
+    pub fn from_slice(val: &[u8]) -> Result<SqliteBuffer> {
+        let ret = Self::alloc(val.len())?;
+        unsafe { copy_nonoverlapping(val.as_ptr(), ret.data.as_ptr(), val.len()) };
+        Ok(ret)
+    }
+
+    /// Return the length of the buffer, excluding the nul terminator of buffers created
+    /// from strings.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the buffer has a length of 0.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the underlying data.
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.data.as_ptr(), self.len) }
+    }
+
+    /// Consumes the buffer, returning a pointer to the data.
+    ///
+    /// After calling this function, the caller is responsible for freeing the memory with
+    /// `sqlite3_free`. Usually, this is done by passing the pointer to an SQLite interface
+    /// which takes ownership of it.
+    pub fn into_raw(self) -> *mut c_void {
+        let ret = self.data.as_ptr().cast();
+        forget(self);
+        ret
+    }
+}
+
+impl FromStr for SqliteBuffer {
+    type Err = Error;
+
+    /// Copy the provided string into a new nul-terminated buffer.
+    fn from_str(val: &str) -> Result<SqliteBuffer> {
+        let len = val.len();
+        let mut ret = Self::alloc(len.checked_add(1).ok_or(SQLITE_NOMEM)?)?;
+        unsafe {
+            copy_nonoverlapping(val.as_ptr(), ret.data.as_ptr(), len);
+            *ret.data.as_ptr().add(len) = 0;
+        }
+        ret.len = len;
+        Ok(ret)
+    }
+}
+
+impl Deref for SqliteBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl Drop for SqliteBuffer {
+    fn drop(&mut self) {
+        unsafe { ffi::sqlite3_free(self.data.as_ptr().cast()) }
+    }
+}
+
+impl std::fmt::Debug for SqliteBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        f.debug_tuple("SqliteBuffer")
+            .field(&self.as_slice())
+            .finish()
+    }
+}
+
+#[cfg(all(test, feature = "static"))]
+mod test {
+    use super::SqliteBuffer;
+    use std::{ffi::CStr, str::FromStr};
+
+    #[test]
+    fn from_str() {
+        let buf = SqliteBuffer::from_str("hello").unwrap();
+        assert_eq!(buf.len(), 5);
+        assert_eq!(&*buf, b"hello");
+        let ptr = buf.into_raw();
+        unsafe {
+            assert_eq!(CStr::from_ptr(ptr as _).to_str(), Ok("hello"));
+            crate::ffi::sqlite3_free(ptr);
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let buf = SqliteBuffer::from_str("").unwrap();
+        assert!(buf.is_empty());
+        let ptr = buf.into_raw();
+        assert!(!ptr.is_null());
+        unsafe {
+            assert_eq!(CStr::from_ptr(ptr as _).to_str(), Ok(""));
+            crate::ffi::sqlite3_free(ptr);
+        }
+        let buf = SqliteBuffer::from_slice(&[]).unwrap();
+        assert_eq!(buf.as_slice(), &[] as &[u8]);
+    }
+}
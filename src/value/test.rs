@@ -1,6 +1,6 @@
 #![cfg(all(test, feature = "static"))]
-use crate::test_helpers::prelude::*;
-use std::f64::consts::PI;
+use crate::{test_helpers::prelude::*, vtab::*};
+use std::{borrow::Cow, f64::consts::PI};
 
 #[test]
 fn get_i64() {
@@ -26,6 +26,86 @@ fn get_f64() {
     });
 }
 
+#[test]
+fn checked_integers() {
+    let h = TestHelpers::new();
+    h.with_value(-1i64, |val| {
+        assert_eq!(val.get_i16(), Ok(-1));
+        assert_eq!(
+            val.get_u64(),
+            Err(Error::Sqlite(
+                ffi::SQLITE_MISMATCH,
+                Some("-1 is out of range for u64".to_owned())
+            ))
+        );
+        assert!(val.get_u32().is_err());
+        assert!(val.get_bool().is_err());
+        Ok(())
+    });
+    h.with_value(i64::from(u32::MAX), |val| {
+        assert_eq!(val.get_u32(), Ok(u32::MAX));
+        assert_eq!(val.get_u64(), Ok(u32::MAX as u64));
+        assert!(val.get_i16().is_err());
+        Ok(())
+    });
+    h.with_value(true, |val| {
+        assert_eq!(val.get_bool(), Ok(true));
+        Ok(())
+    });
+    h.with_value(PI, |val| {
+        assert_eq!(val.get_f32(), Ok(PI as f32));
+        Ok(())
+    });
+    h.with_value(f64::MAX, |val| {
+        assert!(val.get_f32().is_err());
+        Ok(())
+    });
+}
+
+#[test]
+fn checked_non_integers() {
+    let h = TestHelpers::new();
+    h.with_value_from_sql("2.0", |val| {
+        assert_eq!(val.get_i16(), Ok(2));
+        assert_eq!(val.get_u64(), Ok(2));
+        Ok(())
+    });
+    h.with_value_from_sql("2.5", |val| {
+        assert_eq!(
+            val.get_i16(),
+            Err(Error::Sqlite(
+                ffi::SQLITE_MISMATCH,
+                Some("2.5 is not an integer".to_owned())
+            ))
+        );
+        assert!(val.get_u32().is_err());
+        assert!(val.get_u64().is_err());
+        assert!(val.get_bool().is_err());
+        assert_eq!(val.get_f32(), Ok(2.5));
+        Ok(())
+    });
+    h.with_value_from_sql("'abc'", |val| {
+        assert_eq!(
+            val.get_u32(),
+            Err(Error::Sqlite(
+                ffi::SQLITE_MISMATCH,
+                Some("Text is not an integer".to_owned())
+            ))
+        );
+        assert!(val.get_i16().is_err());
+        assert!(val.get_bool().is_err());
+        assert!(val.get_f32().is_err());
+        Ok(())
+    });
+    h.with_value_from_sql("NULL", |val| {
+        assert!(val.get_i16().is_err());
+        assert!(val.get_u64().is_err());
+        assert!(val.get_bool().is_err());
+        assert!(val.get_f32().is_err());
+        Ok(())
+    });
+}
+
 #[test]
 fn get_blob() {
     let h = TestHelpers::new();
@@ -102,3 +182,181 @@ fn get_str_invalid() {
         Ok(())
     });
 }
+
+#[test]
+fn get_str_lossy() {
+    let h = TestHelpers::new();
+    h.with_value_from_sql("CAST(x'61ff62c3' AS TEXT)", |val| {
+        assert_eq!(val.get_str_lossy()?.as_deref(), Some("a\u{fffd}b\u{fffd}"));
+        assert!(val.get_text_owned().is_err());
+        Ok(())
+    });
+    let h = TestHelpers::new();
+    h.with_value("valid", |val| {
+        let ret = val.get_str_lossy()?;
+        assert!(matches!(ret, Some(Cow::Borrowed("valid"))));
+        Ok(())
+    });
+    let h = TestHelpers::new();
+    h.with_value(None::<i64>, |val| {
+        assert_eq!(val.get_str_lossy()?, None);
+        assert_eq!(val.get_text_owned()?, None);
+        Ok(())
+    });
+    let h = TestHelpers::new();
+    h.with_value(42, |val| {
+        assert_eq!(val.get_str_lossy()?.as_deref(), Some("42"));
+        Ok(())
+    });
+}
+
+#[test]
+fn get_text_owned() -> Result<()> {
+    let h = TestHelpers::new();
+    h.db.create_scalar_function(
+        "swap_concat",
+        &FunctionOptions::default().set_n_args(2),
+        |ctx, args| {
+            // Both strings are held at once, and the args can still be modified.
+            let a = args[0].get_text_owned()?.unwrap_or_default();
+            let b = args[1].get_text_owned()?.unwrap_or_default();
+            args[0].numeric_type();
+            ctx.set_result(format!("{b}{a}"))
+        },
+    )?;
+    let ret: (String, String) =
+        h.db.query_row_as("SELECT swap_concat('a', 'b'), swap_concat(NULL, 12)", ())?;
+    assert_eq!(ret, ("ba".to_owned(), "12".to_owned()));
+    Ok(())
+}
+
+#[test]
+fn matrix_bind() -> Result<()> {
+    for encoding in ENCODINGS {
+        let (db, cases) = value_matrix(encoding);
+        for case in cases.iter() {
+            let ret = db.query_row(&format!("SELECT {}", case.sql), (), |r| r[0].to_owned())?;
+            assert_same_value(case, "literal", &ret);
+            let ret = db.query_row("SELECT ?", [&case.value], |r| r[0].to_owned())?;
+            assert_same_value(case, "bind", &ret);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn matrix_function() -> Result<()> {
+    for encoding in ENCODINGS {
+        let (db, cases) = value_matrix(encoding);
+        let opts = FunctionOptions::default().set_n_args(1);
+        db.create_scalar_function("echo", &opts, |c, args| c.set_result(&*args[0]))?;
+        db.create_scalar_function("echo_owned", &opts, |c, args| {
+            c.set_result(args[0].to_owned()?)
+        })?;
+        for case in cases.iter() {
+            for func in ["echo", "echo_owned"] {
+                let sql = format!("SELECT {func}({})", case.sql);
+                let ret = db.query_row(&sql, (), |r| r[0].to_owned())?;
+                assert_same_value(case, func, &ret);
+                let sql = format!("SELECT {func}(?)");
+                let ret = db.query_row(&sql, [&case.value], |r| r[0].to_owned())?;
+                assert_same_value(case, func, &ret);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Table-valued function which returns its argument as its only row.
+struct EchoVTab;
+
+struct EchoCursor(Option<Value>);
+
+impl VTab<'_> for EchoVTab {
+    type Aux = ();
+    type Cursor = EchoCursor;
+
+    fn connect(_: &VTabConnection, _: &(), _: &[&str]) -> Result<(String, Self)> {
+        Ok(("CREATE TABLE x ( value, arg HIDDEN )".to_owned(), EchoVTab))
+    }
+
+    fn best_index(&self, index_info: &mut IndexInfo) -> Result<()> {
+        index_info
+            .claim(1, ConstraintOp::Eq)
+            .ok_or(SQLITE_CONSTRAINT)?;
+        Ok(())
+    }
+
+    fn open(&self) -> Result<Self::Cursor> {
+        Ok(EchoCursor(None))
+    }
+}
+
+impl VTabCursor for EchoCursor {
+    fn filter(&mut self, _: i32, _: Option<&str>, args: &mut [&mut ValueRef]) -> Result<()> {
+        self.0 = Some(args[0].to_owned()?);
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.0 = None;
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        self.0.is_none()
+    }
+
+    fn column(&mut self, _: usize, c: &ColumnContext) -> Result<()> {
+        c.set_result(self.0.clone())
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(1)
+    }
+}
+
+#[test]
+fn matrix_vtab() -> Result<()> {
+    for encoding in ENCODINGS {
+        let (db, cases) = value_matrix(encoding);
+        db.create_module("echo", EponymousModule::<EchoVTab>::new(), ())?;
+        for case in cases.iter() {
+            let sql = format!("SELECT value FROM echo({})", case.sql);
+            let ret = db.query_row(&sql, (), |r| r[0].to_owned())?;
+            assert_same_value(case, "vtab", &ret);
+            let ret = db.query_row("SELECT value FROM echo(?)", [&case.value], |r| {
+                r[0].to_owned()
+            })?;
+            assert_same_value(case, "vtab", &ret);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn matrix_rebind() -> Result<()> {
+    for encoding in ENCODINGS {
+        let (db, cases) = value_matrix(encoding);
+        for case in cases.iter() {
+            let owned = db.query_row(&format!("SELECT {}", case.sql), (), |r| r[0].to_owned())?;
+            let ret = db.query_row("SELECT ?", [owned], |r| r[0].to_owned())?;
+            assert_same_value(case, "rebind", &ret);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn nan_is_null() -> Result<()> {
+    let h = TestHelpers::new();
+    assert_eq!(Value::from(f64::NAN), Value::Null);
+    let ret =
+        h.db.query_row("SELECT ?", [f64::NAN], |r| r[0].to_owned())?;
+    assert_eq!(ret, Value::Null);
+    let opts = FunctionOptions::default().set_n_args(0);
+    h.db.create_scalar_function("nan", &opts, |c, _| c.set_result(f64::NAN))?;
+    let ret = h.db.query_row("SELECT nan()", (), |r| r[0].to_owned())?;
+    assert_eq!(ret, Value::Null);
+    Ok(())
+}
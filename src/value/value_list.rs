@@ -1,4 +1,4 @@
-use super::ValueRef;
+use super::{FromValue, Value, ValueRef};
 use crate::{ffi, sqlite3_match_version, sqlite3_require_version, types::*, FallibleIteratorMut};
 use std::ptr;
 
@@ -38,6 +38,10 @@ use std::ptr;
 ///     Ok(())
 /// }
 /// ```
+///
+/// The list can be iterated multiple times using [reset](Self::reset), which allows a
+/// virtual table to make one pass over the values to plan its work and another to produce
+/// results, without copying the values.
 pub struct ValueList<'list> {
     #[cfg_attr(not(modern_sqlite), allow(unused))]
     base: &'list mut ValueRef,
@@ -70,6 +74,46 @@ impl<'list> ValueList<'list> {
             })
         })
     }
+
+    /// Restart iteration from the first value in the list.
+    pub fn reset(&mut self) -> Result<()> {
+        sqlite3_match_version! {
+            3_038_000 => unsafe {
+                let mut first: *mut ffi::sqlite3_value = ptr::null_mut();
+                Error::from_sqlite(ffi::sqlite3_vtab_in_first(self.base.as_ptr(), &mut first as _))?;
+                self.pending = Some(ptr::NonNull::new(first));
+                Ok(())
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    /// Return the number of values in the list.
+    ///
+    /// SQLite does not provide the length of the list directly, so this method iterates
+    /// over the entire list to count the values. Afterwards, iteration restarts from the
+    /// first value.
+    pub fn len_hint(&mut self) -> Result<usize> {
+        self.reset()?;
+        let mut len = 0;
+        while self.next()?.is_some() {
+            len += 1;
+        }
+        self.reset()?;
+        Ok(len)
+    }
+
+    /// Return owned copies of all of the values in the list. Afterwards, iteration restarts
+    /// from the first value.
+    pub fn collect_owned(&mut self) -> Result<Vec<Value>> {
+        self.reset()?;
+        let mut ret = vec![];
+        while let Some(x) = self.next()? {
+            ret.push(x.to_owned()?);
+        }
+        self.reset()?;
+        Ok(ret)
+    }
 }
 
 impl FallibleIteratorMut for ValueList<'_> {
@@ -0,0 +1,106 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+/// Shared, mutable state for use as the [Aux](super::VTab::Aux) data of a virtual table.
+///
+/// The Aux data is only available to a virtual table by shared reference, so any state which
+/// the virtual table modifies requires interior mutability. An AuxCell holds the state in an
+/// `Arc<Mutex<T>>`. Cloning the AuxCell produces another handle to the same state, so a
+/// clone can be passed to [Connection::create_module](crate::Connection::create_module) on
+/// each connection which uses the module, including connections on other threads. If only a
+/// single connection will use the state, `Rc<RefCell<T>>` is a cheaper alternative.
+///
+/// The state is accessed using [with](Self::with). If a closure passed to `with` panics, the
+/// mutex is poisoned, but later calls ignore the poisoning and receive the state as the
+/// panicking closure left it.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sqlite3_ext::{vtab::*, *};
+///
+/// # struct MyVTab;
+/// # impl VTab<'_> for MyVTab {
+/// #     type Aux = AuxCell<usize>;
+/// #     type Cursor = MyCursor;
+/// #     fn best_index(&self, _: &mut IndexInfo) -> Result<()> { Ok(()) }
+/// #     fn open(&self) -> Result<Self::Cursor> { Ok(MyCursor) }
+/// // Count the instances of the table across all connections.
+/// fn connect(_: &VTabConnection, aux: &Self::Aux, _: &[&str]) -> Result<(String, Self)> {
+///     let id = aux.with(|n| {
+///         *n += 1;
+///         *n
+///     });
+///     Ok((format!("CREATE TABLE x (id DEFAULT {id})"), MyVTab))
+/// }
+/// # }
+/// # struct MyCursor;
+/// # impl VTabCursor for MyCursor {
+/// #     fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> { Ok(()) }
+/// #     fn next(&mut self) -> Result<()> { Ok(()) }
+/// #     fn eof(&mut self) -> bool { true }
+/// #     fn column(&mut self, _: usize, _: &ColumnContext) -> Result<()> { Ok(()) }
+/// #     fn rowid(&mut self) -> Result<i64> { Ok(0) }
+/// # }
+/// ```
+pub struct AuxCell<T> {
+    state: Arc<Mutex<T>>,
+}
+
+impl<T> AuxCell<T> {
+    /// Create a new AuxCell holding the given state.
+    pub fn new(state: T) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// Run the closure with exclusive access to the state, and return its result.
+    ///
+    /// The state is locked while the closure runs, so the closure must not call `with` on
+    /// this AuxCell or any of its clones.
+    pub fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        let mut guard = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        f(&mut guard)
+    }
+
+    /// Returns a copy of the state.
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(|state| state.clone())
+    }
+
+    /// Replace the state, returning the previous state.
+    pub fn replace(&self, state: T) -> T {
+        self.with(|cur| std::mem::replace(cur, state))
+    }
+
+    /// Returns true if both AuxCells refer to the same state.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+impl<T> Clone for AuxCell<T> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T: Default> Default for AuxCell<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AuxCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.with(|state| f.debug_tuple("AuxCell").field(state).finish())
+    }
+}
@@ -0,0 +1,277 @@
+use super::*;
+
+type UpdateFn<'a, S> = dyn Fn(&S, &mut ChangeInfo) -> Result<i64> + 'a;
+
+/// The operators which are passed to the rows closure of a [ClosureModule].
+const FILTER_OPS: &[ConstraintOp] = &[
+    ConstraintOp::Eq,
+    ConstraintOp::GT,
+    ConstraintOp::GE,
+    ConstraintOp::LT,
+    ConstraintOp::LE,
+    ConstraintOp::NE,
+];
+
+/// Define a virtual table module from closures. See [ClosureModule] for details.
+///
+/// The schema is a CREATE TABLE statement declaring the columns of the table. The connect
+/// closure receives the arguments to CREATE VIRTUAL TABLE (which are empty when the table
+/// is used eponymously) and creates the state of the table. The rows closure returns every
+/// row of the table matching a [FilterArgs], in the order the columns were declared.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sqlite3_ext::{vtab::*, *};
+///
+/// fn init(db: &Connection) -> Result<()> {
+///     vtab::from_closures(
+///         "CREATE TABLE x (code TEXT, name TEXT)",
+///         |_| Ok(vec![("us", "United States"), ("fr", "France")]),
+///         |countries, filter| {
+///             let code = filter.eq(0).cloned();
+///             Ok(countries
+///                 .iter()
+///                 .map(|&(c, n)| vec![Value::Text(c.into()), Value::Text(n.into())])
+///                 .filter(|row| code.as_ref().map_or(true, |code| row[0] == *code))
+///                 .collect())
+///         },
+///     )
+///     .register(db, "countries")
+/// }
+/// ```
+pub fn from_closures<S, C, R>(schema: &str, connect: C, rows: R) -> ClosureModule<'static, S, C, R>
+where
+    C: Fn(&[&str]) -> Result<S>,
+    R: Fn(&S, &FilterArgs) -> Result<Vec<Vec<Value>>>,
+{
+    ClosureModule {
+        schema: schema.to_owned(),
+        connect,
+        rows,
+        update: None,
+        rowid_column: None,
+    }
+}
+
+/// A virtual table module defined by closures, created with [from_closures].
+///
+/// This is intended for prototypes and tests, where implementing [VTab] and [VTabCursor]
+/// is more ceremony than the table deserves. It trades efficiency for ergonomics: every
+/// query calls the rows closure, which materializes all of the matching rows in memory
+/// before SQLite reads the first one. The table does not support ORDER BY optimization,
+/// LIMIT pushdown, or transactions. Tables which outgrow these limitations should
+/// implement the virtual table traits directly.
+///
+/// The module is eponymous, so it can be queried using the name it was registered with,
+/// and also supports CREATE VIRTUAL TABLE.
+pub struct ClosureModule<'a, S, C, R> {
+    schema: String,
+    connect: C,
+    rows: R,
+    update: Option<Box<UpdateFn<'a, S>>>,
+    rowid_column: Option<usize>,
+}
+
+impl<'a, S, C, R> ClosureModule<'a, S, C, R>
+where
+    C: Fn(&[&str]) -> Result<S>,
+    R: Fn(&S, &FilterArgs) -> Result<Vec<Vec<Value>>>,
+{
+    /// Support INSERT, UPDATE, and DELETE using the given closure, which behaves like
+    /// [UpdateVTab::update]. The closure should usually be combined with
+    /// [with_rowid_column](Self::with_rowid_column), so that the rowids passed to it
+    /// identify rows.
+    pub fn with_update<'b, U>(self, update: U) -> ClosureModule<'b, S, C, R>
+    where
+        U: Fn(&S, &mut ChangeInfo) -> Result<i64> + 'b,
+    {
+        ClosureModule {
+            schema: self.schema,
+            connect: self.connect,
+            rows: self.rows,
+            update: Some(Box::new(update)),
+            rowid_column: self.rowid_column,
+        }
+    }
+
+    /// Use the given column, which must be an INTEGER, as the rowid of each row. By
+    /// default, the rowid of a row is its position in the list returned by the rows
+    /// closure, which does not identify the row outside of a single query.
+    pub fn with_rowid_column(mut self, column: usize) -> Self {
+        self.rowid_column = Some(column);
+        self
+    }
+
+    /// Register the module with the connection.
+    pub fn register<'db>(self, db: &'db Connection, name: &str) -> Result<()>
+    where
+        'a: 'db,
+        S: 'db,
+        C: 'db,
+        R: 'db,
+    {
+        let module = EponymousModule::<ClosureVTab<'db, S, C, R>>::new();
+        let module = match self.update {
+            Some(_) => module.with_update(),
+            None => module,
+        };
+        db.create_module(name, module, self)
+    }
+}
+
+/// The constraints on a query of a [ClosureModule].
+///
+/// The rows closure may use these to avoid producing rows which do not match, but it is
+/// not required to: SQLite checks every constraint again on the rows that are returned.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FilterArgs {
+    constraints: Vec<(usize, ConstraintOp, Value)>,
+}
+
+impl FilterArgs {
+    /// Returns the column index, operator, and value of each constraint. Only the
+    /// comparison operators (`=`, `>`, `>=`, `<`, `<=`, and `!=`) are included.
+    pub fn constraints(&self) -> &[(usize, ConstraintOp, Value)] {
+        &self.constraints
+    }
+
+    /// Returns the value of the first `=` constraint on the given column.
+    pub fn eq(&self, column: usize) -> Option<&Value> {
+        self.constraints
+            .iter()
+            .find(|(c, op, _)| *c == column && *op == ConstraintOp::Eq)
+            .map(|(_, _, v)| v)
+    }
+}
+
+#[doc(hidden)]
+pub struct ClosureVTab<'vtab, S, C, R> {
+    module: &'vtab ClosureModule<'vtab, S, C, R>,
+    state: S,
+}
+
+impl<'vtab, S: 'vtab, C: 'vtab, R: 'vtab> VTab<'vtab> for ClosureVTab<'vtab, S, C, R>
+where
+    C: Fn(&[&str]) -> Result<S>,
+    R: Fn(&S, &FilterArgs) -> Result<Vec<Vec<Value>>>,
+{
+    type Aux = ClosureModule<'vtab, S, C, R>;
+    type Cursor = ClosureCursor<'vtab, S, C, R>;
+
+    fn connect(
+        _: &'vtab VTabConnection,
+        module: &'vtab Self::Aux,
+        args: &[&str],
+    ) -> Result<(String, Self)> {
+        // The first three arguments are the module, database, and table names.
+        let state = (module.connect)(args.get(3..).unwrap_or(&[]))?;
+        Ok((module.schema.clone(), ClosureVTab { module, state }))
+    }
+
+    fn best_index(&'vtab self, index_info: &mut IndexInfo) -> Result<()> {
+        // The constraints are not omitted, so the rows closure is free to ignore them.
+        let mut claimed = vec![];
+        let mut argv = 0;
+        for mut c in index_info.constraints() {
+            if c.usable() && FILTER_OPS.contains(&c.op()) {
+                c.set_argv_index(Some(argv));
+                argv += 1;
+                claimed.push(format!("{}:{}", c.column(), c.op().to_sqlite()));
+            }
+        }
+        index_info.set_estimated_cost(1_000_000.0 / (1 + claimed.len()) as f64);
+        index_info.set_index_str(Some(&claimed.join(",")))
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        Ok(ClosureCursor {
+            vtab: self,
+            rows: vec![],
+            pos: 0,
+        })
+    }
+}
+
+impl<'vtab, S: 'vtab, C: 'vtab, R: 'vtab> UpdateVTab<'vtab> for ClosureVTab<'vtab, S, C, R>
+where
+    C: Fn(&[&str]) -> Result<S>,
+    R: Fn(&S, &FilterArgs) -> Result<Vec<Vec<Value>>>,
+{
+    fn update(&'vtab self, info: &mut ChangeInfo) -> Result<i64> {
+        match &self.module.update {
+            Some(update) => update(&self.state, info),
+            None => Err(SQLITE_READONLY),
+        }
+    }
+}
+
+#[doc(hidden)]
+pub struct ClosureCursor<'vtab, S, C, R> {
+    vtab: &'vtab ClosureVTab<'vtab, S, C, R>,
+    rows: Vec<Vec<Value>>,
+    pos: usize,
+}
+
+impl<'vtab, S, C, R> VTabCursor for ClosureCursor<'vtab, S, C, R>
+where
+    C: Fn(&[&str]) -> Result<S>,
+    R: Fn(&S, &FilterArgs) -> Result<Vec<Vec<Value>>>,
+{
+    fn filter(
+        &mut self,
+        _: i32,
+        index_str: Option<&str>,
+        args: &mut [&mut ValueRef],
+    ) -> Result<()> {
+        let invalid = || Error::Module(format!("invalid query plan: {index_str:?}"));
+        let constraints = index_str
+            .unwrap_or("")
+            .split(',')
+            .filter(|x| !x.is_empty())
+            .zip(args.iter_mut())
+            .map(|(c, arg)| {
+                let (column, op) = c.split_once(':').ok_or_else(invalid)?;
+                let column = column.parse().map_err(|_| invalid())?;
+                let op = op
+                    .parse()
+                    .ok()
+                    .and_then(ConstraintOp::try_from_sqlite)
+                    .ok_or_else(invalid)?;
+                Ok((column, op, arg.to_owned()?))
+            })
+            .collect::<Result<_>>()?;
+        let filter = FilterArgs { constraints };
+        self.rows = (self.vtab.module.rows)(&self.vtab.state, &filter)?;
+        self.pos = 0;
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        self.pos >= self.rows.len()
+    }
+
+    fn column(&mut self, idx: usize, ctx: &ColumnContext) -> Result<()> {
+        match self.rows[self.pos].get(idx) {
+            Some(val) => ctx.set_result(val.clone()),
+            None => Ok(()),
+        }
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        match self.vtab.module.rowid_column {
+            Some(col) => match self.rows[self.pos].get(col) {
+                Some(Value::Integer(x)) => Ok(*x),
+                _ => Err(Error::Module(format!(
+                    "rowid column {col} is not an integer"
+                ))),
+            },
+            None => Ok(self.pos as _),
+        }
+    }
+}
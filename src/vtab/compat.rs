@@ -0,0 +1,54 @@
+//! Adapters for porting virtual tables written against older versions of this crate.
+//!
+//! Older versions of the [UpdateVTab] trait had separate methods for each kind of change.
+//! Implementing [SplitUpdateVTab] instead of [UpdateVTab] allows those virtual tables to be
+//! ported without restructuring them: a blanket implementation of [UpdateVTab] inspects
+//! [ChangeInfo::change_type] and calls the matching method.
+//!
+//! The arguments map to [ChangeInfo] as follows:
+//!
+//! | Change | Method | `rowid` | `args` |
+//! |--------|--------|---------|--------|
+//! | INSERT | [insert](SplitUpdateVTab::insert) | - | [ChangeInfo::args] |
+//! | UPDATE | [update_row](SplitUpdateVTab::update_row) | [ChangeInfo::rowid] | [ChangeInfo::args] |
+//! | DELETE | [delete](SplitUpdateVTab::delete) | [ChangeInfo::rowid] | - |
+//!
+//! In both cases, `args[0]` is the new rowid (or NULL) and `args[1..]` are the column
+//! values in the order declared in the schema.
+use super::*;
+
+/// A virtual table that supports INSERT/UPDATE/DELETE using a separate method for each kind
+/// of change.
+///
+/// Every type implementing this trait implements [UpdateVTab]. See [the module
+/// documentation](self) for how the arguments correspond to [ChangeInfo].
+pub trait SplitUpdateVTab<'vtab>: VTab<'vtab> {
+    /// Insert a new row. The first element of args is the rowid of the new row, and the
+    /// remaining elements are the values of the columns.
+    ///
+    /// If the table has rowids and the provided rowid is NULL, the virtual table must
+    /// generate and return a rowid for the inserted row. Otherwise, the returned value is
+    /// ignored.
+    fn insert(&'vtab self, args: &mut [&mut ValueRef]) -> Result<i64>;
+
+    /// Update the row identified by rowid. The first element of args is the new rowid of
+    /// the row, which may be the same as the old rowid, and the remaining elements are the
+    /// values of the columns.
+    fn update_row(&'vtab self, rowid: &mut ValueRef, args: &mut [&mut ValueRef]) -> Result<()>;
+
+    /// Delete the row identified by rowid.
+    fn delete(&'vtab self, rowid: &mut ValueRef) -> Result<()>;
+}
+
+impl<'vtab, T: SplitUpdateVTab<'vtab>> UpdateVTab<'vtab> for T {
+    fn update(&'vtab self, info: &mut ChangeInfo) -> Result<i64> {
+        match info.change_type() {
+            ChangeType::Insert => self.insert(info.args_mut()),
+            ChangeType::Update => {
+                let (rowid, args) = info.rowid_and_args_mut();
+                self.update_row(rowid, args).map(|_| 0)
+            }
+            ChangeType::Delete => self.delete(info.rowid_mut()).map(|_| 0),
+        }
+    }
+}
@@ -2,6 +2,7 @@ use super::{
     super::{
         ffi,
         function::{Context, InternalContext},
+        sql::ident_eq,
         types::*,
         value::*,
     },
@@ -21,14 +22,32 @@ type CFunc = unsafe extern "C" fn(*mut ffi::sqlite3_context, c_int, *mut *mut ff
 ///
 /// This object is responsible for storing the data associated with overloaded functions. All
 /// functions stored in the list must last for the entire lifetime of the virtual table.
+///
+/// # Lifecycle
+///
+/// The list is owned by a single virtual table instance, so the overloads are usually
+/// registered in [VTab::connect] (and [CreateVTab::create](super::CreateVTab::create)). When
+/// SQLite disconnects the virtual table, for example after a schema change or an ALTER TABLE
+/// RENAME, the instance and its list are dropped, and the next connection registers a fresh
+/// list. Overloads which depend on the configuration of the virtual table (such as a locale
+/// passed as an argument to CREATE VIRTUAL TABLE) should store that configuration on the
+/// virtual table and use [add_method](Self::add_method) to access it.
+///
+/// SQLite caches the function pointer returned from xFindFunction in each prepared statement
+/// which uses it. Prepared statements also hold a reference to the virtual table itself, so
+/// the virtual table will not be disconnected while such a statement exists. For the same
+/// reason, [clear](Self::clear) does not free the functions it removes: they remain valid
+/// until the virtual table is dropped.
 pub struct VTabFunctionList<'vtab, T: VTab<'vtab>> {
     list: RefCell<Vec<Pin<Box<VTabFunction<'vtab, T>>>>>,
+    retired: RefCell<Vec<Pin<Box<VTabFunction<'vtab, T>>>>>,
 }
 
 impl<'vtab, T: VTab<'vtab>> Default for VTabFunctionList<'vtab, T> {
     fn default() -> Self {
         Self {
             list: RefCell::new(Vec::new()),
+            retired: RefCell::new(Vec::new()),
         }
     }
 }
@@ -45,16 +64,32 @@ impl<'vtab, T: VTab<'vtab> + 'vtab> VTabFunctionList<'vtab, T> {
         if let Some(c) = &constraint {
             c.assert_valid_function_constraint();
         }
-        self.list
-            .borrow_mut()
-            .push(VTabFunction::new(n_args, name, constraint, func));
+        let func = VTabFunction::new(n_args, name, constraint, func);
+        let mut list = self.list.borrow_mut();
+        match list
+            .iter_mut()
+            .find(|f| f.n_args == n_args && ident_eq(&f.name, &func.name))
+        {
+            Some(existing) => {
+                let old = std::mem::replace(existing, func);
+                self.retired.borrow_mut().push(old);
+            }
+            None => list.push(func),
+        }
     }
 
     /// Add a scalar function to the list.
     ///
     /// This method adds a function with the given name and n_args to the list of
-    /// overloaded functions. Note that when looking for applicable overloads, a function
-    /// with the correct n_args value will be selected before a function with n_args of -1.
+    /// overloaded functions, replacing any function already in the list with the same name
+    /// and n_args. Names are compared using [ident_eq].
+    ///
+    /// An n_args of -1 matches calls with any number of arguments. When looking for
+    /// applicable overloads, a function with the correct n_args value will be selected
+    /// before a function with n_args of -1. Note that SQLite only asks the virtual table to
+    /// overload a function if a function with the same name and number of arguments exists,
+    /// for example one created by
+    /// [create_overloaded_function](crate::Connection::create_overloaded_function).
     ///
     /// A constraint may be provided. If it is, then the constraint will be provided as an
     /// [IndexInfoConstraint](super::index_info::IndexInfoConstraint) to [VTab::best_index].
@@ -91,6 +126,47 @@ impl<'vtab, T: VTab<'vtab> + 'vtab> VTabFunctionList<'vtab, T> {
         self._add(n_args, name, constraint, func);
     }
 
+    /// Remove the function with the given name and n_args from the list. Returns false if
+    /// there was no such function. Calls with that number of arguments will use the function
+    /// with n_args of -1, if there is one.
+    ///
+    /// As with [clear](Self::clear), the removed function is retained until the virtual
+    /// table is dropped.
+    pub fn remove(&self, n_args: i32, name: &str) -> bool {
+        let mut list = self.list.borrow_mut();
+        match list
+            .iter()
+            .position(|f| f.n_args == n_args && ident_eq(&f.name, name))
+        {
+            Some(idx) => {
+                self.retired.borrow_mut().push(list.remove(idx));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the n_args, name, and constraint of each function in the list, in the order
+    /// they were added. This is intended for debugging.
+    pub fn iter(&self) -> impl Iterator<Item = (i32, String, Option<ConstraintOp>)> {
+        let list = self.list.borrow();
+        let entries: Vec<_> = list
+            .iter()
+            .map(|f| (f.n_args, f.name.to_string(), f.constraint))
+            .collect();
+        entries.into_iter()
+    }
+
+    /// Remove all functions from the list.
+    ///
+    /// Statements which have already been prepared may continue to use the removed
+    /// functions, so they are retained until the virtual table is dropped. Statements
+    /// prepared afterwards will only see functions added after this call.
+    pub fn clear(&self) {
+        let mut list = self.list.borrow_mut();
+        self.retired.borrow_mut().append(&mut list);
+    }
+
     /// Find the best overridden implementation of a function in this list. Prefer a
     /// precise number of arguments, but fall back to overloads which accept any number of
     /// arguments.
@@ -103,9 +179,10 @@ impl<'vtab, T: VTab<'vtab> + 'vtab> VTabFunctionList<'vtab, T> {
         name: &str,
     ) -> Option<((CFunc, *mut ::std::os::raw::c_void), Option<ConstraintOp>)> {
         let list = self.list.borrow();
-        let found = [n_args, -1]
-            .into_iter()
-            .find_map(|n_args| list.iter().find(|f| f.n_args == n_args && f.name == name));
+        let found = [n_args, -1].into_iter().find_map(|n_args| {
+            list.iter()
+                .find(|f| f.n_args == n_args && ident_eq(&f.name, name))
+        });
         found.map(|r| (r.bind(vtab), r.constraint))
     }
 }
@@ -1,5 +1,34 @@
+use super::DeclaredSchema;
 use crate::{ffi, sqlite3_match_version, sqlite3_require_version, types::*, value::*};
-use std::{ffi::CStr, ptr};
+use bitflags::bitflags;
+use std::{cell::Cell, ffi::CStr, os::raw::c_int, ptr, str::FromStr};
+
+thread_local! {
+    /// The schema of the virtual table whose best_index is running. See
+    /// [IndexInfo::order_by_with_collation].
+    static SCHEMA: Cell<*const DeclaredSchema> = const { Cell::new(ptr::null()) };
+}
+
+/// Run the function with the schema of the virtual table available to
+/// [IndexInfo::order_by_with_collation].
+pub(crate) fn with_declared_schema<R>(schema: &DeclaredSchema, f: impl FnOnce() -> R) -> R {
+    let prev = SCHEMA.with(|s| s.replace(schema));
+    let ret = f();
+    SCHEMA.with(|s| s.set(prev));
+    ret
+}
+
+bitflags! {
+    /// Flags which describe a query plan. See [IndexInfo::set_scan_flags].
+    #[repr(transparent)]
+    pub struct ScanFlags: c_int {
+        /// The query plan will visit at most a single row.
+        const UNIQUE = ffi::SQLITE_INDEX_SCAN_UNIQUE;
+        /// The query plan should be displayed with hexadecimal notation in EXPLAIN QUERY
+        /// PLAN. Requires SQLite 3.47.0; earlier versions ignore this flag.
+        const HEX = 2;
+    }
+}
 
 /// Information about a query plan.
 ///
@@ -65,7 +94,9 @@ impl IndexInfo {
     /// Set the index string of this query plan. This is an arbitrary value which will be
     /// passed to [VTabCursor::filter](super::VTabCursor::filter).
     ///
-    /// This function can fail if SQLite is not able to allocate memory for the string.
+    /// The string is copied into memory allocated by SQLite, so an empty string is passed
+    /// to filter as `Some("")`. This function can fail if SQLite is not able to allocate
+    /// memory for the string.
     pub fn set_index_str(&mut self, val: Option<&str>) -> Result<()> {
         if self.base.needToFreeIdxStr != 0 {
             unsafe { ffi::sqlite3_free(self.base.idxStr as _) };
@@ -76,7 +107,7 @@ impl IndexInfo {
                 self.base.needToFreeIdxStr = 0;
             }
             Some(x) => {
-                self.base.idxStr = ffi::str_to_sqlite3(x)?;
+                self.base.idxStr = SqliteBuffer::from_str(x)?.into_raw() as _;
                 self.base.needToFreeIdxStr = 1;
             }
         }
@@ -92,6 +123,45 @@ impl IndexInfo {
         self.base.needToFreeIdxStr = 0;
     }
 
+    /// Returns each term of the [ORDER BY](Self::order_by) clause, along with the
+    /// collating sequence of the column it sorts by.
+    ///
+    /// SQLite does not pass the collating sequence of ORDER BY terms to virtual tables.
+    /// Instead, it only passes the ORDER BY clause if every term uses the collating sequence
+    /// declared for its column, so this is the collating sequence given to COLLATE in the
+    /// schema returned from [VTab::connect](super::VTab::connect), or "BINARY" if none was
+    /// given. Terms which sort by the rowid, or by a column which could not be found in the
+    /// schema, have no collating sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::{vtab::*, *};
+    ///
+    /// /// Consume the ORDER BY clause if the table can produce rows in that order.
+    /// fn consume_order_by(index_info: &mut IndexInfo) {
+    ///     let sorted = index_info
+    ///         .order_by_with_collation()
+    ///         .iter()
+    ///         .all(|(o, coll)| o.column() == 0 && !o.desc() && coll.as_deref() == Some("NOCASE"));
+    ///     index_info.set_order_by_consumed(sorted);
+    /// }
+    /// ```
+    pub fn order_by_with_collation(&self) -> Vec<(IndexInfoOrderBy<'_>, Option<String>)> {
+        // The schema is only set while best_index is running, which is the only time an
+        // IndexInfo is available.
+        let schema = unsafe { SCHEMA.with(|s| s.get()).as_ref() };
+        self.order_by()
+            .map(|o| {
+                let column = usize::try_from(o.column())
+                    .ok()
+                    .and_then(|i| schema?.columns.get(i));
+                let collation = column.map(|c| c.collation().unwrap_or("BINARY").to_owned());
+                (o, collation)
+            })
+            .collect()
+    }
+
     /// Retrieve the value previously set by
     /// [set_order_by_consumed](Self::set_order_by_consumed).
     pub fn order_by_consumed(&self) -> bool {
@@ -136,16 +206,34 @@ impl IndexInfo {
     }
 
     /// Retrieve the value previously set by
-    /// [set_scan_flags](Self::set_scan_flags).
+    /// [set_scan_flags](Self::set_scan_flags). Unknown flags are ignored; use
+    /// [scan_flags_raw](Self::scan_flags_raw) to retrieve them.
     ///
     /// Requires SQLite 3.9.0.
-    pub fn scan_flags(&self) -> Result<usize> {
+    pub fn scan_flags(&self) -> Result<ScanFlags> {
+        self.scan_flags_raw()
+            .map(|x| ScanFlags::from_bits_truncate(x as _))
+    }
+
+    /// Requires SQLite 3.9.0. On earlier versions of SQLite, this function is a harmless
+    /// no-op.
+    pub fn set_scan_flags(&mut self, val: ScanFlags) {
+        self.set_scan_flags_raw(val.bits() as _)
+    }
+
+    /// Retrieve the raw value previously set by
+    /// [set_scan_flags_raw](Self::set_scan_flags_raw).
+    ///
+    /// Requires SQLite 3.9.0.
+    pub fn scan_flags_raw(&self) -> Result<usize> {
         sqlite3_require_version!(3_009_000, Ok(self.base.idxFlags as _))
     }
 
+    /// Set the scan flags to a raw value, which may contain flags unknown to [ScanFlags].
+    ///
     /// Requires SQLite 3.9.0. On earlier versions of SQLite, this function is a harmless
     /// no-op.
-    pub fn set_scan_flags(&mut self, val: usize) -> () {
+    pub fn set_scan_flags_raw(&mut self, val: usize) {
         let _ = val;
         sqlite3_match_version! {
             3_009_000 => self.base.idxFlags = val as _,
@@ -153,10 +241,298 @@ impl IndexInfo {
         }
     }
 
+    /// Indicate that the query plan will visit at most a single row. This is equivalent to
+    /// adding [ScanFlags::UNIQUE] to the [scan flags](Self::set_scan_flags).
+    ///
+    /// This should only be used when the plan uses an equality constraint on a unique
+    /// column. In debug builds, this method panics if no equality constraint has been
+    /// assigned an [argv_index](IndexInfoConstraint::set_argv_index).
+    ///
+    /// Requires SQLite 3.9.0. On earlier versions of SQLite, this function is a harmless
+    /// no-op.
+    pub fn set_unique_scan(&mut self) {
+        debug_assert!(
+            self.constraints()
+                .any(|c| c.op() == ConstraintOp::Eq && c.argv_index().is_some()),
+            "set_unique_scan requires an equality constraint to be used"
+        );
+        let flags = self.scan_flags_raw().unwrap_or(0);
+        self.set_scan_flags_raw(flags | ScanFlags::UNIQUE.bits() as usize);
+    }
+
     /// Requires SQLite 3.10.0.
     pub fn columns_used(&self) -> Result<u64> {
         sqlite3_require_version!(3_010_000, Ok(self.base.colUsed))
     }
+
+    /// Returns the [ConstraintOp::Limit] constraint, if the query has a LIMIT clause which
+    /// SQLite is offering to the virtual table.
+    ///
+    /// Requires SQLite 3.38.0. On earlier versions, this method always returns None.
+    pub fn limit_constraint(&self) -> Option<IndexInfoConstraint<'_>> {
+        self.constraints().find(|c| c.op() == ConstraintOp::Limit)
+    }
+
+    /// Returns the [ConstraintOp::Offset] constraint, if the query has an OFFSET clause
+    /// which SQLite is offering to the virtual table.
+    ///
+    /// Requires SQLite 3.38.0. On earlier versions, this method always returns None.
+    pub fn offset_constraint(&self) -> Option<IndexInfoConstraint<'_>> {
+        self.constraints().find(|c| c.op() == ConstraintOp::Offset)
+    }
+
+    /// Claim the LIMIT and OFFSET constraints, so that the virtual table can stop producing
+    /// rows once the limit is reached. This method should be called after assigning argv
+    /// indexes to all other constraints.
+    ///
+    /// SQLite rejects a query plan which uses LIMIT or OFFSET unless every other
+    /// constraint is also used, so nothing is claimed if any other constraint has no argv
+    /// index. Otherwise, the LIMIT and OFFSET values are assigned the next argv indexes,
+    /// so they are passed as the last arguments to
+    /// [VTabCursor::filter](super::VTabCursor::filter), and both are marked as omitted.
+    /// The cursor must then skip the first OFFSET rows itself, and produce at most LIMIT
+    /// rows after that. A negative LIMIT means there is no limit.
+    ///
+    /// The returned value holds the argv indexes which were assigned. The virtual table
+    /// typically records this in the [index_num](Self::set_index_num) so that filter knows
+    /// which arguments it received.
+    ///
+    /// Requires SQLite 3.38.0. On earlier versions, this method never claims anything.
+    pub fn consume_limit_offset(&mut self) -> LimitOffset {
+        let mut ret = LimitOffset::default();
+        let is_limit =
+            |c: &IndexInfoConstraint| matches!(c.op(), ConstraintOp::Limit | ConstraintOp::Offset);
+        if self
+            .constraints()
+            .any(|c| !is_limit(&c) && c.argv_index().is_none())
+        {
+            return ret;
+        }
+        let limit = self.limit_constraint();
+        let offset = self.offset_constraint();
+        // Claiming only the LIMIT would cause SQLite to apply the OFFSET to the already
+        // limited rows, so claim both or neither.
+        if limit.iter().chain(offset.iter()).any(|c| !c.usable()) {
+            return ret;
+        }
+        let mut next = self.next_argv_index();
+        for (c, dest) in [(limit, &mut ret.limit), (offset, &mut ret.offset)] {
+            if let Some(mut c) = c {
+                c.set_argv_index(Some(next));
+                c.set_omit(true);
+                *dest = Some(next);
+                next += 1;
+            }
+        }
+        ret
+    }
+
+    /// Claim a usable constraint with the given column and operator, which has not already
+    /// been assigned an argv index. The constraint is assigned the next argv index and
+    /// marked as omitted, and the argv index is returned. Returns None if there is no such
+    /// constraint.
+    ///
+    /// The column can be given as an `i32` or, for columns declared with
+    /// [VTabColumns](super::VTabColumns), as the column itself.
+    pub fn claim(&mut self, column: impl Into<i32>, op: ConstraintOp) -> Option<u32> {
+        let column = column.into();
+        let next = self.next_argv_index();
+        let mut c = self.constraints().find(|c| {
+            c.column() == column && c.op() == op && c.usable() && c.argv_index().is_none()
+        })?;
+        c.set_argv_index(Some(next));
+        c.set_omit(true);
+        Some(next)
+    }
+
+    /// Returns the argv index following the largest one assigned so far.
+    fn next_argv_index(&self) -> u32 {
+        self.constraints()
+            .filter_map(|c| c.argv_index())
+            .max()
+            .map_or(0, |x| x + 1)
+    }
+}
+
+/// The LIMIT and OFFSET constraints claimed by [IndexInfo::consume_limit_offset].
+#[derive(Debug, Default, Eq, PartialEq, Clone, Copy)]
+pub struct LimitOffset {
+    /// The argv index assigned to the LIMIT value, if it was claimed.
+    pub limit: Option<u32>,
+    /// The argv index assigned to the OFFSET value, if it was claimed.
+    pub offset: Option<u32>,
+}
+
+/// Builds a query plan in [VTab::best_index](super::VTab::best_index) from a list of
+/// constraints that the virtual table knows how to handle.
+///
+/// Each call to [require](Self::require) or [optional](Self::optional) declares a slot,
+/// numbered from 0 in the order the calls are made. [finish](Self::finish) records which
+/// slots were claimed in the [index_num](IndexInfo::set_index_num) and
+/// [index_str](IndexInfo::set_index_str), and [Plan::decode] recovers them in
+/// [VTabCursor::filter](super::VTabCursor::filter). The virtual table must declare the
+/// same slots in the same order every time, since the slot numbers are what connects
+/// best_index to filter.
+///
+/// Constraints may also be claimed directly with [IndexInfo::claim] before or after using a
+/// PlanBuilder. The argv index of each slot is recorded in the index_str, so such claims do
+/// not disturb the plan.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sqlite3_ext::{vtab::*, *};
+///
+/// const EQ_A: usize = 0;
+/// const MIN_B: usize = 1;
+///
+/// fn best_index(index_info: &mut IndexInfo) -> Result<()> {
+///     let mut plan = PlanBuilder::new(index_info);
+///     plan.optional(0, &[ConstraintOp::Eq]);
+///     plan.optional(1, &[ConstraintOp::GT, ConstraintOp::GE]);
+///     plan.finish()?;
+///     Ok(())
+/// }
+///
+/// fn filter(index_num: i32, index_str: Option<&str>, args: &mut [&mut ValueRef]) -> Result<()> {
+///     let plan = Plan::decode(index_num, index_str)?;
+///     if let Some(c) = plan.get(EQ_A) {
+///         println!("a = {}", args[c.argv].get_i64());
+///     }
+///     if let Some(c) = plan.get(MIN_B) {
+///         println!("b {:?} {}", c.op, args[c.argv].get_i64());
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct PlanBuilder<'a> {
+    index_info: &'a mut IndexInfo,
+    slots: Vec<Option<Claim>>,
+}
+
+impl<'a> PlanBuilder<'a> {
+    /// Start building a query plan.
+    pub fn new(index_info: &'a mut IndexInfo) -> Self {
+        Self {
+            index_info,
+            slots: vec![],
+        }
+    }
+
+    /// Returns the IndexInfo, for example to set the estimated cost.
+    pub fn index_info(&mut self) -> &mut IndexInfo {
+        self.index_info
+    }
+
+    /// Declare a slot which claims a usable constraint on the column with any of the given
+    /// operators, if there is one. Returns true if a constraint was claimed.
+    pub fn optional(&mut self, column: impl Into<i32>, ops: &[ConstraintOp]) -> bool {
+        let column = column.into();
+        let claim = ops.iter().find_map(|&op| {
+            self.index_info.claim(column, op).map(|argv| Claim {
+                column,
+                op,
+                argv: argv as _,
+            })
+        });
+        self.slots.push(claim);
+        claim.is_some()
+    }
+
+    /// Declare a slot which must claim a usable constraint on the column with any of the
+    /// given operators. Fails with [SQLITE_CONSTRAINT] if there is no such constraint,
+    /// which tells SQLite that this query plan cannot be used.
+    pub fn require(&mut self, column: impl Into<i32>, ops: &[ConstraintOp]) -> Result<()> {
+        match self.optional(column, ops) {
+            true => Ok(()),
+            false => Err(SQLITE_CONSTRAINT),
+        }
+    }
+
+    /// Record the query plan in the IndexInfo, and return the index_num that was set.
+    ///
+    /// Fails with [SQLITE_MISUSE] if more than 31 slots were declared.
+    pub fn finish(self) -> Result<i32> {
+        if self.slots.len() > 31 {
+            return Err(SQLITE_MISUSE);
+        }
+        let mut index_num = 0;
+        let mut index_str = vec![];
+        for (i, c) in self.slots.iter().enumerate() {
+            if let Some(c) = c {
+                index_num |= 1 << i;
+                index_str.push(format!("{}:{}:{}", c.column, c.op.to_sqlite(), c.argv));
+            }
+        }
+        self.index_info.set_index_num(index_num);
+        self.index_info.set_index_str(Some(&index_str.join(",")))?;
+        Ok(index_num)
+    }
+}
+
+/// A query plan created with [PlanBuilder], as received by
+/// [VTabCursor::filter](super::VTabCursor::filter).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Plan {
+    slots: Vec<Option<Claim>>,
+}
+
+impl Plan {
+    /// Recover the query plan from the index_num and index_str passed to filter.
+    ///
+    /// Fails with [Error::Module] if the values were not created by [PlanBuilder::finish].
+    pub fn decode(index_num: i32, index_str: Option<&str>) -> Result<Plan> {
+        let invalid = || Error::Module(format!("invalid query plan: {index_num} {index_str:?}"));
+        let mut claims = index_str
+            .ok_or_else(invalid)?
+            .split(',')
+            .filter(|x| !x.is_empty());
+        let mut slots = vec![];
+        for i in 0..31 {
+            if index_num >> i == 0 {
+                break;
+            }
+            if index_num & (1 << i) == 0 {
+                slots.push(None);
+                continue;
+            }
+            let parts: Vec<&str> = claims.next().ok_or_else(invalid)?.split(':').collect();
+            let (column, op, argv) = match parts[..] {
+                [column, op, argv] => (column, op, argv),
+                _ => return Err(invalid()),
+            };
+            let column = column.parse().map_err(|_| invalid())?;
+            let op = op
+                .parse()
+                .ok()
+                .and_then(ConstraintOp::try_from_sqlite)
+                .ok_or_else(invalid)?;
+            let argv = argv.parse().map_err(|_| invalid())?;
+            slots.push(Some(Claim { column, op, argv }));
+        }
+        if index_num < 0 || claims.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(Plan { slots })
+    }
+
+    /// Returns the constraint claimed by the given slot, or None if the slot did not claim
+    /// a constraint.
+    pub fn get(&self, slot: usize) -> Option<Claim> {
+        self.slots.get(slot).copied().flatten()
+    }
+}
+
+/// A constraint claimed by a [PlanBuilder] slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Claim {
+    /// The column of the constraint.
+    pub column: i32,
+    /// The operator of the constraint.
+    pub op: ConstraintOp,
+    /// The index of the constraint's value in the args passed to
+    /// [VTabCursor::filter](super::VTabCursor::filter).
+    pub argv: usize,
 }
 
 #[derive(Copy, Clone)]
@@ -448,7 +824,11 @@ impl ConstraintOp {
     }
 
     fn from_sqlite(val: u8) -> ConstraintOp {
-        match val as _ {
+        Self::try_from_sqlite(val).expect("invalid constraint op")
+    }
+
+    pub(super) fn try_from_sqlite(val: u8) -> Option<ConstraintOp> {
+        Some(match val as _ {
             2 => ConstraintOp::Eq,
             4 => ConstraintOp::GT,
             8 => ConstraintOp::LE,
@@ -466,7 +846,29 @@ impl ConstraintOp {
             73 => ConstraintOp::Limit,
             74 => ConstraintOp::Offset,
             150..=255 => ConstraintOp::Function(val),
-            _ => panic!("invalid constraint op"),
+            _ => return None,
+        })
+    }
+
+    pub(super) fn to_sqlite(self) -> u8 {
+        match self {
+            ConstraintOp::Eq => 2,
+            ConstraintOp::GT => 4,
+            ConstraintOp::LE => 8,
+            ConstraintOp::LT => 16,
+            ConstraintOp::GE => 32,
+            ConstraintOp::Match => 64,
+            ConstraintOp::Like => 65,
+            ConstraintOp::Glob => 66,
+            ConstraintOp::Regexp => 67,
+            ConstraintOp::NE => 68,
+            ConstraintOp::IsNot => 69,
+            ConstraintOp::IsNotNull => 70,
+            ConstraintOp::IsNull => 71,
+            ConstraintOp::Is => 72,
+            ConstraintOp::Limit => 73,
+            ConstraintOp::Offset => 74,
+            ConstraintOp::Function(x) => x,
         }
     }
 }
@@ -527,7 +929,9 @@ impl std::fmt::Debug for IndexInfo {
         self.estimated_rows()
             .map(|v| ds.field("estimated_rows", &v))
             .ok();
-        self.scan_flags().map(|v| ds.field("scan_flags", &v)).ok();
+        self.scan_flags_raw()
+            .map(|v| ds.field("scan_flags", &v))
+            .ok();
         self.columns_used()
             .map(|v| ds.field("columns_used", &v))
             .ok();
@@ -0,0 +1,174 @@
+use super::*;
+use std::cell::OnceCell;
+
+/// A virtual table whose expensive resources are acquired on first use rather than in
+/// [VTab::connect]. Use it with [LazyConnect].
+///
+/// SQLite calls connect whenever it needs the declared schema of the table, which is not
+/// only when the table is queried. For example, an ALTER TABLE on an unrelated table
+/// reparses the schema, and may connect to every virtual table in it. If connect fails,
+/// so does the statement which caused it, even though it never uses the table. SQLite
+/// does not tell a virtual table why it is being connected ([Connection::is_schema_parse]
+/// is only a heuristic), so a virtual table which depends on a resource that might be
+/// unavailable, such as a network service, should keep connect cheap and infallible and
+/// defer acquiring the resource until the table is actually used.
+///
+/// The methods of this trait correspond to those of [VTab], except that resources are
+/// acquired by [initialize](Self::initialize) and passed to [open](Self::open).
+pub trait LazyVTab<'vtab>: Sized + 'vtab {
+    /// See [VTab::Aux].
+    type Aux: 'vtab;
+
+    /// See [VTab::Cursor].
+    type Cursor: VTabCursor + 'vtab;
+
+    /// The resources acquired by [initialize](Self::initialize).
+    type State: 'vtab;
+
+    /// Corresponds to [VTab::connect]. This method should only determine the schema of
+    /// the table, without acquiring any resources.
+    fn connect(
+        db: &'vtab VTabConnection,
+        aux: &'vtab Self::Aux,
+        args: &[&str],
+    ) -> Result<(String, Self)>;
+
+    /// Corresponds to [VTab::best_index]. The state is not available when planning
+    /// queries.
+    fn best_index(&'vtab self, index_info: &mut IndexInfo) -> Result<()>;
+
+    /// Acquire the resources of the virtual table. This method is called the first time a
+    /// cursor is opened or the table is modified. If it fails, the statement fails, and
+    /// the next use of the table calls this method again.
+    fn initialize(&'vtab self) -> Result<Self::State>;
+
+    /// Corresponds to [VTab::open].
+    fn open(&'vtab self, state: &'vtab Self::State) -> Result<Self::Cursor>;
+}
+
+/// A [LazyVTab] that supports CREATE VIRTUAL TABLE. See [CreateVTab].
+pub trait LazyCreateVTab<'vtab>: LazyVTab<'vtab> {
+    /// See [CreateVTab::SHADOW_NAMES].
+    const SHADOW_NAMES: &'static [&'static str] = &[];
+
+    /// See [CreateVTab::is_shadow_name].
+    fn is_shadow_name(suffix: &str) -> bool {
+        Self::SHADOW_NAMES.iter().any(|c| ident_eq(c, suffix))
+    }
+
+    /// Corresponds to [CreateVTab::create].
+    fn create(
+        db: &'vtab VTabConnection,
+        aux: &'vtab Self::Aux,
+        args: &[&str],
+    ) -> Result<(String, Self)>;
+
+    /// Corresponds to [CreateVTab::destroy]. The state is None if the table was never
+    /// used on this connection.
+    fn destroy(&self, state: Option<&Self::State>) -> Result<()>;
+}
+
+/// A [LazyVTab] that supports INSERT/UPDATE/DELETE. See [UpdateVTab].
+pub trait LazyUpdateVTab<'vtab>: LazyVTab<'vtab> {
+    /// Corresponds to [UpdateVTab::update].
+    fn update(&'vtab self, state: &'vtab Self::State, info: &mut ChangeInfo) -> Result<i64>;
+}
+
+/// Adapter which implements [VTab] for a [LazyVTab], initializing its state the first time
+/// it is needed.
+///
+/// The adapter also implements [CreateVTab] and [UpdateVTab] when the wrapped type
+/// implements [LazyCreateVTab] and [LazyUpdateVTab]. Register it with the module type
+/// directly, since the [sqlite3_ext_vtab](sqlite3_ext_macro::sqlite3_ext_vtab) macro cannot
+/// be applied to it.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sqlite3_ext::{vtab::*, *};
+///
+/// fn init<'vtab, T: LazyCreateVTab<'vtab, Aux = ()>>(db: &'vtab Connection) -> Result<()> {
+///     db.create_module("remote", StandardModule::<LazyConnect<T>>::new(), ())
+/// }
+/// ```
+pub struct LazyConnect<'vtab, T: LazyVTab<'vtab>> {
+    inner: T,
+    state: OnceCell<T::State>,
+}
+
+impl<'vtab, T: LazyVTab<'vtab>> LazyConnect<'vtab, T> {
+    fn new(inner: T) -> Self {
+        Self {
+            inner,
+            state: OnceCell::new(),
+        }
+    }
+
+    /// Returns the wrapped virtual table.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns the state of the virtual table, or None if it has not been initialized.
+    pub fn state(&self) -> Option<&T::State> {
+        self.state.get()
+    }
+
+    fn get_or_init(&'vtab self) -> Result<&'vtab T::State> {
+        if let Some(state) = self.state.get() {
+            return Ok(state);
+        }
+        let state = self.inner.initialize()?;
+        Ok(self.state.get_or_init(|| state))
+    }
+}
+
+impl<'vtab, T: LazyVTab<'vtab>> VTab<'vtab> for LazyConnect<'vtab, T> {
+    type Aux = T::Aux;
+    type Cursor = T::Cursor;
+
+    fn connect(
+        db: &'vtab VTabConnection,
+        aux: &'vtab Self::Aux,
+        args: &[&str],
+    ) -> Result<(String, Self)> {
+        T::connect(db, aux, args).map(|(sql, inner)| (sql, Self::new(inner)))
+    }
+
+    fn best_index(&'vtab self, index_info: &mut IndexInfo) -> Result<()> {
+        self.inner.best_index(index_info)
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        self.inner.open(self.get_or_init()?)
+    }
+}
+
+impl<'vtab, T: LazyCreateVTab<'vtab>> CreateVTab<'vtab> for LazyConnect<'vtab, T> {
+    const SHADOW_NAMES: &'static [&'static str] = T::SHADOW_NAMES;
+
+    fn is_shadow_name(suffix: &str) -> bool {
+        T::is_shadow_name(suffix)
+    }
+
+    fn create(
+        db: &'vtab VTabConnection,
+        aux: &'vtab Self::Aux,
+        args: &[&str],
+    ) -> Result<(String, Self)> {
+        T::create(db, aux, args).map(|(sql, inner)| (sql, Self::new(inner)))
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        match self.inner.destroy(self.state.get()) {
+            Ok(()) => Ok(()),
+            Err(e) => Err((self, e)),
+        }
+    }
+}
+
+impl<'vtab, T: LazyUpdateVTab<'vtab>> UpdateVTab<'vtab> for LazyConnect<'vtab, T> {
+    fn update(&'vtab self, info: &mut ChangeInfo) -> Result<i64> {
+        self.inner.update(self.get_or_init()?, info)
+    }
+}
@@ -19,22 +19,68 @@
 //! - [CreateVTab] indicates that the table supports CREATE VIRTUAL TABLE.
 //! - [UpdateVTab] indicates that the table supports INSERT/UPDATE/DELETE.
 //! - [TransactionVTab] indicates that the table supports ROLLBACK.
+//! - [ReadTransactionVTab] indicates that the table holds a guard while it is being read.
 //! - [FindFunctionVTab] indicates that the table overrides certain SQL functions when they
 //!   operate on the table.
 //! - [RenameVTab] indicates that the table supports ALTER TABLE RENAME TO.
+//! - [IntegrityVTab] indicates that the table can be checked by PRAGMA integrity_check.
+//! - [LazyVTab] can be wrapped in [LazyConnect] to defer acquiring resources until the
+//!   table is used.
+//! - [compat::SplitUpdateVTab] implements [UpdateVTab] using separate methods for INSERT,
+//!   UPDATE, and DELETE, like older versions of this crate.
+//! - [array] provides the `rust_array` table-valued function, which allows binding arrays as
+//!   query parameters.
+//! - [series] provides a `generate_series` table-valued function with full query plan
+//!   pushdown.
+//! - [AuxCell] holds state which is shared by every instance of a virtual table, including
+//!   instances on other connections and threads.
+//! - [from_closures] defines a simple virtual table from closures, for prototypes.
+//! - [HasWorkers] indicates that the table owns background [Worker] threads, which are
+//!   stopped when the table is disconnected.
+//! - [testing] records the calls made to a virtual table, for use in tests. It requires the
+//!   `testing` feature.
 
 use super::{
     ffi, function::ToContextResult, sqlite3_match_version, types::*, value::*, Connection,
+    ConnectionId,
 };
+use crate::{connection::quote_identifier, sql::ident_eq};
+pub use aux_cell::*;
+pub use closures::*;
 pub use function::*;
 pub use index_info::*;
+pub use lazy::*;
 pub use module::*;
-use std::{ffi::c_void, ops::Deref, slice};
+pub use rowid_map::*;
+pub use schema_builder::*;
+use std::{
+    cell::{Cell, RefCell},
+    ffi::{c_void, CString},
+    ops::Deref,
+    slice,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, OnceLock,
+    },
+};
+pub use virtual_table::*;
+pub use worker::*;
 
+pub mod array;
+mod aux_cell;
+mod closures;
+pub mod compat;
 mod function;
 mod index_info;
+mod lazy;
 mod module;
+mod rowid_map;
+mod schema_builder;
+pub mod series;
 pub(crate) mod stubs;
+pub mod testing;
+mod virtual_table;
+mod worker;
 
 pub type DisconnectResult<T> = std::result::Result<(), (T, Error)>;
 
@@ -48,6 +94,12 @@ pub trait VTab<'vtab>: Sized {
     /// When registering the module with [Connection::create_module], additional data can
     /// be passed as a parameter. This data will be passed to [connect](VTab::connect) and
     /// [create](CreateVTab::create). It can be used for any purpose.
+    ///
+    /// Each call to [Connection::create_module] takes ownership of its own Aux data, which
+    /// is only used by that connection, so no `Send` or `Sync` bound is required. To share
+    /// mutable state between several connections, possibly on different threads, pass a
+    /// clone of an [AuxCell] to each of them. To use the same Aux data for several modules
+    /// without requiring it to be Clone, register them with [Connection::create_module_arc].
     type Aux: 'vtab;
 
     /// Cursor implementation for this virtual table.
@@ -63,8 +115,17 @@ pub trait VTab<'vtab>: Sized {
     /// configured table instance. Additionally, all virtual tables are recommended to set
     /// a risk level using [VTabConnection::set_risk_level].
     ///
-    /// The virtual table implementation will return an error if any of the arguments
-    /// contain invalid UTF-8.
+    /// If any of the arguments contain invalid UTF-8, the virtual table fails with an error
+    /// identifying the argument and the byte offset of the invalid data, without calling this
+    /// method. Modules created with [with_lossy_args](Module::with_lossy_args) instead
+    /// receive the arguments with invalid sequences replaced by U+FFFD, and can use
+    /// [VTabConnection::args_replaced] to detect this.
+    ///
+    /// SQLite may call this method for statements which do not use the virtual table, for
+    /// example when ALTER TABLE renames another table and checks the views which refer to
+    /// this one. An error returned from this method fails those statements as well. Virtual
+    /// tables which depend on resources that may be unavailable can use [LazyConnect] to
+    /// defer acquiring them until the table is queried.
     fn connect(
         db: &'vtab VTabConnection,
         aux: &'vtab Self::Aux,
@@ -106,11 +167,32 @@ pub trait CreateVTab<'vtab>: VTab<'vtab> {
     /// understand that "vtab_data" is a shadow table for a table named "vtab" created with
     /// this module.
     ///
+    /// SQLite splits the name of a candidate shadow table at its last underscore, so a
+    /// table named "a_b" can have a shadow table named "a_b_data", but a shadow name which
+    /// itself contains an underscore never matches. Shadow names are compared using
+    /// [ident_eq](crate::sql::ident_eq), so "vtab_DATA" is also a shadow table.
+    ///
     /// Shadow tables are read-only if the database has SQLITE_DBCONFIG_DEFENSIVE set, and
     /// SQLite is version 3.26.0 or greater. For more information, see [the SQLite
     /// documentation](https://www.sqlite.org/vtab.html#the_xshadowname_method).
     const SHADOW_NAMES: &'static [&'static str] = &[];
 
+    /// Corresponds to xShadowName.
+    ///
+    /// Returns true if a table whose name is the name of a virtual table created with this
+    /// module, followed by an underscore and `suffix`, is a shadow table of that virtual
+    /// table. The default implementation checks whether `suffix` appears in
+    /// [SHADOW_NAMES](Self::SHADOW_NAMES). Override this method if the shadow table names
+    /// are computed, for example "idx_1", "idx_2", and so on.
+    ///
+    /// When checking a new table name, SQLite only considers the part after the last
+    /// underscore, so a suffix containing an underscore only matches while SQLite is
+    /// marking the existing tables of the schema, which happens when the virtual table is
+    /// created and when the schema is loaded.
+    fn is_shadow_name(suffix: &str) -> bool {
+        Self::SHADOW_NAMES.iter().any(|c| ident_eq(c, suffix))
+    }
+
     /// Corresponds to xCreate.
     ///
     /// This method is invoked when a CREATE VIRTUAL TABLE statement is invoked on the
@@ -131,21 +213,58 @@ pub trait CreateVTab<'vtab>: VTab<'vtab> {
 }
 
 /// A virtual table that supports INSERT/UPDATE/DELETE.
+///
+/// Implementations must provide [update](Self::update). SQLite calls
+/// [update2](Self::update2), which reports the result of the change more precisely, and whose
+/// default implementation calls update. Virtual tables which override update2 must still
+/// provide update, but may implement it by converting the result of update2. In a future
+/// version, update2 will become the required method and update will be removed.
 pub trait UpdateVTab<'vtab>: VTab<'vtab> {
     /// Modify a single row in the virtual table. The info parameter may be used to
     /// determine the type of change being performed by this update.
     ///
-    /// If the change is an INSERT for a table with rowids and the provided rowid was NULL,
-    /// then the virtual table must generate and return a rowid for the inserted row. In
-    /// all other cases, the returned Ok value of this method is ignored.
+    /// If the change is an INSERT for a table with rowids, the virtual table should return
+    /// [UpdateResult::Inserted] with the rowid of the new row, which SQLite reports as the
+    /// [last_insert_rowid](Connection::last_insert_rowid). If the provided rowid was NULL,
+    /// the virtual table must generate this rowid. Other changes should return
+    /// [UpdateResult::Done].
     ///
     /// It isn't possible to provide a mutable reference to the virtual table
     /// implementation because there may be active cursors affecting the table or even the
     /// row that is being updated. Use Rust's interior mutability types to properly
     /// implement this method.
+    ///
+    /// The default implementation calls [update](Self::update), and returns
+    /// [UpdateResult::Inserted] with its return value for an INSERT.
+    fn update2(&'vtab self, info: &mut ChangeInfo) -> Result<UpdateResult> {
+        let rowid = self.update(info)?;
+        Ok(match info.change_type() {
+            ChangeType::Insert => UpdateResult::Inserted { rowid },
+            _ => UpdateResult::Done,
+        })
+    }
+
+    /// Modify a single row in the virtual table. This is the older form of
+    /// [update2](Self::update2).
+    ///
+    /// If the change is an INSERT for a table with rowids and the provided rowid was NULL,
+    /// then the virtual table must generate and return a rowid for the inserted row. In
+    /// all other cases, the returned Ok value of this method is ignored.
     fn update(&'vtab self, info: &mut ChangeInfo) -> Result<i64>;
 }
 
+/// The result of [UpdateVTab::update2].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateResult {
+    /// A row was inserted with the given rowid.
+    Inserted {
+        /// The rowid of the new row.
+        rowid: i64,
+    },
+    /// The change was made, and there is no rowid to report.
+    Done,
+}
+
 /// A virtual table that supports ROLLBACK.
 ///
 /// See [VTabTransaction] for details.
@@ -156,6 +275,35 @@ pub trait TransactionVTab<'vtab>: UpdateVTab<'vtab> {
     fn begin(&'vtab self) -> Result<Self::Transaction>;
 }
 
+/// A virtual table that holds a guard, such as a snapshot of an external data source, while it
+/// is being read.
+///
+/// SQLite only calls xBegin, xCommit, and xRollback on virtual tables which are written to, so
+/// [TransactionVTab] cannot be used to keep the results of a read-only virtual table
+/// consistent. Instead, modules registered with this trait track the cursors opened on the
+/// table:
+///
+/// - [begin_read](Self::begin_read) is called when the first cursor is opened.
+/// - In autocommit mode, the guard is dropped when the last cursor is closed, so every
+///   statement reading the table receives its own guard. Statements which use the table
+///   several times, such as self-joins, share a single guard.
+/// - Inside an explicit transaction, the guard is kept across statements. SQLite does not
+///   notify virtual tables when a read-only transaction ends, so the guard is dropped the next
+///   time the table is opened in autocommit mode, or when the table is disconnected. A
+///   transaction which immediately follows another one without reading the table in
+///   autocommit mode in between will reuse the same guard.
+///
+/// A virtual table may implement both this trait and [TransactionVTab]. The two are
+/// independent: writes are still reported through [VTabTransaction].
+pub trait ReadTransactionVTab<'vtab>: VTab<'vtab> {
+    /// The guard which is held while the table is being read. It is dropped to end the read
+    /// transaction.
+    type ReadGuard: 'vtab;
+
+    /// Begin a read transaction.
+    fn begin_read(&'vtab self) -> Result<Self::ReadGuard>;
+}
+
 /// A virtual table that overloads some functions.
 ///
 /// A virtual table implementation may choose to overload certain functions when the first
@@ -211,11 +359,65 @@ pub trait FindFunctionVTab<'vtab>: VTab<'vtab> {
 }
 
 /// A virtual table that supports ALTER TABLE RENAME.
+///
+/// # Shadow tables
+///
+/// SQLite does not rename the shadow tables of a virtual table (see
+/// [CreateVTab::SHADOW_NAMES]) on its own, in any version, regardless of the
+/// legacy_alter_table setting. A virtual table which stores its data in shadow tables must
+/// rename them in [rename](Self::rename), which is most easily done with
+/// [rename_shadow_tables] using the connection passed to [VTab::connect] and
+/// [CreateVTab::create]. The ALTER TABLE statements are run as part of the ALTER TABLE
+/// statement which renamed the virtual table, so they are permitted even when
+/// SQLITE_DBCONFIG_DEFENSIVE makes the shadow tables read-only.
+///
+/// Beginning with SQLite 3.25.0, unless legacy_alter_table is enabled, ALTER TABLE also
+/// updates references to the renamed table in triggers and views. This applies both to the
+/// virtual table and to shadow tables renamed this way.
 pub trait RenameVTab<'vtab>: VTab<'vtab> {
-    /// Corresponds to xRename, when ALTER TABLE RENAME is run on the virtual table. If
-    /// this method returns Ok, then SQLite will disconnect this virtual table
-    /// implementation and connect to a new implementation with the updated name.
-    fn rename(&'vtab self, name: &str) -> Result<()>;
+    /// Corresponds to xRename, when ALTER TABLE RENAME is run on the virtual table. The
+    /// schema parameter is the name of the database containing the table (such as "main"),
+    /// from is the current name of the table, and to is the new name. If this method
+    /// returns Ok, then SQLite will disconnect this virtual table implementation and connect
+    /// to a new implementation with the updated name.
+    fn rename(&'vtab self, schema: &str, from: &str, to: &str) -> Result<()>;
+}
+
+/// Rename the shadow tables of a virtual table from `{from}_{suffix}` to `{to}_{suffix}`,
+/// for each of the given suffixes, in the database named by schema. This is meant to be
+/// called from [RenameVTab::rename] with its arguments, usually with
+/// [CreateVTab::SHADOW_NAMES] as the suffixes. The table names are quoted, so they may
+/// contain any characters.
+pub fn rename_shadow_tables(
+    db: &Connection,
+    schema: &str,
+    suffixes: &[&str],
+    from: &str,
+    to: &str,
+) -> Result<()> {
+    for suffix in suffixes {
+        let sql = format!(
+            "ALTER TABLE {}.{} RENAME TO {}",
+            quote_identifier(schema),
+            quote_identifier(&format!("{from}_{suffix}")),
+            quote_identifier(&format!("{to}_{suffix}")),
+        );
+        db.execute(&sql, ())?;
+    }
+    Ok(())
+}
+
+/// A virtual table that participates in PRAGMA integrity_check.
+///
+/// Requires SQLite 3.44.0. On earlier versions of SQLite, the method is never called.
+pub trait IntegrityVTab<'vtab>: VTab<'vtab> {
+    /// Corresponds to xIntegrity, when PRAGMA integrity_check or PRAGMA quick_check is run
+    /// on the database containing the virtual table. Return Ok(Some(message)) to report
+    /// that the table is corrupt, or Ok(None) if no problems were found. An Err indicates
+    /// that the check itself could not be performed.
+    ///
+    /// The flags parameter is 1 for PRAGMA quick_check and 0 for PRAGMA integrity_check.
+    fn integrity(&'vtab self, schema: &str, table: &str, flags: u64) -> Result<Option<String>>;
 }
 
 /// Implementation of the cursor type for a virtual table.
@@ -245,10 +447,21 @@ pub trait VTabCursor {
     /// columns were declared by [VTab::connect]. The output value must be assigned to the context
     /// using [ColumnContext::set_result]. If no result is set, SQL NULL is returned. If this
     /// method returns an Err value, the SQL statement will fail, even if a result had been set
-    /// before the failure.
+    /// before the failure. The error message is prefixed with the name of the table and the
+    /// column, for example `tbl: column 1 (b): message`.
     fn column(&mut self, idx: usize, context: &ColumnContext) -> Result<()>;
 
     /// Fetch the rowid for the current row.
+    ///
+    /// The rowid of a row must not change while a statement is running, even if the statement
+    /// opens several cursors or modifies the table. Virtual tables which support
+    /// [UpdateVTab] must also accept the rowids returned here in
+    /// [update](UpdateVTab::update). Using the position of the row as its rowid does not
+    /// satisfy this; see [RowidMap] for a way to derive stable rowids from keys.
+    ///
+    /// Virtual tables declared WITHOUT ROWID (see
+    /// [with_without_rowid](Module::with_without_rowid)) do not have a rowid, and this method
+    /// is never called for them. Such cursors can return an error.
     fn rowid(&mut self) -> Result<i64>;
 }
 
@@ -338,11 +551,117 @@ pub struct VTabConnection {
     db: ffi::sqlite3,
 }
 
+thread_local! {
+    /// The schema most recently declared with [VTabConnection::declare_vtab], which is
+    /// used when [VTab::connect] returns an empty schema.
+    static DECLARED_SCHEMA: RefCell<Option<String>> = const { RefCell::new(None) };
+    /// Whether any of the arguments passed to the running connect or create contained
+    /// invalid UTF-8. See [VTabConnection::args_replaced].
+    static ARGS_REPLACED: Cell<bool> = const { Cell::new(false) };
+    /// The runtime of the virtual table being connected. See [VTabConnection::runtime].
+    static RUNTIME: RefCell<Option<VTabRuntime>> = const { RefCell::new(None) };
+}
+
+/// Bookkeeping which the crate maintains about a connected virtual table.
+///
+/// A virtual table can obtain its runtime with [VTabConnection::runtime] and keep it for
+/// as long as it likes. Clones of the runtime refer to the same virtual table.
+#[derive(Clone, Debug, Default)]
+pub struct VTabRuntime {
+    open_cursors: Arc<AtomicUsize>,
+    columns: Arc<OnceLock<Vec<ColumnInfo>>>,
+}
+
+impl VTabRuntime {
+    /// Returns the number of cursors on the virtual table which have been opened and not
+    /// yet closed.
+    ///
+    /// SQLite closes every cursor before it disconnects or destroys a virtual table, so
+    /// this is always 0 when [VTab::disconnect] or [CreateVTab::destroy] is called. If it
+    /// is not, the crate writes a warning to the SQLite error log, and debug builds
+    /// panic.
+    pub fn open_cursors(&self) -> usize {
+        self.open_cursors.load(Ordering::SeqCst)
+    }
+
+    /// Returns the columns declared by the virtual table, in the order they are declared
+    /// and including HIDDEN columns, so that column `i` corresponds to `args()[i + 1]` of
+    /// [ChangeInfo]. This is the same information that [ChangeInfo::column_name] and
+    /// [ChangeInfo::column_decltype] provide.
+    ///
+    /// The columns are known once [VTab::connect] or [CreateVTab::create] returns. Until
+    /// then, this method returns an empty slice.
+    pub fn columns(&self) -> &[ColumnInfo] {
+        self.columns.get().map_or(&[], |c| c.as_slice())
+    }
+
+    pub(crate) fn set_columns(&self, columns: Vec<ColumnInfo>) {
+        let _ = self.columns.set(columns);
+    }
+
+    pub(crate) fn cursor_opened(&self) {
+        self.open_cursors.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub(crate) fn cursor_closed(&self) {
+        self.open_cursors.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
 impl VTabConnection {
     unsafe fn from_ptr<'a>(db: *mut ffi::sqlite3) -> &'a Self {
         &*(db as *mut Self)
     }
 
+    /// Declare the schema of the virtual table.
+    ///
+    /// Normally, the schema is the CREATE TABLE statement returned from [VTab::connect] or
+    /// [CreateVTab::create], and it is declared automatically. Virtual tables which need to
+    /// handle errors in their schema themselves, or which want to try several schemas, can
+    /// instead call this method and then return an empty string from connect or create.
+    ///
+    /// This method can only be called from within connect or create, before they return,
+    /// and it can only succeed once. Other calls fail with [SQLITE_MISUSE]. SQLite fails
+    /// with an error if connect or create returns an empty string without successfully
+    /// calling this method.
+    ///
+    /// If SQLite rejects the schema, this method fails with [Error::Module], whose message
+    /// includes both the reason and the schema. Another schema can then be tried.
+    pub fn declare_vtab(&self, sql: &str) -> Result<()> {
+        let guard = self.lock();
+        let csql = CString::new(sql)?;
+        let rc = unsafe { ffi::sqlite3_declare_vtab(guard.as_mut_ptr(), csql.as_ptr()) };
+        match Error::from_sqlite_desc(rc, guard) {
+            Ok(()) => {
+                DECLARED_SCHEMA.with(|s| *s.borrow_mut() = Some(sql.to_owned()));
+                Ok(())
+            }
+            Err(Error::Sqlite(ffi::SQLITE_ERROR, msg)) => Err(Error::Module(format!(
+                "invalid virtual table schema ({}): {sql}",
+                msg.as_deref().unwrap_or("unknown error")
+            ))),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Return true if any of the arguments passed to [VTab::connect] or [CreateVTab::create]
+    /// contained invalid UTF-8, which was replaced with U+FFFD.
+    ///
+    /// This can only happen for modules created with
+    /// [with_lossy_args](Module::with_lossy_args), and the result is only meaningful while
+    /// connect or create is running.
+    pub fn args_replaced(&self) -> bool {
+        ARGS_REPLACED.with(|r| r.get())
+    }
+
+    /// Returns the [VTabRuntime] of the virtual table being connected.
+    ///
+    /// This method can only be called from within [VTab::connect] or [CreateVTab::create],
+    /// before they return. Other calls fail with [SQLITE_MISUSE].
+    pub fn runtime(&self) -> Result<VTabRuntime> {
+        RUNTIME.with(|r| r.borrow().clone().ok_or(SQLITE_MISUSE))
+    }
+
     /// Indicate that this virtual table properly verifies constraints for updates.
     ///
     /// If this is enabled, then the virtual table guarantees that if the
@@ -409,6 +728,7 @@ pub struct ChangeInfo {
     db: *mut ffi::sqlite3,
     argc: usize,
     argv: *mut *mut ValueRef,
+    schema: *const DeclaredSchema,
 }
 
 impl ChangeInfo {
@@ -470,6 +790,140 @@ impl ChangeInfo {
         unsafe { slice::from_raw_parts_mut(self.argv.offset(1) as _, self.argc - 1) }
     }
 
+    /// Returns [rowid_mut](Self::rowid_mut) and [args_mut](Self::args_mut) together.
+    pub(crate) fn rowid_and_args_mut(&mut self) -> (&mut ValueRef, &mut [&mut ValueRef]) {
+        debug_assert!(self.argc > 0);
+        unsafe {
+            let all: &mut [&mut ValueRef] = slice::from_raw_parts_mut(self.argv as _, self.argc);
+            let (rowid, args) = all.split_first_mut().unwrap();
+            (&mut **rowid, args)
+        }
+    }
+
+    /// Returns the number of entries that [args](Self::args) has for an INSERT or UPDATE:
+    /// one for the rowid slot, plus one for each column declared by the virtual table,
+    /// including HIDDEN columns.
+    pub fn column_count(&self) -> usize {
+        self.schema().columns.len() + 1
+    }
+
+    /// Returns the name of the column corresponding to `args()[i]`. Index 0 is the rowid
+    /// slot, which is named "rowid", or has no name for a WITHOUT ROWID table. Returns None
+    /// if the index is out of range.
+    ///
+    /// Column names come from the CREATE TABLE statement returned by [VTab::connect] /
+    /// [CreateVTab::create].
+    pub fn column_name(&self, i: usize) -> Option<&str> {
+        match i {
+            0 if self.schema().without_rowid => None,
+            0 => Some("rowid"),
+            i => self.schema().columns.get(i - 1).map(|c| c.name()),
+        }
+    }
+
+    /// Returns the declared type of the column corresponding to `args()[i]`, or None if
+    /// the column has no declared type or the index is out of range. The rowid slot never
+    /// has a declared type. The HIDDEN keyword is not part of the declared type.
+    pub fn column_decltype(&self, i: usize) -> Option<&str> {
+        match i {
+            0 => None,
+            i => self
+                .schema()
+                .columns
+                .get(i - 1)
+                .map(|c| c.decltype())
+                .filter(|t| !t.is_empty()),
+        }
+    }
+
+    /// Returns the PRIMARY KEY of the row being inserted or, for an UPDATE, the new PRIMARY
+    /// KEY of the row, for a virtual table declared WITHOUT ROWID. SQLite only allows
+    /// writable WITHOUT ROWID virtual tables to have a single PRIMARY KEY column.
+    ///
+    /// For a WITHOUT ROWID table, the rowid slot of [args](Self::args) is always NULL for an
+    /// INSERT, so the key has to be taken from the column values, which this method does.
+    /// The PRIMARY KEY of the row being deleted or updated is [rowid](Self::rowid).
+    ///
+    /// Returns None for a DELETE, or if the virtual table is not WITHOUT ROWID.
+    pub fn primary_key(&mut self) -> Option<&mut ValueRef> {
+        let schema = self.schema();
+        if !schema.without_rowid || self.change_type() == ChangeType::Delete {
+            return None;
+        }
+        let i = *schema.primary_key.first()?;
+        self.args_mut().get_mut(i + 1).map(|a| &mut **a)
+    }
+
+    /// Build an UPDATE statement which applies this change to a shadow table, writing only
+    /// the columns which changed.
+    ///
+    /// The columns slice names the shadow table column which stores each column of the
+    /// virtual table, in the order they are declared; that is, `columns[i]` stores
+    /// `args()[i + 1]`. Columns of the virtual table beyond the end of the slice are not
+    /// written. The shadow table is matched using its rowid, which is also updated if the
+    /// change assigns a new rowid. The shadow table is in the database named by schema,
+    /// which is normally the schema of the virtual table (the second argument passed to
+    /// [VTab::connect]). The schema, table, and column names are quoted, so they may contain
+    /// any characters.
+    ///
+    /// Returns the SQL and the values to bind to it, in order. Values which are
+    /// [nochange](ValueRef::nochange) are left out, so the virtual table should return
+    /// [Error::NoChange] from [VTabCursor::column] when [ColumnContext::nochange] is true.
+    /// On versions of SQLite before 3.22.0, every column is written. Returns None if this
+    /// change is not an UPDATE, or if nothing would be written.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::{vtab::*, *};
+    ///
+    /// fn update_shadow(db: &Connection, info: &ChangeInfo) -> Result<()> {
+    ///     if let Some((sql, params)) = info.build_update_sql("main", "tbl_data", &["a", "b"]) {
+    ///         db.execute(&sql, params)?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn build_update_sql(
+        &self,
+        schema: &str,
+        table: &str,
+        columns: &[&str],
+    ) -> Option<(String, Vec<&ValueRef>)> {
+        if self.change_type() != ChangeType::Update {
+            return None;
+        }
+        let args = self.args();
+        let rowid = self.rowid();
+        let mut set = vec![];
+        let mut params = vec![];
+        if args[0].get_i64() != rowid.get_i64() {
+            set.push("rowid = ?".to_owned());
+            params.push(args[0]);
+        }
+        for (name, val) in columns.iter().zip(&args[1..]) {
+            if !val.nochange() {
+                set.push(format!("{} = ?", quote_identifier(name)));
+                params.push(*val);
+            }
+        }
+        if set.is_empty() {
+            return None;
+        }
+        params.push(rowid);
+        let sql = format!(
+            "UPDATE {}.{} SET {} WHERE rowid = ?",
+            quote_identifier(schema),
+            quote_identifier(table),
+            set.join(", ")
+        );
+        Some((sql, params))
+    }
+
+    fn schema(&self) -> &DeclaredSchema {
+        unsafe { &*self.schema }
+    }
+
     /// Return the ON CONFLICT mode of the current SQL statement. In order for this method
     /// to be useful, the virtual table needs to have previously enabled ON CONFLICT
     /// support using [VTabConnection::enable_constraints].
@@ -543,18 +997,24 @@ impl ConflictMode {
 }
 
 /// Describes the run-time environment of the [VTabCursor::column] method.
-#[repr(transparent)]
 pub struct ColumnContext {
-    base: ffi::sqlite3_context,
+    base: *mut ffi::sqlite3_context,
+    index: usize,
 }
 
 impl ColumnContext {
-    pub(crate) fn as_ptr<'a>(&self) -> *mut ffi::sqlite3_context {
-        &self.base as *const ffi::sqlite3_context as _
+    pub(crate) fn as_ptr(&self) -> *mut ffi::sqlite3_context {
+        self.base
+    }
+
+    pub(crate) unsafe fn from_ptr(base: *mut ffi::sqlite3_context, index: usize) -> Self {
+        Self { base, index }
     }
 
-    pub(crate) unsafe fn from_ptr<'a>(base: *mut ffi::sqlite3_context) -> &'a mut Self {
-        &mut *(base as *mut Self)
+    /// Return the index of the column being fetched. This is the same value that is passed
+    /// to [VTabCursor::column].
+    pub fn column_index(&self) -> usize {
+        self.index
     }
 
     /// Return a handle to the current database.
@@ -562,6 +1022,12 @@ impl ColumnContext {
         unsafe { Connection::from_ptr(ffi::sqlite3_context_db_handle(self.as_ptr())) }
     }
 
+    /// Return the identifier of the current database. This is equivalent to
+    /// `self.db().id()`.
+    pub fn db_id(&self) -> ConnectionId {
+        ConnectionId::from_ptr(unsafe { ffi::sqlite3_context_db_handle(self.as_ptr()) })
+    }
+
     /// Return true if the column being fetched is part of an UPDATE operation during which
     /// the column value will not change.
     ///
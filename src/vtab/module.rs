@@ -1,9 +1,14 @@
 //! Wrappers for creating virtual tables.
 
 use super::*;
-use crate::{ffi, sqlite3_match_version, sqlite3_require_version, Connection};
+use crate::{
+    extension::{boxed_destructor, check_transient_drop},
+    ffi, sqlite3_match_version, sqlite3_require_version, Connection,
+};
 use sealed::sealed;
-use std::{ffi::CString, marker::PhantomData};
+#[cfg(modern_sqlite)]
+use std::ptr::{null, null_mut};
+use std::{ffi::CString, marker::PhantomData, sync::Arc};
 
 union ModuleBytes {
     bytes: [u8; std::mem::size_of::<ffi::sqlite3_module>()],
@@ -28,10 +33,20 @@ fn set_version(m: &mut ffi::sqlite3_module, val: i32) {
 }
 
 /// Handle to the module and aux data, so that it can be properly dropped when the module is
-/// unloaded.
+/// unloaded. The aux data is shared with other registrations made by
+/// [Connection::create_module_arc].
 pub(super) struct Handle<'vtab, T: VTab<'vtab>> {
     pub vtab: ffi::sqlite3_module,
-    pub aux: T::Aux,
+    pub aux: Arc<T::Aux>,
+    pub options: ModuleOptions,
+}
+
+/// Options for a module which are used by the stubs rather than SQLite.
+#[doc(hidden)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ModuleOptions {
+    pub lossy_args: bool,
+    pub without_rowid: bool,
 }
 
 impl<'vtab, T: VTab<'vtab>> Handle<'vtab, T> {
@@ -52,6 +67,35 @@ where
     #[doc(hidden)]
     fn module(&mut self) -> &mut ffi::sqlite3_module;
 
+    #[doc(hidden)]
+    fn options(&mut self) -> &mut ModuleOptions;
+
+    /// Accept arguments to CREATE VIRTUAL TABLE which contain invalid UTF-8.
+    ///
+    /// By default, the virtual table fails to connect if any of its arguments contain
+    /// invalid UTF-8. With this option, the invalid sequences are replaced with U+FFFD
+    /// instead, and the virtual table can check whether this happened using
+    /// [VTabConnection::args_replaced].
+    fn with_lossy_args(mut self) -> Self {
+        self.options().lossy_args = true;
+        self
+    }
+
+    /// Declare that the virtual table is WITHOUT ROWID.
+    ///
+    /// The schema returned by [VTab::connect] must then end with WITHOUT ROWID, otherwise
+    /// the virtual table fails to connect. The cursor's [rowid](VTabCursor::rowid) method
+    /// must still be implemented, but SQLite will not call it, so it may simply return an
+    /// error. The PRIMARY KEY of a changed row is available from [ChangeInfo::primary_key].
+    ///
+    /// This is normally enabled by passing `WithoutRowId` to
+    /// [sqlite3_ext_vtab](::sqlite3_ext_macro::sqlite3_ext_vtab).
+    fn with_without_rowid(mut self) -> Self {
+        self.options().without_rowid = true;
+        self.module().xRowid = Some(stubs::vtab_rowid_without_rowid);
+        self
+    }
+
     #[doc(hidden)]
     fn with_update(mut self) -> Self
     where
@@ -84,6 +128,17 @@ where
         self
     }
 
+    #[doc(hidden)]
+    fn with_read_transactions(mut self) -> Self
+    where
+        T: ReadTransactionVTab<'vtab>,
+    {
+        let m = self.module();
+        m.xOpen = Some(stubs::vtab_open_read::<T>);
+        m.xClose = Some(stubs::vtab_close_read::<T>);
+        self
+    }
+
     #[doc(hidden)]
     fn with_initial_transaction(&mut self)
     where
@@ -106,6 +161,37 @@ where
         self.module().xRename = Some(stubs::vtab_rename::<T>);
         self
     }
+
+    #[doc(hidden)]
+    fn with_workers(mut self) -> Self
+    where
+        T: HasWorkers<'vtab>,
+    {
+        self.module().xDisconnect = Some(stubs::vtab_disconnect_workers::<T>);
+        self.with_workers_destroy();
+        self
+    }
+
+    #[doc(hidden)]
+    fn with_workers_destroy(&mut self)
+    where
+        T: HasWorkers<'vtab>;
+
+    #[doc(hidden)]
+    fn with_integrity(#[cfg_attr(not(modern_sqlite), allow(unused_mut))] mut self) -> Self
+    where
+        T: IntegrityVTab<'vtab>,
+    {
+        sqlite3_match_version! {
+            3_044_000 => {
+                let m = self.module();
+                set_version(m, 4);
+                m.xIntegrity = Some(stubs::vtab_integrity::<T>);
+            }
+            _ => (),
+        }
+        self
+    }
 }
 
 macro_rules! module_base {
@@ -113,6 +199,7 @@ macro_rules! module_base {
         $(#[$attr])*
         pub struct $name<'vtab, T: VTab<'vtab>> {
             base: ffi::sqlite3_module,
+            options: ModuleOptions,
             phantom: PhantomData<&'vtab T>,
         }
 
@@ -122,6 +209,10 @@ macro_rules! module_base {
                 &mut self.base
             }
 
+            fn options(&mut self) -> &mut ModuleOptions {
+                &mut self.options
+            }
+
             $($extra)*
         }
     };
@@ -141,6 +232,13 @@ module_base!(
         // This is a standard table, so we need to override this.
         self.base.xCreate = Some(stubs::vtab_create_transaction::<T>);
     }
+
+    fn with_workers_destroy(&mut self)
+    where
+        T: HasWorkers<'vtab>,
+    {
+        self.base.xDestroy = Some(stubs::vtab_destroy_workers::<T>);
+    }
 });
 
 module_base!(
@@ -162,6 +260,13 @@ module_base!(
         self.base.xConnect = Some(stubs::vtab_connect_transaction::<T>);
         self.base.xCreate = Some(stubs::vtab_connect_transaction::<T>);
     }
+
+    fn with_workers_destroy(&mut self)
+    where
+        T: HasWorkers<'vtab>,
+    {
+        self.base.xDestroy = Some(stubs::vtab_disconnect_workers::<T>);
+    }
 });
 
 module_base!(
@@ -186,6 +291,13 @@ module_base!(
     {
         // CREATE VIRTUAL TABLE will never be called on this table.
     }
+
+    fn with_workers_destroy(&mut self)
+    where
+        T: HasWorkers<'vtab>,
+    {
+        self.base.xDestroy = Some(stubs::vtab_disconnect_workers::<T>);
+    }
 });
 
 impl<'vtab, T: CreateVTab<'vtab>> StandardModule<'vtab, T> {
@@ -208,14 +320,13 @@ impl<'vtab, T: CreateVTab<'vtab>> StandardModule<'vtab, T> {
                 xRowid: Some(stubs::vtab_rowid::<T>),
                 ..EMPTY_MODULE
             },
+            options: ModuleOptions::default(),
             phantom: PhantomData,
         };
         sqlite3_match_version! {
             3_026_000 => {
-                if T::SHADOW_NAMES.len() > 0 {
-                    set_version(&mut ret.base, 3);
-                    ret.base.xShadowName = Some(stubs::vtab_shadow_name::<T>);
-                }
+                set_version(&mut ret.base, 3);
+                ret.base.xShadowName = Some(stubs::vtab_shadow_name::<T>);
             }
             _ => (),
         }
@@ -242,6 +353,7 @@ impl<'vtab, T: VTab<'vtab>> EponymousModule<'vtab, T> {
                 xRowid: Some(stubs::vtab_rowid::<T>),
                 ..EMPTY_MODULE
             },
+            options: ModuleOptions::default(),
             phantom: PhantomData,
         }
     }
@@ -267,6 +379,7 @@ impl<'vtab, T: VTab<'vtab>> EponymousOnlyModule<'vtab, T> {
                     xRowid: Some(stubs::vtab_rowid::<T>),
                     ..EMPTY_MODULE
                 },
+                options: ModuleOptions::default(),
                 phantom: PhantomData,
             })
         )
@@ -275,18 +388,93 @@ impl<'vtab, T: VTab<'vtab>> EponymousOnlyModule<'vtab, T> {
 
 impl Connection {
     /// Register the provided virtual table module with this connection.
+    ///
+    /// If the Aux data needs to be dropped and this is called by a non-persistent
+    /// `unload_safe` extension, this function fails. See
+    /// [Extension](crate::Extension#non-persistent-extensions) for details.
     pub fn create_module<'db: 'vtab, 'vtab, T: VTab<'vtab> + 'vtab, M: Module<'vtab, T> + 'vtab>(
         &'db self,
         name: &str,
-        mut vtab: M,
+        vtab: M,
+        aux: T::Aux,
+    ) -> Result<()>
+    where
+        T::Aux: 'db,
+    {
+        check_transient_drop::<T::Aux>("module aux data")?;
+        self.create_module_internal(name, vtab, Arc::new(aux), false)
+    }
+
+    /// Register the provided virtual table module with this connection, sharing the Aux
+    /// data with other registrations. This function is identical to
+    /// [create_module](Self::create_module), except that the same Aux data can be used for
+    /// several modules, or for the same module under several names, without requiring it to
+    /// be Clone.
+    ///
+    /// The module holds a reference to the Aux data until SQLite destroys the module. This
+    /// happens once the module has been removed, by closing the connection, registering
+    /// another module with the same name, or calling [drop_module](Self::drop_module), and
+    /// every virtual table using the module has been disconnected. [Arc::strong_count] can
+    /// be used to observe this.
+    ///
+    /// This function always fails when called by a non-persistent `unload_safe` extension.
+    /// See [Extension](crate::Extension#non-persistent-extensions) for details.
+    pub fn create_module_arc<
+        'db: 'vtab,
+        'vtab,
+        T: VTab<'vtab> + 'vtab,
+        M: Module<'vtab, T> + 'vtab,
+    >(
+        &'db self,
+        name: &str,
+        vtab: M,
+        aux: Arc<T::Aux>,
+    ) -> Result<()>
+    where
+        T::Aux: 'db,
+    {
+        check_transient_drop::<Arc<T::Aux>>("module aux data")?;
+        self.create_module_internal(name, vtab, aux, false)
+    }
+
+    /// Register the provided virtual table module with this connection, without ever dropping
+    /// it. This function is identical to [create_module](Self::create_module), except that
+    /// the module and its Aux data are leaked instead of being dropped when the connection
+    /// closes. This allows non-persistent extensions to use Aux data which needs to be
+    /// dropped. See [Extension](crate::Extension#non-persistent-extensions) for details.
+    pub fn create_module_leaky<
+        'db: 'vtab,
+        'vtab,
+        T: VTab<'vtab> + 'vtab,
+        M: Module<'vtab, T> + 'vtab,
+    >(
+        &'db self,
+        name: &str,
+        vtab: M,
         aux: T::Aux,
     ) -> Result<()>
     where
         T::Aux: 'db,
     {
+        self.create_module_internal(name, vtab, Arc::new(aux), true)
+    }
+
+    fn create_module_internal<'vtab, T: VTab<'vtab> + 'vtab, M: Module<'vtab, T> + 'vtab>(
+        &self,
+        name: &str,
+        mut vtab: M,
+        aux: Arc<T::Aux>,
+        leak: bool,
+    ) -> Result<()> {
         let name = CString::new(name).unwrap();
+        let options = *vtab.options();
         let vtab = vtab.module().clone();
-        let handle = Box::new(Handle::<'vtab, T> { vtab, aux });
+        let handle = Box::new(Handle::<'vtab, T> { vtab, aux, options });
+        let destroy: Option<unsafe extern "C" fn(*mut c_void)> = if leak {
+            None
+        } else {
+            boxed_destructor::<Handle<T>>()
+        };
         let guard = self.lock();
         Error::from_sqlite_desc(
             unsafe {
@@ -295,10 +483,62 @@ impl Connection {
                     name.as_ptr() as _,
                     &handle.vtab,
                     Box::into_raw(handle) as _,
-                    Some(ffi::drop_boxed::<Handle<T>>),
+                    destroy,
                 )
             },
             guard,
         )
     }
+
+    /// Remove the virtual table module with the given name from this connection. It is not
+    /// an error if no such module is registered.
+    ///
+    /// Virtual tables which are already connected continue to use the module, and the
+    /// module's Aux data is only dropped once the last of them is disconnected. New virtual
+    /// tables cannot be created or connected using the module, and existing ones cannot be
+    /// dropped with `DROP TABLE` until the module is registered again.
+    ///
+    /// Requires SQLite 3.30.0.
+    pub fn drop_module(&self, name: &str) -> Result<()> {
+        let _ = name;
+        sqlite3_require_version!(3_030_000, {
+            let name = CString::new(name)?;
+            let guard = self.lock();
+            Error::from_sqlite_desc(
+                unsafe {
+                    ffi::sqlite3_create_module_v2(
+                        self.as_mut_ptr(),
+                        name.as_ptr() as _,
+                        null(),
+                        null_mut(),
+                        None,
+                    )
+                },
+                guard,
+            )
+        })
+    }
+
+    /// Remove all virtual table modules from this connection, except for the ones named in
+    /// `keep`. This has the same effect as calling [drop_module](Self::drop_module) for each
+    /// of the removed modules.
+    ///
+    /// Requires SQLite 3.30.0.
+    pub fn drop_modules_except(&self, keep: &[&str]) -> Result<()> {
+        let _ = keep;
+        sqlite3_require_version!(3_030_000, {
+            let keep = keep
+                .iter()
+                .map(|name| CString::new(*name))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            let mut ptrs: Vec<*const std::os::raw::c_char> =
+                keep.iter().map(|name| name.as_ptr()).collect();
+            ptrs.push(null());
+            let guard = self.lock();
+            Error::from_sqlite_desc(
+                unsafe { ffi::sqlite3_drop_modules(self.as_mut_ptr(), ptrs.as_mut_ptr()) },
+                guard,
+            )
+        })
+    }
 }
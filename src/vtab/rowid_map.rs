@@ -0,0 +1,274 @@
+//! Stable rowids for virtual tables whose rows are identified by keys.
+use crate::{
+    connection::quote_identifier, ffi, params, types::*, value::*, Connection, FallibleIterator,
+    FallibleIteratorMut,
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// A key identifying a row of a virtual table, used with [RowidMap].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RowidKey {
+    Integer(i64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl RowidKey {
+    fn to_value(&self) -> Value {
+        match self {
+            RowidKey::Integer(x) => Value::Integer(*x),
+            RowidKey::Text(x) => Value::Text(x.clone()),
+            RowidKey::Blob(x) => Value::Blob(Blob::from(x.as_slice())),
+        }
+    }
+}
+
+impl From<i64> for RowidKey {
+    fn from(val: i64) -> Self {
+        RowidKey::Integer(val)
+    }
+}
+
+impl From<String> for RowidKey {
+    fn from(val: String) -> Self {
+        RowidKey::Text(val)
+    }
+}
+
+impl From<&str> for RowidKey {
+    fn from(val: &str) -> Self {
+        RowidKey::Text(val.to_owned())
+    }
+}
+
+impl From<Vec<u8>> for RowidKey {
+    fn from(val: Vec<u8>) -> Self {
+        RowidKey::Blob(val)
+    }
+}
+
+impl From<&[u8]> for RowidKey {
+    fn from(val: &[u8]) -> Self {
+        RowidKey::Blob(val.to_vec())
+    }
+}
+
+impl TryFrom<Value> for RowidKey {
+    type Error = Error;
+
+    fn try_from(val: Value) -> Result<Self> {
+        match val {
+            Value::Integer(x) => Ok(RowidKey::Integer(x)),
+            Value::Text(x) => Ok(RowidKey::Text(x)),
+            Value::Blob(x) => Ok(RowidKey::Blob(x.as_slice().to_vec())),
+            Value::Float(_) | Value::Null => Err(SQLITE_MISMATCH),
+        }
+    }
+}
+
+/// A bidirectional map between the keys of a virtual table's rows and stable rowids.
+///
+/// SQLite requires that the rowid of a row does not change for the duration of a statement,
+/// even across multiple cursors on the same virtual table. For example, `DELETE FROM tbl
+/// WHERE rowid IN (SELECT rowid FROM tbl WHERE ...)` collects the rowids using one cursor,
+/// and then passes each of them to [UpdateVTab::update2](super::UpdateVTab::update2). A
+/// virtual table which numbers its rows in the order it enumerates them will delete the
+/// wrong rows, because deleting the first row renumbers the rest.
+///
+/// Virtual tables whose rows have some other unique key can use a RowidMap to assign each
+/// key a rowid the first time it is seen, and keep using that rowid for as long as the map
+/// exists. Rowids are assigned in increasing order starting at 1, skipping any which are
+/// already in use. Once the largest rowid has been used, the lowest unused rowid is assigned
+/// instead.
+///
+/// A RowidMap is normally held for the lifetime of the virtual table. Tables which are
+/// created with [CreateVTab](super::CreateVTab) and need rowids to survive reconnecting can
+/// store the map in a shadow table, using [create_table](Self::create_table) in
+/// [create](super::CreateVTab::create), [load](Self::load) in
+/// [connect](super::VTab::connect), and [save](Self::save) after the map changes.
+#[derive(Debug, Clone)]
+pub struct RowidMap {
+    by_key: HashMap<RowidKey, i64>,
+    by_rowid: BTreeMap<i64, RowidKey>,
+    next: Option<i64>,
+}
+
+impl Default for RowidMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RowidMap {
+    /// Create an empty map.
+    pub fn new() -> Self {
+        RowidMap {
+            by_key: HashMap::new(),
+            by_rowid: BTreeMap::new(),
+            next: Some(1),
+        }
+    }
+
+    /// Return the number of keys in the map.
+    pub fn len(&self) -> usize {
+        self.by_key.len()
+    }
+
+    /// Return true if the map contains no keys.
+    pub fn is_empty(&self) -> bool {
+        self.by_key.is_empty()
+    }
+
+    /// Return the rowid for the key, assigning a new one if the key has not been seen before.
+    ///
+    /// Fails with [SQLITE_FULL](ffi::SQLITE_FULL) if every positive rowid is in use.
+    pub fn rowid(&mut self, key: impl Into<RowidKey>) -> Result<i64> {
+        let key = key.into();
+        if let Some(rowid) = self.by_key.get(&key) {
+            return Ok(*rowid);
+        }
+        let rowid = self.next_rowid()?;
+        self.by_key.insert(key.clone(), rowid);
+        self.by_rowid.insert(rowid, key);
+        Ok(rowid)
+    }
+
+    /// Return the rowid for the key, if it has one.
+    pub fn get_rowid(&self, key: &RowidKey) -> Option<i64> {
+        self.by_key.get(key).copied()
+    }
+
+    /// Return the key with the given rowid.
+    pub fn key(&self, rowid: i64) -> Option<&RowidKey> {
+        self.by_rowid.get(&rowid)
+    }
+
+    /// Associate the key with a specific rowid, such as one which was provided in an INSERT
+    /// statement.
+    ///
+    /// Fails with [SQLITE_CONSTRAINT] if the rowid is already used by a different key, or
+    /// if the key already has a different rowid. Rowids must be positive.
+    pub fn insert(&mut self, key: impl Into<RowidKey>, rowid: i64) -> Result<()> {
+        let key = key.into();
+        if rowid <= 0 {
+            return Err(Error::Module(format!("invalid rowid {rowid}")));
+        }
+        match (self.by_key.get(&key), self.by_rowid.get(&rowid)) {
+            (Some(r), _) if *r == rowid => return Ok(()),
+            (None, None) => (),
+            _ => return Err(SQLITE_CONSTRAINT),
+        }
+        self.by_key.insert(key.clone(), rowid);
+        self.by_rowid.insert(rowid, key);
+        if self.next.is_some_and(|next| next <= rowid) {
+            self.next = rowid.checked_add(1);
+        }
+        Ok(())
+    }
+
+    /// Change the key associated with a rowid, for example when an UPDATE changes the
+    /// primary key of a row. Returns the previous key.
+    ///
+    /// Fails with [SQLITE_CONSTRAINT] if the new key already has a different rowid, and with
+    /// an error if the rowid is not in the map. Use [insert](Self::insert) to add a rowid.
+    pub fn rekey(&mut self, rowid: i64, key: impl Into<RowidKey>) -> Result<RowidKey> {
+        let key = key.into();
+        match self.by_key.get(&key) {
+            Some(r) if *r == rowid => return Ok(key),
+            Some(_) => return Err(SQLITE_CONSTRAINT),
+            None => (),
+        }
+        let old = match self.by_rowid.get_mut(&rowid) {
+            Some(x) => std::mem::replace(x, key.clone()),
+            None => return Err(Error::Module(format!("no such rowid {rowid}"))),
+        };
+        self.by_key.remove(&old);
+        self.by_key.insert(key, rowid);
+        Ok(old)
+    }
+
+    /// Remove the key, returning its rowid. The rowid will not be reused until every larger
+    /// rowid has been used.
+    pub fn remove_key(&mut self, key: &RowidKey) -> Option<i64> {
+        let rowid = self.by_key.remove(key)?;
+        self.by_rowid.remove(&rowid);
+        Some(rowid)
+    }
+
+    /// Remove the rowid, returning its key.
+    pub fn remove_rowid(&mut self, rowid: i64) -> Option<RowidKey> {
+        let key = self.by_rowid.remove(&rowid)?;
+        self.by_key.remove(&key);
+        Some(key)
+    }
+
+    /// Iterate over the rowids and keys in the map, in rowid order.
+    pub fn iter(&self) -> impl Iterator<Item = (i64, &RowidKey)> {
+        self.by_rowid.iter().map(|(rowid, key)| (*rowid, key))
+    }
+
+    fn next_rowid(&mut self) -> Result<i64> {
+        if let Some(next) = self.next {
+            self.next = next.checked_add(1);
+            return Ok(next);
+        }
+        // The largest rowid has been used, so look for a gap.
+        let mut candidate = 1;
+        for rowid in self.by_rowid.keys() {
+            if *rowid > candidate {
+                break;
+            }
+            candidate = rowid
+                .checked_add(1)
+                .ok_or(Error::Sqlite(ffi::SQLITE_FULL, None))?;
+        }
+        Ok(candidate)
+    }
+
+    /// Create a table which can hold a RowidMap, if it does not already exist. The table is
+    /// created in the database named by schema, which for a shadow table is the schema of
+    /// the virtual table (the second argument passed to [CreateVTab::create]). The names are
+    /// quoted, so they may contain any characters.
+    ///
+    /// [CreateVTab::create]: super::CreateVTab::create
+    pub fn create_table(db: &Connection, schema: &str, table: &str) -> Result<()> {
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {}.{} (rowid INTEGER PRIMARY KEY, key UNIQUE NOT NULL)",
+            quote_identifier(schema),
+            quote_identifier(table)
+        );
+        db.execute(&sql, ())?;
+        Ok(())
+    }
+
+    /// Load a map which was saved to the table with [save](Self::save).
+    pub fn load(db: &Connection, schema: &str, table: &str) -> Result<Self> {
+        let sql = format!(
+            "SELECT rowid, key FROM {}.{}",
+            quote_identifier(schema),
+            quote_identifier(table)
+        );
+        let rows: Vec<(i64, Value)> = db
+            .prepare(&sql)?
+            .query(())?
+            .map(|row| Ok((row[0].get_i64(), row[1].to_owned()?)))
+            .collect()?;
+        let mut ret = RowidMap::new();
+        for (rowid, key) in rows {
+            ret.insert(RowidKey::try_from(key)?, rowid)?;
+        }
+        Ok(ret)
+    }
+
+    /// Replace the contents of the table with this map. The table must have been created
+    /// with [create_table](Self::create_table).
+    pub fn save(&self, db: &Connection, schema: &str, table: &str) -> Result<()> {
+        let table = format!("{}.{}", quote_identifier(schema), quote_identifier(table));
+        db.execute(&format!("DELETE FROM {table}"), ())?;
+        let mut stmt = db.prepare(&format!("INSERT INTO {table} (rowid, key) VALUES (?, ?)"))?;
+        for (rowid, key) in self.iter() {
+            stmt.execute(params!(rowid, key.to_value()))?;
+        }
+        Ok(())
+    }
+}
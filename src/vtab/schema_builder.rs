@@ -0,0 +1,706 @@
+use super::virtual_table::is_single_arg;
+use crate::{connection::quote_identifier, ffi, sql::ident_eq, sqlite3_match_version, types::*};
+
+/// Information about a column declared with [SchemaBuilder].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnInfo {
+    name: String,
+    decltype: String,
+    hidden: bool,
+    collation: Option<String>,
+}
+
+impl ColumnInfo {
+    /// Returns the name of the column.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the declared type of the column. This is empty if no type was declared.
+    pub fn decltype(&self) -> &str {
+        &self.decltype
+    }
+
+    /// Returns true if the column is HIDDEN.
+    pub fn hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Returns the collating sequence declared with COLLATE, or None if the column uses
+    /// the default, BINARY.
+    pub fn collation(&self) -> Option<&str> {
+        self.collation.as_deref()
+    }
+
+    /// Parse the columns from a CREATE TABLE statement, such as the one returned from
+    /// [VTab::connect](super::VTab::connect). Columns are returned in the order they are
+    /// declared, including HIDDEN columns, and table constraints are ignored. The statement
+    /// is expected to be valid SQL; the result for invalid SQL is unspecified, except that
+    /// an unterminated quoted identifier fails with [SQLITE_MISUSE](ffi::SQLITE_MISUSE).
+    pub fn from_schema(sql: &str) -> Result<Vec<ColumnInfo>> {
+        Ok(DeclaredSchema::parse(sql)?.columns)
+    }
+}
+
+/// Builds the CREATE TABLE statement which a virtual table returns from
+/// [VTab::connect](super::VTab::connect).
+///
+/// The builder remembers the columns that were declared, so it can be kept in the virtual
+/// table and used to look up columns by name, for example in
+/// [best_index](super::VTab::best_index). Columns are numbered in the order they are declared,
+/// including HIDDEN columns, which matches [IndexInfoConstraint::column](super::IndexInfoConstraint::column).
+///
+/// # Examples
+///
+/// ```no_run
+/// use sqlite3_ext::{vtab::SchemaBuilder, *};
+///
+/// fn schema() -> Result<()> {
+///     let schema = SchemaBuilder::new()
+///         .column("value", "")
+///         .hidden_column("start", "INTEGER")
+///         .hidden_column("stop", "INTEGER");
+///     assert_eq!(
+///         schema.build()?,
+///         "CREATE TABLE x ( value, start INTEGER HIDDEN, stop INTEGER HIDDEN )"
+///     );
+///     assert_eq!(schema.column_index("stop"), Some(2));
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SchemaBuilder {
+    columns: Vec<ColumnInfo>,
+    primary_key: Vec<String>,
+    without_rowid: bool,
+}
+
+impl SchemaBuilder {
+    /// Create an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a column. The decltype can be empty to declare a column without a type.
+    pub fn column(self, name: &str, decltype: &str) -> Self {
+        self.add_column(name, decltype, false)
+    }
+
+    /// Add a HIDDEN column. Hidden columns are not returned by `SELECT *`, and are used as
+    /// the parameters of table-valued functions.
+    pub fn hidden_column(self, name: &str, decltype: &str) -> Self {
+        self.add_column(name, decltype, true)
+    }
+
+    /// Declare the PRIMARY KEY of the table. Every name must be a declared column.
+    pub fn primary_key(mut self, columns: &[&str]) -> Self {
+        self.primary_key = columns.iter().map(|c| (*c).to_owned()).collect();
+        self
+    }
+
+    /// Declare the table as WITHOUT ROWID. Such a table must have a
+    /// [primary key](Self::primary_key).
+    pub fn without_rowid(mut self) -> Self {
+        self.without_rowid = true;
+        self
+    }
+
+    fn add_column(mut self, name: &str, decltype: &str, hidden: bool) -> Self {
+        self.columns.push(ColumnInfo {
+            name: name.to_owned(),
+            decltype: decltype.trim().to_owned(),
+            hidden,
+            // An invalid decltype is reported by build.
+            collation: declared_collation(&tokenize(decltype)).unwrap_or_default(),
+        });
+        self
+    }
+
+    /// Returns the columns declared so far.
+    pub fn columns(&self) -> &[ColumnInfo] {
+        &self.columns
+    }
+
+    /// Returns the index of the column with the given name. Column names are compared
+    /// case-insensitively, like SQLite does.
+    pub fn column_index(&self, name: &str) -> Option<i32> {
+        self.columns
+            .iter()
+            .position(|c| ident_eq(&c.name, name))
+            .map(|i| i as _)
+    }
+
+    /// Produce the CREATE TABLE statement.
+    ///
+    /// Fails with [SQLITE_MISUSE](ffi::SQLITE_MISUSE) if there are no columns, a column
+    /// name is empty or declared twice, a decltype is not a single type declaration, or the
+    /// primary key refers to an undeclared column.
+    pub fn build(&self) -> Result<String> {
+        if self.columns.is_empty() {
+            return Err(misuse("virtual table has no columns".to_owned()));
+        }
+        for (i, c) in self.columns.iter().enumerate() {
+            if c.name.is_empty() {
+                return Err(misuse("empty column name".to_owned()));
+            }
+            if self.columns[..i].iter().any(|p| ident_eq(&p.name, &c.name)) {
+                return Err(misuse(format!("duplicate column name: {}", c.name)));
+            }
+            if !c.decltype.is_empty() && !is_single_arg(&c.decltype) {
+                return Err(misuse(format!("invalid column type: {}", c.decltype)));
+            }
+        }
+        if let Some(k) = self
+            .primary_key
+            .iter()
+            .find(|k| self.column_index(k).is_none())
+        {
+            return Err(misuse(format!("no such column in primary key: {k}")));
+        }
+        if self.without_rowid && self.primary_key.is_empty() {
+            return Err(misuse("WITHOUT ROWID requires a primary key".to_owned()));
+        }
+
+        let mut defs: Vec<String> = self
+            .columns
+            .iter()
+            .map(|c| {
+                // HIDDEN must be part of the type, so it goes before any constraints.
+                let (ty, constraints) = split_constraints(&c.decltype);
+                let mut def = quote_if_needed(&c.name);
+                if !ty.is_empty() {
+                    def = format!("{def} {ty}");
+                }
+                if c.hidden {
+                    def.push_str(" HIDDEN");
+                }
+                if !constraints.is_empty() {
+                    def = format!("{def} {constraints}");
+                }
+                def
+            })
+            .collect();
+        if !self.primary_key.is_empty() {
+            let keys: Vec<_> = self
+                .primary_key
+                .iter()
+                .map(|k| quote_if_needed(k))
+                .collect();
+            defs.push(format!("PRIMARY KEY ({})", keys.join(", ")));
+        }
+        let mut sql = format!("CREATE TABLE x ( {} )", defs.join(", "));
+        if self.without_rowid {
+            sql.push_str(" WITHOUT ROWID");
+        }
+        Ok(sql)
+    }
+}
+
+/// The columns of a virtual table, listed as the variants of a fieldless enum.
+///
+/// Keeping the column indices, the schema, and the code which uses them in one place avoids
+/// mismatches between them. This trait is implemented with `#[derive(VTabColumns)]`, which
+/// declares one column per variant, in order. The column name is the name of the variant,
+/// converted to snake_case. Each variant can be annotated with `#[column(...)]`, which
+/// accepts:
+///
+/// - `name = "..."` to override the column name.
+/// - `decltype = "..."` to declare the type of the column.
+/// - `collate = "..."` to declare the default collating sequence of the column.
+/// - `hidden` to make the column HIDDEN.
+///
+/// The derive also implements `From<Self> for i32`, so variants can be compared against
+/// [IndexInfoConstraint::column](super::IndexInfoConstraint::column).
+///
+/// # Examples
+///
+/// ```no_run
+/// use sqlite3_ext::{vtab::*, *};
+///
+/// #[derive(VTabColumns, Debug, PartialEq)]
+/// enum Col {
+///     Value,
+///     #[column(hidden, decltype = "INTEGER")]
+///     Start,
+///     #[column(hidden, name = "stop_at")]
+///     Stop,
+/// }
+///
+/// fn check() -> Result<()> {
+///     assert_eq!(Col::COUNT, 3);
+///     assert_eq!(
+///         Col::SCHEMA_FRAGMENT,
+///         "\"value\", \"start\" INTEGER HIDDEN, \"stop_at\" HIDDEN"
+///     );
+///     assert_eq!(Col::from_index(1), Some(Col::Start));
+///     assert_eq!(i32::from(Col::Stop), 2);
+///     assert_eq!(Col::schema_builder().column_index("stop_at"), Some(2));
+///     Ok(())
+/// }
+/// ```
+pub trait VTabColumns: Sized {
+    /// The number of columns.
+    const COUNT: usize;
+
+    /// The column definitions, separated by commas, for use in the CREATE TABLE statement
+    /// returned from [VTab::connect](super::VTab::connect). Column names are always
+    /// quoted.
+    const SCHEMA_FRAGMENT: &'static str;
+
+    /// Returns the column with the given index, or None if the index is out of range.
+    fn from_index(idx: usize) -> Option<Self>;
+
+    /// Returns a [SchemaBuilder] which declares every column.
+    fn schema_builder() -> SchemaBuilder;
+}
+
+fn misuse(msg: String) -> Error {
+    Error::Sqlite(ffi::SQLITE_MISUSE, Some(msg))
+}
+
+/// Quote the identifier, unless it is a plain identifier which is not a keyword.
+fn quote_if_needed(name: &str) -> String {
+    let plain = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if plain && !is_keyword(name) {
+        name.to_owned()
+    } else {
+        quote_identifier(name)
+    }
+}
+
+/// Returns true if the name is an SQL keyword. Every name is assumed to be a keyword on
+/// versions of SQLite which cannot check.
+fn is_keyword(name: &str) -> bool {
+    let _ = name;
+    sqlite3_match_version! {
+        3_024_000 => unsafe { ffi::sqlite3_keyword_check(name.as_ptr() as _, name.len() as _) != 0 },
+        _ => true,
+    }
+}
+
+/// The columns declared by a virtual table, parsed from the CREATE TABLE statement it
+/// returned from [VTab::connect](super::VTab::connect).
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DeclaredSchema {
+    pub columns: Vec<ColumnInfo>,
+    pub without_rowid: bool,
+    /// Indexes of the PRIMARY KEY columns, in the order they appear in the key.
+    pub primary_key: Vec<usize>,
+}
+
+impl DeclaredSchema {
+    /// Parse the CREATE TABLE statement. SQLite has already accepted the statement, so
+    /// this only needs to handle valid SQL; anything unexpected results in fewer columns.
+    /// Unterminated quoted identifiers are an error, since they cannot be dequoted.
+    pub fn parse(sql: &str) -> Result<Self> {
+        let toks = tokenize(sql);
+        let mut ret = DeclaredSchema::default();
+        let open = match toks.iter().position(|&t| t == "(") {
+            Some(x) => x,
+            None => return Ok(ret),
+        };
+        let mut depth = 0;
+        let mut def: Vec<&str> = vec![];
+        let mut pk = vec![];
+        let mut end = toks.len();
+        for (i, &t) in toks.iter().enumerate().skip(open + 1) {
+            match t {
+                "(" => depth += 1,
+                ")" if depth == 0 => {
+                    end = i + 1;
+                    break;
+                }
+                ")" => depth -= 1,
+                "," if depth == 0 => {
+                    ret.add_definition(sql, &def, &mut pk)?;
+                    def.clear();
+                    continue;
+                }
+                _ => (),
+            }
+            def.push(t);
+        }
+        ret.add_definition(sql, &def, &mut pk)?;
+        ret.primary_key = pk
+            .iter()
+            .filter_map(|name| ret.columns.iter().position(|c| ident_eq(&c.name, name)))
+            .collect();
+        let rest: Vec<_> = toks[end.min(toks.len())..].iter().collect();
+        ret.without_rowid = rest
+            .windows(2)
+            .any(|w| w[0].eq_ignore_ascii_case("without") && w[1].eq_ignore_ascii_case("rowid"));
+        Ok(ret)
+    }
+
+    /// Add a column definition or table constraint. The names of the PRIMARY KEY columns
+    /// are added to `pk`.
+    fn add_definition(&mut self, sql: &str, def: &[&str], pk: &mut Vec<String>) -> Result<()> {
+        const TABLE_CONSTRAINTS: [&str; 5] =
+            ["CONSTRAINT", "PRIMARY", "UNIQUE", "CHECK", "FOREIGN"];
+        let name = match def.first() {
+            None => return Ok(()),
+            Some(x) if TABLE_CONSTRAINTS.iter().any(|k| k.eq_ignore_ascii_case(x)) => {
+                pk.extend(table_primary_key(def)?);
+                return Ok(());
+            }
+            Some(x) => dequote(x)?,
+        };
+        let mut depth = 0;
+        let mut last = 0;
+        let mut in_type = true;
+        for (i, t) in def.iter().enumerate().skip(1) {
+            match *t {
+                "(" => depth += 1,
+                ")" => depth -= 1,
+                t if depth == 0 && t.eq_ignore_ascii_case("PRIMARY") => {
+                    pk.push(name.clone());
+                    break;
+                }
+                t if depth == 0 && is_column_constraint(t) => in_type = false,
+                _ => (),
+            }
+            if in_type {
+                last = i;
+            }
+        }
+        let decltype = match last {
+            0 => "",
+            _ => span(sql, def[1], def[last]),
+        };
+        let (decltype, hidden) = remove_hidden(decltype);
+        self.columns.push(ColumnInfo {
+            name,
+            decltype,
+            hidden,
+            collation: declared_collation(&def[1..])?,
+        });
+        Ok(())
+    }
+}
+
+/// Returns the names of the columns in a PRIMARY KEY table constraint, or nothing if the
+/// constraint is of a different kind.
+fn table_primary_key(def: &[&str]) -> Result<Vec<String>> {
+    let start = match def.iter().position(|t| t.eq_ignore_ascii_case("PRIMARY")) {
+        Some(x) => x,
+        None => return Ok(vec![]),
+    };
+    let mut ret = vec![];
+    let mut depth = 0;
+    let mut expect_name = false;
+    for &t in def.iter().skip(start + 1) {
+        match t {
+            "(" => {
+                depth += 1;
+                expect_name = depth == 1;
+            }
+            ")" if depth == 1 => break,
+            ")" => depth -= 1,
+            "," if depth == 1 => expect_name = true,
+            t if expect_name => {
+                ret.push(dequote(t)?);
+                expect_name = false;
+            }
+            _ => (),
+        }
+    }
+    Ok(ret)
+}
+
+/// Keywords which begin a column constraint, ending the type of a column definition.
+const COLUMN_CONSTRAINTS: [&str; 11] = [
+    "CONSTRAINT",
+    "PRIMARY",
+    "NOT",
+    "NULL",
+    "UNIQUE",
+    "CHECK",
+    "DEFAULT",
+    "COLLATE",
+    "REFERENCES",
+    "GENERATED",
+    "AS",
+];
+
+fn is_column_constraint(tok: &str) -> bool {
+    COLUMN_CONSTRAINTS
+        .iter()
+        .any(|k| k.eq_ignore_ascii_case(tok))
+}
+
+/// Returns the name given to the COLLATE constraint in the tokens of a column definition.
+fn declared_collation(def: &[&str]) -> Result<Option<String>> {
+    let mut depth = 0;
+    for (i, t) in def.iter().enumerate() {
+        match *t {
+            "(" => depth += 1,
+            ")" => depth -= 1,
+            t if depth == 0 && t.eq_ignore_ascii_case("COLLATE") => {
+                return def.get(i + 1).map(|c| dequote(c)).transpose();
+            }
+            _ => (),
+        }
+    }
+    Ok(None)
+}
+
+/// Split a column definition, without the name, into the type and the constraints.
+fn split_constraints(def: &str) -> (&str, &str) {
+    let mut depth = 0;
+    for t in tokenize(def) {
+        match t {
+            "(" => depth += 1,
+            ")" => depth -= 1,
+            t if depth == 0 && is_column_constraint(t) => {
+                let (ty, constraints) = def.split_at(t.as_ptr() as usize - def.as_ptr() as usize);
+                return (ty.trim_end(), constraints);
+            }
+            _ => (),
+        }
+    }
+    (def, "")
+}
+
+/// Returns the text of the SQL from the start of the first token to the end of the last.
+fn span<'a>(sql: &'a str, first: &str, last: &str) -> &'a str {
+    let start = first.as_ptr() as usize - sql.as_ptr() as usize;
+    let end = last.as_ptr() as usize - sql.as_ptr() as usize + last.len();
+    &sql[start..end]
+}
+
+/// Remove the word HIDDEN from a declared type, the same way SQLite does.
+fn remove_hidden(decltype: &str) -> (String, bool) {
+    let b = decltype.as_bytes();
+    let found = (0..b.len()).find(|&i| {
+        b.len() >= i + 6
+            && b[i..i + 6].eq_ignore_ascii_case(b"hidden")
+            && (i == 0 || b[i - 1] == b' ')
+            && (i + 6 == b.len() || b[i + 6] == b' ')
+    });
+    match found {
+        None => (decltype.to_owned(), false),
+        Some(i) => {
+            let end = (i + 7).min(b.len());
+            let mut ret = format!("{}{}", &decltype[..i], &decltype[end..]);
+            if end == b.len() && ret.ends_with(' ') {
+                ret.pop();
+            }
+            (ret, true)
+        }
+    }
+}
+
+/// Split SQL into tokens: quoted strings and identifiers, words, and single punctuation
+/// characters. Whitespace and comments are dropped.
+fn tokenize(sql: &str) -> Vec<&str> {
+    let b = sql.as_bytes();
+    let mut ret = vec![];
+    let mut i = 0;
+    while i < b.len() {
+        let start = i;
+        match b[i] {
+            c if c.is_ascii_whitespace() => {
+                i += 1;
+                continue;
+            }
+            b'-' if b.get(i + 1) == Some(&b'-') => {
+                while i < b.len() && b[i] != b'\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            b'/' if b.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i < b.len() && !b[i..].starts_with(b"*/") {
+                    i += 1;
+                }
+                i = (i + 2).min(b.len());
+                continue;
+            }
+            q @ (b'\'' | b'"' | b'`' | b'[') => {
+                let close = if q == b'[' { b']' } else { q };
+                i += 1;
+                while i < b.len() {
+                    i += 1;
+                    if b[i - 1] == close {
+                        // A doubled quote is an escaped quote.
+                        if close != b']' && b.get(i) == Some(&close) {
+                            i += 1;
+                            continue;
+                        }
+                        break;
+                    }
+                }
+            }
+            c if c.is_ascii_alphanumeric() || c == b'_' || c >= 0x80 => {
+                while i < b.len()
+                    && (b[i].is_ascii_alphanumeric()
+                        || b[i] == b'_'
+                        || b[i] == b'$'
+                        || b[i] >= 0x80)
+                {
+                    i += 1;
+                }
+            }
+            _ => i += 1,
+        }
+        ret.push(&sql[start..i]);
+    }
+    ret
+}
+
+/// Remove the quotes from an identifier. Fails if the quote is not closed.
+fn dequote(ident: &str) -> Result<String> {
+    let b = ident.as_bytes();
+    let close = match b.first() {
+        Some(b'[') => b']',
+        Some(&q @ (b'\'' | b'"' | b'`')) => q,
+        _ => return Ok(ident.to_owned()),
+    };
+    let unterminated = || misuse(format!("unterminated identifier: {ident}"));
+    if b.len() < 2 || b[b.len() - 1] != close {
+        return Err(unterminated());
+    }
+    let inner = &ident[1..ident.len() - 1];
+    match close {
+        b']' => Ok(inner.to_owned()),
+        q => {
+            let (q, qq) = (q as char, format!("{}{}", q as char, q as char));
+            // Any quote which is not doubled would have ended the identifier early.
+            if inner.replace(&qq, "").contains(q) {
+                return Err(unterminated());
+            }
+            Ok(inner.replace(&qq, &q.to_string()))
+        }
+    }
+}
+
+#[cfg(all(test, feature = "static"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn build() -> Result<()> {
+        let schema = SchemaBuilder::new()
+            .column("key", "TEXT NOT NULL")
+            .column("my col", "DECIMAL(10, 2)")
+            .hidden_column("Pattern", "")
+            .primary_key(&["key"])
+            .without_rowid();
+        let sql = schema.build()?;
+        assert!(sql.ends_with(" ) WITHOUT ROWID"), "{sql}");
+        sqlite3_match_version! {
+            3_024_000 => assert_eq!(
+                sql,
+                "CREATE TABLE x ( \"key\" TEXT NOT NULL, \"my col\" DECIMAL(10, 2), Pattern HIDDEN, PRIMARY KEY (\"key\") ) WITHOUT ROWID"
+            ),
+            _ => (),
+        }
+        assert_eq!(schema.column_index("pattern"), Some(2));
+        assert_eq!(schema.column_index("nope"), None);
+        assert!(schema.columns()[2].hidden());
+        Ok(())
+    }
+
+    #[test]
+    fn hidden_with_constraints() -> Result<()> {
+        let sql = SchemaBuilder::new()
+            .column("value", "")
+            .hidden_column("label", "TEXT COLLATE NOCASE")
+            .hidden_column("flags", "NOT NULL")
+            .build()?;
+        sqlite3_match_version! {
+            3_024_000 => assert_eq!(
+                sql,
+                "CREATE TABLE x ( value, label TEXT HIDDEN COLLATE NOCASE, flags HIDDEN NOT NULL )"
+            ),
+            _ => (),
+        }
+        let cols = ColumnInfo::from_schema(&sql)?;
+        assert!(cols[1].hidden() && cols[2].hidden());
+        assert_eq!(cols[1].decltype(), "TEXT");
+        assert_eq!(cols[1].collation(), Some("NOCASE"));
+        assert_eq!(cols[2].collation(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn parse() -> Result<()> {
+        let schema = DeclaredSchema::parse(
+            "CREATE TABLE x ( \"a b\" DECIMAL(10, 2) NOT NULL, [c] HIDDEN, d TEXT hidden \
+             DEFAULT 'x,y', e, -- comment, f\n g CHECK (g > 0), PRIMARY KEY (a, c) ) WITHOUT ROWID",
+        )?;
+        let cols: Vec<_> = schema
+            .columns
+            .iter()
+            .map(|c| (c.name(), c.decltype(), c.hidden()))
+            .collect();
+        assert_eq!(
+            cols,
+            vec![
+                ("a b", "DECIMAL(10, 2)", false),
+                ("c", "", true),
+                ("d", "TEXT", true),
+                ("e", "", false),
+                ("g", "", false),
+            ]
+        );
+        assert!(schema.without_rowid);
+        // There is no column named "a", only "a b".
+        assert_eq!(schema.primary_key, vec![1]);
+        let schema = DeclaredSchema::parse("CREATE TABLE x(a)")?;
+        assert!(!schema.without_rowid);
+        assert!(schema.primary_key.is_empty());
+        let schema = DeclaredSchema::parse(
+            "CREATE TABLE x (a, b TEXT COLLATE NOCASE PRIMARY KEY NOT NULL) WITHOUT ROWID",
+        )?;
+        assert_eq!(schema.columns[1].decltype(), "TEXT");
+        assert_eq!(schema.columns[1].collation(), Some("NOCASE"));
+        assert_eq!(schema.primary_key, vec![1]);
+        let schema = DeclaredSchema::parse(
+            "CREATE TABLE x (a, b, CONSTRAINT pk PRIMARY KEY (\"B\" DESC, a COLLATE BINARY))",
+        )?;
+        assert_eq!(schema.primary_key, vec![1, 0]);
+        Ok(())
+    }
+
+    #[test]
+    fn unterminated() {
+        for sql in [
+            "CREATE TABLE x ( [",
+            "CREATE TABLE x ( \"a",
+            "CREATE TABLE x ( \"a\"\" )",
+            "CREATE TABLE x ( a TEXT COLLATE ` )",
+            "CREATE TABLE x ( a, PRIMARY KEY ('a ) )",
+        ] {
+            assert!(
+                matches!(
+                    ColumnInfo::from_schema(sql),
+                    Err(Error::Sqlite(ffi::SQLITE_MISUSE, _))
+                ),
+                "{sql}"
+            );
+        }
+        assert_eq!(dequote("[a]").unwrap(), "a");
+        assert_eq!(dequote("\"a\"\"b\"").unwrap(), "a\"b");
+    }
+
+    #[test]
+    fn invalid() {
+        for schema in [
+            SchemaBuilder::new(),
+            SchemaBuilder::new().column("", ""),
+            SchemaBuilder::new().column("a", "").column("A", ""),
+            SchemaBuilder::new().column("a", "INT, b"),
+            SchemaBuilder::new().column("a", "INT) --"),
+            SchemaBuilder::new().column("a", "").primary_key(&["b"]),
+            SchemaBuilder::new().column("a", "").without_rowid(),
+        ] {
+            assert!(
+                matches!(schema.build(), Err(Error::Sqlite(ffi::SQLITE_MISUSE, _))),
+                "{schema:?}"
+            );
+        }
+    }
+}
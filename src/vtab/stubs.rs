@@ -1,6 +1,7 @@
-use super::super::{ffi, value::*, vtab::*, Connection};
+use super::super::{ffi, value::*, vtab::*};
 use std::{
-    ffi::{CStr, CString},
+    borrow::Cow,
+    ffi::CStr,
     marker::PhantomData,
     os::raw::{c_int, c_void},
     ptr, slice,
@@ -11,10 +12,26 @@ struct VTabHandle<'vtab, T: VTab<'vtab>> {
     base: ffi::sqlite3_vtab,
     vtab: T,
     db: *mut ffi::sqlite3,
+    /// The name of the database containing the virtual table, as passed to xCreate or
+    /// xConnect.
+    schema_name: String,
+    /// The name of the virtual table, as passed to xCreate or xConnect.
+    name: String,
     txn: Option<ptr::NonNull<c_void>>,
+    /// The [ReadTransactionVTab::ReadGuard], when the module uses read transactions. It
+    /// borrows `vtab`, so it must be dropped before `vtab` is disconnected.
+    read_guard: Option<Box<dyn ErasedReadGuard + 'vtab>>,
+    schema: DeclaredSchema,
+    runtime: VTabRuntime,
     phantom: PhantomData<&'vtab T>,
 }
 
+/// Type-erased [ReadTransactionVTab::ReadGuard], so that it can be dropped without knowing
+/// the concrete module type.
+trait ErasedReadGuard {}
+
+impl<T> ErasedReadGuard for T {}
+
 #[repr(C)]
 struct VTabCursorHandle<'vtab, T: VTab<'vtab>> {
     base: ffi::sqlite3_vtab_cursor,
@@ -22,6 +39,31 @@ struct VTabCursorHandle<'vtab, T: VTab<'vtab>> {
     phantom: PhantomData<&'vtab T>,
 }
 
+/// Convert the arguments of xCreate or xConnect to strings, returning whether any invalid
+/// UTF-8 was replaced.
+unsafe fn connect_args<'a>(argv: &[*const i8], lossy: bool) -> Result<(Vec<Cow<'a, str>>, bool)> {
+    let mut replaced = false;
+    let args = argv
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| {
+            let bytes = CStr::from_ptr(*arg).to_bytes();
+            match std::str::from_utf8(bytes) {
+                Ok(s) => Ok(Cow::Borrowed(s)),
+                Err(_) if lossy => {
+                    replaced = true;
+                    Ok(String::from_utf8_lossy(bytes))
+                }
+                Err(e) => Err(Error::Module(format!(
+                    "virtual table argument {i} contains invalid UTF-8 at byte {}",
+                    e.valid_up_to()
+                ))),
+            }
+        })
+        .collect::<Result<_>>()?;
+    Ok((args, replaced))
+}
+
 macro_rules! vtab_connect {
     ($name:ident, $trait:ident, $func:ident) => {
         pub unsafe extern "C" fn $name<'vtab, T: $trait<'vtab> + 'vtab>(
@@ -32,29 +74,44 @@ macro_rules! vtab_connect {
             p_vtab: *mut *mut ffi::sqlite3_vtab,
             err_msg: *mut *mut i8,
         ) -> c_int {
-            let conn = &*(db as *mut Connection);
             let module = module::Handle::<'vtab, T>::from_ptr(module);
-            let args: std::result::Result<Vec<&str>, _> = slice::from_raw_parts(argv, argc as _)
-                .into_iter()
-                .map(|arg| CStr::from_ptr(*arg).to_str())
-                .collect();
-            let args = match args {
+            let argv = slice::from_raw_parts(argv, argc as _);
+            let (args, replaced) = match connect_args(argv, module.options.lossy_args) {
                 Ok(x) => x,
                 Err(e) => return ffi::handle_error(e, err_msg),
             };
+            let args: Vec<&str> = args.iter().map(|a| &**a).collect();
+            let schema_name = args[1].to_owned();
+            let name = args[2].to_owned();
             let vtab_conn = VTabConnection::from_ptr(db);
+            DECLARED_SCHEMA.with(|s| *s.borrow_mut() = None);
+            ARGS_REPLACED.with(|r| r.set(replaced));
+            let runtime = VTabRuntime::default();
+            RUNTIME.with(|r| *r.borrow_mut() = Some(runtime.clone()));
             let ret = T::$func(&vtab_conn, &module.aux, args.as_slice());
+            RUNTIME.with(|r| *r.borrow_mut() = None);
             let (sql, vtab) = match ret {
                 Ok(x) => x,
                 Err(e) => return ffi::handle_error(e, err_msg),
             };
-            let rc = ffi::sqlite3_declare_vtab(
-                conn.as_mut_ptr(),
-                CString::from_vec_unchecked(sql.into_bytes()).as_ptr() as _,
-            );
-            if rc != ffi::SQLITE_OK {
-                return rc;
+            let declared = DECLARED_SCHEMA.with(|s| s.borrow_mut().take());
+            let sql = match sql.is_empty() {
+                // The virtual table declared its schema with VTabConnection::declare_vtab.
+                true => declared.unwrap_or_default(),
+                false => match vtab_conn.declare_vtab(&sql) {
+                    Ok(()) => sql,
+                    Err(e) => return ffi::handle_error(e, err_msg),
+                },
+            };
+            let schema = match DeclaredSchema::parse(&sql) {
+                Ok(x) => x,
+                Err(e) => return ffi::handle_error(e, err_msg),
+            };
+            if module.options.without_rowid && !schema.without_rowid {
+                let msg = format!("virtual table schema must be WITHOUT ROWID: {sql}");
+                return ffi::handle_error(Error::Module(msg), err_msg);
             }
+            runtime.set_columns(schema.columns.clone());
             let vtab = Box::new(VTabHandle {
                 base: ffi::sqlite3_vtab {
                     pModule: ptr::null_mut(),
@@ -63,7 +120,12 @@ macro_rules! vtab_connect {
                 },
                 vtab,
                 db,
+                schema_name,
+                name,
                 txn: None,
+                read_guard: None,
+                schema,
+                runtime,
                 phantom: PhantomData,
             });
             *p_vtab = Box::into_raw(vtab) as _;
@@ -114,7 +176,8 @@ pub unsafe extern "C" fn vtab_best_index<'vtab, T: VTab<'vtab> + 'vtab>(
 ) -> c_int {
     let vtab = &mut *(vtab.cast::<VTabHandle<T>>());
     let info = &mut *(info as *mut IndexInfo);
-    ffi::handle_result(vtab.vtab.best_index(info), &mut vtab.base.zErrMsg)
+    let ret = index_info::with_declared_schema(&vtab.schema, || vtab.vtab.best_index(info));
+    ffi::handle_result(ret, &mut vtab.base.zErrMsg)
 }
 
 pub unsafe extern "C" fn vtab_open<'vtab, T: VTab<'vtab> + 'vtab>(
@@ -133,6 +196,7 @@ pub unsafe extern "C" fn vtab_open<'vtab, T: VTab<'vtab> + 'vtab>(
         cursor,
         phantom: PhantomData,
     });
+    vtab.runtime.cursor_opened();
     *p_cursor = Box::into_raw(cursor) as _;
     ffi::SQLITE_OK
 }
@@ -141,14 +205,70 @@ pub unsafe extern "C" fn vtab_close<'vtab, T: VTab<'vtab> + 'vtab>(
     cursor: *mut ffi::sqlite3_vtab_cursor,
 ) -> c_int {
     let cursor: Box<VTabCursorHandle<T>> = Box::from_raw(cursor as _);
+    let vtab = &*(cursor.base.pVtab.cast::<VTabHandle<T>>());
     std::mem::drop(cursor);
+    vtab.runtime.cursor_closed();
     ffi::SQLITE_OK
 }
 
+/// Drop the read guard if the table is no longer being read. Inside of an explicit
+/// transaction, the guard is kept until the table is next used in autocommit mode.
+unsafe fn end_read_transaction<'vtab, T: VTab<'vtab>>(vtab: &mut VTabHandle<'vtab, T>) {
+    if vtab.runtime.open_cursors() == 0 && ffi::sqlite3_get_autocommit(vtab.db) != 0 {
+        vtab.read_guard = None;
+    }
+}
+
+pub unsafe extern "C" fn vtab_open_read<'vtab, T: ReadTransactionVTab<'vtab> + 'vtab>(
+    vtab: *mut ffi::sqlite3_vtab,
+    p_cursor: *mut *mut ffi::sqlite3_vtab_cursor,
+) -> c_int {
+    let handle = &mut *(vtab.cast::<VTabHandle<T>>());
+    // Release a guard left over from an explicit transaction which has since ended.
+    end_read_transaction(handle);
+    if handle.read_guard.is_none() {
+        match handle.vtab.begin_read() {
+            Ok(guard) => handle.read_guard = Some(Box::new(guard)),
+            Err(e) => return ffi::handle_error(e, &mut handle.base.zErrMsg),
+        }
+    }
+    let rc = vtab_open::<T>(vtab, p_cursor);
+    if rc != ffi::SQLITE_OK {
+        end_read_transaction(&mut *(vtab.cast::<VTabHandle<T>>()));
+    }
+    rc
+}
+
+pub unsafe extern "C" fn vtab_close_read<'vtab, T: ReadTransactionVTab<'vtab> + 'vtab>(
+    cursor: *mut ffi::sqlite3_vtab_cursor,
+) -> c_int {
+    let vtab = (*cursor).pVtab.cast::<VTabHandle<T>>();
+    let rc = vtab_close::<T>(cursor);
+    end_read_transaction(&mut *vtab);
+    rc
+}
+
+/// Report cursors which are still open when the virtual table is about to be freed. SQLite
+/// never does this, so it indicates a bug which would otherwise cause a use-after-free.
+unsafe fn check_cursors_closed<'vtab, T: VTab<'vtab>>(vtab: &VTabHandle<'vtab, T>, method: &str) {
+    let open = vtab.runtime.open_cursors();
+    if open == 0 {
+        return;
+    }
+    let msg = format!(
+        "virtual table {} has {open} open cursors in {method}",
+        vtab.name
+    );
+    ffi::log_warning(&msg);
+    debug_assert!(false, "{msg}");
+}
+
 pub unsafe extern "C" fn vtab_disconnect<'vtab, T: VTab<'vtab> + 'vtab>(
     vtab: *mut ffi::sqlite3_vtab,
 ) -> c_int {
     let mut vtab: Box<VTabHandle<T>> = Box::from_raw(vtab as _);
+    check_cursors_closed(&vtab, "xDisconnect");
+    vtab.read_guard = None;
     match vtab.vtab.disconnect() {
         Ok(_) => ffi::SQLITE_OK,
         Err((v, e)) => {
@@ -164,6 +284,8 @@ pub unsafe extern "C" fn vtab_destroy<'vtab, T: CreateVTab<'vtab> + 'vtab>(
     vtab: *mut ffi::sqlite3_vtab,
 ) -> c_int {
     let mut vtab: Box<VTabHandle<T>> = Box::from_raw(vtab as _);
+    check_cursors_closed(&vtab, "xDestroy");
+    vtab.read_guard = None;
     match vtab.vtab.destroy() {
         Ok(_) => ffi::SQLITE_OK,
         Err((v, e)) => {
@@ -175,6 +297,25 @@ pub unsafe extern "C" fn vtab_destroy<'vtab, T: CreateVTab<'vtab> + 'vtab>(
     }
 }
 
+pub unsafe extern "C" fn vtab_disconnect_workers<'vtab, T: HasWorkers<'vtab> + 'vtab>(
+    vtab: *mut ffi::sqlite3_vtab,
+) -> c_int {
+    let handle = &mut *(vtab as *mut VTabHandle<T>);
+    super::worker::stop_workers(&mut handle.vtab);
+    vtab_disconnect::<T>(vtab)
+}
+
+pub unsafe extern "C" fn vtab_destroy_workers<
+    'vtab,
+    T: CreateVTab<'vtab> + HasWorkers<'vtab> + 'vtab,
+>(
+    vtab: *mut ffi::sqlite3_vtab,
+) -> c_int {
+    let handle = &mut *(vtab as *mut VTabHandle<T>);
+    super::worker::stop_workers(&mut handle.vtab);
+    vtab_destroy::<T>(vtab)
+}
+
 pub unsafe extern "C" fn vtab_filter<'vtab, T: VTab<'vtab> + 'vtab>(
     cursor: *mut ffi::sqlite3_vtab_cursor,
     index_num: i32,
@@ -215,11 +356,20 @@ pub unsafe extern "C" fn vtab_column<'vtab, T: VTab<'vtab> + 'vtab>(
     i: i32,
 ) -> c_int {
     let cursor = &mut *(cursor as *mut VTabCursorHandle<T>);
-    let context = ColumnContext::from_ptr(context);
-    if let Err(e) = cursor.cursor.column(i as _, &context) {
-        context.set_result(e).unwrap();
+    let context = ColumnContext::from_ptr(context, i as _);
+    match cursor.cursor.column(i as _, &context) {
+        // NULL is returned in place of the unchanged value.
+        Ok(()) | Err(Error::NoChange) => ffi::SQLITE_OK,
+        Err(e) => {
+            let vtab = &mut *(cursor.base.pVtab as *mut VTabHandle<T>);
+            let column = match vtab.schema.columns.get(i as usize) {
+                Some(c) => format!("column {} ({})", i, c.name()),
+                None => format!("column {}", i),
+            };
+            let e = e.with_context(format_args!("{}: {}", vtab.name, column));
+            ffi::handle_error(e, &mut vtab.base.zErrMsg)
+        }
     }
-    ffi::SQLITE_OK
 }
 
 pub unsafe extern "C" fn vtab_rowid<'vtab, T: VTab<'vtab> + 'vtab>(
@@ -236,6 +386,14 @@ pub unsafe extern "C" fn vtab_rowid<'vtab, T: VTab<'vtab> + 'vtab>(
     }
 }
 
+pub unsafe extern "C" fn vtab_rowid_without_rowid(
+    cursor: *mut ffi::sqlite3_vtab_cursor,
+    _: *mut i64,
+) -> c_int {
+    let err = Error::Module("virtual table is WITHOUT ROWID and has no rowid".to_owned());
+    ffi::handle_error(err, &mut (*(*cursor).pVtab).zErrMsg)
+}
+
 pub unsafe extern "C" fn vtab_update<'vtab, T: UpdateVTab<'vtab> + 'vtab>(
     vtab: *mut ffi::sqlite3_vtab,
     argc: i32,
@@ -247,12 +405,14 @@ pub unsafe extern "C" fn vtab_update<'vtab, T: UpdateVTab<'vtab> + 'vtab>(
         db: vtab.db,
         argc: argc as _,
         argv: argv as _,
+        schema: &vtab.schema,
     };
-    match vtab.vtab.update(&mut context) {
-        Ok(rowid) => {
+    match vtab.vtab.update2(&mut context) {
+        Ok(UpdateResult::Inserted { rowid }) => {
             *p_rowid = rowid;
             ffi::SQLITE_OK
         }
+        Ok(UpdateResult::Done) => ffi::SQLITE_OK,
         Err(e) => ffi::handle_error(e, &mut vtab.base.zErrMsg),
     }
 }
@@ -336,7 +496,40 @@ pub unsafe extern "C" fn vtab_rename<'vtab, T: RenameVTab<'vtab> + 'vtab>(
         Ok(name) => name,
         Err(e) => return ffi::handle_error(e, &mut vtab.base.zErrMsg),
     };
-    ffi::handle_result(vtab.vtab.rename(name), &mut vtab.base.zErrMsg)
+    match vtab.vtab.rename(&vtab.schema_name, &vtab.name, name) {
+        Ok(()) => {
+            vtab.name = name.to_owned();
+            ffi::SQLITE_OK
+        }
+        Err(e) => ffi::handle_error(e, &mut vtab.base.zErrMsg),
+    }
+}
+
+#[cfg(modern_sqlite)]
+pub unsafe extern "C" fn vtab_integrity<'vtab, T: IntegrityVTab<'vtab> + 'vtab>(
+    vtab: *mut ffi::sqlite3_vtab,
+    schema: *const i8,
+    table: *const i8,
+    flags: c_int,
+    err_msg: *mut *mut i8,
+) -> c_int {
+    let vtab = &*(vtab.cast::<VTabHandle<T>>());
+    let ret = CStr::from_ptr(schema)
+        .to_str()
+        .and_then(|schema| Ok((schema, CStr::from_ptr(table).to_str()?)))
+        .map_err(Error::from)
+        .and_then(|(schema, table)| vtab.vtab.integrity(schema, table, flags as _));
+    match ret {
+        Ok(None) => ffi::SQLITE_OK,
+        Ok(Some(msg)) => match ffi::str_to_sqlite3(&msg) {
+            Ok(msg) => {
+                *err_msg = msg;
+                ffi::SQLITE_OK
+            }
+            Err(e) => ffi::handle_error(e, err_msg),
+        },
+        Err(e) => ffi::handle_error(e, err_msg),
+    }
 }
 
 #[cfg(modern_sqlite)]
@@ -373,11 +566,10 @@ pub unsafe extern "C" fn vtab_rollback_to<'vtab, T: TransactionVTab<'vtab> + 'vt
 pub unsafe extern "C" fn vtab_shadow_name<'vtab, T: CreateVTab<'vtab> + 'vtab>(
     name: *const i8,
 ) -> c_int {
-    let name = CStr::from_ptr(name).to_bytes();
-    for candidate in T::SHADOW_NAMES {
-        if candidate.as_bytes() == name {
-            return 1;
-        }
-    }
-    0
+    // SQLite passes only the part of the table name after the last underscore.
+    let name = match CStr::from_ptr(name).to_str() {
+        Ok(name) => name,
+        Err(_) => return 0,
+    };
+    T::is_shadow_name(name) as _
 }
@@ -0,0 +1,482 @@
+//! Utilities for testing virtual table implementations.
+//!
+//! [RecordingVTab] wraps any [VTab] and records every call SQLite makes to it, so that a
+//! test can assert on the exact sequence of calls, in the way the [vtablog
+//! example](https://github.com/CGamesPlay/sqlite3_ext/blob/main/examples/vtablog/main.rs)
+//! prints them. This module requires the `testing` feature.
+#![cfg(feature = "testing")]
+#![cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+
+use super::*;
+use std::rc::Rc;
+
+/// A shared log of the calls made to a [RecordingVTab].
+///
+/// Clones of a recorder share the same log. A recorder is passed to the module as part of
+/// a [RecordingAux], and every table created from that module records into it.
+#[derive(Clone, Debug, Default)]
+pub struct Recorder {
+    calls: Rc<RefCell<Vec<CallRecord>>>,
+}
+
+impl Recorder {
+    /// Returns a copy of the calls recorded so far.
+    pub fn calls(&self) -> Vec<CallRecord> {
+        self.calls.borrow().clone()
+    }
+
+    /// Returns the calls recorded so far, and clears the log.
+    pub fn take(&self) -> Vec<CallRecord> {
+        self.calls.take()
+    }
+
+    /// Clear the log.
+    pub fn clear(&self) {
+        self.calls.borrow_mut().clear();
+    }
+
+    fn record(&self, call: CallRecord) {
+        self.calls.borrow_mut().push(call);
+    }
+}
+
+/// The [VTab::Aux] of a [RecordingVTab]: the recorder, and the Aux of the wrapped virtual
+/// table.
+pub struct RecordingAux<A> {
+    recorder: Recorder,
+    aux: A,
+}
+
+impl<A> RecordingAux<A> {
+    /// Combine a recorder with the Aux of the wrapped virtual table.
+    pub fn new(recorder: Recorder, aux: A) -> Self {
+        Self { recorder, aux }
+    }
+
+    /// Returns the recorder.
+    pub fn recorder(&self) -> &Recorder {
+        &self.recorder
+    }
+
+    /// Returns the Aux of the wrapped virtual table.
+    pub fn aux(&self) -> &A {
+        &self.aux
+    }
+}
+
+/// A call made by SQLite to a [RecordingVTab].
+///
+/// Cursors and transactions are numbered in the order they were opened, starting from 1.
+/// The numbering is per table, so tables which share a recorder reuse the same numbers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CallRecord {
+    /// [VTab::connect], with all of the arguments.
+    Connect { args: Vec<String> },
+    /// [CreateVTab::create], with all of the arguments.
+    Create { args: Vec<String> },
+    /// [VTab::best_index], with the query plan chosen by the wrapped table.
+    BestIndex(IndexInfoSnapshot),
+    /// [VTab::open].
+    Open { cursor: usize },
+    /// [VTabCursor::filter].
+    Filter {
+        cursor: usize,
+        index_num: i32,
+        index_str: Option<String>,
+        args: Vec<Value>,
+    },
+    /// [VTabCursor::next].
+    Next { cursor: usize },
+    /// [VTabCursor::eof], with the returned value.
+    Eof { cursor: usize, eof: bool },
+    /// [VTabCursor::column].
+    Column { cursor: usize, idx: usize },
+    /// [VTabCursor::rowid].
+    Rowid { cursor: usize },
+    /// The cursor was closed.
+    Close { cursor: usize },
+    /// [UpdateVTab::update2].
+    Update(ChangeInfoSnapshot),
+    /// [TransactionVTab::begin].
+    Begin { transaction: usize },
+    /// [VTabTransaction::sync].
+    Sync { transaction: usize },
+    /// [VTabTransaction::commit].
+    Commit { transaction: usize },
+    /// [VTabTransaction::rollback].
+    Rollback { transaction: usize },
+    /// [VTabTransaction::savepoint].
+    Savepoint { transaction: usize, n: i32 },
+    /// [VTabTransaction::release].
+    Release { transaction: usize, n: i32 },
+    /// [VTabTransaction::rollback_to].
+    RollbackTo { transaction: usize, n: i32 },
+    /// [VTab::disconnect].
+    Disconnect,
+    /// [CreateVTab::destroy].
+    Destroy,
+}
+
+/// A copy of the fields of an [IndexInfo] which are available on every version of SQLite.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexInfoSnapshot {
+    pub constraints: Vec<ConstraintSnapshot>,
+    /// The column and direction of each term of the ORDER BY clause.
+    pub order_by: Vec<(i32, bool)>,
+    pub index_num: i32,
+    pub index_str: Option<String>,
+    pub order_by_consumed: bool,
+    pub estimated_cost: f64,
+}
+
+impl From<&IndexInfo> for IndexInfoSnapshot {
+    fn from(info: &IndexInfo) -> Self {
+        IndexInfoSnapshot {
+            constraints: info
+                .constraints()
+                .map(|c| ConstraintSnapshot {
+                    column: c.column(),
+                    op: c.op(),
+                    usable: c.usable(),
+                    argv_index: c.argv_index(),
+                    omit: c.omit(),
+                })
+                .collect(),
+            order_by: info.order_by().map(|o| (o.column(), o.desc())).collect(),
+            index_num: info.index_num(),
+            index_str: info.index_str().map(String::from),
+            order_by_consumed: info.order_by_consumed(),
+            estimated_cost: info.estimated_cost(),
+        }
+    }
+}
+
+/// A copy of an [IndexInfoConstraint].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConstraintSnapshot {
+    pub column: i32,
+    pub op: ConstraintOp,
+    pub usable: bool,
+    pub argv_index: Option<u32>,
+    pub omit: bool,
+}
+
+/// A copy of a [ChangeInfo].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChangeInfoSnapshot {
+    pub change_type: ChangeType,
+    /// See [ChangeInfo::rowid]. This is [Value::Null] for an INSERT.
+    pub rowid: Value,
+    /// See [ChangeInfo::args]. This is empty for a DELETE.
+    pub args: Vec<Value>,
+    /// The indexes in args of the values which are [nochange](ValueRef::nochange). This
+    /// is always empty on versions of SQLite before 3.22.0.
+    pub unchanged: Vec<usize>,
+}
+
+impl From<&ChangeInfo> for ChangeInfoSnapshot {
+    fn from(info: &ChangeInfo) -> Self {
+        let args = info.args();
+        let unchanged = sqlite3_match_version! {
+            3_022_000 => args
+                .iter()
+                .enumerate()
+                .filter(|(_, a)| a.nochange())
+                .map(|(i, _)| i)
+                .collect(),
+            _ => vec![],
+        };
+        ChangeInfoSnapshot {
+            change_type: info.change_type(),
+            rowid: snapshot_value(info.rowid()),
+            args: args.iter().map(|a| snapshot_value(a)).collect(),
+            unchanged,
+        }
+    }
+}
+
+fn snapshot_value(val: &ValueRef) -> Value {
+    val.to_owned().unwrap_or(Value::Null)
+}
+
+/// Adapter which records every call made to the wrapped [VTab] in a [Recorder].
+///
+/// The adapter also implements [CreateVTab], [UpdateVTab], and [TransactionVTab] when the
+/// wrapped type implements them. Register it with the module type directly, since the
+/// [sqlite3_ext_vtab](sqlite3_ext_macro::sqlite3_ext_vtab) macro cannot be applied to it,
+/// and pass a [RecordingAux] as the Aux.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sqlite3_ext::{vtab::{testing::*, *}, *};
+///
+/// fn check<'vtab, T: CreateVTab<'vtab, Aux = ()> + 'vtab>(db: &'vtab Connection) -> Result<()> {
+///     let recorder = Recorder::default();
+///     db.create_module(
+///         "recorded",
+///         StandardModule::<RecordingVTab<T>>::new(),
+///         RecordingAux::new(recorder.clone(), ()),
+///     )?;
+///     db.execute("CREATE VIRTUAL TABLE tbl USING recorded", ())?;
+///     assert!(matches!(recorder.take()[0], CallRecord::Create { .. }));
+///     Ok(())
+/// }
+/// ```
+pub struct RecordingVTab<'vtab, T: VTab<'vtab>> {
+    inner: T,
+    recorder: &'vtab Recorder,
+    num_cursors: Cell<usize>,
+    num_transactions: Cell<usize>,
+}
+
+impl<'vtab, T: VTab<'vtab>> RecordingVTab<'vtab, T> {
+    fn new(recorder: &'vtab Recorder, inner: T) -> Self {
+        Self {
+            inner,
+            recorder,
+            num_cursors: Cell::new(0),
+            num_transactions: Cell::new(0),
+        }
+    }
+
+    /// Returns the wrapped virtual table.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+fn args_to_vec(args: &[&str]) -> Vec<String> {
+    args.iter().map(|a| (*a).to_owned()).collect()
+}
+
+impl<'vtab, T: VTab<'vtab> + 'vtab> VTab<'vtab> for RecordingVTab<'vtab, T> {
+    type Aux = RecordingAux<T::Aux>;
+    type Cursor = RecordingCursor<'vtab, T>;
+
+    fn connect(
+        db: &'vtab VTabConnection,
+        aux: &'vtab Self::Aux,
+        args: &[&str],
+    ) -> Result<(String, Self)> {
+        aux.recorder.record(CallRecord::Connect {
+            args: args_to_vec(args),
+        });
+        let (sql, inner) = T::connect(db, &aux.aux, args)?;
+        Ok((sql, Self::new(&aux.recorder, inner)))
+    }
+
+    fn best_index(&'vtab self, index_info: &mut IndexInfo) -> Result<()> {
+        let ret = self.inner.best_index(index_info);
+        self.recorder
+            .record(CallRecord::BestIndex((&*index_info).into()));
+        ret
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        let id = self.num_cursors.get() + 1;
+        self.num_cursors.set(id);
+        self.recorder.record(CallRecord::Open { cursor: id });
+        Ok(RecordingCursor {
+            inner: self.inner.open()?,
+            recorder: self.recorder,
+            id,
+        })
+    }
+
+    fn disconnect(self) -> DisconnectResult<Self> {
+        self.recorder.record(CallRecord::Disconnect);
+        let RecordingVTab {
+            inner,
+            recorder,
+            num_cursors,
+            num_transactions,
+        } = self;
+        inner.disconnect().map_err(|(inner, e)| {
+            let vtab = RecordingVTab {
+                inner,
+                recorder,
+                num_cursors,
+                num_transactions,
+            };
+            (vtab, e)
+        })
+    }
+}
+
+impl<'vtab, T: CreateVTab<'vtab> + 'vtab> CreateVTab<'vtab> for RecordingVTab<'vtab, T> {
+    const SHADOW_NAMES: &'static [&'static str] = T::SHADOW_NAMES;
+
+    fn is_shadow_name(suffix: &str) -> bool {
+        T::is_shadow_name(suffix)
+    }
+
+    fn create(
+        db: &'vtab VTabConnection,
+        aux: &'vtab Self::Aux,
+        args: &[&str],
+    ) -> Result<(String, Self)> {
+        aux.recorder.record(CallRecord::Create {
+            args: args_to_vec(args),
+        });
+        let (sql, inner) = T::create(db, &aux.aux, args)?;
+        Ok((sql, Self::new(&aux.recorder, inner)))
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        self.recorder.record(CallRecord::Destroy);
+        let RecordingVTab {
+            inner,
+            recorder,
+            num_cursors,
+            num_transactions,
+        } = self;
+        inner.destroy().map_err(|(inner, e)| {
+            let vtab = RecordingVTab {
+                inner,
+                recorder,
+                num_cursors,
+                num_transactions,
+            };
+            (vtab, e)
+        })
+    }
+}
+
+impl<'vtab, T: UpdateVTab<'vtab> + 'vtab> UpdateVTab<'vtab> for RecordingVTab<'vtab, T> {
+    fn update2(&'vtab self, info: &mut ChangeInfo) -> Result<UpdateResult> {
+        self.recorder.record(CallRecord::Update((&*info).into()));
+        self.inner.update2(info)
+    }
+
+    fn update(&'vtab self, info: &mut ChangeInfo) -> Result<i64> {
+        self.recorder.record(CallRecord::Update((&*info).into()));
+        self.inner.update(info)
+    }
+}
+
+impl<'vtab, T: TransactionVTab<'vtab> + 'vtab> TransactionVTab<'vtab> for RecordingVTab<'vtab, T> {
+    type Transaction = RecordingTransaction<'vtab, T>;
+
+    fn begin(&'vtab self) -> Result<Self::Transaction> {
+        let id = self.num_transactions.get() + 1;
+        self.num_transactions.set(id);
+        self.recorder.record(CallRecord::Begin { transaction: id });
+        Ok(RecordingTransaction {
+            inner: self.inner.begin()?,
+            recorder: self.recorder,
+            id,
+        })
+    }
+}
+
+/// The cursor of a [RecordingVTab].
+pub struct RecordingCursor<'vtab, T: VTab<'vtab>> {
+    inner: T::Cursor,
+    recorder: &'vtab Recorder,
+    id: usize,
+}
+
+impl<'vtab, T: VTab<'vtab>> VTabCursor for RecordingCursor<'vtab, T> {
+    fn filter(
+        &mut self,
+        index_num: i32,
+        index_str: Option<&str>,
+        args: &mut [&mut ValueRef],
+    ) -> Result<()> {
+        self.recorder.record(CallRecord::Filter {
+            cursor: self.id,
+            index_num,
+            index_str: index_str.map(String::from),
+            args: args.iter().map(|a| snapshot_value(a)).collect(),
+        });
+        self.inner.filter(index_num, index_str, args)
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.recorder.record(CallRecord::Next { cursor: self.id });
+        self.inner.next()
+    }
+
+    fn eof(&mut self) -> bool {
+        let eof = self.inner.eof();
+        self.recorder.record(CallRecord::Eof {
+            cursor: self.id,
+            eof,
+        });
+        eof
+    }
+
+    fn column(&mut self, idx: usize, context: &ColumnContext) -> Result<()> {
+        self.recorder.record(CallRecord::Column {
+            cursor: self.id,
+            idx,
+        });
+        self.inner.column(idx, context)
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        self.recorder.record(CallRecord::Rowid { cursor: self.id });
+        self.inner.rowid()
+    }
+}
+
+impl<'vtab, T: VTab<'vtab>> Drop for RecordingCursor<'vtab, T> {
+    fn drop(&mut self) {
+        self.recorder.record(CallRecord::Close { cursor: self.id });
+    }
+}
+
+/// The transaction of a [RecordingVTab].
+pub struct RecordingTransaction<'vtab, T: TransactionVTab<'vtab>> {
+    inner: T::Transaction,
+    recorder: &'vtab Recorder,
+    id: usize,
+}
+
+impl<'vtab, T: TransactionVTab<'vtab>> VTabTransaction for RecordingTransaction<'vtab, T> {
+    fn sync(&mut self) -> Result<()> {
+        self.recorder.record(CallRecord::Sync {
+            transaction: self.id,
+        });
+        self.inner.sync()
+    }
+
+    fn commit(self) -> Result<()> {
+        self.recorder.record(CallRecord::Commit {
+            transaction: self.id,
+        });
+        self.inner.commit()
+    }
+
+    fn rollback(self) -> Result<()> {
+        self.recorder.record(CallRecord::Rollback {
+            transaction: self.id,
+        });
+        self.inner.rollback()
+    }
+
+    fn savepoint(&mut self, n: i32) -> Result<()> {
+        self.recorder.record(CallRecord::Savepoint {
+            transaction: self.id,
+            n,
+        });
+        self.inner.savepoint(n)
+    }
+
+    fn release(&mut self, n: i32) -> Result<()> {
+        self.recorder.record(CallRecord::Release {
+            transaction: self.id,
+            n,
+        });
+        self.inner.release(n)
+    }
+
+    fn rollback_to(&mut self, n: i32) -> Result<()> {
+        self.recorder.record(CallRecord::RollbackTo {
+            transaction: self.id,
+            n,
+        });
+        self.inner.rollback_to(n)
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{connection::quote_identifier, ffi, types::*, value::*, Connection};
+use std::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Source of unique names for [Connection::create_virtual_table].
+static NEXT_NAME: AtomicU64 = AtomicU64::new(0);
+
+/// Identifies a database attached to a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Schema<'a> {
+    /// The main database.
+    Main,
+    /// The database used for temporary tables. Tables in this schema are only visible to
+    /// the connection which created them, and are removed when it is closed.
+    Temp,
+    /// An attached database.
+    Named(&'a str),
+}
+
+impl Schema<'_> {
+    /// Returns the name of the schema.
+    pub fn name(&self) -> &str {
+        match self {
+            Schema::Main => "main",
+            Schema::Temp => "temp",
+            Schema::Named(x) => x,
+        }
+    }
+}
+
+impl fmt::Display for Schema<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.name().fmt(f)
+    }
+}
+
+/// A virtual table created with [Connection::create_virtual_table].
+///
+/// The table is dropped when the handle is dropped, unless [keep](Self::keep) is called
+/// first. Errors while dropping the table are ignored; use [drop_table](Self::drop_table) to
+/// observe them. The handle borrows the connection, so the table is always dropped before
+/// the connection is closed.
+#[derive(Debug)]
+pub struct VirtualTableHandle<'db> {
+    db: &'db Connection,
+    schema: String,
+    name: Option<String>,
+}
+
+impl VirtualTableHandle<'_> {
+    /// Returns the name of the schema containing the table.
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    /// Returns the name of the table.
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap()
+    }
+
+    /// Returns the fully-qualified name of the table, quoted for use in SQL.
+    pub fn qualified_name(&self) -> String {
+        format!(
+            "{}.{}",
+            quote_identifier(&self.schema),
+            quote_identifier(self.name())
+        )
+    }
+
+    /// Drop the table. It is not an error if the table has already been dropped.
+    pub fn drop_table(mut self) -> Result<()> {
+        self.drop_internal()
+    }
+
+    /// Keep the table after the handle is dropped, returning its name.
+    pub fn keep(mut self) -> String {
+        self.name.take().unwrap()
+    }
+
+    fn drop_internal(&mut self) -> Result<()> {
+        match self.name {
+            Some(_) => {
+                let sql = format!("DROP TABLE IF EXISTS {}", self.qualified_name());
+                self.name = None;
+                self.db.execute(&sql, ()).map(|_| ())
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for VirtualTableHandle<'_> {
+    fn drop(&mut self) {
+        let _ = self.drop_internal();
+    }
+}
+
+impl Connection {
+    /// Create a virtual table using the given module, and return a handle which drops the
+    /// table when it goes out of scope. If name is None, a name which is not used by any
+    /// other table in the schema is generated.
+    ///
+    /// Each argument is passed to the module verbatim, so it must already be written the way
+    /// the module expects to receive it. For example, to pass a string literal containing a
+    /// quote, use `"'it''s'"`. Fails with [SQLITE_MISUSE](ffi::SQLITE_MISUSE) if an argument
+    /// would not be received by the module as a single argument, for example because it
+    /// contains an unquoted comma or unbalanced parentheses.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sqlite3_ext::{vtab::Schema, *};
+    ///
+    /// fn scratch_series(db: &Connection) -> Result<i64> {
+    ///     let tbl = db.create_virtual_table(Schema::Temp, None, "series", &["'start=1'"])?;
+    ///     let sql = format!("SELECT COUNT(*) FROM {}", tbl.qualified_name());
+    ///     db.query_row(&sql, (), |r| Ok(r[0].get_i64()))
+    /// }
+    /// ```
+    pub fn create_virtual_table(
+        &self,
+        schema: Schema,
+        name: Option<&str>,
+        module: &str,
+        args: &[&str],
+    ) -> Result<VirtualTableHandle<'_>> {
+        if let Some(arg) = args.iter().find(|a| !is_single_arg(a)) {
+            return Err(Error::Sqlite(
+                ffi::SQLITE_MISUSE,
+                Some(format!("invalid virtual table argument: {arg}")),
+            ));
+        }
+        let name = match name {
+            Some(x) => x.to_owned(),
+            None => self.unique_table_name(schema)?,
+        };
+        let mut sql = format!(
+            "CREATE VIRTUAL TABLE {}.{} USING {}",
+            quote_identifier(schema.name()),
+            quote_identifier(&name),
+            quote_identifier(module),
+        );
+        if !args.is_empty() {
+            sql = format!("{sql}({})", args.join(", "));
+        }
+        match self.prepare_first(&sql)? {
+            (Some(mut stmt), rest) if rest.trim().is_empty() => stmt.execute(())?,
+            _ => {
+                return Err(Error::Sqlite(
+                    ffi::SQLITE_MISUSE,
+                    Some(format!("invalid virtual table arguments: {sql}")),
+                ))
+            }
+        };
+        Ok(VirtualTableHandle {
+            db: self,
+            schema: schema.name().to_owned(),
+            name: Some(name),
+        })
+    }
+
+    fn unique_table_name(&self, schema: Schema) -> Result<String> {
+        let sql = format!(
+            "SELECT COUNT(*) FROM {}.sqlite_master WHERE name = ?",
+            quote_identifier(schema.name())
+        );
+        let mut stmt = self.prepare(&sql)?;
+        loop {
+            let name = format!(
+                "sqlite3_ext_vtab_{}",
+                NEXT_NAME.fetch_add(1, Ordering::Relaxed)
+            );
+            if stmt.query_row([name.as_str()], |r| Ok(r[0].get_i64()))? == 0 {
+                return Ok(name);
+            }
+        }
+    }
+}
+
+/// Returns true if SQLite would pass the argument to the module as a single argument.
+pub(super) fn is_single_arg(arg: &str) -> bool {
+    let mut depth = 0;
+    let mut chars = arg.chars();
+    while let Some(c) = chars.next() {
+        let close = match c {
+            '\'' | '"' | '`' => c,
+            '[' => ']',
+            '(' => {
+                depth += 1;
+                continue;
+            }
+            ')' if depth == 0 => return false,
+            ')' => {
+                depth -= 1;
+                continue;
+            }
+            ',' if depth == 0 => return false,
+            ';' => return false,
+            _ => continue,
+        };
+        // A doubled quote is an escaped quote, which is handled by closing and immediately
+        // reopening the quoted string.
+        if !chars.any(|c| c == close) {
+            return false;
+        }
+    }
+    depth == 0 && !arg.trim().is_empty()
+}
+
+#[cfg(all(test, feature = "static"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn single_arg() {
+        for arg in [
+            "a", "'a, b'", "\"a)\"", "[a,b]", "f(a, b)", "'it''s'", "`x`",
+        ] {
+            assert!(is_single_arg(arg), "{arg}");
+        }
+        for arg in ["", " ", "a, b", "a)", "(a", "'a", "a; b", "[a"] {
+            assert!(!is_single_arg(arg), "{arg}");
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! Background threads owned by a virtual table.
+
+use super::VTab;
+use crate::{ffi, types::*};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/// A virtual table which owns background [Worker] threads.
+///
+/// When the virtual table is disconnected or destroyed, every worker is asked to stop, and
+/// then the workers are joined, waiting no longer than [SHUTDOWN_TIMEOUT](Self::SHUTDOWN_TIMEOUT)
+/// in total. Workers which have not finished by then are detached and left to finish on their
+/// own, so a wedged worker cannot block the database connection. Each worker which is
+/// detached or which panicked is reported as a warning in the SQLite error log (see
+/// SQLITE_CONFIG_LOG). This happens before [VTab::disconnect] or
+/// [CreateVTab::destroy](super::CreateVTab::destroy) is called.
+///
+/// SQLite may disconnect the virtual table from a different thread than the one which
+/// connected it, so only the closures run by the workers are required to be `Send +
+/// 'static`; the virtual table itself does not need to be Send. [Worker] is Send but not
+/// Sync, so cursors should communicate with a worker through channels rather than through
+/// the Worker itself.
+///
+/// Enable this by passing `HasWorkers` to
+/// [sqlite3_ext_vtab](::sqlite3_ext_macro::sqlite3_ext_vtab).
+pub trait HasWorkers<'vtab>: VTab<'vtab> {
+    /// The longest time to wait for the workers to finish.
+    const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// Return the workers owned by this virtual table.
+    fn workers(&mut self) -> Vec<&mut Worker>;
+}
+
+/// A supervised background thread.
+///
+/// The thread runs on its own, so it cannot borrow from the virtual table or use the database
+/// connection. The closure passed to [spawn](Self::spawn) must be `Send + 'static`, which
+/// means that any state shared with the virtual table has to be shared through an [Arc] or a
+/// channel. A common pattern is for the virtual table to keep the sending half of a request
+/// channel, with each cursor sending a request which includes the sending half of a channel
+/// the cursor reads its rows from.
+///
+/// The worker should check [StopToken::is_stopped] regularly, and exit promptly once it
+/// returns true. Dropping a Worker requests that it stop, but does not wait for it.
+#[derive(Debug)]
+pub struct Worker {
+    name: String,
+    stop: StopToken,
+    handle: Option<JoinHandle<()>>,
+    done: mpsc::Receiver<()>,
+}
+
+/// Used by a [Worker] to determine when it should stop.
+#[derive(Debug, Clone, Default)]
+pub struct StopToken(Arc<AtomicBool>);
+
+impl StopToken {
+    /// Returns true if the worker has been asked to stop.
+    pub fn is_stopped(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+impl Worker {
+    /// Start a new thread with the given name, running the provided function.
+    pub fn spawn<F>(name: impl Into<String>, f: F) -> Result<Worker>
+    where
+        F: FnOnce(StopToken) + Send + 'static,
+    {
+        let name = name.into();
+        let stop = StopToken::default();
+        let (done_tx, done) = mpsc::channel::<()>();
+        let token = stop.clone();
+        let handle = thread::Builder::new()
+            .name(name.clone())
+            .spawn(move || {
+                // Dropped when the thread exits, even if it panics.
+                let _done = done_tx;
+                f(token)
+            })
+            .map_err(|e| Error::Module(format!("failed to spawn worker {name}: {e}")))?;
+        Ok(Worker {
+            name,
+            stop,
+            handle: Some(handle),
+            done,
+        })
+    }
+
+    /// The name of the worker.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Ask the worker to stop. This method does not wait for the worker to finish.
+    pub fn request_stop(&self) {
+        self.stop.0.store(true, Ordering::Release);
+    }
+
+    /// Returns true if the worker has finished, or has been joined or detached.
+    pub fn is_finished(&self) -> bool {
+        match self.handle {
+            Some(_) => matches!(self.done.try_recv(), Err(mpsc::TryRecvError::Disconnected)),
+            None => true,
+        }
+    }
+
+    /// Wait for the worker to finish, for no longer than the given timeout.
+    ///
+    /// Returns true if the worker finished and was joined. If the worker is still running
+    /// after the timeout, it is detached and this method returns false. If the worker
+    /// panicked, it is joined and this method returns an error. This method does not ask the
+    /// worker to stop; see [request_stop](Self::request_stop).
+    pub fn join_timeout(&mut self, timeout: Duration) -> Result<bool> {
+        let handle = match self.handle.take() {
+            Some(x) => x,
+            None => return Ok(true),
+        };
+        match self.done.recv_timeout(timeout) {
+            Err(mpsc::RecvTimeoutError::Timeout) => Ok(false),
+            _ => match handle.join() {
+                Ok(()) => Ok(true),
+                Err(_) => Err(Error::Module(format!("worker {} panicked", self.name))),
+            },
+        }
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        self.request_stop();
+    }
+}
+
+/// Stop all of the workers of the virtual table, waiting no longer than
+/// [HasWorkers::SHUTDOWN_TIMEOUT].
+pub(super) fn stop_workers<'vtab, T: HasWorkers<'vtab>>(vtab: &mut T) {
+    let deadline = Instant::now() + T::SHUTDOWN_TIMEOUT;
+    let mut workers = vtab.workers();
+    for w in workers.iter() {
+        w.request_stop();
+    }
+    for w in workers.iter_mut() {
+        // The virtual table is going away, so the error log is the only place to report
+        // problems.
+        let msg = match w.join_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(true) => continue,
+            Ok(false) => format!(
+                "worker {} did not stop within {:?} and was detached",
+                w.name(),
+                T::SHUTDOWN_TIMEOUT
+            ),
+            Err(e) => e.to_string(),
+        };
+        ffi::log_warning(&msg);
+    }
+}
@@ -0,0 +1,58 @@
+//! Tests for automatic extensions. Automatic extensions are global to the process, so these
+//! tests live in their own binary and run as a single test.
+#![cfg(modern_sqlite)]
+use sqlite3_ext::{function::*, *};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static LOADS: AtomicUsize = AtomicUsize::new(0);
+
+#[sqlite3_ext_init]
+fn auto_init(db: &Connection) -> Result<()> {
+    LOADS.fetch_add(1, Ordering::SeqCst);
+    let opts = FunctionOptions::default().set_n_args(0);
+    db.create_scalar_function("auto_function", &opts, |c, _| c.set_result(1))
+}
+
+#[sqlite3_ext_init]
+fn other_init(db: &Connection) -> Result<()> {
+    let opts = FunctionOptions::default().set_n_args(0);
+    db.create_scalar_function("other_function", &opts, |c, _| c.set_result(2))
+}
+
+fn has_function(db: &Connection, name: &str) -> bool {
+    db.query_row(&format!("SELECT {name}()"), (), |_| Ok(()))
+        .is_ok()
+}
+
+#[test]
+fn auto_extension() -> Result<()> {
+    let before = Database::open(":memory:")?;
+
+    auto_init.register_auto()?;
+    auto_init.register_auto()?;
+    let db = Database::open(":memory:")?;
+    assert!(has_function(&db, "auto_function"));
+    assert!(!has_function(&before, "auto_function"));
+    // Registering twice only loads the extension once.
+    assert_eq!(LOADS.load(Ordering::SeqCst), 1);
+
+    assert!(auto_init.cancel_auto()?);
+    assert!(!auto_init.cancel_auto()?);
+    let after = Database::open(":memory:")?;
+    assert!(!has_function(&after, "auto_function"));
+    // Connections which were already open keep the extension.
+    assert!(has_function(&db, "auto_function"));
+
+    auto_init.register_auto()?;
+    other_init.register_auto()?;
+    let db = Database::open(":memory:")?;
+    assert!(has_function(&db, "auto_function"));
+    assert!(has_function(&db, "other_function"));
+
+    reset_auto_extensions();
+    let db = Database::open(":memory:")?;
+    assert!(!has_function(&db, "auto_function"));
+    assert!(!has_function(&db, "other_function"));
+    assert!(!auto_init.cancel_auto()?);
+    Ok(())
+}
@@ -0,0 +1,48 @@
+//! Tests for the global configuration. These live in their own test binary, because the
+//! configuration must be applied before SQLite is initialized.
+use sqlite3_ext::{config::*, *};
+
+fn lookaside_status(db: &Connection, op: i32) -> (i32, i32) {
+    let (mut cur, mut hi) = (0, 0);
+    let rc = unsafe { ffi::sqlite3_db_status(db.as_mut_ptr(), op, &mut cur, &mut hi, 0) };
+    assert_eq!(rc, ffi::SQLITE_OK);
+    (cur, hi)
+}
+
+#[test]
+fn config() -> Result<()> {
+    let config = ConfigBuilder::new()
+        .threading(ThreadingMode::Serialized)
+        .memstatus(true)
+        .lookaside(128, 16);
+    #[cfg(modern_sqlite)]
+    let config = config.uri(true);
+    config.apply()?;
+
+    let db = Database::open(":memory:")?;
+    db.execute("CREATE TABLE tbl(a, b)", ())?;
+    db.execute(
+        "WITH RECURSIVE c(x) AS (VALUES(1) UNION ALL SELECT x+1 FROM c WHERE x < 100)
+        INSERT INTO tbl SELECT x, 'value ' || x FROM c",
+        (),
+    )?;
+    db.query_row("SELECT COUNT(*), group_concat(b) FROM tbl", (), |_| Ok(()))?;
+    // Some distributions compile SQLite without the lookaside allocator.
+    let omit_lookaside =
+        unsafe { ffi::sqlite3_compileoption_used(c"OMIT_LOOKASIDE".as_ptr()) } != 0;
+    if !omit_lookaside {
+        let (_, used) = lookaside_status(&db, ffi::SQLITE_DBSTATUS_LOOKASIDE_USED);
+        assert!(used > 0 && used <= 16, "lookaside high-water is {used}");
+        let (_, full) = lookaside_status(&db, ffi::SQLITE_DBSTATUS_LOOKASIDE_MISS_FULL);
+        assert!(full > 0, "lookaside never filled");
+    }
+
+    assert_eq!(
+        ConfigBuilder::new().memstatus(false).apply(),
+        Err(Error::Sqlite(
+            ffi::SQLITE_MISUSE,
+            Some("SQLite must be configured before it is initialized".to_owned())
+        ))
+    );
+    Ok(())
+}
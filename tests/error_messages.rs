@@ -0,0 +1,111 @@
+//! Checks that error messages passed to SQLite are not leaked. SQLite's memory counter is
+//! global, so these tests live in their own binary and run one at a time.
+use sqlite3_ext::{vtab::*, *};
+use std::{ffi::CStr, os::raw::c_char, ptr::null_mut, sync::Mutex};
+
+static SERIAL: Mutex<()> = Mutex::new(());
+
+fn memory_used() -> i64 {
+    unsafe { ffi::sqlite3_memory_used() }
+}
+
+/// A table which rejects every change with a long message.
+#[sqlite3_ext_vtab(StandardModule, UpdateVTab)]
+struct RejectVTab;
+
+impl<'vtab> VTab<'vtab> for RejectVTab {
+    type Aux = ();
+    type Cursor = EmptyCursor;
+
+    fn connect(_: &VTabConnection, _: &'vtab Self::Aux, _: &[&str]) -> Result<(String, Self)> {
+        Ok(("CREATE TABLE x (value)".to_owned(), RejectVTab))
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        Ok(EmptyCursor)
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for RejectVTab {
+    fn create(db: &VTabConnection, aux: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        Self::connect(db, aux, args)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+impl<'vtab> UpdateVTab<'vtab> for RejectVTab {
+    fn update(&'vtab self, _: &mut ChangeInfo) -> Result<i64> {
+        Err(Error::Module("x".repeat(4000)).with_context("table \"reject\""))
+    }
+}
+
+struct EmptyCursor;
+
+impl VTabCursor for EmptyCursor {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        true
+    }
+
+    fn column(&mut self, _: usize, _: &ColumnContext) -> Result<()> {
+        Ok(())
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(0)
+    }
+}
+
+#[test]
+fn failing_updates() -> Result<()> {
+    let _serial = SERIAL.lock().unwrap();
+    let db = Database::open(":memory:")?;
+    db.create_module("reject", RejectVTab::module(), ())?;
+    db.execute("CREATE VIRTUAL TABLE tbl USING reject", ())?;
+    let mut stmt = db.prepare("INSERT INTO tbl VALUES (1)")?;
+    let mut fail = || {
+        let err = stmt.execute(()).unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.starts_with("table \"reject\": xxx"), "{msg}");
+        assert!(msg.len() <= 1024 && msg.ends_with("..."), "{msg}");
+    };
+    fail();
+    let before = memory_used();
+    for _ in 0..5000 {
+        fail();
+    }
+    assert_eq!(memory_used(), before);
+    Ok(())
+}
+
+#[test]
+fn replace_message() {
+    let _serial = SERIAL.lock().unwrap();
+    let before = memory_used();
+    let mut msg: *mut c_char = null_mut();
+    for i in 0..5000 {
+        let rc = unsafe { ffi::handle_error(format!("failure {i}"), &mut msg) };
+        assert_eq!(rc, ffi::SQLITE_ERROR);
+    }
+    let text = unsafe { CStr::from_ptr(msg) };
+    assert_eq!(text.to_str(), Ok("failure 4999"));
+    // An error without a message frees the previous one.
+    let rc = unsafe { ffi::handle_error(SQLITE_CONSTRAINT, &mut msg) };
+    assert_eq!(rc, ffi::SQLITE_CONSTRAINT);
+    assert!(msg.is_null());
+    assert_eq!(memory_used(), before);
+}
@@ -0,0 +1,254 @@
+//! Tests for the entry points generated by sqlite3_ext_init.
+use sqlite3_ext::{function::*, *};
+use std::{
+    cell::Cell,
+    ffi::CStr,
+    os::raw::{c_char, c_int},
+    ptr::{null, null_mut},
+    rc::Rc,
+};
+
+const OPTS: FunctionOptions = FunctionOptions::default().set_n_args(0);
+
+#[sqlite3_ext_init(export = transient_entry)]
+fn transient_init(db: &Connection) -> Result<()> {
+    let state = Rc::new(1);
+    db.create_scalar_function("state", &OPTS, move |c, _| c.set_result(*state as i64))
+}
+
+#[sqlite3_ext_init(export = unload_safe_entry, unload_safe)]
+fn unload_safe_init(db: &Connection) -> Result<()> {
+    let state = Rc::new(1);
+    db.create_scalar_function("state", &OPTS, move |c, _| c.set_result(*state as i64))
+}
+
+#[sqlite3_ext_init(export = transient_plain_entry, unload_safe)]
+fn transient_plain_init(db: &Connection) -> Result<()> {
+    db.create_scalar_function("state", &OPTS, |c, _| c.set_result(1))
+}
+
+#[sqlite3_ext_init(export = transient_leaky_entry, unload_safe)]
+fn transient_leaky_init(db: &Connection) -> Result<()> {
+    let state = Rc::new(1);
+    db.create_scalar_function_leaky("state", &OPTS, move |c, _| c.set_result(*state as i64))
+}
+
+#[sqlite3_ext_init(export = collation_needed_entry, unload_safe)]
+fn collation_needed_init(db: &Connection) -> Result<()> {
+    let state = Rc::new(1);
+    db.set_collation_needed_func(move |_| {
+        let _ = &state;
+    })
+}
+
+#[cfg(modern_sqlite)]
+#[sqlite3_ext_init(export = persistent_entry, persistent, unload_safe)]
+fn persistent_init(db: &Connection) -> Result<()> {
+    let state = Rc::new(1);
+    db.create_scalar_function("state", &OPTS, move |c, _| c.set_result(*state as i64))
+}
+
+thread_local! {
+    static PERSISTENT_LOAD: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+fn record_load() -> Result<()> {
+    PERSISTENT_LOAD.with(|p| p.set(Some(Extension::is_persistent_load())));
+    Ok(())
+}
+
+#[sqlite3_ext_init(export = record_transient_entry)]
+fn record_transient_init(_: &Connection) -> Result<()> {
+    record_load()
+}
+
+#[sqlite3_ext_init(export = record_lenient_entry, persistent)]
+fn record_lenient_init(_: &Connection) -> Result<()> {
+    record_load()
+}
+
+#[sqlite3_ext_init(export = record_required_entry, persistent(required))]
+fn record_required_init(_: &Connection) -> Result<()> {
+    record_load()
+}
+
+extern "C" {
+    fn transient_entry(db: *mut ffi::sqlite3, err: *mut *mut c_char, api: *mut ()) -> c_int;
+    fn unload_safe_entry(db: *mut ffi::sqlite3, err: *mut *mut c_char, api: *mut ()) -> c_int;
+    fn transient_plain_entry(db: *mut ffi::sqlite3, err: *mut *mut c_char, api: *mut ()) -> c_int;
+    fn transient_leaky_entry(db: *mut ffi::sqlite3, err: *mut *mut c_char, api: *mut ()) -> c_int;
+    fn collation_needed_entry(db: *mut ffi::sqlite3, err: *mut *mut c_char, api: *mut ()) -> c_int;
+    #[cfg(modern_sqlite)]
+    fn persistent_entry(db: *mut ffi::sqlite3, err: *mut *mut c_char, api: *mut ()) -> c_int;
+    #[cfg(feature = "testing")]
+    fn record_transient_entry(db: *mut ffi::sqlite3, err: *mut *mut c_char, api: *mut ()) -> c_int;
+    #[cfg(feature = "testing")]
+    fn record_lenient_entry(db: *mut ffi::sqlite3, err: *mut *mut c_char, api: *mut ()) -> c_int;
+    #[cfg(feature = "testing")]
+    fn record_required_entry(db: *mut ffi::sqlite3, err: *mut *mut c_char, api: *mut ()) -> c_int;
+}
+
+type Entry = unsafe extern "C" fn(*mut ffi::sqlite3, *mut *mut c_char, *mut ()) -> c_int;
+
+/// Invoke the entry point the way SQLite would, returning the error message on failure.
+fn load(db: &Database, entry: Entry) -> std::result::Result<c_int, String> {
+    let mut err: *mut c_char = null_mut();
+    let rc = unsafe { entry(db.as_mut_ptr(), &mut err, null::<()>() as _) };
+    if err.is_null() {
+        return Ok(rc);
+    }
+    let msg = unsafe { CStr::from_ptr(err) }
+        .to_string_lossy()
+        .into_owned();
+    unsafe { ffi::sqlite3_free(err as _) };
+    Err(msg)
+}
+
+/// Invoke the entry point, returning its result and the value of
+/// Extension::is_persistent_load seen by the init function, if it ran.
+#[cfg(feature = "testing")]
+fn load_recorded(
+    db: &Database,
+    entry: Entry,
+) -> (std::result::Result<c_int, String>, Option<bool>) {
+    PERSISTENT_LOAD.with(|p| p.set(None));
+    let ret = load(db, entry);
+    (ret, PERSISTENT_LOAD.with(|p| p.get()))
+}
+
+fn call_state(db: &Database) -> Result<i64> {
+    db.query_row("SELECT state()", (), |r| Ok(r[0].get_i64()))
+}
+
+#[test]
+fn transient_allows_drop() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    assert_eq!(load(&db, transient_entry), Ok(ffi::SQLITE_OK));
+    assert_eq!(call_state(&db)?, 1);
+    Ok(())
+}
+
+#[test]
+fn unload_safe_refuses_drop() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    let err = load(&db, unload_safe_entry).unwrap_err();
+    assert_eq!(
+        err,
+        "scalar function has state which must be dropped and cannot be registered by a non-persistent extension"
+    );
+    assert!(call_state(&db).is_err());
+    Ok(())
+}
+
+#[test]
+fn unload_safe_refuses_collation_needed() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    let err = load(&db, collation_needed_entry).unwrap_err();
+    assert_eq!(
+        err,
+        "collation needed callback has state which must be dropped and cannot be registered by a non-persistent extension"
+    );
+    Ok(())
+}
+
+#[test]
+fn transient_plain() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    assert_eq!(load(&db, transient_plain_entry), Ok(ffi::SQLITE_OK));
+    assert_eq!(call_state(&db)?, 1);
+    Ok(())
+}
+
+#[test]
+fn transient_leaky() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    assert_eq!(load(&db, transient_leaky_entry), Ok(ffi::SQLITE_OK));
+    assert_eq!(call_state(&db)?, 1);
+    Ok(())
+}
+
+#[test]
+#[cfg(modern_sqlite)]
+fn persistent() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    assert_eq!(
+        load(&db, persistent_entry),
+        Ok(ffi::SQLITE_OK_LOAD_PERMANENTLY)
+    );
+    assert_eq!(call_state(&db)?, 1);
+    Ok(())
+}
+
+#[test]
+fn direct_call() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    unload_safe_init(&db)?;
+    assert_eq!(call_state(&db)?, 1);
+    Ok(())
+}
+
+#[test]
+fn load_mode_restored_after_panic() -> Result<()> {
+    let ret =
+        std::panic::catch_unwind(|| Extension::enter_init(false, true, || panic!("init failed")));
+    assert!(ret.is_err());
+    // The panicking init function must not leave the unload_safe check enabled.
+    let db = Database::open(":memory:")?;
+    unload_safe_init(&db)?;
+    assert_eq!(call_state(&db)?, 1);
+    Ok(())
+}
+
+#[test]
+#[cfg(all(modern_sqlite, feature = "testing"))]
+fn persistence_modes() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    assert_eq!(
+        load_recorded(&db, record_transient_entry),
+        (Ok(ffi::SQLITE_OK), Some(false))
+    );
+    assert_eq!(
+        load_recorded(&db, record_lenient_entry),
+        (Ok(ffi::SQLITE_OK_LOAD_PERMANENTLY), Some(true))
+    );
+    assert_eq!(
+        load_recorded(&db, record_required_entry),
+        (Ok(ffi::SQLITE_OK_LOAD_PERMANENTLY), Some(true))
+    );
+    assert!(record_required_init.is_persistent());
+    assert!(!record_transient_init.is_persistent());
+
+    // Calling the init function directly is not a persistent load.
+    record_required_init(&db)?;
+    assert_eq!(PERSISTENT_LOAD.with(|p| p.get()), Some(false));
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "testing")]
+fn persistence_fallback() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    SQLITE_VERSION.with_override(3_013_000, || {
+        assert_eq!(
+            load_recorded(&db, record_transient_entry),
+            (Ok(ffi::SQLITE_OK), Some(false))
+        );
+        // The lenient mode loads the extension normally.
+        assert_eq!(
+            load_recorded(&db, record_lenient_entry),
+            (Ok(ffi::SQLITE_OK), Some(false))
+        );
+        // The required mode fails without running the init function.
+        assert_eq!(
+            load_recorded(&db, record_required_entry),
+            (
+                Err(
+                    "extension must be loaded persistently, which requires SQLite 3.14.0 or above"
+                        .to_owned()
+                ),
+                None
+            )
+        );
+    });
+    Ok(())
+}
@@ -0,0 +1,86 @@
+//! Tests for the registration functions generated by sqlite3_ext_fn.
+use sqlite3_ext::{function::*, *};
+
+#[sqlite3_ext_fn(n_args = 1, deterministic, export = "double_it")]
+fn double(ctx: &Context, args: &mut [&mut ValueRef]) -> Result<()> {
+    ctx.set_result(args[0].get_i64() * 2)
+}
+
+/// Joins its arguments with the separator passed as user data.
+#[sqlite3_ext_fn(n_args = 1, export = "join_all", user_data = String::from("-"))]
+struct JoinAll {
+    sep: String,
+    parts: Vec<String>,
+}
+
+impl FromUserData<String> for JoinAll {
+    fn from_user_data(sep: &String) -> Self {
+        JoinAll {
+            sep: sep.clone(),
+            parts: vec![],
+        }
+    }
+}
+
+impl AggregateFunction<String> for JoinAll {
+    fn step(&mut self, _: &Context, args: &mut [&mut ValueRef]) -> Result<()> {
+        self.parts.push(args[0].get_str()?.to_owned());
+        Ok(())
+    }
+
+    fn value(&self, ctx: &Context) -> Result<()> {
+        ctx.set_result(self.parts.join(&self.sep))
+    }
+
+    fn inverse(&mut self, _: &Context, _: &mut [&mut ValueRef]) -> Result<()> {
+        self.parts.remove(0);
+        Ok(())
+    }
+}
+
+/// An aggregate with no user data.
+#[sqlite3_ext_fn(n_args = 0, export = "count_rows")]
+#[derive(Default)]
+struct CountRows(i64);
+
+impl AggregateFunction<()> for CountRows {
+    fn step(&mut self, _: &Context, _: &mut [&mut ValueRef]) -> Result<()> {
+        self.0 += 1;
+        Ok(())
+    }
+
+    fn value(&self, ctx: &Context) -> Result<()> {
+        ctx.set_result(self.0)
+    }
+
+    fn inverse(&mut self, _: &Context, _: &mut [&mut ValueRef]) -> Result<()> {
+        self.0 -= 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn register() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    register_double(&db)?;
+    register_join_all(&db)?;
+    register_count_rows(&db)?;
+    let doubled = db.query_row("SELECT double_it(2)", (), |r| Ok(r[0].get_i64()))?;
+    assert_eq!(doubled, 4);
+    let ret = db.query_row(
+        "SELECT join_all(y), count_rows() FROM (SELECT 'a' AS y UNION ALL SELECT 'b')",
+        (),
+        |r| Ok((r[0].get_str()?.to_owned(), r[1].get_i64())),
+    );
+    let (joined, count) = ret?;
+    assert_eq!(joined, "a-b");
+    assert_eq!(count, 2);
+    // The options are applied to the registered function.
+    assert_eq!(
+        db.query_row("SELECT double_it(1, 2)", (), |_| Ok(()))
+            .unwrap_err()
+            .to_string(),
+        "wrong number of arguments to function double_it()"
+    );
+    Ok(())
+}
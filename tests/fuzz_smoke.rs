@@ -0,0 +1,109 @@
+//! Runs the fuzz harnesses over a fixed set of seeds and a short deterministic stream of
+//! generated inputs, so that regressions in panic-freedom are caught without cargo-fuzz.
+//! Longer runs use the targets in the `fuzz` directory.
+#[path = "../fuzz/src/lib.rs"]
+#[allow(dead_code)]
+mod harness;
+
+const ITERATIONS: usize = 2000;
+
+/// Fragments which are likely to be interesting to the SQLite tokenizer.
+const FRAGMENTS: &[&str] = &[
+    "SELECT ",
+    "1",
+    ";",
+    " ",
+    "'",
+    "\"",
+    "`",
+    "[",
+    "]",
+    "(",
+    ")",
+    ",",
+    "=",
+    "x'00'",
+    "--",
+    "\n",
+    "/*",
+    "*/",
+    "\0",
+    "CREATE TABLE t(a)",
+    "é",
+    "\u{FFFD}",
+    "a",
+    "?",
+    "$x",
+];
+
+const SEEDS: &[&str] = &[
+    "",
+    ";",
+    "SELECT 1; SELECT 2",
+    "SELECT 1;\0SELECT 2",
+    "\0",
+    "-- comment",
+    "/* unterminated",
+    "'unterminated",
+    "a, 'b', \"c\", [d], `e`",
+    "a=(1,2), b='x)'",
+    "é\0ü",
+];
+
+/// A xorshift generator, so that failures are reproducible.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn bytes(&mut self) -> Vec<u8> {
+        let len = (self.next() % 64) as usize;
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+
+    fn sql(&mut self) -> String {
+        let len = (self.next() % 16) as usize;
+        (0..len)
+            .map(|_| FRAGMENTS[(self.next() % FRAGMENTS.len() as u64) as usize])
+            .collect()
+    }
+}
+
+#[test]
+fn vtab_args() {
+    let mut rng = Rng(0x2545F4914F6CDD1D);
+    for seed in SEEDS {
+        harness::vtab_args(seed);
+    }
+    for _ in 0..ITERATIONS {
+        harness::vtab_args(&rng.sql());
+    }
+}
+
+#[test]
+fn value_roundtrip() {
+    let mut rng = Rng(0x9E3779B97F4A7C15);
+    for seed in SEEDS {
+        harness::value_roundtrip(seed.as_bytes());
+    }
+    harness::value_roundtrip(&[0xff, 0, 0xfe]);
+    for _ in 0..ITERATIONS {
+        harness::value_roundtrip(&rng.bytes());
+    }
+}
+
+#[test]
+fn prepare_first() {
+    let mut rng = Rng(0xD1B54A32D192ED03);
+    for seed in SEEDS {
+        harness::prepare_first(seed);
+    }
+    for _ in 0..ITERATIONS {
+        harness::prepare_first(&rng.sql());
+    }
+}
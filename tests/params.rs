@@ -0,0 +1,110 @@
+//! Tests for binding parameters by reference. These live in their own test binary because
+//! they install a global allocator to count allocations, and because SQLite's memory
+//! counter is global.
+use sqlite3_ext::*;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+const BLOB_LEN: usize = 4 * 1024 * 1024;
+
+/// Counts allocations which are at least as large as the test BLOB.
+struct CountingAlloc;
+
+static LARGE_ALLOCS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.size() >= BLOB_LEN {
+            LARGE_ALLOCS.fetch_add(1, Ordering::SeqCst);
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn memory_used() -> i64 {
+    unsafe { ffi::sqlite3_memory_used() }
+}
+
+#[test]
+fn bind_by_reference() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("sqlite3_ext_params_{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let start = memory_used();
+    let a = Database::open(&path)?;
+    let b = Database::open(&path)?;
+    a.execute("CREATE TABLE tbl(id, data)", ())?;
+
+    let before = LARGE_ALLOCS.load(Ordering::SeqCst);
+    let params = vec![
+        Value::Integer(1),
+        Value::Blob(Blob::from(&[0xa5u8; BLOB_LEN][..])),
+    ];
+    assert_eq!(LARGE_ALLOCS.load(Ordering::SeqCst) - before, 1);
+
+    let mut stmt = b.prepare("INSERT INTO tbl VALUES (?, ?)")?;
+    stmt.execute(&params)?;
+    // SQLite copies a BLOB bound by reference into its own memory, which the allocator
+    // above does not see. Track that memory from here on.
+    let sqlite_used = memory_used();
+    unsafe { ffi::sqlite3_memory_highwater(1) };
+
+    // The first attempt fails because of the lock held by the other connection, the second
+    // succeeds after the lock is released.
+    a.execute("BEGIN EXCLUSIVE", ())?;
+    let mut calls = 0;
+    stmt.retry(&params, 3, Duration::from_millis(1), |stmt, params| {
+        calls += 1;
+        if calls == 2 {
+            a.execute("COMMIT", ())?;
+        }
+        stmt.execute(params)
+    })?;
+    assert_eq!(calls, 2);
+    assert_eq!(LARGE_ALLOCS.load(Ordering::SeqCst) - before, 1);
+
+    // Retrying gives up after the last attempt.
+    a.execute("BEGIN EXCLUSIVE", ())?;
+    let mut calls = 0;
+    let err = stmt
+        .retry(&params, 3, Duration::from_millis(1), |stmt, params| {
+            calls += 1;
+            stmt.execute(params)
+        })
+        .unwrap_err();
+    assert!(matches!(err, Error::Sqlite(ffi::SQLITE_BUSY, _)), "{err:?}");
+    assert_eq!(calls, 3);
+    a.execute("COMMIT", ())?;
+    // Each attempt releases the copy made by the previous one. At most the copy and the
+    // record built from it are alive at once.
+    let blob_len = BLOB_LEN as i64;
+    assert!(memory_used() - sqlite_used < blob_len, "{}", memory_used());
+    let peak = unsafe { ffi::sqlite3_memory_highwater(0) };
+    assert!(peak - sqlite_used < 3 * blob_len, "{peak}");
+
+    let (count, len): (i64, i64) =
+        a.query_row_as("SELECT COUNT(*), SUM(LENGTH(data)) FROM tbl", ())?;
+    assert_eq!((count, len), (2, 2 * BLOB_LEN as i64));
+    assert_eq!(LARGE_ALLOCS.load(Ordering::SeqCst) - before, 1);
+    drop((stmt, params));
+
+    let names = ["one", "two"];
+    let mut stmt = a.prepare("SELECT ? || ?")?;
+    for _ in 0..2 {
+        let ret: (String,) = stmt.query_row_as(names)?;
+        assert_eq!(ret.0, "onetwo");
+    }
+    drop((stmt, a, b));
+    assert_eq!(memory_used(), start);
+    std::fs::remove_file(path).unwrap();
+    Ok(())
+}
@@ -0,0 +1,139 @@
+//! Tests for the rusqlite compatibility layer. The bodies of `half` and
+//! `regexp_with_auxilliary` are copied from rusqlite's tests.
+use regex::Regex;
+use sqlite3_ext::{compat::rusqlite_functions::*, Connection, Database, FromValue};
+use std::os::raw::c_double;
+
+fn half(ctx: &Context<'_>) -> Result<c_double> {
+    assert_eq!(ctx.len(), 1, "called with unexpected number of arguments");
+    let value = ctx.get::<c_double>(0)?;
+    Ok(value / 2f64)
+}
+
+fn regexp_with_auxilliary(ctx: &Context<'_>) -> Result<bool> {
+    assert_eq!(ctx.len(), 2, "called with unexpected number of arguments");
+    type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+    let regexp: std::sync::Arc<Regex> = ctx.get_or_create_aux(0, |vr| -> Result<_, BoxError> {
+        Ok(Regex::new(vr.as_str()?)?)
+    })?;
+
+    let is_match = {
+        let text = ctx
+            .get_raw(1)
+            .as_str()
+            .map_err(|e| Error::UserFunctionError(e.into()))?;
+
+        regexp.is_match(text)
+    };
+
+    Ok(is_match)
+}
+
+fn query_i64(db: &Connection, sql: &str) -> sqlite3_ext::Result<i64> {
+    db.query_row(sql, (), |r| Ok(r[0].get_i64()))
+}
+
+fn query_str(db: &Connection, sql: &str) -> sqlite3_ext::Result<String> {
+    db.query_row(sql, (), |r| Ok(r[0].get_str()?.to_owned()))
+}
+
+#[test]
+fn function_half() -> sqlite3_ext::Result<()> {
+    let db = Database::open(":memory:")?;
+    register_rusqlite_style_fn(
+        &db,
+        "half",
+        1,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        half,
+    )?;
+    let result = db.query_row("SELECT half(6)", (), |r| Ok(r[0].get_f64()))?;
+    assert!((3f64 - result).abs() < f64::EPSILON);
+
+    let err = db
+        .query_row("SELECT half('six')", (), |_| Ok(()))
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid function parameter type Text at index 0"
+    );
+    Ok(())
+}
+
+#[test]
+fn function_regexp_with_auxilliary() -> sqlite3_ext::Result<()> {
+    let db = Database::open(":memory:")?;
+    db.execute("CREATE TABLE foo (x string)", ())?;
+    db.execute("INSERT INTO foo VALUES ('lisa'), ('lXsi'), ('lisX')", ())?;
+    register_rusqlite_style_fn(
+        &db,
+        "regexp",
+        2,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        regexp_with_auxilliary,
+    )?;
+
+    assert_eq!(query_i64(&db, "SELECT regexp('l.s[aeiouy]', 'lisa')")?, 1);
+    assert_eq!(
+        query_i64(
+            &db,
+            "SELECT COUNT(*) FROM foo WHERE regexp('l.s[aeiouy]', x) == 1"
+        )?,
+        2
+    );
+
+    let err = db
+        .query_row("SELECT regexp('(', 'lisa')", (), |_| Ok(()))
+        .unwrap_err();
+    assert!(err.to_string().starts_with("regex parse error"), "{err}");
+    Ok(())
+}
+
+#[test]
+fn conversions() -> sqlite3_ext::Result<()> {
+    let db = Database::open(":memory:")?;
+    let flags = FunctionFlags::default();
+    register_rusqlite_style_fn(&db, "as_i8", 1, flags, |ctx| ctx.get::<i8>(0))?;
+    register_rusqlite_style_fn(&db, "describe", 1, flags, |ctx| {
+        let value: Value = ctx.get(0)?;
+        Ok(format!("{}", value.data_type()))
+    })?;
+    register_rusqlite_style_fn(&db, "maybe_blob", 1, flags, |ctx| {
+        Ok(ctx.get::<Option<String>>(0)?.map(String::into_bytes))
+    })?;
+
+    assert_eq!(query_i64(&db, "SELECT as_i8(-5)")?, -5);
+    let err = db
+        .query_row("SELECT as_i8(300)", (), |_| Ok(()))
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Integer 300 is out of range at index 0");
+
+    assert_eq!(query_str(&db, "SELECT describe(1.5)")?, "Real");
+    assert_eq!(query_str(&db, "SELECT describe(x'00')")?, "Blob");
+    assert_eq!(query_str(&db, "SELECT typeof(maybe_blob('a'))")?, "blob");
+    assert_eq!(query_str(&db, "SELECT typeof(maybe_blob(NULL))")?, "null");
+    Ok(())
+}
+
+#[test]
+fn stateful() -> sqlite3_ext::Result<()> {
+    let db = Database::open(":memory:")?;
+    let mut count = 0;
+    register_rusqlite_style_fn(&db, "counter", 0, FunctionFlags::SQLITE_UTF8, move |_| {
+        count += 1;
+        Ok(count)
+    })?;
+    assert_eq!(
+        query_i64(&db, "SELECT SUM(counter()) FROM (VALUES (1), (2), (3))")?,
+        6
+    );
+
+    register_rusqlite_style_fn(&db, "recurse", 0, FunctionFlags::SQLITE_UTF8, |ctx| {
+        Ok(query_i64(ctx.db(), "SELECT recurse()")?)
+    })?;
+    let err = db
+        .query_row("SELECT recurse()", (), |_| Ok(()))
+        .unwrap_err();
+    assert_eq!(err.to_string(), "function cannot be invoked recursively");
+    Ok(())
+}
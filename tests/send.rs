@@ -0,0 +1,55 @@
+//! Tests for moving a database connection between threads with the send feature.
+use sqlite3_ext::{function::*, *};
+use std::thread;
+
+fn assert_send<T: Send>() {}
+
+#[test]
+fn send_markers() {
+    assert_send::<SendDatabase>();
+    assert_send::<Value>();
+}
+
+#[test]
+fn move_database() -> Result<()> {
+    let mut db = unsafe { Database::open(":memory:")?.into_send() }.map_err(|(e, _)| e)?;
+    db.call(|db| {
+        db.execute("CREATE TABLE tbl(a, b)", ())?;
+        db.create_scalar_function("double", &FunctionOptions::default(), |ctx, args| {
+            ctx.set_result(args[0].get_i64() * 2)
+        })
+    })?;
+
+    let (mut db, value) = thread::spawn(move || {
+        let value = db.call(|db| {
+            db.execute(
+                "INSERT INTO tbl VALUES (double(21), ?)",
+                [Value::Blob(Blob::from([1, 2, 3]))],
+            )?;
+            let (a, b): (i64, Value) = db.query_row_as("SELECT a, b FROM tbl", ())?;
+            assert_eq!(a, 42);
+            Ok(b)
+        });
+        (db, value)
+    })
+    .join()
+    .unwrap();
+
+    // The Value came back from the other thread.
+    assert_eq!(value?, Value::Blob(Blob::from([1, 2, 3])));
+    let count =
+        db.call(|db| db.query_row("SELECT COUNT(*) FROM tbl", (), |r| Ok(r[0].get_i64())))?;
+    assert_eq!(count, 1);
+    db.into_inner().close().map_err(|(e, _)| e)
+}
+
+#[test]
+fn requires_serialized() {
+    let db = Database::open_with_flags(":memory:", OpenFlags::DEFAULT | OpenFlags::UNSAFE_NOMUTEX)
+        .unwrap();
+    let (err, db) = unsafe { db.into_send() }.unwrap_err();
+    assert_eq!(err, SQLITE_MISUSE);
+    assert_eq!(err.to_string(), "the connection is not in serialized mode");
+    // The connection is still usable.
+    db.execute("CREATE TABLE tbl(a)", ()).unwrap();
+}
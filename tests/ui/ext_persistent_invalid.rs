@@ -0,0 +1,13 @@
+use sqlite3_ext::*;
+
+#[sqlite3_ext_init(export = optional_entry, persistent(optional))]
+fn optional_init(_: &Connection) -> Result<()> {
+    Ok(())
+}
+
+#[sqlite3_ext_init(persistent(required))]
+fn unexported_init(_: &Connection) -> Result<()> {
+    Ok(())
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+use sqlite3_ext::*;
+
+#[sqlite3_ext_fn(n_args = 0, export = "scalar", user_data = 1)]
+fn scalar_with_user_data(ctx: &function::Context, _: &mut [&mut ValueRef]) -> Result<()> {
+    ctx.set_result(1)
+}
+
+#[sqlite3_ext_fn(n_args = 0, user_data = 1)]
+fn user_data_without_export(ctx: &function::Context, _: &mut [&mut ValueRef]) -> Result<()> {
+    ctx.set_result(1)
+}
+
+#[sqlite3_ext_fn(export = "a", export = "b")]
+fn exported_twice(ctx: &function::Context, _: &mut [&mut ValueRef]) -> Result<()> {
+    ctx.set_result(1)
+}
+
+#[sqlite3_ext_fn(export = name)]
+fn export_not_a_string(ctx: &function::Context, _: &mut [&mut ValueRef]) -> Result<()> {
+    ctx.set_result(1)
+}
+
+fn main() {}
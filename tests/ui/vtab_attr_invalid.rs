@@ -0,0 +1,12 @@
+use sqlite3_ext::*;
+
+#[sqlite3_ext_vtab(StandardModule, name = "a", name = "b")]
+struct DuplicateName {}
+
+#[sqlite3_ext_vtab(StandardModule, UpdateVTab, RenameVTab, UpdateVTab)]
+struct DuplicateTrait {}
+
+#[sqlite3_ext_vtab(StandardModule, name = "")]
+struct EmptyName {}
+
+fn main() {}
@@ -0,0 +1,47 @@
+use sqlite3_ext::{vtab::*, *};
+
+#[sqlite3_ext_vtab(EponymousOnlyModule, UpdateVTab, name = "my_vtab")]
+struct MyVTab {}
+
+impl VTab<'_> for MyVTab {
+    type Aux = ();
+    type Cursor = MyCursor;
+
+    fn connect(_: &VTabConnection, _: &Self::Aux, _: &[&str]) -> Result<(String, Self)> {
+        todo!()
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        todo!()
+    }
+
+    fn open(&self) -> Result<Self::Cursor> {
+        todo!()
+    }
+}
+
+struct MyCursor {}
+
+impl VTabCursor for MyCursor {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        todo!()
+    }
+
+    fn next(&mut self) -> Result<()> {
+        todo!()
+    }
+
+    fn eof(&mut self) -> bool {
+        todo!()
+    }
+
+    fn column(&mut self, _: usize, _: &ColumnContext) -> Result<()> {
+        todo!()
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        todo!()
+    }
+}
+
+fn main() {}
@@ -0,0 +1,133 @@
+//! Tests for virtual table arguments which contain invalid UTF-8.
+use sqlite3_ext::{vtab::*, *};
+use std::{cell::RefCell, ptr::null_mut, rc::Rc};
+
+type Log = Rc<RefCell<Vec<(Vec<String>, bool)>>>;
+
+/// A virtual table which records the arguments it was created with.
+#[sqlite3_ext_vtab(StandardModule)]
+struct ArgsVTab;
+
+impl<'vtab> VTab<'vtab> for ArgsVTab {
+    type Aux = Log;
+    type Cursor = EmptyCursor;
+
+    fn connect(
+        db: &VTabConnection,
+        aux: &'vtab Self::Aux,
+        args: &[&str],
+    ) -> Result<(String, Self)> {
+        let args = args[3..].iter().map(|a| a.to_string()).collect();
+        aux.borrow_mut().push((args, db.args_replaced()));
+        Ok(("CREATE TABLE x (value)".to_owned(), ArgsVTab))
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        Ok(EmptyCursor)
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for ArgsVTab {
+    fn create(db: &VTabConnection, aux: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        Self::connect(db, aux, args)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+struct EmptyCursor;
+
+impl VTabCursor for EmptyCursor {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        true
+    }
+
+    fn column(&mut self, _: usize, _: &ColumnContext) -> Result<()> {
+        Ok(())
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(0)
+    }
+}
+
+/// Run SQL which is not valid UTF-8, which cannot be done through Connection::execute.
+fn exec_bytes(db: &Connection, sql: &[u8]) -> Result<()> {
+    let sql = std::ffi::CString::new(sql).unwrap();
+    let guard = db.lock();
+    let rc = unsafe {
+        ffi::sqlite3_exec(
+            guard.as_mut_ptr(),
+            sql.as_ptr(),
+            None,
+            null_mut(),
+            null_mut(),
+        )
+    };
+    Error::from_sqlite_desc(rc, guard)
+}
+
+#[test]
+fn strict() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    let log = Log::default();
+    db.create_module("args", ArgsVTab::module(), log.clone())?;
+    exec_bytes(
+        &db,
+        b"CREATE VIRTUAL TABLE ok USING args(plain, 'caf\xc3\xa9')",
+    )?;
+    assert_eq!(
+        log.take(),
+        vec![(vec!["plain".to_owned(), "'caf\u{e9}'".to_owned()], false)]
+    );
+    let err = exec_bytes(
+        &db,
+        b"CREATE VIRTUAL TABLE bad USING args(plain, 'caf\xe9')",
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        Error::Sqlite(
+            ffi::SQLITE_ERROR,
+            Some("virtual table argument 4 contains invalid UTF-8 at byte 4".to_owned())
+        )
+    );
+    assert!(log.take().is_empty());
+    Ok(())
+}
+
+#[test]
+fn lossy() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    let log = Log::default();
+    db.create_module("args", ArgsVTab::module().with_lossy_args(), log.clone())?;
+    exec_bytes(&db, b"CREATE VIRTUAL TABLE ok USING args(plain)")?;
+    exec_bytes(
+        &db,
+        b"CREATE VIRTUAL TABLE bad USING args(plain, 'caf\xe9')",
+    )?;
+    assert_eq!(
+        log.take(),
+        vec![
+            (vec!["plain".to_owned()], false),
+            (vec!["plain".to_owned(), "'caf\u{fffd}'".to_owned()], true),
+        ]
+    );
+    let (count,): (i64,) = db.query_row_as("SELECT COUNT(*) FROM bad", ())?;
+    assert_eq!(count, 0);
+    Ok(())
+}
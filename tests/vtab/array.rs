@@ -0,0 +1,77 @@
+//! Tests for the rust_array table-valued function.
+use sqlite3_ext::{vtab::array::*, *};
+use std::rc::Rc;
+
+fn setup() -> Result<Database> {
+    let conn = Database::open(":memory:")?;
+    conn.register_array_module()?;
+    conn.execute("CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT)", ())?;
+    let mut stmt = conn.prepare("INSERT INTO t VALUES (?, ?)")?;
+    for i in 0..10_000 {
+        stmt.execute(params!(i, format!("row {i}")))?;
+    }
+    Ok(conn)
+}
+
+#[test]
+fn bind_ids() -> Result<()> {
+    let conn = setup()?;
+    let ids: ArrayValues = Rc::new((0..3000).map(|x| Value::Integer(x * 3)).collect());
+    let mut stmt =
+        conn.prepare("SELECT id, name FROM t WHERE id IN (SELECT value FROM rust_array(?))")?;
+    Array::bind(&mut stmt, 1, ids.clone())?;
+    // The statement shares the array instead of copying it.
+    assert_eq!(Rc::strong_count(&ids), 2);
+    let mut count = 0;
+    while let Some(row) = stmt.next()? {
+        let id = row[0].get_i64();
+        assert_eq!(id % 3, 0);
+        assert_eq!(row[1].get_str()?, format!("row {id}"));
+        count += 1;
+    }
+    assert_eq!(count, 3000);
+    drop(stmt);
+    assert_eq!(Rc::strong_count(&ids), 1);
+    Ok(())
+}
+
+#[test]
+fn value_types() -> Result<()> {
+    let conn = setup()?;
+    let values: ArrayValues = Rc::new(vec![
+        Value::Integer(1),
+        Value::Float(2.5),
+        Value::Text("three".to_owned()),
+        Value::Blob(Blob::from(&[4u8, 5][..])),
+        Value::Null,
+    ]);
+    let mut stmt = conn.prepare("SELECT value, typeof(value) FROM rust_array(?)")?;
+    stmt.query([PassedRef::new(values.clone())])?;
+    let ret: Vec<(Value, String)> = stmt
+        .map(|r| Ok((r[0].to_owned()?, r[1].get_str()?.to_owned())))
+        .collect()?;
+    let types: Vec<_> = ret.iter().map(|(_, t)| t.as_str()).collect();
+    assert_eq!(types, vec!["integer", "real", "text", "blob", "null"]);
+    let ret: Vec<_> = ret.into_iter().map(|(v, _)| v).collect();
+    assert_eq!(ret, *values);
+    // Without an array, the table is empty.
+    let count: i64 = conn.query_row("SELECT COUNT(*) FROM rust_array(NULL)", (), |r| {
+        Ok(r[0].get_i64())
+    })?;
+    assert_eq!(count, 0);
+    Ok(())
+}
+
+#[test]
+fn repeated_pointer() -> Result<()> {
+    let conn = setup()?;
+    let values: ArrayValues = Rc::new(vec![Value::Integer(1), Value::Integer(2)]);
+    let mut stmt =
+        conn.prepare("SELECT COUNT(*) FROM rust_array WHERE pointer = ?1 AND pointer = ?1")?;
+    stmt.query([PassedRef::new(values)])?;
+    let count = stmt.map(|r| Ok(r[0].get_i64())).next()?;
+    // The array is read from the first constraint. SQLite checks the second one against the
+    // hidden column, which is always NULL, like the carray extension.
+    assert_eq!(count, Some(0));
+    Ok(())
+}
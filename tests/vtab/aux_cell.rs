@@ -0,0 +1,124 @@
+//! Tests for sharing state between connections with AuxCell.
+use sqlite3_ext::{vtab::*, *};
+use std::thread;
+
+#[derive(Default)]
+struct Counters {
+    connects: usize,
+    queries: usize,
+}
+
+/// A table with a single row containing the number of queries run so far, across every
+/// connection which shares the counters.
+#[sqlite3_ext_vtab(EponymousModule)]
+struct CounterVTab<'vtab> {
+    counters: &'vtab AuxCell<Counters>,
+}
+
+impl<'vtab> VTab<'vtab> for CounterVTab<'vtab> {
+    type Aux = AuxCell<Counters>;
+    type Cursor = CounterCursor<'vtab>;
+
+    fn connect(
+        _: &'vtab VTabConnection,
+        counters: &'vtab Self::Aux,
+        _: &[&str],
+    ) -> Result<(String, Self)> {
+        counters.with(|c| c.connects += 1);
+        Ok(("CREATE TABLE x (n)".to_owned(), CounterVTab { counters }))
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        Ok(CounterCursor {
+            vtab: self,
+            value: None,
+        })
+    }
+}
+
+struct CounterCursor<'vtab> {
+    vtab: &'vtab CounterVTab<'vtab>,
+    value: Option<i64>,
+}
+
+impl VTabCursor for CounterCursor<'_> {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        let n = self.vtab.counters.with(|c| {
+            // Read and write in separate steps, which would lose updates if the
+            // connections were not synchronized.
+            let n = c.queries + 1;
+            thread::yield_now();
+            c.queries = n;
+            n
+        });
+        self.value = Some(n as _);
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.value = None;
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        self.value.is_none()
+    }
+
+    fn column(&mut self, _: usize, ctx: &ColumnContext) -> Result<()> {
+        ctx.set_result(self.value)
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(1)
+    }
+}
+
+#[test]
+fn shared_between_threads() -> Result<()> {
+    const QUERIES: usize = 500;
+    let counters = AuxCell::<Counters>::default();
+    let threads: Vec<_> = (0..2)
+        .map(|_| {
+            let counters = counters.clone();
+            thread::spawn(move || -> Result<Vec<i64>> {
+                let db = Database::open(":memory:")?;
+                db.create_module("counter", CounterVTab::module(), counters)?;
+                let mut stmt = db.prepare("SELECT n FROM counter")?;
+                (0..QUERIES)
+                    .map(|_| stmt.query_row((), |r| Ok(r[0].get_i64())))
+                    .collect()
+            })
+        })
+        .collect();
+    let mut seen = vec![];
+    for t in threads {
+        seen.extend(t.join().unwrap()?);
+    }
+    seen.sort_unstable();
+    let expected: Vec<i64> = (1..=2 * QUERIES as i64).collect();
+    assert_eq!(seen, expected);
+    assert_eq!(counters.with(|c| (c.connects, c.queries)), (2, 2 * QUERIES));
+    Ok(())
+}
+
+#[test]
+fn poison_recovery() {
+    let cell = AuxCell::new(1);
+    let clone = cell.clone();
+    assert!(cell.ptr_eq(&clone));
+    let result = thread::spawn(move || {
+        clone.with(|n| {
+            *n = 2;
+            panic!("poison the mutex");
+        })
+    })
+    .join();
+    assert!(result.is_err());
+    assert_eq!(cell.get(), 2);
+    assert_eq!(cell.replace(3), 2);
+    assert_eq!(format!("{cell:?}"), "AuxCell(3)");
+}
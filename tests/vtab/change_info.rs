@@ -0,0 +1,145 @@
+//! Tests for the column metadata available from ChangeInfo.
+use sqlite3_ext::{vtab::*, *};
+use std::{cell::RefCell, rc::Rc};
+
+type Changes = Rc<RefCell<Vec<String>>>;
+
+/// A virtual table which records the name, declared type, and value of each argument it
+/// receives in an update.
+#[sqlite3_ext_vtab(StandardModule, UpdateVTab)]
+struct RecordVTab {
+    changes: Changes,
+    runtime: VTabRuntime,
+}
+
+struct EmptyCursor;
+
+impl RecordVTab {
+    fn new(db: &VTabConnection, aux: &(String, Changes)) -> Result<(String, Self)> {
+        let changes = aux.1.clone();
+        let runtime = db.runtime()?;
+        assert!(runtime.columns().is_empty());
+        Ok((aux.0.clone(), RecordVTab { changes, runtime }))
+    }
+}
+
+impl<'vtab> VTab<'vtab> for RecordVTab {
+    type Aux = (String, Changes);
+    type Cursor = EmptyCursor;
+
+    fn connect(db: &VTabConnection, aux: &'vtab Self::Aux, _: &[&str]) -> Result<(String, Self)> {
+        Self::new(db, aux)
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        Ok(EmptyCursor)
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for RecordVTab {
+    fn create(db: &VTabConnection, aux: &'vtab Self::Aux, _: &[&str]) -> Result<(String, Self)> {
+        Self::new(db, aux)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+impl<'vtab> UpdateVTab<'vtab> for RecordVTab {
+    fn update(&'vtab self, info: &mut ChangeInfo) -> Result<i64> {
+        assert_eq!(info.column_count(), info.args().len());
+        assert_eq!(info.column_name(info.column_count()), None);
+        let columns = self.runtime.columns();
+        assert_eq!(columns.len() + 1, info.column_count());
+        for (i, col) in columns.iter().enumerate() {
+            assert_eq!(info.column_name(i + 1), Some(col.name()));
+        }
+        let mut changes = self.changes.borrow_mut();
+        for i in 0..info.args().len() {
+            let name = info.column_name(i).unwrap_or("?").to_owned();
+            let decltype = info.column_decltype(i).unwrap_or("-").to_owned();
+            let val = &mut info.args_mut()[i];
+            let val = match val.value_type() {
+                ValueType::Null => "NULL".to_owned(),
+                _ => val.get_str()?.to_owned(),
+            };
+            changes.push(format!("{name}:{decltype}={val}"));
+        }
+        Ok(1)
+    }
+}
+
+impl VTabCursor for EmptyCursor {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        true
+    }
+
+    fn column(&mut self, _: usize, _: &ColumnContext) -> Result<()> {
+        Ok(())
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(0)
+    }
+}
+
+fn changes(schema: &str, sql: &str) -> Result<Vec<String>> {
+    let db = Database::open(":memory:")?;
+    let changes = Changes::default();
+    db.create_module(
+        "record",
+        RecordVTab::module(),
+        (schema.to_owned(), changes.clone()),
+    )?;
+    db.execute("CREATE VIRTUAL TABLE tbl USING record", ())?;
+    db.execute(sql, ())?;
+    let ret = changes.borrow().clone();
+    Ok(ret)
+}
+
+#[test]
+fn column_names() -> Result<()> {
+    let ret = changes(
+        "CREATE TABLE x ( a INTEGER, \"b c\" HIDDEN, d TEXT NOT NULL )",
+        "INSERT INTO tbl (a, \"b c\", d) VALUES (1, 2, 'three')",
+    )?;
+    assert_eq!(
+        ret,
+        vec!["rowid:-=NULL", "a:INTEGER=1", "b c:-=2", "d:TEXT=three"]
+    );
+    Ok(())
+}
+
+#[test]
+fn without_rowid() -> Result<()> {
+    let ret = changes(
+        "CREATE TABLE x ( k TEXT PRIMARY KEY, v ) WITHOUT ROWID",
+        "INSERT INTO tbl VALUES ('key', 'value')",
+    )?;
+    assert_eq!(ret, vec!["?:-=NULL", "k:TEXT=key", "v:-=value"]);
+    Ok(())
+}
+
+#[test]
+fn from_schema() -> Result<()> {
+    let cols = ColumnInfo::from_schema("CREATE TABLE x ( a INTEGER, b HIDDEN )")?;
+    let cols: Vec<_> = cols
+        .iter()
+        .map(|c| (c.name(), c.decltype(), c.hidden()))
+        .collect();
+    assert_eq!(cols, vec![("a", "INTEGER", false), ("b", "", true)]);
+    Ok(())
+}
@@ -0,0 +1,200 @@
+//! Tests for keeping internal writes by a virtual table out of the connection's counters.
+use sqlite3_ext::{query::CursorAdapter, vtab::*, *};
+
+/// A virtual table which stores its rows in a shadow table, and records every change in a
+/// journal table. If created with the "preserve" argument, the writes are wrapped in
+/// Connection::preserve_changes.
+#[sqlite3_ext_vtab(StandardModule, UpdateVTab)]
+struct ShadowVTab<'vtab> {
+    db: &'vtab Connection,
+    data: String,
+    preserve: bool,
+}
+
+struct ShadowCursor(CursorAdapter);
+
+impl<'vtab> ShadowVTab<'vtab> {
+    fn new(db: &'vtab Connection, args: &[&str]) -> Result<(String, Self)> {
+        let vtab = ShadowVTab {
+            db,
+            data: format!("{}_data", args[2]),
+            preserve: args.get(3) == Some(&"preserve"),
+        };
+        Ok(("CREATE TABLE x (value)".to_owned(), vtab))
+    }
+
+    fn write(&self, info: &mut ChangeInfo) -> Result<i64> {
+        let (op, rowid) = match info.change_type() {
+            ChangeType::Insert => {
+                let sql = format!("INSERT INTO {} (value) VALUES (?)", self.data);
+                (
+                    "insert",
+                    self.db.prepare(&sql)?.insert(params!(&info.args()[1]))?,
+                )
+            }
+            ChangeType::Update => {
+                let sql = format!("UPDATE {} SET value = ? WHERE rowid = ?", self.data);
+                let rowid = info.rowid().get_i64();
+                self.db.execute(&sql, params!(&info.args()[1], rowid))?;
+                ("update", rowid)
+            }
+            ChangeType::Delete => {
+                let sql = format!("DELETE FROM {} WHERE rowid = ?", self.data);
+                let rowid = info.rowid().get_i64();
+                self.db.execute(&sql, [rowid])?;
+                ("delete", rowid)
+            }
+        };
+        self.db
+            .execute("INSERT INTO journal VALUES (?, ?)", params!(op, rowid))?;
+        Ok(rowid)
+    }
+}
+
+impl<'vtab> VTab<'vtab> for ShadowVTab<'vtab> {
+    type Aux = &'vtab Connection;
+    type Cursor = ShadowCursor;
+
+    fn connect(_: &VTabConnection, aux: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        Self::new(aux, args)
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        let stmt = self
+            .db
+            .prepare(&format!("SELECT value, rowid FROM {}", self.data))?;
+        Ok(ShadowCursor(CursorAdapter::new(stmt, Some(1))))
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for ShadowVTab<'vtab> {
+    fn create(db: &VTabConnection, aux: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        db.execute(&format!("CREATE TABLE {}_data (value)", args[2]), ())?;
+        Self::new(aux, args)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+impl<'vtab> UpdateVTab<'vtab> for ShadowVTab<'vtab> {
+    fn update(&'vtab self, info: &mut ChangeInfo) -> Result<i64> {
+        match self.preserve {
+            true => self.db.preserve_changes(|| self.write(info)),
+            false => self.write(info),
+        }
+    }
+}
+
+impl VTabCursor for ShadowCursor {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        self.0.filter(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.0.next()
+    }
+
+    fn eof(&mut self) -> bool {
+        self.0.eof()
+    }
+
+    fn column(&mut self, idx: usize, ctx: &ColumnContext) -> Result<()> {
+        self.0.column(idx, ctx)
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        self.0.rowid()
+    }
+}
+
+fn setup(conn: &Connection) -> Result<()> {
+    conn.create_module("shadow", ShadowVTab::module(), conn)?;
+    conn.execute("CREATE TABLE journal (op, id)", ())?;
+    conn.execute("CREATE TABLE host (value)", ())?;
+    conn.execute("CREATE VIRTUAL TABLE plain USING shadow", ())?;
+    conn.execute("CREATE VIRTUAL TABLE preserved USING shadow(preserve)", ())?;
+    Ok(())
+}
+
+#[test]
+fn changes() -> Result<()> {
+    let conn = Database::open(":memory:")?;
+    setup(&conn)?;
+    for tbl in ["plain", "preserved"] {
+        // The count for a statement only includes the rows it modified directly.
+        let n = conn.execute(&format!("INSERT INTO {tbl} VALUES ('a'), ('b')"), ())?;
+        assert_eq!(n, 2, "{tbl}");
+        let n = conn.execute(&format!("UPDATE {tbl} SET value = 'c'"), ())?;
+        assert_eq!(n, 2, "{tbl}");
+        assert_eq!(conn.changes(), 2, "{tbl}");
+    }
+    // The total includes the internal writes.
+    let ((), n) = conn.count_changes(|| {
+        conn.execute("DELETE FROM preserved", ())?;
+        Ok(())
+    })?;
+    assert_eq!(n, 6);
+    assert_eq!(conn.changes(), 2);
+    Ok(())
+}
+
+#[test]
+#[cfg(modern_sqlite)]
+fn last_insert_rowid() -> Result<()> {
+    let conn = Database::open(":memory:")?;
+    setup(&conn)?;
+    for tbl in ["plain", "preserved"] {
+        // SQLite sets the rowid after an INSERT into the virtual table, overwriting the
+        // rowid of the journal entry.
+        conn.execute(&format!("INSERT INTO {tbl} VALUES ('a')"), ())?;
+        assert_eq!(conn.last_insert_rowid(), 1, "{tbl}");
+    }
+
+    conn.execute("INSERT INTO host VALUES ('a'), ('b'), ('c')", ())?;
+    // A DELETE does not set the rowid, so the journal entry leaks out.
+    conn.execute("DELETE FROM plain", ())?;
+    let (journal,): (i64,) = conn.query_row_as("SELECT MAX(rowid) FROM journal", ())?;
+    assert_eq!(conn.last_insert_rowid(), journal);
+
+    conn.execute("INSERT INTO host VALUES ('d')", ())?;
+    conn.execute("DELETE FROM preserved", ())?;
+    assert_eq!(conn.last_insert_rowid(), 4);
+    Ok(())
+}
+
+#[test]
+#[cfg(modern_sqlite)]
+fn sibling() -> Result<()> {
+    let path = std::env::temp_dir().join(format!(
+        "sqlite3_ext_vtab_changes_{}.db",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    let conn = Database::open(&path)?;
+    setup(&conn)?;
+    conn.execute("INSERT INTO plain VALUES ('a')", ())?;
+    let total = conn.total_changes();
+
+    // Maintenance performed on a sibling connection does not affect the host's counters.
+    let sibling = conn.open_sibling()?;
+    sibling.execute("DELETE FROM journal", ())?;
+    assert_eq!(conn.total_changes(), total);
+    assert_eq!(conn.last_insert_rowid(), 1);
+    let (count,): (i64,) = conn.query_row_as("SELECT COUNT(*) FROM journal", ())?;
+    assert_eq!(count, 0);
+
+    // The sibling cannot write while the host has a write transaction open.
+    conn.execute("BEGIN IMMEDIATE", ())?;
+    let err = sibling.execute("DELETE FROM journal", ()).unwrap_err();
+    assert!(matches!(err, Error::Sqlite(ffi::SQLITE_BUSY, _)), "{err:?}");
+    conn.execute("COMMIT", ())?;
+    drop((conn, sibling));
+    std::fs::remove_file(&path).unwrap();
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use sqlite3_ext::{vtab::*, *};
+use std::{cell::RefCell, collections::BTreeMap};
+
+#[test]
+fn lookup_table() -> Result<()> {
+    let conn = Database::open(":memory:")?;
+    let filters = RefCell::new(vec![]);
+    vtab::from_closures(
+        "CREATE TABLE x (code TEXT, name TEXT)",
+        |args| {
+            let codes = match args {
+                [] => vec!["fr", "jp", "us"],
+                _ => args.to_vec(),
+            };
+            Ok(codes
+                .into_iter()
+                .map(|c| (c.to_owned(), c.to_uppercase()))
+                .collect::<Vec<_>>())
+        },
+        |countries, filter| {
+            filters.borrow_mut().push(filter.clone());
+            let code = filter.eq(0);
+            Ok(countries
+                .iter()
+                .filter(|(c, _)| code.map_or(true, |v| *v == Value::Text(c.clone())))
+                .map(|(c, n)| vec![Value::Text(c.clone()), Value::Text(n.clone())])
+                .collect())
+        },
+    )
+    .register(&conn, "countries")?;
+
+    let (name,): (String,) =
+        conn.query_row_as("SELECT name FROM countries WHERE code = 'jp'", ())?;
+    assert_eq!(name, "JP");
+    assert_eq!(
+        filters.borrow_mut().pop().unwrap().constraints(),
+        &[(0, ConstraintOp::Eq, Value::Text("jp".into()))]
+    );
+
+    // Constraints which the closure ignores are still applied by SQLite.
+    let mut stmt =
+        conn.prepare("SELECT code FROM countries WHERE name > 'G' ORDER BY code DESC")?;
+    let codes: Vec<(String,)> = stmt.query_as(())?.collect()?;
+    assert_eq!(codes, vec![("us".to_owned(),), ("jp".to_owned(),)]);
+
+    // The arguments to CREATE VIRTUAL TABLE are passed to the connect closure.
+    conn.execute("CREATE VIRTUAL TABLE temp.custom USING countries(a, b)", ())?;
+    let mut stmt = conn.prepare("SELECT code FROM custom")?;
+    let codes: Vec<(String,)> = stmt.query_as(())?.collect()?;
+    assert_eq!(codes, vec![("a".to_owned(),), ("b".to_owned(),)]);
+
+    let err = conn.execute("DELETE FROM countries", ()).unwrap_err();
+    assert_eq!(err.to_string(), "table countries may not be modified");
+    Ok(())
+}
+
+#[test]
+fn update() -> Result<()> {
+    let conn = Database::open(":memory:")?;
+    vtab::from_closures(
+        "CREATE TABLE x (id INTEGER, value TEXT)",
+        |_| Ok(RefCell::new(BTreeMap::<i64, String>::new())),
+        |map, _| {
+            Ok(map
+                .borrow()
+                .iter()
+                .map(|(&k, v)| vec![Value::Integer(k), Value::Text(v.clone())])
+                .collect())
+        },
+    )
+    .with_rowid_column(0)
+    .with_update(|map, info| {
+        let mut map = map.borrow_mut();
+        if info.change_type() != ChangeType::Insert {
+            map.remove(&info.rowid().get_i64());
+        }
+        if info.change_type() == ChangeType::Delete {
+            return Ok(0);
+        }
+        let args = info.args_mut();
+        // The first argument is the rowid, followed by the columns.
+        let id = args[1].get_i64();
+        map.insert(id, args[2].get_str()?.to_owned());
+        Ok(id)
+    })
+    .register(&conn, "kv")?;
+
+    conn.execute(
+        "INSERT INTO kv (id, value) VALUES (1, 'a'), (2, 'b'), (3, 'c')",
+        (),
+    )?;
+    conn.execute("UPDATE kv SET value = 'B', id = 20 WHERE id = 2", ())?;
+    conn.execute("DELETE FROM kv WHERE value = 'c'", ())?;
+    let mut stmt = conn.prepare("SELECT id, value, rowid FROM kv")?;
+    let rows: Vec<(i64, String, i64)> = stmt.query_as(())?.collect()?;
+    assert_eq!(rows, vec![(1, "a".into(), 1), (20, "B".into(), 20)]);
+    Ok(())
+}
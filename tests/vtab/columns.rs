@@ -0,0 +1,166 @@
+//! Tests for #[derive(VTabColumns)].
+use sqlite3_ext::{vtab::*, *};
+
+#[derive(VTabColumns, Debug, PartialEq, Clone, Copy)]
+enum Col {
+    Id,
+    #[column(decltype = "TEXT", collate = "NOCASE")]
+    FullName,
+    #[column(hidden, name = "select", decltype = "BLOB")]
+    Payload,
+    #[column(collate = "BINARY", hidden)]
+    Flags,
+}
+
+/// A table-valued function with a single row, whose columns are declared by Col.
+#[sqlite3_ext_vtab(EponymousModule)]
+struct ColVTab;
+
+struct ColCursor {
+    eof: bool,
+}
+
+impl VTab<'_> for ColVTab {
+    type Aux = ();
+    type Cursor = ColCursor;
+
+    fn connect(_: &VTabConnection, _: &Self::Aux, _: &[&str]) -> Result<(String, Self)> {
+        let sql = format!("CREATE TABLE x ( {} )", Col::SCHEMA_FRAGMENT);
+        Ok((sql, ColVTab))
+    }
+
+    fn best_index(&self, index_info: &mut IndexInfo) -> Result<()> {
+        for mut c in index_info.constraints() {
+            // Payload is an input, so it must be usable.
+            if c.column() == i32::from(Col::Payload) && c.op() == ConstraintOp::Eq {
+                if !c.usable() {
+                    return Err(SQLITE_CONSTRAINT);
+                }
+                c.set_argv_index(Some(0));
+                c.set_omit(true);
+            }
+        }
+        Ok(())
+    }
+
+    fn open(&self) -> Result<Self::Cursor> {
+        Ok(ColCursor { eof: false })
+    }
+}
+
+impl VTabCursor for ColCursor {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        self.eof = false;
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.eof = true;
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        self.eof
+    }
+
+    fn column(&mut self, idx: usize, ctx: &ColumnContext) -> Result<()> {
+        match Col::from_index(idx).unwrap() {
+            Col::Id => ctx.set_result(1),
+            Col::FullName => ctx.set_result("Alice"),
+            Col::Payload => ctx.set_result(()),
+            Col::Flags => ctx.set_result(7),
+        }
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(1)
+    }
+}
+
+#[test]
+fn derive() -> Result<()> {
+    assert_eq!(Col::COUNT, 4);
+    assert_eq!(
+        Col::SCHEMA_FRAGMENT,
+        "\"id\", \"full_name\" TEXT COLLATE \"NOCASE\", \"select\" BLOB HIDDEN, \"flags\" HIDDEN COLLATE \"BINARY\""
+    );
+    let cols: Vec<_> = (0..=Col::COUNT).map(Col::from_index).collect();
+    assert_eq!(
+        cols,
+        vec![
+            Some(Col::Id),
+            Some(Col::FullName),
+            Some(Col::Payload),
+            Some(Col::Flags),
+            None
+        ]
+    );
+    assert_eq!(i32::from(Col::Flags), 3);
+
+    let schema = Col::schema_builder();
+    let cols: Vec<_> = schema
+        .columns()
+        .iter()
+        .map(|c| (c.name(), c.decltype(), c.hidden()))
+        .collect();
+    assert_eq!(
+        cols,
+        vec![
+            ("id", "", false),
+            ("full_name", "TEXT COLLATE \"NOCASE\"", false),
+            ("select", "BLOB", true),
+            ("flags", "COLLATE \"BINARY\"", true),
+        ]
+    );
+    // The builder and the fragment declare the same columns.
+    assert_eq!(
+        ColumnInfo::from_schema(&schema.build()?)?,
+        ColumnInfo::from_schema(&format!("CREATE TABLE x ( {} )", Col::SCHEMA_FRAGMENT))?
+    );
+    Ok(())
+}
+
+#[test]
+fn hidden() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    db.create_module("cols", ColVTab::module(), ())?;
+    let mut stmt = db.prepare("SELECT name, hidden FROM pragma_table_xinfo('cols')")?;
+    let cols: Vec<(String, i64)> = stmt.query_as(())?.collect()?;
+    assert_eq!(
+        cols,
+        vec![
+            ("id".to_owned(), 0),
+            ("full_name".to_owned(), 0),
+            ("select".to_owned(), 1),
+            ("flags".to_owned(), 1),
+        ]
+    );
+    let row: (i64, String) = db.query_row_as("SELECT * FROM cols('x')", ())?;
+    assert_eq!(row, (1, "Alice".to_owned()));
+    // The collation is taken from the declaration.
+    let (count,): (i64,) =
+        db.query_row_as("SELECT COUNT(*) FROM cols WHERE full_name = 'ALICE'", ())?;
+    assert_eq!(count, 1);
+    Ok(())
+}
+
+#[derive(VTabColumns)]
+enum QuotedCol {
+    #[column(collate = "odd \"name\"")]
+    Value,
+}
+
+#[test]
+fn quoted_collation() -> Result<()> {
+    assert_eq!(
+        QuotedCol::SCHEMA_FRAGMENT,
+        "\"value\" COLLATE \"odd \"\"name\"\"\""
+    );
+    let db = Database::open(":memory:")?;
+    db.create_collation("odd \"name\"", |a: &str, b: &str| a.cmp(b))?;
+    let sql = format!("CREATE TABLE t ( {} )", QuotedCol::SCHEMA_FRAGMENT);
+    db.execute(&sql, ())?;
+    let cols = ColumnInfo::from_schema(&sql)?;
+    assert_eq!(cols[0].collation(), Some("odd \"name\""));
+    Ok(())
+}
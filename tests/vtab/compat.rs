@@ -0,0 +1,155 @@
+//! Tests for porting virtual tables using vtab::compat::SplitUpdateVTab.
+use sqlite3_ext::{
+    vtab::{compat::SplitUpdateVTab, *},
+    *,
+};
+use std::{cell::RefCell, collections::BTreeMap};
+
+/// A virtual table which stores its rows in memory, written against the split update API.
+#[sqlite3_ext_vtab(StandardModule, UpdateVTab)]
+struct SplitVTab {
+    rows: RefCell<BTreeMap<i64, String>>,
+    log: RefCell<Vec<String>>,
+}
+
+struct SplitCursor {
+    rows: Vec<(i64, String)>,
+    pos: usize,
+}
+
+impl<'vtab> VTab<'vtab> for SplitVTab {
+    type Aux = ();
+    type Cursor = SplitCursor;
+
+    fn connect(_: &VTabConnection, _: &Self::Aux, _: &[&str]) -> Result<(String, Self)> {
+        Ok((
+            "CREATE TABLE x (value)".to_owned(),
+            SplitVTab {
+                rows: Default::default(),
+                log: Default::default(),
+            },
+        ))
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&self) -> Result<Self::Cursor> {
+        let mut rows: Vec<_> = self
+            .rows
+            .borrow()
+            .iter()
+            .map(|(k, v)| (*k, v.clone()))
+            .collect();
+        // Report the log as extra rows, so the test can read it through SQL.
+        rows.extend(self.log.borrow().iter().map(|l| (-1, l.clone())));
+        Ok(SplitCursor { rows, pos: 0 })
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for SplitVTab {
+    fn create(db: &VTabConnection, aux: &Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        Self::connect(db, aux, args)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+impl<'vtab> SplitUpdateVTab<'vtab> for SplitVTab {
+    fn insert(&'vtab self, args: &mut [&mut ValueRef]) -> Result<i64> {
+        let mut rows = self.rows.borrow_mut();
+        let rowid = match args[0].is_null() {
+            true => rows.keys().next_back().map_or(1, |k| k + 1),
+            false => args[0].get_i64(),
+        };
+        let value = args[1].get_str()?.to_owned();
+        self.log
+            .borrow_mut()
+            .push(format!("insert {rowid} {value}"));
+        rows.insert(rowid, value);
+        Ok(rowid)
+    }
+
+    fn update_row(&'vtab self, rowid: &mut ValueRef, args: &mut [&mut ValueRef]) -> Result<()> {
+        let mut rows = self.rows.borrow_mut();
+        rows.remove(&rowid.get_i64());
+        let value = args[1].get_str()?.to_owned();
+        self.log.borrow_mut().push(format!(
+            "update {} -> {} {value}",
+            rowid.get_i64(),
+            args[0].get_i64()
+        ));
+        rows.insert(args[0].get_i64(), value);
+        Ok(())
+    }
+
+    fn delete(&'vtab self, rowid: &mut ValueRef) -> Result<()> {
+        self.log
+            .borrow_mut()
+            .push(format!("delete {}", rowid.get_i64()));
+        self.rows.borrow_mut().remove(&rowid.get_i64());
+        Ok(())
+    }
+}
+
+impl VTabCursor for SplitCursor {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        self.pos = 0;
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        self.pos >= self.rows.len()
+    }
+
+    fn column(&mut self, _: usize, ctx: &ColumnContext) -> Result<()> {
+        ctx.set_result(self.rows[self.pos].1.clone())
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(self.rows[self.pos].0)
+    }
+}
+
+fn contents(conn: &Connection) -> Result<Vec<(i64, String)>> {
+    conn.prepare("SELECT rowid, value FROM tbl")?
+        .query(())?
+        .map(|r| Ok((r[0].get_i64(), r[1].get_str()?.to_owned())))
+        .collect()
+}
+
+#[test]
+fn split_update() -> Result<()> {
+    let conn = Database::open(":memory:")?;
+    conn.create_module("split", SplitVTab::module(), ())?;
+    conn.execute("CREATE VIRTUAL TABLE tbl USING split", ())?;
+
+    conn.execute("INSERT INTO tbl VALUES ('a'), ('b')", ())?;
+    assert_eq!(conn.last_insert_rowid(), 2);
+    conn.execute("INSERT INTO tbl (rowid, value) VALUES (10, 'c')", ())?;
+    conn.execute("UPDATE tbl SET value = 'B' WHERE rowid = 2", ())?;
+    conn.execute("UPDATE tbl SET rowid = 5 WHERE rowid = 10", ())?;
+    conn.execute("DELETE FROM tbl WHERE rowid = 1", ())?;
+    assert_eq!(
+        contents(&conn)?,
+        vec![
+            (2, "B".to_owned()),
+            (5, "c".to_owned()),
+            (-1, "insert 1 a".to_owned()),
+            (-1, "insert 2 b".to_owned()),
+            (-1, "insert 10 c".to_owned()),
+            (-1, "update 2 -> 2 B".to_owned()),
+            (-1, "update 10 -> 5 c".to_owned()),
+            (-1, "delete 1".to_owned()),
+        ]
+    );
+    Ok(())
+}
@@ -0,0 +1,142 @@
+//! A virtual table whose cursor is built entirely on CursorAdapter.
+use sqlite3_ext::{function::*, query::CursorAdapter, vtab::*, *};
+
+#[sqlite3_ext_vtab(StandardModule)]
+struct KvVTab<'vtab> {
+    db: &'vtab Connection,
+}
+
+struct KvCursor(CursorAdapter);
+
+impl<'vtab> VTab<'vtab> for KvVTab<'vtab> {
+    type Aux = &'vtab Connection;
+    type Cursor = KvCursor;
+
+    fn connect(_: &VTabConnection, aux: &'vtab Self::Aux, _: &[&str]) -> Result<(String, Self)> {
+        Ok(("CREATE TABLE x (key, value)".to_owned(), KvVTab { db: aux }))
+    }
+
+    fn best_index(&self, index_info: &mut IndexInfo) -> Result<()> {
+        for mut c in index_info.constraints() {
+            if c.usable() && c.column() == 0 && c.op() == ConstraintOp::Eq {
+                c.set_argv_index(Some(0));
+                c.set_omit(true);
+                index_info.set_index_num(1);
+                index_info.set_estimated_cost(1.0);
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        let stmt = self.db.prepare(
+            "SELECT key, fail_on(value), rowid FROM kv_data WHERE ?1 IS NULL OR key = ?1",
+        )?;
+        Ok(KvCursor(CursorAdapter::new(stmt, Some(2))))
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for KvVTab<'vtab> {
+    fn create(db: &VTabConnection, aux: &'vtab Self::Aux, _: &[&str]) -> Result<(String, Self)> {
+        db.execute("CREATE TABLE kv_data (key, value)", ())?;
+        Ok(("CREATE TABLE x (key, value)".to_owned(), KvVTab { db: aux }))
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+impl VTabCursor for KvCursor {
+    fn filter(&mut self, _: i32, _: Option<&str>, args: &mut [&mut ValueRef]) -> Result<()> {
+        self.0.filter(args)
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.0.next()
+    }
+
+    fn eof(&mut self) -> bool {
+        self.0.eof()
+    }
+
+    fn column(&mut self, idx: usize, ctx: &ColumnContext) -> Result<()> {
+        self.0.column(idx, ctx)
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        self.0.rowid()
+    }
+}
+
+fn setup(conn: &Connection) -> Result<()> {
+    conn.create_module("kv", KvVTab::module(), conn)?;
+    conn.create_scalar_function(
+        "fail_on",
+        &FunctionOptions::default().set_n_args(1),
+        |ctx, args| {
+            if args[0].get_str().ok() == Some("fail") {
+                Err(Error::Module("fail_on failed".to_owned()))
+            } else {
+                ctx.set_result(&*args[0])
+            }
+        },
+    )?;
+    conn.execute("CREATE VIRTUAL TABLE tbl USING kv", ())?;
+    Ok(())
+}
+
+#[test]
+fn cursor_adapter() -> Result<()> {
+    let conn = Database::open(":memory:")?;
+    setup(&conn)?;
+    let big = vec![7u8; 1 << 20];
+    conn.execute(
+        "INSERT INTO kv_data (rowid, key, value) VALUES (10, 'a', 1), (20, 'b', NULL), (30, 'c', ?)",
+        [&big],
+    )?;
+
+    let rows: Vec<(i64, String, Value)> = conn
+        .prepare("SELECT rowid, key, value FROM tbl ORDER BY rowid")?
+        .query_as(())?
+        .collect()?;
+    assert_eq!(
+        rows,
+        vec![
+            (10, "a".to_owned(), Value::Integer(1)),
+            (20, "b".to_owned(), Value::Null),
+            (30, "c".to_owned(), Value::Blob(Blob::from(&big[..]))),
+        ]
+    );
+
+    let mut stmt = conn.prepare("SELECT rowid FROM tbl WHERE key = ?")?;
+    assert_eq!(stmt.query_row_as::<(i64,), _>(["b"])?, (20,));
+    assert_eq!(stmt.query_row_as::<(i64,), _>(["c"])?, (30,));
+    assert_eq!(stmt.query_row_as::<(i64,), _>(["z"]), Err(SQLITE_EMPTY));
+    Ok(())
+}
+
+#[test]
+fn cursor_adapter_error() -> Result<()> {
+    let conn = Database::open(":memory:")?;
+    setup(&conn)?;
+    conn.execute(
+        "INSERT INTO kv_data (key, value) VALUES ('a', 1), ('b', 'fail'), ('c', 3)",
+        (),
+    )?;
+    let mut stmt = conn.prepare("SELECT key FROM tbl")?;
+    stmt.query(())?;
+    assert_eq!(
+        stmt.next()?.map(|r| r[0].get_str().map(String::from)),
+        Some(Ok("a".into()))
+    );
+    match stmt.next() {
+        Err(Error::Sqlite(_, Some(msg))) => assert_eq!(msg, "fail_on failed"),
+        x => panic!("expected an error, got {:?}", x.map(|_| ())),
+    }
+    // The virtual table remains usable after a failure.
+    let count: (i64,) = conn.query_row_as("SELECT COUNT(*) FROM tbl WHERE key = 'c'", ())?;
+    assert_eq!(count, (1,));
+    Ok(())
+}
@@ -0,0 +1,112 @@
+//! Tests for the open cursor count maintained by VTabRuntime.
+use sqlite3_ext::{vtab::*, *};
+use std::{cell::RefCell, rc::Rc};
+
+#[derive(Default)]
+struct Observer {
+    runtime: RefCell<Option<VTabRuntime>>,
+    open_at_disconnect: RefCell<Vec<usize>>,
+}
+
+impl Observer {
+    fn open_cursors(&self) -> usize {
+        self.runtime.borrow().as_ref().unwrap().open_cursors()
+    }
+}
+
+/// An endless table of integers, which is only stopped by interrupting the query.
+#[sqlite3_ext_vtab(EponymousModule)]
+struct CountVTab<'vtab> {
+    observer: &'vtab Observer,
+    runtime: VTabRuntime,
+}
+
+impl<'vtab> VTab<'vtab> for CountVTab<'vtab> {
+    type Aux = Rc<Observer>;
+    type Cursor = CountCursor;
+
+    fn connect(
+        db: &'vtab VTabConnection,
+        observer: &'vtab Self::Aux,
+        _: &[&str],
+    ) -> Result<(String, Self)> {
+        let runtime = db.runtime()?;
+        *observer.runtime.borrow_mut() = Some(runtime.clone());
+        Ok((
+            "CREATE TABLE x (value)".to_owned(),
+            CountVTab { observer, runtime },
+        ))
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&self) -> Result<Self::Cursor> {
+        Ok(CountCursor { value: 0 })
+    }
+
+    fn disconnect(self) -> DisconnectResult<Self> {
+        self.observer
+            .open_at_disconnect
+            .borrow_mut()
+            .push(self.runtime.open_cursors());
+        Ok(())
+    }
+}
+
+struct CountCursor {
+    value: i64,
+}
+
+impl VTabCursor for CountCursor {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        self.value = 0;
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.value += 1;
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        false
+    }
+
+    fn column(&mut self, _: usize, ctx: &ColumnContext) -> Result<()> {
+        ctx.set_result(self.value)
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(self.value)
+    }
+}
+
+#[test]
+fn interrupted_query() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    let observer = Rc::new(Observer::default());
+    db.create_module("counter", CountVTab::module(), observer.clone())?;
+
+    let mut stmt = db.prepare("SELECT a.value, b.value FROM counter a, counter b")?;
+    stmt.query(())?;
+    assert!(stmt.next()?.is_some());
+    assert_eq!(observer.open_cursors(), 2);
+    db.interrupt();
+    let err = stmt.next().unwrap_err();
+    assert_eq!(err, Error::Sqlite(ffi::SQLITE_INTERRUPT, None));
+    drop(stmt);
+    assert_eq!(observer.open_cursors(), 0);
+
+    // The table is still usable after the interrupted query released its cursors.
+    let value = db.query_row("SELECT value FROM counter LIMIT 1 OFFSET 4", (), |r| {
+        Ok(r[0].get_i64())
+    })?;
+    assert_eq!(value, 4);
+    assert_eq!(observer.open_cursors(), 0);
+
+    drop(db);
+    assert_eq!(*observer.open_at_disconnect.borrow(), vec![0]);
+    Ok(())
+}
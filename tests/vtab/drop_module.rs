@@ -0,0 +1,75 @@
+use crate::test_vtab::*;
+use sqlite3_ext::*;
+use std::sync::Arc;
+
+struct Hooks;
+
+impl TestHooks for Hooks {}
+
+#[test]
+fn shared_aux() -> Result<()> {
+    let hooks = Hooks;
+    let aux = Arc::new(&hooks);
+    let db = Database::open(":memory:")?;
+    db.create_module_arc("vtab_a", TestVTab::module(), aux.clone())?;
+    db.create_module_arc("vtab_b", TestVTab::module(), aux.clone())?;
+    assert_eq!(Arc::strong_count(&aux), 3);
+    db.execute("CREATE VIRTUAL TABLE tbl_a USING vtab_a", ())?;
+    db.execute("CREATE VIRTUAL TABLE tbl_b USING vtab_b", ())?;
+    let (count,): (i64,) = db.query_row_as(
+        "SELECT (SELECT COUNT(*) FROM tbl_a) + (SELECT COUNT(*) FROM tbl_b)",
+        (),
+    )?;
+    assert_eq!(count, 6);
+    db.close().map_err(|(e, _)| e)?;
+    assert_eq!(Arc::strong_count(&aux), 1);
+    Ok(())
+}
+
+#[test]
+#[cfg(modern_sqlite)]
+fn drop_module() -> Result<()> {
+    let hooks = Hooks;
+    let aux = Arc::new(&hooks);
+    let db = Database::open(":memory:")?;
+    db.create_module_arc("vtab_a", TestVTab::module(), aux.clone())?;
+    db.create_module_arc("vtab_b", TestVTab::module(), aux.clone())?;
+    db.execute("CREATE VIRTUAL TABLE tbl USING vtab_a", ())?;
+
+    // The table still uses vtab_a, so its aux data must stay alive.
+    db.drop_module("vtab_a")?;
+    db.drop_module("vtab_b")?;
+    db.drop_module("missing")?;
+    assert_eq!(Arc::strong_count(&aux), 2);
+    let (count,): (i64,) = db.query_row_as("SELECT COUNT(*) FROM tbl", ())?;
+    assert_eq!(count, 3);
+    let err = db
+        .execute("CREATE VIRTUAL TABLE other USING vtab_a", ())
+        .unwrap_err();
+    assert_eq!(err.to_string(), "no such module: vtab_a");
+
+    // Disconnecting the table releases the module.
+    db.close().map_err(|(e, _)| e)?;
+    assert_eq!(Arc::strong_count(&aux), 1);
+    Ok(())
+}
+
+#[test]
+#[cfg(modern_sqlite)]
+fn drop_modules_except() -> Result<()> {
+    let hooks = Hooks;
+    let aux = Arc::new(&hooks);
+    let db = Database::open(":memory:")?;
+    for name in ["vtab_a", "vtab_b", "vtab_c"] {
+        db.create_module_arc(name, TestVTab::module(), aux.clone())?;
+    }
+    assert_eq!(Arc::strong_count(&aux), 4);
+    db.drop_modules_except(&["vtab_b"])?;
+    assert_eq!(Arc::strong_count(&aux), 2);
+    db.execute("CREATE VIRTUAL TABLE tbl USING vtab_b", ())?;
+    let err = db
+        .execute("CREATE VIRTUAL TABLE other USING vtab_c", ())
+        .unwrap_err();
+    assert_eq!(err.to_string(), "no such module: vtab_c");
+    Ok(())
+}
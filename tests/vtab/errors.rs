@@ -19,3 +19,178 @@ fn errors() -> Result<()> {
     assert_eq!(err.to_string(), "SQL logic error".to_string());
     Ok(())
 }
+
+/// A virtual table which declares the schema given as its Aux data. If the schema is a
+/// list separated by semicolons, each one is tried in turn with declare_vtab, and the first
+/// one that works is used.
+#[sqlite3_ext_vtab(StandardModule)]
+struct SchemaVTab;
+
+impl<'vtab> VTab<'vtab> for SchemaVTab {
+    type Aux = &'static str;
+    type Cursor = EmptyCursor;
+
+    fn connect(db: &VTabConnection, aux: &'vtab Self::Aux, _: &[&str]) -> Result<(String, Self)> {
+        if !aux.contains(';') {
+            return Ok((aux.to_string(), SchemaVTab));
+        }
+        for sql in aux.split(';').filter(|s| !s.is_empty()) {
+            match db.declare_vtab(sql) {
+                Ok(()) => {
+                    // The schema can only be declared once.
+                    let err = db.declare_vtab(sql).unwrap_err();
+                    assert!(
+                        matches!(err, Error::Sqlite(ffi::SQLITE_MISUSE, _)),
+                        "{err:?}"
+                    );
+                    break;
+                }
+                Err(Error::Module(msg)) => assert!(msg.contains(sql), "{msg}"),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok((String::new(), SchemaVTab))
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        Ok(EmptyCursor)
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for SchemaVTab {
+    fn create(db: &VTabConnection, aux: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        Self::connect(db, aux, args)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+struct EmptyCursor;
+
+impl VTabCursor for EmptyCursor {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        true
+    }
+
+    fn column(&mut self, _: usize, _: &ColumnContext) -> Result<()> {
+        Ok(())
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(0)
+    }
+}
+
+fn create_schema_vtab(schema: &'static str) -> Result<Database> {
+    let conn = Database::open(":memory:")?;
+    conn.create_module("schema", SchemaVTab::module(), schema)?;
+    conn.execute("CREATE VIRTUAL TABLE tbl USING schema", ())?;
+    Ok(conn)
+}
+
+#[test]
+fn invalid_schema() {
+    let err = create_schema_vtab("CREATE TABLE x (a,, b)").unwrap_err();
+    let msg = err.to_string();
+    assert!(msg.contains("invalid virtual table schema"), "{msg}");
+    assert!(msg.contains("CREATE TABLE x (a,, b)"), "{msg}");
+}
+
+#[test]
+fn declare_vtab() -> Result<()> {
+    let conn = create_schema_vtab("CREATE TABLE x (a,, b);CREATE TABLE x (c, d);")?;
+    let mut stmt = conn.prepare("SELECT name FROM pragma_table_info('tbl')")?;
+    let cols: Vec<(String,)> = stmt.query_as(())?.collect()?;
+    assert_eq!(cols, vec![("c".to_owned(),), ("d".to_owned(),)]);
+    Ok(())
+}
+
+#[test]
+fn declare_vtab_missing() {
+    // Every schema is invalid, so connect returns without declaring one.
+    let err = create_schema_vtab("CREATE TABLE x (a,, b);").unwrap_err();
+    assert!(
+        matches!(err, Error::Sqlite(ffi::SQLITE_ERROR, _)),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn extended_codes() -> Result<()> {
+    struct Hooks;
+
+    impl TestHooks for Hooks {
+        fn filter<'a>(
+            &self,
+            _: &mut TestVTabCursor<'a, Self>,
+            _: &mut [&mut ValueRef],
+        ) -> Result<()> {
+            Err(Error::Sqlite(
+                ffi::SQLITE_IOERR_READ,
+                Some("read failed".to_string()),
+            ))
+        }
+    }
+
+    let hooks = Hooks;
+    let conn = setup(&hooks)?;
+    for (extended, expected) in [(false, ffi::SQLITE_IOERR), (true, ffi::SQLITE_IOERR_READ)] {
+        unsafe { ffi::sqlite3_extended_result_codes(conn.as_mut_ptr(), extended as _) };
+        let err = conn
+            .query_row("SELECT a FROM tbl", (), |_| Ok(()))
+            .unwrap_err();
+        assert_eq!(err, Error::Sqlite(expected, None));
+        assert_eq!(err.to_string(), "read failed");
+        let code = unsafe { ffi::sqlite3_extended_errcode(conn.as_mut_ptr()) };
+        assert_eq!(code, ffi::SQLITE_IOERR_READ);
+    }
+    Ok(())
+}
+
+#[test]
+fn column_errors() -> Result<()> {
+    struct Hooks;
+
+    impl TestHooks for Hooks {
+        fn column(&self, idx: usize, ctx: &ColumnContext) -> Result<()> {
+            assert_eq!(ctx.column_index(), idx);
+            match idx {
+                1 => Err(Error::Sqlite(
+                    ffi::SQLITE_CONSTRAINT,
+                    Some("value out of range".to_string()),
+                )),
+                2 => Err(Error::Module("bad value".to_string())),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    let hooks = Hooks;
+    let conn = setup(&hooks)?;
+    let (a,): (String,) = conn.query_row_as("SELECT a FROM tbl", ())?;
+    assert_eq!(a, "a0");
+    let err = conn
+        .query_row("SELECT b FROM tbl", (), |_| Ok(()))
+        .unwrap_err();
+    assert_eq!(err, Error::Sqlite(ffi::SQLITE_CONSTRAINT, None));
+    assert_eq!(err.to_string(), "tbl: column 1 (b): value out of range");
+    let err = conn
+        .query_row("SELECT c FROM tbl", (), |_| Ok(()))
+        .unwrap_err();
+    assert_eq!(err.to_string(), "tbl: column 2 (c): bad value");
+    Ok(())
+}
@@ -38,3 +38,121 @@ fn find_function() -> Result<()> {
     assert!(hooks.was_called.get(), "overloaded_func was not called");
     Ok(())
 }
+
+/// Counts how many times it has been dropped, to detect leaked overloads.
+struct DropCounter<'a>(&'a Cell<usize>);
+
+impl Drop for DropCounter<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+#[derive(Default)]
+struct ReconnectHooks {
+    connects: Cell<usize>,
+    drops: Cell<usize>,
+}
+
+impl TestHooks for ReconnectHooks {
+    fn connect_create<'a>(&'a self, vtab: &mut TestVTab<'a, Self>) {
+        self.connects.set(self.connects.get() + 1);
+        let generation = self.connects.get();
+        let guard = DropCounter(&self.drops);
+        vtab.functions.add(1, "tagged", None, move |c, _| {
+            let _ = &guard;
+            c.set_result(format!("old{generation}"))
+        });
+        vtab.functions.clear();
+        let guard = DropCounter(&self.drops);
+        vtab.functions.add(1, "tagged", None, move |c, a| {
+            let _ = &guard;
+            c.set_result(format!("{generation}:{}", a[0].get_str()?))
+        });
+    }
+}
+
+#[test]
+fn reconnect() -> Result<()> {
+    let hooks = ReconnectHooks::default();
+    let conn = setup(&hooks)?;
+    conn.create_overloaded_function("tagged", &FunctionOptions::default().set_n_args(1))?;
+    let mut stmt = conn.prepare("SELECT tagged(a) FROM tbl")?;
+    let rows: Vec<String> = stmt.query_as(())?.map(|(x,)| Ok(x)).collect()?;
+    assert_eq!(rows, vec!["1:a0", "1:a1", "1:a2"]);
+    // Cleared functions are retained while the virtual table is alive.
+    assert_eq!(hooks.drops.get(), 0);
+
+    conn.execute("DROP TABLE tbl", ())?;
+    conn.execute(
+        "CREATE VIRTUAL TABLE tbl USING vtab(schema='CREATE TABLE x(a,b,c)', rows=3)",
+        (),
+    )?;
+    let rows: Vec<String> = stmt.query_as(())?.map(|(x,)| Ok(x)).collect()?;
+    assert_eq!(rows, vec!["2:a0", "2:a1", "2:a2"]);
+    assert_eq!(hooks.connects.get(), 2);
+    // The first instance is gone, along with both of its functions.
+    assert_eq!(hooks.drops.get(), 2);
+
+    drop(stmt);
+    drop(conn);
+    assert_eq!(hooks.drops.get(), 4);
+    Ok(())
+}
+
+#[derive(Default)]
+struct MatchHooks {
+    entries: std::cell::RefCell<Vec<(i32, String)>>,
+    removed: Cell<(bool, bool)>,
+}
+
+impl TestHooks for MatchHooks {
+    fn connect_create<'a>(&'a self, vtab: &mut TestVTab<'a, Self>) {
+        let functions = &vtab.functions;
+        functions.add(-1, "match", None, |c, a| {
+            c.set_result(format!("any:{}", a.len()))
+        });
+        functions.add(1, "match", None, |c, _| c.set_result("replaced"));
+        // Replaces the previous overload, because names are case-insensitive.
+        functions.add(1, "MATCH", None, |c, _| c.set_result("one"));
+        // The MATCH operator calls match(pattern, column).
+        functions.add(2, "match", None, |c, a| {
+            let pattern = a[0].get_str()?.to_owned();
+            c.set_result(pattern == a[1].get_str()?)
+        });
+        functions.add(3, "match", None, |c, _| c.set_result("three"));
+        self.removed
+            .set((functions.remove(3, "Match"), functions.remove(4, "match")));
+        self.entries
+            .replace(functions.iter().map(|(n, name, _)| (n, name)).collect());
+    }
+}
+
+#[test]
+fn overload_arity() -> Result<()> {
+    let hooks = MatchHooks::default();
+    let conn = setup(&hooks)?;
+    for n_args in [1, 3, 4] {
+        conn.create_overloaded_function("match", &FunctionOptions::default().set_n_args(n_args))?;
+    }
+    assert_eq!(
+        *hooks.entries.borrow(),
+        vec![
+            (-1, "match".to_owned()),
+            (1, "MATCH".to_owned()),
+            (2, "match".to_owned())
+        ]
+    );
+    assert_eq!(hooks.removed.get(), (true, false));
+
+    let query = |sql: &str| -> Result<String> {
+        let (ret,): (String,) = conn.query_row_as(sql, ())?;
+        Ok(ret)
+    };
+    assert_eq!(query("SELECT match(a) FROM tbl LIMIT 1")?, "one");
+    assert_eq!(query("SELECT a FROM tbl WHERE a MATCH 'a1'")?, "a1");
+    // The removed overload falls back to the wildcard.
+    assert_eq!(query("SELECT match(a, 1, 2) FROM tbl LIMIT 1")?, "any:3");
+    assert_eq!(query("SELECT match(a, 1, 2, 3) FROM tbl LIMIT 1")?, "any:4");
+    Ok(())
+}
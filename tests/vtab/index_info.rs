@@ -1,5 +1,6 @@
 use crate::test_vtab::*;
 use sqlite3_ext::{vtab::*, *};
+use std::{cell::RefCell, rc::Rc};
 
 #[test]
 fn best_index_rhs() -> Result<()> {
@@ -74,3 +75,295 @@ fn best_index_in() -> Result<()> {
     assert_eq!(hooks.num_filter.get(), 1);
     Ok(())
 }
+
+#[test]
+fn unique_scan() -> Result<()> {
+    #[derive(Default)]
+    struct Hooks {
+        num_filter: std::cell::Cell<u32>,
+    }
+
+    impl TestHooks for Hooks {
+        fn best_index<'a>(
+            &'a self,
+            _vtab: &TestVTab<'a, Self>,
+            index_info: &mut IndexInfo,
+        ) -> Result<()> {
+            let mut c = index_info.constraints().next().expect("no constraint");
+            if c.usable() && c.op() == ConstraintOp::Eq {
+                c.set_argv_index(Some(0));
+                index_info.set_unique_scan();
+                index_info.set_estimated_cost(1.0);
+                #[cfg(modern_sqlite)]
+                {
+                    assert_eq!(index_info.scan_flags()?, ScanFlags::UNIQUE);
+                    index_info.set_scan_flags(ScanFlags::UNIQUE | ScanFlags::HEX);
+                    assert_eq!(index_info.scan_flags_raw()?, 3);
+                    index_info.set_scan_flags_raw(ffi::SQLITE_INDEX_SCAN_UNIQUE as _);
+                }
+            }
+            Ok(())
+        }
+
+        fn filter<'a>(
+            &self,
+            _cursor: &mut TestVTabCursor<'a, Self>,
+            args: &mut [&mut ValueRef],
+        ) -> Result<()> {
+            self.num_filter.set(self.num_filter.get() + 1);
+            assert_eq!(args[0].get_str()?, "a1");
+            Ok(())
+        }
+    }
+
+    let hooks = Hooks::default();
+    let conn = setup(&hooks)?;
+    let rows: Vec<(String, String)> = conn
+        .prepare("SELECT a, b FROM tbl WHERE a = 'a1'")?
+        .query_as(())?
+        .collect()?;
+    assert_eq!(rows, vec![("a1".to_owned(), "b1".to_owned())]);
+    assert_eq!(hooks.num_filter.get(), 1);
+    Ok(())
+}
+
+#[test]
+fn best_index_in_two_pass() -> Result<()> {
+    #[derive(Default)]
+    struct Hooks {
+        wanted: std::cell::Cell<bool>,
+        probes: std::cell::RefCell<Vec<Vec<Value>>>,
+    }
+
+    impl TestHooks for Hooks {
+        fn best_index<'a>(
+            &'a self,
+            _vtab: &TestVTab<'a, Self>,
+            index_info: &mut IndexInfo,
+        ) -> Result<()> {
+            let mut c = index_info.constraints().next().expect("no constraint");
+            if c.usable() {
+                c.set_argv_index(Some(0));
+                // On SQLite before 3.38.0, this returns false and filter is called once
+                // for each value.
+                self.wanted.set(c.set_value_list_wanted(true));
+                index_info.set_estimated_cost(1.0);
+            }
+            Ok(())
+        }
+
+        fn filter<'a>(
+            &self,
+            _cursor: &mut TestVTabCursor<'a, Self>,
+            args: &mut [&mut ValueRef],
+        ) -> Result<()> {
+            let probe = if self.wanted.get() {
+                let mut list = ValueList::from_value_ref(args[0])?;
+                let len = list.len_hint()?;
+                let owned = list.collect_owned()?;
+                assert_eq!(owned.len(), len);
+                let mut second = vec![];
+                while let Some(x) = list.next()? {
+                    second.push(x.to_owned()?);
+                }
+                assert_eq!(second, owned);
+                list.reset()?;
+                assert_eq!(list.len_hint()?, len);
+                owned
+            } else {
+                assert!(ValueList::from_value_ref(args[0]).is_err());
+                vec![args[0].to_owned()?]
+            };
+            self.probes.borrow_mut().push(probe);
+            Ok(())
+        }
+    }
+
+    let hooks = Hooks::default();
+    let conn = setup(&hooks)?;
+    let rows: Vec<(String,)> = conn
+        .prepare("SELECT a FROM tbl WHERE a IN ('a2', 'a0', 'zz') ORDER BY a")?
+        .query_as(())?
+        .collect()?;
+    assert_eq!(rows, vec![("a0".to_owned(),), ("a2".to_owned(),)]);
+    let mut probes: Vec<Value> = hooks.probes.borrow().concat();
+    probes.sort_by(|a, b| format!("{:?}", a).cmp(&format!("{:?}", b)));
+    assert_eq!(
+        probes,
+        vec![
+            Value::Text("a0".to_owned()),
+            Value::Text("a2".to_owned()),
+            Value::Text("zz".to_owned()),
+        ]
+    );
+    let expected_calls = if hooks.wanted.get() { 1 } else { 3 };
+    assert_eq!(hooks.probes.borrow().len(), expected_calls);
+    Ok(())
+}
+
+#[test]
+fn index_str() -> Result<()> {
+    #[derive(Default)]
+    struct Hooks {
+        index_str: std::cell::RefCell<Option<&'static str>>,
+        seen: std::cell::RefCell<Vec<Option<String>>>,
+    }
+
+    impl TestHooks for Hooks {
+        fn best_index<'a>(
+            &'a self,
+            _vtab: &TestVTab<'a, Self>,
+            index_info: &mut IndexInfo,
+        ) -> Result<()> {
+            let val = *self.index_str.borrow();
+            index_info.set_index_str(val)?;
+            assert_eq!(index_info.index_str(), val);
+            Ok(())
+        }
+
+        fn filter<'a>(
+            &self,
+            cursor: &mut TestVTabCursor<'a, Self>,
+            _args: &mut [&mut ValueRef],
+        ) -> Result<()> {
+            self.seen.borrow_mut().push(cursor.index_str.clone());
+            Ok(())
+        }
+    }
+
+    let hooks = Hooks::default();
+    let conn = setup(&hooks)?;
+    for val in [Some(""), Some("plan"), None] {
+        *hooks.index_str.borrow_mut() = val;
+        conn.query_row("SELECT COUNT(*) FROM tbl", (), |_| Ok(()))?;
+    }
+    assert_eq!(
+        *hooks.seen.borrow(),
+        vec![Some("".to_owned()), Some("plan".to_owned()), None]
+    );
+    Ok(())
+}
+
+/// A table with a NOCASE column, which can only produce rows in NOCASE order.
+#[sqlite3_ext_vtab(EponymousModule)]
+struct NocaseVTab<'vtab> {
+    seen: &'vtab Seen,
+}
+
+type Seen = Rc<RefCell<Vec<(i32, Option<String>)>>>;
+
+struct NocaseCursor {
+    rows: Vec<&'static str>,
+    idx: usize,
+}
+
+impl<'vtab> VTab<'vtab> for NocaseVTab<'vtab> {
+    type Aux = Seen;
+    type Cursor = NocaseCursor;
+
+    fn connect(
+        _: &'vtab VTabConnection,
+        seen: &'vtab Self::Aux,
+        _: &[&str],
+    ) -> Result<(String, Self)> {
+        Ok((
+            "CREATE TABLE x (name TEXT COLLATE NOCASE, code TEXT)".to_owned(),
+            NocaseVTab { seen },
+        ))
+    }
+
+    fn best_index(&self, index_info: &mut IndexInfo) -> Result<()> {
+        let order_by = index_info.order_by_with_collation();
+        let sorted = !order_by.is_empty()
+            && order_by
+                .iter()
+                .all(|(o, coll)| o.column() == 0 && !o.desc() && coll.as_deref() == Some("NOCASE"));
+        self.seen
+            .borrow_mut()
+            .extend(order_by.into_iter().map(|(o, coll)| (o.column(), coll)));
+        index_info.set_order_by_consumed(sorted);
+        index_info.set_index_num(sorted as _);
+        Ok(())
+    }
+
+    fn open(&self) -> Result<Self::Cursor> {
+        Ok(NocaseCursor {
+            rows: vec![],
+            idx: 0,
+        })
+    }
+}
+
+impl VTabCursor for NocaseCursor {
+    fn filter(&mut self, index_num: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        self.rows = vec!["banana", "apple", "Cherry"];
+        if index_num == 1 {
+            self.rows.sort_by_key(|s| s.to_lowercase());
+        }
+        self.idx = 0;
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.idx += 1;
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        self.idx >= self.rows.len()
+    }
+
+    fn column(&mut self, idx: usize, ctx: &ColumnContext) -> Result<()> {
+        match idx {
+            0 => ctx.set_result(self.rows[self.idx]),
+            _ => ctx.set_result(self.rows[self.idx].to_uppercase()),
+        }
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(self.idx as _)
+    }
+}
+
+#[test]
+fn order_by_collation() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    let seen = Rc::new(RefCell::new(vec![]));
+    db.create_module("nocase", NocaseVTab::module(), seen.clone())?;
+    let names = |sql: &str| -> Result<Vec<String>> {
+        seen.borrow_mut().clear();
+        db.prepare(sql)?
+            .query(())?
+            .map(|row| Ok(row[0].get_str()?.to_owned()))
+            .collect()
+    };
+    let nocase = (0, Some("NOCASE".to_owned()));
+
+    assert_eq!(
+        names("SELECT name FROM nocase ORDER BY name")?,
+        vec!["apple", "banana", "Cherry"]
+    );
+    assert_eq!(*seen.borrow(), vec![nocase.clone()]);
+    assert_eq!(
+        names("SELECT name FROM nocase ORDER BY name COLLATE NOCASE")?,
+        vec!["apple", "banana", "Cherry"]
+    );
+    assert_eq!(*seen.borrow(), vec![nocase]);
+
+    // SQLite does not pass ORDER BY terms with a different collation to the table.
+    assert_eq!(
+        names("SELECT name FROM nocase ORDER BY name COLLATE BINARY")?,
+        vec!["Cherry", "apple", "banana"]
+    );
+    assert_eq!(*seen.borrow(), vec![]);
+
+    assert_eq!(
+        names("SELECT name FROM nocase ORDER BY code, rowid")?,
+        vec!["apple", "banana", "Cherry"]
+    );
+    assert_eq!(
+        *seen.borrow(),
+        vec![(1, Some("BINARY".to_owned())), (-1, None)]
+    );
+    Ok(())
+}
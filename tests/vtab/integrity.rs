@@ -0,0 +1,85 @@
+//! Tests for IntegrityVTab.
+use sqlite3_ext::{vtab::*, *};
+
+#[sqlite3_ext_vtab(StandardModule, IntegrityVTab)]
+struct CorruptVTab {}
+
+struct EmptyCursor;
+
+impl<'vtab> VTab<'vtab> for CorruptVTab {
+    type Aux = ();
+    type Cursor = EmptyCursor;
+
+    fn connect(_: &VTabConnection, _: &'vtab Self::Aux, _: &[&str]) -> Result<(String, Self)> {
+        Ok(("CREATE TABLE x (a)".to_owned(), CorruptVTab {}))
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        Ok(EmptyCursor)
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for CorruptVTab {
+    fn create(db: &VTabConnection, aux: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        Self::connect(db, aux, args)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+impl<'vtab> IntegrityVTab<'vtab> for CorruptVTab {
+    fn integrity(&'vtab self, schema: &str, table: &str, _: u64) -> Result<Option<String>> {
+        Ok(Some(format!("{schema}.{table} is corrupt")))
+    }
+}
+
+impl VTabCursor for EmptyCursor {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        true
+    }
+
+    fn column(&mut self, _: usize, _: &ColumnContext) -> Result<()> {
+        Ok(())
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(0)
+    }
+}
+
+#[test]
+fn integrity_check() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    db.create_module("corrupt", CorruptVTab::module(), ())?;
+    db.execute("CREATE VIRTUAL TABLE tbl USING corrupt()", ())?;
+    let mut stmt = db.prepare("PRAGMA integrity_check")?;
+    let mut results = Vec::new();
+    let rows = stmt.query(())?;
+    while let Some(row) = rows.next()? {
+        results.push(row[0].get_str()?.to_owned());
+    }
+    let expected = sqlite3_match_version! {
+        3_044_000 => "main.tbl is corrupt",
+        // Older versions of SQLite do not consult virtual tables.
+        _ => "ok",
+    };
+    assert!(
+        results.iter().any(|r| r.contains(expected)),
+        "{results:?} does not contain {expected:?}"
+    );
+    Ok(())
+}
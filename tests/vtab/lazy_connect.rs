@@ -0,0 +1,333 @@
+//! Tests for deferring virtual table resources with LazyConnect.
+#[cfg(feature = "testing")]
+use sqlite3_ext::vtab::testing::*;
+use sqlite3_ext::{vtab::*, *};
+use std::{
+    cell::Cell,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+/// A stand-in for a remote service, which can be taken down.
+#[derive(Default)]
+struct Backend {
+    down: Cell<bool>,
+    fetches: Cell<usize>,
+}
+
+impl Backend {
+    fn fetch(&self) -> Result<Vec<i64>> {
+        if self.down.get() {
+            return Err(Error::Module("backend is down".to_owned()));
+        }
+        self.fetches.set(self.fetches.get() + 1);
+        Ok(vec![1, 2, 3])
+    }
+}
+
+/// Fetches its rows from the backend when it is connected.
+struct EagerVTab {
+    rows: Vec<i64>,
+}
+
+impl<'vtab> VTab<'vtab> for EagerVTab {
+    type Aux = Rc<Backend>;
+    type Cursor = RowsCursor<'vtab>;
+
+    fn connect(_: &VTabConnection, aux: &Self::Aux, _: &[&str]) -> Result<(String, Self)> {
+        let rows = aux.fetch()?;
+        Ok(("CREATE TABLE x (value)".to_owned(), EagerVTab { rows }))
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        Ok(RowsCursor::new(&self.rows))
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for EagerVTab {
+    fn create(
+        db: &'vtab VTabConnection,
+        aux: &'vtab Self::Aux,
+        args: &[&str],
+    ) -> Result<(String, Self)> {
+        Self::connect(db, aux, args)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+/// Fetches its rows from the backend when it is first queried.
+struct LazyRemote<'vtab> {
+    backend: &'vtab Backend,
+}
+
+impl<'vtab> LazyVTab<'vtab> for LazyRemote<'vtab> {
+    type Aux = Rc<Backend>;
+    type Cursor = RowsCursor<'vtab>;
+    type State = Vec<i64>;
+
+    fn connect(
+        _: &'vtab VTabConnection,
+        aux: &'vtab Self::Aux,
+        _: &[&str],
+    ) -> Result<(String, Self)> {
+        Ok((
+            "CREATE TABLE x (value)".to_owned(),
+            LazyRemote { backend: aux },
+        ))
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn initialize(&self) -> Result<Vec<i64>> {
+        self.backend.fetch()
+    }
+
+    fn open(&'vtab self, state: &'vtab Vec<i64>) -> Result<Self::Cursor> {
+        Ok(RowsCursor::new(state))
+    }
+}
+
+impl<'vtab> LazyCreateVTab<'vtab> for LazyRemote<'vtab> {
+    fn create(
+        db: &'vtab VTabConnection,
+        aux: &'vtab Self::Aux,
+        args: &[&str],
+    ) -> Result<(String, Self)> {
+        Self::connect(db, aux, args)
+    }
+
+    fn destroy(&self, _: Option<&Vec<i64>>) -> Result<()> {
+        Ok(())
+    }
+}
+
+struct RowsCursor<'vtab> {
+    rows: &'vtab [i64],
+    idx: usize,
+}
+
+impl<'vtab> RowsCursor<'vtab> {
+    fn new(rows: &'vtab [i64]) -> Self {
+        RowsCursor { rows, idx: 0 }
+    }
+}
+
+impl VTabCursor for RowsCursor<'_> {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        self.idx = 0;
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.idx += 1;
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        self.idx >= self.rows.len()
+    }
+
+    fn column(&mut self, _: usize, ctx: &ColumnContext) -> Result<()> {
+        ctx.set_result(self.rows[self.idx])
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(self.idx as _)
+    }
+}
+
+/// Open the database with both modules.
+fn open(path: &Path, backend: &Rc<Backend>) -> Result<Database> {
+    let db = Database::open(path)?;
+    db.create_module("eager", StandardModule::<EagerVTab>::new(), backend.clone())?;
+    db.create_module(
+        "lazy",
+        StandardModule::<LazyConnect<LazyRemote>>::new(),
+        backend.clone(),
+    )?;
+    Ok(db)
+}
+
+/// Open the database, recording the calls made to both modules.
+#[cfg(feature = "testing")]
+fn open_recorded(path: &Path, backend: &Rc<Backend>, recorder: &Recorder) -> Result<Database> {
+    let db = Database::open(path)?;
+    db.create_module(
+        "eager",
+        StandardModule::<RecordingVTab<EagerVTab>>::new(),
+        RecordingAux::new(recorder.clone(), backend.clone()),
+    )?;
+    db.create_module(
+        "lazy",
+        StandardModule::<RecordingVTab<LazyConnect<LazyRemote>>>::new(),
+        RecordingAux::new(recorder.clone(), backend.clone()),
+    )?;
+    Ok(db)
+}
+
+#[cfg(feature = "testing")]
+fn connects(calls: &[CallRecord]) -> usize {
+    calls
+        .iter()
+        .filter(|c| matches!(c, CallRecord::Connect { .. }))
+        .count()
+}
+
+/// Create a database with a virtual table using the module, and a view which refers to
+/// it. Renaming any table checks every view, which connects to the virtual table.
+fn setup(name: &str, module: &str, backend: &Rc<Backend>) -> Result<PathBuf> {
+    let path =
+        std::env::temp_dir().join(format!("sqlite3_ext_lazy_{name}_{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let db = open(&path, backend)?;
+    db.execute("CREATE TABLE other (a)", ())?;
+    db.execute(&format!("CREATE VIRTUAL TABLE tbl USING {module}"), ())?;
+    db.execute("CREATE VIEW view AS SELECT value FROM tbl", ())?;
+    Ok(path)
+}
+
+fn sum(db: &Connection) -> Result<i64> {
+    db.query_row("SELECT sum(value) FROM view", (), |r| Ok(r[0].get_i64()))
+}
+
+#[test]
+fn eager_connect() -> Result<()> {
+    let backend = Rc::new(Backend::default());
+    let path = setup("eager", "eager", &backend)?;
+    backend.down.set(true);
+    let db = open(&path, &backend)?;
+    let err = db
+        .execute("ALTER TABLE other RENAME TO renamed", ())
+        .unwrap_err();
+    assert_eq!(err.to_string(), "error in view view: backend is down");
+    drop(db);
+
+    #[cfg(feature = "testing")]
+    {
+        let recorder = Recorder::default();
+        let db = open_recorded(&path, &backend, &recorder)?;
+        db.execute("ALTER TABLE other RENAME TO renamed", ())
+            .unwrap_err();
+        assert_eq!(connects(&recorder.take()), 1);
+    }
+    std::fs::remove_file(path).unwrap();
+    Ok(())
+}
+
+#[test]
+fn lazy_connect() -> Result<()> {
+    let backend = Rc::new(Backend::default());
+    let path = setup("lazy", "lazy", &backend)?;
+    assert_eq!(backend.fetches.get(), 0);
+    backend.down.set(true);
+    #[cfg(feature = "testing")]
+    let recorder = Recorder::default();
+    #[cfg(feature = "testing")]
+    let db = open_recorded(&path, &backend, &recorder)?;
+    #[cfg(not(feature = "testing"))]
+    let db = open(&path, &backend)?;
+    db.execute("ALTER TABLE other RENAME TO renamed", ())?;
+    assert_eq!(backend.fetches.get(), 0);
+    #[cfg(feature = "testing")]
+    {
+        let calls = recorder.take();
+        assert!(connects(&calls) > 0);
+        assert!(!calls.iter().any(|c| matches!(c, CallRecord::Open { .. })));
+    }
+
+    // The first use of the table fails while the backend is down, and is retried.
+    let err = sum(&db).unwrap_err();
+    assert_eq!(err.to_string(), "backend is down");
+    backend.down.set(false);
+    assert_eq!(sum(&db)?, 6);
+    assert_eq!(sum(&db)?, 6);
+    assert_eq!(backend.fetches.get(), 1);
+    drop(db);
+    #[cfg(feature = "testing")]
+    assert_eq!(recorder.calls().last(), Some(&CallRecord::Disconnect));
+    std::fs::remove_file(path).unwrap();
+    Ok(())
+}
+
+/// Records whether each connect happened during a schema change.
+struct ProbeVTab;
+
+impl<'vtab> VTab<'vtab> for ProbeVTab {
+    type Aux = Rc<std::cell::RefCell<Vec<bool>>>;
+    type Cursor = RowsCursor<'vtab>;
+
+    fn connect(db: &VTabConnection, aux: &Self::Aux, _: &[&str]) -> Result<(String, Self)> {
+        aux.borrow_mut().push(db.is_schema_parse());
+        Ok(("CREATE TABLE x (value)".to_owned(), ProbeVTab))
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        Ok(RowsCursor::new(&[]))
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for ProbeVTab {
+    fn create(
+        db: &'vtab VTabConnection,
+        aux: &'vtab Self::Aux,
+        args: &[&str],
+    ) -> Result<(String, Self)> {
+        Self::connect(db, aux, args)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+#[test]
+fn is_schema_parse() -> Result<()> {
+    let path = std::env::temp_dir().join(format!(
+        "sqlite3_ext_schema_parse_{}.db",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    let flags = Rc::new(std::cell::RefCell::new(vec![]));
+    let open = || -> Result<Database> {
+        let db = Database::open(&path)?;
+        db.create_module("probe", StandardModule::<ProbeVTab>::new(), flags.clone())?;
+        Ok(db)
+    };
+    let db = open()?;
+    db.execute("CREATE TABLE other (a)", ())?;
+    db.execute("CREATE VIRTUAL TABLE tbl USING probe", ())?;
+    db.execute("CREATE VIEW view AS SELECT value FROM tbl", ())?;
+    drop(db);
+    assert_eq!(flags.take(), vec![false]);
+
+    let db = open()?;
+    db.execute("/* rename */ ALTER TABLE other RENAME TO renamed", ())?;
+    let during_alter = flags.take();
+    assert!(!during_alter.is_empty());
+    // The running statements cannot be inspected on older versions of SQLite.
+    assert!(
+        during_alter.iter().all(|f| *f == cfg!(modern_sqlite)),
+        "{during_alter:?}"
+    );
+    drop(db);
+
+    let db = open()?;
+    db.query_row("SELECT count(*) FROM view", (), |_| Ok(()))?;
+    assert_eq!(flags.take(), vec![false]);
+    drop(db);
+    std::fs::remove_file(path).unwrap();
+    Ok(())
+}
@@ -0,0 +1,176 @@
+//! Tests for pushing LIMIT and OFFSET down to a virtual table.
+use sqlite3_ext::{vtab::*, *};
+use std::{cell::RefCell, rc::Rc};
+
+type Log = Rc<RefCell<Vec<String>>>;
+
+const LIMIT: i32 = 1;
+const OFFSET: i32 = 2;
+
+/// A virtual table containing the integers 0 to 99, which logs each call to its cursor.
+#[sqlite3_ext_vtab(StandardModule)]
+struct CountVTab {
+    log: Log,
+}
+
+struct CountCursor<'vtab> {
+    vtab: &'vtab CountVTab,
+    value: i64,
+    end: i64,
+}
+
+impl<'vtab> VTab<'vtab> for CountVTab {
+    type Aux = Log;
+    type Cursor = CountCursor<'vtab>;
+
+    fn connect(_: &VTabConnection, aux: &'vtab Self::Aux, _: &[&str]) -> Result<(String, Self)> {
+        Ok((
+            "CREATE TABLE x (value)".to_owned(),
+            CountVTab { log: aux.clone() },
+        ))
+    }
+
+    fn best_index(&self, index_info: &mut IndexInfo) -> Result<()> {
+        let claimed = index_info.consume_limit_offset();
+        let mut index_num = 0;
+        if claimed.limit.is_some() {
+            index_num |= LIMIT;
+        }
+        if claimed.offset.is_some() {
+            index_num |= OFFSET;
+        }
+        index_info.set_index_num(index_num);
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        Ok(CountCursor {
+            vtab: self,
+            value: 0,
+            end: 0,
+        })
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for CountVTab {
+    fn create(db: &VTabConnection, aux: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        Self::connect(db, aux, args)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+impl VTabCursor for CountCursor<'_> {
+    fn filter(
+        &mut self,
+        index_num: i32,
+        _: Option<&str>,
+        args: &mut [&mut ValueRef],
+    ) -> Result<()> {
+        let mut args = args.iter();
+        let limit = match index_num & LIMIT {
+            0 => -1,
+            _ => args.next().unwrap().get_i64(),
+        };
+        let offset = match index_num & OFFSET {
+            0 => 0,
+            _ => args.next().unwrap().get_i64().max(0),
+        };
+        self.vtab
+            .log
+            .borrow_mut()
+            .push(format!("filter(limit={limit}, offset={offset})"));
+        self.value = offset;
+        self.end = match limit {
+            l if l < 0 => 100,
+            l => (offset + l).min(100),
+        };
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.vtab.log.borrow_mut().push("next".to_owned());
+        self.value += 1;
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        self.value >= self.end
+    }
+
+    fn column(&mut self, _: usize, ctx: &ColumnContext) -> Result<()> {
+        ctx.set_result(self.value)
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(self.value)
+    }
+}
+
+/// Run the query, returning the values and the log of calls to the cursor.
+fn run(sql: &str) -> Result<(Vec<i64>, Vec<String>)> {
+    let db = Database::open(":memory:")?;
+    let log = Log::default();
+    db.create_module("count", CountVTab::module(), log.clone())?;
+    db.execute("CREATE VIRTUAL TABLE tbl USING count", ())?;
+    let mut values = Vec::new();
+    let mut stmt = db.prepare(sql)?;
+    let rows = stmt.query(())?;
+    while let Some(row) = rows.next()? {
+        values.push(row[0].get_i64());
+    }
+    let ret = log.take();
+    Ok((values, ret))
+}
+
+#[test]
+fn limit_offset() -> Result<()> {
+    let (values, log) = run("SELECT value FROM tbl LIMIT 5 OFFSET 10")?;
+    assert_eq!(values, vec![10, 11, 12, 13, 14]);
+    assert!(log[0].starts_with("filter("));
+    sqlite3_match_version! {
+        3_038_000 => {
+            // The cursor starts at the offset, and SQLite stops stepping once it has
+            // received the fifth row.
+            let expected: Vec<_> = ["filter(limit=5, offset=10)".to_owned()]
+                .into_iter()
+                .chain(std::iter::repeat("next".to_owned()).take(4))
+                .collect();
+            assert_eq!(log, expected);
+        },
+        _ => (),
+    }
+    Ok(())
+}
+
+#[test]
+fn limit_only() -> Result<()> {
+    let (values, log) = run("SELECT value FROM tbl LIMIT 3")?;
+    assert_eq!(values, vec![0, 1, 2]);
+    assert!(log[0].starts_with("filter("));
+    sqlite3_match_version! {
+        3_038_000 => assert_eq!(log[0], "filter(limit=3, offset=0)"),
+        _ => (),
+    }
+    Ok(())
+}
+
+#[test]
+fn unused_constraint() -> Result<()> {
+    // The WHERE clause is not handled by the virtual table, so SQLite must apply the
+    // LIMIT and OFFSET itself.
+    let (values, log) = run("SELECT value FROM tbl WHERE value % 2 = 0 LIMIT 2 OFFSET 1")?;
+    assert_eq!(values, vec![2, 4]);
+    assert_eq!(log[0], "filter(limit=-1, offset=0)");
+    Ok(())
+}
+
+#[test]
+fn unused_column_constraint() -> Result<()> {
+    let (values, log) = run("SELECT value FROM tbl WHERE value > 50 LIMIT 2")?;
+    assert_eq!(values, vec![51, 52]);
+    assert_eq!(log[0], "filter(limit=-1, offset=0)");
+    Ok(())
+}
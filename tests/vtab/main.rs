@@ -1,5 +1,32 @@
+mod args;
+#[cfg(modern_sqlite)]
+mod array;
+mod aux_cell;
+mod change_info;
+mod changes;
+mod closures;
+mod columns;
+mod compat;
+mod cursor_adapter;
+mod cursors;
+mod drop_module;
 mod errors;
 mod find_function;
 mod index_info;
+mod integrity;
+mod lazy_connect;
+mod limit_offset;
 mod module_types;
+mod plan;
+mod read_transaction;
+mod rename;
+mod rowid_map;
+mod series;
+#[cfg(modern_sqlite)]
+mod shadow_name;
 mod test_vtab;
+mod update_result;
+mod update_sql;
+mod virtual_table;
+mod without_rowid;
+mod worker;
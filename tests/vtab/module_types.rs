@@ -1,15 +1,14 @@
 //! Test cases for the different table types (eponymous, eponymous-only, standard).
 use sqlite3_ext::{vtab::*, *};
 
+#[sqlite3_ext_vtab(EponymousOnlyModule, name = "eponymous_only_vtab")]
 struct TestVTab;
 struct TestCursor;
 
 impl TestVTab {
     fn connect_create() -> Result<(String, Self)> {
-        Ok((
-            "CREATE TABLE x ( value INTEGER NOT NULL )".to_owned(),
-            TestVTab,
-        ))
+        let schema = SchemaBuilder::new().column("value", "INTEGER NOT NULL");
+        Ok((schema.build()?, TestVTab))
     }
 }
 
@@ -71,11 +70,8 @@ impl VTabCursor for TestCursor {
 #[cfg(modern_sqlite)]
 fn eponymous_only() -> Result<()> {
     let conn = Database::open(":memory:")?;
-    conn.create_module(
-        "eponymous_only_vtab",
-        EponymousOnlyModule::<TestVTab>::new().unwrap(),
-        (),
-    )?;
+    TestVTab::register(&conn, ())?;
+    assert_eq!(TestVTab::MODULE_NAME, "eponymous_only_vtab");
     let err = conn
         .execute("CREATE VIRTUAL TABLE tbl USING eponymous_only_vtab()", ())
         .unwrap_err();
@@ -0,0 +1,257 @@
+//! Tests for PlanBuilder and Plan.
+use sqlite3_ext::{vtab::*, *};
+use std::{cell::RefCell, rc::Rc};
+
+type Log = Rc<RefCell<Vec<String>>>;
+
+const EQ_A: usize = 0;
+const MIN_B: usize = 1;
+const MAX_B: usize = 2;
+
+/// Bit of the index_num which is set when the manual claim succeeded.
+const MANUAL: i32 = 1 << 30;
+
+#[derive(VTabColumns)]
+enum Col {
+    #[column(decltype = "INTEGER")]
+    A,
+    #[column(decltype = "INTEGER")]
+    B,
+}
+
+/// A virtual table with the rows (a, a * 10) for a from 0 to 9, which pushes down
+/// equality on a and ranges on b. If manual is set, an upper bound on a is claimed
+/// directly from the IndexInfo before the PlanBuilder is used.
+#[sqlite3_ext_vtab(StandardModule)]
+struct RangeVTab {
+    log: Log,
+    manual: bool,
+}
+
+struct RangeCursor {
+    log: Log,
+    rows: Vec<(i64, i64)>,
+    pos: usize,
+}
+
+impl<'vtab> VTab<'vtab> for RangeVTab {
+    type Aux = (Log, bool);
+    type Cursor = RangeCursor;
+
+    fn connect(_: &VTabConnection, aux: &'vtab Self::Aux, _: &[&str]) -> Result<(String, Self)> {
+        let sql = Col::schema_builder().build()?;
+        let vtab = RangeVTab {
+            log: aux.0.clone(),
+            manual: aux.1,
+        };
+        Ok((sql, vtab))
+    }
+
+    fn best_index(&self, index_info: &mut IndexInfo) -> Result<()> {
+        use ConstraintOp::*;
+        let manual = self.manual && index_info.claim(Col::A, LT).is_some();
+        let mut plan = PlanBuilder::new(index_info);
+        let eq = plan.optional(Col::A, &[Eq]);
+        let min = plan.optional(Col::B, &[GT, GE]);
+        let max = plan.optional(Col::B, &[LT, LE]);
+        let cost = match (eq, min || max) {
+            (true, _) => 1.0,
+            (false, true) => 5.0,
+            (false, false) => 10.0,
+        };
+        plan.index_info().set_estimated_cost(cost);
+        let index_num = plan.finish()?;
+        if manual {
+            index_info.set_index_num(index_num | MANUAL);
+        }
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        Ok(RangeCursor {
+            log: self.log.clone(),
+            rows: vec![],
+            pos: 0,
+        })
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for RangeVTab {
+    fn create(db: &VTabConnection, aux: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        Self::connect(db, aux, args)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+impl VTabCursor for RangeCursor {
+    fn filter(
+        &mut self,
+        index_num: i32,
+        index_str: Option<&str>,
+        args: &mut [&mut ValueRef],
+    ) -> Result<()> {
+        let plan = Plan::decode(index_num & !MANUAL, index_str)?;
+        let mut log = vec![];
+        self.rows = (0..10).map(|a| (a, a * 10)).collect();
+        self.pos = 0;
+        if index_num & MANUAL != 0 {
+            // The manual claim was made first, so it has the first argv index.
+            let val = args[0].get_i64();
+            log.push(format!("manual{val}"));
+            self.rows.retain(|&(a, _)| a < val);
+        }
+        for c in [EQ_A, MIN_B, MAX_B].into_iter().filter_map(|s| plan.get(s)) {
+            let val = args[c.argv].get_i64();
+            log.push(format!("{}{:?}{val}", c.column, c.op));
+            self.rows.retain(|&(a, b)| {
+                let x = if c.column == i32::from(Col::A) { a } else { b };
+                match c.op {
+                    ConstraintOp::Eq => x == val,
+                    ConstraintOp::GT => x > val,
+                    ConstraintOp::GE => x >= val,
+                    ConstraintOp::LT => x < val,
+                    ConstraintOp::LE => x <= val,
+                    _ => unreachable!(),
+                }
+            });
+        }
+        self.log.borrow_mut().push(log.join(" "));
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        self.pos >= self.rows.len()
+    }
+
+    fn column(&mut self, idx: usize, ctx: &ColumnContext) -> Result<()> {
+        let (a, b) = self.rows[self.pos];
+        match Col::from_index(idx) {
+            Some(Col::A) => ctx.set_result(a),
+            Some(Col::B) => ctx.set_result(b),
+            None => Ok(()),
+        }
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(self.rows[self.pos].0)
+    }
+}
+
+/// Run the query, returning the values of a and the plans received by filter.
+fn run(sql: &str) -> Result<(Vec<i64>, Vec<String>)> {
+    run_with(sql, false)
+}
+
+fn run_with(sql: &str, manual: bool) -> Result<(Vec<i64>, Vec<String>)> {
+    let db = Database::open(":memory:")?;
+    let log = Log::default();
+    db.create_module("range", RangeVTab::module(), (log.clone(), manual))?;
+    db.execute("CREATE VIRTUAL TABLE tbl USING range", ())?;
+    let mut values = vec![];
+    let mut stmt = db.prepare(sql)?;
+    let rows = stmt.query(())?;
+    while let Some(row) = rows.next()? {
+        values.push(row[0].get_i64());
+    }
+    let ret = log.take();
+    Ok((values, ret))
+}
+
+#[test]
+fn equality() -> Result<()> {
+    let (values, log) = run("SELECT a FROM tbl WHERE a = 3")?;
+    assert_eq!(values, vec![3]);
+    assert_eq!(log, vec!["0Eq3"]);
+    Ok(())
+}
+
+#[test]
+fn range() -> Result<()> {
+    let (values, log) = run("SELECT a FROM tbl WHERE b > 20 AND b <= 60")?;
+    assert_eq!(values, vec![3, 4, 5, 6]);
+    assert_eq!(log, vec!["1GT20 1LE60"]);
+    let (values, log) = run("SELECT a FROM tbl WHERE 50 > b")?;
+    assert_eq!(values, vec![0, 1, 2, 3, 4]);
+    assert_eq!(log, vec!["1LT50"]);
+    Ok(())
+}
+
+#[test]
+fn equality_and_range() -> Result<()> {
+    let (values, log) = run("SELECT a FROM tbl WHERE a = 4 AND b >= 40 AND b < 90")?;
+    assert_eq!(values, vec![4]);
+    assert_eq!(log, vec!["0Eq4 1GE40 1LT90"]);
+    let (values, _) = run("SELECT a FROM tbl WHERE a = 4 AND b > 40")?;
+    assert_eq!(values, Vec::<i64>::new());
+    Ok(())
+}
+
+#[test]
+fn unclaimed() -> Result<()> {
+    // Constraints which are not claimed are checked by SQLite.
+    let (values, log) = run("SELECT a FROM tbl WHERE a != 3 AND b < 50")?;
+    assert_eq!(values, vec![0, 1, 2, 4]);
+    assert_eq!(log, vec!["1LT50"]);
+    Ok(())
+}
+
+#[test]
+fn manual_claim() -> Result<()> {
+    let sql = "SELECT a FROM tbl WHERE a < 8 AND b >= 30 AND a = 5";
+    let (values, log) = run_with(sql, true)?;
+    assert_eq!(values, vec![5]);
+    assert_eq!(log, vec!["manual8 0Eq5 1GE30"]);
+    let (values, log) = run_with("SELECT a FROM tbl WHERE a < 8 AND b >= 70", true)?;
+    assert_eq!(values, vec![7]);
+    assert_eq!(log, vec!["manual8 1GE70"]);
+    Ok(())
+}
+
+#[test]
+fn decode() -> Result<()> {
+    let plan = Plan::decode(5, Some("0:2:0,1:32:1"))?;
+    assert_eq!(
+        plan.get(0),
+        Some(Claim {
+            column: 0,
+            op: ConstraintOp::Eq,
+            argv: 0
+        })
+    );
+    assert_eq!(plan.get(1), None);
+    assert_eq!(
+        plan.get(2),
+        Some(Claim {
+            column: 1,
+            op: ConstraintOp::GE,
+            argv: 1
+        })
+    );
+    assert_eq!(plan.get(3), None);
+    assert_eq!(Plan::decode(0, Some(""))?, Plan::default());
+    for (num, s) in [
+        (1, None),
+        (1, Some("")),
+        (1, Some("0:2:0,1:2:1")),
+        (1, Some("0:1:0")),
+        (1, Some("x:2:0")),
+        (1, Some("0:2")),
+        (1, Some("0:2:x")),
+        (1, Some("0:2:0:0")),
+        (-1, Some("0:2:0")),
+    ] {
+        assert!(
+            matches!(Plan::decode(num, s), Err(Error::Module(_))),
+            "{num} {s:?}"
+        );
+    }
+    Ok(())
+}
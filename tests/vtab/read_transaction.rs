@@ -0,0 +1,154 @@
+//! Test cases for ReadTransactionVTab.
+use sqlite3_ext::{vtab::*, *};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+type Log = Rc<RefCell<Vec<String>>>;
+
+#[sqlite3_ext_vtab(EponymousModule, ReadTransactionVTab, name = "read_vtab")]
+struct ReadVTab {
+    log: Log,
+    generation: Cell<u32>,
+}
+
+struct ReadGuard {
+    log: Log,
+    generation: u32,
+}
+
+struct ReadCursor {
+    rowid: i64,
+}
+
+impl VTab<'_> for ReadVTab {
+    type Aux = Log;
+    type Cursor = ReadCursor;
+
+    fn connect(_db: &VTabConnection, aux: &Self::Aux, _args: &[&str]) -> Result<(String, Self)> {
+        let vtab = ReadVTab {
+            log: aux.clone(),
+            generation: Cell::new(0),
+        };
+        Ok(("CREATE TABLE x(value)".to_owned(), vtab))
+    }
+
+    fn best_index(&self, _index_info: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&self) -> Result<Self::Cursor> {
+        Ok(ReadCursor { rowid: 0 })
+    }
+}
+
+impl ReadTransactionVTab<'_> for ReadVTab {
+    type ReadGuard = ReadGuard;
+
+    fn begin_read(&self) -> Result<Self::ReadGuard> {
+        let generation = self.generation.get() + 1;
+        self.generation.set(generation);
+        self.log.borrow_mut().push(format!("begin {generation}"));
+        Ok(ReadGuard {
+            log: self.log.clone(),
+            generation,
+        })
+    }
+}
+
+impl Drop for ReadGuard {
+    fn drop(&mut self) {
+        self.log
+            .borrow_mut()
+            .push(format!("end {}", self.generation));
+    }
+}
+
+impl VTabCursor for ReadCursor {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        self.rowid = 1;
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.rowid += 1;
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        self.rowid > 3
+    }
+
+    fn column(&mut self, _: usize, c: &ColumnContext) -> Result<()> {
+        c.set_result(self.rowid)
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(self.rowid)
+    }
+}
+
+fn setup() -> Result<(Database, Log)> {
+    let db = Database::open(":memory:")?;
+    let log = Log::default();
+    ReadVTab::register(&db, log.clone())?;
+    Ok((db, log))
+}
+
+fn take(log: &Log) -> Vec<String> {
+    std::mem::take(&mut *log.borrow_mut())
+}
+
+#[test]
+fn autocommit() -> Result<()> {
+    let (db, log) = setup()?;
+    let sum: i64 = db.query_row("SELECT SUM(value) FROM read_vtab", (), |r| {
+        Ok(r[0].get_i64())
+    })?;
+    assert_eq!(sum, 6);
+    assert_eq!(take(&log), ["begin 1", "end 1"]);
+    db.query_row("SELECT COUNT(*) FROM read_vtab", (), |_| Ok(()))?;
+    assert_eq!(take(&log), ["begin 2", "end 2"]);
+    Ok(())
+}
+
+#[test]
+fn self_join() -> Result<()> {
+    let (db, log) = setup()?;
+    let count: i64 = db.query_row(
+        "SELECT COUNT(*) FROM read_vtab a, read_vtab b WHERE a.value IN (SELECT value FROM read_vtab)",
+        (),
+        |r| Ok(r[0].get_i64()),
+    )?;
+    assert_eq!(count, 9);
+    assert_eq!(take(&log), ["begin 1", "end 1"]);
+    Ok(())
+}
+
+#[test]
+fn explicit_transaction() -> Result<()> {
+    let (db, log) = setup()?;
+    db.execute("BEGIN", ())?;
+    db.query_row("SELECT COUNT(*) FROM read_vtab", (), |_| Ok(()))?;
+    db.query_row("SELECT SUM(value) FROM read_vtab", (), |_| Ok(()))?;
+    db.execute("COMMIT", ())?;
+    assert_eq!(take(&log), ["begin 1"]);
+
+    // The guard from the committed transaction is released before the next read.
+    db.query_row("SELECT COUNT(*) FROM read_vtab", (), |_| Ok(()))?;
+    assert_eq!(take(&log), ["end 1", "begin 2", "end 2"]);
+    Ok(())
+}
+
+#[test]
+fn disconnect() -> Result<()> {
+    let (db, log) = setup()?;
+    db.execute("BEGIN", ())?;
+    db.query_row("SELECT COUNT(*) FROM read_vtab", (), |_| Ok(()))?;
+    db.execute("ROLLBACK", ())?;
+    assert_eq!(take(&log), ["begin 1"]);
+    db.close().map_err(|(e, _)| e)?;
+    assert_eq!(take(&log), ["end 1"]);
+    Ok(())
+}
@@ -0,0 +1,215 @@
+//! Tests for renaming virtual tables which own shadow tables.
+use sqlite3_ext::{query::CursorAdapter, vtab::*, *};
+use std::{cell::RefCell, rc::Rc};
+
+type Log = Rc<RefCell<Vec<(String, String)>>>;
+
+/// A read-only virtual table which stores its rows in a shadow table.
+#[sqlite3_ext_vtab(StandardModule, RenameVTab)]
+struct ShadowVTab<'vtab> {
+    db: &'vtab Connection,
+    log: &'vtab Log,
+    schema: String,
+    name: String,
+}
+
+struct ShadowCursor(CursorAdapter);
+
+impl<'vtab> VTab<'vtab> for ShadowVTab<'vtab> {
+    type Aux = Log;
+    type Cursor = ShadowCursor;
+
+    fn connect(
+        db: &'vtab VTabConnection,
+        aux: &'vtab Self::Aux,
+        args: &[&str],
+    ) -> Result<(String, Self)> {
+        let vtab = ShadowVTab {
+            db,
+            log: aux,
+            schema: args[1].to_owned(),
+            name: args[2].to_owned(),
+        };
+        Ok(("CREATE TABLE x (value)".to_owned(), vtab))
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        let sql = format!(
+            "SELECT value, rowid FROM \"{}\".\"{}_data\"",
+            self.schema, self.name
+        );
+        Ok(ShadowCursor(CursorAdapter::new(
+            self.db.prepare(&sql)?,
+            Some(1),
+        )))
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for ShadowVTab<'vtab> {
+    const SHADOW_NAMES: &'static [&'static str] = &["data", "config"];
+
+    fn create(
+        db: &'vtab VTabConnection,
+        aux: &'vtab Self::Aux,
+        args: &[&str],
+    ) -> Result<(String, Self)> {
+        for suffix in Self::SHADOW_NAMES {
+            db.execute(
+                &format!(
+                    "CREATE TABLE \"{}\".\"{}_{suffix}\" (value)",
+                    args[1], args[2]
+                ),
+                (),
+            )?;
+        }
+        Self::connect(db, aux, args)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+impl<'vtab> RenameVTab<'vtab> for ShadowVTab<'vtab> {
+    fn rename(&'vtab self, schema: &str, from: &str, to: &str) -> Result<()> {
+        self.log.borrow_mut().push((from.to_owned(), to.to_owned()));
+        rename_shadow_tables(self.db, schema, Self::SHADOW_NAMES, from, to)
+    }
+}
+
+impl VTabCursor for ShadowCursor {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        self.0.filter(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.0.next()
+    }
+
+    fn eof(&mut self) -> bool {
+        self.0.eof()
+    }
+
+    fn column(&mut self, idx: usize, ctx: &ColumnContext) -> Result<()> {
+        self.0.column(idx, ctx)
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        self.0.rowid()
+    }
+}
+
+fn setup(log: &Log) -> Result<Database> {
+    let db = Database::open(":memory:")?;
+    db.create_module("shadow", ShadowVTab::module(), log.clone())?;
+    db.execute("CREATE VIRTUAL TABLE tbl USING shadow", ())?;
+    db.execute("INSERT INTO tbl_data VALUES ('a'), ('b')", ())?;
+    Ok(db)
+}
+
+fn tables(db: &Connection) -> Result<Vec<String>> {
+    schema_tables(db, "main")
+}
+
+fn schema_tables(db: &Connection, schema: &str) -> Result<Vec<String>> {
+    let sql =
+        format!("SELECT name FROM \"{schema}\".sqlite_master WHERE type = 'table' ORDER BY name");
+    db.prepare(&sql)?
+        .query(())?
+        .map(|row| Ok(row[0].get_str()?.to_owned()))
+        .collect()
+}
+
+fn values(db: &Connection, table: &str) -> Result<Vec<String>> {
+    db.prepare(&format!("SELECT value FROM \"{table}\""))?
+        .query(())?
+        .map(|row| Ok(row[0].get_str()?.to_owned()))
+        .collect()
+}
+
+#[test]
+fn rename() -> Result<()> {
+    let log = Log::default();
+    let db = setup(&log)?;
+    db.execute("ALTER TABLE tbl RENAME TO renamed", ())?;
+    db.execute("ALTER TABLE renamed RENAME TO \"odd name\"", ())?;
+    assert_eq!(
+        log.take(),
+        vec![
+            ("tbl".to_owned(), "renamed".to_owned()),
+            ("renamed".to_owned(), "odd name".to_owned()),
+        ]
+    );
+    assert_eq!(
+        tables(&db)?,
+        vec!["odd name", "odd name_config", "odd name_data"]
+    );
+    assert_eq!(values(&db, "odd name")?, vec!["a", "b"]);
+    Ok(())
+}
+
+#[test]
+fn rename_attached() -> Result<()> {
+    let log = Log::default();
+    let db = Database::open(":memory:")?;
+    db.create_module("shadow", ShadowVTab::module(), log.clone())?;
+    db.execute("ATTACH ':memory:' AS aux", ())?;
+    db.execute("CREATE VIRTUAL TABLE aux.t USING shadow", ())?;
+    db.execute("INSERT INTO aux.t_data VALUES ('a')", ())?;
+    // An unrelated table in main with the same name as a shadow table.
+    db.execute("CREATE TABLE main.t_data (value)", ())?;
+    db.execute("ALTER TABLE aux.t RENAME TO u", ())?;
+    assert_eq!(schema_tables(&db, "main")?, vec!["t_data"]);
+    assert_eq!(schema_tables(&db, "aux")?, vec!["u", "u_config", "u_data"]);
+    assert_eq!(values(&db, "u")?, vec!["a"]);
+    Ok(())
+}
+
+#[test]
+#[cfg(modern_sqlite)]
+fn rename_defensive() -> Result<()> {
+    let log = Log::default();
+    let db = setup(&log)?;
+    db.db_config_defensive(true)?;
+    // The shadow tables are read-only to ordinary SQL...
+    assert!(db
+        .execute("ALTER TABLE tbl_data RENAME TO other", ())
+        .is_err());
+    // ...but are renamed along with the virtual table.
+    db.execute("ALTER TABLE tbl RENAME TO renamed", ())?;
+    assert_eq!(
+        tables(&db)?,
+        vec!["renamed", "renamed_config", "renamed_data"]
+    );
+    assert_eq!(values(&db, "renamed")?, vec!["a", "b"]);
+    Ok(())
+}
+
+#[test]
+#[cfg(modern_sqlite)]
+fn shadow_names() -> Result<()> {
+    let log = Log::default();
+    let db = Database::open(":memory:")?;
+    db.create_module("shadow", ShadowVTab::module(), log.clone())?;
+    db.execute("CREATE VIRTUAL TABLE a_b USING shadow", ())?;
+    db.execute("DROP TABLE a_b_config", ())?;
+    db.execute("CREATE TABLE \"a_b_CONFIG\" (value)", ())?;
+    db.execute("CREATE TABLE a_b_other (value)", ())?;
+    let types: Vec<(String, String)> = db
+        .prepare("SELECT name, type FROM pragma_table_list WHERE name LIKE 'a^_b^_%' ESCAPE '^' ORDER BY name")?
+        .query_as(())?
+        .collect()?;
+    assert_eq!(
+        types,
+        vec![
+            ("a_b_CONFIG".to_owned(), "shadow".to_owned()),
+            ("a_b_data".to_owned(), "shadow".to_owned()),
+            ("a_b_other".to_owned(), "table".to_owned()),
+        ]
+    );
+    Ok(())
+}
@@ -0,0 +1,128 @@
+//! Tests for RowidMap.
+use sqlite3_ext::{vtab::*, *};
+
+#[test]
+fn assign() -> Result<()> {
+    let mut map = RowidMap::new();
+    assert!(map.is_empty());
+    assert_eq!(map.rowid("a")?, 1);
+    assert_eq!(map.rowid(7)?, 2);
+    assert_eq!(map.rowid(&b"a"[..])?, 3);
+    assert_eq!(map.rowid("a")?, 1);
+    assert_eq!(map.len(), 3);
+    assert_eq!(map.key(2), Some(&RowidKey::Integer(7)));
+    assert_eq!(map.get_rowid(&RowidKey::from("a")), Some(1));
+
+    // Removed rowids are not reused right away.
+    assert_eq!(map.remove_key(&RowidKey::from("a")), Some(1));
+    assert_eq!(map.rowid("b")?, 4);
+    assert_eq!(map.remove_rowid(4), Some(RowidKey::from("b")));
+    assert_eq!(map.key(4), None);
+    assert_eq!(
+        map.iter().collect::<Vec<_>>(),
+        vec![
+            (2, &RowidKey::Integer(7)),
+            (3, &RowidKey::Blob(b"a".to_vec()))
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn collisions() -> Result<()> {
+    let mut map = RowidMap::new();
+    map.insert("a", 10)?;
+    map.insert("a", 10)?;
+    assert_eq!(map.insert("b", 10), Err(SQLITE_CONSTRAINT));
+    assert_eq!(map.insert("a", 11), Err(SQLITE_CONSTRAINT));
+    assert!(map.insert("b", 0).is_err());
+    // Assignment continues after the largest inserted rowid.
+    assert_eq!(map.rowid("b")?, 11);
+
+    assert_eq!(map.rekey(10, "c")?, RowidKey::from("a"));
+    assert_eq!(map.get_rowid(&RowidKey::from("a")), None);
+    assert_eq!(map.rekey(10, "b"), Err(SQLITE_CONSTRAINT));
+    assert_eq!(map.key(10), Some(&RowidKey::from("c")));
+    // A missing rowid is not added, so it can't collide with an assigned one.
+    assert!(map.rekey(12, "d").is_err());
+    assert_eq!(map.rowid("d")?, 12);
+    Ok(())
+}
+
+#[test]
+fn overflow() -> Result<()> {
+    let mut map = RowidMap::new();
+    map.insert("a", 1)?;
+    map.insert("b", 3)?;
+    map.insert("max", i64::MAX)?;
+    // Once the largest rowid is used, the gaps are filled from the bottom.
+    assert_eq!(map.rowid("c")?, 2);
+    assert_eq!(map.rowid("d")?, 4);
+    map.remove_key(&RowidKey::from("a"));
+    assert_eq!(map.rowid("e")?, 1);
+
+    let mut map = RowidMap::new();
+    map.insert("max", i64::MAX)?;
+    assert_eq!(map.rowid("f")?, 1);
+    assert_eq!(map.rowid("g")?, 2);
+    Ok(())
+}
+
+#[test]
+fn persistence() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    let mut map = RowidMap::new();
+    map.rowid("a")?;
+    map.rowid(2)?;
+    map.rowid(&b"\x00\x01"[..])?;
+    map.insert("z", 100)?;
+    map.remove_key(&RowidKey::from("a"));
+
+    RowidMap::create_table(&db, "main", "tbl_rowids")?;
+    RowidMap::create_table(&db, "main", "tbl_rowids")?;
+    map.save(&db, "main", "tbl_rowids")?;
+    let loaded = RowidMap::load(&db, "main", "tbl_rowids")?;
+    assert_eq!(
+        loaded.iter().collect::<Vec<_>>(),
+        map.iter().collect::<Vec<_>>()
+    );
+
+    // The loaded map does not reuse the rowids of saved keys.
+    let mut loaded = loaded;
+    assert_eq!(loaded.rowid("y")?, 101);
+
+    // Saving again replaces the previous contents.
+    loaded.save(&db, "main", "tbl_rowids")?;
+    let count = db.query_row(
+        "SELECT COUNT(*) FROM tbl_rowids",
+        (),
+        |r| Ok(r[0].get_i64()),
+    )?;
+    assert_eq!(count, 4);
+
+    db.execute("INSERT INTO tbl_rowids VALUES (200, 1.5)", ())?;
+    assert_eq!(
+        RowidMap::load(&db, "main", "tbl_rowids").err(),
+        Some(SQLITE_MISMATCH)
+    );
+    Ok(())
+}
+
+#[test]
+fn persistence_attached() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    db.execute("ATTACH ':memory:' AS aux", ())?;
+    let mut map = RowidMap::new();
+    map.rowid("a")?;
+    RowidMap::create_table(&db, "aux", "tbl_rowids")?;
+    RowidMap::create_table(&db, "main", "tbl_rowids")?;
+    map.save(&db, "aux", "tbl_rowids")?;
+    let count = |schema: &str| {
+        let sql = format!("SELECT COUNT(*) FROM {schema}.tbl_rowids");
+        db.query_row(&sql, (), |r| Ok(r[0].get_i64()))
+    };
+    assert_eq!((count("main")?, count("aux")?), (0, 1));
+    assert!(RowidMap::load(&db, "main", "tbl_rowids")?.is_empty());
+    assert_eq!(RowidMap::load(&db, "aux", "tbl_rowids")?.len(), 1);
+    Ok(())
+}
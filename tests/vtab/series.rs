@@ -0,0 +1,176 @@
+//! Tests for the generate_series module provided by vtab::series.
+use sqlite3_ext::*;
+
+fn setup() -> Result<Database> {
+    let db = Database::open(":memory:")?;
+    db.register_series_module("generate_series")?;
+    Ok(db)
+}
+
+fn values(db: &Connection, sql: &str) -> Result<Vec<i64>> {
+    db.prepare(sql)?
+        .query(())?
+        .map(|row| Ok(row[0].get_i64()))
+        .collect()
+}
+
+#[test]
+fn ascending() -> Result<()> {
+    let db = setup()?;
+    let cases: &[(&str, &[i64])] = &[
+        ("SELECT value FROM generate_series(1, 5)", &[1, 2, 3, 4, 5]),
+        (
+            "SELECT value FROM generate_series(5, 100, 25)",
+            &[5, 30, 55, 80],
+        ),
+        ("SELECT value FROM generate_series(5, 1)", &[]),
+        ("SELECT value FROM generate_series(1, NULL)", &[]),
+        ("SELECT value FROM generate_series(1) LIMIT 3", &[1, 2, 3]),
+        (
+            "SELECT value FROM generate_series(1, 20, 3) WHERE value > 4 AND value <= 14.5",
+            &[7, 10, 13],
+        ),
+        (
+            "SELECT value FROM generate_series(0, 10, 5) WHERE value = 5.0",
+            &[5],
+        ),
+        (
+            "SELECT value FROM generate_series(0, 10) WHERE value = 2.5",
+            &[],
+        ),
+        (
+            "SELECT value FROM generate_series(0, 3) WHERE value < 'a'",
+            &[0, 1, 2, 3],
+        ),
+    ];
+    for (sql, expected) in cases {
+        assert_eq!(values(&db, sql)?, *expected, "{sql}");
+    }
+    Ok(())
+}
+
+#[test]
+fn descending() -> Result<()> {
+    let db = setup()?;
+    let cases: &[(&str, &[i64])] = &[
+        ("SELECT value FROM generate_series(5, 10, -2)", &[9, 7, 5]),
+        (
+            "SELECT value FROM generate_series(5, 10, -2) ORDER BY value",
+            &[5, 7, 9],
+        ),
+        (
+            "SELECT value FROM generate_series(1, 4) ORDER BY value DESC",
+            &[4, 3, 2, 1],
+        ),
+        (
+            "SELECT value FROM generate_series(0, 20, -5) WHERE value < 12",
+            &[10, 5, 0],
+        ),
+    ];
+    for (sql, expected) in cases {
+        assert_eq!(values(&db, sql)?, *expected, "{sql}");
+    }
+    Ok(())
+}
+
+#[test]
+fn hidden_columns() -> Result<()> {
+    let db = setup()?;
+    let row = db.query_row(
+        "SELECT rowid, value, start, stop, step FROM generate_series(1, 10, -4)",
+        (),
+        |r| Ok([0, 1, 2, 3, 4].map(|i| r[i].get_i64())),
+    )?;
+    assert_eq!(row, [1, 9, 1, 10, -4]);
+    let rows: Vec<(i64, i64)> = db
+        .prepare(
+            "SELECT a.value, b.value FROM generate_series(1, 2) a, generate_series(a.value, 3) b",
+        )?
+        .query(())?
+        .map(|r| Ok((r[0].get_i64(), r[1].get_i64())))
+        .collect()?;
+    assert_eq!(rows, vec![(1, 1), (1, 2), (1, 3), (2, 2), (2, 3)]);
+    assert_eq!(
+        values(
+            &db,
+            "SELECT value FROM generate_series(1, 3) WHERE stop > 2"
+        )?,
+        [1, 2, 3]
+    );
+    assert_eq!(
+        values(
+            &db,
+            "SELECT value FROM generate_series(1, 3) WHERE stop > 5"
+        )?,
+        []
+    );
+    Ok(())
+}
+
+#[test]
+fn overflow() -> Result<()> {
+    let db = setup()?;
+    assert_eq!(
+        values(
+            &db,
+            "SELECT value FROM generate_series(9223372036854775805, 9223372036854775807, 2)"
+        )?,
+        vec![9223372036854775805, 9223372036854775807]
+    );
+    assert_eq!(
+        values(
+            &db,
+            "SELECT value FROM generate_series(-9223372036854775808, 9223372036854775807, -9223372036854775808)"
+        )?,
+        vec![0, -9223372036854775808]
+    );
+    Ok(())
+}
+
+#[test]
+fn errors() -> Result<()> {
+    let db = setup()?;
+    let err = values(&db, "SELECT value FROM generate_series(1, 10, 0)").unwrap_err();
+    assert_eq!(err.to_string(), "generate_series() step cannot be zero");
+    let err = values(&db, "SELECT value FROM generate_series").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "first argument to \"generate_series()\" missing or unusable"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(modern_sqlite)]
+fn limit_offset() -> Result<()> {
+    let db = setup()?;
+    // Stepping to the offset one row at a time would never finish, so interrupt the query
+    // if it runs for long.
+    let mut ops = 0;
+    db.set_progress_handler(1000, move || {
+        ops += 1;
+        ops > 100
+    })?;
+    assert_eq!(
+        values(
+            &db,
+            "SELECT value FROM generate_series(0) LIMIT 2 OFFSET 9223372036854775000"
+        )?,
+        vec![9223372036854775000, 9223372036854775001]
+    );
+    assert_eq!(
+        values(
+            &db,
+            "SELECT value FROM generate_series(0) ORDER BY value DESC LIMIT 2 OFFSET 7"
+        )?,
+        vec![9223372036854775800, 9223372036854775799]
+    );
+    assert_eq!(
+        values(
+            &db,
+            "SELECT value FROM generate_series(1, 10, 3) LIMIT -1 OFFSET 1"
+        )?,
+        vec![4, 7, 10]
+    );
+    Ok(())
+}
@@ -0,0 +1,89 @@
+//! Tests for computed shadow table names.
+use sqlite3_ext::{vtab::*, *};
+
+/// An empty table which claims every "<name>_idx_<n>" table as a shadow table.
+#[sqlite3_ext_vtab(StandardModule)]
+struct IndexedVTab;
+
+struct IndexedCursor;
+
+impl<'vtab> VTab<'vtab> for IndexedVTab {
+    type Aux = ();
+    type Cursor = IndexedCursor;
+
+    fn connect(_: &VTabConnection, _: &'vtab Self::Aux, _: &[&str]) -> Result<(String, Self)> {
+        Ok(("CREATE TABLE x (value)".to_owned(), IndexedVTab))
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        Ok(IndexedCursor)
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for IndexedVTab {
+    fn is_shadow_name(suffix: &str) -> bool {
+        match suffix.strip_prefix("idx_") {
+            Some(n) => !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()),
+            None => false,
+        }
+    }
+
+    fn create(db: &VTabConnection, aux: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        Self::connect(db, aux, args)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+impl VTabCursor for IndexedCursor {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        true
+    }
+
+    fn column(&mut self, _: usize, _: &ColumnContext) -> Result<()> {
+        Ok(())
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(0)
+    }
+}
+
+#[test]
+fn computed_shadow_names() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    db.db_config_defensive(true)?;
+    db.create_module("indexed", IndexedVTab::module(), ())?;
+    db.execute("CREATE TABLE vtab_idx_3 (value)", ())?;
+    db.execute("CREATE TABLE vtab_unrelated (value)", ())?;
+    // Creating the virtual table marks the existing tables which it claims.
+    db.execute("CREATE VIRTUAL TABLE vtab USING indexed", ())?;
+    let types: Vec<(String, String)> = db
+        .prepare("SELECT name, type FROM pragma_table_list WHERE name LIKE 'vtab^_%' ESCAPE '^' ORDER BY name")?
+        .query_as(())?
+        .collect()?;
+    assert_eq!(
+        types,
+        vec![
+            ("vtab_idx_3".to_owned(), "shadow".to_owned()),
+            ("vtab_unrelated".to_owned(), "table".to_owned()),
+        ]
+    );
+    assert!(db.execute("INSERT INTO vtab_idx_3 VALUES (1)", ()).is_err());
+    db.execute("INSERT INTO vtab_unrelated VALUES (1)", ())?;
+    Ok(())
+}
@@ -18,11 +18,15 @@ pub trait TestHooks: Sized {
     ) -> Result<()> {
         Ok(())
     }
+
+    fn column(&self, _idx: usize, _ctx: &ColumnContext) -> Result<()> {
+        Ok(())
+    }
 }
 
 pub fn setup<Hooks: TestHooks>(hooks: &Hooks) -> Result<Database> {
     let conn = Database::open(":memory:")?;
-    conn.create_module("vtab", TestVTab::module(), hooks)?;
+    TestVTab::register(&conn, hooks)?;
     conn.execute(
         "CREATE VIRTUAL TABLE tbl USING vtab(schema='CREATE TABLE x(a,b,c)', rows=3)",
         (),
@@ -30,7 +34,7 @@ pub fn setup<Hooks: TestHooks>(hooks: &Hooks) -> Result<Database> {
     Ok(conn)
 }
 
-#[sqlite3_ext_vtab(StandardModule, FindFunctionVTab)]
+#[sqlite3_ext_vtab(StandardModule, FindFunctionVTab, name = "vtab")]
 pub struct TestVTab<'vtab, Hooks: TestHooks + 'vtab> {
     hooks: &'vtab Hooks,
     pub functions: VTabFunctionList<'vtab, Self>,
@@ -40,6 +44,7 @@ pub struct TestVTab<'vtab, Hooks: TestHooks + 'vtab> {
 pub struct TestVTabCursor<'vtab, Hooks: TestHooks + 'vtab> {
     vtab: &'vtab TestVTab<'vtab, Hooks>,
     rowid: i64,
+    pub index_str: Option<String>,
 }
 
 impl<'vtab, Hooks: TestHooks + 'vtab> TestVTab<'vtab, Hooks> {
@@ -70,6 +75,7 @@ impl<'vtab, Hooks: TestHooks + 'vtab> VTab<'vtab> for TestVTab<'vtab, Hooks> {
         let ret = TestVTabCursor {
             vtab: self,
             rowid: 0,
+            index_str: None,
         };
         Ok(ret)
     }
@@ -92,8 +98,14 @@ impl<'vtab, Hooks: TestHooks + 'vtab> FindFunctionVTab<'vtab> for TestVTab<'vtab
 }
 
 impl<'vtab, Hooks: TestHooks + 'vtab> VTabCursor for TestVTabCursor<'vtab, Hooks> {
-    fn filter(&mut self, _: i32, _: Option<&str>, args: &mut [&mut ValueRef]) -> Result<()> {
+    fn filter(
+        &mut self,
+        _: i32,
+        index_str: Option<&str>,
+        args: &mut [&mut ValueRef],
+    ) -> Result<()> {
         self.rowid = 0;
+        self.index_str = index_str.map(String::from);
         self.vtab.hooks.filter(self, args)
     }
 
@@ -107,6 +119,7 @@ impl<'vtab, Hooks: TestHooks + 'vtab> VTabCursor for TestVTabCursor<'vtab, Hooks
     }
 
     fn column(&mut self, idx: usize, ctx: &ColumnContext) -> Result<()> {
+        self.vtab.hooks.column(idx, ctx)?;
         const ALPHABET: &[u8] = "abcdefghijklmnopqrstuvwxyz".as_bytes();
         let ret = match () {
             _ if ctx.nochange() => Err(Error::NoChange),
@@ -0,0 +1,140 @@
+//! Tests for the rowid reported by UpdateVTab::update2.
+use sqlite3_ext::{vtab::*, *};
+use std::{cell::RefCell, collections::BTreeMap};
+
+/// A key-value table which generates rowids starting at 100.
+#[sqlite3_ext_vtab(StandardModule, UpdateVTab)]
+struct MapVTab {
+    rows: RefCell<BTreeMap<i64, String>>,
+}
+
+struct MapCursor {
+    rows: Vec<(i64, String)>,
+    pos: usize,
+}
+
+impl<'vtab> VTab<'vtab> for MapVTab {
+    type Aux = ();
+    type Cursor = MapCursor;
+
+    fn connect(_: &VTabConnection, _: &'vtab Self::Aux, _: &[&str]) -> Result<(String, Self)> {
+        Ok((
+            "CREATE TABLE x (v)".to_owned(),
+            MapVTab {
+                rows: RefCell::default(),
+            },
+        ))
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        Ok(MapCursor {
+            rows: self
+                .rows
+                .borrow()
+                .iter()
+                .map(|(k, v)| (*k, v.clone()))
+                .collect(),
+            pos: 0,
+        })
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for MapVTab {
+    fn create(db: &VTabConnection, aux: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        Self::connect(db, aux, args)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+impl<'vtab> UpdateVTab<'vtab> for MapVTab {
+    fn update2(&'vtab self, info: &mut ChangeInfo) -> Result<UpdateResult> {
+        let mut rows = self.rows.borrow_mut();
+        match info.change_type() {
+            ChangeType::Insert => {
+                let args = info.args_mut();
+                let rowid = match args[0].value_type() {
+                    ValueType::Null => rows.keys().next_back().map_or(100, |k| k + 1),
+                    _ => args[0].get_i64(),
+                };
+                rows.insert(rowid, args[1].get_str()?.to_owned());
+                Ok(UpdateResult::Inserted { rowid })
+            }
+            ChangeType::Update => {
+                let rowid = info.rowid().get_i64();
+                let value = info.args_mut()[1].get_str()?.to_owned();
+                rows.insert(rowid, value);
+                Ok(UpdateResult::Done)
+            }
+            ChangeType::Delete => {
+                rows.remove(&info.rowid().get_i64());
+                Ok(UpdateResult::Done)
+            }
+        }
+    }
+
+    fn update(&'vtab self, info: &mut ChangeInfo) -> Result<i64> {
+        Ok(match self.update2(info)? {
+            UpdateResult::Inserted { rowid } => rowid,
+            UpdateResult::Done => 0,
+        })
+    }
+}
+
+impl VTabCursor for MapCursor {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        self.pos = 0;
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        self.pos >= self.rows.len()
+    }
+
+    fn column(&mut self, _: usize, ctx: &ColumnContext) -> Result<()> {
+        ctx.set_result(self.rows[self.pos].1.clone())
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(self.rows[self.pos].0)
+    }
+}
+
+#[test]
+fn last_insert_rowid() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    db.create_module("map", MapVTab::module(), ())?;
+    db.execute("CREATE VIRTUAL TABLE tbl USING map", ())?;
+
+    db.execute("INSERT INTO tbl (v) VALUES ('a')", ())?;
+    assert_eq!(db.last_insert_rowid(), 100);
+    db.execute("INSERT INTO tbl (v) VALUES ('b')", ())?;
+    assert_eq!(db.last_insert_rowid(), 101);
+    db.execute("INSERT INTO tbl (rowid, v) VALUES (7, 'c')", ())?;
+    assert_eq!(db.last_insert_rowid(), 7);
+
+    // UPDATE and DELETE don't change the last inserted rowid.
+    db.execute("UPDATE tbl SET v = 'z' WHERE rowid = 100", ())?;
+    assert_eq!(db.last_insert_rowid(), 7);
+    db.execute("DELETE FROM tbl WHERE rowid = 101", ())?;
+    assert_eq!(db.last_insert_rowid(), 7);
+
+    let rows: Vec<(i64, String)> = db
+        .prepare("SELECT rowid, v FROM tbl")?
+        .query(())?
+        .map(|r| Ok((r[0].get_i64(), r[1].get_str()?.to_owned())))
+        .collect()?;
+    assert_eq!(rows, vec![(7, "c".to_owned()), (100, "z".to_owned())]);
+    Ok(())
+}
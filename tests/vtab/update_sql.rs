@@ -0,0 +1,178 @@
+//! Tests for building UPDATE statements for shadow tables from ChangeInfo.
+use sqlite3_ext::{vtab::*, *};
+use std::{cell::RefCell, rc::Rc};
+
+type Updates = Rc<RefCell<Vec<Option<(String, Vec<Value>)>>>>;
+
+/// A virtual table with a single row, which records the UPDATE statement built for each
+/// change. Columns report that they are unchanged whenever SQLite allows it.
+#[sqlite3_ext_vtab(StandardModule, UpdateVTab)]
+struct ShadowVTab {
+    updates: Updates,
+    schema: String,
+}
+
+struct RowCursor {
+    eof: bool,
+}
+
+impl<'vtab> VTab<'vtab> for ShadowVTab {
+    type Aux = Updates;
+    type Cursor = RowCursor;
+
+    fn connect(_: &VTabConnection, aux: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        let vtab = ShadowVTab {
+            updates: aux.clone(),
+            schema: args[1].to_owned(),
+        };
+        Ok(("CREATE TABLE x (a, \"b c\", d)".to_owned(), vtab))
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        Ok(RowCursor { eof: true })
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for ShadowVTab {
+    fn create(
+        db: &'vtab VTabConnection,
+        aux: &'vtab Self::Aux,
+        args: &[&str],
+    ) -> Result<(String, Self)> {
+        Self::connect(db, aux, args)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+impl<'vtab> UpdateVTab<'vtab> for ShadowVTab {
+    fn update(&'vtab self, info: &mut ChangeInfo) -> Result<i64> {
+        let ret = match info.build_update_sql(&self.schema, "tbl \"data\"", &["a", "b c", "d"]) {
+            Some((sql, params)) => {
+                let params = params
+                    .into_iter()
+                    .map(|v| v.to_owned())
+                    .collect::<Result<_>>()?;
+                Some((sql, params))
+            }
+            None => None,
+        };
+        self.updates.borrow_mut().push(ret);
+        Ok(1)
+    }
+}
+
+impl VTabCursor for RowCursor {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        self.eof = false;
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.eof = true;
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        self.eof
+    }
+
+    fn column(&mut self, idx: usize, ctx: &ColumnContext) -> Result<()> {
+        if ctx.nochange() {
+            return Err(Error::NoChange);
+        }
+        ctx.set_result(idx as i64 * 10)
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(1)
+    }
+}
+
+fn update(sql: &str) -> Result<Option<(String, Vec<Value>)>> {
+    update_in("main", sql)
+}
+
+fn update_in(schema: &str, sql: &str) -> Result<Option<(String, Vec<Value>)>> {
+    let db = Database::open(":memory:")?;
+    db.execute("ATTACH ':memory:' AS aux", ())?;
+    let updates = Updates::default();
+    db.create_module("shadow", ShadowVTab::module(), updates.clone())?;
+    db.execute(
+        &format!("CREATE VIRTUAL TABLE {schema}.tbl USING shadow"),
+        (),
+    )?;
+    db.execute(sql, ())?;
+    let mut updates = updates.borrow_mut();
+    assert_eq!(updates.len(), 1);
+    Ok(updates.pop().unwrap())
+}
+
+#[test]
+fn all_columns() -> Result<()> {
+    assert_eq!(
+        update("UPDATE tbl SET a = 1, \"b c\" = 2, d = 3")?,
+        Some((
+            "UPDATE \"main\".\"tbl \"\"data\"\"\" SET \"a\" = ?, \"b c\" = ?, \"d\" = ? WHERE rowid = ?"
+                .to_owned(),
+            vec![
+                Value::Integer(1),
+                Value::Integer(2),
+                Value::Integer(3),
+                Value::Integer(1)
+            ]
+        ))
+    );
+    Ok(())
+}
+
+#[test]
+fn attached() -> Result<()> {
+    let (sql, _) = update_in("aux", "UPDATE aux.tbl SET a = 1, \"b c\" = 2, d = 3")?.unwrap();
+    assert!(
+        sql.starts_with("UPDATE \"aux\".\"tbl \"\"data\"\"\" SET"),
+        "{sql}"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(modern_sqlite)]
+fn one_column() -> Result<()> {
+    assert_eq!(
+        update("UPDATE tbl SET d = 'x'")?,
+        Some((
+            "UPDATE \"main\".\"tbl \"\"data\"\"\" SET \"d\" = ? WHERE rowid = ?".to_owned(),
+            vec![Value::Text("x".to_owned()), Value::Integer(1)]
+        ))
+    );
+    // Columns which are only read are still unchanged.
+    assert_eq!(
+        update("UPDATE tbl SET d = a + 1")?,
+        Some((
+            "UPDATE \"main\".\"tbl \"\"data\"\"\" SET \"d\" = ? WHERE rowid = ?".to_owned(),
+            vec![Value::Integer(1), Value::Integer(1)]
+        ))
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(modern_sqlite)]
+fn rowid() -> Result<()> {
+    assert_eq!(update("UPDATE tbl SET rowid = rowid")?, None);
+    assert_eq!(
+        update("UPDATE tbl SET rowid = 5")?,
+        Some((
+            "UPDATE \"main\".\"tbl \"\"data\"\"\" SET rowid = ? WHERE rowid = ?".to_owned(),
+            vec![Value::Integer(5), Value::Integer(1)]
+        ))
+    );
+    Ok(())
+}
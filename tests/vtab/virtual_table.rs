@@ -0,0 +1,183 @@
+//! Tests for Connection::create_virtual_table.
+use sqlite3_ext::{vtab::*, *};
+
+/// A virtual table whose rows are the arguments it was created with.
+#[sqlite3_ext_vtab(StandardModule)]
+struct ArgsVTab {
+    args: Vec<String>,
+}
+
+struct ArgsCursor<'vtab> {
+    vtab: &'vtab ArgsVTab,
+    idx: usize,
+}
+
+impl ArgsVTab {
+    fn new(args: &[&str]) -> Result<(String, Self)> {
+        let args = args.iter().skip(3).map(|s| (*s).to_owned()).collect();
+        Ok(("CREATE TABLE x (arg)".to_owned(), ArgsVTab { args }))
+    }
+}
+
+impl<'vtab> VTab<'vtab> for ArgsVTab {
+    type Aux = ();
+    type Cursor = ArgsCursor<'vtab>;
+
+    fn connect(_: &VTabConnection, _: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        Self::new(args)
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        Ok(ArgsCursor { vtab: self, idx: 0 })
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for ArgsVTab {
+    fn create(_: &VTabConnection, _: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        Self::new(args)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+impl VTabCursor for ArgsCursor<'_> {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        self.idx = 0;
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.idx += 1;
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        self.idx >= self.vtab.args.len()
+    }
+
+    fn column(&mut self, _: usize, ctx: &ColumnContext) -> Result<()> {
+        ctx.set_result(self.vtab.args[self.idx].clone())
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(self.idx as _)
+    }
+}
+
+fn setup() -> Result<Database> {
+    let db = Database::open(":memory:")?;
+    db.create_module("args", ArgsVTab::module(), ())?;
+    Ok(db)
+}
+
+fn args_of(db: &Connection, tbl: &VirtualTableHandle) -> Result<Vec<String>> {
+    let sql = format!("SELECT arg FROM {}", tbl.qualified_name());
+    let mut stmt = db.prepare(&sql)?;
+    let mut ret = Vec::new();
+    let rows = stmt.query(())?;
+    while let Some(row) = rows.next()? {
+        ret.push(row[0].get_str()?.to_owned());
+    }
+    Ok(ret)
+}
+
+fn table_exists(db: &Connection, schema: &str, name: &str) -> Result<bool> {
+    let sql = format!("SELECT COUNT(*) FROM \"{schema}\".sqlite_master WHERE name = ?");
+    db.query_row(&sql, [name], |r| Ok(r[0].get_i64() > 0))
+}
+
+#[test]
+fn quoting() -> Result<()> {
+    let db = setup()?;
+    db.execute("ATTACH ':memory:' AS \"odd\"\"name\"", ())?;
+    let args = ["'it''s'", "\"a, b\"", "f(1, ')')", "[x]", "plain"];
+    for schema in [Schema::Main, Schema::Temp, Schema::Named("odd\"name")] {
+        let tbl = db.create_virtual_table(schema, Some("tbl \"1\""), "args", &args)?;
+        assert_eq!(tbl.name(), "tbl \"1\"");
+        assert_eq!(args_of(&db, &tbl)?, args);
+        tbl.drop_table()?;
+    }
+
+    let tbl = db.create_virtual_table(Schema::Main, None, "args", &[])?;
+    assert_eq!(args_of(&db, &tbl)?, Vec::<String>::new());
+
+    for bad in ["a, b", "a)", "'unterminated", "a); DROP TABLE x; --", ""] {
+        match db.create_virtual_table(Schema::Main, Some("bad"), "args", &[bad]) {
+            Err(Error::Sqlite(ffi::SQLITE_MISUSE, Some(_))) => (),
+            x => panic!("{bad}: expected SQLITE_MISUSE, got {x:?}"),
+        }
+    }
+    assert!(!table_exists(&db, "main", "bad")?);
+    Ok(())
+}
+
+#[test]
+fn unique_names() -> Result<()> {
+    let db = setup()?;
+    let a = db.create_virtual_table(Schema::Temp, None, "args", &["1"])?;
+    let b = db.create_virtual_table(Schema::Temp, None, "args", &["2"])?;
+    assert_ne!(a.name(), b.name());
+    assert_eq!(args_of(&db, &a)?, ["1"]);
+    assert_eq!(args_of(&db, &b)?, ["2"]);
+
+    // Connections on other threads share the source of names.
+    let names: Vec<String> = (0..4)
+        .map(|_| {
+            std::thread::spawn(|| -> Result<Vec<String>> {
+                let db = setup()?;
+                (0..10)
+                    .map(|_| {
+                        Ok(db
+                            .create_virtual_table(Schema::Main, None, "args", &[])?
+                            .keep())
+                    })
+                    .collect()
+            })
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|t| t.join().unwrap())
+        .collect::<Result<Vec<_>>>()?
+        .concat();
+    let mut unique = names.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), names.len());
+    Ok(())
+}
+
+#[test]
+fn drop_semantics() -> Result<()> {
+    let db = setup()?;
+    let tbl = db.create_virtual_table(Schema::Temp, None, "args", &[])?;
+    let name = tbl.name().to_owned();
+    assert!(table_exists(&db, "temp", &name)?);
+    drop(tbl);
+    assert!(!table_exists(&db, "temp", &name)?);
+
+    let name = db
+        .create_virtual_table(Schema::Temp, None, "args", &[])?
+        .keep();
+    assert!(table_exists(&db, "temp", &name)?);
+
+    // Dropping a handle whose table is already gone is not an error.
+    let tbl = db.create_virtual_table(Schema::Main, None, "args", &[])?;
+    db.execute(&format!("DROP TABLE {}", tbl.qualified_name()), ())?;
+    tbl.drop_table()?;
+    let tbl = db.create_virtual_table(Schema::Main, None, "args", &[])?;
+    db.execute(&format!("DROP TABLE {}", tbl.qualified_name()), ())?;
+    drop(tbl);
+
+    // Closing the connection removes temporary tables, and the handle cannot outlive the
+    // connection, so the connection can be closed as soon as the handle is gone.
+    let tbl = db.create_virtual_table(Schema::Temp, None, "args", &[])?;
+    drop(tbl);
+    db.close().map_err(|(e, _)| e)?;
+    Ok(())
+}
@@ -0,0 +1,205 @@
+//! Tests for WITHOUT ROWID virtual tables.
+use sqlite3_ext::{vtab::*, *};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+type Log = Rc<RefCell<Vec<String>>>;
+
+/// A key-value store keyed by TEXT, which logs the changes made to it. The schema can be
+/// overridden by the module arguments.
+#[sqlite3_ext_vtab(StandardModule, UpdateVTab, WithoutRowId)]
+struct KvVTab {
+    data: RefCell<BTreeMap<String, String>>,
+    log: Log,
+}
+
+struct KvCursor {
+    rows: Vec<(String, String)>,
+    pos: usize,
+}
+
+impl<'vtab> VTab<'vtab> for KvVTab {
+    type Aux = Log;
+    type Cursor = KvCursor;
+
+    fn connect(_: &VTabConnection, aux: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        let sql = match args.get(3) {
+            Some(x) => x.trim_matches('"').to_owned(),
+            None => "CREATE TABLE x (key TEXT PRIMARY KEY, value) WITHOUT ROWID".to_owned(),
+        };
+        let vtab = KvVTab {
+            data: RefCell::default(),
+            log: aux.clone(),
+        };
+        Ok((sql, vtab))
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        let rows = self
+            .data
+            .borrow()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        Ok(KvCursor { rows, pos: 0 })
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for KvVTab {
+    fn create(db: &VTabConnection, aux: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        Self::connect(db, aux, args)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+impl<'vtab> UpdateVTab<'vtab> for KvVTab {
+    fn update(&'vtab self, info: &mut ChangeInfo) -> Result<i64> {
+        let mut data = self.data.borrow_mut();
+        let key = info
+            .primary_key()
+            .map(|pk| pk.get_str().map(str::to_owned))
+            .transpose()?;
+        match info.change_type() {
+            ChangeType::Insert => {
+                let key = key.unwrap();
+                assert!(info.rowid().is_null());
+                if data.contains_key(&key) {
+                    return Err(SQLITE_CONSTRAINT);
+                }
+                data.insert(key.clone(), info.args_mut()[2].get_str()?.to_owned());
+                self.log.borrow_mut().push(format!("insert {key}"));
+            }
+            ChangeType::Update => {
+                let old = info.rowid_mut().get_str()?.to_owned();
+                let key = key.unwrap();
+                data.remove(&old);
+                data.insert(key.clone(), info.args_mut()[2].get_str()?.to_owned());
+                self.log.borrow_mut().push(format!("update {old} -> {key}"));
+            }
+            ChangeType::Delete => {
+                assert_eq!(key, None);
+                let old = info.rowid_mut().get_str()?.to_owned();
+                data.remove(&old);
+                self.log.borrow_mut().push(format!("delete {old}"));
+            }
+        }
+        // There is no rowid to return.
+        Ok(0)
+    }
+}
+
+impl VTabCursor for KvCursor {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        self.pos = 0;
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        self.pos >= self.rows.len()
+    }
+
+    fn column(&mut self, idx: usize, ctx: &ColumnContext) -> Result<()> {
+        let (key, value) = &self.rows[self.pos];
+        match idx {
+            0 => ctx.set_result(key.clone()),
+            _ => ctx.set_result(value.clone()),
+        }
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Err(Error::Module("WITHOUT ROWID".to_owned()))
+    }
+}
+
+fn setup(log: &Log) -> Result<Database> {
+    let db = Database::open(":memory:")?;
+    db.create_module("kv", KvVTab::module(), log.clone())?;
+    db.execute("CREATE VIRTUAL TABLE tbl USING kv", ())?;
+    Ok(db)
+}
+
+fn contents(db: &Connection) -> Result<Vec<(String, String)>> {
+    db.prepare("SELECT key, value FROM tbl")?
+        .query_as(())?
+        .collect()
+}
+
+#[test]
+fn insert_update_delete() -> Result<()> {
+    let log = Log::default();
+    let db = setup(&log)?;
+    db.execute(
+        "INSERT INTO tbl VALUES ('apple', 'red'), ('banana', 'yellow'), ('cherry', 'red')",
+        (),
+    )?;
+    let n = db.execute("UPDATE tbl SET value = 'green' WHERE key = 'apple'", ())?;
+    assert_eq!(n, 1);
+    db.execute(
+        "UPDATE tbl SET key = 'blueberry', value = 'blue' WHERE key = 'banana'",
+        (),
+    )?;
+    db.execute("DELETE FROM tbl WHERE value = 'red'", ())?;
+    assert_eq!(
+        contents(&db)?,
+        vec![
+            ("apple".to_owned(), "green".to_owned()),
+            ("blueberry".to_owned(), "blue".to_owned()),
+        ]
+    );
+    let err = db
+        .execute("INSERT INTO tbl VALUES ('apple', 'red')", ())
+        .unwrap_err();
+    assert!(
+        matches!(err, Error::Sqlite(ffi::SQLITE_CONSTRAINT, _)),
+        "{err:?}"
+    );
+    assert_eq!(
+        log.take(),
+        vec![
+            "insert apple",
+            "insert banana",
+            "insert cherry",
+            "update apple -> apple",
+            "update banana -> blueberry",
+            "delete cherry",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn schema_must_be_without_rowid() -> Result<()> {
+    let db = Database::open(":memory:")?;
+    db.create_module("kv", KvVTab::module(), Log::default())?;
+    let err = db
+        .execute(
+            "CREATE VIRTUAL TABLE tbl USING kv(\"CREATE TABLE x (key TEXT PRIMARY KEY, value)\")",
+            (),
+        )
+        .unwrap_err();
+    let msg = err.to_string();
+    assert!(msg.contains("must be WITHOUT ROWID"), "{msg}");
+    // SQLite only supports a single PRIMARY KEY column on writable WITHOUT ROWID tables.
+    let err = db
+        .execute(
+            "CREATE VIRTUAL TABLE tbl USING kv(\"CREATE TABLE x (a, b, PRIMARY KEY (a, b)) WITHOUT ROWID\")",
+            (),
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("invalid virtual table schema"),
+        "{err}"
+    );
+    Ok(())
+}
@@ -0,0 +1,230 @@
+//! Tests for virtual tables which own background workers.
+use sqlite3_ext::{vtab::*, *};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// A request for the worker to produce the given number of rows.
+struct Request {
+    count: i64,
+    reply: mpsc::SyncSender<i64>,
+}
+
+#[derive(Clone, Default)]
+struct Aux {
+    /// When set, the worker blocks on this channel and ignores requests to stop.
+    wedge: Option<Arc<Mutex<mpsc::Receiver<()>>>>,
+    /// Number of workers which have exited.
+    exited: Arc<AtomicUsize>,
+}
+
+/// A virtual table which fetches its rows from a background worker, a page at a time.
+#[sqlite3_ext_vtab(StandardModule, HasWorkers)]
+struct WorkerVTab {
+    count: i64,
+    requests: mpsc::Sender<Request>,
+    worker: Worker,
+}
+
+struct WorkerCursor<'vtab> {
+    vtab: &'vtab WorkerVTab,
+    rows: Option<mpsc::Receiver<i64>>,
+    current: Option<i64>,
+}
+
+fn run_worker(aux: Aux, requests: mpsc::Receiver<Request>, stop: StopToken) {
+    if let Some(wedge) = &aux.wedge {
+        let _ = wedge.lock().unwrap().recv();
+    }
+    while !stop.is_stopped() {
+        match requests.recv_timeout(Duration::from_millis(10)) {
+            Ok(Request { count, reply }) => {
+                for i in 0..count {
+                    if reply.send(i).is_err() {
+                        break;
+                    }
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => (),
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    aux.exited.fetch_add(1, Ordering::SeqCst);
+}
+
+impl<'vtab> VTab<'vtab> for WorkerVTab {
+    type Aux = Aux;
+    type Cursor = WorkerCursor<'vtab>;
+
+    fn connect(_: &VTabConnection, aux: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        let count = args[3]
+            .parse()
+            .map_err(|_| Error::Module("invalid count".to_owned()))?;
+        let (requests, rx) = mpsc::channel();
+        let aux = aux.clone();
+        let worker = Worker::spawn("fetch", move |stop| run_worker(aux, rx, stop))?;
+        let vtab = WorkerVTab {
+            count,
+            requests,
+            worker,
+        };
+        Ok(("CREATE TABLE x (value)".to_owned(), vtab))
+    }
+
+    fn best_index(&self, _: &mut IndexInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn open(&'vtab self) -> Result<Self::Cursor> {
+        Ok(WorkerCursor {
+            vtab: self,
+            rows: None,
+            current: None,
+        })
+    }
+}
+
+impl<'vtab> CreateVTab<'vtab> for WorkerVTab {
+    fn create(db: &VTabConnection, aux: &'vtab Self::Aux, args: &[&str]) -> Result<(String, Self)> {
+        Self::connect(db, aux, args)
+    }
+
+    fn destroy(self) -> DisconnectResult<Self> {
+        Ok(())
+    }
+}
+
+impl<'vtab> HasWorkers<'vtab> for WorkerVTab {
+    const SHUTDOWN_TIMEOUT: Duration = Duration::from_millis(100);
+
+    fn workers(&mut self) -> Vec<&mut Worker> {
+        vec![&mut self.worker]
+    }
+}
+
+impl VTabCursor for WorkerCursor<'_> {
+    fn filter(&mut self, _: i32, _: Option<&str>, _: &mut [&mut ValueRef]) -> Result<()> {
+        let (reply, rows) = mpsc::sync_channel(1);
+        let request = Request {
+            count: self.vtab.count,
+            reply,
+        };
+        self.vtab
+            .requests
+            .send(request)
+            .map_err(|_| Error::Module("worker has stopped".to_owned()))?;
+        self.rows = Some(rows);
+        self.next()
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.current = self.rows.as_ref().and_then(|r| r.recv().ok());
+        Ok(())
+    }
+
+    fn eof(&mut self) -> bool {
+        self.current.is_none()
+    }
+
+    fn column(&mut self, _: usize, ctx: &ColumnContext) -> Result<()> {
+        ctx.set_result(self.current)
+    }
+
+    fn rowid(&mut self) -> Result<i64> {
+        Ok(self.current.unwrap_or_default())
+    }
+}
+
+fn setup(aux: &Aux) -> Result<Database> {
+    let db = Database::open(":memory:")?;
+    db.create_module("workers", WorkerVTab::module(), aux.clone())?;
+    db.execute("CREATE VIRTUAL TABLE tbl USING workers(3)", ())?;
+    Ok(db)
+}
+
+fn values(db: &Connection) -> Result<Vec<i64>> {
+    db.prepare("SELECT value FROM tbl")?
+        .query(())?
+        .map(|row| Ok(row[0].get_i64()))
+        .collect()
+}
+
+#[test]
+fn disconnect() -> Result<()> {
+    let aux = Aux::default();
+    let db = setup(&aux)?;
+    assert_eq!(values(&db)?, vec![0, 1, 2]);
+    assert_eq!(values(&db)?, vec![0, 1, 2]);
+    assert_eq!(aux.exited.load(Ordering::SeqCst), 0);
+    drop(db);
+    assert_eq!(aux.exited.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[test]
+fn destroy() -> Result<()> {
+    let aux = Aux::default();
+    let db = setup(&aux)?;
+    db.execute("DROP TABLE tbl", ())?;
+    assert_eq!(aux.exited.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[test]
+fn wedged() -> Result<()> {
+    let (unwedge, wedge) = mpsc::channel::<()>();
+    let aux = Aux {
+        wedge: Some(Arc::new(Mutex::new(wedge))),
+        ..Aux::default()
+    };
+    let db = setup(&aux)?;
+    let start = Instant::now();
+    drop(db);
+    let elapsed = start.elapsed();
+    assert!(elapsed >= WorkerVTab::SHUTDOWN_TIMEOUT, "{elapsed:?}");
+    assert!(elapsed < Duration::from_secs(5), "{elapsed:?}");
+    assert_eq!(aux.exited.load(Ordering::SeqCst), 0);
+
+    // The detached worker still finishes once it is no longer wedged.
+    drop(unwedge);
+    for _ in 0..500 {
+        if aux.exited.load(Ordering::SeqCst) == 1 {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    panic!("worker did not exit");
+}
+
+#[test]
+fn worker_lifecycle() -> Result<()> {
+    let (tx, rx) = mpsc::channel::<()>();
+    let mut worker = Worker::spawn("lifecycle", move |stop| {
+        while !stop.is_stopped() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        let _ = rx.recv();
+    })?;
+    assert_eq!(worker.name(), "lifecycle");
+    assert!(!worker.is_finished());
+    worker.request_stop();
+    assert!(!worker.join_timeout(Duration::from_millis(20))?);
+    // The worker is detached, so there is nothing left to wait for.
+    assert!(worker.is_finished());
+    assert!(worker.join_timeout(Duration::ZERO)?);
+    drop(tx);
+    Ok(())
+}
+
+#[test]
+fn worker_panic() -> Result<()> {
+    let mut worker = Worker::spawn("panicky", |_| panic!("worker failed"))?;
+    let err = worker.join_timeout(Duration::from_secs(5)).unwrap_err();
+    assert_eq!(err.to_string(), "worker panicky panicked");
+    Ok(())
+}